@@ -2,7 +2,7 @@ use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use nimby_graph::train_journey::TrainJourney;
 use nimby_graph::conflict::{detect_line_conflicts, SerializableConflictContext};
 use nimby_graph::import::csv::{analyze_csv, parse_csv_with_mapping};
-use nimby_graph::models::RailwayGraph;
+use nimby_graph::models::{ConflictSettings, RailwayGraph};
 
 fn benchmark_conflict_detection(c: &mut Criterion) {
     // Load test data from R70.csv
@@ -24,9 +24,7 @@ fn benchmark_conflict_detection(c: &mut Criterion) {
     let context = SerializableConflictContext::from_graph(
         &graph,
         station_indices,
-        chrono::Duration::seconds(30),
-        chrono::Duration::seconds(30),
-        false,
+        &ConflictSettings::default(),
     );
 
     // Benchmark journey generation
@@ -59,9 +57,7 @@ fn benchmark_conflict_detection(c: &mut Criterion) {
             let context = SerializableConflictContext::from_graph(
                 &graph,
                 station_indices,
-                chrono::Duration::seconds(30),
-                chrono::Duration::seconds(30),
-                false,
+                &ConflictSettings::default(),
             );
 
             detect_line_conflicts(
@@ -1,7 +1,7 @@
 use nimby_graph::train_journey::TrainJourney;
 use nimby_graph::conflict::{detect_line_conflicts, SerializableConflictContext};
 use nimby_graph::import::csv::{analyze_csv, parse_csv_with_mapping};
-use nimby_graph::models::{RailwayGraph, Stations};
+use nimby_graph::models::{ConflictSettings, RailwayGraph, Stations};
 use std::fs;
 
 fn main() {
@@ -49,9 +49,7 @@ fn main() {
     let context = SerializableConflictContext::from_graph(
         &graph,
         station_indices,
-        chrono::Duration::seconds(30),
-        chrono::Duration::seconds(30),
-        false,
+        &ConflictSettings::default(),
     );
 
     // Run conflict detection (timing happens inside the function)
@@ -1,7 +1,7 @@
 use crate::components::window::Window;
 use crate::components::platform_editor::PlatformEditor;
 use crate::components::add_station_quick::{AddStationQuick, QuickEntryStation};
-use crate::models::{RailwayGraph, Platform, Track, TrackDirection};
+use crate::models::{RailwayGraph, Platform, Track, TrackDirection, DEFAULT_PLATFORM_CAPACITY};
 use leptos::{component, create_effect, create_signal, event_target_checked, event_target_value, IntoView, ReadSignal, Signal, SignalGet, SignalSet, SignalUpdate, use_context, view, WriteSignal};
 use petgraph::stable_graph::NodeIndex;
 use petgraph::visit::{EdgeRef, IntoEdgeReferences};
@@ -26,8 +26,8 @@ pub fn AddStation(
     let (is_passing_loop, set_is_passing_loop) = create_signal(false);
     let (connect_to_station, set_connect_to_station) = create_signal(None::<NodeIndex>);
     let (platforms, set_platforms) = create_signal(vec![
-        Platform { name: "1".to_string() },
-        Platform { name: "2".to_string() },
+        Platform { name: "1".to_string(), capacity: DEFAULT_PLATFORM_CAPACITY },
+        Platform { name: "2".to_string(), capacity: DEFAULT_PLATFORM_CAPACITY },
     ]);
 
     // Quick entry mode signals
@@ -49,8 +49,8 @@ pub fn AddStation(
             // Default to last added station if available
             set_connect_to_station.set(last_added_station.get());
             set_platforms.set(vec![
-                Platform { name: "1".to_string() },
-                Platform { name: "2".to_string() },
+                Platform { name: "1".to_string(), capacity: DEFAULT_PLATFORM_CAPACITY },
+                Platform { name: "2".to_string(), capacity: DEFAULT_PLATFORM_CAPACITY },
             ]);
             // Reset quick entry mode
             set_quick_entry_mode.set(false);
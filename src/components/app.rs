@@ -1,22 +1,30 @@
 use crate::components::alpha_disclaimer::AlphaDisclaimer;
 use crate::components::button::Button;
 use crate::components::changelog_popup::ChangelogPopup;
+use crate::components::checkpoint_list::CheckpointList;
+use crate::components::graph_canvas::{FocusedStations, HighlightedJourney, ReferenceOverlay, VisualizationTime};
+use crate::components::history_panel::UndoHistory;
 use crate::components::infrastructure_view::InfrastructureView;
+use crate::components::error_list::AcknowledgedConflicts;
+use crate::components::service_patterns_editor::ServicePatterns;
+use crate::components::line_controls::{LineSolo, MissingJourneys, SoloVisibility};
 use crate::components::project_manager::ProjectManager;
 use crate::components::report_issue_button::ReportIssueButton;
+use crate::components::save_indicator::SaveIndicator;
+use crate::components::storage_warning::StorageWarning;
 use crate::components::time_graph::TimeGraph;
 use crate::components::toast::{Toast, ToastNotification};
 use crate::conflict::Conflict;
 #[allow(unused_imports)]
 use crate::logging::log;
 use crate::models::{GraphView, Legend, Project, RailwayGraph, Routes, ViewportState, UndoManager, UndoSnapshot};
-use crate::storage::{IndexedDbStorage, Storage};
-use crate::train_journey::TrainJourney;
+use crate::storage::{AutoSave, IndexedDbStorage, SaveStatus, Storage};
+use crate::train_journey::{lines_without_journeys, ReferenceTimetable, SimulationHorizon, TrainJourney};
 use crate::worker_bridge::ConflictDetector;
 use leptos::{
-    component, create_effect, create_signal, event_target_value, provide_context, spawn_local,
-    store_value, view, Callback, IntoView, Show, Signal, SignalGet, SignalGetUntracked, SignalSet,
-    SignalUpdate, WriteSignal,
+    component, create_effect, create_memo, create_signal, event_target_value, provide_context, spawn_local,
+    store_value, view, Callback, IntoView, ReadSignal, Show, Signal, SignalGet, SignalGetUntracked, SignalSet,
+    SignalUpdate, SignalWith, SignalWithUntracked, StoredValue, WriteSignal,
 };
 use wasm_bindgen::JsCast;
 use leptos_meta::{provide_meta_context, Title};
@@ -81,6 +89,62 @@ fn update_view(
     }
 }
 
+/// Save the project once the debounce for `edit` has elapsed.
+/// Does nothing when newer edits superseded it or a save is running; that save starts another when it finishes.
+fn save_after_debounce(
+    edit: u64,
+    auto_save: StoredValue<AutoSave>,
+    set_save_status: WriteSignal<SaveStatus>,
+    current_project: ReadSignal<Project>,
+) {
+    let should_save = auto_save.try_update_value(|state| state.start_save(edit)).unwrap_or(false);
+    set_save_status.set(auto_save.with_value(AutoSave::status));
+    if should_save {
+        spawn_save(edit, auto_save, set_save_status, current_project);
+    }
+}
+
+/// Save edits still waiting for their debounce straight away, before the project is swapped or the page unloads
+fn flush_pending_save(
+    auto_save: StoredValue<AutoSave>,
+    set_save_status: WriteSignal<SaveStatus>,
+    current_project: ReadSignal<Project>,
+) {
+    let Some(edit) = auto_save.try_update_value(AutoSave::flush).flatten() else {
+        return;
+    };
+    set_save_status.set(auto_save.with_value(AutoSave::status));
+    spawn_save(edit, auto_save, set_save_status, current_project);
+}
+
+/// Write the project as it is now, recording the outcome for `edit`
+fn spawn_save(
+    edit: u64,
+    auto_save: StoredValue<AutoSave>,
+    set_save_status: WriteSignal<SaveStatus>,
+    current_project: ReadSignal<Project>,
+) {
+    let project = current_project.get_untracked();
+    spawn_local(async move {
+        let storage = IndexedDbStorage;
+        let result = match storage.save_project(&project).await {
+            Ok(()) => storage.set_current_project_id(&project.metadata.id).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = &result {
+            web_sys::console::error_1(&format!("Auto-save failed: {e}").into());
+        }
+
+        let save_again = auto_save
+            .try_update_value(|state| state.finish_save(edit, result, js_sys::Date::now()))
+            .unwrap_or(false);
+        set_save_status.set(auto_save.with_value(AutoSave::status));
+        if save_again {
+            save_after_debounce(auto_save.with_value(AutoSave::latest_edit), auto_save, set_save_status, current_project);
+        }
+    });
+}
+
 #[component]
 #[must_use]
 #[allow(clippy::too_many_lines)]
@@ -120,6 +184,8 @@ pub fn App() -> impl IntoView {
     let (graph, set_graph) = create_signal(RailwayGraph::new());
     let (legend, set_legend) = create_signal(Legend::default());
     let (settings, set_settings) = create_signal(crate::models::ProjectSettings::default());
+    let (acknowledged_conflicts, set_acknowledged_conflicts) = create_signal(std::collections::HashSet::new());
+    let (service_patterns, set_service_patterns) = create_signal(Vec::new());
     let (views, set_views) = create_signal(Vec::new());
     let (is_loading, set_is_loading) = create_signal(true);
     let (initial_load_complete, set_initial_load_complete) = create_signal(false);
@@ -134,6 +200,7 @@ pub fn App() -> impl IntoView {
     let (train_journeys, set_train_journeys) =
         create_signal(std::collections::HashMap::<uuid::Uuid, TrainJourney>::new());
     let (selected_day, set_selected_day) = create_signal(None::<chrono::Weekday>);
+    let (missing_journeys, set_missing_journeys) = create_signal(HashMap::new());
 
     // Project manager state
     let (show_project_manager, set_show_project_manager) = create_signal(false);
@@ -174,6 +241,15 @@ pub fn App() -> impl IntoView {
     // Undo/redo management
     let undo_manager = store_value(UndoManager::default());
     let (is_performing_undo_redo, set_is_performing_undo_redo) = create_signal(false);
+    let (checkpoints, set_checkpoints) = create_signal(Vec::<crate::models::CheckpointEntry>::new());
+    let (history_entries, set_history_entries) = create_signal(Vec::<crate::models::HistoryEntry>::new());
+
+    // Store a labeled snapshot of the current state (used before destructive edits)
+    let create_checkpoint = Callback::new(move |label: String| {
+        let snapshot = UndoSnapshot::new(graph.get_untracked(), lines.get_untracked());
+        undo_manager.update_value(|manager| manager.named_checkpoint(&label, snapshot));
+        set_checkpoints.set(undo_manager.with_value(UndoManager::checkpoints));
+    });
 
     // Create debounced function for capturing snapshots
     let record_snapshot = store_value(leptos::leptos_dom::helpers::debounce(
@@ -188,6 +264,7 @@ pub fn App() -> impl IntoView {
             undo_manager.update_value(|manager| {
                 manager.push_snapshot(snapshot);
             });
+            set_history_entries.set(undo_manager.with_value(UndoManager::history));
         },
     ));
 
@@ -257,6 +334,8 @@ pub fn App() -> impl IntoView {
             set_lines.set(project.lines.clone());
             set_folders.set(project.folders.clone());
             set_graph.set(project.graph.clone());
+            set_acknowledged_conflicts.set(project.acknowledged_conflicts.clone());
+            set_service_patterns.set(project.service_patterns.clone());
             set_legend.set(project.legend);
             set_settings.set(project.settings);
 
@@ -267,11 +346,7 @@ pub fn App() -> impl IntoView {
             }
 
             // Extract viewport states into separate signal
-            let viewports: HashMap<Uuid, ViewportState> = views
-                .iter()
-                .map(|v| (v.id, v.viewport_state.clone()))
-                .collect();
-            set_viewport_states.set(viewports);
+            set_viewport_states.set(GraphView::viewports(&views));
             set_infrastructure_viewport.set(project.infrastructure_viewport.clone());
 
             set_views.set(views.clone());
@@ -351,13 +426,17 @@ pub fn App() -> impl IntoView {
         (node_count, edge_count)
     });
 
-    // Auto-save project whenever lines, folders, graph, legend, settings, views, viewport states, or active tab change
+    // Auto-save project a short while after lines, folders, graph, legend, settings, acknowledged conflicts, service patterns, views, viewport states, or active tab change
+    let auto_save = store_value(AutoSave::default());
+    let (save_status, set_save_status) = create_signal(SaveStatus::Idle);
     create_effect(move |_| {
         let current_lines = lines.get();
         let current_folders = folders.get();
         let current_graph = graph.get();
         let current_legend = legend.get();
         let current_settings = settings.get();
+        let current_acknowledged = acknowledged_conflicts.get();
+        let current_service_patterns = service_patterns.get();
         let current_views = views.get();
         let current_viewports = viewport_states.get();
         let current_infrastructure_viewport = infrastructure_viewport.get();
@@ -374,12 +453,7 @@ pub fn App() -> impl IntoView {
             // Merge viewport states back into views for saving
             let views_with_viewports: Vec<GraphView> = current_views
                 .into_iter()
-                .map(|mut v| {
-                    if let Some(viewport) = current_viewports.get(&v.id) {
-                        v.viewport_state = viewport.clone();
-                    }
-                    v
-                })
+                .map(|v| v.with_saved_viewport(&current_viewports))
                 .collect();
 
             // Update project with current data, preserving metadata
@@ -388,29 +462,34 @@ pub fn App() -> impl IntoView {
             proj.graph = current_graph;
             proj.legend = current_legend;
             proj.settings = current_settings;
+            proj.acknowledged_conflicts = current_acknowledged;
+            proj.service_patterns = current_service_patterns;
             proj.views = views_with_viewports;
             proj.active_tab_id = active_tab_id;
             proj.infrastructure_viewport = current_infrastructure_viewport;
             proj.touch_updated_at();
 
             // Update current_project signal to keep it synchronized
-            set_current_project.set(proj.clone());
-
-            let project_id = proj.metadata.id.clone();
-            spawn_local(async move {
-                if let Err(e) = storage.save_project(&proj).await {
-                    web_sys::console::error_1(&format!("Auto-save failed: {e}").into());
-                    return;
-                }
-                if let Err(e) = storage.set_current_project_id(&project_id).await {
-                    web_sys::console::error_1(
-                        &format!("Failed to set current project ID: {e}").into(),
-                    );
-                }
-            });
+            set_current_project.set(proj);
+
+            let edit = auto_save.try_update_value(AutoSave::record_edit).unwrap_or_default();
+            set_save_status.set(auto_save.with_value(AutoSave::status));
+            let delay_seconds = user_settings.with_untracked(|settings| settings.auto_save_delay_seconds);
+            leptos::set_timeout(
+                move || save_after_debounce(edit, auto_save, set_save_status, current_project),
+                std::time::Duration::from_secs_f64(delay_seconds.max(0.0)),
+            );
         }
     });
 
+    // Write pending edits when the tab is closed or navigated away, rather than waiting for the debounce
+    leptos::leptos_dom::helpers::window_event_listener(leptos::ev::pagehide, move |_| {
+        flush_pending_save(auto_save, set_save_status, current_project);
+    });
+    leptos::leptos_dom::helpers::window_event_listener(leptos::ev::beforeunload, move |_| {
+        flush_pending_save(auto_save, set_save_status, current_project);
+    });
+
     // Mark loading complete once initial data is loaded
     create_effect(move |_| {
         if initial_load_complete.get() {
@@ -418,11 +497,14 @@ pub fn App() -> impl IntoView {
         }
     });
 
-    // Generate train journeys when lines or graph change
+    let reference_week = create_memo(move |_| settings.with(|settings| settings.reference_week));
+
+    // Generate train journeys when lines, graph, service patterns or the reference week change
     create_effect(move |_| {
         let current_lines = lines.get();
         let current_graph = graph.get();
         let day_filter = selected_day.get();
+        let reference_week = reference_week.get();
 
         // Filter to only visible lines
         let visible_lines: Vec<_> = current_lines
@@ -431,11 +513,29 @@ pub fn App() -> impl IntoView {
             .collect();
 
         // Generate journeys for the full day
-        let new_journeys =
-            TrainJourney::generate_journeys(&visible_lines, &current_graph, day_filter);
+        let new_journeys = service_patterns.with(|patterns| {
+            TrainJourney::generate_journeys_with_patterns(&visible_lines, &current_graph, day_filter, patterns, reference_week)
+        });
+        let horizon = day_filter.map_or(SimulationHorizon::FullWeek, SimulationHorizon::SingleDay);
+        set_missing_journeys.set(service_patterns.with(|patterns| {
+            lines_without_journeys(&visible_lines, &new_journeys, horizon, patterns, reference_week)
+        }));
         set_train_journeys.set(new_journeys);
     });
 
+    // Reference timetable drawn behind the time graph; kept out of conflict detection
+    let (reference_project, set_reference_project) = create_signal(None::<Project>);
+    let (reference_timetable, set_reference_timetable) = create_signal(None::<ReferenceTimetable>);
+    let (reference_visible, set_reference_visible) = create_signal(true);
+    create_effect(move |_| {
+        let day_filter = selected_day.get();
+        let timetable = reference_project.with(|project| {
+            let project = project.as_ref()?;
+            Some(graph.with(|current_graph| ReferenceTimetable::from_project(project, current_graph, day_filter)))
+        });
+        set_reference_timetable.set(timetable);
+    });
+
     // Compute conflicts at app level using worker
     let (conflicts, set_conflicts) = create_signal(Vec::new());
 
@@ -540,10 +640,14 @@ pub fn App() -> impl IntoView {
         }
 
         // Extract viewport states
-        let viewports: HashMap<Uuid, ViewportState> = project_views
-            .iter()
-            .map(|v| (v.id, v.viewport_state.clone()))
-            .collect();
+        let viewports = GraphView::viewports(&project_views);
+
+        // Save the previous project's pending edits before its state is replaced
+        flush_pending_save(auto_save, set_save_status, current_project);
+
+        // Checkpoints belong to the previous project
+        undo_manager.update_value(UndoManager::clear_checkpoints);
+        set_checkpoints.set(Vec::new());
 
         // Batch all signal updates to prevent auto-save from triggering with partial state
         leptos::batch(move || {
@@ -553,6 +657,8 @@ pub fn App() -> impl IntoView {
             set_graph.set(project.graph.clone());
             set_legend.set(project.legend.clone());
             set_settings.set(project.settings.clone());
+            set_acknowledged_conflicts.set(project.acknowledged_conflicts.clone());
+            set_service_patterns.set(project.service_patterns.clone());
             set_viewport_states.set(viewports);
             set_infrastructure_viewport.set(project.infrastructure_viewport.clone());
             set_views.set(project_views.clone());
@@ -577,6 +683,46 @@ pub fn App() -> impl IntoView {
     provide_context((user_settings, set_user_settings));
     provide_context((is_capturing_shortcut, set_is_capturing_shortcut));
 
+    // Line visibility to restore after the solo shortcut, kept across tab switches
+    provide_context(LineSolo(store_value(SoloVisibility::default())));
+    provide_context(MissingJourneys(missing_journeys));
+    provide_context(AcknowledgedConflicts {
+        keys: acknowledged_conflicts,
+        set_keys: set_acknowledged_conflicts,
+    });
+    provide_context(ServicePatterns {
+        patterns: service_patterns,
+        set_patterns: set_service_patterns,
+    });
+
+    // Journey highlighted across the time graph and infrastructure view
+    let (highlighted_journey_id, set_highlighted_journey_id) = create_signal(None::<uuid::Uuid>);
+    provide_context(HighlightedJourney {
+        journey_id: highlighted_journey_id,
+        set_journey_id: set_highlighted_journey_id,
+    });
+
+    provide_context(ReferenceOverlay {
+        timetable: reference_timetable,
+        visible: reference_visible,
+        set_visible: set_reference_visible,
+        set_project: set_reference_project,
+    });
+
+    // Stations in view in one of the infrastructure and time graph views, highlighted in the other
+    let (focused_stations, set_focused_stations) = create_signal(None);
+    provide_context(FocusedStations {
+        focus: focused_stations,
+        set_focus: set_focused_stations,
+    });
+
+    // Current time shared by the time graph and the infrastructure view's train markers
+    let (visualization_time, set_visualization_time) = create_signal(chrono::Local::now().naive_local());
+    provide_context(VisualizationTime {
+        time: visualization_time,
+        set_time: set_visualization_time,
+    });
+
     // Setup tab switching keyboard shortcuts
     crate::components::tab_shortcuts::setup_tab_switching(
         is_capturing_shortcut,
@@ -590,6 +736,35 @@ pub fn App() -> impl IntoView {
         set_lines.set(snapshot.lines);
     };
 
+    let restore_checkpoint = Callback::new(move |checkpoint: crate::models::CheckpointEntry| {
+        if let Some(snapshot) = undo_manager.with_value(|manager| manager.restore_checkpoint(checkpoint.id)) {
+            restore_snapshot(snapshot);
+            show_toast(format!("Restored checkpoint: {}", checkpoint.label));
+        }
+    });
+
+    // Jump to an arbitrary point in the undo history
+    let restore_history = Callback::new(move |index: usize| {
+        set_is_performing_undo_redo.set(true);
+
+        spawn_local(async move {
+            if let Some(snapshot) = undo_manager.try_update_value(|manager| manager.restore_to(index)).flatten() {
+                restore_snapshot(snapshot);
+                set_history_entries.set(undo_manager.with_value(UndoManager::history));
+                show_toast("Restored earlier state".to_string());
+
+                // Wait longer than the debounce delay to ensure pending debounced
+                // calls don't record the restored state
+                gloo_timers::future::TimeoutFuture::new(400).await;
+            }
+            set_is_performing_undo_redo.set(false);
+        });
+    });
+    provide_context(UndoHistory {
+        entries: history_entries,
+        on_restore: restore_history,
+    });
+
     // Setup undo/redo keyboard shortcuts
     leptos::leptos_dom::helpers::window_event_listener(leptos::ev::keydown, move |ev| {
         // Don't handle shortcuts when capturing in the shortcuts editor
@@ -644,6 +819,7 @@ pub fn App() -> impl IntoView {
 
                     if let Some(snapshot) = snapshot_opt.into_inner() {
                         restore_snapshot(snapshot);
+                        set_history_entries.set(undo_manager.with_value(UndoManager::history));
                         show_toast("Undoing last change".to_string());
 
                         // Wait longer than the debounce delay to ensure pending debounced
@@ -677,6 +853,7 @@ pub fn App() -> impl IntoView {
 
                     if let Some(snapshot) = snapshot_opt.into_inner() {
                         restore_snapshot(snapshot);
+                        set_history_entries.set(undo_manager.with_value(UndoManager::history));
                         show_toast("Redoing last change".to_string());
 
                         // Wait longer than the debounce delay to ensure pending debounced
@@ -869,6 +1046,7 @@ pub fn App() -> impl IntoView {
                     }}
                     </div>
                     <div class="app-header-actions">
+                        <SaveIndicator status=save_status.into() />
                         <Button
                             class="button-icon-only"
                             on_click=leptos::Callback::new(move |_| set_sidebar_visible.update(|v| *v = !*v))
@@ -877,6 +1055,7 @@ pub fn App() -> impl IntoView {
                         >
                             <i class="fa-solid fa-bars-staggered"></i>
                         </Button>
+                        <CheckpointList checkpoints=checkpoints on_restore=restore_checkpoint />
                         <ReportIssueButton />
                     </div>
                 </div>
@@ -911,11 +1090,15 @@ pub fn App() -> impl IntoView {
                                 set_show_project_manager.set(true);
                             })
                             sidebar_visible=sidebar_visible
+                            on_checkpoint=create_checkpoint
+                            train_journeys=train_journeys
                         />
                     }.into_view(),
                     AppTab::GraphView(view_id) => {
-                        // Find the view with matching ID
-                        if let Some(view) = views.get().iter().find(|v| v.id == view_id).cloned() {
+                        // Find the view with matching ID, restoring the viewport it was last left at
+                        let view = views.get().iter().find(|v| v.id == view_id).cloned()
+                            .map(|v| v.with_saved_viewport(&viewport_states.get_untracked()));
+                        if let Some(view) = view {
                             view! {
                                 <TimeGraph
                                     lines=lines
@@ -964,6 +1147,11 @@ pub fn App() -> impl IntoView {
                 current_project=current_project.into()
             />
 
+            <StorageWarning
+                ready=Signal::derive(move || !is_loading.get())
+                current_project_id=Signal::derive(move || current_project.with(|p| p.metadata.id.clone()))
+            />
+
             <AlphaDisclaimer />
             <ChangelogPopup
                 manual_open=Signal::derive(move || manual_open_changelog.get())
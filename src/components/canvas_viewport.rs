@@ -2,11 +2,20 @@ use leptos::{batch, create_signal, ReadSignal, WriteSignal, SignalGet, SignalSet
 use std::time::Duration;
 use wasm_bindgen::JsCast;
 use web_sys::WheelEvent;
-use crate::models::UserSettings;
+use crate::components::graph_canvas::types::ViewportState;
+use crate::models::{RailwayGraph, Stations, UserSettings};
+use petgraph::stable_graph::NodeIndex;
+use std::collections::HashSet;
 
 // WASD panning speed (pixels per frame at 60fps)
 const WASD_PAN_SPEED: f64 = 10.0;
 
+// Zoom-to-fit settings
+const FIT_MARGIN: f64 = 50.0;
+const FIT_DEFAULT_ZOOM: f64 = 1.0;
+const MIN_ZOOM: f64 = 0.1;
+const MAX_ZOOM: f64 = 25.0;
+
 #[derive(Clone, Copy)]
 pub struct ViewportSignals {
     pub zoom_level: ReadSignal<f64>,
@@ -83,6 +92,72 @@ pub fn create_viewport_signals_with_initial(enable_horizontal_zoom: bool, initia
     }
 }
 
+/// World-space rectangle (left, top, right, bottom) visible on a canvas at the given zoom and pan
+#[must_use]
+pub fn visible_world_bounds(zoom: f64, (pan_x, pan_y): (f64, f64), (width, height): (f64, f64)) -> (f64, f64, f64, f64) {
+    (-pan_x / zoom, -pan_y / zoom, (width - pan_x) / zoom, (height - pan_y) / zoom)
+}
+
+/// Nodes positioned inside world-space `bounds` (left, top, right, bottom), edges included
+#[must_use]
+pub fn nodes_within_bounds(graph: &RailwayGraph, (left, top, right, bottom): (f64, f64, f64, f64)) -> HashSet<NodeIndex> {
+    graph.graph.node_indices()
+        .filter(|&idx| graph.get_station_position(idx)
+            .is_some_and(|(x, y)| (left..=right).contains(&x) && (top..=bottom).contains(&y)))
+        .collect()
+}
+
+/// Pan offsets that place a world point at the center of the canvas
+#[must_use]
+pub fn pan_to_center(world: (f64, f64), zoom: f64, canvas_dims: (f64, f64)) -> (f64, f64) {
+    (canvas_dims.0 / 2.0 - world.0 * zoom, canvas_dims.1 / 2.0 - world.1 * zoom)
+}
+
+/// Compute a viewport that centers all node positions on the canvas and fits them within a margin.
+/// Falls back to the default zoom when there is nothing to fit or the network has no extent.
+#[must_use]
+pub fn fit_to_bounds(graph: &RailwayGraph, canvas_dims: (f64, f64)) -> ViewportState {
+    fit_to_bounds_with_margin(graph, canvas_dims, FIT_MARGIN)
+}
+
+/// Same as [`fit_to_bounds`], with an explicit margin in screen pixels
+#[must_use]
+pub fn fit_to_bounds_with_margin(graph: &RailwayGraph, canvas_dims: (f64, f64), margin: f64) -> ViewportState {
+    let (canvas_width, canvas_height) = canvas_dims;
+    let positions: Vec<(f64, f64)> = graph.graph.node_indices()
+        .filter_map(|idx| graph.get_station_position(idx))
+        .collect();
+
+    let Some(&(first_x, first_y)) = positions.first() else {
+        return ViewportState { zoom_level: FIT_DEFAULT_ZOOM, zoom_level_x: 1.0, pan_offset_x: 0.0, pan_offset_y: 0.0 };
+    };
+
+    let (min_x, max_x, min_y, max_y) = positions.iter().fold(
+        (first_x, first_x, first_y, first_y),
+        |(min_x, max_x, min_y, max_y), &(x, y)| (min_x.min(x), max_x.max(x), min_y.min(y), max_y.max(y)),
+    );
+
+    let available_width = (canvas_width - 2.0 * margin).max(1.0);
+    let available_height = (canvas_height - 2.0 * margin).max(1.0);
+    let zoom_x = (max_x > min_x).then(|| available_width / (max_x - min_x));
+    let zoom_y = (max_y > min_y).then(|| available_height / (max_y - min_y));
+    let zoom = match (zoom_x, zoom_y) {
+        (Some(zx), Some(zy)) => zx.min(zy),
+        (Some(z), None) | (None, Some(z)) => z,
+        (None, None) => FIT_DEFAULT_ZOOM,
+    }.clamp(MIN_ZOOM, MAX_ZOOM);
+
+    let center_x = (min_x + max_x) / 2.0;
+    let center_y = (min_y + max_y) / 2.0;
+
+    ViewportState {
+        zoom_level: zoom,
+        zoom_level_x: 1.0,
+        pan_offset_x: canvas_width / 2.0 - center_x * zoom,
+        pan_offset_y: canvas_height / 2.0 - center_y * zoom,
+    }
+}
+
 pub fn handle_pan_start(
     x: f64,
     y: f64,
@@ -156,7 +231,7 @@ fn apply_horizontal_zoom(
     };
 
     let old_zoom_x = zoom_x_signal.get();
-    let new_zoom_x = (old_zoom_x * zoom_factor).clamp(0.1, 25.0);
+    let new_zoom_x = (old_zoom_x * zoom_factor).clamp(MIN_ZOOM, MAX_ZOOM);
 
     let pan_x = viewport.pan_offset_x.get();
     let new_pan_x = mouse_x - (mouse_x - pan_x) * (new_zoom_x / old_zoom_x);
@@ -176,8 +251,8 @@ fn apply_normal_zoom(
     canvas_dimensions: Option<(f64, f64)>,
 ) {
     let old_zoom = viewport.zoom_level.get();
-    let min = min_zoom.unwrap_or(0.1);
-    let new_zoom = (old_zoom * zoom_factor).clamp(min, 25.0);
+    let min = min_zoom.unwrap_or(MIN_ZOOM);
+    let new_zoom = (old_zoom * zoom_factor).clamp(min, MAX_ZOOM);
 
     let pan_x = viewport.pan_offset_x.get();
     let pan_y = viewport.pan_offset_y.get();
@@ -416,3 +491,56 @@ pub fn setup_keyboard_listeners(
         }
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph_with_positions(positions: &[(f64, f64)]) -> RailwayGraph {
+        let mut graph = RailwayGraph::new();
+        for (i, &position) in positions.iter().enumerate() {
+            let idx = graph.add_or_get_station(format!("Station {i}"));
+            graph.set_station_position(idx, position);
+        }
+        graph
+    }
+
+    #[test]
+    fn test_fit_to_bounds_centers_and_fits_network() {
+        let graph = graph_with_positions(&[(0.0, 0.0), (200.0, 0.0), (200.0, 100.0)]);
+        let viewport = fit_to_bounds(&graph, (500.0, 400.0));
+
+        // Width limits the zoom: (500 - 2 * 50) / 200 = 2.0
+        assert!((viewport.zoom_level - 2.0).abs() < f64::EPSILON);
+        // Center (100, 50) maps to the canvas center (250, 200)
+        assert!((viewport.pan_offset_x - 50.0).abs() < f64::EPSILON);
+        assert!((viewport.pan_offset_y - 100.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_nodes_within_viewport_bounds() {
+        let graph = graph_with_positions(&[(0.0, 0.0), (100.0, 50.0), (400.0, 50.0), (100.0, -20.0)]);
+
+        // A 300x200 canvas at zoom 2, panned so world (-10, -10) is the top-left corner
+        let bounds = visible_world_bounds(2.0, (20.0, 20.0), (300.0, 200.0));
+        assert_eq!(bounds, (-10.0, -10.0, 140.0, 90.0));
+
+        let visible = nodes_within_bounds(&graph, bounds);
+        assert_eq!(visible, [NodeIndex::new(0), NodeIndex::new(1)].into_iter().collect());
+
+        // Nodes on the edge count as visible
+        assert_eq!(nodes_within_bounds(&graph, (100.0, 50.0, 400.0, 50.0)).len(), 2);
+        assert!(nodes_within_bounds(&RailwayGraph::new(), bounds).is_empty());
+    }
+
+    #[test]
+    fn test_fit_to_bounds_degenerate_cases_use_default_zoom() {
+        let empty = fit_to_bounds(&RailwayGraph::new(), (500.0, 400.0));
+        assert!((empty.zoom_level - FIT_DEFAULT_ZOOM).abs() < f64::EPSILON);
+
+        let single = fit_to_bounds(&graph_with_positions(&[(10.0, 20.0)]), (500.0, 400.0));
+        assert!((single.zoom_level - FIT_DEFAULT_ZOOM).abs() < f64::EPSILON);
+        assert!((single.pan_offset_x - 240.0).abs() < f64::EPSILON);
+        assert!((single.pan_offset_y - 180.0).abs() < f64::EPSILON);
+    }
+}
@@ -0,0 +1,45 @@
+use crate::models::CheckpointEntry;
+use leptos::{component, view, IntoView, ReadSignal, Callback, Callable, create_signal, SignalGet, SignalSet, SignalUpdate};
+
+#[component]
+#[must_use]
+pub fn CheckpointList(
+    /// Checkpoints, most recent first
+    checkpoints: ReadSignal<Vec<CheckpointEntry>>,
+    /// Called with the checkpoint to restore
+    on_restore: Callback<CheckpointEntry>,
+) -> impl IntoView {
+    let (is_open, set_is_open) = create_signal(false);
+
+    view! {
+        <div class="checkpoint-list">
+            <button
+                class="button-icon-only"
+                on:click=move |_| set_is_open.update(|open| *open = !*open)
+                prop:disabled=move || checkpoints.get().is_empty()
+                title="Restore a checkpoint taken before a deletion"
+            >
+                <i class="fa-solid fa-clock-rotate-left"></i>
+            </button>
+            {move || is_open.get().then(|| view! {
+                <ul class="checkpoint-list-items">
+                    {checkpoints.get().into_iter().map(|checkpoint| {
+                        let label = checkpoint.label.clone();
+                        let taken_at = checkpoint.taken_at.format("%H:%M:%S").to_string();
+                        view! {
+                            <li>
+                                <button on:click=move |_| {
+                                    on_restore.call(checkpoint.clone());
+                                    set_is_open.set(false);
+                                }>
+                                    <span class="checkpoint-label">{label}</span>
+                                    <span class="checkpoint-time">{taken_at}</span>
+                                </button>
+                            </li>
+                        }
+                    }).collect::<Vec<_>>()}
+                </ul>
+            })}
+        </div>
+    }
+}
@@ -33,7 +33,7 @@ pub fn ConflictTooltip(
                     }).unwrap_or("?");
                     conflict.format_platform_message(&station1_name, platform_name)
                 } else {
-                    conflict.format_message(&station1_name, &station2_name)
+                    conflict.format_message(&station1_name, &station2_name, conflict.distance_along(&current_graph))
                 };
                 let timestamp = conflict.time.format("%H:%M:%S");
                 let tooltip_text = format!("{timestamp} - {message}");
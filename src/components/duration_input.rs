@@ -1,8 +1,11 @@
-use leptos::{component, event_target_value, IntoView, Signal, SignalGetUntracked, SignalGet, view};
+use leptos::{component, create_signal, event_target_value, IntoView, Signal, SignalGetUntracked, SignalGet, SignalSet, view};
+use crate::time::parse_flexible_duration;
 use chrono::Duration;
 use web_sys::KeyboardEvent;
 use std::rc::Rc;
 
+const INVALID_DURATION_HINT: &str = "Enter a duration like 8:05, 8m5s or 485s";
+
 fn duration_to_hhmmss(duration: Duration) -> String {
     let total_seconds = duration.num_seconds();
     let hours = total_seconds / 3600;
@@ -11,32 +14,6 @@ fn duration_to_hhmmss(duration: Duration) -> String {
     format!("{hours:02}:{minutes:02}:{seconds:02}")
 }
 
-fn parse_hhmmss(input: &str) -> Option<Duration> {
-    // Try flexible format (NIMBY Rails format)
-    if let Some((hours, minutes, seconds)) = crate::time::parse_flexible_time(input) {
-        // For durations, we allow any non-negative values (no 24-hour or 60-minute limit)
-        if hours >= 0 && minutes >= 0 && seconds >= 0 {
-            return Some(Duration::hours(hours) + Duration::minutes(minutes) + Duration::seconds(seconds));
-        }
-    }
-
-    // Fall back to strict format
-    let parts: Vec<&str> = input.split(':').collect();
-    if parts.len() != 3 {
-        return None;
-    }
-
-    let hours: i64 = parts[0].parse().ok()?;
-    let minutes: i64 = parts[1].parse().ok()?;
-    let seconds: i64 = parts[2].parse().ok()?;
-
-    if hours < 0 || !(0..60).contains(&minutes) || !(0..60).contains(&seconds) {
-        return None;
-    }
-
-    Some(Duration::hours(hours) + Duration::minutes(minutes) + Duration::seconds(seconds))
-}
-
 #[component]
 pub fn DurationInput(
     duration: Signal<Duration>,
@@ -44,16 +21,22 @@ pub fn DurationInput(
 ) -> impl IntoView {
     let on_change = Rc::new(on_change);
     let on_change_keydown = on_change.clone();
+    let (invalid, set_invalid) = create_signal(false);
 
     view! {
         <input
             type="text"
             class="duration-input"
+            class:invalid=invalid
+            title=move || invalid.get().then_some(INVALID_DURATION_HINT)
             placeholder="00:30:00"
             prop:value=move || duration_to_hhmmss(duration.get())
+            on:input=move |ev| {
+                set_invalid.set(parse_flexible_duration(&event_target_value(&ev)).is_none());
+            }
             on:change=move |ev| {
                 let input_str = event_target_value(&ev);
-                if let Some(new_duration) = parse_hhmmss(&input_str) {
+                if let Some(new_duration) = parse_flexible_duration(&input_str) {
                     on_change(new_duration);
                 }
             }
@@ -85,18 +68,26 @@ pub fn OptionalDurationInput(
 ) -> impl IntoView {
     let on_change = Rc::new(on_change);
     let on_change_keydown = on_change.clone();
+    let (invalid, set_invalid) = create_signal(false);
 
     view! {
         <input
             type="text"
             class="duration-input"
+            class:invalid=invalid
+            title=move || invalid.get().then_some(INVALID_DURATION_HINT)
             placeholder="-"
             prop:value=move || duration.get().map_or(String::new(), duration_to_hhmmss)
+            on:input=move |ev| {
+                let input_str = event_target_value(&ev);
+                let cleared = matches!(input_str.trim(), "" | "-");
+                set_invalid.set(!cleared && parse_flexible_duration(&input_str).is_none());
+            }
             on:change=move |ev| {
                 let input_str = event_target_value(&ev).trim().to_string();
                 if input_str.is_empty() || input_str == "-" {
                     on_change(None);
-                } else if let Some(new_duration) = parse_hhmmss(&input_str) {
+                } else if let Some(new_duration) = parse_flexible_duration(&input_str) {
                     on_change(Some(new_duration));
                 }
             }
@@ -130,7 +121,7 @@ mod tests {
     #[test]
     fn test_parse_duration_large_hours() {
         // Durations should accept any non-negative hours
-        let result = parse_hhmmss("26.0.0");
+        let result = parse_flexible_duration("26.0.0");
         assert!(result.is_some());
         let duration = result.expect("should parse");
         assert_eq!(duration.num_hours(), 26);
@@ -139,7 +130,7 @@ mod tests {
     #[test]
     fn test_parse_duration_large_minutes() {
         // Durations should accept any non-negative minutes
-        let result = parse_hhmmss("0.70.0");
+        let result = parse_flexible_duration("0.70.0");
         assert!(result.is_some());
         let duration = result.expect("should parse");
         assert_eq!(duration.num_minutes(), 70);
@@ -148,7 +139,7 @@ mod tests {
     #[test]
     fn test_parse_duration_large_seconds() {
         // Durations should accept any non-negative seconds
-        let result = parse_hhmmss("0.0.90");
+        let result = parse_flexible_duration("0.0.90");
         assert!(result.is_some());
         let duration = result.expect("should parse");
         assert_eq!(duration.num_seconds(), 90);
@@ -156,7 +147,7 @@ mod tests {
 
     #[test]
     fn test_parse_duration_nimby_format() {
-        let result = parse_hhmmss("5.15.");
+        let result = parse_flexible_duration("5.15.");
         assert!(result.is_some());
         let duration = result.expect("should parse");
         assert_eq!(duration.num_hours(), 5);
@@ -165,7 +156,7 @@ mod tests {
 
     #[test]
     fn test_parse_duration_standard_format() {
-        let result = parse_hhmmss("01:30:45");
+        let result = parse_flexible_duration("01:30:45");
         assert!(result.is_some());
         let duration = result.expect("should parse");
         assert_eq!(duration.num_seconds(), 3600 + 30 * 60 + 45);
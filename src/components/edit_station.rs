@@ -1,14 +1,35 @@
 use crate::components::window::Window;
 use crate::components::platform_editor::PlatformEditor;
 use crate::components::connect_to_station::ConnectToStation;
-use crate::models::{RailwayGraph, Platform};
-use leptos::{component, create_effect, create_signal, event_target_checked, event_target_value, IntoView, ReadSignal, Signal, SignalGet, SignalSet, SignalGetUntracked, view, For};
+use crate::components::platform_timeline::PlatformTimeline;
+use crate::models::{RailwayGraph, Platform, StationNode, Stations};
+use crate::train_journey::TrainJourney;
+use leptos::{component, create_effect, create_signal, event_target_checked, event_target_value, IntoView, ReadSignal, Signal, SignalGet, SignalSet, SignalGetUntracked, SignalWith, WriteSignal, view, For, Show};
 use petgraph::stable_graph::{NodeIndex, EdgeIndex};
 use petgraph::visit::EdgeRef;
 use std::rc::Rc;
 
 type TrackDefaultsCallback = Rc<dyn Fn(EdgeIndex, Option<usize>, Option<usize>)>;
 type AddConnectionCallback = Rc<dyn Fn(NodeIndex, NodeIndex)>;
+pub type SaveStationCallback = Rc<dyn Fn(NodeIndex, StationEdit)>;
+
+/// Values entered in the edit station dialog
+#[derive(Debug, Clone)]
+pub struct StationEdit {
+    pub name: String,
+    pub passing_loop: bool,
+    pub is_depot: bool,
+    /// Minimum dwell in seconds
+    pub min_dwell_secs: Option<i64>,
+    /// Conflict station margin in seconds, `None` to use the project-wide margin
+    pub conflict_margin_secs: Option<i64>,
+    pub platforms: Vec<Platform>,
+    /// Label rotation in degrees clockwise
+    pub label_angle: f64,
+    pub label_offset: (f64, f64),
+    /// Exact canvas position typed in while auto layout is off; `None` leaves the station where it is
+    pub position: Option<(f64, f64)>,
+}
 
 #[derive(Clone, Debug)]
 struct ConnectedTrack {
@@ -136,43 +157,259 @@ fn load_connected_tracks(station_idx: NodeIndex, graph: &RailwayGraph) -> Vec<Co
     tracks
 }
 
+/// Optional per-station timings, entered as whole seconds
+#[derive(Clone, Copy)]
+struct TimingDraft {
+    min_dwell: ReadSignal<String>,
+    set_min_dwell: WriteSignal<String>,
+    conflict_margin: ReadSignal<String>,
+    set_conflict_margin: WriteSignal<String>,
+}
+
+impl TimingDraft {
+    fn new() -> Self {
+        let (min_dwell, set_min_dwell) = create_signal(String::new());
+        let (conflict_margin, set_conflict_margin) = create_signal(String::new());
+        Self { min_dwell, set_min_dwell, conflict_margin, set_conflict_margin }
+    }
+
+    fn load(&self, station: &StationNode) {
+        let text = |secs: Option<i64>| secs.map(|secs| secs.to_string()).unwrap_or_default();
+        self.set_min_dwell.set(text(station.min_dwell_secs));
+        self.set_conflict_margin.set(text(station.conflict_margin_secs));
+    }
+
+    /// Entered (minimum dwell, conflict margin), each `None` when blank or not a positive number
+    fn entered(&self) -> (Option<i64>, Option<i64>) {
+        (parse_positive_secs(&self.min_dwell.get()), parse_positive_secs(&self.conflict_margin.get()))
+    }
+}
+
+#[component]
+fn StationTimingFields(timing: TimingDraft) -> impl IntoView {
+    let TimingDraft { min_dwell, set_min_dwell, conflict_margin, set_conflict_margin } = timing;
+    view! {
+        <div class="form-field">
+            <label>"Minimum dwell (s, optional)"</label>
+            <input
+                type="text"
+                placeholder="e.g., 60"
+                prop:value=move || min_dwell.get()
+                on:input=move |ev| set_min_dwell.set(event_target_value(&ev))
+            />
+            <p class="help-text">"Stops shorter than this are listed as issues"</p>
+        </div>
+        <div class="form-field">
+            <label>"Conflict margin (s, optional)"</label>
+            <input
+                type="text"
+                placeholder="Project default"
+                prop:value=move || conflict_margin.get()
+                on:input=move |ev| set_conflict_margin.set(event_target_value(&ev))
+            />
+            <p class="help-text">"Trains meeting this close to a stop here count as crossing at the station"</p>
+        </div>
+    }
+}
+
+/// Parse a whole number of seconds, treating blank, invalid or non-positive input as unset
+fn parse_positive_secs(text: &str) -> Option<i64> {
+    text.trim().parse::<i64>().ok().filter(|secs| *secs > 0)
+}
+
+#[component]
+fn LabelPlacementFields(
+    angle: ReadSignal<String>,
+    set_angle: WriteSignal<String>,
+    offset_x: ReadSignal<String>,
+    set_offset_x: WriteSignal<String>,
+    offset_y: ReadSignal<String>,
+    set_offset_y: WriteSignal<String>,
+) -> impl IntoView {
+    let reset = move |_| {
+        set_angle.set("0".to_string());
+        set_offset_x.set("0".to_string());
+        set_offset_y.set("0".to_string());
+    };
+
+    view! {
+        <div class="form-field">
+            <label>"Label rotation (°) and offset (x, y)"</label>
+            <div class="label-placement-inputs">
+                <input
+                    type="number"
+                    step="15"
+                    prop:value=move || angle.get()
+                    on:input=move |ev| set_angle.set(event_target_value(&ev))
+                />
+                <input
+                    type="number"
+                    prop:value=move || offset_x.get()
+                    on:input=move |ev| set_offset_x.set(event_target_value(&ev))
+                />
+                <input
+                    type="number"
+                    prop:value=move || offset_y.get()
+                    on:input=move |ev| set_offset_y.set(event_target_value(&ev))
+                />
+                <button on:click=reset>"Reset"</button>
+            </div>
+            <p class="help-text">"Turns the label around the station and shifts it, to untangle crowded areas"</p>
+        </div>
+    }
+}
+
+/// Parse a number field, treating blank or invalid input as zero
+fn parse_or_zero(value: &str) -> f64 {
+    value.trim().parse::<f64>().ok().filter(|v| v.is_finite()).unwrap_or(0.0)
+}
+
+/// Parse the X/Y coordinate fields. Both blank means no position was entered.
+fn parse_position(x: &str, y: &str) -> Result<Option<(f64, f64)>, &'static str> {
+    if x.trim().is_empty() && y.trim().is_empty() {
+        return Ok(None);
+    }
+    let parse = |value: &str| value.trim().parse::<f64>().ok().filter(|v| v.is_finite());
+    parse(x).zip(parse(y)).map(Some).ok_or("Enter a number for both X and Y")
+}
+
+/// Exact coordinates typed into the dialog, previewed on the canvas as they change
+#[derive(Clone, Copy)]
+struct PositionDraft {
+    x: ReadSignal<String>,
+    set_x: WriteSignal<String>,
+    y: ReadSignal<String>,
+    set_y: WriteSignal<String>,
+    parsed: Signal<Result<Option<(f64, f64)>, &'static str>>,
+    auto_layout_enabled: Signal<bool>,
+}
+
+impl PositionDraft {
+    fn new(
+        editing_station: ReadSignal<Option<NodeIndex>>,
+        auto_layout_enabled: Signal<bool>,
+        set_position_preview: WriteSignal<Option<(NodeIndex, (f64, f64))>>,
+    ) -> Self {
+        let (x, set_x) = create_signal(String::new());
+        let (y, set_y) = create_signal(String::new());
+        let parsed = Signal::derive(move || parse_position(&x.get(), &y.get()));
+
+        // The preview only overlays the canvas, so closing the dialog just drops it
+        create_effect(move |_| {
+            let typed = parsed.get().ok().flatten().filter(|_| !auto_layout_enabled.get_untracked());
+            set_position_preview.set(editing_station.get().zip(typed));
+        });
+        Self { x, set_x, y, set_y, parsed, auto_layout_enabled }
+    }
+
+    fn load(&self, position: Option<(f64, f64)>) {
+        self.set_x.set(position.map(|(x, _)| x.to_string()).unwrap_or_default());
+        self.set_y.set(position.map(|(_, y)| y.to_string()).unwrap_or_default());
+    }
+
+    /// Position to save, `None` when auto layout owns positions or the fields are blank
+    fn entered(&self) -> Result<Option<(f64, f64)>, &'static str> {
+        if self.auto_layout_enabled.get_untracked() { Ok(None) } else { self.parsed.get_untracked() }
+    }
+}
+
 #[component]
+fn PositionFields(position: PositionDraft, error: Signal<Option<&'static str>>) -> impl IntoView {
+    let PositionDraft { x, set_x, y, set_y, .. } = position;
+    view! {
+        <div class="form-field">
+            <label>"Position (x, y)"</label>
+            <div class="label-placement-inputs">
+                <input
+                    type="number"
+                    prop:value=move || x.get()
+                    on:input=move |ev| set_x.set(event_target_value(&ev))
+                />
+                <input
+                    type="number"
+                    prop:value=move || y.get()
+                    on:input=move |ev| set_y.set(event_target_value(&ev))
+                />
+            </div>
+            {move || error.get().map(|message| view! { <p class="name-taken-warning">{message}</p> })}
+            <p class="help-text">"Places the station exactly, without grid snapping, e.g. to match a survey"</p>
+        </div>
+    }
+}
+
+#[component]
+#[allow(clippy::too_many_lines)]
 pub fn EditStation(
     editing_station: ReadSignal<Option<NodeIndex>>,
     on_close: Rc<dyn Fn()>,
-    on_save: Rc<dyn Fn(NodeIndex, String, bool, Vec<Platform>)>,
+    on_save: SaveStationCallback,
     on_delete: Rc<dyn Fn(NodeIndex)>,
     graph: ReadSignal<RailwayGraph>,
     on_update_track_defaults: TrackDefaultsCallback,
     on_add_connection: AddConnectionCallback,
+    /// Where the canvas draws the station while coordinates are typed
+    set_position_preview: WriteSignal<Option<(NodeIndex, (f64, f64))>>,
+    #[prop(into)]
+    auto_layout_enabled: Signal<bool>,
+    #[prop(default = None)]
+    train_journeys: Option<ReadSignal<std::collections::HashMap<uuid::Uuid, TrainJourney>>>,
 ) -> impl IntoView {
     let (station_name, set_station_name) = create_signal(String::new());
     let (is_passing_loop, set_is_passing_loop) = create_signal(false);
+    let (is_depot, set_is_depot) = create_signal(false);
+    let timing = TimingDraft::new();
+    let (label_angle, set_label_angle) = create_signal(String::new());
+    let (label_offset_x, set_label_offset_x) = create_signal(String::new());
+    let (label_offset_y, set_label_offset_y) = create_signal(String::new());
+    let position = PositionDraft::new(editing_station, auto_layout_enabled, set_position_preview);
     let (platforms, set_platforms) = create_signal(Vec::<Platform>::new());
     let (connected_tracks, set_connected_tracks) = create_signal(Vec::<ConnectedTrack>::new());
 
     // Load current station data when dialog opens
     create_effect(move |_| {
-        if let Some(idx) = editing_station.get() {
-            let current_graph = graph.get_untracked();
-            if let Some(node) = current_graph.graph.node_weight(idx) {
-                if let Some(station) = node.as_station() {
-                    set_station_name.set(station.name.clone());
-                    set_is_passing_loop.set(station.passing_loop);
-                    set_platforms.set(station.platforms.clone());
-                    set_connected_tracks.set(load_connected_tracks(idx, &current_graph));
-                }
-            }
-        }
+        let Some(idx) = editing_station.get() else { return };
+        let current_graph = graph.get_untracked();
+        let Some(node) = current_graph.graph.node_weight(idx) else { return };
+        let Some(station) = node.as_station() else { return };
+        set_station_name.set(station.name.clone());
+        set_is_passing_loop.set(station.passing_loop);
+        set_is_depot.set(station.is_depot);
+        timing.load(station);
+        set_label_angle.set(station.label_angle.to_string());
+        set_label_offset_x.set(station.label_offset.0.to_string());
+        set_label_offset_y.set(station.label_offset.1.to_string());
+        position.load(node.position());
+        set_platforms.set(station.platforms.clone());
+        set_connected_tracks.set(load_connected_tracks(idx, &current_graph));
+    });
+
+    // Another station already has the typed name; keeping an existing duplicate name is allowed
+    let name_taken = Signal::derive(move || {
+        let Some(idx) = editing_station.get() else { return false };
+        let name = station_name.get();
+        graph.with(|g| g.get_station_name(idx) != Some(name.as_str()) && g.station_named_other_than(idx, &name).is_some())
     });
 
+    let position_error = Signal::derive(move || position.parsed.get().err());
     let on_close_clone = on_close.clone();
     let handle_save = move |_| {
         if let Some(idx) = editing_station.get() {
             let name = station_name.get();
             let current_platforms = platforms.get();
-            if !name.is_empty() && !current_platforms.is_empty() {
-                on_save(idx, name, is_passing_loop.get(), current_platforms);
+            let Ok(position) = position.entered() else { return };
+            let (min_dwell_secs, conflict_margin_secs) = timing.entered();
+            if !name.is_empty() && !current_platforms.is_empty() && !name_taken.get_untracked() {
+                on_save(idx, StationEdit {
+                    name,
+                    passing_loop: is_passing_loop.get(),
+                    is_depot: is_depot.get(),
+                    min_dwell_secs,
+                    conflict_margin_secs,
+                    platforms: current_platforms,
+                    label_angle: parse_or_zero(&label_angle.get()),
+                    label_offset: (parse_or_zero(&label_offset_x.get()), parse_or_zero(&label_offset_y.get())),
+                    position,
+                });
             }
         }
     };
@@ -209,6 +446,9 @@ pub fn EditStation(
                         prop:value=move || station_name.get()
                         on:input=move |ev| set_station_name.set(event_target_value(&ev))
                     />
+                    <Show when=move || name_taken.get()>
+                        <p class="name-taken-warning">"Another station already has this name"</p>
+                    </Show>
                 </div>
                 <div class="form-field">
                     <label>
@@ -220,6 +460,29 @@ pub fn EditStation(
                         " Passing Loop"
                     </label>
                 </div>
+                <div class="form-field">
+                    <label>
+                        <input
+                            type="checkbox"
+                            checked=move || is_depot.get()
+                            on:change=move |ev| set_is_depot.set(event_target_checked(&ev))
+                        />
+                        " Depot"
+                    </label>
+                    <p class="help-text">"Depots are shown on the map but left off the time graph"</p>
+                </div>
+                <StationTimingFields timing=timing/>
+                <LabelPlacementFields
+                    angle=label_angle
+                    set_angle=set_label_angle
+                    offset_x=label_offset_x
+                    set_offset_x=set_label_offset_x
+                    offset_y=label_offset_y
+                    set_offset_y=set_label_offset_y
+                />
+                <Show when=move || !auto_layout_enabled.get()>
+                    <PositionFields position=position error=position_error/>
+                </Show>
                 <PlatformEditor
                     platforms=platforms
                     set_platforms=set_platforms
@@ -256,11 +519,26 @@ pub fn EditStation(
                     />
                 </div>
 
+                {train_journeys.map(|train_journeys| view! {
+                    <div class="form-section">
+                        <h3>"Platform Occupancy"</h3>
+                        <PlatformTimeline
+                            station=Signal::derive(move || editing_station.get())
+                            graph=graph
+                            train_journeys=train_journeys
+                        />
+                    </div>
+                })}
+
                 <div class="form-buttons">
                     <button class="danger" on:click=handle_delete>"Delete"</button>
                     <div class="flex-spacer"></div>
                     <button on:click=move |_| on_close()>"Cancel"</button>
-                    <button class="primary" on:click=handle_save>"Save"</button>
+                    <button
+                        class="primary"
+                        on:click=handle_save
+                        disabled=move || name_taken.get() || (!auto_layout_enabled.get() && position_error.get().is_some())
+                    >"Save"</button>
                 </div>
             </div>
         </Window>
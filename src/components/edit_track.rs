@@ -1,14 +1,155 @@
 use crate::components::window::Window;
 use crate::components::track_editor::TrackEditor;
-use crate::models::{RailwayGraph, Track, TrackDirection, Line};
+use crate::models::{has_suspicious_directions, parse_gradient, Electrification, RailwayGraph, Track, TrackDirection, TrackHandedness, Line};
 use crate::import::shared::create_tracks_with_count;
-use leptos::{component, create_effect, create_signal, event_target_value, IntoView, ReadSignal, Signal, SignalGet, SignalSet, SignalUpdate, view};
+use leptos::{component, create_effect, create_signal, event_target_value, IntoView, ReadSignal, Show, Signal, SignalGet, SignalSet, SignalUpdate, SignalWith, WriteSignal, view};
 use petgraph::stable_graph::EdgeIndex;
 use std::rc::Rc;
 
-type SaveTrackCallback = Rc<dyn Fn(EdgeIndex, Vec<Track>, Option<f64>)>;
+/// Values entered in the edit track dialog
+#[derive(Debug, Clone)]
+pub struct TrackEdit {
+    pub tracks: Vec<Track>,
+    pub distance: Option<f64>,
+    pub electrification: Electrification,
+    pub gradient_permille: Option<f64>,
+    pub speed_limit_kph: Option<f64>,
+    pub handedness: Option<TrackHandedness>,
+    pub notes: String,
+}
+
+type SaveTrackCallback = Rc<dyn Fn(EdgeIndex, TrackEdit)>;
+
+#[component]
+fn TrackDirectionWarning(tracks: ReadSignal<Vec<Track>>) -> impl IntoView {
+    view! {
+        <Show when=move || tracks.with(|t| has_suspicious_directions(t))>
+            <div class="track-warning">
+                <i class="fa-solid fa-triangle-exclamation"></i>
+                <div class="warning-content">
+                    {move || if tracks.with(Vec::is_empty) {
+                        view! {
+                            <strong>"No tracks:"</strong>
+                            " Trains cannot run over this section until a track is added."
+                        }
+                    } else {
+                        view! {
+                            <strong>"Check track directions:"</strong>
+                            " Every track runs the same way, so trains have no return path over this section."
+                        }
+                    }}
+                </div>
+            </div>
+        </Show>
+    }
+}
+
+#[component]
+fn TextField(
+    label: &'static str,
+    placeholder: &'static str,
+    value: ReadSignal<String>,
+    set_value: WriteSignal<String>,
+    #[prop(optional)] error: Option<Signal<Option<String>>>,
+) -> impl IntoView {
+    view! {
+        <div class="form-field">
+            <label>{label}</label>
+            <input
+                type="text"
+                placeholder=placeholder
+                prop:value=move || value.get()
+                on:input=move |ev| set_value.set(event_target_value(&ev))
+            />
+            {move || error.and_then(|error| error.get()).map(|message| view! { <p class="field-error">{message}</p> })}
+        </div>
+    }
+}
+
+#[component]
+fn ElectrificationSelect(
+    value: ReadSignal<Electrification>,
+    set_value: WriteSignal<Electrification>,
+) -> impl IntoView {
+    view! {
+        <select
+            on:change=move |ev| {
+                let selected = Electrification::ALL
+                    .into_iter()
+                    .find(|e| format!("{e:?}") == event_target_value(&ev))
+                    .unwrap_or_default();
+                set_value.set(selected);
+            }
+        >
+            {Electrification::ALL.into_iter().map(|option| {
+                view! {
+                    <option
+                        value=format!("{option:?}")
+                        selected=move || value.get() == option
+                    >
+                        {option.as_str()}
+                    </option>
+                }
+            }).collect::<Vec<_>>()}
+        </select>
+    }
+}
 
 #[component]
+fn HandednessSelect(
+    value: ReadSignal<Option<TrackHandedness>>,
+    set_value: WriteSignal<Option<TrackHandedness>>,
+) -> impl IntoView {
+    let options = [
+        (None, "Project default"),
+        (Some(TrackHandedness::RightHand), "Right-hand running"),
+        (Some(TrackHandedness::LeftHand), "Left-hand running"),
+    ];
+
+    view! {
+        <select
+            on:change=move |ev| {
+                let selected = options
+                    .into_iter()
+                    .find(|(option, _)| format!("{option:?}") == event_target_value(&ev))
+                    .and_then(|(option, _)| option);
+                set_value.set(selected);
+            }
+        >
+            {options.into_iter().map(|(option, label)| {
+                view! {
+                    <option
+                        value=format!("{option:?}")
+                        selected=move || value.get() == option
+                    >
+                        {label}
+                    </option>
+                }
+            }).collect::<Vec<_>>()}
+        </select>
+    }
+}
+
+#[component]
+fn NotesField(
+    value: ReadSignal<String>,
+    set_value: WriteSignal<String>,
+) -> impl IntoView {
+    view! {
+        <div class="form-field">
+            <label>"Notes"</label>
+            <textarea
+                class="track-notes"
+                placeholder="e.g., Temporary speed restriction until June"
+                prop:value=move || value.get()
+                on:input=move |ev| set_value.set(event_target_value(&ev))
+            />
+        </div>
+    }
+}
+
+#[component]
+#[allow(clippy::too_many_lines)]
 pub fn EditTrack(
     editing_track: ReadSignal<Option<EdgeIndex>>,
     on_close: Rc<dyn Fn()>,
@@ -20,6 +161,11 @@ pub fn EditTrack(
 ) -> impl IntoView {
     let (tracks, set_tracks) = create_signal(Vec::<Track>::new());
     let (distance, set_distance) = create_signal(String::new());
+    let (electrification, set_electrification) = create_signal(Electrification::None);
+    let (gradient, set_gradient) = create_signal(String::new());
+    let (speed_limit, set_speed_limit) = create_signal(String::new());
+    let (handedness, set_handedness) = create_signal(None::<TrackHandedness>);
+    let (notes, set_notes) = create_signal(String::new());
     let (from_station_name, set_from_station_name) = create_signal(String::new());
     let (to_station_name, set_to_station_name) = create_signal(String::new());
     let (affected_lines, set_affected_lines) = create_signal(Vec::<String>::new());
@@ -35,6 +181,11 @@ pub fn EditTrack(
 
                 // Load distance if available
                 set_distance.set(track_segment.distance.map(|d| d.to_string()).unwrap_or_default());
+                set_electrification.set(track_segment.electrification);
+                set_gradient.set(track_segment.gradient_permille.map(|g| g.to_string()).unwrap_or_default());
+                set_speed_limit.set(track_segment.speed_limit_kph.map(|s| s.to_string()).unwrap_or_default());
+                set_handedness.set(track_segment.handedness);
+                set_notes.set(track_segment.notes.clone());
             }
 
             // Get station/junction names
@@ -59,16 +210,24 @@ pub fn EditTrack(
             // Reset signals when dialog closes to prevent stale values
             set_tracks.set(Vec::new());
             set_distance.set(String::new());
+            set_electrification.set(Electrification::None);
+            set_gradient.set(String::new());
+            set_speed_limit.set(String::new());
+            set_handedness.set(None);
+            set_notes.set(String::new());
             set_from_station_name.set(String::new());
             set_to_station_name.set(String::new());
             set_affected_lines.set(Vec::new());
         }
     });
 
+    let gradient_error = Signal::derive(move || parse_gradient(&gradient.get()).err());
+
     let on_close_clone = on_close.clone();
     let handle_save = move |_| {
         if let Some(edge_idx) = editing_track.get() {
             let current_tracks = tracks.get();
+            let Ok(gradient_permille) = parse_gradient(&gradient.get()) else { return };
             if !current_tracks.is_empty() {
                 // Parse distance, treating empty string as None
                 let parsed_distance = distance.get()
@@ -77,7 +236,15 @@ pub fn EditTrack(
                     .ok()
                     .filter(|d| *d > 0.0); // Only accept positive distances
 
-                on_save(edge_idx, current_tracks, parsed_distance);
+                on_save(edge_idx, TrackEdit {
+                    tracks: current_tracks,
+                    distance: parsed_distance,
+                    electrification: electrification.get(),
+                    gradient_permille,
+                    speed_limit_kph: speed_limit.get().trim().parse::<f64>().ok().filter(|s| *s > 0.0),
+                    handedness: handedness.get(),
+                    notes: notes.get().trim().to_string(),
+                });
             }
         }
     };
@@ -91,7 +258,7 @@ pub fn EditTrack(
     let handle_add_track = move || {
         set_tracks.update(|t| {
             let new_count = t.len() + 1;
-            let handedness = settings.get().track_handedness;
+            let handedness = handedness.get().unwrap_or(settings.get().track_handedness);
             *t = create_tracks_with_count(new_count, handedness);
         });
     };
@@ -100,7 +267,7 @@ pub fn EditTrack(
         set_tracks.update(|t| {
             if t.len() > 1 {
                 let new_count = t.len() - 1;
-                let handedness = settings.get().track_handedness;
+                let handedness = handedness.get().unwrap_or(settings.get().track_handedness);
                 *t = create_tracks_with_count(new_count, handedness);
             }
         });
@@ -151,16 +318,26 @@ pub fn EditTrack(
                     }
                 }}
 
+                <TextField label="Distance (km, optional)" placeholder="e.g., 5.2" value=distance set_value=set_distance/>
+
+                <TextField label="Maximum speed (km/h, optional)" placeholder="e.g., 120" value=speed_limit set_value=set_speed_limit/>
+
+                <TextField label="Gradient (‰, optional, negative is downhill)" placeholder="e.g., -12.5" value=gradient set_value=set_gradient error=gradient_error/>
+
                 <div class="form-field">
-                    <label>"Distance (km, optional)"</label>
-                    <input
-                        type="text"
-                        placeholder="e.g., 5.2"
-                        prop:value=move || distance.get()
-                        on:input=move |ev| set_distance.set(event_target_value(&ev))
-                    />
+                    <label>"Electrification"</label>
+                    <ElectrificationSelect value=electrification set_value=set_electrification/>
+                </div>
+
+                <div class="form-field">
+                    <label>"Running side"</label>
+                    <HandednessSelect value=handedness set_value=set_handedness/>
                 </div>
 
+                <NotesField value=notes set_value=set_notes/>
+
+                <TrackDirectionWarning tracks=tracks/>
+
                 <div class="form-field">
                     <label>"Tracks"</label>
                     <TrackEditor
@@ -177,7 +354,7 @@ pub fn EditTrack(
                     <button class="danger" on:click=handle_delete>"Delete Track"</button>
                     <div class="flex-spacer"></div>
                     <button on:click=move |_| on_close()>"Cancel"</button>
-                    <button class="primary" on:click=handle_save>"Save"</button>
+                    <button class="primary" on:click=handle_save disabled=move || gradient_error.get().is_some()>"Save"</button>
                 </div>
             </div>
         </Window>
@@ -1,19 +1,214 @@
-use leptos::{component, create_node_ref, create_signal, IntoView, ReadSignal, Signal, SignalGet, SignalSet, SignalUpdate, view, SignalWith};
+use leptos::{component, create_memo, create_node_ref, create_signal, use_context, Callable, Callback, IntoView, Memo, ReadSignal, Signal, SignalGet, SignalSet, SignalUpdate, view, SignalWith, WriteSignal};
 use leptos::leptos_dom::helpers::window_event_listener;
 use leptos_use::{use_infinite_scroll_with_options, UseInfiniteScrollOptions};
 use wasm_bindgen::JsCast;
-use crate::conflict::Conflict;
-use crate::time::time_to_fraction;
-use crate::models::{RailwayGraph, Node, Stations};
+use crate::conflict::{conflict_station_nodes, filter_conflicts, without_acknowledged, Conflict, ConflictKey, ConflictType};
+use crate::models::{Line, RailwayGraph, Node, Stations};
+use crate::train_journey::{dwell_violations, find_illegal_overtakes, TrainJourney};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
 
 const CONFLICTS_PER_PAGE: usize = 50;
 
+/// Conflicts the planner has accepted, stored with the project and hidden from the conflict list
+#[derive(Clone, Copy)]
+pub struct AcknowledgedConflicts {
+    pub keys: ReadSignal<HashSet<ConflictKey>>,
+    pub set_keys: WriteSignal<HashSet<ConflictKey>>,
+}
+
+/// A journey segment whose arrival is not after the departure from the previous stop
+#[derive(Clone, PartialEq)]
+struct TimingError {
+    train_number: String,
+    from_station: String,
+    to_station: String,
+    departure: chrono::NaiveDateTime,
+    arrival: chrono::NaiveDateTime,
+}
+
+/// Segments with zero or negative run times across all journeys, ordered by train number and time
+fn collect_timing_errors(journeys: &HashMap<Uuid, TrainJourney>, graph: &RailwayGraph) -> Vec<TimingError> {
+    let station_name = |idx: petgraph::stable_graph::NodeIndex| {
+        graph.graph.node_weight(idx).map_or_else(|| "Unknown".to_string(), |n| n.display_name().clone())
+    };
+
+    let mut errors: Vec<TimingError> = journeys.values()
+        .filter_map(|journey| journey.validate_monotonic_times().err().map(|indices| (journey, indices)))
+        .flat_map(|(journey, indices)| {
+            indices.into_iter().map(move |i| {
+                let (from_idx, _, departure) = journey.station_times[i - 1];
+                let (to_idx, arrival, _) = journey.station_times[i];
+                (journey.train_number.clone(), from_idx, to_idx, departure, arrival)
+            })
+        })
+        .map(|(train_number, from_idx, to_idx, departure, arrival)| TimingError {
+            train_number,
+            from_station: station_name(from_idx),
+            to_station: station_name(to_idx),
+            departure,
+            arrival,
+        })
+        .collect();
+    errors.sort_by(|a, b| a.train_number.cmp(&b.train_number).then(a.departure.cmp(&b.departure)));
+    errors
+}
+
+/// A stop shorter than its station's minimum dwell
+#[derive(Clone, PartialEq)]
+struct DwellError {
+    train_number: String,
+    station: String,
+    arrival: chrono::NaiveDateTime,
+    dwell_secs: i64,
+    min_dwell_secs: i64,
+}
+
+/// Stops below their station's minimum dwell across all journeys, ordered by train number and time
+fn collect_dwell_errors(journeys: &HashMap<Uuid, TrainJourney>, graph: &RailwayGraph) -> Vec<DwellError> {
+    let mut errors: Vec<DwellError> = dwell_violations(journeys.values(), graph).into_iter()
+        .filter_map(|(journey_id, stop)| {
+            let journey = journeys.get(&journey_id)?;
+            let (node, arrival, departure) = journey.station_times.get(stop)?;
+            let station = graph.graph.node_weight(*node)?.as_station()?;
+            Some(DwellError {
+                train_number: journey.train_number.clone(),
+                station: station.name.clone(),
+                arrival: *arrival,
+                dwell_secs: (*departure - *arrival).num_seconds(),
+                min_dwell_secs: station.min_dwell_secs?,
+            })
+        })
+        .collect();
+    errors.sort_by(|a, b| a.train_number.cmp(&b.train_number).then(a.arrival.cmp(&b.arrival)));
+    errors
+}
+
+#[component]
+fn DwellErrorList(dwell_errors: Signal<Vec<DwellError>>) -> impl IntoView {
+    view! {
+        <div class="error-items timing-errors">
+            {move || dwell_errors.get().into_iter().map(|error| view! {
+                <div class="error-item timing-error">
+                    <div class="error-item-header">
+                        <i class="fa-solid fa-hourglass-half"></i>
+                        <span class="error-type">"Short Dwell"</span>
+                    </div>
+                    <div class="error-item-details">
+                        <div class="error-detail">
+                            <span class="value">
+                                {format!("{} stops {} s at {}, which needs {} s", error.train_number, error.dwell_secs, error.station, error.min_dwell_secs)}
+                            </span>
+                        </div>
+                        <div class="error-detail">
+                            <span class="value">{error.arrival.format("%H:%M:%S").to_string()}</span>
+                        </div>
+                    </div>
+                </div>
+            }).collect::<Vec<_>>()}
+        </div>
+    }
+}
+
+/// A train scheduled to pass another where there is no loop or second track
+#[derive(Clone, PartialEq)]
+struct OvertakeError {
+    overtaking: String,
+    overtaken: String,
+    location: String,
+}
+
+/// Overtakes where trains cannot pass, ordered by train number
+fn collect_overtake_errors(journeys: &HashMap<Uuid, TrainJourney>, graph: &RailwayGraph) -> Vec<OvertakeError> {
+    let train_number = |id: Uuid| journeys.get(&id).map_or_else(String::new, |journey| journey.train_number.clone());
+    let mut errors: Vec<OvertakeError> = find_illegal_overtakes(journeys.values(), graph).into_iter()
+        .map(|(overtaking, overtaken, node)| OvertakeError {
+            overtaking: train_number(overtaking),
+            overtaken: train_number(overtaken),
+            location: graph.graph.node_weight(node).map_or_else(|| "Unknown".to_string(), Node::display_name),
+        })
+        .collect();
+    errors.sort_by(|a, b| a.overtaking.cmp(&b.overtaking).then(a.overtaken.cmp(&b.overtaken)));
+    errors
+}
+
+#[component]
+fn OvertakeErrorList(overtake_errors: Memo<Vec<OvertakeError>>) -> impl IntoView {
+    view! {
+        <div class="error-items timing-errors">
+            {move || overtake_errors.get().into_iter().map(|error| view! {
+                <div class="error-item timing-error">
+                    <div class="error-item-header">
+                        <i class="fa-solid fa-forward"></i>
+                        <span class="error-type">"Impossible Overtake"</span>
+                    </div>
+                    <div class="error-item-details">
+                        <div class="error-detail">
+                            <span class="value">
+                                {format!("{} passes {} at {}, where there is no loop or second track", error.overtaking, error.overtaken, error.location)}
+                            </span>
+                        </div>
+                    </div>
+                </div>
+            }).collect::<Vec<_>>()}
+        </div>
+    }
+}
+
+#[component]
+fn TimingErrorList(timing_errors: Memo<Vec<TimingError>>) -> impl IntoView {
+    let scroll_container_ref = create_node_ref::<leptos::html::Div>();
+    let (displayed_count, set_displayed_count) = create_signal(CONFLICTS_PER_PAGE);
+
+    let _ = use_infinite_scroll_with_options(
+        scroll_container_ref,
+        move |_| async move {
+            set_displayed_count.update(|count| {
+                let total = timing_errors.with(Vec::len);
+                *count = (*count + CONFLICTS_PER_PAGE).min(total);
+            });
+        },
+        UseInfiniteScrollOptions::default()
+            .distance(10.0)
+    );
+
+    view! {
+        <div class="error-list-content timing-errors" node_ref=scroll_container_ref>
+            <div class="error-items">
+                {move || timing_errors.get().into_iter().take(displayed_count.get()).map(|error| view! {
+                    <div class="error-item timing-error">
+                        <div class="error-item-header">
+                            <i class="fa-solid fa-clock-rotate-left"></i>
+                            <span class="error-type">"Invalid Run Time"</span>
+                        </div>
+                        <div class="error-item-details">
+                            <div class="error-detail">
+                                <span class="value">
+                                    {format!("{} reaches {} no later than it leaves {}", error.train_number, error.to_station, error.from_station)}
+                                </span>
+                            </div>
+                            <div class="error-detail">
+                                <span class="value">
+                                    {format!("{} → {}", error.departure.format("%H:%M:%S"), error.arrival.format("%H:%M:%S"))}
+                                </span>
+                            </div>
+                        </div>
+                    </div>
+                }).collect::<Vec<_>>()}
+            </div>
+        </div>
+    }
+}
+
 #[component]
 fn ErrorListPopover(
     conflicts: Signal<Vec<Conflict>>,
-    on_conflict_click: impl Fn(f64, f64) + 'static + Copy,
+    on_conflict_click: impl Fn(f64, f64, &Conflict) + 'static + Copy,
     nodes: Signal<Vec<(petgraph::stable_graph::NodeIndex, Node)>>,
     station_idx_map: leptos::Memo<std::collections::HashMap<usize, usize>>,
+    graph: ReadSignal<RailwayGraph>,
+    acknowledged: Signal<HashSet<ConflictKey>>,
+    on_toggle_acknowledged: Callback<ConflictKey>,
 ) -> impl IntoView {
     let scroll_container_ref = create_node_ref::<leptos::html::Div>();
     let (displayed_count, set_displayed_count) = create_signal(CONFLICTS_PER_PAGE);
@@ -33,110 +228,214 @@ fn ErrorListPopover(
     );
 
     view! {
-        <div class="error-list-popover">
-            <div class="error-list-content" node_ref=scroll_container_ref>
-                {move || {
-                    let current_conflicts = conflicts.get();
-                    let total_count = current_conflicts.len();
-
-                    if total_count == 0 {
-                        view! {
-                            <p class="no-errors">"No conflicts detected"</p>
-                        }.into_view()
-                    } else {
-                        let display_count = displayed_count.get();
-                        let visible_conflicts = current_conflicts.into_iter().take(display_count);
-
-                        view! {
-                            <div class="error-items">
-                                {
-                                    let current_nodes = nodes.get();
-                                    let idx_map = station_idx_map.get();
-                                    visible_conflicts.filter_map(|conflict| {
-                                        let conflict_type_text = conflict.type_name();
-
-                                        // Map conflict indices to display indices
-                                        let Some(&display_idx1) = idx_map.get(&conflict.station1_idx) else {
-                                            #[cfg(debug_assertions)]
-                                            leptos::logging::warn!(
-                                                "Conflict station1_idx {} not found in index map for conflict: {} at {}",
-                                                conflict.station1_idx, conflict.journey1_id, conflict.time
-                                            );
-                                            return None;
-                                        };
-                                        let Some(&display_idx2) = idx_map.get(&conflict.station2_idx) else {
-                                            #[cfg(debug_assertions)]
-                                            leptos::logging::warn!(
-                                                "Conflict station2_idx {} not found in index map for conflict: {} at {}",
-                                                conflict.station2_idx, conflict.journey1_id, conflict.time
-                                            );
-                                            return None;
-                                        };
-
-                                        // Get node names
-                                        let station1_name = current_nodes.get(display_idx1)
-                                            .map_or_else(|| "Unknown".to_string(), |(_, n)| n.display_name().clone());
-                                        let station2_name = current_nodes.get(display_idx2)
-                                            .map_or_else(|| "Unknown".to_string(), |(_, n)| n.display_name().clone());
-
-                                        let conflict_message = if conflict.conflict_type == crate::conflict::ConflictType::PlatformViolation {
-                                            // Look up platform name directly from nodes to avoid expensive graph traversal
-                                            let platform_name = conflict.platform_idx.and_then(|idx| {
-                                                current_nodes.get(display_idx1)
-                                                    .and_then(|(_, n)| n.as_station())
-                                                    .and_then(|s| s.platforms.get(idx))
-                                                    .map(|p| p.name.as_str())
-                                            }).unwrap_or("?");
-                                            conflict.format_platform_message(&station1_name, platform_name)
-                                        } else {
-                                            conflict.format_message(&station1_name, &station2_name)
-                                        };
-
-                                        let time_fraction = time_to_fraction(conflict.time);
-                                        // Calculate display position using mapped indices
-                                        // Handle bidirectional travel by using min/max to ensure valid range
-                                        let (min_idx, max_idx) = (display_idx1.min(display_idx2), display_idx1.max(display_idx2));
-                                        #[allow(clippy::cast_precision_loss)]
-                                        let station_position = min_idx as f64 + (conflict.position * (max_idx as f64 - min_idx as f64));
-
-                                        Some(view! {
-                                            <div
-                                                class="error-item clickable"
-                                                on:click=move |_| {
-                                                    on_conflict_click(time_fraction, station_position);
-                                                }
-                                            >
-                                                <div class="error-item-header">
-                                                    <i class="fa-solid fa-triangle-exclamation"></i>
-                                                    <span class="error-type">{conflict_type_text}</span>
+        <div class="error-list-content" node_ref=scroll_container_ref>
+            {move || {
+                let current_conflicts = conflicts.get();
+                let total_count = current_conflicts.len();
+
+                if total_count == 0 {
+                    view! {
+                        <p class="no-errors">"No conflicts detected"</p>
+                    }.into_view()
+                } else {
+                    let display_count = displayed_count.get();
+                    let visible_conflicts = current_conflicts.into_iter().take(display_count);
+
+                    view! {
+                        <div class="error-items">
+                            {
+                                let current_nodes = nodes.get();
+                                let idx_map = station_idx_map.get();
+                                let current_graph = graph.get();
+                                let acknowledged_keys = acknowledged.get();
+                                let station_nodes = conflict_station_nodes(&current_graph);
+                                visible_conflicts.filter_map(|conflict| {
+                                    let conflict_type_text = conflict.type_name();
+
+                                    // Map conflict indices to display indices
+                                    let Some(&display_idx1) = idx_map.get(&conflict.station1_idx) else {
+                                        #[cfg(debug_assertions)]
+                                        leptos::logging::warn!(
+                                            "Conflict station1_idx {} not found in index map for conflict: {} at {}",
+                                            conflict.station1_idx, conflict.journey1_id, conflict.time
+                                        );
+                                        return None;
+                                    };
+                                    let Some(&display_idx2) = idx_map.get(&conflict.station2_idx) else {
+                                        #[cfg(debug_assertions)]
+                                        leptos::logging::warn!(
+                                            "Conflict station2_idx {} not found in index map for conflict: {} at {}",
+                                            conflict.station2_idx, conflict.journey1_id, conflict.time
+                                        );
+                                        return None;
+                                    };
+
+                                    // Get node names
+                                    let station1_name = current_nodes.get(display_idx1)
+                                        .map_or_else(|| "Unknown".to_string(), |(_, n)| n.display_name().clone());
+                                    let station2_name = current_nodes.get(display_idx2)
+                                        .map_or_else(|| "Unknown".to_string(), |(_, n)| n.display_name().clone());
+
+                                    let conflict_message = if conflict.conflict_type == crate::conflict::ConflictType::PlatformViolation {
+                                        // Look up platform name directly from nodes to avoid expensive graph traversal
+                                        let platform_name = conflict.platform_idx.and_then(|idx| {
+                                            current_nodes.get(display_idx1)
+                                                .and_then(|(_, n)| n.as_station())
+                                                .and_then(|s| s.platforms.get(idx))
+                                                .map(|p| p.name.as_str())
+                                        }).unwrap_or("?");
+                                        conflict.format_platform_message(&station1_name, platform_name)
+                                    } else {
+                                        conflict.format_message(&station1_name, &station2_name, conflict.distance_along(&current_graph))
+                                    };
+
+                                    let (time_fraction, station_position) = conflict.graph_position(&idx_map)?;
+
+                                    let clicked_conflict = conflict.clone();
+                                    let is_near_miss = conflict.conflict_type == crate::conflict::ConflictType::NearMiss;
+                                    let key = conflict.key(&station_nodes);
+                                    let is_acknowledged = acknowledged_keys.contains(&key);
+
+                                    Some(view! {
+                                        <div
+                                            class="error-item clickable"
+                                            class:near-miss=is_near_miss
+                                            class:acknowledged=is_acknowledged
+                                            on:click=move |_| {
+                                                on_conflict_click(time_fraction, station_position, &clicked_conflict);
+                                            }
+                                        >
+                                            <div class="error-item-header">
+                                                <i class="fa-solid fa-triangle-exclamation"></i>
+                                                <span class="error-type">{conflict_type_text}</span>
+                                                <button
+                                                    class="acknowledge-button"
+                                                    title=if is_acknowledged { "Show this conflict again" } else { "Acknowledge and hide this conflict" }
+                                                    on:click=move |ev| {
+                                                        ev.stop_propagation();
+                                                        on_toggle_acknowledged.call(key.clone());
+                                                    }
+                                                >
+                                                    <i class=if is_acknowledged { "fa-solid fa-eye" } else { "fa-solid fa-eye-slash" }></i>
+                                                </button>
+                                            </div>
+                                            <div class="error-item-details">
+                                                <div class="error-detail">
+                                                    <span class="value">{conflict_message}</span>
                                                 </div>
-                                                <div class="error-item-details">
-                                                    <div class="error-detail">
-                                                        <span class="value">{conflict_message}</span>
-                                                    </div>
-                                                    <div class="error-detail">
-                                                        <span class="value">{conflict.time.format("%H:%M:%S").to_string()}</span>
-                                                    </div>
+                                                <div class="error-detail">
+                                                    <span class="value">{conflict.time.format("%H:%M:%S").to_string()}</span>
                                                 </div>
                                             </div>
-                                        })
-                                    }).collect::<Vec<_>>()
-                                }
-                            </div>
-                            {
-                                if display_count < total_count {
-                                    view! {
-                                        <div class="scroll-status">
-                                            "Showing " {display_count} " of " {total_count} " conflicts (scroll for more)"
                                         </div>
-                                    }.into_view()
-                                } else {
-                                    view! {}.into_view()
-                                }
+                                    })
+                                }).collect::<Vec<_>>()
+                            }
+                        </div>
+                        {
+                            if display_count < total_count {
+                                view! {
+                                    <div class="scroll-status">
+                                        "Showing " {display_count} " of " {total_count} " conflicts (scroll for more)"
+                                    </div>
+                                }.into_view()
+                            } else {
+                                view! {}.into_view()
                             }
-                        }.into_view()
-                    }
+                        }
+                    }.into_view()
+                }
+            }}
+        </div>
+    }
+}
+
+/// Toggle a value in a filter set
+fn toggle_in<T: Eq + std::hash::Hash>(set: &mut HashSet<T>, value: T) {
+    if !set.remove(&value) {
+        set.insert(value);
+    }
+}
+
+#[component]
+fn AcknowledgedSummary(
+    hidden_count: Signal<usize>,
+    show_acknowledged: ReadSignal<bool>,
+    set_show_acknowledged: WriteSignal<bool>,
+) -> impl IntoView {
+    move || (hidden_count.get() > 0).then(|| view! {
+        <div class="acknowledged-summary">
+            <span>
+                {move || {
+                    let count = hidden_count.get();
+                    format!("{count} acknowledged conflict{}", if count == 1 { "" } else { "s" })
                 }}
+            </span>
+            <button on:click=move |_| set_show_acknowledged.update(|show| *show = !*show)>
+                {move || if show_acknowledged.get() { "Hide" } else { "Show" }}
+            </button>
+        </div>
+    })
+}
+
+#[component]
+fn ConflictFilters(
+    conflicts: Signal<Vec<Conflict>>,
+    lines: ReadSignal<Vec<Line>>,
+    train_journeys: ReadSignal<HashMap<Uuid, TrainJourney>>,
+    type_filter: ReadSignal<HashSet<ConflictType>>,
+    set_type_filter: WriteSignal<HashSet<ConflictType>>,
+    line_filter: ReadSignal<HashSet<Uuid>>,
+    set_line_filter: WriteSignal<HashSet<Uuid>>,
+) -> impl IntoView {
+    // Only offer types and lines that actually appear in the current conflicts
+    let present_types = move || conflicts.with(|all| {
+        ConflictType::ALL.into_iter()
+            .filter(|t| all.iter().any(|c| c.conflict_type == *t))
+            .collect::<Vec<_>>()
+    });
+    let involved_lines = move || {
+        let train_numbers: HashSet<String> = conflicts.with(|all| {
+            all.iter().flat_map(|c| [c.journey1_id.clone(), c.journey2_id.clone()]).collect()
+        });
+        let line_ids: HashSet<Uuid> = train_journeys.with(|journeys| {
+            journeys.values()
+                .filter(|j| train_numbers.contains(&j.train_number))
+                .map(|j| j.line_id)
+                .collect()
+        });
+        lines.with(|all| {
+            all.iter()
+                .filter(|line| line_ids.contains(&line.id))
+                .map(|line| (line.id, line.name.clone(), line.color.clone()))
+                .collect::<Vec<_>>()
+        })
+    };
+
+    view! {
+        <div class="conflict-filters">
+            <div class="conflict-filter-group">
+                {move || present_types().into_iter().map(|conflict_type| view! {
+                    <button
+                        class="conflict-filter-chip"
+                        class:active=move || type_filter.with(|f| f.contains(&conflict_type))
+                        on:click=move |_| set_type_filter.update(|f| toggle_in(f, conflict_type))
+                    >
+                        {conflict_type.name()}
+                    </button>
+                }).collect::<Vec<_>>()}
+            </div>
+            <div class="conflict-filter-group">
+                {move || involved_lines().into_iter().map(|(line_id, name, color)| view! {
+                    <button
+                        class="conflict-filter-chip"
+                        class:active=move || line_filter.with(|f| f.contains(&line_id))
+                        style=format!("border-left-color: {color};")
+                        on:click=move |_| set_line_filter.update(|f| toggle_in(f, line_id))
+                    >
+                        {name}
+                    </button>
+                }).collect::<Vec<_>>()}
             </div>
         </div>
     }
@@ -145,18 +444,70 @@ fn ErrorListPopover(
 #[component]
 pub fn ErrorList(
     conflicts: Signal<Vec<Conflict>>,
-    on_conflict_click: impl Fn(f64, f64) + 'static + Copy,
+    on_conflict_click: impl Fn(f64, f64, &Conflict) + 'static + Copy,
     graph: ReadSignal<RailwayGraph>,
     station_idx_map: leptos::Memo<std::collections::HashMap<usize, usize>>,
+    lines: ReadSignal<Vec<Line>>,
+    train_journeys: ReadSignal<HashMap<Uuid, TrainJourney>>,
 ) -> impl IntoView {
     let (is_open, set_is_open) = create_signal(false);
+    let (type_filter, set_type_filter) = create_signal(HashSet::<ConflictType>::new());
+    let (line_filter, set_line_filter) = create_signal(HashSet::<Uuid>::new());
+    let (show_acknowledged, set_show_acknowledged) = create_signal(false);
+
+    let acknowledged_conflicts = use_context::<AcknowledgedConflicts>();
+    let acknowledged = Signal::derive(move || acknowledged_conflicts.map(|a| a.keys.get()).unwrap_or_default());
+    let on_toggle_acknowledged = Callback::new(move |key: ConflictKey| {
+        if let Some(a) = acknowledged_conflicts {
+            a.set_keys.update(|keys| toggle_in(keys, key));
+        }
+    });
+    let unacknowledged = Signal::derive(move || {
+        acknowledged.with(|keys| conflicts.with(|all| graph.with(|graph| without_acknowledged(all, keys, graph))))
+    });
+    let hidden_count = Signal::derive(move || unacknowledged.with(|(_, hidden)| *hidden));
+    // Acknowledged conflicts stay listed while shown, so they can be un-acknowledged
+    let listed_conflicts = Signal::derive(move || {
+        if show_acknowledged.get() {
+            conflicts.get()
+        } else {
+            unacknowledged.with(|(shown, _)| shown.clone())
+        }
+    });
+
+    let filtered_conflicts = Signal::derive(move || {
+        let types = type_filter.get();
+        let line_ids = line_filter.get();
+        // Journeys are only needed to resolve lines, so skip collecting them when no line is selected
+        let journeys: Vec<TrainJourney> = if line_ids.is_empty() {
+            Vec::new()
+        } else {
+            train_journeys.with(|j| j.values().cloned().collect())
+        };
+        listed_conflicts.with(|all| filter_conflicts(all, &types, &line_ids, &journeys))
+    });
 
     let toggle_popover = move |_| {
         set_is_open.update(|open| *open = !*open);
     };
 
-    let conflict_count = move || conflicts.get().len();
-    let has_errors = move || conflict_count() > 0;
+    let timing_errors = create_memo(move |_| {
+        graph.with(|graph| train_journeys.with(|journeys| collect_timing_errors(journeys, graph)))
+    });
+
+    let dwell_errors = Signal::derive(move || {
+        graph.with(|graph| train_journeys.with(|journeys| collect_dwell_errors(journeys, graph)))
+    });
+
+    let conflict_count = move || unacknowledged.with(|(shown, _)| shown.len());
+    let overtake_errors = create_memo(move |_| {
+        graph.with(|graph| train_journeys.with(|journeys| collect_overtake_errors(journeys, graph)))
+    });
+
+    let issue_count = move || timing_errors.with(Vec::len) + dwell_errors.with(Vec::len) + overtake_errors.with(Vec::len);
+    let error_count = move || conflict_count() + issue_count();
+    let has_errors = move || error_count() > 0;
+    let error_label = move || if issue_count() == 0 { " Conflicts" } else { " Issues" };
 
     // Close when clicking outside
     let container_ref = create_node_ref::<leptos::html::Div>();
@@ -188,8 +539,8 @@ pub fn ErrorList(
                             on:click=toggle_popover
                         >
                             <i class="fa-solid fa-triangle-exclamation"></i>
-                            <span class="error-count">{conflict_count()}</span>
-                            <span class="error-label">" Conflicts"</span>
+                            <span class="error-count">{error_count()}</span>
+                            <span class="error-label">{error_label()}</span>
                         </button>
                     }.into_view()
                 } else {
@@ -203,12 +554,40 @@ pub fn ErrorList(
                     let all_nodes = graph.get().get_all_nodes_ordered();
                     let nodes_signal = Signal::derive(move || all_nodes.clone());
                     view! {
-                        <ErrorListPopover
-                            conflicts=conflicts
-                            on_conflict_click=on_conflict_click
-                            nodes=nodes_signal
-                            station_idx_map=station_idx_map
-                        />
+                        <div class="error-list-popover">
+                            {move || timing_errors.with(|errors| !errors.is_empty()).then(|| view! {
+                                <TimingErrorList timing_errors=timing_errors />
+                            })}
+                            {move || dwell_errors.with(|errors| !errors.is_empty()).then(|| view! {
+                                <DwellErrorList dwell_errors=dwell_errors />
+                            })}
+                            {move || overtake_errors.with(|errors| !errors.is_empty()).then(|| view! {
+                                <OvertakeErrorList overtake_errors=overtake_errors />
+                            })}
+                            <AcknowledgedSummary
+                                hidden_count=hidden_count
+                                show_acknowledged=show_acknowledged
+                                set_show_acknowledged=set_show_acknowledged
+                            />
+                            <ConflictFilters
+                                conflicts=listed_conflicts
+                                lines=lines
+                                train_journeys=train_journeys
+                                type_filter=type_filter
+                                set_type_filter=set_type_filter
+                                line_filter=line_filter
+                                set_line_filter=set_line_filter
+                            />
+                            <ErrorListPopover
+                                conflicts=filtered_conflicts
+                                on_conflict_click=on_conflict_click
+                                nodes=nodes_signal
+                                station_idx_map=station_idx_map
+                                graph=graph
+                                acknowledged=acknowledged
+                                on_toggle_acknowledged=on_toggle_acknowledged
+                            />
+                        </div>
                     }.into_view()
                 } else {
                     view! {}.into_view()
@@ -0,0 +1,120 @@
+use crate::models::{GeoAnchor, GeoReference};
+use leptos::{component, view, create_rw_signal, event_target_value, For, IntoView, RwSignal, Signal, SignalGet, SignalGetUntracked, SignalSet, SignalUpdate};
+use petgraph::stable_graph::NodeIndex;
+
+/// One anchor as typed so far; only complete anchors become part of the reference
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct AnchorDraft {
+    station: Option<NodeIndex>,
+    lon: Option<f64>,
+    lat: Option<f64>,
+}
+
+impl AnchorDraft {
+    fn from_anchor(anchor: Option<GeoAnchor>) -> Self {
+        anchor.map_or_else(Self::default, |anchor| Self {
+            station: Some(anchor.station),
+            lon: Some(anchor.lon_lat.0),
+            lat: Some(anchor.lon_lat.1),
+        })
+    }
+
+    fn anchor(self) -> Option<GeoAnchor> {
+        Some(GeoAnchor { station: self.station?, lon_lat: (self.lon?, self.lat?) })
+    }
+}
+
+fn parse_coordinate(text: &str, limit: f64) -> Option<f64> {
+    text.trim().parse::<f64>().ok().filter(|value| value.abs() <= limit)
+}
+
+#[component]
+fn GeoAnchorRow(
+    label: &'static str,
+    stations: Signal<Vec<(NodeIndex, String)>>,
+    draft: RwSignal<AnchorDraft>,
+    on_change: impl Fn() + 'static + Copy,
+) -> impl IntoView {
+    let format_coordinate = move |value: Option<f64>| value.map(|v| v.to_string()).unwrap_or_default();
+
+    view! {
+        <div class="geo-anchor-row">
+            <label>{label}</label>
+            <select
+                on:change=move |ev| {
+                    let station = event_target_value(&ev).parse::<usize>().ok().map(NodeIndex::new);
+                    draft.update(|d| d.station = station);
+                    on_change();
+                }
+            >
+                <option value="" selected=move || draft.get().station.is_none()>"Choose station"</option>
+                <For
+                    each=move || stations.get()
+                    key=|(idx, name)| (*idx, name.clone())
+                    children=move |(idx, name)| view! {
+                        <option value=idx.index().to_string() selected=move || draft.get().station == Some(idx)>{name}</option>
+                    }
+                />
+            </select>
+            <input
+                type="text"
+                placeholder="Longitude"
+                prop:value=move || format_coordinate(draft.get().lon)
+                on:change=move |ev| {
+                    let lon = parse_coordinate(&event_target_value(&ev), 180.0);
+                    draft.update(|d| d.lon = lon);
+                    on_change();
+                }
+            />
+            <input
+                type="text"
+                placeholder="Latitude"
+                prop:value=move || format_coordinate(draft.get().lat)
+                on:change=move |ev| {
+                    let lat = parse_coordinate(&event_target_value(&ev), 90.0);
+                    draft.update(|d| d.lat = lat);
+                    on_change();
+                }
+            />
+        </div>
+    }
+}
+
+/// Pins two stations to real-world coordinates so exports can be projected onto a map
+#[component]
+#[must_use]
+pub fn GeoReferenceEditor(
+    stations: Signal<Vec<(NodeIndex, String)>>,
+    geo_reference: Signal<Option<GeoReference>>,
+    set_geo_reference: impl Fn(Option<GeoReference>) + 'static + Copy,
+) -> impl IntoView {
+    let current = geo_reference.get_untracked();
+    let first = create_rw_signal(AnchorDraft::from_anchor(current.map(|r| r.first)));
+    let second = create_rw_signal(AnchorDraft::from_anchor(current.map(|r| r.second)));
+
+    // Only a complete pair of anchors makes a usable reference
+    let handle_change = move || {
+        let reference = first.get_untracked().anchor().zip(second.get_untracked().anchor())
+            .map(|(first, second)| GeoReference { first, second });
+        if reference.is_some() {
+            set_geo_reference(reference);
+        }
+    };
+
+    let handle_clear = move |_| {
+        first.set(AnchorDraft::default());
+        second.set(AnchorDraft::default());
+        set_geo_reference(None);
+    };
+
+    view! {
+        <div class="geo-reference-editor">
+            <GeoAnchorRow label="First station" stations=stations draft=first on_change=handle_change/>
+            <GeoAnchorRow label="Second station" stations=stations draft=second on_change=handle_change/>
+            <button class="clear-geo-reference" on:click=handle_clear>
+                <i class="fa-solid fa-xmark"></i>
+                " Clear"
+            </button>
+        </div>
+    }
+}
@@ -10,13 +10,16 @@ use crate::models::{RailwayGraph, UserSettings};
 use crate::conflict::Conflict;
 use crate::train_journey::TrainJourney;
 use crate::components::conflict_tooltip::ConflictTooltip;
+use crate::components::journey_tooltip::JourneyTooltip;
 use crate::components::station_label_tooltip::StationLabelTooltip;
 use crate::components::canvas_controls_hint::CanvasControlsHint;
+use crate::components::playback_controls::PlaybackControls;
+use crate::components::measure_tool::MeasureTool;
 use crate::components::canvas_viewport;
 use crate::constants::BASE_DATE;
 use crate::time::time_to_fraction;
-use super::{station_labels, time_labels, conflict_indicators, train_positions, train_journeys, time_scrubber, graph_content};
-use super::types::{GraphDimensions, ViewportState, ConflictDisplayState, HoverState};
+use super::{station_labels, time_labels, conflict_indicators, train_positions, train_journeys, time_scrubber, graph_content, measure};
+use super::types::{GraphDimensions, ViewportState, ConflictDisplayState, HoverState, HighlightedJourney, ReferenceOverlay, FocusSource, FocusedStations, DEFAULT_TIME_WINDOW};
 use crate::theme::{Theme, use_theme};
 
 // Layout constants for the graph canvas
@@ -56,6 +59,7 @@ fn setup_render_effect(
     conflicts_memo: Memo<Vec<Conflict>>,
     show_conflicts: Signal<bool>,
     show_line_blocks: Signal<bool>,
+    headway_separation: Signal<Option<chrono::Duration>>,
     spacing_mode: Signal<crate::models::SpacingMode>,
     hovered_conflict: ReadSignal<Option<(Conflict, f64, f64)>>,
     hovered_journey_id: ReadSignal<Option<uuid::Uuid>>,
@@ -65,6 +69,7 @@ fn setup_render_effect(
     station_label_width: ReadSignal<f64>,
     edited_line_ids: ReadSignal<std::collections::HashSet<uuid::Uuid>>,
     theme: ReadSignal<Theme>,
+    time_window: Signal<(f64, f64)>,
 ) {
     let (render_requested, set_render_requested) = create_signal(false);
     let is_disposed = Rc::new(Cell::new(false));
@@ -72,6 +77,9 @@ fn setup_render_effect(
     let zoom_level_x = viewport.zoom_level_x.expect("horizontal zoom enabled").0;
     let pan_offset_x = viewport.pan_offset_x;
     let pan_offset_y = viewport.pan_offset_y;
+    let highlighted_journey = use_context::<HighlightedJourney>();
+    let reference_overlay = use_context::<ReferenceOverlay>();
+    let focus = use_context::<FocusedStations>();
 
     {
         let is_disposed = Rc::clone(&is_disposed);
@@ -96,11 +104,16 @@ fn setup_render_effect(
         let _ = show_conflicts.get();
         let _ = hovered_conflict.get();
         let _ = show_line_blocks.get();
+        let _ = headway_separation.get();
         let _ = hovered_journey_id.get();
         let _ = spacing_mode.get();
         let _ = station_label_width.get();
         let _ = edited_line_ids.get();
         let _ = theme.get();
+        let _ = time_window.get();
+        let _ = highlighted_journey.map(|h| h.journey_id.get());
+        let _ = reference_overlay.map(|r| (r.timetable.with(|_| ()), r.visible.get()));
+        let _ = focus.map(|f| f.focus.with(|_| ()));
 
         if !render_requested.get_untracked() {
             set_render_requested.set(true);
@@ -151,20 +164,31 @@ fn setup_render_effect(
                 let conflict_display = ConflictDisplayState {
                     conflicts: &current_conflicts,
                     show_conflicts: show_conflicts.get_untracked(),
+                    headway_separation: headway_separation.get_untracked(),
                 };
                 let hovered = hovered_conflict.get_untracked();
                 let hovered_journey_value = hovered_journey_id.get_untracked();
+                let highlighted_journey_value = highlighted_journey.and_then(|h| h.journey_id.get_untracked());
+                let focused_stations = focus
+                    .map(|f| leptos::untrack(|| f.highlighted_in(FocusSource::TimeGraph)))
+                    .unwrap_or_default();
                 let hover_state = HoverState {
                     hovered_conflict: hovered.as_ref().map(|(c, _, _)| c),
                     show_line_blocks: show_line_blocks.get_untracked(),
                     hovered_journey_id: hovered_journey_value.as_ref(),
+                    highlighted_journey_id: highlighted_journey_value.as_ref(),
+                    focused_stations: &focused_stations,
                 };
                 let current_spacing_mode = spacing_mode.get_untracked();
                 let current_edge_path = view_edge_path.get_untracked();
                 let label_width = station_label_width.get_untracked();
                 let current_edited_line_ids = edited_line_ids.get_untracked();
                 let current_theme = theme.get_untracked();
-                render_graph(&canvas, &stations_for_render, &journeys, current, &viewport, &conflict_display, &hover_state, &current_graph, &idx_map, current_spacing_mode, &current_edge_path, label_width, &current_edited_line_ids, current_theme);
+                let reference = reference_overlay
+                    .filter(|r| r.visible.get_untracked())
+                    .and_then(|r| r.timetable.get_untracked());
+                let reference_journeys = reference.map(|r| r.journeys).unwrap_or_default();
+                render_graph(&canvas, &stations_for_render, &journeys, &reference_journeys, current, &viewport, &conflict_display, &hover_state, &current_graph, &idx_map, current_spacing_mode, &current_edge_path, label_width, &current_edited_line_ids, current_theme, time_window.get_untracked());
             });
 
             let _ = window.request_animation_frame(callback.as_ref().unchecked_ref());
@@ -183,17 +207,16 @@ fn handle_mouse_move_hover(
     viewport: ViewportState,
     conflicts_memo: Memo<Vec<Conflict>>,
     display_stations: Signal<Vec<(petgraph::stable_graph::NodeIndex, crate::models::Node)>>,
-    show_line_blocks: Signal<bool>,
     train_journeys: ReadSignal<std::collections::HashMap<uuid::Uuid, TrainJourney>>,
     set_hovered_conflict: WriteSignal<Option<(Conflict, f64, f64)>>,
-    set_hovered_journey_id: WriteSignal<Option<uuid::Uuid>>,
     set_hovered_station_label: WriteSignal<Option<(String, f64, f64)>>,
     station_idx_map: leptos::Memo<std::collections::HashMap<usize, usize>>,
     graph: ReadSignal<RailwayGraph>,
     spacing_mode: Signal<crate::models::SpacingMode>,
     view_edge_path: Signal<Vec<usize>>,
     station_label_width: f64,
-) {
+    time_window: (f64, f64),
+) -> Option<uuid::Uuid> {
     let current_conflicts = conflicts_memo.get();
     let current_stations = display_stations.get();
     let idx_map = station_idx_map.get();
@@ -204,13 +227,18 @@ fn handle_mouse_move_hover(
     // Calculate station positions for accurate hover detection
     let canvas_width = f64::from(canvas.width());
     let canvas_height = f64::from(canvas.height());
-    let dimensions = GraphDimensions::new(canvas_width, canvas_height, station_label_width);
+    let dimensions = GraphDimensions::new(canvas_width, canvas_height, station_label_width)
+        .with_time_window(time_window.0, time_window.1);
     let station_y_positions = current_graph.calculate_station_positions(
         &current_stations,
         current_spacing_mode,
         dimensions.graph_height,
         dimensions.top_margin,
     );
+    let viewport = ViewportState {
+        pan_offset_x: dimensions.window_pan_offset(viewport.pan_offset_x, viewport.zoom_level * viewport.zoom_level_x),
+        ..viewport
+    };
 
     let hovered = conflict_indicators::check_conflict_hover(
         x, y, &current_conflicts, &current_stations, &station_y_positions,
@@ -228,20 +256,82 @@ fn handle_mouse_move_hover(
     );
     set_hovered_station_label.set(hovered_label);
 
-    if show_line_blocks.get() {
-        let journeys = train_journeys.get();
-        let mut journeys_vec: Vec<_> = journeys.values().collect();
-        journeys_vec.sort_by_key(|j| j.departure_time);
-        let hovered_journey = train_journeys::check_journey_hover(
-            x, y, &journeys_vec, &current_stations, &station_y_positions,
-            &current_edge_path,
-            &dimensions,
-            &viewport
+    let journeys = train_journeys.get();
+    let mut journeys_vec: Vec<_> = journeys.values().collect();
+    journeys_vec.sort_by_key(|j| j.departure_time);
+    train_journeys::check_journey_hover(
+        x, y, &journeys_vec, &current_stations, &station_y_positions,
+        &current_edge_path,
+        &dimensions,
+        &viewport
+    )
+}
+
+/// Scroll the station axis so the station at the given display index is vertically centered
+fn setup_pan_to_station_effect(
+    pan_signal: ReadSignal<Option<usize>>,
+    canvas_ref: NodeRef<html::Canvas>,
+    graph: ReadSignal<RailwayGraph>,
+    display_stations: Signal<Vec<(petgraph::stable_graph::NodeIndex, crate::models::Node)>>,
+    spacing_mode: Signal<crate::models::SpacingMode>,
+    station_label_width: ReadSignal<f64>,
+    viewport: &canvas_viewport::ViewportSignals,
+) {
+    let zoom_level = viewport.zoom_level;
+    let set_pan_offset_y = viewport.set_pan_offset_y;
+    create_effect(move |_| {
+        let Some(display_idx) = pan_signal.get() else { return };
+        let Some(canvas_elem) = canvas_ref.get_untracked() else { return };
+        let canvas: &web_sys::HtmlCanvasElement = &canvas_elem;
+        let canvas_height = f64::from(canvas.height());
+        let dims = GraphDimensions::new(f64::from(canvas.width()), canvas_height, station_label_width.get_untracked());
+
+        let station_y_positions = graph.get_untracked().calculate_station_positions(
+            &display_stations.get_untracked(),
+            spacing_mode.get_untracked(),
+            dims.graph_height,
+            dims.top_margin,
         );
-        set_hovered_journey_id.set(hovered_journey);
-    } else {
-        set_hovered_journey_id.set(None);
-    }
+        let Some(&y_pos) = station_y_positions.get(display_idx) else { return };
+
+        // Subtract TOP_MARGIN since station_y_positions include it but we're in transformed coords
+        let target_y = ((y_pos - TOP_MARGIN) * zoom_level.get_untracked()) - (canvas_height / 2.0);
+        set_pan_offset_y.set(-target_y);
+    });
+}
+
+/// Share the stations whose rows are on screen with the infrastructure view
+fn setup_focus_effect(
+    canvas_ref: NodeRef<html::Canvas>,
+    graph: ReadSignal<RailwayGraph>,
+    display_stations: Signal<Vec<(petgraph::stable_graph::NodeIndex, crate::models::Node)>>,
+    spacing_mode: Signal<crate::models::SpacingMode>,
+    station_label_width: ReadSignal<f64>,
+    viewport: &canvas_viewport::ViewportSignals,
+) {
+    let Some(focus) = use_context::<FocusedStations>() else { return };
+    let zoom_level = viewport.zoom_level;
+    let pan_offset_y = viewport.pan_offset_y;
+    create_effect(move |_| {
+        let zoom = zoom_level.get();
+        let pan_y = pan_offset_y.get();
+        let stations = display_stations.get();
+        let Some(canvas_elem) = canvas_ref.get() else { return };
+        let canvas: &web_sys::HtmlCanvasElement = &canvas_elem;
+        let dims = GraphDimensions::new(f64::from(canvas.width()), f64::from(canvas.height()), station_label_width.get());
+
+        let station_y_positions = graph.with(|graph| graph.calculate_station_positions(
+            &stations,
+            spacing_mode.get(),
+            dims.graph_height,
+            dims.top_margin,
+        ));
+        let visible: std::collections::HashSet<_> = station_labels::visible_station_rows(&dims, &station_y_positions, zoom, pan_y)
+            .filter_map(|(idx, _)| stations.get(idx).map(|(node, _)| *node))
+            .collect();
+        let partial = !visible.is_empty() && visible.len() < stations.len();
+        focus.publish(FocusSource::TimeGraph, partial.then_some(visible));
+    });
 }
 
 #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation, clippy::cast_sign_loss, clippy::too_many_lines)]
@@ -254,11 +344,14 @@ pub fn GraphCanvas(
     set_visualization_time: WriteSignal<NaiveDateTime>,
     show_conflicts: Signal<bool>,
     show_line_blocks: Signal<bool>,
+    /// Minimum separation to draw headway bands with, or `None` to hide them
+    headway_separation: Signal<Option<chrono::Duration>>,
     spacing_mode: Signal<crate::models::SpacingMode>,
     hovered_journey_id: ReadSignal<Option<uuid::Uuid>>,
     set_hovered_journey_id: WriteSignal<Option<uuid::Uuid>>,
     conflicts_memo: Memo<Vec<Conflict>>,
     #[prop(optional)] pan_to_conflict_signal: Option<ReadSignal<Option<(f64, f64)>>>,
+    #[prop(optional)] pan_to_station_signal: Option<ReadSignal<Option<usize>>>,
     display_stations: Signal<Vec<(petgraph::stable_graph::NodeIndex, crate::models::Node)>>,
     station_idx_map: leptos::Memo<std::collections::HashMap<usize, usize>>,
     view_edge_path: Signal<Vec<usize>>,
@@ -266,7 +359,10 @@ pub fn GraphCanvas(
     on_viewport_change: leptos::Callback<crate::models::ViewportState>,
     edited_line_ids: ReadSignal<std::collections::HashSet<uuid::Uuid>>,
     #[prop(optional, into)] sidebar_width: MaybeSignal<f64>,
+    /// Start and end hour spanning the graph width at default zoom
+    #[prop(optional, into)] time_window: Option<Signal<(f64, f64)>>,
 ) -> impl IntoView {
+    let time_window = time_window.unwrap_or_else(|| Signal::derive(|| DEFAULT_TIME_WINDOW));
     // Get user settings from context
     let (user_settings, _) = use_context::<(ReadSignal<UserSettings>, WriteSignal<UserSettings>)>()
         .expect("UserSettings context not found");
@@ -280,12 +376,16 @@ pub fn GraphCanvas(
 
     let canvas_ref = create_node_ref::<leptos::html::Canvas>();
     let (is_dragging, set_is_dragging) = create_signal(false);
+    let (is_playing, set_is_playing) = create_signal(false);
+    let (is_measuring, set_is_measuring) = create_signal(false);
+    let (measure_points, set_measure_points) = create_signal(Vec::<measure::MeasurePoint>::new());
     let (is_resizing_station_labels, set_is_resizing_station_labels) = create_signal(false);
     let (resize_start_x, set_resize_start_x) = create_signal(0.0);
     let (resize_start_width, set_resize_start_width) = create_signal(0.0);
     let (is_hovering_resize_boundary, set_is_hovering_resize_boundary) = create_signal(false);
     let (hovered_conflict, set_hovered_conflict) = create_signal(None::<(Conflict, f64, f64)>);
     let (hovered_station_label, set_hovered_station_label) = create_signal(None::<(String, f64, f64)>);
+    let (hovered_journey_tooltip, set_hovered_journey_tooltip) = create_signal(None::<(uuid::Uuid, f64, f64)>);
     let (space_pressed, set_space_pressed) = create_signal(false);
 
     // Track WASD keys for panning
@@ -427,7 +527,9 @@ pub fn GraphCanvas(
                     let canvas_height = f64::from(canvas.height());
                     let label_width = station_label_width.get();
 
-                    let dims = GraphDimensions::new(canvas_width, canvas_height, label_width);
+                    let (start_hour, end_hour) = time_window.get_untracked();
+                    let dims = GraphDimensions::new(canvas_width, canvas_height, label_width)
+                        .with_time_window(start_hour, end_hour);
 
                     let current_graph = graph.get();
                     let current_stations = display_stations.get();
@@ -451,26 +553,92 @@ pub fn GraphCanvas(
                     // Subtract TOP_MARGIN since station_y_positions include it but we're in transformed coords
                     let target_y = ((y_pos - TOP_MARGIN) * target_zoom) - (canvas_height / 2.0);
 
-                    set_pan_offset_x.set(-target_x);
+                    // Undo the window shift the renderer folds into the pan
+                    set_pan_offset_x.set(-target_x + dims.start_hour * dims.hour_width * target_zoom * zoom_level_x.get_untracked());
                     set_pan_offset_y.set(-target_y);
                 }
             }
         });
     }
 
+    if let Some(pan_signal) = pan_to_station_signal {
+        setup_pan_to_station_effect(pan_signal, canvas_ref, graph, display_stations, spacing_mode, station_label_width, &viewport);
+    }
+    setup_focus_effect(canvas_ref, graph, display_stations, spacing_mode, station_label_width, &viewport);
+
     setup_render_effect(
         canvas_ref, train_journeys, visualization_time, graph, &viewport,
-        conflicts_memo, show_conflicts, show_line_blocks, spacing_mode,
+        conflicts_memo, show_conflicts, show_line_blocks, headway_separation, spacing_mode,
         hovered_conflict, hovered_journey_id, display_stations, station_idx_map,
-        view_edge_path, station_label_width, edited_line_ids, theme
+        view_edge_path, station_label_width, edited_line_ids, theme, time_window
     );
 
+    let highlighted_journey = use_context::<HighlightedJourney>();
+
+    // Dimensions and viewport the canvas is currently drawn with, for mapping clicks back to the graph
+    let current_transform = move |canvas: &web_sys::HtmlCanvasElement| {
+        let (start_hour, end_hour) = time_window.get_untracked();
+        let dims = GraphDimensions::new(f64::from(canvas.width()), f64::from(canvas.height()), station_label_width.get_untracked())
+            .with_time_window(start_hour, end_hour);
+        let viewport = ViewportState {
+            zoom_level: zoom_level.get_untracked(),
+            zoom_level_x: zoom_level_x.get_untracked(),
+            pan_offset_x: pan_offset_x.get_untracked(),
+            pan_offset_y: pan_offset_y.get_untracked(),
+        };
+        (dims, viewport)
+    };
+
+    let add_measure_click = move |canvas: &web_sys::HtmlCanvasElement, x: f64, y: f64| {
+        let (dims, viewport) = current_transform(canvas);
+        let point = graph.with_untracked(|graph| {
+            let station_y_positions = display_stations.with_untracked(|stations| {
+                graph.calculate_station_positions(stations, spacing_mode.get_untracked(), dims.graph_height, dims.top_margin)
+            });
+            let cumulative = view_edge_path.with_untracked(|path| measure::cumulative_distances(graph, path));
+            measure::measure_point_at(x, y, &dims, &viewport, &station_y_positions, cumulative.as_deref())
+        });
+        set_measure_points.update(|points| measure::add_measure_point(points, point));
+    };
+
+    let measurement = Signal::derive(move || {
+        measure_points.with(|points| match points.as_slice() {
+            [first, second] => Some(measure::Measurement::between(first, second)),
+            _ => None,
+        })
+    });
+
+    // Screen positions of the measure points, following zoom and pan
+    let measure_screen_points = Signal::derive(move || {
+        let _ = (zoom_level.get(), zoom_level_x.get(), pan_offset_x.get(), pan_offset_y.get());
+        let Some(canvas) = canvas_ref.get() else { return Vec::new() };
+        let (dims, viewport) = current_transform(&canvas);
+        measure_points.with(|points| {
+            points.iter()
+                .map(|point| measure::graph_to_screen(point.hours, point.graph_y, &dims, &viewport))
+                .collect::<Vec<_>>()
+        })
+    });
+
+    // Leaving measure mode clears the measurement
+    create_effect(move |_| {
+        if !is_measuring.get() {
+            set_measure_points.set(Vec::new());
+        }
+    });
+
     let handle_mouse_down = move |ev: MouseEvent| {
         if let Some(canvas_elem) = canvas_ref.get() {
             let canvas: &web_sys::HtmlCanvasElement = &canvas_elem;
             let rect = canvas.get_bounding_client_rect();
             let x = f64::from(ev.client_x()) - rect.left();
 
+            if is_measuring.get_untracked() && !space_pressed.get_untracked() {
+                let y = f64::from(ev.client_y()) - rect.top();
+                add_measure_click(canvas, x, y);
+                return;
+            }
+
             let label_width = station_label_width.get();
             let resize_boundary = label_width;
             let resize_handle_width = 5.0;
@@ -481,9 +649,18 @@ pub fn GraphCanvas(
                 set_resize_start_x.set(x);
                 set_resize_start_width.set(label_width);
             } else if !space_pressed.get() {
-                // Only handle time scrubbing if not resizing and space is not pressed
-                let canvas_width = f64::from(canvas.width());
-                handle_time_scrubbing(x, canvas_width, zoom_level.get(), zoom_level_x.get(), pan_offset_x.get(), set_is_dragging, set_visualization_time, label_width);
+                // Clicking a journey highlights it, clicking empty space clears the highlight
+                if let Some(highlighted) = highlighted_journey {
+                    highlighted.set_journey_id.set(hovered_journey_id.get_untracked());
+                }
+
+                // Only handle time scrubbing if not resizing and space is not pressed;
+                // scrubbing manually takes over from playback
+                set_is_playing.set(false);
+                let (start_hour, end_hour) = time_window.get();
+                let dims = GraphDimensions::new(f64::from(canvas.width()), f64::from(canvas.height()), label_width)
+                    .with_time_window(start_hour, end_hour);
+                handle_time_scrubbing(x, &dims, zoom_level.get(), zoom_level_x.get(), pan_offset_x.get(), set_is_dragging, set_visualization_time);
             }
         }
     };
@@ -514,12 +691,12 @@ pub fn GraphCanvas(
             } else if is_panning.get() {
                 canvas_viewport::handle_pan_move(x, y, &viewport);
             } else if is_dragging.get() {
-                let canvas_width = f64::from(canvas.width());
-                let label_width = station_label_width.get();
-                let graph_width = canvas_width - label_width - RIGHT_PADDING;
+                let (start_hour, end_hour) = time_window.get();
+                let dims = GraphDimensions::new(f64::from(canvas.width()), f64::from(canvas.height()), station_label_width.get())
+                    .with_time_window(start_hour, end_hour);
 
-                if x >= label_width && x <= label_width + graph_width {
-                    update_time_from_x(x, label_width, graph_width, zoom_level.get(), zoom_level_x.get(), pan_offset_x.get(), set_visualization_time);
+                if x >= dims.left_margin && x <= dims.left_margin + dims.graph_width {
+                    update_time_from_x(x, &dims, zoom_level.get(), zoom_level_x.get(), pan_offset_x.get(), set_visualization_time);
                 }
             } else {
                 // Check if hovering over resize boundary
@@ -534,7 +711,11 @@ pub fn GraphCanvas(
                     pan_offset_x: pan_offset_x.get(),
                     pan_offset_y: pan_offset_y.get(),
                 };
-                handle_mouse_move_hover(x, y, viewport_x, viewport_y, canvas, viewport_state, conflicts_memo, display_stations, show_line_blocks, train_journeys, set_hovered_conflict, set_hovered_journey_id, set_hovered_station_label, station_idx_map, graph, spacing_mode, view_edge_path, label_width);
+                let hovered_journey = handle_mouse_move_hover(x, y, viewport_x, viewport_y, canvas, viewport_state, conflicts_memo, display_stations, train_journeys, set_hovered_conflict, set_hovered_station_label, station_idx_map, graph, spacing_mode, view_edge_path, label_width, time_window.get());
+                set_hovered_journey_id.set(hovered_journey.filter(|_| show_line_blocks.get()));
+                // Conflict tooltips take precedence over the journey timing breakdown
+                let show_journey_tooltip = hovered_conflict.get_untracked().is_none();
+                set_hovered_journey_tooltip.set(hovered_journey.filter(|_| show_journey_tooltip).map(|id| (id, x, y)));
             }
         }
     };
@@ -551,6 +732,7 @@ pub fn GraphCanvas(
         canvas_viewport::handle_pan_end(&viewport);
         set_hovered_conflict.set(None);
         set_hovered_station_label.set(None);
+        set_hovered_journey_tooltip.set(None);
     };
 
     let handle_wheel = move |ev: WheelEvent| {
@@ -613,7 +795,35 @@ pub fn GraphCanvas(
             ></canvas>
 
             <ConflictTooltip hovered_conflict=hovered_conflict graph=graph />
+            <JourneyTooltip hovered_journey=hovered_journey_tooltip train_journeys=train_journeys graph=graph />
             <StationLabelTooltip hovered_station_label=hovered_station_label />
+            <PlaybackControls
+                visualization_time=visualization_time
+                set_visualization_time=set_visualization_time
+                is_playing=is_playing
+                set_is_playing=set_is_playing
+                time_window=time_window
+            />
+            <MeasureTool
+                is_measuring=is_measuring
+                set_is_measuring=set_is_measuring
+                point_count=Signal::derive(move || measure_points.with(Vec::len))
+                measurement=measurement
+                right_offset=Signal::derive(move || sidebar_width.get() + 20.0)
+            />
+            {move || {
+                let points = measure_screen_points.get();
+                (!points.is_empty()).then(|| view! {
+                    <svg class="measure-overlay">
+                        {points.windows(2).map(|pair| view! {
+                            <line x1=pair[0].0 y1=pair[0].1 x2=pair[1].0 y2=pair[1].1 />
+                        }).collect::<Vec<_>>()}
+                        {points.iter().map(|&(x, y)| view! {
+                            <circle cx=x cy=y r="4" />
+                        }).collect::<Vec<_>>()}
+                    </svg>
+                })
+            }}
             <CanvasControlsHint
                 visible=show_hint
                 show_horizontal_scaling=true
@@ -624,11 +834,11 @@ pub fn GraphCanvas(
 }
 
 #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
-fn update_time_from_x(x: f64, left_margin: f64, graph_width: f64, zoom_level: f64, zoom_level_x: f64, pan_offset_x: f64, set_time: WriteSignal<NaiveDateTime>) {
+fn update_time_from_x(x: f64, dims: &GraphDimensions, zoom_level: f64, zoom_level_x: f64, pan_offset_x: f64, set_time: WriteSignal<NaiveDateTime>) {
     // Transform mouse coordinates to account for zoom and pan
     // Reverse the transformations applied in render_graph:
     // 1. Remove left margin offset to get graph-relative position
-    let graph_x = x - left_margin;
+    let graph_x = x - dims.left_margin;
     // 2. Account for pan offset (subtract because pan moves the content)
     let panned_x = graph_x - pan_offset_x;
     // 3. Account for uniform zoom (divide because zoom scales the content up)
@@ -636,11 +846,8 @@ fn update_time_from_x(x: f64, left_margin: f64, graph_width: f64, zoom_level: f6
     // 4. Account for horizontal zoom (divide because it stretches time axis)
     let time_unzoomed_x = uniformly_unzoomed_x / zoom_level_x;
 
-    // Now calculate fraction based on the base (unzoomed) graph width
-    let base_graph_width = graph_width;
-    let fraction = time_unzoomed_x / base_graph_width;
-
-    let total_hours = fraction * 48.0; // 48 hours to support past-midnight
+    // Map back through the configured time window
+    let total_hours = dims.x_to_time(dims.left_margin + time_unzoomed_x);
     let total_minutes = (total_hours * 60.0) as u32;
 
     // Calculate days, hours, and minutes
@@ -657,11 +864,39 @@ fn update_time_from_x(x: f64, left_margin: f64, graph_width: f64, zoom_level: f6
     }
 }
 
+/// Journeys overlapping the visible time range, sorted by departure time for a consistent draw order
+fn journeys_in_time_range(
+    journeys: &std::collections::HashMap<uuid::Uuid, TrainJourney>,
+    visible_start: f64,
+    visible_end: f64,
+) -> Vec<&TrainJourney> {
+    let mut visible: Vec<&TrainJourney> = journeys.values()
+        .filter(|journey| {
+            // Quick time-based culling: check if journey overlaps visible time range
+            if let (Some((_, start, _)), Some((_, _, end))) =
+                (journey.station_times.first(), journey.station_times.last()) {
+                let start_frac = time_to_fraction(*start);
+                let end_frac = time_to_fraction(*end);
+
+                // Journey is visible if it overlaps with visible range
+                end_frac >= visible_start && start_frac <= visible_end
+            } else {
+                false
+            }
+        })
+        .collect();
+
+    // Sort by departure time for consistent draw order (prevents z-fighting)
+    visible.sort_by_key(|j| j.departure_time);
+    visible
+}
+
 #[allow(clippy::too_many_lines, clippy::too_many_arguments)]
 fn render_graph(
     canvas: &leptos::HtmlElement<leptos::html::Canvas>,
     stations: &[(petgraph::stable_graph::NodeIndex, crate::models::Node)],
     train_journeys: &std::collections::HashMap<uuid::Uuid, TrainJourney>,
+    reference_journeys: &std::collections::HashMap<uuid::Uuid, TrainJourney>,
     current_time: chrono::NaiveDateTime,
     viewport: &ViewportState,
     conflict_display: &ConflictDisplayState,
@@ -673,13 +908,19 @@ fn render_graph(
     station_label_width: f64,
     edited_line_ids: &std::collections::HashSet<uuid::Uuid>,
     theme: Theme,
+    time_window: (f64, f64),
 ) {
     let canvas_element: &web_sys::HtmlCanvasElement = canvas;
     let canvas_width = f64::from(canvas_element.width());
     let canvas_height = f64::from(canvas_element.height());
 
     // Create dimensions once for the entire render
-    let dimensions = GraphDimensions::new(canvas_width, canvas_height, station_label_width);
+    let dimensions = GraphDimensions::new(canvas_width, canvas_height, station_label_width)
+        .with_time_window(time_window.0, time_window.1);
+    let viewport = &ViewportState {
+        pan_offset_x: dimensions.window_pan_offset(viewport.pan_offset_x, viewport.zoom_level * viewport.zoom_level_x),
+        ..viewport.clone()
+    };
 
     // Calculate station Y positions based on spacing mode
     let station_y_positions = graph.calculate_station_positions(
@@ -694,24 +935,8 @@ fn render_graph(
     let visible_start = -viewport.pan_offset_x / visible_hour_width;
     let visible_end = visible_start + (dimensions.graph_width / visible_hour_width);
 
-    let mut journeys_vec: Vec<&TrainJourney> = train_journeys.values()
-        .filter(|journey| {
-            // Quick time-based culling: check if journey overlaps visible time range
-            if let (Some((_, start, _)), Some((_, _, end))) =
-                (journey.station_times.first(), journey.station_times.last()) {
-                let start_frac = time_to_fraction(*start);
-                let end_frac = time_to_fraction(*end);
-
-                // Journey is visible if it overlaps with visible range
-                end_frac >= visible_start && start_frac <= visible_end
-            } else {
-                false
-            }
-        })
-        .collect();
-
-    // Sort by departure time for consistent draw order (prevents z-fighting)
-    journeys_vec.sort_by_key(|j| j.departure_time);
+    let journeys_vec = journeys_in_time_range(train_journeys, visible_start, visible_end);
+    let reference_vec = journeys_in_time_range(reference_journeys, visible_start, visible_end);
 
     let Ok(Some(context)) = canvas_element.get_context("2d") else {
         leptos::logging::warn!("Failed to get 2D context");
@@ -756,6 +981,31 @@ fn render_graph(
     graph_content::draw_station_grid(&ctx, &zoomed_dimensions, stations, &station_y_positions, viewport.zoom_level, viewport.pan_offset_x, theme);
     graph_content::draw_double_track_indicators(&ctx, &zoomed_dimensions, stations, &station_y_positions, graph, viewport.zoom_level, viewport.pan_offset_x, theme);
 
+    // Draw the reference timetable underneath the current journeys
+    train_journeys::draw_reference_journeys(
+        &ctx,
+        &zoomed_dimensions,
+        stations,
+        &station_y_positions,
+        &reference_vec,
+        view_edge_path,
+        viewport.zoom_level,
+        time_to_fraction,
+    );
+
+    if let Some(separation) = conflict_display.headway_separation {
+        train_journeys::draw_headway_bands(
+            &ctx,
+            &zoomed_dimensions,
+            stations,
+            &station_y_positions,
+            &journeys_vec,
+            view_edge_path,
+            separation,
+            time_to_fraction,
+        );
+    }
+
     // Draw train journeys
     train_journeys::draw_train_journeys(
         &ctx,
@@ -767,6 +1017,7 @@ fn render_graph(
         viewport.zoom_level,
         time_to_fraction,
         edited_line_ids,
+        hover_state.highlighted_journey_id,
     );
 
     // Draw conflicts if enabled
@@ -846,8 +1097,8 @@ fn render_graph(
         &dimensions,
         stations,
         &station_y_positions,
-        viewport.zoom_level,
-        viewport.pan_offset_y,
+        viewport,
+        hover_state.focused_stations,
         theme,
     );
 
@@ -900,18 +1151,15 @@ fn clear_canvas(ctx: &CanvasRenderingContext2d, width: f64, height: f64) {
 
 fn handle_time_scrubbing(
     x: f64,
-    canvas_width: f64,
+    dims: &GraphDimensions,
     zoom_level: f64,
     zoom_level_x: f64,
     pan_offset_x: f64,
     set_is_dragging: WriteSignal<bool>,
     set_visualization_time: WriteSignal<NaiveDateTime>,
-    station_label_width: f64,
 ) {
-    let graph_width = canvas_width - station_label_width - RIGHT_PADDING;
-
-    if x >= station_label_width && x <= station_label_width + graph_width {
+    if x >= dims.left_margin && x <= dims.left_margin + dims.graph_width {
         set_is_dragging.set(true);
-        update_time_from_x(x, station_label_width, graph_width, zoom_level, zoom_level_x, pan_offset_x, set_visualization_time);
+        update_time_from_x(x, dims, zoom_level, zoom_level_x, pan_offset_x, set_visualization_time);
     }
 }
@@ -1,6 +1,6 @@
 use super::types::GraphDimensions;
 use crate::models::Node;
-use crate::conflict::Conflict;
+use crate::conflict::{Conflict, ConflictType};
 use crate::constants::BASE_MIDNIGHT;
 use crate::theme::Theme;
 use web_sys::{CanvasRenderingContext2d, Path2d};
@@ -8,6 +8,7 @@ use web_sys::{CanvasRenderingContext2d, Path2d};
 const CONFLICT_TRIANGLE_SIZE: f64 = 15.0;
 const CONFLICT_TRIANGLE_FILL: &str = "rgba(255, 200, 0, 0.9)";
 const CONFLICT_TRIANGLE_STROKE: &str = "rgba(0, 0, 0, 0.8)";
+const NEAR_MISS_TRIANGLE_FILL: &str = "rgba(255, 140, 0, 0.6)";
 const CONFLICT_LABEL_FONT_SIZE: f64 = 9.0;
 const CONFLICT_LABEL_OFFSET: f64 = 5.0;
 const CONFLICT_WARNING_FONT_SIZE: f64 = 14.0;
@@ -120,7 +121,13 @@ pub fn draw_conflict_highlights(
 
         // Draw triangle using Path2D
         if let Some(ref path) = triangle_path {
-            ctx.set_fill_style_str(CONFLICT_TRIANGLE_FILL);
+            // Near-misses are drawn in a softer amber to set them apart from real conflicts
+            let fill = if conflict.conflict_type == ConflictType::NearMiss {
+                NEAR_MISS_TRIANGLE_FILL
+            } else {
+                CONFLICT_TRIANGLE_FILL
+            };
+            ctx.set_fill_style_str(fill);
             ctx.set_stroke_style_str(CONFLICT_TRIANGLE_STROKE);
             ctx.fill_with_path_2d(path);
             ctx.stroke_with_path(path);
@@ -253,8 +260,7 @@ pub fn check_conflict_hover(
         // Calculate conflict position in screen coordinates
         // The canvas uses: translate(left_margin, top_margin) + translate(pan) + scale(zoom)
         let time_fraction = time_to_fraction(conflict.time);
-        let total_hours = 48.0;
-        let hour_width = dims.graph_width / total_hours;
+        let hour_width = dims.hour_width;
 
         // Position in zoomed coordinate system (before translation)
         let x_in_zoomed = time_fraction * hour_width;
@@ -0,0 +1,186 @@
+use crate::models::RailwayGraph;
+use super::types::{GraphDimensions, ViewportState};
+use petgraph::stable_graph::EdgeIndex;
+
+/// A clicked point on the graph in unzoomed graph coordinates
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MeasurePoint {
+    /// Hours after `BASE_DATE` midnight
+    pub hours: f64,
+    /// Y in the same space as the station y positions
+    pub graph_y: f64,
+    /// Distance along the view from its first station, if all track distances are known
+    pub distance_km: Option<f64>,
+}
+
+/// Elapsed time, distance and implied speed between two measure points
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Measurement {
+    pub elapsed_hours: f64,
+    pub distance_km: Option<f64>,
+    pub speed_kmh: Option<f64>,
+}
+
+impl Measurement {
+    /// Measure between two points regardless of the order they were clicked in
+    #[must_use]
+    pub fn between(first: &MeasurePoint, second: &MeasurePoint) -> Self {
+        let elapsed_hours = (second.hours - first.hours).abs();
+        let distance_km = first.distance_km
+            .zip(second.distance_km)
+            .map(|(a, b)| (b - a).abs());
+        let speed_kmh = distance_km
+            .filter(|_| elapsed_hours > 0.0)
+            .map(|distance| distance / elapsed_hours);
+
+        Self { elapsed_hours, distance_km, speed_kmh }
+    }
+}
+
+/// Convert a canvas position to (hours, graph y), reversing the zoom and pan applied in `render_graph`.
+/// `viewport.pan_offset_x` is the user pan, without the time window start folded in.
+#[must_use]
+pub fn screen_to_graph(x: f64, y: f64, dims: &GraphDimensions, viewport: &ViewportState) -> (f64, f64) {
+    let unzoomed_x = (x - dims.left_margin - viewport.pan_offset_x) / (viewport.zoom_level * viewport.zoom_level_x);
+    let hours = dims.x_to_time(dims.left_margin + unzoomed_x);
+    let graph_y = dims.top_margin + (y - dims.top_margin - viewport.pan_offset_y) / viewport.zoom_level;
+    (hours, graph_y)
+}
+
+/// Inverse of `screen_to_graph`
+#[must_use]
+pub fn graph_to_screen(hours: f64, graph_y: f64, dims: &GraphDimensions, viewport: &ViewportState) -> (f64, f64) {
+    let unzoomed_x = dims.time_to_x(hours) - dims.left_margin;
+    let x = dims.left_margin + viewport.pan_offset_x + unzoomed_x * viewport.zoom_level * viewport.zoom_level_x;
+    let y = dims.top_margin + viewport.pan_offset_y + (graph_y - dims.top_margin) * viewport.zoom_level;
+    (x, y)
+}
+
+/// Measure point for a canvas click, with its distance when the view's track distances are known
+#[must_use]
+pub fn measure_point_at(
+    x: f64,
+    y: f64,
+    dims: &GraphDimensions,
+    viewport: &ViewportState,
+    station_y_positions: &[f64],
+    cumulative: Option<&[f64]>,
+) -> MeasurePoint {
+    let (hours, graph_y) = screen_to_graph(x, y, dims, viewport);
+    let distance_km = cumulative.and_then(|cumulative| {
+        station_position_at(graph_y, station_y_positions).and_then(|position| distance_at(position, cumulative))
+    });
+    MeasurePoint { hours, graph_y, distance_km }
+}
+
+/// Record a click; a third click starts a new measurement
+pub fn add_measure_point(points: &mut Vec<MeasurePoint>, point: MeasurePoint) {
+    if points.len() >= 2 {
+        points.clear();
+    }
+    points.push(point);
+}
+
+/// Fractional station index for a graph y, clamped to the first and last station
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn station_position_at(graph_y: f64, station_y_positions: &[f64]) -> Option<f64> {
+    let first = *station_y_positions.first()?;
+    if graph_y <= first {
+        return Some(0.0);
+    }
+
+    for (i, pair) in station_y_positions.windows(2).enumerate() {
+        if graph_y <= pair[1] {
+            let span = pair[1] - pair[0];
+            let fraction = if span > 0.0 { (graph_y - pair[0]) / span } else { 0.0 };
+            return Some(i as f64 + fraction);
+        }
+    }
+
+    Some((station_y_positions.len() - 1) as f64)
+}
+
+/// Distance from the first station of the view to each station, or `None` if any track distance is missing
+#[must_use]
+pub fn cumulative_distances(graph: &RailwayGraph, view_edge_path: &[usize]) -> Option<Vec<f64>> {
+    let mut distances = vec![0.0];
+    for &edge_index in view_edge_path {
+        let segment = graph.graph.edge_weight(EdgeIndex::new(edge_index))?;
+        let distance = segment.distance.filter(|&d| d > 0.0)?;
+        let total = distances.last().copied().unwrap_or(0.0) + distance;
+        distances.push(total);
+    }
+    Some(distances)
+}
+
+/// Distance at a fractional station index, interpolating within the track between stations
+#[must_use]
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub fn distance_at(station_position: f64, cumulative: &[f64]) -> Option<f64> {
+    let index = station_position.floor().max(0.0) as usize;
+    let start = *cumulative.get(index)?;
+    let end = cumulative.get(index + 1).copied().unwrap_or(start);
+    Some(start + (end - start) * station_position.fract())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn viewport() -> ViewportState {
+        ViewportState { zoom_level: 2.0, zoom_level_x: 1.5, pan_offset_x: -120.0, pan_offset_y: 35.0 }
+    }
+
+    #[test]
+    fn test_screen_to_graph_inverts_graph_to_screen() {
+        let dims = GraphDimensions::new(1200.0, 800.0, 150.0).with_time_window(6.0, 30.0);
+        let viewport = viewport();
+
+        let (x, y) = graph_to_screen(9.5, 240.0, &dims, &viewport);
+        let (hours, graph_y) = screen_to_graph(x, y, &dims, &viewport);
+        assert!((hours - 9.5).abs() < 1e-9);
+        assert!((graph_y - 240.0).abs() < 1e-9);
+
+        // Without zoom or pan the left edge of the graph is the window start
+        let identity = ViewportState { zoom_level: 1.0, zoom_level_x: 1.0, pan_offset_x: 0.0, pan_offset_y: 0.0 };
+        let (hours, _) = screen_to_graph(dims.left_margin, 0.0, &dims, &identity);
+        assert!((hours - 6.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_station_position_and_distance() {
+        let station_y_positions = [100.0, 200.0, 400.0];
+        assert_eq!(station_position_at(150.0, &station_y_positions), Some(0.5));
+        assert_eq!(station_position_at(300.0, &station_y_positions), Some(1.5));
+        assert_eq!(station_position_at(50.0, &station_y_positions), Some(0.0));
+        assert_eq!(station_position_at(500.0, &station_y_positions), Some(2.0));
+
+        let cumulative = [0.0, 4.0, 10.0];
+        assert_eq!(distance_at(0.5, &cumulative), Some(2.0));
+        assert_eq!(distance_at(1.5, &cumulative), Some(7.0));
+        assert_eq!(distance_at(2.0, &cumulative), Some(10.0));
+    }
+
+    #[test]
+    fn test_measurement_speed_in_either_click_order() {
+        let start = MeasurePoint { hours: 8.0, graph_y: 100.0, distance_km: Some(2.0) };
+        let end = MeasurePoint { hours: 8.25, graph_y: 300.0, distance_km: Some(17.0) };
+
+        let forward = Measurement::between(&start, &end);
+        assert!((forward.elapsed_hours - 0.25).abs() < 1e-9);
+        assert_eq!(forward.distance_km, Some(15.0));
+        assert_eq!(forward.speed_kmh, Some(60.0));
+        assert_eq!(Measurement::between(&end, &start), forward);
+
+        // Unknown distances still give the elapsed time
+        let unknown = MeasurePoint { distance_km: None, ..end };
+        let partial = Measurement::between(&start, &unknown);
+        assert_eq!(partial.distance_km, None);
+        assert_eq!(partial.speed_kmh, None);
+
+        // Two clicks at the same time have no implied speed
+        let same_time = MeasurePoint { hours: 8.0, ..end };
+        assert_eq!(Measurement::between(&start, &same_time).speed_kmh, None);
+    }
+}
@@ -6,8 +6,9 @@ pub mod train_positions;
 pub mod train_journeys;
 pub mod time_scrubber;
 pub mod junction_indicators;
+pub mod measure;
 pub mod types;
 mod canvas;
 
 pub use canvas::*;
-pub use types::{GraphDimensions, ViewportState};
\ No newline at end of file
+pub use types::{FocusSource, FocusedStations, GraphDimensions, HighlightedJourney, ReferenceOverlay, ViewportState, VisualizationTime};
\ No newline at end of file
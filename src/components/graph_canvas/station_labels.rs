@@ -1,14 +1,17 @@
 use web_sys::CanvasRenderingContext2d;
 use crate::models::Node;
 use crate::theme::Theme;
-use super::types::GraphDimensions;
+use super::types::{GraphDimensions, ViewportState};
 use petgraph::stable_graph::NodeIndex;
+use std::collections::HashSet;
 
 const STATION_LABEL_FONT: &str = "11px monospace";
 const STATION_LABEL_X: f64 = 5.0;
 const STATION_LABEL_Y_OFFSET: f64 = 3.0;
 const LABEL_RIGHT_PADDING: f64 = 5.0;
 
+const FOCUS_ROW_HEIGHT: f64 = 16.0;
+
 const JUNCTION_DIAMOND_SIZE: f64 = 6.0;
 const JUNCTION_LABEL_X_OFFSET: f64 = 12.0;
 
@@ -17,6 +20,7 @@ struct Palette {
     single_platform: &'static str,
     passing_loop: &'static str,
     junction: &'static str,
+    focus_background: &'static str,
 }
 
 const DARK_PALETTE: Palette = Palette {
@@ -24,6 +28,7 @@ const DARK_PALETTE: Palette = Palette {
     single_platform: "#888",
     passing_loop: "#777",
     junction: "#ffb84d",
+    focus_background: "rgba(74, 158, 255, 0.12)",
 };
 
 const LIGHT_PALETTE: Palette = Palette {
@@ -31,6 +36,7 @@ const LIGHT_PALETTE: Palette = Palette {
     single_platform: "#777",
     passing_loop: "#888",
     junction: "#cc8800",
+    focus_background: "rgba(25, 118, 210, 0.1)",
 };
 
 fn get_palette(theme: Theme) -> &'static Palette {
@@ -78,45 +84,60 @@ fn truncate_text_with_ellipsis(ctx: &CanvasRenderingContext2d, text: &str, max_w
     format!("{truncated}{ellipsis}")
 }
 
+/// Screen Y of each station row at the given zoom and pan, paired with its index,
+/// keeping only rows inside the visible graph area
+pub fn visible_station_rows<'a>(
+    dims: &'a GraphDimensions,
+    station_y_positions: &'a [f64],
+    zoom_level: f64,
+    pan_offset_y: f64,
+) -> impl Iterator<Item = (usize, f64)> + 'a {
+    use super::canvas::TOP_MARGIN as ORIGINAL_TOP_MARGIN;
+
+    station_y_positions.iter().enumerate().filter_map(move |(idx, y)| {
+        // station_y_positions include the original TOP_MARGIN, subtract it to get graph-relative coords
+        // Then apply zoom and pan transformations to get screen coordinates
+        let adjusted_y = dims.top_margin + ((y - ORIGINAL_TOP_MARGIN) * zoom_level) + pan_offset_y;
+        (adjusted_y >= dims.top_margin && adjusted_y <= dims.top_margin + dims.graph_height)
+            .then_some((idx, adjusted_y))
+    })
+}
+
 #[allow(clippy::cast_precision_loss)]
 pub fn draw_station_labels(
     ctx: &CanvasRenderingContext2d,
     dims: &GraphDimensions,
     stations: &[(NodeIndex, Node)],
     station_y_positions: &[f64],
-    zoom_level: f64,
-    pan_offset_y: f64,
+    viewport: &ViewportState,
+    focused_stations: &HashSet<NodeIndex>,
     theme: Theme,
 ) {
-    use super::canvas::TOP_MARGIN as ORIGINAL_TOP_MARGIN;
-
     let palette = get_palette(theme);
     let station_label_width = dims.left_margin;
-    // Draw labels for each node in the stations list (includes both stations and junctions)
+    // Draw labels for each visible node in the stations list (includes both stations and junctions)
 
-    for (idx, (_, station_node)) in stations.iter().enumerate() {
-        // station_y_positions include the original TOP_MARGIN, subtract it to get graph-relative coords
-        // Then apply zoom and pan transformations to get screen coordinates
-        let base_y = station_y_positions[idx] - ORIGINAL_TOP_MARGIN;
-        let adjusted_y = dims.top_margin + (base_y * zoom_level) + pan_offset_y;
-
-        // Only draw if visible
-        if adjusted_y >= dims.top_margin && adjusted_y <= dims.top_margin + dims.graph_height {
-            // Check if this is a junction or a station
-            match station_node {
-                Node::Station(station) => {
-                    if station.passing_loop {
-                        draw_passing_loop_label(ctx, &station_node.display_name(), adjusted_y, station_label_width, palette);
-                    } else if station.platforms.len() == 1 {
-                        draw_single_platform_label(ctx, &station_node.display_name(), adjusted_y, station_label_width, palette);
-                    } else {
-                        draw_station_label(ctx, &station_node.display_name(), adjusted_y, station_label_width, palette);
-                    }
-                }
-                Node::Junction(_) => {
-                    draw_junction_label(ctx, Some(&station_node.display_name()), adjusted_y, station_label_width, palette);
+    for (idx, adjusted_y) in visible_station_rows(dims, station_y_positions, viewport.zoom_level, viewport.pan_offset_y) {
+        let Some((node_idx, station_node)) = stations.get(idx) else { continue };
+        if focused_stations.contains(node_idx) {
+            ctx.set_fill_style_str(palette.focus_background);
+            ctx.fill_rect(0.0, adjusted_y - FOCUS_ROW_HEIGHT / 2.0, station_label_width, FOCUS_ROW_HEIGHT);
+        }
+
+        // Check if this is a junction or a station
+        match station_node {
+            Node::Station(station) => {
+                if station.passing_loop {
+                    draw_passing_loop_label(ctx, &station_node.display_name(), adjusted_y, station_label_width, palette);
+                } else if station.platforms.len() == 1 {
+                    draw_single_platform_label(ctx, &station_node.display_name(), adjusted_y, station_label_width, palette);
+                } else {
+                    draw_station_label(ctx, &station_node.display_name(), adjusted_y, station_label_width, palette);
                 }
             }
+            Node::Junction(_) => {
+                draw_junction_label(ctx, Some(&station_node.display_name()), adjusted_y, station_label_width, palette);
+            }
         }
     }
 }
@@ -10,10 +10,14 @@ const MIDNIGHT_WRAP_THRESHOLD: f64 = 0.5;
 const HOVER_DISTANCE_THRESHOLD: f64 = 10.0; // pixels
 const DOT_RADIUS_MULTIPLIER: f64 = 1.5; // Scale dots relative to line thickness
 const MIN_DOT_RADIUS: f64 = 2.0; // Minimum dot radius in pixels
-const TOTAL_HOURS: f64 = 48.0; // Total hours displayed on the graph
 const CONTINUATION_ARROW_LENGTH: f64 = 12.0; // Length of continuation arrow
 const CONTINUATION_ARROW_HEAD_SIZE: f64 = 6.0; // Size of arrow head
 const NON_EDITED_JOURNEY_OPACITY: f64 = 0.5; // Opacity for journeys when line editor is open
+const NON_HIGHLIGHTED_JOURNEY_OPACITY: f64 = 0.25; // Opacity for other journeys while one is highlighted
+const HIGHLIGHTED_JOURNEY_THICKNESS_MULTIPLIER: f64 = 2.0;
+const REFERENCE_JOURNEY_OPACITY: f64 = 0.2; // Opacity for journeys of the reference timetable
+const HEADWAY_BAND_OPACITY: f64 = 0.15; // Opacity of the minimum separation band around each journey
+const HEADWAY_OVERLAP_FILL: &str = "rgba(255, 100, 0, 0.45)"; // Bands of trains running closer than the minimum separation
 
 /// Update search direction based on position change
 fn update_search_direction(
@@ -203,6 +207,23 @@ fn draw_continuation_indicator(
     ctx.restore();
 }
 
+/// Stroke color and thickness for a journey, dimming it when another journey is highlighted
+/// or when line editors are open for other lines
+fn journey_style(
+    journey: &TrainJourney,
+    edited_line_ids: &std::collections::HashSet<uuid::Uuid>,
+    highlighted_journey: Option<&uuid::Uuid>,
+) -> (String, f64) {
+    match highlighted_journey {
+        Some(id) if *id == journey.id => (journey.color.clone(), journey.thickness * HIGHLIGHTED_JOURNEY_THICKNESS_MULTIPLIER),
+        Some(_) => (super::types::hex_to_rgba(&journey.color, NON_HIGHLIGHTED_JOURNEY_OPACITY), journey.thickness),
+        None if !edited_line_ids.is_empty() && !edited_line_ids.contains(&journey.line_id) => {
+            (super::types::hex_to_rgba(&journey.color, NON_EDITED_JOURNEY_OPACITY), journey.thickness)
+        }
+        None => (journey.color.clone(), journey.thickness),
+    }
+}
+
 #[allow(clippy::cast_precision_loss, clippy::too_many_lines, clippy::cast_possible_truncation, clippy::too_many_arguments)]
 pub fn draw_train_journeys(
     ctx: &CanvasRenderingContext2d,
@@ -214,9 +235,17 @@ pub fn draw_train_journeys(
     zoom_level: f64,
     time_to_fraction: fn(chrono::NaiveDateTime) -> f64,
     edited_line_ids: &std::collections::HashSet<uuid::Uuid>,
+    highlighted_journey: Option<&uuid::Uuid>,
 ) {
+    // Draw the highlighted journey last so it sits on top of the others
+    let train_journeys: Vec<&TrainJourney> = train_journeys.iter()
+        .copied()
+        .filter(|j| highlighted_journey != Some(&j.id))
+        .chain(train_journeys.iter().copied().filter(|j| highlighted_journey == Some(&j.id)))
+        .collect();
+
     // Draw lines for each journey
-    for journey in train_journeys {
+    for journey in &train_journeys {
         if journey.station_times.is_empty() {
             continue;
         }
@@ -229,16 +258,10 @@ pub fn draw_train_journeys(
             nodes,
         );
 
-        // Apply dimming to journeys not in edited lines
-        let should_dim = !edited_line_ids.is_empty() && !edited_line_ids.contains(&journey.line_id);
-        let color = if should_dim {
-            super::types::hex_to_rgba(&journey.color, NON_EDITED_JOURNEY_OPACITY)
-        } else {
-            journey.color.clone()
-        };
+        let (color, thickness) = journey_style(journey, edited_line_ids, highlighted_journey);
 
         ctx.set_stroke_style_str(&color);
-        ctx.set_line_width(journey.thickness / zoom_level);
+        ctx.set_line_width(thickness / zoom_level);
         ctx.begin_path();
 
         let mut last_visible_point: Option<(f64, f64, usize)> = None; // (x, y, view_position)
@@ -362,7 +385,7 @@ pub fn draw_train_journeys(
     }
 
     // Draw dots for each journey
-    for journey in train_journeys {
+    for journey in &train_journeys {
         if journey.station_times.is_empty() {
             continue;
         }
@@ -375,16 +398,10 @@ pub fn draw_train_journeys(
             nodes,
         );
 
-        // Apply dimming to journeys not in edited lines
-        let should_dim = !edited_line_ids.is_empty() && !edited_line_ids.contains(&journey.line_id);
-        let color = if should_dim {
-            super::types::hex_to_rgba(&journey.color, NON_EDITED_JOURNEY_OPACITY)
-        } else {
-            journey.color.clone()
-        };
+        let (color, thickness) = journey_style(journey, edited_line_ids, highlighted_journey);
 
         ctx.set_fill_style_str(&color);
-        let dot_radius = (journey.thickness * DOT_RADIUS_MULTIPLIER).max(MIN_DOT_RADIUS);
+        let dot_radius = (thickness * DOT_RADIUS_MULTIPLIER).max(MIN_DOT_RADIUS);
         ctx.begin_path();
 
         // Collect visible node info with original node_idx
@@ -450,6 +467,153 @@ pub fn draw_train_journeys(
     }
 }
 
+/// Horizontal width in pixels of a band spanning `separation` on a time axis of `hour_width` pixels per hour
+#[allow(clippy::cast_precision_loss)]
+#[must_use]
+pub fn headway_band_width(separation: chrono::Duration, hour_width: f64) -> f64 {
+    separation.num_milliseconds() as f64 / 3_600_000.0 * hour_width
+}
+
+/// A journey's run between two consecutive visible stations
+struct BandSegment {
+    edge_index: usize,
+    from_node: NodeIndex,
+    departure: chrono::NaiveDateTime,
+    arrival: chrono::NaiveDateTime,
+    start: (f64, f64),
+    end: (f64, f64),
+}
+
+fn journey_band_segments(
+    journey: &TrainJourney,
+    dims: &GraphDimensions,
+    nodes: &[(NodeIndex, Node)],
+    station_y_positions: &[f64],
+    view_edge_path: &[usize],
+    time_to_fraction: fn(chrono::NaiveDateTime) -> f64,
+) -> Vec<BandSegment> {
+    let positions = match_journey_stations_to_view_by_edges(&journey.segments, &journey.station_times, view_edge_path, nodes);
+    let y_of = |i: usize| {
+        let idx = positions.get(i).copied().flatten()?;
+        station_y_positions.get(idx).map(|y| y - super::canvas::TOP_MARGIN)
+    };
+    let x_of = |time| dims.left_margin + time_to_fraction(time) * dims.hour_width;
+
+    journey.segments.iter().enumerate()
+        .filter_map(|(i, segment)| {
+            let (from_node, _, departure) = *journey.station_times.get(i)?;
+            let (_, arrival, _) = *journey.station_times.get(i + 1)?;
+            if departure < BASE_MIDNIGHT {
+                return None;
+            }
+            Some(BandSegment {
+                edge_index: segment.edge_index,
+                from_node,
+                departure,
+                arrival,
+                start: (x_of(departure), y_of(i)?),
+                end: (x_of(arrival), y_of(i + 1)?),
+            })
+        })
+        .collect()
+}
+
+/// Whether two runs over the same edge in the same direction come closer than `separation`
+fn bands_overlap(a: &BandSegment, b: &BandSegment, separation: chrono::Duration) -> bool {
+    let departure_gap = a.departure - b.departure;
+    let arrival_gap = a.arrival - b.arrival;
+    let overtakes = departure_gap.num_seconds().signum() != arrival_gap.num_seconds().signum();
+    departure_gap.abs() < separation || arrival_gap.abs() < separation || overtakes
+}
+
+/// Mark the segments, by journey and segment position, whose band overlaps another journey's band
+fn overlapping_band_segments(segments: &[Vec<BandSegment>], separation: chrono::Duration) -> std::collections::HashSet<(usize, usize)> {
+    let mut by_edge: std::collections::HashMap<(usize, NodeIndex), Vec<(usize, usize)>> = std::collections::HashMap::new();
+    for (journey_idx, journey_segments) in segments.iter().enumerate() {
+        for (segment_idx, segment) in journey_segments.iter().enumerate() {
+            by_edge.entry((segment.edge_index, segment.from_node)).or_default().push((journey_idx, segment_idx));
+        }
+    }
+
+    let mut overlapping = std::collections::HashSet::new();
+    for runs in by_edge.values() {
+        for (n, &(journey_a, segment_a)) in runs.iter().enumerate() {
+            for &(journey_b, segment_b) in &runs[n + 1..] {
+                if journey_a != journey_b && bands_overlap(&segments[journey_a][segment_a], &segments[journey_b][segment_b], separation) {
+                    overlapping.insert((journey_a, segment_a));
+                    overlapping.insert((journey_b, segment_b));
+                }
+            }
+        }
+    }
+    overlapping
+}
+
+/// Draw a translucent band `separation` wide around each journey, highlighting bands of
+/// same-direction trains on a shared edge that overlap
+#[allow(clippy::too_many_arguments)]
+pub fn draw_headway_bands(
+    ctx: &CanvasRenderingContext2d,
+    dims: &GraphDimensions,
+    nodes: &[(NodeIndex, Node)],
+    station_y_positions: &[f64],
+    train_journeys: &[&TrainJourney],
+    view_edge_path: &[usize],
+    separation: chrono::Duration,
+    time_to_fraction: fn(chrono::NaiveDateTime) -> f64,
+) {
+    let half_width = headway_band_width(separation, dims.hour_width) / 2.0;
+    let segments: Vec<Vec<BandSegment>> = train_journeys.iter()
+        .map(|journey| journey_band_segments(journey, dims, nodes, station_y_positions, view_edge_path, time_to_fraction))
+        .collect();
+    let overlapping = overlapping_band_segments(&segments, separation);
+
+    for (journey_idx, (journey, journey_segments)) in train_journeys.iter().zip(&segments).enumerate() {
+        let band_fill = super::types::hex_to_rgba(&journey.color, HEADWAY_BAND_OPACITY);
+        for (segment_idx, segment) in journey_segments.iter().enumerate() {
+            let fill = if overlapping.contains(&(journey_idx, segment_idx)) { HEADWAY_OVERLAP_FILL } else { &band_fill };
+            let ((x1, y1), (x2, y2)) = (segment.start, segment.end);
+            ctx.set_fill_style_str(fill);
+            ctx.begin_path();
+            ctx.move_to(x1 - half_width, y1);
+            ctx.line_to(x2 - half_width, y2);
+            ctx.line_to(x2 + half_width, y2);
+            ctx.line_to(x1 + half_width, y1);
+            ctx.close_path();
+            ctx.fill();
+        }
+    }
+}
+
+/// Draw the journeys of a reference timetable faded out, without highlighting or editing emphasis
+#[allow(clippy::too_many_arguments)]
+pub fn draw_reference_journeys(
+    ctx: &CanvasRenderingContext2d,
+    dims: &GraphDimensions,
+    nodes: &[(NodeIndex, Node)],
+    station_y_positions: &[f64],
+    reference_journeys: &[&TrainJourney],
+    view_edge_path: &[usize],
+    zoom_level: f64,
+    time_to_fraction: fn(chrono::NaiveDateTime) -> f64,
+) {
+    ctx.save();
+    ctx.set_global_alpha(REFERENCE_JOURNEY_OPACITY);
+    draw_train_journeys(
+        ctx,
+        dims,
+        nodes,
+        station_y_positions,
+        reference_journeys,
+        view_edge_path,
+        zoom_level,
+        time_to_fraction,
+        &std::collections::HashSet::new(),
+        None,
+    );
+    ctx.restore();
+}
+
 #[allow(clippy::cast_precision_loss, clippy::too_many_arguments)]
 #[must_use]
 pub fn check_journey_hover(
@@ -468,24 +632,32 @@ pub fn check_journey_hover(
         return None;
     }
 
-    train_journeys
-        .iter()
-        .find_map(|journey| {
-            check_single_journey_hover(
-                mouse_x,
-                mouse_y,
-                journey,
-                nodes,
-                dims,
-                station_y_positions,
-                view_edge_path,
-                viewport,
-            )
-        })
+    nearest_journey(train_journeys.iter().filter_map(|journey| {
+        journey_hover_distance(
+            mouse_x,
+            mouse_y,
+            journey,
+            nodes,
+            dims,
+            station_y_positions,
+            view_edge_path,
+            viewport,
+        )
+        .map(|distance| (journey.id, distance))
+    }))
 }
 
+/// Pick the journey closest to the cursor, so overlapping lines resolve to the one under it
+fn nearest_journey(candidates: impl Iterator<Item = (uuid::Uuid, f64)>) -> Option<uuid::Uuid> {
+    candidates
+        .filter(|(_, distance)| *distance < HOVER_DISTANCE_THRESHOLD)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(id, _)| id)
+}
+
+/// Screen distance from the cursor to the closest drawn segment of a journey
 #[allow(clippy::cast_precision_loss)]
-fn check_single_journey_hover(
+fn journey_hover_distance(
     mouse_x: f64,
     mouse_y: f64,
     journey: &TrainJourney,
@@ -494,7 +666,7 @@ fn check_single_journey_hover(
     station_y_positions: &[f64],
     view_edge_path: &[usize],
     viewport: &super::types::ViewportState,
-) -> Option<uuid::Uuid> {
+) -> Option<f64> {
     use crate::time::time_to_fraction;
 
     // Match journey stations to view positions using edge-based matching
@@ -506,8 +678,12 @@ fn check_single_journey_hover(
     );
 
     let mut prev_departure_point: Option<(f64, f64)> = None;
+    let mut closest: Option<f64> = None;
+    let mut consider = |distance: f64| {
+        closest = Some(closest.map_or(distance, |c: f64| c.min(distance)));
+    };
 
-    let hour_width = dims.graph_width / TOTAL_HOURS;
+    let hour_width = dims.hour_width;
     let mut first_point = true;
     let mut prev_x = 0.0;
 
@@ -537,20 +713,14 @@ fn check_single_journey_hover(
 
             // Check diagonal segment from previous departure to this arrival
             if let Some((prev_dep_x, prev_dep_y)) = prev_departure_point {
-                let distance = point_to_line_distance(mouse_x, mouse_y, prev_dep_x, prev_dep_y, arrival_screen_x, screen_y);
-                if distance < HOVER_DISTANCE_THRESHOLD {
-                    return Some(journey.id);
-                }
+                consider(point_to_line_distance(mouse_x, mouse_y, prev_dep_x, prev_dep_y, arrival_screen_x, screen_y));
             }
 
             // Check horizontal segment from arrival to departure at this station
             let has_wait_time = departure_screen_x - arrival_screen_x > f64::EPSILON;
 
             if has_wait_time {
-                let distance = point_to_line_distance(mouse_x, mouse_y, arrival_screen_x, screen_y, departure_screen_x, screen_y);
-                if distance < HOVER_DISTANCE_THRESHOLD {
-                    return Some(journey.id);
-                }
+                consider(point_to_line_distance(mouse_x, mouse_y, arrival_screen_x, screen_y, departure_screen_x, screen_y));
             }
 
             // Update prev point to the actual position we drew to
@@ -562,7 +732,7 @@ fn check_single_journey_hover(
         prev_x = departure_x_zoomed;
     }
 
-    None
+    closest
 }
 
 fn point_to_line_distance(px: f64, py: f64, x1: f64, y1: f64, x2: f64, y2: f64) -> f64 {
@@ -585,4 +755,36 @@ fn point_to_line_distance(px: f64, py: f64, x1: f64, y1: f64, x2: f64, y2: f64)
 
     // Calculate distance
     ((px - closest_x) * (px - closest_x) + (py - closest_y) * (py - closest_y)).sqrt()
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nearest_journey_picks_closest_overlapping_line() {
+        let (first, second) = (uuid::Uuid::new_v4(), uuid::Uuid::new_v4());
+        let (mouse_x, mouse_y) = (50.0, 53.0);
+
+        // Two nearly overlapping diagonal lines, both within hover range
+        let first_distance = point_to_line_distance(mouse_x, mouse_y, 0.0, 0.0, 100.0, 100.0);
+        let second_distance = point_to_line_distance(mouse_x, mouse_y, 0.0, 4.0, 100.0, 104.0);
+        assert!(first_distance < HOVER_DISTANCE_THRESHOLD && second_distance < HOVER_DISTANCE_THRESHOLD);
+
+        let candidates = [(first, first_distance), (second, second_distance)];
+        assert_eq!(nearest_journey(candidates.into_iter()), Some(second));
+        assert_eq!(nearest_journey(candidates.into_iter().rev()), Some(second));
+
+        // Lines outside the hover threshold are never picked
+        assert_eq!(nearest_journey([(first, HOVER_DISTANCE_THRESHOLD + 1.0)].into_iter()), None);
+    }
+
+    #[test]
+    fn test_headway_band_width_converts_separation_to_pixels() {
+        // 3 minutes on a 120px hour is 6px, and doubles when the time axis is zoomed in 2x
+        assert_eq!(headway_band_width(chrono::Duration::minutes(3), 120.0), 6.0);
+        assert_eq!(headway_band_width(chrono::Duration::minutes(3), 240.0), 12.0);
+        assert_eq!(headway_band_width(chrono::Duration::seconds(90), 120.0), 3.0);
+        assert_eq!(headway_band_width(chrono::Duration::zero(), 120.0), 0.0);
+    }
+}
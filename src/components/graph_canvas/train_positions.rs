@@ -32,7 +32,53 @@ fn get_palette(theme: Theme) -> &'static Palette {
     }
 }
 
-#[allow(clippy::too_many_arguments, clippy::cast_precision_loss)]
+/// Where a train is relative to the view's stations at a given moment
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrainPosition {
+    /// View station index the train last departed from, or is dwelling at
+    pub from_station: usize,
+    /// View station index the train is heading to, equal to `from_station` while dwelling
+    pub to_station: usize,
+    /// Fraction of the run between the two stations already covered
+    pub progress: f64,
+}
+
+/// Locate a train at `time` by interpolating between its station times.
+/// Returns `None` when the train is not running or not visible in the view at that moment.
+#[allow(clippy::cast_precision_loss)]
+#[must_use]
+pub fn interpolate_train_position(
+    station_times: &[(NodeIndex, NaiveDateTime, NaiveDateTime)],
+    station_positions: &[Option<usize>],
+    time: NaiveDateTime,
+) -> Option<TrainPosition> {
+    let mut prev_departure: Option<(NaiveDateTime, usize)> = None;
+
+    for (i, (_node_idx, arrival_time, departure_time)) in station_times.iter().enumerate() {
+        let Some(station_idx) = station_positions.get(i).and_then(|&opt| opt) else { continue };
+
+        // Train is waiting at this station
+        if *arrival_time <= time && time <= *departure_time {
+            return Some(TrainPosition { from_station: station_idx, to_station: station_idx, progress: 0.0 });
+        }
+
+        if *departure_time <= time {
+            prev_departure = Some((*departure_time, station_idx));
+            continue;
+        }
+
+        // First station not yet reached: the train is between it and the previous departure
+        let (prev_time, prev_idx) = prev_departure?;
+        let segment_duration = arrival_time.signed_duration_since(prev_time).num_seconds() as f64;
+        let elapsed = time.signed_duration_since(prev_time).num_seconds() as f64;
+        let progress = if segment_duration > 0.0 { (elapsed / segment_duration).clamp(0.0, 1.0) } else { 1.0 };
+        return Some(TrainPosition { from_station: prev_idx, to_station: station_idx, progress });
+    }
+
+    None
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn draw_current_train_positions(
     ctx: &CanvasRenderingContext2d,
     dims: &GraphDimensions,
@@ -46,6 +92,8 @@ pub fn draw_current_train_positions(
     theme: Theme,
 ) {
     let palette = get_palette(theme);
+    // Positions are interpolated linearly in time, so the dot always sits on the time cursor
+    let x = dims.left_margin + (time_to_fraction(visualization_time) * dims.hour_width);
 
     for journey in train_journeys {
         // Match journey stations to view positions using edge-based matching
@@ -56,87 +104,75 @@ pub fn draw_current_train_positions(
             stations,
         );
 
-        // Find which segment the train is currently on (or if it's waiting at a station)
-        let mut prev_departure: Option<(usize, NaiveDateTime, usize)> = None;
-        let mut next_arrival: Option<(usize, NaiveDateTime, usize)> = None;
-
-        for (i, (_node_idx, arrival_time, departure_time)) in journey.station_times.iter().enumerate() {
-            if let Some(station_idx) = station_positions.get(i).and_then(|&opt| opt) {
-                // Check if train is currently waiting at this station
-                if *arrival_time <= visualization_time && visualization_time <= *departure_time {
-                    // Train is waiting at this station
-                    let x = dims.left_margin + (time_to_fraction(visualization_time) * dims.hour_width);
-                    // Note: station_y_positions include the original TOP_MARGIN, subtract it for transformed coords
-                    let y = station_y_positions[station_idx] - super::canvas::TOP_MARGIN;
-
-                    // Draw train as a larger dot with an outline
-                    ctx.set_fill_style_str(&journey.color);
-                    ctx.set_stroke_style_str(palette.train_outline);
-                    ctx.set_line_width(CURRENT_TRAIN_OUTLINE_WIDTH / zoom_level);
-                    ctx.begin_path();
-                    let _ = ctx.arc(x, y, CURRENT_TRAIN_RADIUS / zoom_level, 0.0, std::f64::consts::PI * 2.0);
-                    ctx.fill();
-                    ctx.stroke();
-
-                    // Draw train number label
-                    ctx.set_fill_style_str(palette.train_label);
-                    ctx.set_font(&format!("bold {}px monospace", CURRENT_TRAIN_LABEL_FONT_SIZE / zoom_level));
-                    let _ = ctx.fill_text(&journey.train_number, x - 12.0 / zoom_level, y - 10.0 / zoom_level);
-                    break;
-                }
-
-                if *departure_time <= visualization_time {
-                    prev_departure = Some((i, *departure_time, station_idx));
-                } else if next_arrival.is_none() {
-                    next_arrival = Some((i, *arrival_time, station_idx));
-                    break;
-                }
-            }
-        }
+        let Some(position) = interpolate_train_position(&journey.station_times, &station_positions, visualization_time) else {
+            continue;
+        };
+
+        // Note: station_y_positions include the original TOP_MARGIN, subtract it for transformed coords
+        let from_y = station_y_positions[position.from_station] - super::canvas::TOP_MARGIN;
+        let to_y = station_y_positions[position.to_station] - super::canvas::TOP_MARGIN;
+        let y = from_y + (to_y - from_y) * position.progress;
+
+        // Draw train as a larger dot with an outline
+        ctx.set_fill_style_str(&journey.color);
+        ctx.set_stroke_style_str(palette.train_outline);
+        ctx.set_line_width(CURRENT_TRAIN_OUTLINE_WIDTH / zoom_level);
+        ctx.begin_path();
+        let _ = ctx.arc(x, y, CURRENT_TRAIN_RADIUS / zoom_level, 0.0, std::f64::consts::PI * 2.0);
+        ctx.fill();
+        ctx.stroke();
+
+        // Draw train number label with zoom-compensated font size
+        ctx.set_fill_style_str(palette.train_label);
+        ctx.set_font(&format!("bold {}px monospace", CURRENT_TRAIN_LABEL_FONT_SIZE / zoom_level));
+        let _ = ctx.fill_text(&journey.train_number, x - 12.0 / zoom_level, y - 10.0 / zoom_level);
+    }
+}
 
-        // If train is traveling between two stations, interpolate its position
-        if let (Some((_, prev_time, prev_idx)), Some((_, next_time, next_idx))) =
-            (prev_departure, next_arrival)
-        {
-            let segment_duration = next_time.signed_duration_since(prev_time).num_seconds() as f64;
-            let elapsed = visualization_time
-                .signed_duration_since(prev_time)
-                .num_seconds() as f64;
-            let progress = (elapsed / segment_duration).clamp(0.0, 1.0);
-
-            let prev_x = dims.left_margin + (time_to_fraction(prev_time) * dims.hour_width);
-            // Note: station_y_positions include the original TOP_MARGIN, subtract it for transformed coords
-            let prev_y = station_y_positions[prev_idx] - super::canvas::TOP_MARGIN;
-
-            let next_x = dims.left_margin + (time_to_fraction(next_time) * dims.hour_width);
-            let next_y = station_y_positions[next_idx] - super::canvas::TOP_MARGIN;
-
-            let current_x = prev_x + (next_x - prev_x) * progress;
-            let current_y = prev_y + (next_y - prev_y) * progress;
-
-            // Draw train as a larger dot with an outline
-            ctx.set_fill_style_str(&journey.color);
-            ctx.set_stroke_style_str(palette.train_outline);
-            ctx.set_line_width(CURRENT_TRAIN_OUTLINE_WIDTH / zoom_level);
-            ctx.begin_path();
-            let _ = ctx.arc(
-                current_x,
-                current_y,
-                CURRENT_TRAIN_RADIUS / zoom_level,
-                0.0,
-                std::f64::consts::PI * 2.0,
-            );
-            ctx.fill();
-            ctx.stroke();
-
-            // Draw train number label with zoom-compensated font size
-            ctx.set_fill_style_str(palette.train_label);
-            ctx.set_font(&format!("bold {}px monospace", CURRENT_TRAIN_LABEL_FONT_SIZE / zoom_level));
-            let _ = ctx.fill_text(
-                &journey.train_number,
-                current_x - 12.0 / zoom_level,
-                current_y - 10.0 / zoom_level,
-            );
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn at(hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 1, 1)
+            .and_then(|d| d.and_hms_opt(hour, minute, 0))
+            .expect("valid time")
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_interpolate_train_position_between_stations() {
+        let station_times = vec![
+            (NodeIndex::new(0), at(8, 0), at(8, 0)),
+            (NodeIndex::new(1), at(8, 10), at(8, 12)),
+            (NodeIndex::new(2), at(8, 32), at(8, 32)),
+        ];
+        let station_positions = vec![Some(0), Some(1), Some(2)];
+
+        // A quarter of the way through the 20 minute run from the second to the third station
+        let position = interpolate_train_position(&station_times, &station_positions, at(8, 17));
+        assert_eq!(position, Some(TrainPosition { from_station: 1, to_station: 2, progress: 0.25 }));
+
+        // Dwelling at the second station
+        let position = interpolate_train_position(&station_times, &station_positions, at(8, 11));
+        assert_eq!(position, Some(TrainPosition { from_station: 1, to_station: 1, progress: 0.0 }));
+
+        // Before departure and after arrival the train is not shown
+        assert_eq!(interpolate_train_position(&station_times, &station_positions, at(7, 59)), None);
+        assert_eq!(interpolate_train_position(&station_times, &station_positions, at(8, 33)), None);
+    }
+
+    #[test]
+    fn test_interpolate_train_position_skips_stations_outside_view() {
+        let station_times = vec![
+            (NodeIndex::new(0), at(8, 0), at(8, 0)),
+            (NodeIndex::new(1), at(8, 10), at(8, 10)),
+            (NodeIndex::new(2), at(8, 20), at(8, 20)),
+        ];
+        // The middle station is not part of the view, so the run spans 20 minutes
+        let station_positions = vec![Some(3), None, Some(5)];
+
+        let position = interpolate_train_position(&station_times, &station_positions, at(8, 15));
+        assert_eq!(position, Some(TrainPosition { from_station: 3, to_station: 5, progress: 0.75 }));
+    }
+}
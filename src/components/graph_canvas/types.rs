@@ -1,5 +1,13 @@
 use super::canvas::{TOP_MARGIN, RIGHT_PADDING, BOTTOM_PADDING};
 use crate::conflict::Conflict;
+use leptos::{ReadSignal, WriteSignal, SignalGetUntracked, SignalSet, SignalWith};
+use petgraph::stable_graph::NodeIndex;
+use std::collections::HashSet;
+
+/// Hours after `BASE_DATE` midnight spanning the graph width at default zoom
+pub const DEFAULT_TIME_WINDOW: (f64, f64) = (0.0, 48.0);
+/// Narrowest time window, keeping the hour width finite
+const MIN_TIME_WINDOW_HOURS: f64 = 1.0;
 
 #[derive(Clone)]
 pub struct GraphDimensions {
@@ -8,6 +16,8 @@ pub struct GraphDimensions {
     pub graph_width: f64,
     pub graph_height: f64,
     pub hour_width: f64,
+    /// Hour shown at the left edge of the graph at default pan
+    pub start_hour: f64,
 }
 
 impl GraphDimensions {
@@ -15,16 +25,106 @@ impl GraphDimensions {
     pub fn new(canvas_width: f64, canvas_height: f64, station_label_width: f64) -> Self {
         let graph_width = canvas_width - station_label_width - RIGHT_PADDING;
         let graph_height = canvas_height - TOP_MARGIN - BOTTOM_PADDING;
-        let total_hours = 48.0; // Show 48 hours to support past-midnight
 
         Self {
             left_margin: station_label_width,
             top_margin: TOP_MARGIN,
             graph_width,
             graph_height,
-            hour_width: graph_width / total_hours,
+            hour_width: graph_width / (DEFAULT_TIME_WINDOW.1 - DEFAULT_TIME_WINDOW.0),
+            start_hour: DEFAULT_TIME_WINDOW.0,
         }
     }
+
+    /// Span the graph width from `start_hour` to `end_hour`; hours past 24 continue into the next day
+    #[must_use]
+    pub fn with_time_window(mut self, start_hour: f64, end_hour: f64) -> Self {
+        self.hour_width = self.graph_width / (end_hour - start_hour).max(MIN_TIME_WINDOW_HOURS);
+        self.start_hour = start_hour;
+        self
+    }
+
+    /// Screen X of a time (hours after `BASE_DATE` midnight) at default zoom and pan
+    #[must_use]
+    pub fn time_to_x(&self, hours: f64) -> f64 {
+        self.left_margin + (hours - self.start_hour) * self.hour_width
+    }
+
+    /// Inverse of `time_to_x`
+    #[must_use]
+    pub fn x_to_time(&self, x: f64) -> f64 {
+        self.start_hour + (x - self.left_margin) / self.hour_width
+    }
+
+    /// Pan offset that places `start_hour` at the left edge when the user pan is zero
+    /// Renderers position times from midnight, so the window start is folded into the pan
+    #[must_use]
+    pub fn window_pan_offset(&self, pan_offset_x: f64, horizontal_scale: f64) -> f64 {
+        pan_offset_x - self.start_hour * self.hour_width * horizontal_scale
+    }
+}
+
+/// Journey highlighted across the time graph and infrastructure views, shared via context
+#[derive(Clone, Copy)]
+pub struct HighlightedJourney {
+    pub journey_id: ReadSignal<Option<uuid::Uuid>>,
+    pub set_journey_id: WriteSignal<Option<uuid::Uuid>>,
+}
+
+/// Current time shown by the time graph cursor and the infrastructure map's train markers, shared via context
+#[derive(Clone, Copy)]
+pub struct VisualizationTime {
+    pub time: ReadSignal<chrono::NaiveDateTime>,
+    pub set_time: WriteSignal<chrono::NaiveDateTime>,
+}
+
+/// Reference timetable drawn faintly behind the time graph, shared via context
+#[derive(Clone, Copy)]
+pub struct ReferenceOverlay {
+    pub timetable: ReadSignal<Option<crate::train_journey::ReferenceTimetable>>,
+    pub visible: ReadSignal<bool>,
+    pub set_visible: WriteSignal<bool>,
+    /// Project to generate the reference journeys from, or `None` to remove the overlay
+    pub set_project: WriteSignal<Option<crate::models::Project>>,
+}
+
+/// View whose on-screen stations make up the focused set
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FocusSource {
+    Infrastructure,
+    TimeGraph,
+}
+
+/// Stations in view in the infrastructure map or the time graph, highlighted in the other view; shared via context
+#[derive(Clone, Copy)]
+pub struct FocusedStations {
+    pub focus: ReadSignal<Option<(FocusSource, HashSet<NodeIndex>)>>,
+    pub set_focus: WriteSignal<Option<(FocusSource, HashSet<NodeIndex>)>>,
+}
+
+impl FocusedStations {
+    /// Record the stations visible in `source`, or `None` when it shows everything.
+    /// Clearing only drops focus this view set, so the other view's focus survives
+    pub fn publish(&self, source: FocusSource, visible: Option<HashSet<NodeIndex>>) {
+        let current = self.focus.get_untracked();
+        let next = match visible {
+            Some(stations) => Some((source, stations)),
+            None if current.as_ref().is_some_and(|(owner, _)| *owner == source) => None,
+            None => return,
+        };
+        if current != next {
+            self.set_focus.set(next);
+        }
+    }
+
+    /// Stations to highlight in `view`: the ones in focus in the other view
+    #[must_use]
+    pub fn highlighted_in(&self, view: FocusSource) -> HashSet<NodeIndex> {
+        self.focus.with(|focus| match focus {
+            Some((source, stations)) if *source != view => stations.clone(),
+            _ => HashSet::new(),
+        })
+    }
 }
 
 #[derive(Clone)]
@@ -38,12 +138,17 @@ pub struct ViewportState {
 pub struct ConflictDisplayState<'a> {
     pub conflicts: &'a [Conflict],
     pub show_conflicts: bool,
+    /// Minimum separation to draw headway bands with, when they are enabled
+    pub headway_separation: Option<chrono::Duration>,
 }
 
 pub struct HoverState<'a> {
     pub hovered_conflict: Option<&'a Conflict>,
     pub show_line_blocks: bool,
     pub hovered_journey_id: Option<&'a uuid::Uuid>,
+    pub highlighted_journey_id: Option<&'a uuid::Uuid>,
+    /// Stations in view on the infrastructure map
+    pub focused_stations: &'a HashSet<NodeIndex>,
 }
 
 /// Convert a hex color to rgba with the specified opacity
@@ -63,4 +168,27 @@ pub fn hex_to_rgba(hex: &str, opacity: f64) -> String {
 
     // Fallback: return original color if parsing fails
     hex.to_owned()
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_time_to_x_with_overnight_window() {
+        let label_width = 100.0;
+        let dims = GraphDimensions::new(1000.0 + label_width + RIGHT_PADDING, 600.0, label_width)
+            .with_time_window(4.0, 28.0);
+
+        assert!((dims.hour_width - 1000.0 / 24.0).abs() < 1e-9);
+        assert!((dims.time_to_x(4.0) - label_width).abs() < 1e-9);
+        assert!((dims.time_to_x(28.0) - (label_width + 1000.0)).abs() < 1e-9);
+        // 02:00 the next day sits 22 hours into the window
+        assert!((dims.time_to_x(26.0) - (label_width + 22.0 * dims.hour_width)).abs() < 1e-9);
+        assert!((dims.x_to_time(dims.time_to_x(13.5)) - 13.5).abs() < 1e-9);
+
+        // Folding the window into the pan puts 04:00 at the left edge of the drawn graph
+        let pan = dims.window_pan_offset(0.0, 1.0);
+        assert!((label_width + 4.0 * dims.hour_width + pan - label_width).abs() < 1e-9);
+    }
+}
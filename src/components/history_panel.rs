@@ -0,0 +1,47 @@
+use leptos::{component, view, IntoView, ReadSignal, Callback, Callable, create_signal, use_context, SignalGet, SignalSet, SignalUpdate};
+use crate::models::HistoryEntry;
+
+/// Undo history shared with the sidebar via context
+#[derive(Clone, Copy)]
+pub struct UndoHistory {
+    pub entries: ReadSignal<Vec<HistoryEntry>>,
+    /// Called with the index of the history entry to jump to
+    pub on_restore: Callback<usize>,
+}
+
+#[component]
+#[must_use]
+pub fn HistoryPanel() -> impl IntoView {
+    let history = use_context::<UndoHistory>();
+    let (is_open, set_is_open) = create_signal(false);
+
+    history.map(|history| view! {
+        <div class="history-panel">
+            <button
+                class="import-button"
+                on:click=move |_| set_is_open.update(|open| *open = !*open)
+                prop:disabled=move || history.entries.get().len() < 2
+                title="Edit history"
+            >
+                <i class="fa-solid fa-list-ul"></i>
+            </button>
+            {move || is_open.get().then(|| view! {
+                <ol class="history-panel-items">
+                    {history.entries.get().into_iter().enumerate().rev().map(|(index, entry)| {
+                        view! {
+                            <li class:current=entry.is_current>
+                                <button on:click=move |_| {
+                                    history.on_restore.call(index);
+                                    set_is_open.set(false);
+                                }>
+                                    <span class="history-label">{entry.label}</span>
+                                    <span class="history-time">{entry.taken_at.format("%H:%M:%S").to_string()}</span>
+                                </button>
+                            </li>
+                        }
+                    }).collect::<Vec<_>>()}
+                </ol>
+            })}
+        </div>
+    })
+}
@@ -1,10 +1,11 @@
 use crate::import::jtraingraph::{parse_jtraingraph, import_jtraingraph};
+use crate::import::railml::{parse_railml_document, import_railml};
 use crate::models::{Line, RailwayGraph};
 use crate::components::button::Button;
 use crate::components::csv_column_mapper::CsvColumnMapper;
 use crate::components::window::Window;
-use crate::import::csv::{analyze_csv, parse_csv_with_mapping, parse_csv_with_existing_infrastructure, CsvImportConfig};
-use leptos::{component, view, WriteSignal, ReadSignal, IntoView, create_node_ref, create_signal, SignalGet, SignalGetUntracked, web_sys, spawn_local, SignalSet, Signal, SignalUpdate, Callback, Show};
+use crate::import::csv::{analyze_csv, detect_csv_dialect, parse_csv_with_mapping, parse_csv_with_existing_infrastructure, CsvImportConfig};
+use leptos::{component, view, WriteSignal, ReadSignal, IntoView, create_node_ref, create_signal, SignalGet, SignalGetUntracked, SignalWith, web_sys, spawn_local, SignalSet, Signal, SignalUpdate, Callback, Callable, Show, event_target_value};
 
 fn handle_fpl_import(
     text: &str,
@@ -49,6 +50,43 @@ fn handle_fpl_import(
     }
 }
 
+fn handle_railml_import(
+    text: &str,
+    set_graph: WriteSignal<RailwayGraph>,
+    set_lines: WriteSignal<Vec<Line>>,
+    lines: ReadSignal<Vec<Line>>,
+) {
+    let document = match parse_railml_document(text) {
+        Ok(document) => document,
+        Err(e) => {
+            leptos::logging::error!("{}", e);
+            return;
+        }
+    };
+
+    let before_lines_count = lines.get().len();
+    let existing_line_names: Vec<String> = lines.get().iter().map(|l| l.name.clone()).collect();
+
+    let mut new_lines = None;
+    set_graph.update(|graph| {
+        // Import into a copy so a failing document leaves the graph untouched
+        let mut updated_graph = graph.clone();
+        match import_railml(&document, &mut updated_graph, before_lines_count, &existing_line_names) {
+            Ok(lines_to_add) => {
+                *graph = updated_graph;
+                new_lines = Some(lines_to_add);
+            }
+            Err(e) => {
+                leptos::logging::error!("Failed to import railML: {}", e);
+            }
+        }
+    });
+
+    if let Some(lines_to_add) = new_lines {
+        set_lines.update(|lines| lines.extend(lines_to_add));
+    }
+}
+
 fn handle_csv_analysis(
     text: &str,
     filename: String,
@@ -76,6 +114,59 @@ fn handle_csv_analysis(
     }
 }
 
+/// Describes the delimiter and header row detected in pasted text
+fn describe_dialect(text: &str) -> String {
+    let (delimiter, has_headers) = detect_csv_dialect(text);
+    let delimiter = match delimiter {
+        '\t' => "Tab",
+        ';' => "Semicolon",
+        _ => "Comma",
+    };
+    let header = if has_headers { "with a header row" } else { "without a header row" };
+    format!("{delimiter}-separated, {header}")
+}
+
+/// Text area for timetables copied from a spreadsheet, sent on to the column mapper
+#[component]
+fn PasteTimetableDialog(
+    is_open: ReadSignal<bool>,
+    set_is_open: WriteSignal<bool>,
+    on_submit: Callback<String>,
+) -> impl IntoView {
+    let (text, set_text) = create_signal(String::new());
+
+    view! {
+        <Window
+            is_open=Signal::derive(move || is_open.get())
+            title=Signal::derive(|| "Paste Timetable".to_string())
+            on_close=move || set_is_open.set(false)
+            position_key="paste-timetable"
+        >
+            <div class="paste-timetable">
+                <textarea
+                    placeholder="Paste rows copied from a spreadsheet or CSV file"
+                    prop:value=move || text.get()
+                    on:input=move |ev| set_text.set(event_target_value(&ev))
+                />
+                <Show when=move || text.with(|text| !text.trim().is_empty())>
+                    <p class="detected-dialect">{move || text.with(|text| describe_dialect(text))}</p>
+                </Show>
+                <div class="import-dialog-buttons">
+                    <button on:click=move |_| set_is_open.set(false)>"Cancel"</button>
+                    <button
+                        class="primary"
+                        disabled=move || text.with(|text| text.trim().is_empty())
+                        on:click=move |_| {
+                            set_is_open.set(false);
+                            on_submit.call(text.get_untracked());
+                        }
+                    >"Map Columns"</button>
+                </div>
+            </div>
+        </Window>
+    }
+}
+
 #[component]
 #[must_use]
 pub fn Importer(
@@ -90,6 +181,12 @@ pub fn Importer(
     let (file_content, set_file_content) = create_signal(String::new());
     let (csv_config, set_csv_config) = create_signal(None::<CsvImportConfig>);
     let (import_error, set_import_error) = create_signal(None::<String>);
+    let (show_paste, set_show_paste) = create_signal(false);
+
+    let handle_paste = Callback::new(move |text: String| {
+        set_file_content.set(text.clone());
+        handle_csv_analysis(&text, String::new(), set_csv_config, set_show_mapper, set_import_error);
+    });
 
     let handle_file_change = move |_| {
         let Some(input_elem) = file_input_ref.get() else { return };
@@ -120,16 +217,22 @@ pub fn Importer(
             set_file_content.set(text.clone());
 
             // Check file type by extension
-            let is_fpl = std::path::Path::new(&filename)
+            let extension = std::path::Path::new(&filename)
                 .extension()
-                .is_some_and(|ext| ext.eq_ignore_ascii_case("fpl"));
-
-            leptos::logging::log!("File type: {}", if is_fpl { "FPL" } else { "CSV" });
+                .and_then(|ext| ext.to_str())
+                .map(str::to_ascii_lowercase);
+            let is_fpl = extension.as_deref() == Some("fpl");
+            let is_railml = matches!(extension.as_deref(), Some("railml" | "xml"));
 
             if is_fpl {
+                leptos::logging::log!("File type: FPL");
                 let handedness = settings.get_untracked().track_handedness;
                 handle_fpl_import(&text, set_graph, set_lines, lines, handedness);
+            } else if is_railml {
+                leptos::logging::log!("File type: railML");
+                handle_railml_import(&text, set_graph, set_lines, lines);
             } else {
+                leptos::logging::log!("File type: CSV");
                 handle_csv_analysis(&text, filename.clone(), set_csv_config, set_show_mapper, set_import_error);
             }
         });
@@ -188,7 +291,7 @@ pub fn Importer(
     view! {
         <input
             type="file"
-            accept=".csv,.fpl"
+            accept=".csv,.tsv,.txt,.fpl,.railml,.xml"
             node_ref=file_input_ref
             on:change=handle_file_change
             style="display: none;"
@@ -204,10 +307,18 @@ pub fn Importer(
                 });
             })
             shortcut_id="import_data"
-            title="Import CSV or JTrainGraph (.fpl)"
+            title="Import CSV, JTrainGraph (.fpl) or railML"
         >
             <i class="fa-solid fa-file-import"></i>
         </Button>
+        <Button
+            class="import-button"
+            on_click=Callback::new(move |_| set_show_paste.set(true))
+            title="Paste a timetable copied from a spreadsheet"
+        >
+            <i class="fa-solid fa-paste"></i>
+        </Button>
+        <PasteTimetableDialog is_open=show_paste set_is_open=set_show_paste on_submit=handle_paste/>
 
         <Show when=move || csv_config.get().is_some()>
             <Window
@@ -227,6 +338,7 @@ pub fn Importer(
                             group_line_names: HashMap::new(),
                             filename: None,
                             disable_infrastructure: false,
+                            delimiter: b',',
                         }
                     }))
                     on_cancel=Callback::new(move |()| {
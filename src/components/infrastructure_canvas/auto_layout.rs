@@ -1,10 +1,15 @@
 use crate::models::{RailwayGraph, Stations, Junctions, ProjectSettings};
 use crate::geometry::{angle_difference, line_segment_distance};
+use super::line_renderer;
 use petgraph::stable_graph::NodeIndex;
 use petgraph::visit::{EdgeRef, IntoEdgeReferences};
 use std::collections::HashSet;
 
-const GRID_SIZE: f64 = 30.0;
+const MIN_ANGLE_SNAP_DEGREES: f64 = 5.0;
+const MIN_STRAIGHTENED_NODE_DISTANCE: f64 = 10.0;
+const STRAIGHTEN_NUDGE_STEPS: u32 = 8;
+/// Fraction of the station spacing other nodes must stay clear of a placed node
+const NODE_COLLISION_SPACING_FACTOR: f64 = 0.9;
 
 // 8 compass directions (45° increments)
 const DIRECTIONS: [f64; 8] = [
@@ -19,10 +24,14 @@ const DIRECTIONS: [f64; 8] = [
 ];
 
 /// Snap coordinates to grid intersections
+/// A `grid_size` of zero (or less) disables snapping and returns the coordinates unchanged
 #[must_use]
-pub fn snap_to_grid(x: f64, y: f64) -> (f64, f64) {
-    let snapped_x = (x / GRID_SIZE).round() * GRID_SIZE;
-    let snapped_y = (y / GRID_SIZE).round() * GRID_SIZE;
+pub fn snap_to_grid(x: f64, y: f64, grid_size: f64) -> (f64, f64) {
+    if grid_size <= 0.0 {
+        return (x, y);
+    }
+    let snapped_x = (x / grid_size).round() * grid_size;
+    let snapped_y = (y / grid_size).round() * grid_size;
     (snapped_x, snapped_y)
 }
 
@@ -77,12 +86,12 @@ fn region_difference(set1: &HashSet<NodeIndex>, set2: &HashSet<NodeIndex>) -> f6
     1.0 - (intersection_size as f64 / union_size as f64)
 }
 
-/// Check if a position has node collision with existing nodes
+/// Check if a position is closer than `min_distance` to any existing node
 fn has_node_collision_at(
     graph: &RailwayGraph,
     test_pos: (f64, f64),
     exclude_node: NodeIndex,
-    base_station_spacing: f64,
+    min_distance: f64,
 ) -> bool {
     for node_idx in graph.graph.node_indices() {
         if node_idx == exclude_node {
@@ -101,7 +110,7 @@ fn has_node_collision_at(
             let dx = test_pos.0 - existing_pos.0;
             let dy = test_pos.1 - existing_pos.1;
             let dist = (dx * dx + dy * dy).sqrt();
-            if dist < base_station_spacing * 0.9 {
+            if dist < min_distance {
                 return true;
             }
         }
@@ -196,6 +205,7 @@ fn find_fallback_position(
     current_pos: (f64, f64),
     neighbor: NodeIndex,
     base_station_spacing: f64,
+    grid_size: f64,
     preferred_direction: f64,
     direction_offset: usize,
 ) -> Option<(f64, f64)> {
@@ -205,8 +215,9 @@ fn find_fallback_position(
         let test_pos = snap_to_grid(
             current_pos.0 + preferred_direction.cos() * base_station_spacing * spacing_mult,
             current_pos.1 + preferred_direction.sin() * base_station_spacing * spacing_mult,
+            grid_size,
         );
-        if !has_node_collision_at(graph, test_pos, neighbor, base_station_spacing) {
+        if !has_node_collision_at(graph, test_pos, neighbor, base_station_spacing * NODE_COLLISION_SPACING_FACTOR) {
             return Some(test_pos);
         }
 
@@ -216,8 +227,9 @@ fn find_fallback_position(
             let test_pos = snap_to_grid(
                 current_pos.0 + test_dir.cos() * base_station_spacing * spacing_mult,
                 current_pos.1 + test_dir.sin() * base_station_spacing * spacing_mult,
+                grid_size,
             );
-            if !has_node_collision_at(graph, test_pos, neighbor, base_station_spacing) {
+            if !has_node_collision_at(graph, test_pos, neighbor, base_station_spacing * NODE_COLLISION_SPACING_FACTOR) {
                 return Some(test_pos);
             }
         }
@@ -228,8 +240,9 @@ fn find_fallback_position(
             let test_pos = snap_to_grid(
                 current_pos.0 + dir.cos() * base_station_spacing * spacing_mult,
                 current_pos.1 + dir.sin() * base_station_spacing * spacing_mult,
+                grid_size,
             );
-            if !has_node_collision_at(graph, test_pos, neighbor, base_station_spacing) {
+            if !has_node_collision_at(graph, test_pos, neighbor, base_station_spacing * NODE_COLLISION_SPACING_FACTOR) {
                 return Some(test_pos);
             }
         }
@@ -248,6 +261,7 @@ fn find_best_direction_for_branch(
     already_used: &[(f64, HashSet<NodeIndex>)],
     incoming_direction: f64,
     base_station_spacing: f64,
+    grid_size: f64,
     is_through_path: bool,
 ) -> (f64, f64, i32) {
     let debug_this = graph.graph[neighbor].display_name() == "Upper Tyndrum";
@@ -268,9 +282,10 @@ fn find_best_direction_for_branch(
             let test_pos = snap_to_grid(
                 current_pos.0 + incoming_direction.cos() * base_station_spacing * spacing_mult,
                 current_pos.1 + incoming_direction.sin() * base_station_spacing * spacing_mult,
+                grid_size,
             );
 
-            if !has_node_collision_at(graph, test_pos, neighbor, base_station_spacing) {
+            if !has_node_collision_at(graph, test_pos, neighbor, base_station_spacing * NODE_COLLISION_SPACING_FACTOR) {
                 // Found a valid position continuing straight
                 return (incoming_direction, spacing_mult, 1000);
             }
@@ -301,9 +316,10 @@ fn find_best_direction_for_branch(
             let test_pos = snap_to_grid(
                 current_pos.0 + direction.cos() * base_station_spacing * spacing_mult,
                 current_pos.1 + direction.sin() * base_station_spacing * spacing_mult,
+                grid_size,
             );
 
-            if has_node_collision_at(graph, test_pos, neighbor, base_station_spacing) {
+            if has_node_collision_at(graph, test_pos, neighbor, base_station_spacing * NODE_COLLISION_SPACING_FACTOR) {
                 if debug_this {
                     leptos::logging::log!("    {:.0}°: COLLISION", direction.to_degrees());
                 }
@@ -334,6 +350,7 @@ fn find_best_direction_for_branch(
                 already_used,
                 incoming_direction,
                 base_station_spacing,
+                grid_size,
             );
 
             if debug_this {
@@ -376,6 +393,7 @@ fn score_direction_for_branch(
     already_used: &[(f64, HashSet<NodeIndex>)],
     incoming_direction: f64,
     base_station_spacing: f64,
+    grid_size: f64,
 ) -> i32 {
     let mut score = 0;
 
@@ -386,6 +404,7 @@ fn score_direction_for_branch(
     let neighbor_pos = snap_to_grid(
         current_pos.0 + direction.cos() * base_station_spacing * spacing_multiplier,
         current_pos.1 + direction.sin() * base_station_spacing * spacing_multiplier,
+        grid_size,
     );
 
     // CRITICAL: Check for geometric overlap with existing edges
@@ -473,7 +492,8 @@ fn score_direction_for_branch(
 
 #[allow(clippy::too_many_lines, clippy::missing_panics_doc, clippy::cast_precision_loss)]
 pub fn apply_layout(graph: &mut RailwayGraph, height: f64, settings: &ProjectSettings) {
-    let base_station_spacing = settings.default_node_distance_grid_squares * GRID_SIZE;
+    let base_station_spacing = settings.default_node_distance_grid_squares * settings.grid_square_size();
+    let grid_size = settings.grid_size;
     let start_x = 150.0;
     let start_y = height / 2.0;
 
@@ -520,6 +540,7 @@ pub fn apply_layout(graph: &mut RailwayGraph, height: f64, settings: &ProjectSet
             let pos = snap_to_grid(
                 start_x + spine_direction.cos() * offset,
                 start_y + spine_direction.sin() * offset,
+                grid_size,
             );
 
             // Place spine nodes without collision checking - spine is the primary structure
@@ -607,6 +628,7 @@ pub fn apply_layout(graph: &mut RailwayGraph, height: f64, settings: &ProjectSet
                 &global_branches,  // Use global branches, not local
                 incoming_direction,
                 base_station_spacing,
+                grid_size,
                 is_through_path,
             );
 
@@ -627,10 +649,11 @@ pub fn apply_layout(graph: &mut RailwayGraph, height: f64, settings: &ProjectSet
             let neighbor_pos = snap_to_grid(
                 current_pos.0 + best_direction.cos() * base_station_spacing * best_spacing,
                 current_pos.1 + best_direction.sin() * base_station_spacing * best_spacing,
+                grid_size,
             );
 
             // Verify the final position doesn't have collision before placing
-            let final_pos = if has_node_collision_at(graph, neighbor_pos, neighbor, base_station_spacing) || best_score == i32::MIN {
+            let final_pos = if has_node_collision_at(graph, neighbor_pos, neighbor, base_station_spacing * NODE_COLLISION_SPACING_FACTOR) || best_score == i32::MIN {
                 // All positions have collisions - try fallback positions
                 let fallback_dir = if best_score == i32::MIN {
                     // Cycle through directions to prevent horizontal lines
@@ -646,6 +669,7 @@ pub fn apply_layout(graph: &mut RailwayGraph, height: f64, settings: &ProjectSet
                     current_pos,
                     neighbor,
                     base_station_spacing,
+                    grid_size,
                     fallback_dir,
                     fallback_direction_index
                 );
@@ -681,6 +705,7 @@ pub fn apply_layout(graph: &mut RailwayGraph, height: f64, settings: &ProjectSet
                 let emergency_pos = snap_to_grid(
                     current_pos.0 + emergency_dir.cos() * base_station_spacing * 20.0,
                     current_pos.1 + emergency_dir.sin() * base_station_spacing * 20.0,
+                    grid_size,
                 );
                 graph.set_station_position(neighbor, emergency_pos);
                 visited.insert(neighbor);
@@ -721,6 +746,7 @@ pub fn apply_layout(graph: &mut RailwayGraph, height: f64, settings: &ProjectSet
                     let pos = snap_to_grid(
                         offset_x,
                         start_y + spine_direction.sin() * offset,
+                        grid_size,
                     );
 
                     // Place disconnected components without adjustment - they're offset far enough
@@ -733,6 +759,8 @@ pub fn apply_layout(graph: &mut RailwayGraph, height: f64, settings: &ProjectSet
             offset_x += 600.0; // Increased spacing between disconnected components
         }
     }
+
+    straighten_sections(graph);
 }
 
 /// Find if a node has any connections to already-placed nodes (excluding current)
@@ -754,18 +782,209 @@ fn find_placed_target(
     None
 }
 
-pub fn adjust_layout(_graph: &mut RailwayGraph) {
-    // TODO: Implement smart adjustment
+pub fn adjust_layout(graph: &mut RailwayGraph) {
+    straighten_sections(graph);
 }
 
-/// Snap station to grid when manually dragging (with branch reorientation)
-pub fn snap_to_angle(graph: &mut RailwayGraph, station_idx: NodeIndex, x: f64, y: f64) {
-    let snapped = snap_to_grid(x, y);
-    graph.set_station_position(station_idx, snapped);
+/// Whether a node is laid out by the chain it sits in rather than on its own:
+/// a junction or passing loop between exactly two neighbors
+fn is_chain_node(graph: &RailwayGraph, node: NodeIndex) -> bool {
+    let is_junction_or_loop = graph.graph.node_weight(node)
+        .is_some_and(|n| n.is_junction() || n.as_station().is_some_and(|s| s.passing_loop));
+    let neighbors: HashSet<NodeIndex> = graph.graph.neighbors_undirected(node).collect();
+    is_junction_or_loop && neighbors.len() == 2
+}
+
+/// Order a section's nodes from one endpoint to the other, or `None` if the section
+/// doesn't run between two distinct endpoints (a closed loop)
+fn section_node_chain(graph: &RailwayGraph, section: &line_renderer::Section, anchors: &HashSet<NodeIndex>) -> Option<Vec<NodeIndex>> {
+    let endpoints = || section.edges.iter()
+        .filter_map(|&edge| graph.graph.edge_endpoints(edge))
+        .flat_map(|(a, b)| [a, b])
+        .filter(|node| anchors.contains(node));
+    let start = endpoints().next()?;
+    let end = endpoints().find(|&node| node != start)?;
+
+    let mut chain = vec![start];
+    let mut used_edges = HashSet::new();
+    while let Some(&current) = chain.last() {
+        if current == end {
+            return Some(chain);
+        }
+        let (edge, next) = section.edges.iter()
+            .filter(|edge| !used_edges.contains(*edge))
+            .find_map(|&edge| match graph.graph.edge_endpoints(edge)? {
+                (a, b) if a == current => Some((edge, b)),
+                (a, b) if b == current => Some((edge, a)),
+                _ => None,
+            })?;
+        if chain.contains(&next) && next != end {
+            return None;
+        }
+        used_edges.insert(edge);
+        chain.push(next);
+    }
+    None
+}
+
+/// Point on the segment nearest `t`, nudged along it to keep clear of other nodes
+fn clear_point_along(graph: &RailwayGraph, node: NodeIndex, from: (f64, f64), to: (f64, f64), t: f64, step: f64) -> (f64, f64) {
+    let point_at = |t: f64| (from.0 + (to.0 - from.0) * t, from.1 + (to.1 - from.1) * t);
+    (0..=STRAIGHTEN_NUDGE_STEPS)
+        .flat_map(|k| [t + step * f64::from(k), t - step * f64::from(k)])
+        .filter(|candidate| *candidate > 0.0 && *candidate < 1.0)
+        .map(point_at)
+        .find(|&pos| !has_node_collision_at(graph, pos, node, MIN_STRAIGHTENED_NODE_DISTANCE))
+        .unwrap_or_else(|| point_at(t))
+}
+
+/// Place the junctions and passing loops of each section evenly on the straight line
+/// between the section's endpoints, so junction chains don't zigzag
+#[allow(clippy::cast_precision_loss)]
+pub fn straighten_sections(graph: &mut RailwayGraph) {
+    let anchors: HashSet<NodeIndex> = graph.graph.node_indices()
+        .filter(|&node| !is_chain_node(graph, node))
+        .collect();
+    let sections = line_renderer::identify_sections(graph, &anchors);
+
+    for section in &sections {
+        let Some(chain) = section_node_chain(graph, section, &anchors) else { continue };
+        let (Some(&start), Some(&end)) = (chain.first(), chain.last()) else { continue };
+        let positioned = |node| graph.get_station_position(node).filter(|&pos| pos != (0.0, 0.0));
+        let (Some(from), Some(to)) = (positioned(start), positioned(end)) else { continue };
+
+        let segments = (chain.len() - 1) as f64;
+        let nudge_step = 1.0 / (segments * f64::from(STRAIGHTEN_NUDGE_STEPS + 1));
+        for (i, &node) in chain.iter().enumerate().skip(1).take(chain.len().saturating_sub(2)) {
+            let pos = clear_point_along(graph, node, from, to, i as f64 / segments, nudge_step);
+            graph.set_station_position(node, pos);
+        }
+    }
+}
+
+/// Round an angle (radians) to the nearest multiple of `granularity_degrees`, normalized to (-π, π]
+#[must_use]
+pub fn snap_angle(angle: f64, granularity_degrees: f64) -> f64 {
+    let step = granularity_degrees.clamp(MIN_ANGLE_SNAP_DEGREES, 180.0).to_radians();
+    let snapped = (angle / step).round() * step;
+    snapped.sin().atan2(snapped.cos())
+}
+
+/// Neighbor a dragged station pivots around: the best connected one, towards the trunk
+fn branch_anchor(graph: &RailwayGraph, station_idx: NodeIndex) -> Option<(f64, f64)> {
+    graph.graph.neighbors_undirected(station_idx)
+        .filter(|&neighbor| neighbor != station_idx)
+        .max_by_key(|&neighbor| graph.graph.neighbors_undirected(neighbor).count())
+        .and_then(|neighbor| graph.get_station_position(neighbor))
+}
+
+/// Snap station when manually dragging (with branch reorientation)
+/// The station is placed at the nearest allowed angle from its anchor neighbor,
+/// at a whole number of grid squares away
+pub fn snap_to_angle(
+    graph: &mut RailwayGraph,
+    station_idx: NodeIndex,
+    (x, y): (f64, f64),
+    granularity_degrees: f64,
+    grid_size: f64,
+) {
+    let Some(anchor) = branch_anchor(graph, station_idx) else {
+        graph.set_station_position(station_idx, snap_to_grid(x, y, grid_size));
+        return;
+    };
+
+    let (dx, dy) = (x - anchor.0, y - anchor.1);
+    let distance = if grid_size > 0.0 {
+        (dx.hypot(dy) / grid_size).round().max(1.0) * grid_size
+    } else {
+        dx.hypot(dy)
+    };
+    let angle = snap_angle(dy.atan2(dx), granularity_degrees);
+    let (mut offset_x, mut offset_y) = (distance * angle.cos(), distance * angle.sin());
+
+    // Axis and diagonal angles can stay on grid intersections without skewing the angle
+    if granularity_degrees % 45.0 == 0.0 {
+        (offset_x, offset_y) = snap_to_grid(offset_x, offset_y, grid_size);
+    }
+    graph.set_station_position(station_idx, (anchor.0 + offset_x, anchor.1 + offset_y));
 }
 
 /// Snap station to grid when manually dragging (along branch)
-pub fn snap_station_along_branch(graph: &mut RailwayGraph, station_idx: NodeIndex, x: f64, y: f64) {
-    let snapped = snap_to_grid(x, y);
+pub fn snap_station_along_branch(graph: &mut RailwayGraph, station_idx: NodeIndex, x: f64, y: f64, grid_size: f64) {
+    let snapped = snap_to_grid(x, y, grid_size);
     graph.set_station_position(station_idx, snapped);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Junction, Track, TrackDirection, Tracks};
+
+    fn snapped_degrees(dx: f64, dy: f64, granularity_degrees: f64) -> f64 {
+        snap_angle(dy.atan2(dx), granularity_degrees).to_degrees()
+    }
+
+    #[test]
+    fn test_snap_angle_picks_nearest_allowed_angle() {
+        // A slightly-off diagonal drag
+        assert!((snapped_degrees(100.0, 90.0, 45.0) - 45.0).abs() < 1e-9);
+        assert!((snapped_degrees(100.0, 90.0, 15.0) - 45.0).abs() < 1e-9);
+        assert!((snapped_degrees(100.0, 90.0, 90.0) - 0.0).abs() < 1e-9);
+
+        // 30° upward drag only survives at granularities that include it
+        let (dx, dy) = (100.0, -100.0 * 30f64.to_radians().tan());
+        assert!((snapped_degrees(dx, dy, 15.0) + 30.0).abs() < 1e-9);
+        assert!((snapped_degrees(dx, dy, 30.0) + 30.0).abs() < 1e-9);
+        assert!((snapped_degrees(dx, dy, 45.0) + 45.0).abs() < 1e-9);
+
+        // Wraps across the ±180° seam
+        assert!((snapped_degrees(-100.0, 5.0, 45.0).abs() - 180.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_snap_to_grid_respects_grid_size() {
+        assert_eq!(snap_to_grid(44.0, 16.0, 30.0), (30.0, 30.0));
+        assert_eq!(snap_to_grid(44.0, 16.0, 10.0), (40.0, 20.0));
+        assert_eq!(snap_to_grid(44.0, 16.0, 50.0), (50.0, 0.0));
+        assert_eq!(snap_to_grid(-44.0, 16.0, 20.0), (-40.0, 20.0));
+
+        // Zero or negative disables snapping
+        assert_eq!(snap_to_grid(44.3, 16.7, 0.0), (44.3, 16.7));
+        assert_eq!(snap_to_grid(44.3, 16.7, -5.0), (44.3, 16.7));
+    }
+
+    #[test]
+    fn test_snap_to_angle_keeps_diagonal_on_grid() {
+        let mut graph = RailwayGraph::new();
+        let trunk = graph.add_or_get_station("Trunk".to_string());
+        let branch = graph.add_or_get_station("Branch".to_string());
+        graph.add_track(trunk, branch, vec![Track { direction: TrackDirection::Bidirectional }]);
+        graph.set_station_position(trunk, (0.0, 0.0));
+
+        snap_to_angle(&mut graph, branch, (95.0, 80.0), 45.0, 30.0);
+
+        let (x, y) = graph.get_station_position(branch).expect("position set");
+        assert!((x - 90.0).abs() < 1e-9 && (y - 90.0).abs() < 1e-9, "got ({x}, {y})");
+    }
+
+    #[test]
+    fn test_straighten_sections_puts_junction_on_section_line() {
+        let mut graph = RailwayGraph::new();
+        let station_a = graph.add_or_get_station("A".to_string());
+        let station_b = graph.add_or_get_station("B".to_string());
+        let junction = graph.add_junction(Junction { name: None, position: None, routing_rules: Vec::new(), label_position: None });
+        let tracks = || vec![Track { direction: TrackDirection::Bidirectional }];
+        graph.add_track(station_a, junction, tracks());
+        graph.add_track(junction, station_b, tracks());
+        graph.set_station_position(station_a, (30.0, 30.0));
+        graph.set_station_position(station_b, (330.0, 90.0));
+        graph.set_station_position(junction, (120.0, 250.0));
+
+        straighten_sections(&mut graph);
+
+        let (x, y) = graph.get_station_position(junction).expect("position set");
+        let distance = crate::geometry::point_to_line_segment_distance((x, y), (30.0, 30.0), (330.0, 90.0));
+        assert!(distance < 1e-6, "junction at ({x}, {y}) is {distance} off the A–B segment");
+        assert!((30.0..=330.0).contains(&x), "junction at ({x}, {y}) lies outside the segment");
+    }
+}
@@ -0,0 +1,154 @@
+use std::collections::HashSet;
+use wasm_bindgen::JsCast;
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
+use crate::components::canvas_viewport::fit_to_bounds;
+use crate::components::graph_canvas::types::ViewportState;
+use crate::models::{Line, RailwayGraph};
+use crate::theme::Theme;
+use super::{line_renderer, renderer};
+
+/// Pixel multipliers offered when exporting an image
+pub const EXPORT_SCALES: [f64; 3] = [2.0, 3.0, 4.0];
+const MIN_EXPORT_SCALE: f64 = 1.0;
+const MAX_EXPORT_SCALE: f64 = 4.0;
+
+/// Display options an exported image is drawn with, matching the on-screen view
+#[derive(Debug, Clone, Copy)]
+pub struct ExportOptions {
+    pub show_lines: bool,
+    pub hide_unscheduled_in_line_mode: bool,
+    pub theme: Theme,
+    pub line_gap_width: f64,
+    pub min_curve_radius: f64,
+    pub constant_line_width: bool,
+}
+
+/// Size and viewport of an exported image
+#[derive(Clone)]
+pub struct ExportLayout {
+    /// Output size in pixels
+    pub pixel_size: (u32, u32),
+    /// Size the network is laid out in before scaling up, so line widths and labels keep their proportions
+    pub logical_size: (f64, f64),
+    /// Output pixels per logical pixel
+    pub scale: f64,
+    /// Viewport fitting the whole network into `logical_size`
+    pub viewport: ViewportState,
+}
+
+/// Lay out an export of `pixel_size` pixels at `scale`, fitting the whole network regardless of the on-screen viewport
+#[must_use]
+pub fn export_layout(graph: &RailwayGraph, pixel_size: (u32, u32), scale: f64) -> ExportLayout {
+    let scale = scale.clamp(MIN_EXPORT_SCALE, MAX_EXPORT_SCALE);
+    let logical_size = (f64::from(pixel_size.0) / scale, f64::from(pixel_size.1) / scale);
+    ExportLayout {
+        pixel_size,
+        logical_size,
+        scale,
+        viewport: fit_to_bounds(graph, logical_size),
+    }
+}
+
+/// Render the network to an offscreen canvas and download it as a PNG
+///
+/// # Errors
+/// Returns an error if the canvas cannot be created or encoded, or the download cannot be started
+pub fn export_png(
+    graph: &RailwayGraph,
+    lines: &[Line],
+    options: ExportOptions,
+    layout: &ExportLayout,
+    filename: &str,
+) -> Result<(), String> {
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or("No document available")?;
+    let canvas = document
+        .create_element("canvas")
+        .map_err(|_| "Failed to create canvas element")?
+        .dyn_into::<HtmlCanvasElement>()
+        .map_err(|_| "Failed to cast to canvas element")?;
+    canvas.set_width(layout.pixel_size.0);
+    canvas.set_height(layout.pixel_size.1);
+
+    let ctx = canvas
+        .get_context("2d")
+        .ok()
+        .flatten()
+        .and_then(|ctx| ctx.dyn_into::<CanvasRenderingContext2d>().ok())
+        .ok_or("Failed to get canvas context")?;
+
+    // Draw at the logical size and let the context scale everything, widths and text included
+    let _ = ctx.scale(layout.scale, layout.scale);
+    let zoom = layout.viewport.zoom_level;
+    let mut cache = renderer::build_topology_cache(graph);
+    renderer::draw_infrastructure(
+        &ctx,
+        graph,
+        lines,
+        options.show_lines,
+        options.hide_unscheduled_in_line_mode,
+        layout.logical_size,
+        zoom,
+        layout.viewport.pan_offset_x,
+        layout.viewport.pan_offset_y,
+        &[],
+        &HashSet::new(),
+        &HashSet::new(),
+        &mut cache,
+        false,
+        None,
+        &[],
+        options.theme,
+        options.line_gap_width,
+        options.min_curve_radius,
+        &[],
+        None,
+        &HashSet::new(),
+        line_renderer::line_width_zoom(zoom, options.constant_line_width),
+    );
+
+    let url = canvas
+        .to_data_url_with_type("image/png")
+        .map_err(|_| "Failed to encode PNG")?;
+    let anchor = document
+        .create_element("a")
+        .map_err(|_| "Failed to create anchor element")?
+        .dyn_into::<web_sys::HtmlAnchorElement>()
+        .map_err(|_| "Failed to cast to anchor element")?;
+    anchor.set_href(&url);
+    anchor.set_download(filename);
+    anchor.click();
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Stations;
+
+    #[test]
+    fn test_export_layout_fits_network_at_scale() {
+        let mut graph = RailwayGraph::new();
+        let west = graph.add_or_get_station("West".to_string());
+        let east = graph.add_or_get_station("East".to_string());
+        graph.set_station_position(west, (0.0, 0.0));
+        graph.set_station_position(east, (1000.0, 400.0));
+
+        let layout = export_layout(&graph, (4000, 2000), 2.0);
+        assert_eq!(layout.pixel_size, (4000, 2000));
+        assert_eq!(layout.logical_size, (2000.0, 1000.0));
+
+        // Same fit as a 2000x1000 screen, so the image is that view at twice the resolution
+        let on_screen = fit_to_bounds(&graph, (2000.0, 1000.0));
+        assert!((layout.viewport.zoom_level - on_screen.zoom_level).abs() < 1e-9);
+        assert!((layout.viewport.pan_offset_y - on_screen.pan_offset_y).abs() < 1e-9);
+        let (left, right) = (layout.viewport.pan_offset_x, 1000.0 * layout.viewport.zoom_level + layout.viewport.pan_offset_x);
+        assert!((left - (2000.0 - right)).abs() < 1e-9);
+        assert!(left >= 0.0 && right <= 2000.0);
+
+        // Scales beyond what is offered are clamped
+        assert!((export_layout(&graph, (4000, 2000), 10.0).scale - MAX_EXPORT_SCALE).abs() < f64::EPSILON);
+    }
+}
@@ -158,3 +158,29 @@ pub fn find_label_at_position(graph: &RailwayGraph, x: f64, y: f64, zoom: f64) -
 
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::infrastructure_canvas::station_renderer::LabelPosition;
+
+    #[test]
+    fn test_find_label_follows_rotated_and_offset_label() {
+        let mut graph = RailwayGraph::new();
+        let idx = graph.add_or_get_station("Alpha".to_string());
+        graph.set_station_position(idx, (0.0, 0.0));
+        let station = graph.graph.node_weight_mut(idx).and_then(|node| node.as_station_mut()).expect("station");
+        station.label_position = Some(LabelPosition::Right);
+
+        // The label starts to the right of the station
+        assert_eq!(find_label_at_position(&graph, 30.0, 0.0, 1.0), Some(idx));
+        assert_eq!(find_label_at_position(&graph, 0.0, 130.0, 1.0), None);
+
+        // Turned to point down from the station, then shifted further down
+        let station = graph.graph.node_weight_mut(idx).and_then(|node| node.as_station_mut()).expect("station");
+        station.label_angle = 90.0;
+        station.label_offset = (0.0, 100.0);
+        assert_eq!(find_label_at_position(&graph, 0.0, 130.0, 1.0), Some(idx));
+        assert_eq!(find_label_at_position(&graph, 30.0, 0.0, 1.0), None);
+    }
+}
@@ -10,7 +10,8 @@ const LINE_BASE_WIDTH: f64 = 3.0;
 const AVOIDANCE_OFFSET_THRESHOLD: f64 = 0.1;
 const TRANSITION_LENGTH: f64 = 30.0;
 const JUNCTION_STOP_DISTANCE: f64 = 14.0;
-pub const MIN_CURVE_RADIUS: f64 = 20.0;
+/// Radii below this collapse curves into degenerate corners
+const CURVE_RADIUS_FLOOR: f64 = 2.0;
 
 // Line style constants
 const DOUBLE_GAP_MULTIPLIER: f64 = 2.0;
@@ -316,6 +317,19 @@ pub fn assign_visual_positions_with_reuse(
     result
 }
 
+/// Zoom that line widths are divided by: the view zoom keeps lines a fixed width
+/// on screen, while 1.0 draws them at a fixed world width that scales with the map
+#[must_use]
+pub fn line_width_zoom(zoom: f64, constant_line_width: bool) -> f64 {
+    if constant_line_width { zoom } else { 1.0 }
+}
+
+/// World-space stroke width of a line of the given thickness
+#[must_use]
+pub fn line_stroke_width(thickness: f64, zoom: f64) -> f64 {
+    (LINE_BASE_WIDTH + thickness) / zoom
+}
+
 /// Identify sections: groups of consecutive edges between junctions
 #[must_use]
 pub fn identify_sections(graph: &RailwayGraph, junctions: &HashSet<NodeIndex>) -> Vec<Section> {
@@ -496,12 +510,29 @@ pub fn get_lines_in_section_sorted<'a>(
     section_lines
 }
 
+/// Compare two lines by their manual section order overrides
+/// Lines with an override come before lines without one, ordered by override value
+fn compare_lines_by_override(line_a: &Line, line_b: &Line) -> std::cmp::Ordering {
+    match (line_a.section_order_override, line_b.section_order_override) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => std::cmp::Ordering::Equal,
+    }
+}
+
 /// Compare two lines based on their stopping behavior in shared segments
+/// Manual section order overrides take precedence over the stop-count heuristic
 /// Returns:
 /// - `Ordering::Less` if `line_a` stops less in shared segments (more express)
 /// - `Ordering::Greater` if `line_a` stops more in shared segments (more local)
 /// - `Ordering::Equal` if they have the same stopping ratio in shared segments
 fn compare_lines_by_shared_stops(line_a: &Line, line_b: &Line) -> std::cmp::Ordering {
+    let override_order = compare_lines_by_override(line_a, line_b);
+    if override_order.is_ne() {
+        return override_order;
+    }
+
     // Find all edge indices that both lines traverse
     let edges_a: HashSet<usize> = line_a.forward_route.iter()
         .map(|seg| seg.edge_index)
@@ -542,6 +573,7 @@ fn compare_lines_by_shared_stops(line_a: &Line, line_b: &Line) -> std::cmp::Orde
 
 /// Order lines within a section by comparing their stopping behavior in shared segments
 /// Returns ordered list of lines where:
+/// - Lines with a manual section order override come first, ordered by override
 /// - For each pair of lines, the one that stops LESS in their shared segments is positioned LEFT
 /// - Line ID used for tie-breaking when two lines have equal stopping behavior
 #[must_use]
@@ -577,6 +609,7 @@ fn draw_single_junction_connection(
     theme: Theme,
     highlighted_edges: &HashSet<EdgeIndex>,
     segment_cumulative_distances: &HashMap<(uuid::Uuid, usize), f64>,
+    min_curve_radius: f64,
 ) {
     let Some(junction_pos) = graph.get_station_position(connection_key.junction) else {
         return;
@@ -660,7 +693,7 @@ fn draw_single_junction_connection(
 
     // Calculate widths
     let entry_section_widths: Vec<f64> = entry_section_ordering.iter()
-        .map(|l| (LINE_BASE_WIDTH + l.thickness) / zoom)
+        .map(|l| line_stroke_width(l.thickness, zoom))
         .collect();
     let entry_num_gaps = entry_section_ordering.len().saturating_sub(1);
     let entry_actual_width: f64 = entry_section_widths.iter().sum::<f64>()
@@ -674,7 +707,7 @@ fn draw_single_junction_connection(
     };
 
     let exit_section_widths: Vec<f64> = exit_section_ordering.iter()
-        .map(|l| (LINE_BASE_WIDTH + l.thickness) / zoom)
+        .map(|l| line_stroke_width(l.thickness, zoom))
         .collect();
     let exit_num_gaps = exit_section_ordering.len().saturating_sub(1);
     let exit_actual_width: f64 = exit_section_widths.iter().sum::<f64>()
@@ -725,7 +758,7 @@ fn draw_single_junction_connection(
             .sum();
         let line_entry_width = entry_section_widths.get(entry_visual_pos)
             .copied()
-            .unwrap_or((LINE_BASE_WIDTH + line.thickness) / zoom);
+            .unwrap_or(line_stroke_width(line.thickness, zoom));
         let mut entry_offset = entry_start_offset + entry_offset_sum + line_entry_width / 2.0;
 
         let Some(&exit_visual_pos) = exit_visual_map.and_then(|map| map.get(&line.id)) else {
@@ -738,7 +771,7 @@ fn draw_single_junction_connection(
             .sum();
         let line_exit_width = exit_section_widths.get(exit_visual_pos)
             .copied()
-            .unwrap_or((LINE_BASE_WIDTH + line.thickness) / zoom);
+            .unwrap_or(line_stroke_width(line.thickness, zoom));
         let mut exit_offset = exit_start_offset + exit_offset_sum + line_exit_width / 2.0;
 
         if flip_exit_offsets {
@@ -758,7 +791,7 @@ fn draw_single_junction_connection(
         let min_stop_distance = calculate_min_stop_distance_for_radius(
             entry_dir,
             exit_dir,
-            MIN_CURVE_RADIUS,
+            min_curve_radius,
             avg_offset
         );
         let adjusted_stop_distance = base_adjusted.max(min_stop_distance);
@@ -983,7 +1016,7 @@ fn draw_junction_connections(
 
         // Calculate widths for entry section
         let entry_section_widths: Vec<f64> = entry_section_ordering.iter()
-            .map(|l| (LINE_BASE_WIDTH + l.thickness) / zoom)
+            .map(|l| line_stroke_width(l.thickness, zoom))
             .collect();
         let entry_num_gaps = entry_section_ordering.len().saturating_sub(1);
         let entry_actual_width: f64 = entry_section_widths.iter().sum::<f64>()
@@ -997,7 +1030,7 @@ fn draw_junction_connections(
 
         // Calculate widths for exit section
         let exit_section_widths: Vec<f64> = exit_section_ordering.iter()
-            .map(|l| (LINE_BASE_WIDTH + l.thickness) / zoom)
+            .map(|l| line_stroke_width(l.thickness, zoom))
             .collect();
         let exit_num_gaps = exit_section_ordering.len().saturating_sub(1);
         let exit_actual_width: f64 = exit_section_widths.iter().sum::<f64>()
@@ -1041,7 +1074,7 @@ fn draw_junction_connections(
                 .sum();
             let line_entry_width = entry_section_widths.get(entry_visual_pos)
                 .copied()
-                .unwrap_or((LINE_BASE_WIDTH + line.thickness) / zoom);
+                .unwrap_or(line_stroke_width(line.thickness, zoom));
             let mut entry_offset = entry_start_offset + entry_offset_sum + line_entry_width / 2.0;
 
             // Find line's visual position in exit section
@@ -1056,7 +1089,7 @@ fn draw_junction_connections(
                 .sum();
             let line_exit_width = exit_section_widths.get(exit_visual_pos)
                 .copied()
-                .unwrap_or((LINE_BASE_WIDTH + line.thickness) / zoom);
+                .unwrap_or(line_stroke_width(line.thickness, zoom));
             let mut exit_offset = exit_start_offset + exit_offset_sum + line_exit_width / 2.0;
 
             // Apply geometric flip if needed (perpendiculars point in opposite directions)
@@ -1180,6 +1213,16 @@ fn calculate_radial_control_point(
     ))
 }
 
+/// Keep a configured curve radius usable for rendering
+#[must_use]
+pub fn clamp_curve_radius(radius: f64) -> f64 {
+    if radius.is_finite() {
+        radius.max(CURVE_RADIUS_FLOOR)
+    } else {
+        CURVE_RADIUS_FLOOR
+    }
+}
+
 /// Calculate minimum stop distance required to achieve minimum curve radius
 /// Uses geometry of circular arc approximation for quadratic Bezier curves
 #[must_use]
@@ -1192,8 +1235,11 @@ pub fn calculate_min_stop_distance_for_radius(
     // Control distance used for S-curve (cubic bezier) control points
     const S_CURVE_CONTROL_DIST: f64 = 15.0;
 
+    let min_radius = clamp_curve_radius(min_radius);
+
     // Calculate angle between entry and exit directions
-    let cos_angle = entry_dir.0 * exit_dir.0 + entry_dir.1 * exit_dir.1;
+    // Clamp so rounding on unit vectors can't push acos out of its domain
+    let cos_angle = (entry_dir.0 * exit_dir.0 + entry_dir.1 * exit_dir.1).clamp(-1.0, 1.0);
     let angle = cos_angle.acos();
 
     // For very small angles (parallel/nearly straight - S-curves):
@@ -1257,7 +1303,7 @@ pub fn calculate_max_offset_for_station_curve(
         };
 
         let check_section_widths: Vec<f64> = check_section_ordering.iter()
-            .map(|l| (LINE_BASE_WIDTH + l.thickness) / zoom)
+            .map(|l| line_stroke_width(l.thickness, zoom))
             .collect();
         let check_num_gaps = check_section_ordering.len().saturating_sub(1);
         let check_actual_width: f64 = check_section_widths.iter().sum::<f64>()
@@ -1273,7 +1319,7 @@ pub fn calculate_max_offset_for_station_curve(
             .sum();
         let check_line_width = check_section_widths.get(check_visual_pos)
             .copied()
-            .unwrap_or((LINE_BASE_WIDTH + check_line.thickness) / zoom);
+            .unwrap_or(line_stroke_width(check_line.thickness, zoom));
         let check_perp_offset = check_start_offset + check_offset_sum + check_line_width / 2.0;
         max_offset = max_offset.max(check_perp_offset.abs());
     }
@@ -1498,6 +1544,7 @@ pub fn draw_lines(
     theme: Theme,
     highlighted_edges: &HashSet<EdgeIndex>,
     line_gap_width: f64,
+    min_curve_radius: f64,
 ) {
     let (left, top, right, bottom) = viewport_bounds;
     let margin = 200.0; // Buffer to include lines slightly outside viewport
@@ -1676,10 +1723,10 @@ pub fn draw_lines(
         };
 
         let entry_section_widths: Vec<f64> = entry_section_ordering.iter()
-            .map(|l| (LINE_BASE_WIDTH + l.thickness) / zoom)
+            .map(|l| line_stroke_width(l.thickness, zoom))
             .collect();
         let exit_section_widths: Vec<f64> = exit_section_ordering.iter()
-            .map(|l| (LINE_BASE_WIDTH + l.thickness) / zoom)
+            .map(|l| line_stroke_width(l.thickness, zoom))
             .collect();
 
         let entry_visual_map = section_visual_positions.get(&entry_section_id)
@@ -1725,7 +1772,7 @@ pub fn draw_lines(
                 .sum();
             let line_entry_width = entry_section_widths.get(entry_visual_pos)
                 .copied()
-                .unwrap_or((LINE_BASE_WIDTH + line.thickness) / zoom);
+                .unwrap_or(line_stroke_width(line.thickness, zoom));
             let entry_offset = entry_start_offset + entry_offset_sum + line_entry_width / 2.0;
 
             let exit_start_offset = -exit_total_width / 2.0;
@@ -1734,7 +1781,7 @@ pub fn draw_lines(
                 .sum();
             let line_exit_width = exit_section_widths.get(exit_visual_pos)
                 .copied()
-                .unwrap_or((LINE_BASE_WIDTH + line.thickness) / zoom);
+                .unwrap_or(line_stroke_width(line.thickness, zoom));
             let exit_offset = exit_start_offset + exit_offset_sum + line_exit_width / 2.0;
 
             let avg_offset = (entry_offset.abs() + exit_offset.abs()) / 2.0;
@@ -1749,7 +1796,7 @@ pub fn draw_lines(
             let min_stop_distance = calculate_min_stop_distance_for_radius(
                 entry_dir,
                 exit_dir,
-                MIN_CURVE_RADIUS,
+                min_curve_radius,
                 avg_offset
             );
 
@@ -1984,7 +2031,7 @@ pub fn draw_lines(
             };
 
             let section_line_widths: Vec<f64> = section_ordering.iter()
-                .map(|l| (LINE_BASE_WIDTH + l.thickness) / zoom)
+                .map(|l| line_stroke_width(l.thickness, zoom))
                 .collect();
 
             let num_gaps = section_ordering.len().saturating_sub(1);
@@ -2002,7 +2049,7 @@ pub fn draw_lines(
                 .sum();
             let line_world_width = section_line_widths.get(visual_pos)
                 .copied()
-                .unwrap_or((LINE_BASE_WIDTH + line.thickness) / zoom);
+                .unwrap_or(line_stroke_width(line.thickness, zoom));
             let perp_offset = start_offset + offset_sum + line_world_width / 2.0;
 
             // Calculate curve stop distance (once per unique station curve)
@@ -2025,7 +2072,7 @@ pub fn draw_lines(
                 let min_stop_distance = calculate_min_stop_distance_for_radius(
                     entry_dir,
                     exit_dir,
-                    MIN_CURVE_RADIUS,
+                    min_curve_radius,
                     max_offset
                 );
                 base_curve_stop.max(min_stop_distance)
@@ -2165,7 +2212,7 @@ pub fn draw_lines(
 
             // Calculate widths for all lines in section ordering (to maintain proper spacing)
             let section_line_widths: Vec<f64> = section_ordering.iter()
-                .map(|l| (LINE_BASE_WIDTH + l.thickness) / zoom)
+                .map(|l| line_stroke_width(l.thickness, zoom))
                 .collect();
 
             let num_gaps = section_ordering.len().saturating_sub(1);
@@ -2185,7 +2232,7 @@ pub fn draw_lines(
                 .sum();
             let line_world_width = section_line_widths.get(visual_pos)
                 .copied()
-                .unwrap_or((LINE_BASE_WIDTH + line.thickness) / zoom);
+                .unwrap_or(line_stroke_width(line.thickness, zoom));
             let offset = start_offset + offset_sum + line_world_width / 2.0;
 
             let ox = nx * offset;
@@ -2313,7 +2360,7 @@ pub fn draw_lines(
             // Multiple lines - position them using visual positions
             // Calculate widths for all lines in section ordering (to maintain proper spacing with gaps)
             let section_line_widths: Vec<f64> = section_ordering.iter()
-                .map(|l| (LINE_BASE_WIDTH + l.thickness) / zoom)
+                .map(|l| line_stroke_width(l.thickness, zoom))
                 .collect();
 
             let num_gaps = section_ordering.len().saturating_sub(1);
@@ -2334,7 +2381,7 @@ pub fn draw_lines(
                     .sum();
                 let line_world_width = section_line_widths.get(*visual_pos)
                     .copied()
-                    .unwrap_or((LINE_BASE_WIDTH + line.thickness) / zoom);
+                    .unwrap_or(line_stroke_width(line.thickness, zoom));
                 let offset = start_offset + offset_sum + line_world_width / 2.0;
 
                 let ox = nx * offset;
@@ -2478,6 +2525,7 @@ pub fn draw_lines(
                     theme,
                     highlighted_edges,
                     &segment_cumulative_distances,
+                    min_curve_radius,
                 );
                 drawn_junctions.insert(*connection_key);
             }
@@ -2486,3 +2534,87 @@ pub fn draw_lines(
 
     // All junction connections and station curves drawn above to maintain z-order
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::RouteSegment;
+    use chrono::Duration;
+
+    fn line_with_stops(name: &str, waits: &[i64]) -> Line {
+        let mut line = Line::create_from_ids(&[name.to_string()], 0).remove(0);
+        line.forward_route = waits.iter().enumerate()
+            .map(|(edge_index, &wait)| RouteSegment {
+                edge_index,
+                track_index: 0,
+                origin_platform: 0,
+                destination_platform: 0,
+                duration: Some(Duration::minutes(3)),
+                wait_time: Duration::seconds(wait),
+                reverses: false,
+            })
+            .collect();
+        line
+    }
+
+    #[test]
+    fn test_section_order_override_beats_stop_count() {
+        let mut local = line_with_stops("Local", &[30, 30, 30]);
+        let mut express = line_with_stops("Express", &[0, 0, 30]);
+        let edges = [EdgeIndex::new(0)];
+
+        // Without overrides the express line stops less and goes left
+        let ordered = order_lines_for_section(&[&local, &express], &edges);
+        assert_eq!(ordered[0].name, "Express");
+
+        local.section_order_override = Some(1);
+        express.section_order_override = Some(2);
+        let ordered = order_lines_for_section(&[&express, &local], &edges);
+        assert_eq!(ordered[0].name, "Local");
+        assert_eq!(ordered[1].name, "Express");
+    }
+
+    #[test]
+    fn test_min_stop_distance_grows_with_radius() {
+        let entry = (1.0, 0.0);
+        let exit = (0.0, 1.0);
+
+        let distances: Vec<f64> = [5.0, 20.0, 60.0].iter()
+            .map(|&radius| calculate_min_stop_distance_for_radius(entry, exit, radius, 0.0))
+            .collect();
+
+        assert!(distances.windows(2).all(|pair| pair[0] < pair[1]), "{distances:?}");
+        // Right angle: d = r * tan(45°) = r, separation r / (2 sin 45°) is smaller
+        assert!((distances[1] - 20.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_min_stop_distance_handles_degenerate_radii() {
+        let entry = (1.0, 0.0);
+        let exit = (0.0, 1.0);
+        let floor = calculate_min_stop_distance_for_radius(entry, exit, CURVE_RADIUS_FLOOR, 0.0);
+
+        for radius in [0.0, -10.0, f64::NAN, f64::INFINITY] {
+            let distance = calculate_min_stop_distance_for_radius(entry, exit, radius, 0.0);
+            assert!((distance - floor).abs() < 1e-9, "radius {radius} gave {distance}");
+        }
+
+        // Nearly parallel directions fall back to the S-curve distance
+        let straight = calculate_min_stop_distance_for_radius(entry, (1.0, 1e-9), 0.0, 4.0);
+        assert!((straight - 17.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_line_stroke_width_by_zoom_mode() {
+        let thickness = 2.0;
+        let on_screen = |zoom: f64, constant: bool| line_stroke_width(thickness, line_width_zoom(zoom, constant)) * zoom;
+
+        // Constant mode keeps the on-screen width fixed
+        assert!((on_screen(0.5, true) - 5.0).abs() < 1e-9);
+        assert!((on_screen(2.0, true) - 5.0).abs() < 1e-9);
+
+        // Otherwise the width scales with the map
+        assert!((on_screen(0.5, false) - 2.5).abs() < 1e-9);
+        assert!((on_screen(2.0, false) - 10.0).abs() < 1e-9);
+    }
+}
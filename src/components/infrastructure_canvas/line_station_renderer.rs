@@ -10,10 +10,8 @@ use web_sys::CanvasRenderingContext2d;
 use super::line_renderer::{
     assign_visual_positions_with_reuse, get_lines_in_section, identify_sections,
     order_lines_for_section, calculate_min_stop_distance_for_radius,
-    calculate_max_offset_for_station_curve, MIN_CURVE_RADIUS,
+    calculate_max_offset_for_station_curve, line_stroke_width,
 };
-
-const LINE_BASE_WIDTH: f64 = 3.0;
 const TICK_LENGTH: f64 = 4.5;
 const TICK_WIDTH: f64 = 5.0;
 const PILL_HEIGHT: f64 = 16.0;
@@ -1074,7 +1072,7 @@ fn calculate_line_offset_at_station(
     let gap_width = line_gap_width / zoom;
     let section_line_widths: Vec<f64> = section_ordering
         .iter()
-        .map(|l| (LINE_BASE_WIDTH + l.thickness) / zoom)
+        .map(|l| line_stroke_width(l.thickness, zoom))
         .collect();
 
     let num_gaps = section_ordering.len().saturating_sub(1);
@@ -1096,7 +1094,7 @@ fn calculate_line_offset_at_station(
     let line_width = section_line_widths
         .get(*visual_pos)
         .copied()
-        .unwrap_or((LINE_BASE_WIDTH + line.thickness) / zoom);
+        .unwrap_or(line_stroke_width(line.thickness, zoom));
     let offset = start_offset + offset_sum + line_width / 2.0;
 
     // Apply perpendicular offset
@@ -1115,6 +1113,7 @@ pub fn draw_line_stations(
     selected_stations: &[NodeIndex],
     theme: Theme,
     line_gap_width: f64,
+    min_curve_radius: f64,
 ) {
     let palette = get_palette(theme);
     let (left, top, right, bottom) = viewport_bounds;
@@ -1284,7 +1283,7 @@ pub fn draw_line_stations(
                 let min_stop_distance = calculate_min_stop_distance_for_radius(
                     entry_dir,
                     exit_dir,
-                    MIN_CURVE_RADIUS,
+                    min_curve_radius,
                     max_offset
                 );
                 base_curve_stop.max(min_stop_distance)
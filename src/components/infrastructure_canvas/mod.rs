@@ -6,3 +6,5 @@ pub mod line_station_renderer;
 pub mod junction_renderer;
 pub mod renderer;
 pub mod hit_detection;
+pub mod train_position_renderer;
+pub mod export;
@@ -1,6 +1,6 @@
-use crate::models::{Line, RailwayGraph, Junctions};
+use crate::models::{Line, RailwayGraph, Junctions, Stations};
 use crate::theme::Theme;
-use super::{track_renderer, station_renderer, line_renderer, line_station_renderer, junction_renderer};
+use super::{track_renderer, station_renderer, line_renderer, line_station_renderer, junction_renderer, train_position_renderer};
 use web_sys::CanvasRenderingContext2d;
 use petgraph::stable_graph::{NodeIndex, EdgeIndex};
 use petgraph::visit::{EdgeRef, IntoEdgeReferences};
@@ -33,10 +33,12 @@ const EMPTY_MESSAGE_FONT: &str = "16px sans-serif";
 const EMPTY_MESSAGE_TEXT: &str = "No stations in network";
 const EMPTY_MESSAGE_OFFSET_X: f64 = 80.0;
 
-const GRID_SIZE: f64 = 30.0;
 const GRID_LINE_WIDTH: f64 = 0.5;
+const MIN_GRID_SCREEN_SPACING: f64 = 4.0;
 
 const SELECTION_BOX_LINE_WIDTH: f64 = 1.5;
+const FOCUS_HALO_RADIUS: f64 = 16.0;
+const CRITICAL_SECTION_WIDTH: f64 = 14.0;
 const SELECTION_BOX_DASH_LENGTH: f64 = 5.0;
 
 struct Palette {
@@ -47,6 +49,8 @@ struct Palette {
     selection_box_fill: &'static str,
     preview_stroke: &'static str,
     preview_fill: &'static str,
+    focus_halo: &'static str,
+    critical_section: &'static str,
 }
 
 const DARK_PALETTE: Palette = Palette {
@@ -57,6 +61,8 @@ const DARK_PALETTE: Palette = Palette {
     selection_box_fill: "rgba(74, 158, 255, 0.1)",
     preview_stroke: "#4a9eff",
     preview_fill: "#2a2a2a",
+    focus_halo: "rgba(74, 158, 255, 0.12)",
+    critical_section: "rgba(255, 112, 67, 0.35)",
 };
 
 const LIGHT_PALETTE: Palette = Palette {
@@ -67,6 +73,8 @@ const LIGHT_PALETTE: Palette = Palette {
     selection_box_fill: "rgba(25, 118, 210, 0.08)",
     preview_stroke: "#1976d2",
     preview_fill: "#f0f0f0",
+    focus_halo: "rgba(25, 118, 210, 0.1)",
+    critical_section: "rgba(230, 81, 0, 0.3)",
 };
 
 fn get_palette(theme: Theme) -> &'static Palette {
@@ -148,15 +156,20 @@ pub fn build_topology_cache(graph: &RailwayGraph) -> TopologyCache {
 }
 
 /// Draw a subtle grid pattern to show snap points
+/// Grids denser than `MIN_GRID_SCREEN_SPACING` pixels on screen are skipped
 fn draw_grid(
     ctx: &CanvasRenderingContext2d,
-    width: f64,
-    height: f64,
+    (width, height): (f64, f64),
+    grid_size: f64,
     zoom: f64,
     pan_x: f64,
     pan_y: f64,
     palette: &Palette,
 ) {
+    if grid_size * zoom < MIN_GRID_SCREEN_SPACING {
+        return;
+    }
+
     ctx.save();
 
     ctx.set_stroke_style_str(palette.grid);
@@ -169,8 +182,8 @@ fn draw_grid(
     let bottom = (height - pan_y) / zoom;
 
     // Round to nearest grid line
-    let start_x = (left / GRID_SIZE).floor() * GRID_SIZE;
-    let start_y = (top / GRID_SIZE).floor() * GRID_SIZE;
+    let start_x = (left / grid_size).floor() * grid_size;
+    let start_y = (top / grid_size).floor() * grid_size;
 
     // Apply transformations
     let _ = ctx.translate(pan_x, pan_y);
@@ -183,7 +196,7 @@ fn draw_grid(
     while x <= right {
         ctx.move_to(x, top);
         ctx.line_to(x, bottom);
-        x += GRID_SIZE;
+        x += grid_size;
     }
 
     // Draw horizontal lines
@@ -191,13 +204,63 @@ fn draw_grid(
     while y <= bottom {
         ctx.move_to(left, y);
         ctx.line_to(right, y);
-        y += GRID_SIZE;
+        y += grid_size;
     }
 
     ctx.stroke();
     ctx.restore();
 }
 
+/// Soft circles behind stations that are in view on the time graph
+fn draw_focus_halos(
+    ctx: &CanvasRenderingContext2d,
+    graph: &RailwayGraph,
+    focused_stations: &HashSet<NodeIndex>,
+    palette: &Palette,
+) {
+    if focused_stations.is_empty() {
+        return;
+    }
+
+    ctx.save();
+    ctx.set_fill_style_str(palette.focus_halo);
+    ctx.begin_path();
+    for &idx in focused_stations {
+        let Some((x, y)) = graph.get_station_position(idx) else { continue };
+        ctx.move_to(x + FOCUS_HALO_RADIUS, y);
+        let _ = ctx.arc(x, y, FOCUS_HALO_RADIUS, 0.0, 2.0 * std::f64::consts::PI);
+    }
+    ctx.fill();
+    ctx.restore();
+}
+
+/// Wide warm bands behind the busiest single-track sections
+fn draw_critical_sections(
+    ctx: &CanvasRenderingContext2d,
+    cache: &TopologyCache,
+    critical_sections: &HashSet<EdgeIndex>,
+    palette: &Palette,
+) {
+    if critical_sections.is_empty() {
+        return;
+    }
+
+    ctx.save();
+    ctx.set_stroke_style_str(palette.critical_section);
+    ctx.set_line_width(CRITICAL_SECTION_WIDTH);
+    ctx.set_line_cap("round");
+    ctx.begin_path();
+    for edge in critical_sections {
+        let Some(segments) = cache.edge_segments.get(edge) else { continue };
+        for &((x1, y1), (x2, y2)) in segments {
+            ctx.move_to(x1, y1);
+            ctx.line_to(x2, y2);
+        }
+    }
+    ctx.stroke();
+    ctx.restore();
+}
+
 /// Get the set of stations and edges that have scheduled lines going through them
 fn get_scheduled_elements(
     graph: &RailwayGraph,
@@ -235,12 +298,18 @@ pub fn draw_infrastructure(
     pan_y: f64,
     selected_stations: &[NodeIndex],
     highlighted_edges: &HashSet<EdgeIndex>,
+    critical_sections: &HashSet<EdgeIndex>,
     cache: &mut TopologyCache,
     is_zooming: bool,
     preview_station_position: Option<(f64, f64)>,
-    selection_box: Option<((f64, f64), (f64, f64))>,
+    selection_outline: &[(f64, f64)],
     theme: Theme,
     line_gap_width: f64,
+    min_curve_radius: f64,
+    train_markers: &[train_position_renderer::TrainMarker],
+    grid_spacing: Option<f64>,
+    focused_stations: &HashSet<NodeIndex>,
+    line_zoom: f64,
 ) {
     let palette = get_palette(theme);
 
@@ -249,7 +318,9 @@ pub fn draw_infrastructure(
     ctx.fill_rect(0.0, 0.0, width, height);
 
     // Draw grid
-    draw_grid(ctx, width, height, zoom, pan_x, pan_y, palette);
+    if let Some(grid_size) = grid_spacing {
+        draw_grid(ctx, (width, height), grid_size, zoom, pan_x, pan_y, palette);
+    }
 
     if graph.graph.node_count() == 0 {
         // Show message if no stations
@@ -260,11 +331,7 @@ pub fn draw_infrastructure(
     }
 
     // Calculate visible world bounds for viewport culling
-    let viewport_left = -pan_x / zoom;
-    let viewport_top = -pan_y / zoom;
-    let viewport_right = (width - pan_x) / zoom;
-    let viewport_bottom = (height - pan_y) / zoom;
-    let viewport_bounds = (viewport_left, viewport_top, viewport_right, viewport_bottom);
+    let viewport_bounds = crate::components::canvas_viewport::visible_world_bounds(zoom, (pan_x, pan_y), (width, height));
 
     // Save context and apply transformations
     ctx.save();
@@ -278,30 +345,37 @@ pub fn draw_infrastructure(
         (HashSet::new(), HashSet::new())
     };
 
+    draw_critical_sections(ctx, cache, critical_sections, palette);
+
     // Draw tracks or lines based on toggle (behind nodes)
     if show_lines {
         if !hide_unscheduled_in_line_mode {
             // Mixed mode: draw unscheduled tracks (infrastructure style) and scheduled lines (line style)
             track_renderer::draw_tracks_filtered(ctx, graph, zoom, highlighted_edges, &cache.avoidance_offsets, viewport_bounds, &cache.junctions, theme, &cache.orphaned_tracks, &cache.crossover_intersections, &scheduled_edges);
         }
-        // Draw lines (use zoom=1.0 for constant size scaling)
-        line_renderer::draw_lines(ctx, graph, lines, 1.0, &cache.avoidance_offsets, viewport_bounds, &cache.junctions, theme, highlighted_edges, line_gap_width);
-        // Draw custom station markers for line mode (use zoom=1.0 for constant size scaling)
-        line_station_renderer::draw_line_stations(ctx, graph, lines, 1.0, viewport_bounds, &cache.label_cache, selected_stations, theme, line_gap_width);
+        // Line widths scale with the map unless they are kept constant on screen
+        line_renderer::draw_lines(ctx, graph, lines, line_zoom, &cache.avoidance_offsets, viewport_bounds, &cache.junctions, theme, highlighted_edges, line_gap_width, min_curve_radius);
+        // Draw custom station markers for line mode, sized to match the lines
+        line_station_renderer::draw_line_stations(ctx, graph, lines, line_zoom, viewport_bounds, &cache.label_cache, selected_stations, theme, line_gap_width, min_curve_radius);
     } else {
         // Infrastructure mode: draw all tracks
         track_renderer::draw_tracks(ctx, graph, zoom, highlighted_edges, &cache.avoidance_offsets, viewport_bounds, &cache.junctions, theme, &cache.orphaned_tracks, &cache.crossover_intersections);
     }
 
+    draw_focus_halos(ctx, graph, focused_stations, palette);
+
     // Draw stations and junctions on top (with label cache)
-    // Use zoom=1.0 in line mode for constant size labels
+    // Use the line width zoom in line mode so markers match the lines
     // Pass scheduled stations in mixed mode so unscheduled stations get infrastructure rendering
     let scheduled_stations_ref = if show_lines && !hide_unscheduled_in_line_mode {
         Some(&scheduled_stations)
     } else {
         None
     };
-    station_renderer::draw_stations_with_cache(ctx, graph, lines, if show_lines { 1.0 } else { zoom }, selected_stations, highlighted_edges, cache, is_zooming, viewport_bounds, show_lines, hide_unscheduled_in_line_mode, scheduled_stations_ref, theme, line_gap_width);
+    station_renderer::draw_stations_with_cache(ctx, graph, lines, if show_lines { line_zoom } else { zoom }, selected_stations, highlighted_edges, cache, is_zooming, viewport_bounds, show_lines, hide_unscheduled_in_line_mode, scheduled_stations_ref, theme, line_gap_width);
+
+    // Draw trains in service at the current time above the network
+    train_position_renderer::draw_train_markers(ctx, train_markers, zoom, theme);
 
     // Draw preview station if position is set
     if let Some((x, y)) = preview_station_position {
@@ -321,15 +395,8 @@ pub fn draw_infrastructure(
         ctx.restore();
     }
 
-    // Draw selection box if dragging
-    if let Some((start, end)) = selection_box {
-        let min_x = start.0.min(end.0);
-        let max_x = start.0.max(end.0);
-        let min_y = start.1.min(end.1);
-        let max_y = start.1.max(end.1);
-        let width_box = max_x - min_x;
-        let height_box = max_y - min_y;
-
+    // Draw selection box or lasso if dragging
+    if let Some((first, rest)) = selection_outline.split_first() {
         ctx.set_stroke_style_str(palette.selection_box_stroke);
         ctx.set_fill_style_str(palette.selection_box_fill);
         ctx.set_line_width(SELECTION_BOX_LINE_WIDTH / zoom);
@@ -338,8 +405,15 @@ pub fn draw_infrastructure(
             &wasm_bindgen::JsValue::from(SELECTION_BOX_DASH_LENGTH / zoom)
         );
         let _ = ctx.set_line_dash(&dash_array);
-        ctx.stroke_rect(min_x, min_y, width_box, height_box);
-        ctx.fill_rect(min_x, min_y, width_box, height_box);
+        ctx.begin_path();
+        ctx.move_to(first.0, first.1);
+        for point in rest {
+            ctx.line_to(point.0, point.1);
+        }
+        ctx.close_path();
+        ctx.stroke();
+        // Even-odd fill matches how self-intersecting lassos select stations
+        ctx.fill_with_canvas_winding_rule(web_sys::CanvasWindingRule::Evenodd);
         // Reset line dash
         let _ = ctx.set_line_dash(&js_sys::Array::new());
     }
@@ -23,6 +23,7 @@ const MAX_LABEL_FONT_SIZE: f64 = 112.0;
 struct Palette {
     station: &'static str,
     passing_loop: &'static str,
+    depot: &'static str,
     node_fill: &'static str,
     label: &'static str,
     selection_ring: &'static str,
@@ -31,6 +32,7 @@ struct Palette {
 const DARK_PALETTE: Palette = Palette {
     station: "#4a9eff",
     passing_loop: "#888",
+    depot: "#b07cd8",
     node_fill: "#2a2a2a",
     label: "#fff",
     selection_ring: "#ffaa00",
@@ -39,6 +41,7 @@ const DARK_PALETTE: Palette = Palette {
 const LIGHT_PALETTE: Palette = Palette {
     station: "#1976d2",
     passing_loop: "#666",
+    depot: "#7b3fa8",
     node_fill: "#f0f0f0",
     label: "#1a1a1a",
     selection_ring: "#ff8800",
@@ -253,6 +256,8 @@ fn draw_station_nodes(
         if let Some(station) = node.as_station() {
             let (border_color, radius) = if station.passing_loop {
                 (palette.passing_loop, NODE_RADIUS * 0.3)
+            } else if station.is_depot {
+                (palette.depot, NODE_RADIUS)
             } else {
                 (palette.station, NODE_RADIUS)
             };
@@ -267,7 +272,12 @@ fn draw_station_nodes(
                 ctx.set_stroke_style_str(border_color);
                 ctx.set_line_width(2.0 / zoom);
                 ctx.begin_path();
-                let _ = ctx.arc(pos.0, pos.1, radius, 0.0, std::f64::consts::PI * 2.0);
+                if station.is_depot {
+                    // Depots are drawn as squares to set them apart from stations
+                    ctx.rect(pos.0 - radius, pos.1 - radius, radius * 2.0, radius * 2.0);
+                } else {
+                    let _ = ctx.arc(pos.0, pos.1, radius, 0.0, std::f64::consts::PI * 2.0);
+                }
                 ctx.fill();
                 ctx.stroke();
 
@@ -346,6 +356,46 @@ fn calculate_label_bounds(
     }
 }
 
+/// A station's custom label rotation in radians and offset, when either is set
+fn custom_label_placement(node: &crate::models::Node) -> Option<(f64, (f64, f64))> {
+    let station = node.as_station()?;
+    (station.label_angle != 0.0 || station.label_offset != (0.0, 0.0))
+        .then(|| (station.label_angle.to_radians(), station.label_offset))
+}
+
+/// Bounds of a label placed around `node_pos` after rotating it about the station by `angle` and shifting it by `offset`
+fn place_label_bounds(bounds: LabelBounds, node_pos: (f64, f64), angle: f64, offset: (f64, f64)) -> LabelBounds {
+    let (sin, cos) = angle.sin_cos();
+    let corners = [
+        (bounds.x, bounds.y),
+        (bounds.x + bounds.width, bounds.y),
+        (bounds.x, bounds.y + bounds.height),
+        (bounds.x + bounds.width, bounds.y + bounds.height),
+    ].map(|(x, y)| {
+        let (dx, dy) = (x - node_pos.0, y - node_pos.1);
+        (node_pos.0 + offset.0 + dx * cos - dy * sin, node_pos.1 + offset.1 + dx * sin + dy * cos)
+    });
+    let min_x = corners.iter().map(|c| c.0).fold(f64::INFINITY, f64::min);
+    let max_x = corners.iter().map(|c| c.0).fold(f64::NEG_INFINITY, f64::max);
+    let min_y = corners.iter().map(|c| c.1).fold(f64::INFINITY, f64::min);
+    let max_y = corners.iter().map(|c| c.1).fold(f64::NEG_INFINITY, f64::max);
+    LabelBounds { x: min_x, y: min_y, width: max_x - min_x, height: max_y - min_y }
+}
+
+/// Move the bounds of labels with a custom rotation or offset to where they are drawn
+fn apply_custom_label_placements(
+    graph: &RailwayGraph,
+    node_positions: &[(NodeIndex, (f64, f64), f64)],
+    label_positions: &mut HashMap<NodeIndex, (LabelBounds, LabelPosition)>,
+) {
+    for (idx, pos, _) in node_positions {
+        let Some((angle, offset)) = graph.graph.node_weight(*idx).and_then(custom_label_placement) else { continue };
+        if let Some((bounds, _)) = label_positions.get_mut(idx) {
+            *bounds = place_label_bounds(*bounds, *pos, angle, offset);
+        }
+    }
+}
+
 fn count_label_overlaps(
     bounds: &LabelBounds,
     idx: NodeIndex,
@@ -457,6 +507,27 @@ fn draw_station_label(
     ctx.restore();
 }
 
+/// Draw a station's label, rotated about the station and shifted by its custom placement if it has one
+fn draw_placed_station_label(
+    ctx: &CanvasRenderingContext2d,
+    node: &crate::models::Node,
+    pos: (f64, f64),
+    position: LabelPosition,
+    radius: f64,
+    offset: f64,
+    scale: f64,
+) {
+    let Some((angle, shift)) = custom_label_placement(node) else {
+        draw_station_label(ctx, &node.display_name(), pos, position, radius, offset, scale);
+        return;
+    };
+    ctx.save();
+    let _ = ctx.translate(pos.0 + shift.0, pos.1 + shift.1);
+    let _ = ctx.rotate(angle);
+    draw_station_label(ctx, &node.display_name(), (0.0, 0.0), position, radius, offset, scale);
+    ctx.restore();
+}
+
 fn get_node_positions_and_radii(graph: &RailwayGraph, junctions: &HashSet<NodeIndex>) -> Vec<(NodeIndex, (f64, f64), f64)> {
     let mut node_positions = Vec::new();
 
@@ -869,6 +940,8 @@ pub fn compute_label_positions(graph: &RailwayGraph, zoom: f64) -> HashMap<NodeI
         );
     }
 
+    apply_custom_label_placements(graph, &node_positions, &mut label_positions);
+
     label_positions.into_iter()
         .map(|(idx, (bounds, _))| (idx, (bounds.x, bounds.y, bounds.width, bounds.height)))
         .collect()
@@ -886,8 +959,6 @@ fn calculate_line_extents_at_stations(
 ) -> HashMap<NodeIndex, (f64, f64, f64)> {
     use petgraph::visit::{EdgeRef, IntoEdgeReferences};
 
-    const LINE_BASE_WIDTH: f64 = 3.0;
-
     // Compute section information (same as line_station_renderer)
     let sections = line_renderer::identify_sections(graph, junctions);
     let section_lines = line_renderer::get_lines_in_section(&sections, lines);
@@ -1023,7 +1094,7 @@ fn calculate_line_extents_at_stations(
             let gap_width = line_gap_width / zoom;
             let section_line_widths: Vec<f64> = section_ordering
                 .iter()
-                .map(|l| (LINE_BASE_WIDTH + l.thickness) / zoom)
+                .map(|l| line_renderer::line_stroke_width(l.thickness, zoom))
                 .collect();
 
             let num_gaps = section_ordering.len().saturating_sub(1);
@@ -1046,7 +1117,7 @@ fn calculate_line_extents_at_stations(
                     let line_width = section_line_widths
                         .get(*visual_pos)
                         .copied()
-                        .unwrap_or((LINE_BASE_WIDTH + line.thickness) / zoom);
+                        .unwrap_or(line_renderer::line_stroke_width(line.thickness, zoom));
                     let offset = start_offset + offset_sum + line_width / 2.0;
 
                     let ox = nx * offset;
@@ -1202,6 +1273,8 @@ pub fn draw_stations_with_cache(
         );
     }
 
+    apply_custom_label_placements(graph, &node_positions, &mut label_positions);
+
     // Update cache with computed positions
     let cached_positions: HashMap<NodeIndex, CachedLabelPosition> = label_positions.iter()
         .map(|(idx, (bounds, position))| (*idx, CachedLabelPosition { position: *position, bounds: *bounds }))
@@ -1250,14 +1323,14 @@ pub fn draw_stations_with_cache(
 
         // Save and restore context for scaled text
         if label_scale == 1.0 {
-            draw_station_label(ctx, &node.display_name(), adjusted_pos, *position, *radius, label_offset, label_scale);
+            draw_placed_station_label(ctx, node, adjusted_pos, *position, *radius, label_offset, label_scale);
         } else {
             ctx.save();
             let scaled_font_size = font_size * label_scale;
             // Use muted color for passing loops
             ctx.set_fill_style_str(palette.passing_loop);
             ctx.set_font(&format!("{scaled_font_size}px sans-serif"));
-            draw_station_label(ctx, &node.display_name(), adjusted_pos, *position, *radius, label_offset, label_scale);
+            draw_placed_station_label(ctx, node, adjusted_pos, *position, *radius, label_offset, label_scale);
             ctx.restore();
         }
     }
@@ -1323,14 +1396,14 @@ fn draw_cached_labels(
 
         // Save and restore context for scaled text
         if label_scale == 1.0 {
-            draw_station_label(ctx, &node.display_name(), adjusted_pos, cached.position, *radius, label_offset, label_scale);
+            draw_placed_station_label(ctx, node, adjusted_pos, cached.position, *radius, label_offset, label_scale);
         } else {
             ctx.save();
             let scaled_font_size = font_size * label_scale;
             // Use muted color for passing loops
             ctx.set_fill_style_str(palette.passing_loop);
             ctx.set_font(&format!("{scaled_font_size}px sans-serif"));
-            draw_station_label(ctx, &node.display_name(), adjusted_pos, cached.position, *radius, label_offset, label_scale);
+            draw_placed_station_label(ctx, node, adjusted_pos, cached.position, *radius, label_offset, label_scale);
             ctx.restore();
         }
     }
@@ -1,4 +1,4 @@
-use crate::models::{RailwayGraph, Stations};
+use crate::models::{Electrification, RailwayGraph, Stations};
 use crate::theme::Theme;
 use petgraph::visit::{EdgeRef, IntoEdgeReferences};
 use petgraph::stable_graph::{EdgeIndex, NodeIndex};
@@ -14,7 +14,10 @@ const PROJECTION_MIN: f64 = 0.1;
 const PROJECTION_MAX: f64 = 0.9;
 
 const TRACK_LINE_WIDTH: f64 = 2.0;
+const NON_ELECTRIFIED_DASH_LENGTH: f64 = 6.0;
+const NON_ELECTRIFIED_GAP_LENGTH: f64 = 4.0;
 const JUNCTION_STOP_DISTANCE: f64 = 14.0;
+const SINGLE_TRACK_TICK_LENGTH: f64 = 8.0;
 
 struct Palette {
     track: &'static str,
@@ -38,6 +41,30 @@ fn get_palette(theme: Theme) -> &'static Palette {
     }
 }
 
+/// Set a dashed stroke for non-electrified tracks and a solid stroke otherwise
+fn set_electrification_dash(ctx: &CanvasRenderingContext2d, electrification: Electrification, zoom: f64) {
+    let dash_array = if electrification.is_electrified() {
+        js_sys::Array::new()
+    } else {
+        js_sys::Array::of2(
+            &wasm_bindgen::JsValue::from(NON_ELECTRIFIED_DASH_LENGTH / zoom),
+            &wasm_bindgen::JsValue::from(NON_ELECTRIFIED_GAP_LENGTH / zoom),
+        )
+    };
+    let _ = ctx.set_line_dash(&dash_array);
+}
+
+/// Mark a bidirectional single track with a short solid tick across its midpoint
+fn draw_single_track_tick(ctx: &CanvasRenderingContext2d, pos1: (f64, f64), pos2: (f64, f64), normal: (f64, f64), zoom: f64) {
+    let mid = ((pos1.0 + pos2.0) / 2.0, (pos1.1 + pos2.1) / 2.0);
+    let half = SINGLE_TRACK_TICK_LENGTH / zoom / 2.0;
+    let _ = ctx.set_line_dash(&js_sys::Array::new());
+    ctx.begin_path();
+    ctx.move_to(mid.0 - normal.0 * half, mid.1 - normal.1 * half);
+    ctx.line_to(mid.0 + normal.0 * half, mid.1 + normal.1 * half);
+    ctx.stroke();
+}
+
 /// Draw a track segment with optional avoidance transitions
 fn draw_track_segment_with_avoidance(
     ctx: &CanvasRenderingContext2d,
@@ -425,6 +452,7 @@ pub fn draw_tracks(
         };
 
         ctx.set_line_width(TRACK_LINE_WIDTH / zoom);
+        set_electrification_dash(ctx, edge.weight().electrification, zoom);
 
         if track_count == 1 {
             // Single track - draw in center (with avoidance if needed)
@@ -456,6 +484,12 @@ pub fn draw_tracks(
             }
 
             ctx.stroke();
+
+            if edge.weight().is_single_track_bidirectional() {
+                let tick1 = (actual_pos1.0 + avoid_x, actual_pos1.1 + avoid_y);
+                let tick2 = (actual_pos2.0 + avoid_x, actual_pos2.1 + avoid_y);
+                draw_single_track_tick(ctx, tick1, tick2, (nx, ny), zoom);
+            }
         } else {
             // Multiple tracks - distribute evenly (with avoidance if needed)
             let total_width = (track_count - 1) as f64 * TRACK_SPACING;
@@ -503,6 +537,8 @@ pub fn draw_tracks(
             }
         }
     }
+
+    let _ = ctx.set_line_dash(&js_sys::Array::new());
 }
 
 /// Draw tracks, excluding edges that have scheduled lines
@@ -582,6 +618,7 @@ pub fn draw_tracks_filtered(
         };
 
         ctx.set_line_width(TRACK_LINE_WIDTH / zoom);
+        set_electrification_dash(ctx, edge.weight().electrification, zoom);
 
         if track_count == 1 {
             // Single track - draw in center (with avoidance if needed)
@@ -613,6 +650,12 @@ pub fn draw_tracks_filtered(
             }
 
             ctx.stroke();
+
+            if edge.weight().is_single_track_bidirectional() {
+                let tick1 = (actual_pos1.0 + avoid_x, actual_pos1.1 + avoid_y);
+                let tick2 = (actual_pos2.0 + avoid_x, actual_pos2.1 + avoid_y);
+                draw_single_track_tick(ctx, tick1, tick2, (nx, ny), zoom);
+            }
         } else {
             // Multiple tracks - distribute evenly (with avoidance if needed)
             let total_width = (track_count - 1) as f64 * TRACK_SPACING;
@@ -660,4 +703,6 @@ pub fn draw_tracks_filtered(
             }
         }
     }
+
+    let _ = ctx.set_line_dash(&js_sys::Array::new());
 }
@@ -0,0 +1,155 @@
+use crate::components::graph_canvas::train_positions::interpolate_train_position;
+use crate::models::{RailwayGraph, Stations};
+use crate::theme::Theme;
+use crate::train_journey::TrainJourney;
+use chrono::NaiveDateTime;
+use web_sys::CanvasRenderingContext2d;
+
+const TRAIN_MARKER_RADIUS: f64 = 6.0;
+const TRAIN_MARKER_OUTLINE_WIDTH: f64 = 2.0;
+const TRAIN_LABEL_FONT_SIZE: f64 = 10.0;
+const TRAIN_LABEL_OFFSET: f64 = 10.0;
+
+struct Palette {
+    marker_outline: &'static str,
+    label: &'static str,
+}
+
+const DARK_PALETTE: Palette = Palette {
+    marker_outline: "#fff",
+    label: "#fff",
+};
+
+const LIGHT_PALETTE: Palette = Palette {
+    marker_outline: "#000",
+    label: "#000",
+};
+
+fn get_palette(theme: Theme) -> &'static Palette {
+    match theme {
+        Theme::Dark => &DARK_PALETTE,
+        Theme::Light => &LIGHT_PALETTE,
+    }
+}
+
+/// A train in service, placed on the map at the current time
+#[derive(Clone, PartialEq)]
+pub struct TrainMarker {
+    pub position: (f64, f64),
+    pub color: String,
+    pub label: String,
+}
+
+/// Point at `fraction` of the way from `start` to `end`
+#[must_use]
+pub fn interpolate_edge_position(start: (f64, f64), end: (f64, f64), fraction: f64) -> (f64, f64) {
+    (
+        start.0 + (end.0 - start.0) * fraction,
+        start.1 + (end.1 - start.1) * fraction,
+    )
+}
+
+/// Map position of a journey at `time`, or `None` if it is not in service
+#[must_use]
+pub fn journey_map_position(graph: &RailwayGraph, journey: &TrainJourney, time: NaiveDateTime) -> Option<(f64, f64)> {
+    // Every node the journey passes has a position on the map, so index the journey's own stations
+    let journey = journey.with_passed_stations();
+    let station_positions: Vec<Option<usize>> = (0..journey.station_times.len()).map(Some).collect();
+    let position = interpolate_train_position(&journey.station_times, &station_positions, time)?;
+
+    let from = graph.get_station_position(journey.station_times[position.from_station].0)?;
+    if position.from_station == position.to_station {
+        return Some(from);
+    }
+    let to = graph.get_station_position(journey.station_times[position.to_station].0)?;
+    Some(interpolate_edge_position(from, to, position.progress))
+}
+
+/// Collect markers for every journey in service at `time`
+#[must_use]
+pub fn collect_train_markers<'a>(
+    graph: &RailwayGraph,
+    journeys: impl Iterator<Item = &'a TrainJourney>,
+    time: NaiveDateTime,
+) -> Vec<TrainMarker> {
+    journeys
+        .filter(|journey| journey.is_running_at(time))
+        .filter_map(|journey| {
+            journey_map_position(graph, journey, time).map(|position| TrainMarker {
+                position,
+                color: journey.color.clone(),
+                label: journey.train_number.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Draw train markers in world coordinates, sized to stay constant on screen
+pub fn draw_train_markers(ctx: &CanvasRenderingContext2d, markers: &[TrainMarker], zoom: f64, theme: Theme) {
+    let palette = get_palette(theme);
+
+    for marker in markers {
+        let (x, y) = marker.position;
+
+        ctx.set_fill_style_str(&marker.color);
+        ctx.set_stroke_style_str(palette.marker_outline);
+        ctx.set_line_width(TRAIN_MARKER_OUTLINE_WIDTH / zoom);
+        ctx.begin_path();
+        let _ = ctx.arc(x, y, TRAIN_MARKER_RADIUS / zoom, 0.0, std::f64::consts::PI * 2.0);
+        ctx.fill();
+        ctx.stroke();
+
+        ctx.set_fill_style_str(palette.label);
+        ctx.set_font(&format!("bold {}px monospace", TRAIN_LABEL_FONT_SIZE / zoom));
+        let _ = ctx.fill_text(&marker.label, x + TRAIN_LABEL_OFFSET / zoom, y - TRAIN_LABEL_OFFSET / zoom);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interpolate_edge_position_at_fraction() {
+        assert_eq!(interpolate_edge_position((0.0, 0.0), (100.0, 40.0), 0.25), (25.0, 10.0));
+        assert_eq!(interpolate_edge_position((10.0, 20.0), (-10.0, 20.0), 0.5), (0.0, 20.0));
+        assert_eq!(interpolate_edge_position((5.0, 5.0), (50.0, 50.0), 0.0), (5.0, 5.0));
+    }
+
+    #[test]
+    fn test_journey_map_position_running_and_dwelling() {
+        use chrono::NaiveDate;
+
+        let at = |hour: u32, minute: u32| {
+            NaiveDate::from_ymd_opt(2024, 1, 1)
+                .and_then(|d| d.and_hms_opt(hour, minute, 0))
+                .expect("valid time")
+        };
+
+        let mut graph = RailwayGraph::new();
+        let a = graph.add_or_get_station("A".to_string());
+        let b = graph.add_or_get_station("B".to_string());
+        graph.set_station_position(a, (0.0, 0.0));
+        graph.set_station_position(b, (100.0, 0.0));
+
+        let journey = TrainJourney {
+            id: uuid::Uuid::new_v4(),
+            line_id: uuid::Uuid::new_v4(),
+            train_number: "1".to_string(),
+            departure_time: at(8, 0),
+            station_times: vec![(a, at(8, 0), at(8, 0)), (b, at(8, 10), at(8, 15))],
+            segments: Vec::new(),
+            color: "#fff".to_string(),
+            thickness: 2.0,
+            route_start_node: Some(a),
+            route_end_node: Some(b),
+            timing_inherited: vec![false, false],
+            is_forward: true,
+            passed_stations: Vec::new(),
+        };
+
+        assert_eq!(journey_map_position(&graph, &journey, at(8, 4)), Some((40.0, 0.0)));
+        assert_eq!(journey_map_position(&graph, &journey, at(8, 12)), Some((100.0, 0.0)));
+        assert_eq!(journey_map_position(&graph, &journey, at(8, 20)), None);
+    }
+}
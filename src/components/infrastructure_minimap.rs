@@ -0,0 +1,168 @@
+use crate::components::canvas_viewport::{fit_to_bounds_with_margin, pan_to_center, ViewportSignals};
+use crate::components::graph_canvas::types::ViewportState;
+use crate::models::RailwayGraph;
+use crate::theme::Theme;
+use leptos::{batch, component, create_effect, create_node_ref, html, view, IntoView, ReadSignal, Signal, SignalGet, SignalGetUntracked, SignalSet, SignalWith, SignalWithUntracked};
+use petgraph::visit::{EdgeRef, IntoEdgeReferences};
+use wasm_bindgen::JsCast;
+use web_sys::{CanvasRenderingContext2d, MouseEvent};
+
+const MINIMAP_WIDTH: f64 = 180.0;
+const MINIMAP_HEIGHT: f64 = 120.0;
+const MINIMAP_MARGIN: f64 = 8.0;
+const MINIMAP_STATION_RADIUS: f64 = 1.5;
+
+struct Palette {
+    track: &'static str,
+    station: &'static str,
+    viewport: &'static str,
+}
+
+const DARK_PALETTE: Palette = Palette {
+    track: "#555",
+    station: "#bbb",
+    viewport: "#ffaa00",
+};
+
+const LIGHT_PALETTE: Palette = Palette {
+    track: "#aaa",
+    station: "#444",
+    viewport: "#ff8800",
+};
+
+fn get_palette(theme: Theme) -> &'static Palette {
+    match theme {
+        Theme::Dark => &DARK_PALETTE,
+        Theme::Light => &LIGHT_PALETTE,
+    }
+}
+
+/// Convert a point on the minimap to world coordinates
+fn minimap_to_world(minimap: &ViewportState, point: (f64, f64)) -> (f64, f64) {
+    (
+        (point.0 - minimap.pan_offset_x) / minimap.zoom_level,
+        (point.1 - minimap.pan_offset_y) / minimap.zoom_level,
+    )
+}
+
+fn draw_minimap(
+    ctx: &CanvasRenderingContext2d,
+    graph: &RailwayGraph,
+    minimap: &ViewportState,
+    main: &ViewportState,
+    canvas_dims: (f64, f64),
+    theme: Theme,
+) {
+    let palette = get_palette(theme);
+    let to_minimap = |(x, y): (f64, f64)| {
+        (x * minimap.zoom_level + minimap.pan_offset_x, y * minimap.zoom_level + minimap.pan_offset_y)
+    };
+
+    ctx.clear_rect(0.0, 0.0, MINIMAP_WIDTH, MINIMAP_HEIGHT);
+
+    ctx.set_stroke_style_str(palette.track);
+    ctx.set_line_width(1.0);
+    ctx.begin_path();
+    for edge in graph.graph.edge_references() {
+        let source = graph.graph.node_weight(edge.source()).and_then(crate::models::Node::position);
+        let target = graph.graph.node_weight(edge.target()).and_then(crate::models::Node::position);
+        if let (Some(source), Some(target)) = (source, target) {
+            let (x1, y1) = to_minimap(source);
+            let (x2, y2) = to_minimap(target);
+            ctx.move_to(x1, y1);
+            ctx.line_to(x2, y2);
+        }
+    }
+    ctx.stroke();
+
+    ctx.set_fill_style_str(palette.station);
+    for position in graph.graph.node_weights().filter_map(crate::models::Node::position) {
+        let (x, y) = to_minimap(position);
+        ctx.fill_rect(x - MINIMAP_STATION_RADIUS, y - MINIMAP_STATION_RADIUS, MINIMAP_STATION_RADIUS * 2.0, MINIMAP_STATION_RADIUS * 2.0);
+    }
+
+    // Visible area of the main canvas, converted from screen to world to minimap coordinates
+    let top_left = to_minimap((-main.pan_offset_x / main.zoom_level, -main.pan_offset_y / main.zoom_level));
+    let bottom_right = to_minimap((
+        (canvas_dims.0 - main.pan_offset_x) / main.zoom_level,
+        (canvas_dims.1 - main.pan_offset_y) / main.zoom_level,
+    ));
+    ctx.set_stroke_style_str(palette.viewport);
+    ctx.stroke_rect(top_left.0, top_left.1, bottom_right.0 - top_left.0, bottom_right.1 - top_left.1);
+}
+
+#[component]
+#[must_use]
+pub fn InfrastructureMinimap(
+    graph: ReadSignal<RailwayGraph>,
+    viewport: ViewportSignals,
+    canvas_dimensions: Signal<Option<(f64, f64)>>,
+    theme: ReadSignal<Theme>,
+) -> impl IntoView {
+    let canvas_ref = create_node_ref::<html::Canvas>();
+
+    // Redraws only when the graph, viewport, canvas size or theme change
+    create_effect(move |_| {
+        let main = ViewportState {
+            zoom_level: viewport.zoom_level.get(),
+            zoom_level_x: 1.0,
+            pan_offset_x: viewport.pan_offset_x.get(),
+            pan_offset_y: viewport.pan_offset_y.get(),
+        };
+        let Some(canvas_dims) = canvas_dimensions.get() else { return };
+        let theme = theme.get();
+        let Some(canvas) = canvas_ref.get() else { return };
+        let Some(ctx) = canvas
+            .get_context("2d")
+            .ok()
+            .flatten()
+            .and_then(|c| c.dyn_into::<CanvasRenderingContext2d>().ok())
+        else {
+            return;
+        };
+
+        graph.with(|g| {
+            let minimap = fit_to_bounds_with_margin(g, (MINIMAP_WIDTH, MINIMAP_HEIGHT), MINIMAP_MARGIN);
+            draw_minimap(&ctx, g, &minimap, &main, canvas_dims, theme);
+        });
+    });
+
+    let handle_click = move |ev: MouseEvent| {
+        let Some(canvas_dims) = canvas_dimensions.get_untracked() else { return };
+        let point = (f64::from(ev.offset_x()), f64::from(ev.offset_y()));
+        let zoom = viewport.zoom_level.get_untracked();
+        let (pan_x, pan_y) = graph.with_untracked(|g| {
+            let minimap = fit_to_bounds_with_margin(g, (MINIMAP_WIDTH, MINIMAP_HEIGHT), MINIMAP_MARGIN);
+            pan_to_center(minimap_to_world(&minimap, point), zoom, canvas_dims)
+        });
+        batch(move || {
+            viewport.set_pan_offset_x.set(pan_x);
+            viewport.set_pan_offset_y.set(pan_y);
+        });
+    };
+
+    view! {
+        <canvas
+            node_ref=canvas_ref
+            class="infrastructure-minimap"
+            width=MINIMAP_WIDTH
+            height=MINIMAP_HEIGHT
+            on:click=handle_click
+        />
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_minimap_click_recenters_on_world_point() {
+        let minimap = ViewportState { zoom_level: 0.5, zoom_level_x: 1.0, pan_offset_x: 10.0, pan_offset_y: 20.0 };
+        let world = minimap_to_world(&minimap, (60.0, 70.0));
+        assert_eq!(world, (100.0, 100.0));
+
+        let (pan_x, pan_y) = pan_to_center(world, 2.0, (800.0, 600.0));
+        assert_eq!((pan_x, pan_y), (200.0, 100.0));
+    }
+}
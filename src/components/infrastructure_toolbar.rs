@@ -1,6 +1,7 @@
-use leptos::{component, view, IntoView, ReadSignal, WriteSignal, SignalGet, SignalSet, Callback, Signal, Show};
+use leptos::{component, view, IntoView, ReadSignal, WriteSignal, SignalGet, SignalSet, SignalGetUntracked, Callback, Callable, Signal, Show, create_signal, event_target_value};
 use petgraph::stable_graph::NodeIndex;
 use crate::components::button::Button;
+use crate::components::infrastructure_canvas::export::EXPORT_SCALES;
 
 /// Check if line view feature is enabled via localStorage
 fn is_line_view_enabled() -> bool {
@@ -16,6 +17,39 @@ pub enum EditMode {
     AddingTrack,
     AddingJunction,
     CreatingView,
+    LassoSelect,
+}
+
+/// Scale picker and button for downloading the whole network as a PNG
+#[component]
+fn ExportPngControl(on_export_png: Callback<f64>) -> impl IntoView {
+    let (scale, set_scale) = create_signal(EXPORT_SCALES[0]);
+
+    view! {
+        <select
+            class="toolbar-select"
+            title="Export resolution"
+            on:change=move |ev| {
+                if let Ok(value) = event_target_value(&ev).parse::<f64>() {
+                    set_scale.set(value);
+                }
+            }
+        >
+            {EXPORT_SCALES.iter().map(|&option| view! {
+                <option value=option.to_string() selected=move || (scale.get() - option).abs() < f64::EPSILON>
+                    {format!("{option}x")}
+                </option>
+            }).collect::<Vec<_>>()}
+        </select>
+        <Button
+            class="toolbar-button"
+            on_click=Callback::new(move |_| on_export_png.call(scale.get_untracked()))
+            title="Download the whole network as a PNG image"
+        >
+            <i class="fa-solid fa-image"></i>
+            " Export PNG"
+        </Button>
+    }
 }
 
 #[component]
@@ -28,6 +62,12 @@ pub fn InfrastructureToolbar(
     edit_mode: ReadSignal<EditMode>,
     set_edit_mode: WriteSignal<EditMode>,
     set_selected_station: WriteSignal<Option<NodeIndex>>,
+    on_zoom_to_fit: Callback<()>,
+    on_export_png: Callback<f64>,
+    on_export_geojson: Callback<()>,
+    on_paste: Callback<()>,
+    show_critical_sections: ReadSignal<bool>,
+    set_show_critical_sections: WriteSignal<bool>,
 ) -> impl IntoView {
     view! {
         <div class="infrastructure-toolbar">
@@ -106,6 +146,55 @@ pub fn InfrastructureToolbar(
                 <i class="fa-solid fa-eye"></i>
                 " Create View"
             </Button>
+            <Button
+                class="toolbar-button"
+                active=Signal::derive(move || edit_mode.get() == EditMode::LassoSelect)
+                on_click=Callback::new(move |_| {
+                    if edit_mode.get() == EditMode::LassoSelect {
+                        set_edit_mode.set(EditMode::None);
+                    } else {
+                        set_edit_mode.set(EditMode::LassoSelect);
+                    }
+                })
+                title="Select stations inside a freehand outline"
+            >
+                <i class="fa-solid fa-draw-polygon"></i>
+                " Lasso Select"
+            </Button>
+            <Button
+                class="toolbar-button"
+                on_click=Callback::new(move |_| on_paste.call(()))
+                shortcut_id="paste_stations"
+                title="Paste copied stations"
+            >
+                <i class="fa-solid fa-paste"></i>
+                " Paste"
+            </Button>
+            <Button
+                class="toolbar-button"
+                on_click=Callback::new(move |_| on_zoom_to_fit.call(()))
+                title="Zoom to Fit"
+            >
+                <i class="fa-solid fa-expand"></i>
+                " Zoom to Fit"
+            </Button>
+            <ExportPngControl on_export_png=on_export_png />
+            <Button
+                class="toolbar-button"
+                on_click=Callback::new(move |_| on_export_geojson.call(()))
+                title="Download stations and tracks as GeoJSON, placed on the map if the project has a map reference"
+            >
+                <i class="fa-solid fa-map-location-dot"></i>
+                " Export GeoJSON"
+            </Button>
+            <button
+                class=move || if show_critical_sections.get() { "toolbar-button active" } else { "toolbar-button" }
+                on:click=move |_| set_show_critical_sections.set(!show_critical_sections.get())
+                title="Highlight the busiest single-track sections"
+            >
+                <i class="fa-solid fa-fire"></i>
+                " Critical Sections"
+            </button>
         </div>
     }
 }
@@ -1,11 +1,14 @@
-use crate::models::{RailwayGraph, Line, Track, TrackDirection, Stations, Tracks, Junctions};
-use crate::components::infrastructure_canvas::{auto_layout, renderer, hit_detection};
+use crate::models::{RailwayGraph, Line, Track, TrackDirection, Stations, Tracks, Junctions, BlockSections};
+use crate::components::infrastructure_canvas::{auto_layout, renderer, hit_detection, line_renderer, export};
+use crate::components::infrastructure_canvas::train_position_renderer::{collect_train_markers, TrainMarker};
+use crate::components::infrastructure_minimap::InfrastructureMinimap;
 use crate::components::infrastructure_toolbar::{InfrastructureToolbar, EditMode};
 use crate::components::line_settings_panel::LineSettingsPanel;
 use crate::components::canvas_viewport;
 use crate::components::canvas_controls_hint::CanvasControlsHint;
 use crate::components::multi_select_toolbar::MultiSelectToolbar;
-use crate::components::graph_canvas::types::ViewportState;
+use crate::components::graph_canvas::types::{FocusSource, FocusedStations, HighlightedJourney, ViewportState, VisualizationTime};
+use crate::train_journey::TrainJourney;
 use crate::components::sidebar::Sidebar;
 use crate::theme::{Theme, use_theme};
 use crate::components::add_station::{AddStation, AddStationsBatchCallback};
@@ -14,9 +17,11 @@ use crate::components::confirmation_dialog::ConfirmationDialog;
 use crate::components::create_view_dialog::CreateViewDialog;
 use crate::components::delete_station_confirmation::DeleteStationConfirmation;
 use crate::components::edit_junction::EditJunction;
-use crate::components::edit_station::EditStation;
-use crate::components::edit_track::EditTrack;
-use leptos::{wasm_bindgen, web_sys, component, view, ReadSignal, WriteSignal, IntoView, create_node_ref, create_signal, create_effect, SignalGet, SignalSet, SignalGetUntracked, Callable, Signal, use_context, StoredValue, store_value};
+use crate::components::edit_station::{EditStation, SaveStationCallback, StationEdit};
+use crate::components::edit_track::{EditTrack, TrackEdit};
+use crate::components::station_search::StationSearch;
+use crate::components::track_tooltip::TrackTooltip;
+use leptos::{wasm_bindgen, web_sys, component, view, ReadSignal, WriteSignal, IntoView, create_node_ref, create_signal, create_effect, SignalGet, SignalSet, SignalUpdate, SignalGetUntracked, Callable, Signal, SignalWith, SignalWithUntracked, use_context, StoredValue, store_value, Memo, create_memo, Show};
 use wasm_bindgen::closure::Closure;
 use crate::models::UserSettings;
 use petgraph::stable_graph::{NodeIndex, EdgeIndex};
@@ -27,6 +32,12 @@ use std::cell::RefCell;
 use wasm_bindgen::JsCast;
 use web_sys::{CanvasRenderingContext2d, MouseEvent, WheelEvent};
 
+const STATION_SEARCH_MIN_ZOOM: f64 = 1.0;
+// Minimum on-screen distance between recorded lasso points, in pixels
+const LASSO_POINT_SPACING: f64 = 4.0;
+// Keep stations placed on a track at least this fraction of the edge away from either end
+const MIN_SPLIT_FRACTION: f64 = 0.05;
+
 // Use the TopologyCache from renderer module
 type TopologyCache = renderer::TopologyCache;
 
@@ -120,7 +131,7 @@ fn handle_segment_click_in_track_mode(
     let junction_idx = updated_graph.add_junction(junction);
 
     // Split the segment and insert the junction
-    split_segment_and_insert_node(clicked_edge, junction_idx, &mut updated_graph, &mut current_lines, true, handedness);
+    split_segment_and_insert_node(clicked_edge, junction_idx, &mut updated_graph, &mut current_lines, true, handedness, 0.5);
 
     // Handle selection logic
     if let Some(first_station) = selected_station.get() {
@@ -146,6 +157,7 @@ fn handle_mouse_move_hover_detection(
     graph: ReadSignal<RailwayGraph>,
     set_is_over_station: WriteSignal<bool>,
     set_is_over_track: WriteSignal<bool>,
+    set_hovered_track: WriteSignal<Option<(EdgeIndex, f64, f64)>>,
     topology_cache: StoredValue<RefCell<TopologyCache>>,
 ) {
     let world_x = (x - viewport.pan_offset_x) / viewport.zoom_level;
@@ -166,19 +178,16 @@ fn handle_mouse_move_hover_detection(
     if hovered_node.is_some() {
         set_is_over_station.set(true);
         set_is_over_track.set(false);
+        set_hovered_track.set(None);
     } else {
         // Use cached edge segments for hit detection
         let track_hit = topology_cache.with_value(|cache| {
             hit_detection::find_track_at_position_cached(&cache.borrow().edge_segments, world_x, world_y)
         });
 
-        if track_hit.is_some() {
-            set_is_over_station.set(false);
-            set_is_over_track.set(true);
-        } else {
-            set_is_over_station.set(false);
-            set_is_over_track.set(false);
-        }
+        set_is_over_station.set(false);
+        set_is_over_track.set(track_hit.is_some());
+        set_hovered_track.set(track_hit.map(|edge_idx| (edge_idx, x, y)));
     }
 }
 
@@ -223,15 +232,17 @@ fn apply_drag_snap(
     station_idx: NodeIndex,
     world_x: f64,
     world_y: f64,
+    angle_snap_degrees: f64,
+    grid_size: f64,
 ) {
     let mut current_graph = graph.get();
 
-    if should_reorient_branch(&current_graph, station_idx, world_x, world_y) {
-        // Significant angle change - reorient entire branch
-        auto_layout::snap_to_angle(&mut current_graph, station_idx, world_x, world_y);
+    if should_reorient_branch(&current_graph, station_idx, world_x, world_y, angle_snap_degrees) {
+        // Drag points at a different allowed angle - reorient entire branch
+        auto_layout::snap_to_angle(&mut current_graph, station_idx, (world_x, world_y), angle_snap_degrees, grid_size);
     } else {
         // Moving along branch - just reposition this station
-        auto_layout::snap_station_along_branch(&mut current_graph, station_idx, world_x, world_y);
+        auto_layout::snap_station_along_branch(&mut current_graph, station_idx, world_x, world_y, grid_size);
     }
 
     set_graph.set(current_graph);
@@ -239,7 +250,7 @@ fn apply_drag_snap(
 
 /// Determine if a station drag should reorient the branch or just reposition along it
 #[allow(clippy::similar_names)]
-fn should_reorient_branch(graph: &RailwayGraph, station_idx: NodeIndex, target_x: f64, target_y: f64) -> bool {
+fn should_reorient_branch(graph: &RailwayGraph, station_idx: NodeIndex, target_x: f64, target_y: f64, angle_snap_degrees: f64) -> bool {
     use crate::models::Stations;
     use petgraph::Direction;
 
@@ -307,16 +318,15 @@ fn should_reorient_branch(graph: &RailwayGraph, station_idx: NodeIndex, target_x
     let drag_dir_x = drag_dx / drag_dist;
     let drag_dir_y = drag_dy / drag_dist;
 
-    // Calculate angle between branch direction and drag direction
-    let dot_product = branch_dir_x * drag_dir_x + branch_dir_y * drag_dir_y;
-    let angle = dot_product.acos();
-
-    // If angle is more than 30 degrees (π/6), consider it a reorientation
-    angle.abs() > std::f64::consts::PI / 6.0
+    // Compare the nearest allowed angles; dragging either way along the branch keeps its orientation
+    let branch_angle = auto_layout::snap_angle(branch_dir_y.atan2(branch_dir_x), angle_snap_degrees);
+    let drag_angle = auto_layout::snap_angle(drag_dir_y.atan2(drag_dir_x), angle_snap_degrees);
+    let difference = crate::geometry::angle_difference(branch_angle, drag_angle);
+    difference > 0.01 && (std::f64::consts::PI - difference) > 0.01
 }
 
 #[allow(clippy::too_many_arguments)]
-/// Split a track segment by inserting a node in the middle
+/// Split a track segment by inserting a node at `split_fraction` along it
 /// Returns (`edge1_index`, `edge2_index`) where edge1 is `from_node` -> `new_node` and edge2 is `new_node` -> `to_node`
 fn split_segment_and_insert_node(
     clicked_edge: EdgeIndex,
@@ -325,6 +335,7 @@ fn split_segment_and_insert_node(
     current_lines: &mut [Line],
     should_set_routing_rules: bool,
     handedness: crate::models::TrackHandedness,
+    split_fraction: f64,
 ) -> (EdgeIndex, EdgeIndex) {
     use crate::models::Junctions;
 
@@ -344,20 +355,25 @@ fn split_segment_and_insert_node(
         .and_then(|node| node.as_station())
         .map_or(1, |station| station.platforms.len());
 
-    // Remove the old edge
+    // Remove the old edge, keeping its block for the two halves
+    let block = updated_graph.block_for_edge(clicked_edge);
+    updated_graph.remove_edges_from_blocks(&[clicked_edge]);
     updated_graph.graph.remove_edge(clicked_edge);
 
     // Create two new edges: from_node -> new_node and new_node -> to_node
-    // If distance is set, split it in half
+    // If distance is set, split it proportionally
     let edge1 = updated_graph.add_track(from_node, new_node_idx, tracks.clone());
     let edge2 = updated_graph.add_track(new_node_idx, to_node, tracks);
+    if let Some(block) = block {
+        updated_graph.assign_edges_to_block(block, &[edge1, edge2]);
+    }
 
     if let Some(dist) = distance {
         if let Some(edge1_weight) = updated_graph.graph.edge_weight_mut(edge1) {
-            edge1_weight.distance = Some(dist / 2.0);
+            edge1_weight.distance = Some(dist * split_fraction);
         }
         if let Some(edge2_weight) = updated_graph.graph.edge_weight_mut(edge2) {
-            edge2_weight.distance = Some(dist / 2.0);
+            edge2_weight.distance = Some(dist * (1.0 - split_fraction));
         }
     }
 
@@ -369,9 +385,11 @@ fn split_segment_and_insert_node(
         }
     }
 
+    updated_graph.prune_junction_rules();
+
     // Update all lines that used the old edge to now use the two new edges
     for line in current_lines {
-        line.replace_split_edge(old_edge_index, edge1.index(), edge2.index(), track_count, updated_graph, platform_count, handedness);
+        line.replace_split_edge(old_edge_index, edge1.index(), edge2.index(), split_fraction, track_count, updated_graph, platform_count, handedness);
     }
 
     (edge1, edge2)
@@ -410,7 +428,7 @@ fn handle_adding_junction(
     let junction_idx = updated_graph.add_junction(junction);
 
     // Split the segment and insert the junction
-    split_segment_and_insert_node(clicked_edge, junction_idx, &mut updated_graph, &mut current_lines, true, handedness);
+    split_segment_and_insert_node(clicked_edge, junction_idx, &mut updated_graph, &mut current_lines, true, handedness, 0.5);
 
     set_graph.set(updated_graph);
     set_lines.set(current_lines);
@@ -422,6 +440,19 @@ fn handle_adding_junction(
     set_edit_mode.set(EditMode::None);
 }
 
+/// Where along a segment a clicked station goes, as a fraction of the segment and a position.
+/// The fraction stays clear of the endpoints so neither half becomes zero-length.
+fn split_point_on_segment(clicked: Option<(f64, f64)>, from_pos: (f64, f64), to_pos: (f64, f64)) -> (f64, (f64, f64)) {
+    let fraction = clicked
+        .map_or(0.5, |point| crate::geometry::project_onto_segment(point, from_pos, to_pos).0)
+        .clamp(MIN_SPLIT_FRACTION, 1.0 - MIN_SPLIT_FRACTION);
+    let position = (
+        from_pos.0 + (to_pos.0 - from_pos.0) * fraction,
+        from_pos.1 + (to_pos.1 - from_pos.1) * fraction,
+    );
+    (fraction, position)
+}
+
 #[allow(clippy::too_many_arguments)]
 fn add_station_handler(
     name: String,
@@ -456,16 +487,18 @@ fn add_station_handler(
     if let Some(segment_edge) = clicked_segment.get_untracked() {
         let mut current_lines = lines.get();
 
-        // Get endpoints for positioning the station at midpoint
+        // Place the station where the click projects onto the track
+        let mut split_fraction = 0.5;
         if let Some((from_node, to_node)) = current_graph.get_track_endpoints(segment_edge) {
             let from_pos = current_graph.get_station_position(from_node).unwrap_or((0.0, 0.0));
             let to_pos = current_graph.get_station_position(to_node).unwrap_or((0.0, 0.0));
-            let midpoint = ((from_pos.0 + to_pos.0) / 2.0, (from_pos.1 + to_pos.1) / 2.0);
-            current_graph.set_station_position(node_idx, midpoint);
+            let (fraction, position) = split_point_on_segment(clicked_position.get_untracked(), from_pos, to_pos);
+            split_fraction = fraction;
+            current_graph.set_station_position(node_idx, position);
         }
 
         // Split the segment and insert the station
-        split_segment_and_insert_node(segment_edge, node_idx, &mut current_graph, &mut current_lines, false, handedness);
+        split_segment_and_insert_node(segment_edge, node_idx, &mut current_graph, &mut current_lines, false, handedness, split_fraction);
 
         set_lines.set(current_lines);
     }
@@ -583,29 +616,34 @@ fn add_stations_batch_handler(
 
 fn edit_station_handler(
     station_idx: NodeIndex,
-    new_name: String,
-    passing_loop: bool,
-    platforms: Vec<crate::models::Platform>,
-    graph: ReadSignal<RailwayGraph>,
+    edit: StationEdit,
     set_graph: WriteSignal<RailwayGraph>,
     set_editing_station: WriteSignal<Option<NodeIndex>>,
 ) {
-    let mut current_graph = graph.get();
-
-    if let Some(node) = current_graph.graph.node_weight_mut(station_idx) {
-        if let Some(station) = node.as_station_mut() {
-            let old_name = station.name.clone();
-            station.name.clone_from(&new_name);
-            station.passing_loop = passing_loop;
-            station.platforms = platforms;
-
-            current_graph.station_name_to_index.remove(&old_name);
-            current_graph.station_name_to_index.insert(new_name, station_idx);
+    let saved = set_graph.try_update(|current_graph| {
+        // Refuse renames that would merge two stations under one name
+        if let Err(e) = current_graph.rename_station(station_idx, edit.name) {
+            leptos::logging::warn!("Not saving station: {}", e);
+            return false;
         }
-    }
+        if let Some(station) = current_graph.graph.node_weight_mut(station_idx).and_then(|node| node.as_station_mut()) {
+            station.passing_loop = edit.passing_loop;
+            station.is_depot = edit.is_depot;
+            station.min_dwell_secs = edit.min_dwell_secs;
+            station.conflict_margin_secs = edit.conflict_margin_secs;
+            station.platforms = edit.platforms;
+            station.label_angle = edit.label_angle;
+            station.label_offset = edit.label_offset;
+        }
+        if let Some(position) = edit.position {
+            current_graph.set_station_position(station_idx, position);
+        }
+        true
+    });
 
-    set_graph.set(current_graph);
-    set_editing_station.set(None);
+    if saved == Some(true) {
+        set_editing_station.set(None);
+    }
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -660,6 +698,7 @@ fn confirm_delete_station_handler(
     set_graph: WriteSignal<RailwayGraph>,
     lines: ReadSignal<Vec<Line>>,
     set_lines: WriteSignal<Vec<Line>>,
+    set_settings: WriteSignal<crate::models::ProjectSettings>,
     set_show_delete_confirmation: WriteSignal<bool>,
     set_station_to_delete: WriteSignal<Option<NodeIndex>>,
 ) {
@@ -669,6 +708,7 @@ fn confirm_delete_station_handler(
     let mut current_lines = lines.get();
 
     let (removed_edges, bypass_mapping) = current_graph.delete_station(station_idx);
+    current_graph.prune_junction_rules();
 
     for line in &mut current_lines {
         line.update_route_after_deletion(&removed_edges, &bypass_mapping);
@@ -676,14 +716,38 @@ fn confirm_delete_station_handler(
 
     set_graph.set(current_graph);
     set_lines.set(current_lines);
+    set_settings.update(|settings| settings.forget_deleted_station(station_idx));
     set_show_delete_confirmation.set(false);
     set_station_to_delete.set(None);
 }
 
+fn apply_track_edit(graph: &mut RailwayGraph, lines: &mut [Line], edge_idx: EdgeIndex, edit: TrackEdit) -> Result<(), String> {
+    if let Some(gradient) = edit.gradient_permille {
+        crate::models::check_gradient(gradient)?;
+    }
+
+    let new_track_count = edit.tracks.len();
+
+    if let Some(track_segment) = graph.graph.edge_weight_mut(edge_idx) {
+        track_segment.tracks = edit.tracks;
+        track_segment.distance = edit.distance;
+        track_segment.electrification = edit.electrification;
+        track_segment.gradient_permille = edit.gradient_permille;
+        track_segment.speed_limit_kph = edit.speed_limit_kph;
+        track_segment.handedness = edit.handedness;
+        track_segment.notes = edit.notes;
+    }
+
+    for line in lines.iter_mut() {
+        line.fix_track_indices_after_change(edge_idx.index(), new_track_count, graph);
+    }
+
+    Ok(())
+}
+
 fn edit_track_handler(
     edge_idx: EdgeIndex,
-    new_tracks: Vec<Track>,
-    new_distance: Option<f64>,
+    edit: TrackEdit,
     graph: ReadSignal<RailwayGraph>,
     set_graph: WriteSignal<RailwayGraph>,
     lines: ReadSignal<Vec<Line>>,
@@ -692,16 +756,10 @@ fn edit_track_handler(
 ) {
     let mut current_graph = graph.get();
     let mut current_lines = lines.get();
-    let edge_index = edge_idx.index();
-    let new_track_count = new_tracks.len();
-
-    if let Some(track_segment) = current_graph.graph.edge_weight_mut(edge_idx) {
-        track_segment.tracks = new_tracks;
-        track_segment.distance = new_distance;
-    }
 
-    for line in &mut current_lines {
-        line.fix_track_indices_after_change(edge_index, new_track_count, &current_graph);
+    if let Err(e) = apply_track_edit(&mut current_graph, &mut current_lines, edge_idx, edit) {
+        leptos::logging::warn!("Rejected track edit: {}", e);
+        return;
     }
 
     set_graph.set(current_graph);
@@ -732,7 +790,9 @@ fn delete_track_handler(
     }
 
     // Now delete the edge
+    current_graph.remove_edges_from_blocks(&[edge_idx]);
     current_graph.graph.remove_edge(edge_idx);
+    current_graph.prune_junction_rules();
 
     // Clean up any segments that still reference the deleted edge (if rerouting failed)
     for line in &mut current_lines {
@@ -776,6 +836,7 @@ fn delete_junction_handler(
     let mut current_lines = lines.get();
 
     let (removed_edges, bypass_mapping) = current_graph.delete_junction(junction_idx);
+    current_graph.prune_junction_rules();
 
     for line in &mut current_lines {
         line.update_route_after_deletion(&removed_edges, &bypass_mapping);
@@ -786,6 +847,19 @@ fn delete_junction_handler(
     set_editing_junction.set(None);
 }
 
+/// Display name of a node for checkpoint labels
+fn checkpoint_node_name(graph: &RailwayGraph, node_idx: NodeIndex) -> String {
+    graph.graph.node_weight(node_idx)
+        .map_or_else(|| "Unknown".to_string(), crate::models::Node::display_name)
+}
+
+/// Ask the app to store a labeled checkpoint of the current state before a destructive edit
+fn record_checkpoint(on_checkpoint: Option<leptos::Callback<String>>, label: String) {
+    if let Some(on_checkpoint) = on_checkpoint {
+        on_checkpoint.call(label);
+    }
+}
+
 #[allow(clippy::type_complexity, clippy::too_many_arguments)]
 fn create_handler_callbacks(
     graph: ReadSignal<RailwayGraph>,
@@ -808,15 +882,17 @@ fn create_handler_callbacks(
     set_clicked_position: WriteSignal<Option<(f64, f64)>>,
     set_clicked_segment: WriteSignal<Option<EdgeIndex>>,
     settings: ReadSignal<crate::models::ProjectSettings>,
+    set_settings: WriteSignal<crate::models::ProjectSettings>,
     set_selected_stations: WriteSignal<Vec<NodeIndex>>,
     set_selection_bounds: WriteSignal<Option<(f64, f64, f64, f64)>>,
+    on_checkpoint: Option<leptos::Callback<String>>,
 ) -> (
     Rc<dyn Fn(String, bool, Option<NodeIndex>, Vec<crate::models::Platform>)>,
     AddStationsBatchCallback,
-    Rc<dyn Fn(NodeIndex, String, bool, Vec<crate::models::Platform>)>,
+    SaveStationCallback,
     Rc<dyn Fn(NodeIndex)>,
     Rc<dyn Fn()>,
-    Rc<dyn Fn(EdgeIndex, Vec<Track>, Option<f64>)>,
+    Rc<dyn Fn(EdgeIndex, TrackEdit)>,
     Rc<dyn Fn(EdgeIndex)>,
     Rc<dyn Fn(NodeIndex, Option<String>)>,
     Rc<dyn Fn(NodeIndex)>,
@@ -830,8 +906,8 @@ fn create_handler_callbacks(
         add_stations_batch_handler(station_entries, connect_to, platforms, tracks, graph, set_graph, lines, set_lines, set_show_add_station, clicked_position, clicked_segment, set_clicked_position, set_clicked_segment, set_selected_stations, set_last_added_station, set_selection_bounds);
     });
 
-    let handle_edit_station = Rc::new(move |station_idx: NodeIndex, new_name: String, passing_loop: bool, platforms: Vec<crate::models::Platform>| {
-        edit_station_handler(station_idx, new_name, passing_loop, platforms, graph, set_graph, set_editing_station);
+    let handle_edit_station = Rc::new(move |station_idx: NodeIndex, edit: StationEdit| {
+        edit_station_handler(station_idx, edit, set_graph, set_editing_station);
     });
 
     let handle_delete_station = Rc::new(move |station_idx: NodeIndex| {
@@ -839,14 +915,23 @@ fn create_handler_callbacks(
     });
 
     let confirm_delete_station = Rc::new(move || {
-        confirm_delete_station_handler(station_to_delete, graph, set_graph, lines, set_lines, set_show_delete_confirmation, set_station_to_delete);
+        if let Some(station_idx) = station_to_delete.get_untracked() {
+            let name = checkpoint_node_name(&graph.get_untracked(), station_idx);
+            record_checkpoint(on_checkpoint, format!("Delete station {name}"));
+        }
+        confirm_delete_station_handler(station_to_delete, graph, set_graph, lines, set_lines, set_settings, set_show_delete_confirmation, set_station_to_delete);
     });
 
-    let handle_edit_track = Rc::new(move |edge_idx: EdgeIndex, new_tracks: Vec<Track>, new_distance: Option<f64>| {
-        edit_track_handler(edge_idx, new_tracks, new_distance, graph, set_graph, lines, set_lines, set_editing_track);
+    let handle_edit_track = Rc::new(move |edge_idx: EdgeIndex, edit: TrackEdit| {
+        edit_track_handler(edge_idx, edit, graph, set_graph, lines, set_lines, set_editing_track);
     });
 
     let handle_delete_track = Rc::new(move |edge_idx: EdgeIndex| {
+        let current_graph = graph.get_untracked();
+        if let Some((from, to)) = current_graph.get_track_endpoints(edge_idx) {
+            let label = format!("Delete track {} – {}", checkpoint_node_name(&current_graph, from), checkpoint_node_name(&current_graph, to));
+            record_checkpoint(on_checkpoint, label);
+        }
         delete_track_handler(edge_idx, graph, set_graph, lines, set_lines, set_editing_track);
     });
 
@@ -855,6 +940,8 @@ fn create_handler_callbacks(
     });
 
     let handle_delete_junction = Rc::new(move |junction_idx: NodeIndex| {
+        let name = checkpoint_node_name(&graph.get_untracked(), junction_idx);
+        record_checkpoint(on_checkpoint, format!("Delete junction {name}"));
         delete_junction_handler(junction_idx, graph, set_graph, lines, set_lines, set_editing_junction);
     });
 
@@ -882,6 +969,7 @@ fn get_canvas_cursor_style(
     } else {
         match edit_mode.get() {
             EditMode::AddingTrack | EditMode::AddingJunction | EditMode::CreatingView => "cursor: pointer;",
+            EditMode::LassoSelect => "cursor: crosshair;",
             EditMode::None => {
                 if is_over_station.get() && editing_station.get().is_some() {
                     "cursor: grab;"
@@ -993,9 +1081,16 @@ fn setup_render_effect(
     set_render_requested: WriteSignal<bool>,
     station_dialog_clicked_position: ReadSignal<Option<(f64, f64)>>,
     selected_stations: ReadSignal<Vec<NodeIndex>>,
-    selection_box_start: ReadSignal<Option<(f64, f64)>>,
-    selection_box_end: ReadSignal<Option<(f64, f64)>>,
+    selection_outline: Signal<Vec<(f64, f64)>>,
     theme: ReadSignal<Theme>,
+    highlighted_journey_edges: Signal<HashSet<EdgeIndex>>,
+    critical_sections: Memo<HashSet<EdgeIndex>>,
+    min_curve_radius: Signal<f64>,
+    train_markers: Memo<Vec<TrainMarker>>,
+    grid_spacing: Signal<Option<f64>>,
+    focused_stations: Signal<HashSet<NodeIndex>>,
+    constant_line_width: Signal<bool>,
+    position_preview: ReadSignal<Option<(NodeIndex, (f64, f64))>>,
 ) {
     create_effect(move |_| {
         // Track all dependencies
@@ -1013,9 +1108,16 @@ fn setup_render_effect(
         let _ = preview_path.get();
         let _ = station_dialog_clicked_position.get();
         let _ = selected_stations.get();
-        let _ = selection_box_start.get();
-        let _ = selection_box_end.get();
+        let _ = selection_outline.get();
         let _ = theme.get();
+        let _ = highlighted_journey_edges.get();
+        let _ = critical_sections.get();
+        let _ = min_curve_radius.get();
+        let _ = train_markers.get();
+        let _ = grid_spacing.get();
+        let _ = focused_stations.get();
+        let _ = constant_line_width.get();
+        let _ = position_preview.get();
 
         // Throttle renders using requestAnimationFrame
         if !render_requested.get_untracked() {
@@ -1027,11 +1129,16 @@ fn setup_render_effect(
 
                 let Some(canvas) = canvas_ref.get_untracked() else { return };
 
-                let current_graph = graph.get_untracked();
+                let mut current_graph = graph.get_untracked();
+                // Typed coordinates are drawn without touching the real graph until saved
+                if let Some((station_idx, position)) = position_preview.get_untracked() {
+                    current_graph.set_station_position(station_idx, position);
+                }
                 let current_lines = lines.get_untracked();
                 let current_show_lines = show_lines.get_untracked();
                 let current_hide_unscheduled = hide_unscheduled_in_line_mode.get_untracked();
                 let current_line_gap_width = line_gap_width.get_untracked();
+                let current_min_curve_radius = min_curve_radius.get_untracked();
                 let zoom = zoom_level.get_untracked();
                 let pan_x = pan_offset_x.get_untracked();
                 let pan_y = pan_offset_y.get_untracked();
@@ -1042,11 +1149,11 @@ fn setup_render_effect(
                 let preview_station_pos = station_dialog_clicked_position.get_untracked();
                 let current_selected_stations = selected_stations.get_untracked();
                 let current_theme = theme.get_untracked();
-                let current_selection_box = if let (Some(start), Some(end)) = (selection_box_start.get_untracked(), selection_box_end.get_untracked()) {
-                    Some((start, end))
-                } else {
-                    None
-                };
+                let current_train_markers = train_markers.get_untracked();
+                let current_grid_spacing = grid_spacing.get_untracked();
+                let current_focused_stations = focused_stations.get_untracked();
+                let line_zoom = line_renderer::line_width_zoom(zoom, constant_line_width.get_untracked());
+                let current_selection_outline = selection_outline.get_untracked();
 
                 // Update topology cache if needed
                 update_cache_if_needed(topology_cache, &current_graph);
@@ -1083,16 +1190,18 @@ fn setup_render_effect(
                 let highlighted_edges: HashSet<EdgeIndex> = if matches!(current_edit_mode, EditMode::CreatingView) {
                     current_preview.unwrap_or_default().into_iter().collect()
                 } else if !selected_stations.is_empty() {
-                    // Highlight edges between selected nodes
-                    find_edges_between_nodes(&current_graph, &selected_stations)
+                    // Highlight edges between selected nodes, plus the highlighted journey's path
+                    let mut edges = find_edges_between_nodes(&current_graph, &selected_stations);
+                    edges.extend(highlighted_journey_edges.get_untracked());
+                    edges
                 } else {
-                    HashSet::new()
+                    highlighted_journey_edges.get_untracked()
                 };
 
                 // Pass cache to renderer (mutable to update label cache)
                 topology_cache.with_value(|cache| {
                     let mut cache_mut = cache.borrow_mut();
-                    renderer::draw_infrastructure(&ctx, &current_graph, &current_lines, current_show_lines, current_hide_unscheduled, (f64::from(container_width), f64::from(container_height)), zoom, pan_x, pan_y, &selected_stations, &highlighted_edges, &mut cache_mut, zooming, preview_station_pos, current_selection_box, current_theme, current_line_gap_width);
+                    renderer::draw_infrastructure(&ctx, &current_graph, &current_lines, current_show_lines, current_hide_unscheduled, (f64::from(container_width), f64::from(container_height)), zoom, pan_x, pan_y, &selected_stations, &highlighted_edges, &critical_sections.get_untracked(), &mut cache_mut, zooming, preview_station_pos, &current_selection_outline, current_theme, current_line_gap_width, current_min_curve_radius, &current_train_markers, current_grid_spacing, &current_focused_stations, line_zoom);
                 });
             });
 
@@ -1114,12 +1223,13 @@ fn toggle_station_selection(
 }
 
 /// Apply position updates to all selected stations during drag
+/// A `grid_size` of zero moves the stations freely without snapping
 fn update_dragged_stations(
     graph: &mut RailwayGraph,
     stations: &[NodeIndex],
     dx: f64,
     dy: f64,
-    snap_to_grid: bool,
+    grid_size: f64,
 ) -> (f64, f64) {
     if stations.is_empty() {
         return (0.0, 0.0);
@@ -1127,9 +1237,7 @@ fn update_dragged_stations(
 
     // Calculate the actual offset by using the first station as a reference
     // This ensures all stations move together and snap to grid properly
-    let (offset_x, offset_y) = if snap_to_grid {
-        const GRID_SIZE: f64 = 30.0;
-
+    let (offset_x, offset_y) = if grid_size > 0.0 {
         // Get the reference station's current position
         let Some((ref_old_x, ref_old_y)) = graph.get_station_position(stations[0]) else {
             return (0.0, 0.0);
@@ -1140,8 +1248,7 @@ fn update_dragged_stations(
         let ref_new_y = ref_old_y + dy;
 
         // Snap the new position to grid
-        let ref_snapped_x = (ref_new_x / GRID_SIZE).round() * GRID_SIZE;
-        let ref_snapped_y = (ref_new_y / GRID_SIZE).round() * GRID_SIZE;
+        let (ref_snapped_x, ref_snapped_y) = auto_layout::snap_to_grid(ref_new_x, ref_new_y, grid_size);
 
         // Calculate the actual offset that was applied after snapping
         (
@@ -1182,7 +1289,18 @@ fn handle_multi_select_mouse_down(
     set_drag_start_pos: WriteSignal<Option<(f64, f64)>>,
     set_selection_box_start: WriteSignal<Option<(f64, f64)>>,
     set_selection_box_end: WriteSignal<Option<(f64, f64)>>,
+    set_lasso_path: Option<WriteSignal<Vec<(f64, f64)>>>,
 ) {
+    // Start a lasso when one is requested, otherwise a selection box
+    let start_selection_area = move || {
+        if let Some(set_lasso_path) = set_lasso_path {
+            set_lasso_path.set(vec![(world_x, world_y)]);
+        } else {
+            set_selection_box_start.set(Some((world_x, world_y)));
+            set_selection_box_end.set(Some((world_x, world_y)));
+        }
+    };
+
     if let Some((min_x, max_x, min_y, max_y)) = selection_bounds.get() {
         // We have bounds - check if click is inside
         let inside = world_x >= min_x && world_x <= max_x && world_y >= min_y && world_y <= max_y;
@@ -1194,8 +1312,7 @@ fn handle_multi_select_mouse_down(
             // Clicked outside - clear and start new selection
             set_selected_stations.set(Vec::new());
             set_selection_bounds.set(None);
-            set_selection_box_start.set(Some((world_x, world_y)));
-            set_selection_box_end.set(Some((world_x, world_y)));
+            start_selection_area();
         }
     } else {
         // No bounds - check if clicking station or empty space
@@ -1206,14 +1323,36 @@ fn handle_multi_select_mouse_down(
             toggle_station_selection(station_idx, &mut current_selection);
             set_selected_stations.set(current_selection);
         } else {
-            // Empty space - start new selection box
+            // Empty space - start new selection box or lasso
             set_selected_stations.set(Vec::new());
-            set_selection_box_start.set(Some((world_x, world_y)));
-            set_selection_box_end.set(Some((world_x, world_y)));
+            start_selection_area();
         }
     }
 }
 
+/// Corners of the selection box spanned by two opposite points
+fn box_outline(start: (f64, f64), end: (f64, f64)) -> Vec<(f64, f64)> {
+    vec![start, (end.0, start.1), end, (start.0, end.1)]
+}
+
+/// Nodes whose positions fall inside a lasso outline
+fn nodes_in_polygon(graph: &RailwayGraph, polygon: &[(f64, f64)]) -> Vec<NodeIndex> {
+    graph.graph.node_indices()
+        .filter(|&idx| {
+            graph.get_station_position(idx)
+                .is_some_and(|position| crate::geometry::point_in_polygon(position, polygon))
+        })
+        .collect()
+}
+
+fn clear_highlight_on_empty_click(graph: &RailwayGraph, world_x: f64, world_y: f64, highlighted: HighlightedJourney) {
+    if hit_detection::find_station_at_position(graph, world_x, world_y).is_none()
+        && hit_detection::find_track_at_position(graph, world_x, world_y).is_none()
+    {
+        highlighted.set_journey_id.set(None);
+    }
+}
+
 #[allow(clippy::type_complexity, clippy::too_many_arguments, clippy::too_many_lines)]
 fn create_event_handlers(
     canvas_ref: leptos::NodeRef<leptos::html::Canvas>,
@@ -1234,6 +1373,7 @@ fn create_event_handlers(
     set_dragging_station: WriteSignal<Option<NodeIndex>>,
     set_is_over_station: WriteSignal<bool>,
     set_is_over_track: WriteSignal<bool>,
+    set_hovered_track: WriteSignal<Option<(EdgeIndex, f64, f64)>>,
     auto_layout_enabled: ReadSignal<bool>,
     space_pressed: ReadSignal<bool>,
     viewport: &canvas_viewport::ViewportSignals,
@@ -1251,6 +1391,8 @@ fn create_event_handlers(
     set_selection_box_start: WriteSignal<Option<(f64, f64)>>,
     selection_box_end: ReadSignal<Option<(f64, f64)>>,
     set_selection_box_end: WriteSignal<Option<(f64, f64)>>,
+    lasso_path: ReadSignal<Vec<(f64, f64)>>,
+    set_lasso_path: WriteSignal<Vec<(f64, f64)>>,
     selection_bounds: ReadSignal<Option<(f64, f64, f64, f64)>>,
     set_selection_bounds: WriteSignal<Option<(f64, f64, f64, f64)>>,
     dragging_selection: ReadSignal<bool>,
@@ -1264,6 +1406,7 @@ fn create_event_handlers(
     let pan_offset_y = viewport.pan_offset_y;
     let is_panning = viewport.is_panning;
     let viewport_copy = *viewport;
+    let highlighted_journey = use_context::<HighlightedJourney>();
 
     let handle_mouse_down = move |ev: MouseEvent| {
         if let Some(canvas_elem) = canvas_ref.get() {
@@ -1283,12 +1426,16 @@ fn create_event_handlers(
 
             // Handle clicks while Add Station dialog is open
             if show_add_station.get() && is_single_click {
-                let snapped_position = auto_layout::snap_to_grid(world_x, world_y);
-                set_station_dialog_clicked_position.set(Some(snapped_position));
-
-                // Check if clicking on a track segment
+                // Check if clicking on a track segment; keep the raw position there
+                // so the station can be projected onto the track precisely
                 let current_graph = graph.get();
                 let clicked_segment = hit_detection::find_track_at_position(&current_graph, world_x, world_y);
+                let clicked_position = if clicked_segment.is_some() {
+                    (world_x, world_y)
+                } else {
+                    auto_layout::snap_to_grid(world_x, world_y, settings.get_untracked().grid_size)
+                };
+                set_station_dialog_clicked_position.set(Some(clicked_position));
                 set_station_dialog_clicked_segment.set(clicked_segment);
                 return;
             }
@@ -1327,7 +1474,7 @@ fn create_event_handlers(
                         view_creation_callbacks(clicked_node);
                     }
                 }
-                EditMode::None => {
+                EditMode::None | EditMode::LassoSelect => {
                     // Don't start selection box or drag if space is pressed (panning mode)
                     if space_pressed.get() {
                         return;
@@ -1341,12 +1488,18 @@ fn create_event_handlers(
                         return;
                     }
 
+                    // Clicking empty space clears the highlighted journey
+                    if let Some(highlighted) = highlighted_journey {
+                        clear_highlight_on_empty_click(&graph.get(), world_x, world_y, highlighted);
+                    }
+
                     // Multi-select mode (only when NOT editing a station)
                     handle_multi_select_mouse_down(
                         world_x, world_y,
                         selection_bounds, graph, selected_stations, set_selected_stations,
                         set_selection_bounds, set_dragging_selection, set_drag_start_pos,
-                        set_selection_box_start, set_selection_box_end
+                        set_selection_box_start, set_selection_box_end,
+                        (current_mode == EditMode::LassoSelect).then_some(set_lasso_path)
                     );
                 }
                 _ => {}
@@ -1388,7 +1541,7 @@ fn create_event_handlers(
                 let stations = selected_stations.get();
 
                 // Get the actual snapped offset that was applied
-                let (applied_offset_x, applied_offset_y) = update_dragged_stations(&mut current_graph, &stations, dx, dy, true);
+                let (applied_offset_x, applied_offset_y) = update_dragged_stations(&mut current_graph, &stations, dx, dy, settings.get_untracked().grid_size);
 
                 set_graph.set(current_graph.clone());
                 // Only advance drag_start by the actual amount moved (snapped)
@@ -1413,13 +1566,25 @@ fn create_event_handlers(
 
                 // Snap to grid if autolayout is enabled
                 let position = if auto_layout_enabled.get() {
-                    auto_layout::snap_to_grid(world_x, world_y)
+                    auto_layout::snap_to_grid(world_x, world_y, settings.get_untracked().grid_size)
                 } else {
                     (world_x, world_y)
                 };
 
                 current_graph.set_station_position(station_idx, position);
                 set_graph.set(current_graph);
+            } else if !lasso_path.with(Vec::is_empty) {
+                // Extend the lasso and select what it encloses so far
+                let zoom = zoom_level.get();
+                let point = screen_to_world(x, y, zoom, pan_offset_x.get(), pan_offset_y.get());
+                let far_enough = lasso_path.with(|path| {
+                    path.last().is_none_or(|last| (point.0 - last.0).hypot(point.1 - last.1) * zoom >= LASSO_POINT_SPACING)
+                });
+                if far_enough {
+                    set_lasso_path.update(|path| path.push(point));
+                    let new_selection = graph.with(|graph| lasso_path.with(|path| nodes_in_polygon(graph, path)));
+                    set_selected_stations.set(new_selection);
+                }
             } else if let Some(start) = selection_box_start.get() {
                 // Update selection box while dragging
                 let zoom = zoom_level.get();
@@ -1465,7 +1630,7 @@ fn create_event_handlers(
                 };
                 handle_mouse_move_hover_detection(
                     x, y, viewport_state,
-                    graph, set_is_over_station, set_is_over_track, topology_cache
+                    graph, set_is_over_station, set_is_over_track, set_hovered_track, topology_cache
                 );
             }
         }
@@ -1494,6 +1659,14 @@ fn create_event_handlers(
             set_selection_box_end.set(None);
         }
 
+        // Finalize lasso, bounding the enclosed stations for future drag detection
+        if !lasso_path.with(Vec::is_empty) {
+            graph.with(|graph| {
+                crate::components::multi_select_toolbar::update_selection_bounds(graph, &selected_stations.get(), set_selection_bounds);
+            });
+            set_lasso_path.set(Vec::new());
+        }
+
         if let Some(station_idx) = dragging_station.get() {
             if let Some(canvas_elem) = canvas_ref.get() {
                 let canvas: &web_sys::HtmlCanvasElement = &canvas_elem;
@@ -1507,11 +1680,12 @@ fn create_event_handlers(
                 let (world_x, world_y) = screen_to_world(x, y, zoom, pan_x, pan_y);
 
                 if auto_layout_enabled.get() {
-                    apply_drag_snap(graph, set_graph, station_idx, world_x, world_y);
+                    let current_settings = settings.get_untracked();
+                    apply_drag_snap(graph, set_graph, station_idx, world_x, world_y, current_settings.angle_snap_degrees, current_settings.grid_size);
                 } else {
                     // When autolayout is off, just snap to grid without branch reorientation
                     let mut current_graph = graph.get();
-                    let snapped = auto_layout::snap_to_grid(world_x, world_y);
+                    let snapped = auto_layout::snap_to_grid(world_x, world_y, settings.get_untracked().grid_size);
                     current_graph.set_station_position(station_idx, snapped);
                     set_graph.set(current_graph);
                 }
@@ -1621,6 +1795,40 @@ fn create_event_handlers(
     (handle_mouse_down, handle_mouse_move, handle_mouse_up, handle_double_click, handle_context_menu, handle_wheel)
 }
 
+#[component]
+fn DuplicateStationNamesWarning(graph: ReadSignal<RailwayGraph>) -> impl IntoView {
+    let duplicates = create_memo(move |_| {
+        graph.with(|g| g.duplicate_station_names().into_iter().map(|(name, _)| name).collect::<Vec<_>>())
+    });
+
+    view! {
+        <Show when=move || duplicates.with(|names| !names.is_empty())>
+            <div class="duplicate-station-names-warning">
+                <i class="fa-solid fa-triangle-exclamation"></i>
+                {move || format!("Multiple stations share a name: {}", duplicates.get().join(", "))}
+            </div>
+        </Show>
+    }
+}
+
+/// Shown after an import, or any edit, leaves track sections with no tracks or only one way
+#[component]
+fn SuspiciousTracksWarning(graph: ReadSignal<RailwayGraph>) -> impl IntoView {
+    let count = create_memo(move |_| graph.with(|g| g.suspicious_track_configs().len()));
+
+    view! {
+        <Show when=move || count.get() != 0>
+            <div class="suspicious-tracks-warning">
+                <i class="fa-solid fa-triangle-exclamation"></i>
+                {move || match count.get() {
+                    1 => "1 track section has no tracks or only runs one way".to_string(),
+                    n => format!("{n} track sections have no tracks or only run one way"),
+                }}
+            </div>
+        </Show>
+    }
+}
+
 #[component]
 #[must_use]
 #[allow(clippy::too_many_lines)]
@@ -1641,6 +1849,10 @@ pub fn InfrastructureView(
     #[prop(optional)]
     on_open_project_manager: Option<leptos::Callback<()>>,
     sidebar_visible: ReadSignal<bool>,
+    #[prop(optional)]
+    on_checkpoint: Option<leptos::Callback<String>>,
+    #[prop(optional)]
+    train_journeys: Option<ReadSignal<std::collections::HashMap<uuid::Uuid, TrainJourney>>>,
 ) -> impl IntoView {
     // Get user settings from context
     let (user_settings, _) = use_context::<(ReadSignal<UserSettings>, WriteSignal<UserSettings>)>()
@@ -1653,6 +1865,37 @@ pub fn InfrastructureView(
     // Get theme signal
     let theme = use_theme();
 
+    // Busiest single-track edges, highlighted on request
+    let (show_critical_sections, set_show_critical_sections) = create_signal(false);
+    let critical_section_edges = create_memo(move |_| {
+        let Some(journeys) = train_journeys.filter(|_| show_critical_sections.get()) else {
+            return HashSet::new();
+        };
+        let journeys: Vec<TrainJourney> = journeys.with(|journeys| journeys.values().cloned().collect());
+        graph.with(|graph| crate::train_journey::critical_sections(&journeys, graph).into_iter().collect::<HashSet<_>>())
+    });
+
+    // Edges of the journey highlighted from the time graph
+    let highlighted_journey = use_context::<HighlightedJourney>();
+    let highlighted_journey_edges = Signal::derive(move || {
+        let (Some(highlighted), Some(journeys)) = (highlighted_journey, train_journeys) else {
+            return HashSet::new();
+        };
+        highlighted.journey_id.get()
+            .and_then(|id| journeys.with(|journeys| journeys.get(&id).map(TrainJourney::edge_indices)))
+            .unwrap_or_default()
+    });
+
+    // Trains in service at the time shown on the time graph
+    let visualization_time = use_context::<VisualizationTime>();
+    let train_markers = create_memo(move |_| {
+        let (Some(current_time), Some(journeys)) = (visualization_time, train_journeys) else {
+            return Vec::new();
+        };
+        let time = current_time.time.get();
+        graph.with(|graph| journeys.with(|journeys| collect_train_markers(graph, journeys.values(), time)))
+    });
+
     let canvas_ref = create_node_ref::<leptos::html::Canvas>();
     let (auto_layout_enabled, set_auto_layout_enabled) = create_signal(true);
     let initial_show_lines = initial_viewport.as_ref().is_some_and(|v| v.show_lines);
@@ -1666,6 +1909,7 @@ pub fn InfrastructureView(
     let (show_add_station, set_show_add_station) = create_signal(false);
     let (last_added_station, set_last_added_station) = create_signal(None::<NodeIndex>);
     let (editing_station, set_editing_station) = create_signal(None::<NodeIndex>);
+    let (position_preview, set_position_preview) = create_signal(None::<(NodeIndex, (f64, f64))>);
     let (editing_junction, set_editing_junction) = create_signal(None::<NodeIndex>);
     let (editing_track, set_editing_track) = create_signal(None::<EdgeIndex>);
     let (show_delete_confirmation, set_show_delete_confirmation) = create_signal(false);
@@ -1676,6 +1920,7 @@ pub fn InfrastructureView(
     let (show_multi_delete_confirmation, set_show_multi_delete_confirmation) = create_signal(false);
     let (is_over_station, set_is_over_station) = create_signal(false);
     let (is_over_track, set_is_over_track) = create_signal(false);
+    let (hovered_track, set_hovered_track) = create_signal(None::<(EdgeIndex, f64, f64)>);
     let (dragging_station, set_dragging_station) = create_signal(None::<NodeIndex>);
     let (station_dialog_clicked_position, set_station_dialog_clicked_position) = create_signal(None::<(f64, f64)>);
     let (station_dialog_clicked_segment, set_station_dialog_clicked_segment) = create_signal(None::<EdgeIndex>);
@@ -1684,6 +1929,11 @@ pub fn InfrastructureView(
     let (selected_stations, set_selected_stations) = create_signal(Vec::<NodeIndex>::new());
     let (selection_box_start, set_selection_box_start) = create_signal(None::<(f64, f64)>);
     let (selection_box_end, set_selection_box_end) = create_signal(None::<(f64, f64)>);
+    let (lasso_path, set_lasso_path) = create_signal(Vec::<(f64, f64)>::new());
+    let selection_outline = Signal::derive(move || match (selection_box_start.get(), selection_box_end.get()) {
+        (Some(start), Some(end)) => box_outline(start, end),
+        _ => lasso_path.get(),
+    });
     let (selection_bounds, set_selection_bounds) = create_signal(None::<(f64, f64, f64, f64)>); // (min_x, max_x, min_y, max_y)
     let (dragging_selection, set_dragging_selection) = create_signal(false);
     let (drag_start_pos, set_drag_start_pos) = create_signal(None::<(f64, f64)>);
@@ -1805,6 +2055,80 @@ pub fn InfrastructureView(
         })
     });
 
+    // Share the stations on screen with the time graph and highlight the ones it has in view
+    let focus = use_context::<FocusedStations>();
+    if let Some(focus) = focus {
+        create_effect(move |_| {
+            let zoom = zoom_level.get();
+            let pan = (pan_offset_x.get(), pan_offset_y.get());
+            let Some(dims) = canvas_dimensions.get() else { return };
+            let visible = graph.with(|graph| {
+                let bounds = canvas_viewport::visible_world_bounds(zoom, pan, dims);
+                let stations = canvas_viewport::nodes_within_bounds(graph, bounds);
+                let positioned = graph.graph.node_indices().filter(|&idx| graph.get_station_position(idx).is_some()).count();
+                (!stations.is_empty() && stations.len() < positioned).then_some(stations)
+            });
+            focus.publish(FocusSource::Infrastructure, visible);
+        });
+    }
+    let focused_stations = Signal::derive(move || {
+        focus.map(|focus| focus.highlighted_in(FocusSource::Infrastructure)).unwrap_or_default()
+    });
+
+    let zoom_to_fit = leptos::Callback::new(move |()| {
+        let Some(dims) = canvas_dimensions.get_untracked() else { return };
+        let fitted = canvas_viewport::fit_to_bounds(&graph.get_untracked(), dims);
+        leptos::batch(move || {
+            viewport.set_zoom_level.set(fitted.zoom_level);
+            set_pan_offset_x.set(fitted.pan_offset_x);
+            set_pan_offset_y.set(fitted.pan_offset_y);
+        });
+    });
+
+    // Export the whole network at a multiple of the canvas size, whatever the current viewport
+    let export_png = leptos::Callback::new(move |scale: f64| {
+        let Some((width, height)) = canvas_dimensions.get_untracked() else { return };
+        // Canvas dimensions are non-negative and far below u32::MAX even at the largest scale
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let pixel_size = ((width * scale).round() as u32, (height * scale).round() as u32);
+        let layout = export::export_layout(&graph.get_untracked(), pixel_size, scale);
+        let options = export::ExportOptions {
+            show_lines: show_lines.get_untracked(),
+            hide_unscheduled_in_line_mode: hide_unscheduled_in_line_mode.get_untracked(),
+            theme: theme.get_untracked(),
+            line_gap_width: line_gap_width.get_untracked(),
+            min_curve_radius: settings.with_untracked(|s| s.min_curve_radius),
+            constant_line_width: settings.with_untracked(|s| s.constant_line_width),
+        };
+        let result = graph.with_untracked(|graph| lines.with_untracked(|lines| {
+            export::export_png(graph, lines, options, &layout, "infrastructure.png")
+        }));
+        if let Err(e) = result {
+            leptos::logging::error!("Failed to export PNG: {}", e);
+        }
+    });
+
+    let export_geojson = leptos::Callback::new(move |()| {
+        let geojson = graph.with_untracked(|graph| settings.with_untracked(|s| {
+            crate::geometry::to_geojson(graph, s.geo_reference.as_ref())
+        }));
+        if let Err(e) = crate::storage::trigger_download(geojson.as_bytes(), "infrastructure.geojson") {
+            leptos::logging::error!("Failed to export GeoJSON: {}", e);
+        }
+    });
+
+    let focus_station = leptos::Callback::new(move |station_idx: NodeIndex| {
+        let Some(dims) = canvas_dimensions.get_untracked() else { return };
+        let Some(position) = graph.with_untracked(|g| g.get_station_position(station_idx)) else { return };
+        let zoom = viewport.zoom_level.get_untracked().max(STATION_SEARCH_MIN_ZOOM);
+        let (pan_x, pan_y) = canvas_viewport::pan_to_center(position, zoom, dims);
+        leptos::batch(move || {
+            viewport.set_zoom_level.set(zoom);
+            set_pan_offset_x.set(pan_x);
+            set_pan_offset_y.set(pan_y);
+        });
+    });
+
     // Setup keyboard listeners for Space and WASD
     canvas_viewport::setup_keyboard_listeners(
         set_space_pressed,
@@ -1883,15 +2207,15 @@ pub fn InfrastructureView(
     };
 
     let (handle_add_station, handle_add_stations_batch, handle_edit_station, handle_delete_station, confirm_delete_station, handle_edit_track, handle_delete_track, handle_edit_junction, handle_delete_junction) =
-        create_handler_callbacks(graph, set_graph, lines, set_lines, set_show_add_station, set_last_added_station, set_editing_station, set_editing_junction, set_editing_track, set_delete_affected_lines, set_station_to_delete, set_delete_station_name, set_delete_bypass_info, set_show_delete_confirmation, station_to_delete, station_dialog_clicked_position, station_dialog_clicked_segment, set_station_dialog_clicked_position, set_station_dialog_clicked_segment, settings, set_selected_stations, set_selection_bounds);
+        create_handler_callbacks(graph, set_graph, lines, set_lines, set_show_add_station, set_last_added_station, set_editing_station, set_editing_junction, set_editing_track, set_delete_affected_lines, set_station_to_delete, set_delete_station_name, set_delete_bypass_info, set_show_delete_confirmation, station_to_delete, station_dialog_clicked_position, station_dialog_clicked_segment, set_station_dialog_clicked_position, set_station_dialog_clicked_segment, settings, set_settings, set_selected_stations, set_selection_bounds, on_checkpoint);
 
-    setup_render_effect(graph, lines, show_lines, hide_unscheduled_in_line_mode, line_gap_width, zoom_level, pan_offset_x, pan_offset_y, canvas_ref, edit_mode, selected_station, view_creation.waypoints, view_creation.preview_path, topology_cache, is_zooming, render_requested, set_render_requested, station_dialog_clicked_position, selected_stations, selection_box_start, selection_box_end, theme);
+    setup_render_effect(graph, lines, show_lines, hide_unscheduled_in_line_mode, line_gap_width, zoom_level, pan_offset_x, pan_offset_y, canvas_ref, edit_mode, selected_station, view_creation.waypoints, view_creation.preview_path, topology_cache, is_zooming, render_requested, set_render_requested, station_dialog_clicked_position, selected_stations, selection_outline, theme, highlighted_journey_edges, critical_section_edges, Signal::derive(move || settings.with(|s| s.min_curve_radius)), train_markers, Signal::derive(move || settings.with(|s| (s.show_grid && s.grid_size > 0.0).then_some(s.grid_size))), focused_stations, Signal::derive(move || settings.with(|s| s.constant_line_width)), position_preview);
 
     let (handle_mouse_down, handle_mouse_move, handle_mouse_up, handle_double_click, handle_context_menu, handle_wheel) = create_event_handlers(
         canvas_ref, edit_mode, set_edit_mode, selected_station, set_selected_station, view_creation_callbacks.on_add_waypoint.clone(), graph, set_graph,
         lines, set_lines,
         editing_station, set_editing_station, set_editing_junction, set_editing_track,
-        dragging_station, set_dragging_station, set_is_over_station, set_is_over_track,
+        dragging_station, set_dragging_station, set_is_over_station, set_is_over_track, set_hovered_track,
         auto_layout_enabled, space_pressed, &viewport, topology_cache, set_is_zooming,
         show_add_station, station_dialog_clicked_position, set_station_dialog_clicked_position, set_station_dialog_clicked_segment,
         settings,
@@ -1899,6 +2223,7 @@ pub fn InfrastructureView(
         selected_stations, set_selected_stations,
         selection_box_start, set_selection_box_start,
         selection_box_end, set_selection_box_end,
+        lasso_path, set_lasso_path,
         selection_bounds, set_selection_bounds,
         dragging_selection, set_dragging_selection,
         drag_start_pos, set_drag_start_pos,
@@ -1908,6 +2233,11 @@ pub fn InfrastructureView(
     // Setup keyboard shortcuts for multi-select operations
     let shortcuts = leptos::create_memo(move |_| user_settings.get().keyboard_shortcuts);
     crate::models::setup_shortcut_handler(is_capturing_shortcut, shortcuts, move |action_id, _ev| {
+        if action_id == "paste_stations" {
+            crate::components::multi_select_toolbar::paste_stations(graph, set_graph, set_selected_stations, set_selection_bounds);
+            return;
+        }
+
         // Only handle multi-select shortcuts when stations are selected
         if selected_stations.get().is_empty() {
             return;
@@ -1920,6 +2250,7 @@ pub fn InfrastructureView(
                     graph,
                     set_graph,
                     set_selection_bounds,
+                    settings.get_untracked().grid_size,
                 );
             }
             "multi_select_rotate_ccw" => {
@@ -1928,6 +2259,7 @@ pub fn InfrastructureView(
                     graph,
                     set_graph,
                     set_selection_bounds,
+                    settings.get_untracked().grid_size,
                 );
             }
             "multi_select_align" => {
@@ -1939,6 +2271,9 @@ pub fn InfrastructureView(
                     settings,
                 );
             }
+            "multi_select_copy" => {
+                crate::components::multi_select_toolbar::copy_selected_stations(selected_stations, graph);
+            }
             "multi_select_delete" => {
                 if !selected_stations.get().is_empty() {
                     set_show_multi_delete_confirmation.set(true);
@@ -1987,6 +2322,7 @@ pub fn InfrastructureView(
         set_dragging_station.set(None);
         set_is_over_station.set(false);
         set_is_over_track.set(false);
+        set_hovered_track.set(None);
     };
 
     view! {
@@ -2004,6 +2340,18 @@ pub fn InfrastructureView(
                     on:contextmenu=handle_context_menu
                     style=move || get_canvas_cursor_style(dragging_station, edit_mode, editing_station, is_over_station, is_over_track, is_panning, space_pressed, dragging_selection, is_over_selection)
                 />
+                <TrackTooltip hovered_track=hovered_track graph=graph/>
+                <div class="infrastructure-warnings">
+                    <DuplicateStationNamesWarning graph=graph/>
+                    <SuspiciousTracksWarning graph=graph/>
+                </div>
+                <StationSearch graph=graph on_select=focus_station/>
+                <InfrastructureMinimap
+                    graph=graph
+                    viewport=viewport
+                    canvas_dimensions=canvas_dimensions
+                    theme=theme
+                />
                 <InfrastructureToolbar
                     auto_layout_enabled=auto_layout_enabled
                     toggle_auto_layout=toggle_auto_layout
@@ -2013,6 +2361,14 @@ pub fn InfrastructureView(
                     edit_mode=edit_mode
                     set_edit_mode=set_edit_mode
                     set_selected_station=set_selected_station
+                    on_zoom_to_fit=zoom_to_fit
+                    on_export_png=export_png
+                    on_export_geojson=export_geojson
+                    on_paste=leptos::Callback::new(move |()| {
+                        crate::components::multi_select_toolbar::paste_stations(graph, set_graph, set_selected_stations, set_selection_bounds);
+                    })
+                    show_critical_sections=show_critical_sections
+                    set_show_critical_sections=set_show_critical_sections
                 />
                 <LineSettingsPanel
                     show_lines=show_lines
@@ -2038,6 +2394,7 @@ pub fn InfrastructureView(
                             graph,
                             set_graph,
                             set_selection_bounds,
+                            settings.get_untracked().grid_size,
                         );
                     })
                     on_rotate_ccw=leptos::Callback::new(move |()| {
@@ -2046,6 +2403,7 @@ pub fn InfrastructureView(
                             graph,
                             set_graph,
                             set_selection_bounds,
+                            settings.get_untracked().grid_size,
                         );
                     })
                     on_align=leptos::Callback::new(move |()| {
@@ -2096,6 +2454,9 @@ pub fn InfrastructureView(
                             set_show_multi_delete_confirmation.set(true);
                         }
                     })
+                    on_copy=leptos::Callback::new(move |()| {
+                        crate::components::multi_select_toolbar::copy_selected_stations(selected_stations, graph);
+                    })
                     on_set_label_position=leptos::Callback::new(move |pos: Option<crate::components::infrastructure_canvas::station_renderer::LabelPosition>| {
                         crate::components::multi_select_toolbar::set_label_position_for_selected(
                             selected_stations,
@@ -2129,6 +2490,9 @@ pub fn InfrastructureView(
                 on_save=handle_edit_station
                 on_delete=handle_delete_station
                 graph=graph
+                train_journeys=train_journeys
+                set_position_preview=set_position_preview
+                auto_layout_enabled=auto_layout_enabled
                 on_update_track_defaults=Rc::new(move |edge_idx: EdgeIndex, source_platform: Option<usize>, target_platform: Option<usize>| {
                     let mut current_graph = graph.get();
                     if let Some(track_segment) = current_graph.graph.edge_weight_mut(edge_idx) {
@@ -2191,6 +2555,7 @@ pub fn InfrastructureView(
                         set_graph,
                         lines,
                         set_lines,
+                        set_settings,
                         set_selected_stations,
                     );
                     set_show_multi_delete_confirmation.set(false);
@@ -2232,3 +2597,178 @@ pub fn InfrastructureView(
         </div>
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Electrification;
+
+    fn single_track_graph() -> (RailwayGraph, EdgeIndex) {
+        let mut graph = RailwayGraph::new();
+        let a = graph.add_or_get_station("A".to_string());
+        let b = graph.add_or_get_station("B".to_string());
+        let edge = graph.add_track(a, b, vec![Track { direction: TrackDirection::Bidirectional }]);
+        (graph, edge)
+    }
+
+    fn track_edit(gradient_permille: Option<f64>) -> TrackEdit {
+        TrackEdit {
+            tracks: vec![Track { direction: TrackDirection::Bidirectional }],
+            distance: Some(4.5),
+            electrification: Electrification::Overhead,
+            gradient_permille,
+            speed_limit_kph: None,
+            handedness: None,
+            notes: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_split_point_on_segment_projects_and_avoids_endpoints() {
+        let (fraction, position) = split_point_on_segment(Some((25.0, 8.0)), (0.0, 0.0), (100.0, 0.0));
+        assert!((fraction - 0.25).abs() < 1e-9);
+        assert_eq!(position, (25.0, 0.0));
+
+        let (fraction, position) = split_point_on_segment(Some((-3.0, 1.0)), (0.0, 0.0), (100.0, 0.0));
+        assert!((fraction - MIN_SPLIT_FRACTION).abs() < 1e-9);
+        assert!(position.0 > 0.0);
+
+        let (fraction, _) = split_point_on_segment(None, (0.0, 0.0), (100.0, 0.0));
+        assert!((fraction - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_split_segment_divides_distance_proportionally() {
+        let (mut graph, edge) = single_track_graph();
+        graph.graph[edge].distance = Some(8.0);
+        let station = graph.add_or_get_station("C".to_string());
+
+        let (first, second) = split_segment_and_insert_node(
+            edge, station, &mut graph, &mut [], false, crate::models::TrackHandedness::default(), 0.25,
+        );
+
+        assert_eq!(graph.graph[first].distance, Some(2.0));
+        assert_eq!(graph.graph[second].distance, Some(6.0));
+    }
+
+    #[test]
+    fn test_split_segment_keeps_block_for_both_halves() {
+        let (mut graph, edge) = single_track_graph();
+        graph.assign_edges_to_block(3, &[edge]);
+        let station = graph.add_or_get_station("C".to_string());
+
+        let (first, second) = split_segment_and_insert_node(
+            edge, station, &mut graph, &mut [], false, crate::models::TrackHandedness::default(), 0.5,
+        );
+
+        assert_eq!(graph.block_for_edge(first), Some(3));
+        assert_eq!(graph.block_for_edge(second), Some(3));
+        assert_eq!(graph.block_sections[0].edges.len(), 2);
+    }
+
+    #[test]
+    fn test_deleted_track_leaves_no_block_for_reused_edge() {
+        let runtime = leptos::create_runtime();
+        let (mut graph, edge) = single_track_graph();
+        graph.assign_edges_to_block(1, &[edge]);
+        let (graph, set_graph) = create_signal(graph);
+        let (lines, set_lines) = create_signal(Vec::<Line>::new());
+        let (_, set_editing_track) = create_signal(Some(edge));
+
+        delete_track_handler(edge, graph, set_graph, lines, set_lines, set_editing_track);
+        assert!(graph.get_untracked().block_sections.is_empty());
+
+        let mut current = graph.get_untracked();
+        let a = current.get_station_index("A").expect("station A");
+        let c = current.add_or_get_station("C".to_string());
+        let new_edge = current.add_track(a, c, vec![Track { direction: TrackDirection::Bidirectional }]);
+        assert_eq!(new_edge, edge);
+        assert_eq!(current.block_for_edge(new_edge), None);
+
+        runtime.dispose();
+    }
+
+    #[test]
+    fn test_apply_track_edit_persists_electrification() {
+        let (mut graph, edge) = single_track_graph();
+        let mut lines: Vec<Line> = Vec::new();
+
+        apply_track_edit(&mut graph, &mut lines, edge, track_edit(None)).expect("edit should be accepted");
+
+        let segment = graph.get_track(edge).expect("track should exist");
+        assert_eq!(segment.electrification, Electrification::Overhead);
+        assert_eq!(segment.distance, Some(4.5));
+    }
+
+    #[test]
+    fn test_edit_track_handler_round_trips_gradient() {
+        let runtime = leptos::create_runtime();
+        let (graph, edge) = single_track_graph();
+        let (graph, set_graph) = create_signal(graph);
+        let (lines, set_lines) = create_signal(Vec::<Line>::new());
+        let (_, set_editing_track) = create_signal(Some(edge));
+
+        edit_track_handler(edge, track_edit(Some(-12.5)), graph, set_graph, lines, set_lines, set_editing_track);
+        let saved = graph.get_untracked().get_track(edge).and_then(|t| t.gradient_permille);
+        assert_eq!(saved, Some(-12.5));
+
+        edit_track_handler(edge, track_edit(Some(150.0)), graph, set_graph, lines, set_lines, set_editing_track);
+        let unchanged = graph.get_untracked().get_track(edge).and_then(|t| t.gradient_permille);
+        assert_eq!(unchanged, Some(-12.5));
+
+        runtime.dispose();
+    }
+
+    #[test]
+    fn test_track_note_survives_edit_and_export() {
+        let runtime = leptos::create_runtime();
+        let (graph, edge) = single_track_graph();
+        let (graph, set_graph) = create_signal(graph);
+        let (lines, set_lines) = create_signal(Vec::<Line>::new());
+        let (_, set_editing_track) = create_signal(Some(edge));
+
+        let edit = TrackEdit { notes: "Temporary speed restriction until June".to_string(), ..track_edit(None) };
+        edit_track_handler(edge, edit, graph, set_graph, lines, set_lines, set_editing_track);
+
+        let mut project = crate::models::Project::new_with_name("Notes".to_string());
+        project.graph = graph.get_untracked();
+        let bytes = crate::storage::serialize_project_to_bytes(&project).expect("project should serialize");
+        let restored = crate::storage::deserialize_project_from_bytes(&bytes).expect("project should deserialize");
+        let note = restored.graph.get_track(edge).map(|t| t.notes.clone());
+        assert_eq!(note.as_deref(), Some("Temporary speed restriction until June"));
+
+        runtime.dispose();
+    }
+
+    #[test]
+    fn test_edit_station_handler_applies_exact_position() {
+        let runtime = leptos::create_runtime();
+        let (graph, _) = single_track_graph();
+        let station = graph.get_station_index("A").expect("station A");
+        let (graph, set_graph) = create_signal(graph);
+        let (editing_station, set_editing_station) = create_signal(Some(station));
+
+        let edit = StationEdit {
+            name: "A".to_string(),
+            passing_loop: false,
+            is_depot: false,
+            min_dwell_secs: None,
+            conflict_margin_secs: None,
+            platforms: graph.get_untracked().graph[station].as_station().expect("station").platforms.clone(),
+            label_angle: 0.0,
+            label_offset: (0.0, 0.0),
+            position: Some((123.456, -78.9)),
+        };
+        edit_station_handler(station, edit, set_graph, set_editing_station);
+        assert_eq!(editing_station.get_untracked(), None);
+        assert_eq!(graph.get_untracked().graph[station].position(), Some((123.456, -78.9)));
+
+        let mut project = crate::models::Project::new_with_name("Survey".to_string());
+        project.graph = graph.get_untracked();
+        let bytes = crate::storage::serialize_project_to_bytes(&project).expect("project should serialize");
+        let restored = crate::storage::deserialize_project_from_bytes(&bytes).expect("project should deserialize");
+        assert_eq!(restored.graph.graph[station].position(), Some((123.456, -78.9)));
+
+        runtime.dispose();
+    }
+}
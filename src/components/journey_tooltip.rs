@@ -0,0 +1,59 @@
+use leptos::{component, IntoView, ReadSignal, SignalGet, SignalWith, view};
+use std::collections::HashMap;
+use crate::models::RailwayGraph;
+use crate::train_journey::TrainJourney;
+
+/// Arrival and departure as shown in the tooltip, collapsed to one time when the train doesn't wait
+fn format_stop_times(arrival: chrono::NaiveDateTime, departure: chrono::NaiveDateTime) -> String {
+    if arrival == departure {
+        departure.format("%H:%M").to_string()
+    } else {
+        format!("{} – {}", arrival.format("%H:%M"), departure.format("%H:%M"))
+    }
+}
+
+#[component]
+#[must_use]
+pub fn JourneyTooltip(
+    hovered_journey: ReadSignal<Option<(uuid::Uuid, f64, f64)>>,
+    train_journeys: ReadSignal<HashMap<uuid::Uuid, TrainJourney>>,
+    graph: ReadSignal<RailwayGraph>,
+) -> impl IntoView {
+    view! {
+        {move || {
+            let Some((journey_id, tooltip_x, tooltip_y)) = hovered_journey.get() else {
+                return view! { <div class="journey-tooltip-hidden"></div> }.into_view();
+            };
+            let Some(journey) = train_journeys.with(|journeys| journeys.get(&journey_id).cloned()) else {
+                return view! { <div class="journey-tooltip-hidden"></div> }.into_view();
+            };
+
+            // Junctions are passed through without a timetabled stop
+            let stops: Vec<(String, String)> = graph.with(|g| {
+                journey.station_times.iter()
+                    .filter_map(|(node_idx, arrival, departure)| {
+                        let station = g.graph.node_weight(*node_idx)?.as_station()?;
+                        Some((station.name.clone(), format_stop_times(*arrival, *departure)))
+                    })
+                    .collect()
+            });
+
+            view! {
+                <div
+                    class="conflict-tooltip journey-tooltip"
+                    style=format!("left: {}px; top: {}px;", tooltip_x + 10.0, tooltip_y + 10.0)
+                >
+                    <div class="journey-tooltip-title">{journey.train_number.clone()}</div>
+                    <table>
+                        {stops.into_iter().map(|(name, times)| view! {
+                            <tr>
+                                <td class="journey-tooltip-times">{times}</td>
+                                <td>{name}</td>
+                            </tr>
+                        }).collect::<Vec<_>>()}
+                    </table>
+                </div>
+            }.into_view()
+        }}
+    }
+}
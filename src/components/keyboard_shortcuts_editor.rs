@@ -208,6 +208,7 @@ pub fn KeyboardShortcutsEditor() -> impl IntoView {
                     let categories = vec![
                         (ShortcutCategory::Navigation, "Navigation"),
                         (ShortcutCategory::Infrastructure, "Infrastructure"),
+                        (ShortcutCategory::Lines, "Lines"),
                         (ShortcutCategory::Project, "Project"),
                     ];
 
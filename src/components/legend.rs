@@ -1,8 +1,128 @@
-use leptos::{component, view, Signal, IntoView, create_signal, SignalGet, SignalSet, event_target_checked, Portal, create_node_ref, html, SignalGetUntracked};
+use leptos::{component, view, Signal, IntoView, create_signal, SignalGet, SignalSet, SignalWith, event_target_checked, event_target_value, Portal, create_node_ref, html, SignalGetUntracked, For, use_context};
+use crate::components::graph_canvas::ReferenceOverlay;
+use crate::models::LegendEntry;
 
 const POPOVER_ESTIMATED_WIDTH: f64 = 300.0;
 const POPOVER_ESTIMATED_HEIGHT: f64 = 250.0;
 const POPOVER_SPACING: f64 = 8.0;
+const DEFAULT_ANNOTATION_COLOR: &str = "#888888";
+
+/// Line colour swatches and manual annotations shown in the legend
+#[component]
+fn LegendEntries(
+    entries: Signal<Vec<LegendEntry>>,
+    set_entries: impl Fn(Vec<LegendEntry>) + 'static + Copy,
+    on_regenerate: impl Fn() + 'static + Copy,
+) -> impl IntoView {
+    let (annotation_label, set_annotation_label) = create_signal(String::new());
+    let (annotation_color, set_annotation_color) = create_signal(DEFAULT_ANNOTATION_COLOR.to_string());
+
+    let add_annotation = move |_| {
+        let label = annotation_label.get_untracked().trim().to_string();
+        if label.is_empty() {
+            return;
+        }
+        let mut updated = entries.get_untracked();
+        updated.push(LegendEntry { label, color: annotation_color.get_untracked(), line_id: None });
+        set_entries(updated);
+        set_annotation_label.set(String::new());
+    };
+
+    let remove_entry = move |index: usize| {
+        let mut updated = entries.get_untracked();
+        if index < updated.len() {
+            updated.remove(index);
+            set_entries(updated);
+        }
+    };
+
+    view! {
+        <div class="legend-entries">
+            <div class="legend-entries-header">
+                <h4>"Legend"</h4>
+                <button
+                    class="regenerate-legend-button"
+                    on:click=move |_| on_regenerate()
+                    title="Rebuild line entries from the visible lines, keeping manual annotations"
+                >
+                    <i class="fa-solid fa-rotate"></i>
+                    " Regenerate legend"
+                </button>
+            </div>
+            <For
+                each=move || entries.get().into_iter().enumerate()
+                key=|(index, entry)| (*index, entry.label.clone(), entry.color.clone())
+                children=move |(index, entry)| {
+                    let is_manual = entry.is_manual();
+                    view! {
+                        <div class="legend-entry">
+                            <span class="legend-swatch" style=format!("background-color: {};", entry.color)></span>
+                            <span class="legend-entry-label">{entry.label}</span>
+                            {is_manual.then(|| view! {
+                                <button
+                                    class="remove-entry-button"
+                                    on:click=move |_| remove_entry(index)
+                                    title="Remove annotation"
+                                >
+                                    "×"
+                                </button>
+                            })}
+                        </div>
+                    }
+                }
+            />
+            <div class="legend-annotation-form">
+                <input
+                    type="color"
+                    prop:value=move || annotation_color.get()
+                    on:input=move |ev| set_annotation_color.set(event_target_value(&ev))
+                />
+                <input
+                    type="text"
+                    placeholder="Add annotation"
+                    prop:value=move || annotation_label.get()
+                    on:input=move |ev| set_annotation_label.set(event_target_value(&ev))
+                />
+                <button on:click=add_annotation title="Add annotation">
+                    <i class="fa-solid fa-plus"></i>
+                </button>
+            </div>
+        </div>
+    }
+}
+
+/// Visibility toggle for the reference timetable, shown only while one is loaded
+#[component]
+fn ReferenceTimetableToggle() -> impl IntoView {
+    let Some(overlay) = use_context::<ReferenceOverlay>() else {
+        return view! {}.into_view();
+    };
+    let reference_name = move || overlay.timetable.with(|t| t.as_ref().map(|t| t.name.clone()));
+
+    view! {
+        {move || reference_name().map(|name| view! {
+            <div class="legend-item">
+                <label class="legend-label">
+                    <input
+                        type="checkbox"
+                        checked=move || overlay.visible.get()
+                        on:change=move |ev| overlay.set_visible.set(event_target_checked(&ev))
+                    />
+                    <span class="legend-icon">"▨"</span>
+                    <span>"Reference Timetable"</span>
+                    <button
+                        class="remove-entry-button"
+                        on:click=move |_| overlay.set_project.set(None)
+                        title="Remove reference timetable"
+                    >
+                        "×"
+                    </button>
+                </label>
+                <p class="legend-description">{format!("Faded journeys from \"{name}\" for comparison")}</p>
+            </div>
+        })}
+    }.into_view()
+}
 
 #[component]
 pub fn Legend(
@@ -10,8 +130,13 @@ pub fn Legend(
     set_show_conflicts: impl Fn(bool) + 'static + Copy,
     show_line_blocks: Signal<bool>,
     set_show_line_blocks: impl Fn(bool) + 'static + Copy,
+    show_headway_bands: Signal<bool>,
+    set_show_headway_bands: impl Fn(bool) + 'static + Copy,
     spacing_mode: Signal<crate::models::SpacingMode>,
     set_spacing_mode: impl Fn(crate::models::SpacingMode) + 'static + Copy,
+    entries: Signal<Vec<LegendEntry>>,
+    set_entries: impl Fn(Vec<LegendEntry>) + 'static + Copy,
+    on_regenerate: impl Fn() + 'static + Copy,
 ) -> impl IntoView {
     let (is_open, set_is_open) = create_signal(false);
     let (popover_position, set_popover_position) = create_signal((0.0, 0.0));
@@ -123,6 +248,21 @@ pub fn Legend(
                                         <p class="legend-description">"Show reservation block when hovering over train lines"</p>
                                     </div>
 
+                                    <div class="legend-item">
+                                        <label class="legend-label">
+                                            <input
+                                                type="checkbox"
+                                                checked=move || show_headway_bands.get()
+                                                on:change=move |ev| {
+                                                    set_show_headway_bands(event_target_checked(&ev));
+                                                }
+                                            />
+                                            <span class="legend-icon">"▥"</span>
+                                            <span>"Headway Bands"</span>
+                                        </label>
+                                        <p class="legend-description">"Band of the minimum separation around each train, highlighted where trains run too close"</p>
+                                    </div>
+
                                     <div class="legend-item">
                                         <label class="legend-label">
                                             <input
@@ -142,7 +282,11 @@ pub fn Legend(
                                         </label>
                                         <p class="legend-description">"Scale vertical spacing by track distance (if available)"</p>
                                     </div>
+
+                                    <ReferenceTimetableToggle />
                                 </div>
+
+                                <LegendEntries entries=entries set_entries=set_entries on_regenerate=on_regenerate />
                             </div>
                         </Portal>
                     }.into_view()
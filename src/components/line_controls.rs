@@ -1,5 +1,5 @@
-use leptos::{component, view, ReadSignal, WriteSignal, IntoView, create_signal, create_memo, SignalGet, SignalUpdate, SignalSet, For, Signal, Callback, Callable, SignalWith, SignalGetUntracked, event_target_value};
-use crate::models::{Line, LineFolder, RailwayGraph, GraphView, LineSortMode};
+use leptos::{component, view, ReadSignal, WriteSignal, IntoView, create_signal, create_memo, SignalGet, SignalUpdate, SignalSet, For, Signal, Callback, Callable, SignalWith, SignalGetUntracked, event_target_value, provide_context, use_context, spawn_local, StoredValue};
+use crate::models::{Line, LineFolder, RailwayGraph, GraphView, LineSortMode, UserSettings, ColorVisionMode, recolor_lines, apply_palette, assign_accessible_colors, TOGGLE_LINE_SHORTCUTS};
 use crate::components::line_editor::LineEditor;
 use crate::components::confirmation_dialog::ConfirmationDialog;
 use crate::components::delete_folder_confirmation::DeleteFolderConfirmation;
@@ -7,10 +7,181 @@ use crate::components::edit_folder_dialog::EditFolderDialog;
 use crate::components::line_sort_selector::LineSortSelector;
 use crate::components::window::Window;
 use crate::components::button::Button;
-use crate::components::tree_item::{TreeItem, DraggedItem, DropZone, find_item_context, build_tree};
-use std::collections::HashSet;
+use crate::components::tree_item::{TreeItem, DraggedItem, DropZone, find_item_context, build_tree, lines_in_tree_order};
+use crate::train_journey::MissingJourneysReason;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 
+/// Visibility of every line before the solo shortcut hid the unfocused ones
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SoloVisibility {
+    saved: Option<HashMap<uuid::Uuid, bool>>,
+}
+
+impl SoloVisibility {
+    #[must_use]
+    pub fn is_active(&self) -> bool {
+        self.saved.is_some()
+    }
+
+    /// Show only the `focused` lines, or restore the saved visibility if already soloed.
+    /// Does nothing when there is nothing to solo.
+    pub fn toggle(&mut self, lines: &mut [Line], focused: &[uuid::Uuid]) {
+        if let Some(saved) = self.saved.take() {
+            for line in lines.iter_mut() {
+                if let Some(&visible) = saved.get(&line.id) {
+                    line.visible = visible;
+                }
+            }
+            return;
+        }
+        if focused.is_empty() {
+            return;
+        }
+        self.saved = Some(lines.iter().map(|line| (line.id, line.visible)).collect());
+        for line in lines.iter_mut() {
+            line.visible = focused.contains(&line.id);
+        }
+    }
+}
+
+/// Solo state shared by every view's sidebar so switching tabs doesn't lose it
+#[derive(Clone, Copy)]
+pub struct LineSolo(pub StoredValue<SoloVisibility>);
+
+/// Visible lines that generated no journeys, with the likely reason, for the sidebar's warning badges
+#[derive(Clone, Copy)]
+pub struct MissingJourneys(pub ReadSignal<HashMap<uuid::Uuid, MissingJourneysReason>>);
+
+/// Flip the visibility of the `index`th line (0-based) in sidebar order
+fn toggle_line_at(tree: &[TreeItem], index: usize, set_lines: WriteSignal<Vec<Line>>) {
+    let Some(line_id) = lines_in_tree_order(tree).get(index).copied() else {
+        return;
+    };
+    set_lines.update(|lines| {
+        if let Some(line) = lines.iter_mut().find(|l| l.id == line_id) {
+            line.visible = !line.visible;
+        }
+    });
+}
+
+/// Lines selected for bulk actions, in the order they were selected
+#[derive(Clone, Copy)]
+pub struct LineSelection {
+    pub selected: ReadSignal<Vec<uuid::Uuid>>,
+    pub set_selected: WriteSignal<Vec<uuid::Uuid>>,
+}
+
+impl LineSelection {
+    /// Add the line to the selection, or remove it if already selected
+    pub fn toggle(&self, line_id: uuid::Uuid) {
+        self.set_selected.update(|selected| {
+            if let Some(pos) = selected.iter().position(|id| *id == line_id) {
+                selected.remove(pos);
+            } else {
+                selected.push(line_id);
+            }
+        });
+    }
+}
+
+#[component]
+fn BulkRecolorBar(
+    selection: LineSelection,
+    set_lines: WriteSignal<Vec<Line>>,
+) -> impl IntoView {
+    let (bulk_color, set_bulk_color) = create_signal(String::from("#808080"));
+
+    view! {
+        {move || {
+            let count = selection.selected.with(Vec::len);
+            (count > 0).then(|| view! {
+                <div class="bulk-recolor-bar">
+                    <span>{format!("{count} selected")}</span>
+                    <input
+                        type="color"
+                        value=bulk_color
+                        on:input=move |ev| set_bulk_color.set(event_target_value(&ev))
+                        title="Color to apply"
+                    />
+                    <Button
+                        on_click=Callback::new(move |_| {
+                            let ids = selection.selected.get_untracked();
+                            set_lines.update(|lines_vec| recolor_lines(&ids, &bulk_color.get_untracked(), lines_vec));
+                        })
+                        title="Apply color to selected lines"
+                    >
+                        <i class="fa-solid fa-fill-drip"></i>
+                    </Button>
+                    <Button
+                        on_click=Callback::new(move |_| {
+                            let ids = selection.selected.get_untracked();
+                            set_lines.update(|lines_vec| apply_palette(&ids, lines_vec));
+                        })
+                        title="Apply palette to selected lines"
+                    >
+                        <i class="fa-solid fa-palette"></i>
+                    </Button>
+                    <Button
+                        on_click=Callback::new(move |_| selection.set_selected.set(Vec::new()))
+                        title="Clear selection"
+                    >
+                        <i class="fa-solid fa-xmark"></i>
+                    </Button>
+                </div>
+            })
+        }}
+    }
+}
+
+/// Recolor all lines from a color-blind-safe palette for the user's color vision mode
+#[component]
+fn AccessibleColorsControl(set_lines: WriteSignal<Vec<Line>>) -> impl IntoView {
+    let (user_settings, set_user_settings) = use_context::<(ReadSignal<UserSettings>, WriteSignal<UserSettings>)>()
+        .expect("UserSettings context not found");
+
+    let set_mode = move |value: String| {
+        let Some(mode) = ColorVisionMode::ALL.into_iter().find(|mode| mode.label() == value) else { return };
+        let settings_to_save = set_user_settings.try_update(|settings| {
+            settings.color_vision_mode = mode;
+            settings.clone()
+        });
+        let Some(settings_to_save) = settings_to_save else { return };
+        spawn_local(async move {
+            if let Err(e) = settings_to_save.save().await {
+                leptos::logging::error!("Failed to save color vision mode: {}", e);
+            }
+        });
+    };
+
+    view! {
+        <div class="accessible-colors-control">
+            <select
+                title="Color vision mode for accessible colors"
+                on:change=move |ev| set_mode(event_target_value(&ev))
+            >
+                {ColorVisionMode::ALL.into_iter().map(|mode| view! {
+                    <option
+                        value=mode.label()
+                        selected=move || user_settings.with(|s| s.color_vision_mode == mode)
+                    >
+                        {mode.label()}
+                    </option>
+                }).collect::<Vec<_>>()}
+            </select>
+            <Button
+                on_click=Callback::new(move |_| {
+                    let mode = user_settings.get_untracked().color_vision_mode;
+                    set_lines.update(|lines_vec| assign_accessible_colors(lines_vec, mode));
+                })
+                title="Assign accessible colors to all lines"
+            >
+                <i class="fa-solid fa-eye"></i>
+            </Button>
+        </div>
+    }
+}
+
 fn initialize_sort_indices_recursive(
     items: &[TreeItem],
     set_lines: WriteSignal<Vec<Line>>,
@@ -218,6 +389,11 @@ pub fn LineControls(
     let (folder_name, set_folder_name) = create_signal(String::from("New Folder"));
     let (folder_color, set_folder_color) = create_signal(String::from("#808080"));
 
+    // Ctrl/Cmd-click on a line toggles it in the bulk selection
+    let (selected_lines, set_selected_lines) = create_signal(Vec::<uuid::Uuid>::new());
+    let selection = LineSelection { selected: selected_lines, set_selected: set_selected_lines };
+    provide_context(selection);
+
     let tree = create_memo(move |_| {
         let lines_vec = lines.get();
         let folders_vec = folders.get();
@@ -225,6 +401,27 @@ pub fn LineControls(
         build_tree(lines_vec, folders_vec, sort_mode)
     });
 
+    // Alt+number toggles a line, and solo shows only the selected lines (or open editors)
+    let solo = use_context::<LineSolo>().map_or_else(|| StoredValue::new(SoloVisibility::default()), |s| s.0);
+    let (user_settings, _) = use_context::<(ReadSignal<UserSettings>, WriteSignal<UserSettings>)>()
+        .expect("UserSettings context not found");
+    let (is_capturing_shortcut, _) = use_context::<(ReadSignal<bool>, WriteSignal<bool>)>()
+        .expect("is_capturing_shortcut context not found");
+    let shortcuts = create_memo(move |_| user_settings.get().keyboard_shortcuts);
+    crate::models::setup_shortcut_handler(is_capturing_shortcut, shortcuts, move |action_id, ev| {
+        if action_id == "solo_line" {
+            ev.prevent_default();
+            let mut focused = selected_lines.get_untracked();
+            if focused.is_empty() {
+                focused = open_editors.get_untracked().into_iter().collect();
+            }
+            set_lines.update(|lines| solo.update_value(|state| state.toggle(lines, &focused)));
+        } else if let Some(index) = TOGGLE_LINE_SHORTCUTS.iter().position(|&(id, _, _)| id == action_id) {
+            ev.prevent_default();
+            toggle_line_at(&tree.get_untracked(), index, set_lines);
+        }
+    });
+
     // Initialize sort indices when switching to Manual mode
     leptos::create_effect(move |prev_mode| {
         let current_mode = settings.with(|s| s.line_sort_mode);
@@ -246,7 +443,9 @@ pub fn LineControls(
                 >
                     <i class="fa-solid fa-folder-plus"></i>
                 </Button>
+                <AccessibleColorsControl set_lines=set_lines />
             </div>
+            <BulkRecolorBar selection=selection set_lines=set_lines />
             <div class="line-controls"
                 on:dragover=move |ev| {
                     ev.prevent_default();
@@ -320,8 +519,8 @@ pub fn LineControls(
                                     set_lines.update(|lines_vec| {
                                         if let Some(line) = lines_vec.iter().find(|l| l.id == id) {
                                             let mut duplicated = line.duplicate();
-                                            // Assign sort_index if in Manual mode
-                                            if settings.with(|s| s.line_sort_mode == LineSortMode::Manual) {
+                                            // Assign a distinct sort_index if in Manual mode or the original had one
+                                            if duplicated.sort_index.is_some() || settings.with(|s| s.line_sort_mode == LineSortMode::Manual) {
                                                 #[allow(clippy::cast_precision_loss)]
                                                 let max_sort_index = lines_vec
                                                     .iter()
@@ -519,3 +718,53 @@ pub fn LineControls(
         />
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn named_lines(names: &[&str]) -> Vec<Line> {
+        let names: Vec<String> = names.iter().map(ToString::to_string).collect();
+        Line::create_from_ids(&names, 0)
+    }
+
+    #[test]
+    fn test_line_index_follows_sidebar_order() {
+        let mut lines = named_lines(&["Charlie", "Alpha", "Bravo"]);
+        let ids: Vec<uuid::Uuid> = lines.iter().map(|l| l.id).collect();
+
+        let tree = build_tree(lines.clone(), Vec::new(), LineSortMode::Alphabetical);
+        assert_eq!(lines_in_tree_order(&tree), [ids[1], ids[2], ids[0]]);
+
+        // Lines inside a folder count in the folder's place, even when it is collapsed
+        let mut folder = LineFolder::new("Zulu".to_string(), "#808080".to_string());
+        folder.collapsed = true;
+        lines[1].folder_id = Some(folder.id);
+        let tree = build_tree(lines, vec![folder], LineSortMode::Alphabetical);
+        let order = lines_in_tree_order(&tree);
+        assert_eq!(order, [ids[2], ids[0], ids[1]]);
+        assert_eq!(order.get(3), None);
+    }
+
+    #[test]
+    fn test_solo_and_restore() {
+        let mut lines = named_lines(&["A", "B", "C"]);
+        lines[2].visible = false;
+        let focused = [lines[0].id];
+        let mut solo = SoloVisibility::default();
+
+        solo.toggle(&mut lines, &focused);
+        assert!(solo.is_active());
+        assert_eq!(lines.iter().map(|l| l.visible).collect::<Vec<_>>(), [true, false, false]);
+
+        // Pressing again restores what was visible before, including hidden lines
+        solo.toggle(&mut lines, &focused);
+        assert!(!solo.is_active());
+        assert_eq!(lines.iter().map(|l| l.visible).collect::<Vec<_>>(), [true, true, false]);
+
+        // Nothing focused leaves everything alone
+        solo.toggle(&mut lines, &[]);
+        assert!(!solo.is_active());
+        assert_eq!(lines.iter().map(|l| l.visible).collect::<Vec<_>>(), [true, true, false]);
+    }
+}
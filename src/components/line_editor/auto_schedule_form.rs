@@ -1,10 +1,10 @@
 use crate::components::{
     days_of_week_selector::DaysOfWeekSelector,
-    duration_input::DurationInput,
+    duration_input::{DurationInput, OptionalDurationInput},
     time_input::TimeInput,
 };
 use crate::models::{Line, DaysOfWeek};
-use leptos::{component, view, IntoView, Signal, SignalGet, event_target_value, SignalGetUntracked, Callback, Callable};
+use leptos::{component, view, IntoView, Show, Signal, SignalGet, event_target_value, SignalGetUntracked, Callback, Callable};
 
 #[component]
 #[allow(clippy::too_many_lines)]
@@ -28,16 +28,32 @@ pub fn AutoScheduleForm(
                     }
                 }
             />
-            <small class="help-text">"Format: {line} for line ID, {seq:04} for sequence number"</small>
+            <small class="help-text">"Format: {line} for line ID, {seq:04} for sequence number, {hhmm} for departure time, {seq_stable} for a number that stays the same when other departures change"</small>
         </div>
 
+        // A service pattern set in the general tab replaces the line's own days
+        <Show when=move || edited_line.get().is_some_and(|l| l.service_pattern_id.is_none())>
+            <div class="form-group">
+                <label>"Operating days"</label>
+                <DaysOfWeekSelector
+                    days_of_week=Signal::derive(move || edited_line.get().map(|l| l.days_of_week).unwrap_or_default())
+                    set_days_of_week=move |days: DaysOfWeek| {
+                        if let Some(mut updated_line) = edited_line.get_untracked() {
+                            updated_line.days_of_week = days;
+                            on_update.call(updated_line);
+                        }
+                    }
+                />
+            </div>
+        </Show>
+
         <div class="form-group">
-            <label>"Operating days"</label>
-            <DaysOfWeekSelector
-                days_of_week=Signal::derive(move || edited_line.get().map(|l| l.days_of_week).unwrap_or_default())
-                set_days_of_week=move |days: DaysOfWeek| {
+            <label>"Frequency"</label>
+            <DurationInput
+                duration=Signal::derive(move || edited_line.get().map(|l| l.frequency).unwrap_or_default())
+                on_change=move |freq| {
                     if let Some(mut updated_line) = edited_line.get_untracked() {
-                        updated_line.days_of_week = days;
+                        updated_line.frequency = freq;
                         on_update.call(updated_line);
                     }
                 }
@@ -45,16 +61,20 @@ pub fn AutoScheduleForm(
         </div>
 
         <div class="form-group">
-            <label>"Frequency"</label>
-            <DurationInput
-                duration=Signal::derive(move || edited_line.get().map(|l| l.frequency).unwrap_or_default())
-                on_change=move |freq| {
+            <label>"Clock-face Interval"</label>
+            <OptionalDurationInput
+                duration=Signal::derive(move || edited_line.get().and_then(|l| l.clock_face_snap))
+                on_change=move |snap| {
                     if let Some(mut updated_line) = edited_line.get_untracked() {
-                        updated_line.frequency = freq;
+                        updated_line.clock_face_snap = snap;
                         on_update.call(updated_line);
                     }
                 }
             />
+            <small class="help-text">"Snap departures to multiples of this interval past the hour"</small>
+            {move || edited_line.get()
+                .and_then(|line| line.validate_clock_face_snap().err())
+                .map(|message| view! { <small class="clock-face-warning">{message}</small> })}
         </div>
 
         <div class="time-fields-row">
@@ -1,4 +1,4 @@
-use crate::models::{Line, RailwayGraph, RouteDirection, Stations};
+use crate::models::{Line, RailwayGraph, RouteDirection, RouteSegment, Routes, Stations};
 use leptos::*;
 
 #[component]
@@ -53,30 +53,47 @@ fn SecondStationSelect(
         .cloned()
         .collect();
 
-    let first_name_for_handler = first_name.clone();
-    let handle_selection = move |ev| {
-        let second_name = event_target_value(&ev);
-        if second_name.is_empty() {
-            return;
-        }
+    let (destination, set_destination) = create_signal(None::<String>);
+    let (via, set_via) = create_signal(Vec::<String>::new());
 
-        let Some(mut line) = edited_line.get_untracked() else { return };
-        let graph_data = graph.get();
-        let direction = route_direction.get();
-        let handedness = settings.get().track_handedness;
+    // Shortest route through the chosen via stops, recomputed as the selection changes
+    let first_name_for_proposal = first_name.clone();
+    let proposal = create_memo(move |_| {
+        let destination_name = destination.get()?;
+        let line = edited_line.get()?;
+        let handedness = settings.with(|s| s.track_handedness);
+        graph.with(|g| {
+            let from = g.get_station_index(&first_name_for_proposal)?;
+            let to = g.get_station_index(&destination_name)?;
+            let via_nodes: Vec<_> = via.get().iter().filter_map(|name| g.get_station_index(name)).collect();
+            line.propose_route(g, from, to, &via_nodes, handedness)
+        })
+    });
 
-        if line.create_route_between_stations(&first_name_for_handler, &second_name, &graph_data, direction, handedness) {
-            on_save(line);
-            first_station.set(None);
+    let handle_accept = move |_| {
+        let Some(route) = proposal.get_untracked() else { return };
+        let Some(mut line) = edited_line.get_untracked() else { return };
+        match route_direction.get_untracked() {
+            RouteDirection::Forward => {
+                line.forward_route = route;
+                line.apply_route_sync_if_enabled();
+            }
+            RouteDirection::Return => line.return_route = route,
         }
+        on_save(line);
+        first_station.set(None);
     };
 
+    let via_options = other_stations.clone();
     view! {
         <div class="empty-route-setup">
             <p class="no-stops">"First stop: " {first_name.clone()} ". Select destination:"</p>
             <select
                 class="station-select"
-                on:change=handle_selection
+                on:change=move |ev| {
+                    let name = event_target_value(&ev);
+                    set_destination.set(Some(name).filter(|n| !n.is_empty()));
+                }
             >
                 <option value="">{"Select destination..."}</option>
                 {other_stations.iter().map(|name| {
@@ -85,6 +102,23 @@ fn SecondStationSelect(
                     }
                 }).collect::<Vec<_>>()}
             </select>
+            <Show when=move || destination.get().is_some()>
+                <RouteProposal
+                    proposal=proposal
+                    route_direction=route_direction
+                    graph=graph
+                    via_options=via_options.clone()
+                    via=via
+                    set_via=set_via
+                />
+                <button
+                    class="accept-route-button"
+                    disabled=move || proposal.get().is_none()
+                    on:click=handle_accept.clone()
+                >
+                    "Use this route"
+                </button>
+            </Show>
             <button
                 class="cancel-button"
                 on:click=move |_| first_station.set(None)
@@ -95,6 +129,56 @@ fn SecondStationSelect(
     }
 }
 
+#[component]
+fn RouteProposal(
+    proposal: Memo<Option<Vec<RouteSegment>>>,
+    route_direction: RwSignal<RouteDirection>,
+    graph: ReadSignal<RailwayGraph>,
+    via_options: Vec<String>,
+    via: ReadSignal<Vec<String>>,
+    set_via: WriteSignal<Vec<String>>,
+) -> impl IntoView {
+    let stops = move || {
+        proposal.get().map(|route| {
+            graph.with(|g| g.get_stations_from_route(&route, route_direction.get()))
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>()
+                .join(" → ")
+        })
+    };
+
+    view! {
+        <p class="route-proposal">
+            {move || stops().unwrap_or_else(|| "No route connects these stations".to_string())}
+        </p>
+        <select
+            class="station-select"
+            on:change=move |ev| {
+                let name = event_target_value(&ev);
+                if !name.is_empty() {
+                    set_via.update(|stops| stops.push(name));
+                }
+            }
+            prop:value=""
+        >
+            <option value="">{"Route via..."}</option>
+            {via_options.iter().map(|name| {
+                view! {
+                    <option value=name.clone()>{name.clone()}</option>
+                }
+            }).collect::<Vec<_>>()}
+        </select>
+        <Show when=move || !via.get().is_empty()>
+            <p class="no-stops">
+                "Via: " {move || via.get().join(", ")}
+                " "
+                <button class="clear-via-button" on:click=move |_| set_via.set(Vec::new())>"Clear"</button>
+            </p>
+        </Show>
+    }
+}
+
 #[component]
 pub fn EmptyRouteSetup(
     first_station: RwSignal<Option<String>>,
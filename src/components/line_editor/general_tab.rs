@@ -1,7 +1,9 @@
 use crate::components::tab_view::TabPanel;
 use crate::components::duration_input::DurationInput;
+use crate::components::service_patterns_editor::ServicePatterns;
 use crate::models::{Line, LineStyle};
-use leptos::{component, view, ReadSignal, WriteSignal, RwSignal, IntoView, store_value, Signal, SignalGet, event_target_value, event_target_checked, SignalGetUntracked, SignalSet, Show};
+use chrono::NaiveDate;
+use leptos::{component, view, ReadSignal, WriteSignal, RwSignal, IntoView, store_value, use_context, Signal, SignalGet, SignalWith, event_target_value, event_target_checked, SignalGetUntracked, SignalSet, Show};
 use std::rc::Rc;
 
 /// Check if line view feature is enabled via localStorage
@@ -12,6 +14,11 @@ fn is_line_view_enabled() -> bool {
         .is_some()
 }
 
+/// Parse a date input's value, treating an empty field as no date
+fn parse_date_input(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()
+}
+
 #[component]
 #[allow(clippy::too_many_lines)]
 pub fn GeneralTab(
@@ -170,6 +177,83 @@ pub fn GeneralTab(
                     <p class="form-help">"Default wait time used when adding new stops to this line"</p>
                 </div>
 
+                <div class="form-group">
+                    <label>"Operating Period"</label>
+                    <div class="operating-period">
+                        <input
+                            type="date"
+                            class="valid-from-input"
+                            value=move || edited_line.get().and_then(|l| l.valid_from).map(|d| d.to_string()).unwrap_or_default()
+                            on:change={
+                                let on_save = on_save.get_value();
+                                move |ev| {
+                                    if let Some(mut updated_line) = edited_line.get_untracked() {
+                                        updated_line.valid_from = parse_date_input(&event_target_value(&ev));
+                                        set_edited_line.set(Some(updated_line.clone()));
+                                        on_save(updated_line);
+                                    }
+                                }
+                            }
+                        />
+                        <span>"to"</span>
+                        <input
+                            type="date"
+                            class="valid-to-input"
+                            value=move || edited_line.get().and_then(|l| l.valid_to).map(|d| d.to_string()).unwrap_or_default()
+                            on:change={
+                                let on_save = on_save.get_value();
+                                move |ev| {
+                                    if let Some(mut updated_line) = edited_line.get_untracked() {
+                                        updated_line.valid_to = parse_date_input(&event_target_value(&ev));
+                                        set_edited_line.set(Some(updated_line.clone()));
+                                        on_save(updated_line);
+                                    }
+                                }
+                            }
+                        />
+                    </div>
+                    <p class="form-help">
+                        "Real dates, matched against the reference week set in the project settings. Leave empty to run without limit"
+                    </p>
+                </div>
+
+                {use_context::<ServicePatterns>().map(|service_patterns| view! {
+                    <div class="form-group">
+                        <label>"Service Pattern"</label>
+                        <select
+                            class="service-pattern-select"
+                            on:change={
+                                let on_save = on_save.get_value();
+                                move |ev| {
+                                    if let Some(mut updated_line) = edited_line.get_untracked() {
+                                        updated_line.service_pattern_id = uuid::Uuid::parse_str(&event_target_value(&ev)).ok();
+                                        set_edited_line.set(Some(updated_line.clone()));
+                                        on_save(updated_line);
+                                    }
+                                }
+                            }
+                        >
+                            <option value="" selected=move || edited_line.get().and_then(|l| l.service_pattern_id).is_none()>
+                                "None (line's own operating days)"
+                            </option>
+                            {move || service_patterns.patterns.with(|patterns| patterns.iter().map(|pattern| {
+                                let id = pattern.id;
+                                view! {
+                                    <option
+                                        value=id.to_string()
+                                        selected=move || edited_line.get().and_then(|l| l.service_pattern_id) == Some(id)
+                                    >
+                                        {pattern.name.clone()}
+                                    </option>
+                                }
+                            }).collect::<Vec<_>>())}
+                        </select>
+                        <p class="form-help">
+                            "Run on a shared calendar from the project settings instead of the line's own operating days"
+                        </p>
+                    </div>
+                })}
+
                 <div class="form-group">
                     <label class="checkbox-label">
                         <input
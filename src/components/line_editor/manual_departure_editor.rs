@@ -1,10 +1,33 @@
 use crate::components::{days_of_week_selector::DaysOfWeekSelector, time_input::TimeInput, duration_input::OptionalDurationInput};
-use crate::models::{ManualDeparture, RailwayGraph, Stations, DaysOfWeek};
+use crate::models::{ManualDeparture, RailwayGraph, Stations, DaysOfWeek, RouteDirection};
 use leptos::{component, view, IntoView, create_signal, store_value, Signal, SignalGet, SignalUpdate, SignalGetUntracked, event_target_value};
 use crate::constants::BASE_DATE;
 
 #[component]
-#[allow(clippy::needless_pass_by_value)]
+fn DirectionSelect(
+    direction: Signal<RouteDirection>,
+    on_change: impl Fn(RouteDirection) + 'static,
+) -> impl IntoView {
+    view! {
+        <select
+            class="direction-input"
+            title="Route direction"
+            on:change=move |ev| {
+                if event_target_value(&ev) == "return" {
+                    on_change(RouteDirection::Return);
+                } else {
+                    on_change(RouteDirection::Forward);
+                }
+            }
+        >
+            <option value="forward" selected=move || direction.get() == RouteDirection::Forward>"Forward"</option>
+            <option value="return" selected=move || direction.get() == RouteDirection::Return>"Return"</option>
+        </select>
+    }
+}
+
+#[component]
+#[allow(clippy::needless_pass_by_value, clippy::too_many_lines)]
 pub fn ManualDepartureEditor(
     index: usize,
     #[prop(into)] departure: ManualDeparture,
@@ -96,6 +119,13 @@ pub fn ManualDepartureEditor(
                     }).collect::<Vec<_>>()
                 }
             </select>
+            <DirectionSelect
+                direction=Signal::derive(move || local_departure.get().direction)
+                on_change=move |direction| {
+                    set_local_departure.update(|dep| dep.direction = direction);
+                    on_update.with_value(|f| f(index, local_departure.get_untracked()));
+                }
+            />
             <button
                 class="remove-departure"
                 on:click=move |_| on_remove.with_value(|f| f(index))
@@ -1,9 +1,120 @@
 use super::ManualDepartureEditor;
-use crate::constants::DEFAULT_DEPARTURE_TIME;
-use crate::models::{Line, ManualDeparture, RailwayGraph, Stations, Tracks};
-use leptos::{component, view, ReadSignal, WriteSignal, IntoView, SignalGet, SignalGetUntracked, SignalSet, For, Signal, store_value};
+use crate::components::{days_of_week_selector::DaysOfWeekSelector, duration_input::DurationInput, time_input::TimeInput};
+use crate::constants::{BASE_DATE, DEFAULT_DEPARTURE_TIME};
+use crate::models::{DaysOfWeek, DepartureSeriesEnd, Line, ManualDeparture, RailwayGraph, Stations, Tracks};
+use chrono::Duration;
+use leptos::{component, view, ReadSignal, WriteSignal, IntoView, SignalGet, SignalGetUntracked, SignalSet, For, Signal, store_value, create_signal, event_target_value};
 use std::rc::Rc;
 
+const DEFAULT_SERIES_INTERVAL_MINUTES: i64 = 20;
+const DEFAULT_SERIES_COUNT: usize = 5;
+
+/// New forward departure running the line's whole forward route at `time`
+fn route_departure(line: &Line, graph: &RailwayGraph, time: chrono::NaiveDateTime, days_of_week: DaysOfWeek) -> ManualDeparture {
+    // Get first and last station NodeIndex from forward route
+    let from_station = line.forward_route.first()
+        .and_then(|segment| {
+            let edge_idx = petgraph::graph::EdgeIndex::new(segment.edge_index);
+            graph.get_track_endpoints(edge_idx).map(|(from, _)| from)
+        })
+        .unwrap_or_else(|| petgraph::graph::NodeIndex::new(0));
+
+    let to_station = line.forward_route.last()
+        .and_then(|segment| {
+            let edge_idx = petgraph::graph::EdgeIndex::new(segment.edge_index);
+            graph.get_track_endpoints(edge_idx).map(|(_, to)| to)
+        })
+        .unwrap_or_else(|| petgraph::graph::NodeIndex::new(1));
+
+    ManualDeparture {
+        id: uuid::Uuid::new_v4(),
+        time,
+        from_station,
+        to_station,
+        days_of_week,
+        train_number: None,
+        repeat_interval: None,
+        repeat_until: None,
+        direction: crate::models::RouteDirection::Forward,
+    }
+}
+
+/// Append a batch of regular-interval departures as individual, editable entries
+#[component]
+fn GenerateSeriesForm(
+    edited_line: ReadSignal<Option<Line>>,
+    set_edited_line: WriteSignal<Option<Line>>,
+    graph: ReadSignal<RailwayGraph>,
+    on_save: Rc<dyn Fn(Line)>,
+) -> impl IntoView {
+    let (start, set_start) = create_signal(DEFAULT_DEPARTURE_TIME);
+    let (interval, set_interval) = create_signal(Duration::minutes(DEFAULT_SERIES_INTERVAL_MINUTES));
+    let (count, set_count) = create_signal(DEFAULT_SERIES_COUNT.to_string());
+    let (until, set_until) = create_signal(String::new());
+    let (days_of_week, set_days_of_week) = create_signal(DaysOfWeek::ALL_DAYS);
+
+    let generate = move |_| {
+        let Some(mut updated_line) = edited_line.get_untracked() else { return };
+        let end = match crate::time::parse_time_hms(until.get_untracked().trim()) {
+            Ok(time) => DepartureSeriesEnd::Until(BASE_DATE.and_time(time)),
+            Err(_) => DepartureSeriesEnd::Count(count.get_untracked().trim().parse().unwrap_or(0)),
+        };
+        let template = route_departure(&updated_line, &graph.get_untracked(), start.get_untracked(), days_of_week.get_untracked());
+        let series = template.series(interval.get_untracked(), end);
+        if series.is_empty() {
+            return;
+        }
+        updated_line.manual_departures.extend(series);
+        set_edited_line.set(Some(updated_line.clone()));
+        on_save(updated_line);
+    };
+
+    view! {
+        <details class="generate-series">
+            <summary>"Generate series"</summary>
+            <div class="generate-series-fields">
+                <TimeInput
+                    label="First departure"
+                    value=Signal::derive(move || start.get())
+                    default_time="08:00"
+                    on_change=Box::new(move |time| set_start.set(time))
+                />
+                <div class="form-group">
+                    <label>"Every"</label>
+                    <DurationInput
+                        duration=Signal::derive(move || interval.get())
+                        on_change=move |duration| set_interval.set(duration)
+                    />
+                </div>
+                <div class="form-group">
+                    <label>"Count"</label>
+                    <input
+                        type="number"
+                        min="1"
+                        prop:value=move || count.get()
+                        on:input=move |ev| set_count.set(event_target_value(&ev))
+                    />
+                </div>
+                <div class="form-group">
+                    <label>"Until (optional)"</label>
+                    <input
+                        type="text"
+                        class="time-input"
+                        placeholder="Use count"
+                        prop:value=move || until.get()
+                        on:input=move |ev| set_until.set(event_target_value(&ev))
+                    />
+                </div>
+            </div>
+            <DaysOfWeekSelector
+                days_of_week=Signal::derive(move || days_of_week.get())
+                set_days_of_week=move |days: DaysOfWeek| set_days_of_week.set(days)
+            />
+            <button class="add-departure" on:click=generate>"Generate"</button>
+        </details>
+    }
+}
+
 #[component]
 pub fn ManualDeparturesList(
     edited_line: ReadSignal<Option<Line>>,
@@ -50,6 +161,9 @@ pub fn ManualDeparturesList(
     view! {
         <div class="form-group">
             <label>"Manual Departures"</label>
+            {move || edited_line.get()
+                .and_then(|line| line.validate_manual_departures().err())
+                .map(|message| view! { <p class="manual-departures-error">{message}</p> })}
             <div class="manual-departures-list">
                 <For
                     each=move || {
@@ -103,33 +217,7 @@ pub fn ManualDeparturesList(
                     let on_save = on_save.clone();
                     move |_| {
                         if let Some(mut updated_line) = edited_line.get_untracked() {
-                            let current_graph = graph.get();
-
-                            // Get first and last station NodeIndex from forward route
-                            let from_station = updated_line.forward_route.first()
-                                .and_then(|segment| {
-                                    let edge_idx = petgraph::graph::EdgeIndex::new(segment.edge_index);
-                                    current_graph.get_track_endpoints(edge_idx).map(|(from, _)| from)
-                                })
-                                .unwrap_or_else(|| petgraph::graph::NodeIndex::new(0));
-
-                            let to_station = updated_line.forward_route.last()
-                                .and_then(|segment| {
-                                    let edge_idx = petgraph::graph::EdgeIndex::new(segment.edge_index);
-                                    current_graph.get_track_endpoints(edge_idx).map(|(_, to)| to)
-                                })
-                                .unwrap_or_else(|| petgraph::graph::NodeIndex::new(1));
-
-                            let new_departure = ManualDeparture {
-                                id: uuid::Uuid::new_v4(),
-                                time: DEFAULT_DEPARTURE_TIME,
-                                from_station,
-                                to_station,
-                                days_of_week: crate::models::DaysOfWeek::ALL_DAYS,
-                                train_number: None,
-                                repeat_interval: None,
-                                repeat_until: None,
-                            };
+                            let new_departure = route_departure(&updated_line, &graph.get(), DEFAULT_DEPARTURE_TIME, DaysOfWeek::ALL_DAYS);
                             updated_line.manual_departures.push(new_departure);
                             set_edited_line.set(Some(updated_line.clone()));
                             on_save(updated_line);
@@ -139,6 +227,12 @@ pub fn ManualDeparturesList(
             >
                 "+ Add Departure"
             </button>
+            <GenerateSeriesForm
+                edited_line=edited_line
+                set_edited_line=set_edited_line
+                graph=graph
+                on_save=on_save
+            />
         </div>
     }
 }
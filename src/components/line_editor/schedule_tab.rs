@@ -1,9 +1,48 @@
 use super::{ManualDeparturesList, auto_schedule_form::AutoScheduleForm};
 use crate::components::tab_view::TabPanel;
 use crate::models::{Line, ScheduleMode, RailwayGraph};
-use leptos::{component, view, ReadSignal, WriteSignal, RwSignal, IntoView, store_value, Signal, SignalGet, event_target_checked, SignalGetUntracked, SignalSet, Show, Callback};
+use leptos::{component, view, ReadSignal, WriteSignal, RwSignal, IntoView, store_value, create_signal, Signal, SignalGet, event_target_checked, event_target_value, SignalGetUntracked, SignalSet, Show, Callback, Callable};
 use std::rc::Rc;
 
+/// Move every departure of the line earlier or later by a signed number of minutes
+#[component]
+fn ShiftScheduleControl(
+    edited_line: ReadSignal<Option<Line>>,
+    on_update: Callback<Line>,
+) -> impl IntoView {
+    let (minutes, set_minutes) = create_signal(String::new());
+
+    let shift = move |_| {
+        let Ok(delta) = minutes.get_untracked().trim().parse::<i64>() else { return };
+        if delta == 0 {
+            return;
+        }
+        if let Some(mut updated_line) = edited_line.get_untracked() {
+            updated_line.shift_schedule(chrono::Duration::minutes(delta));
+            on_update.call(updated_line);
+            set_minutes.set(String::new());
+        }
+    };
+
+    view! {
+        <div class="form-group">
+            <label>"Shift all departures"</label>
+            <div class="shift-schedule-row">
+                <input
+                    type="number"
+                    step="1"
+                    placeholder="Minutes, e.g. -5"
+                    prop:value=move || minutes.get()
+                    on:input=move |ev| set_minutes.set(event_target_value(&ev))
+                />
+                <button on:click=shift title="Move every departure by this many minutes; negative values move them earlier">
+                    "Shift"
+                </button>
+            </div>
+        </div>
+    }
+}
+
 #[component]
 pub fn ScheduleTab(
     edited_line: ReadSignal<Option<Line>>,
@@ -51,6 +90,17 @@ pub fn ScheduleTab(
                     />
                 </Show>
 
+                <ShiftScheduleControl
+                    edited_line=edited_line
+                    on_update=Callback::new({
+                        let on_save = on_save.get_value();
+                        move |updated_line: Line| {
+                            set_edited_line.set(Some(updated_line.clone()));
+                            on_save(updated_line);
+                        }
+                    })
+                />
+
                 <div class="manual-departures-section">
                     <ManualDeparturesList
                         edited_line=edited_line
@@ -62,8 +62,10 @@ fn delete_stop(
     }
 }
 
+/// Delete button at either end of the route, reverse toggle at the stops in between
 #[component]
-fn DeleteButton(
+fn StopAction(
+    index: usize,
     is_first: bool,
     is_last: bool,
     route_len: usize,
@@ -73,7 +75,17 @@ fn DeleteButton(
 ) -> impl IntoView {
     let can_delete = (is_first || is_last) && route_len > 1;
 
-    if can_delete {
+    if !is_first && !is_last {
+        view! {
+            <ReverseToggle
+                index=index
+                route_direction=route_direction
+                edited_line=edited_line
+                on_save=on_save
+            />
+        }
+        .into_view()
+    } else if can_delete {
         view! {
             <button
                 class="delete-stop-button"
@@ -94,6 +106,49 @@ fn DeleteButton(
     }
 }
 
+/// Toggle whether the train reverses at an intermediate stop, stored on the segment arriving there
+#[component]
+fn ReverseToggle(
+    index: usize,
+    route_direction: RouteDirection,
+    edited_line: ReadSignal<Option<Line>>,
+    on_save: Rc<dyn Fn(Line)>,
+) -> impl IntoView {
+    let reverses = move || edited_line.with(|line| {
+        line.as_ref().is_some_and(|l| {
+            let route = match route_direction {
+                RouteDirection::Forward => &l.forward_route,
+                RouteDirection::Return => &l.return_route,
+            };
+            route.get(index - 1).is_some_and(|segment| segment.reverses)
+        })
+    });
+
+    view! {
+        <button
+            class="reverse-stop-button"
+            class:active=reverses
+            on:click=move |_| {
+                let Some(mut updated_line) = edited_line.get_untracked() else { return };
+                let route = match route_direction {
+                    RouteDirection::Forward => &mut updated_line.forward_route,
+                    RouteDirection::Return => &mut updated_line.return_route,
+                };
+                let Some(segment) = route.get_mut(index - 1) else { return };
+                segment.reverses = !segment.reverses;
+
+                if matches!(route_direction, RouteDirection::Forward) {
+                    updated_line.apply_route_sync_if_enabled();
+                }
+                on_save(updated_line);
+            }
+            title=move || if reverses() { "Train reverses here; click to run through" } else { "Reverse here" }
+        >
+            <i class="fa-solid fa-rotate-left"></i>
+        </button>
+    }
+}
+
 #[component]
 pub fn StopRow(
     index: usize,
@@ -239,6 +294,7 @@ pub fn StopRow(
                                     index=index
                                     route_direction=route_direction
                                     edited_line=edited_line
+                                    graph=graph
                                     on_save=on_save.clone()
                                 />
                                 <WaitTimeColumn
@@ -249,7 +305,8 @@ pub fn StopRow(
                                     is_junction=is_junction
                                     is_first=is_first
                                 />
-                                <DeleteButton
+                                <StopAction
+                                    index=index
                                     is_first=is_first
                                     is_last=is_last
                                     route_len=route_len.unwrap_or(0)
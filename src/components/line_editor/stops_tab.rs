@@ -1,10 +1,22 @@
 use super::{
     empty_route_setup::EmptyRouteSetup, StationPosition, StationSelect, StopRow, TimeDisplayMode,
 };
+use crate::components::service_patterns_editor::ServicePatterns;
 use crate::components::tab_view::TabPanel;
 use crate::models::{Line, RailwayGraph, RouteDirection, Routes};
 use leptos::*;
 
+/// Warning naming the first route that uses the same track section twice
+fn repeated_edge_warning(line: &Line) -> Option<String> {
+    line.has_repeated_edges().map(|(direction, idx)| {
+        let name = match direction {
+            RouteDirection::Forward => "Forward",
+            RouteDirection::Return => "Return",
+        };
+        format!("{name} route uses the same track section twice at segment {}; journeys will loop", idx + 1)
+    })
+}
+
 fn get_column_header(mode: TimeDisplayMode) -> &'static str {
     match mode {
         TimeDisplayMode::Difference => "Travel Time to Next",
@@ -185,6 +197,80 @@ fn RouteStopsList(
     .into_view()
 }
 
+#[component]
+fn MirrorTimingsButton(
+    edited_line: ReadSignal<Option<Line>>,
+    route_direction: RwSignal<RouteDirection>,
+    on_save: std::rc::Rc<dyn Fn(Line)>,
+) -> impl IntoView {
+    let (warning, set_warning) = create_signal(None::<String>);
+    let visible = move || {
+        route_direction.get() == RouteDirection::Return
+            && edited_line.with(|line| line.as_ref().is_some_and(|l| !l.sync_routes))
+    };
+
+    view! {
+        <Show when=visible>
+            <button
+                class="mirror-timings-button"
+                title="Copy travel and wait times from the forward route"
+                on:click={
+                    let on_save = on_save.clone();
+                    move |_| {
+                        let Some(mut updated_line) = edited_line.get_untracked() else { return };
+                        match updated_line.mirror_forward_to_return() {
+                            Ok(()) => {
+                                set_warning.set(None);
+                                on_save(updated_line);
+                            }
+                            Err(message) => set_warning.set(Some(message)),
+                        }
+                    }
+                }
+            >
+                "Mirror timings from forward"
+            </button>
+            {move || warning.get().map(|message| view! {
+                <span class="mirror-timings-warning">{message}</span>
+            })}
+        </Show>
+    }
+}
+
+#[component]
+fn AutoAssignPlatformsButton(
+    edited_line: ReadSignal<Option<Line>>,
+    graph: ReadSignal<RailwayGraph>,
+    settings: ReadSignal<crate::models::ProjectSettings>,
+    on_save: std::rc::Rc<dyn Fn(Line)>,
+) -> impl IntoView {
+    let visible = move || edited_line.with(|line| line.as_ref().is_some_and(|l| !l.forward_route.is_empty()));
+    let service_patterns = use_context::<ServicePatterns>();
+
+    view! {
+        <Show when=visible>
+            <button
+                class="auto-platforms-button"
+                title="Pick platforms so this line's own trains don't share one at the same time"
+                on:click={
+                    let on_save = on_save.clone();
+                    move |_| {
+                        let Some(mut updated_line) = edited_line.get_untracked() else { return };
+                        let (handedness, reference_week) = settings.with_untracked(|s| (s.track_handedness, s.reference_week));
+                        let patterns = service_patterns
+                            .map(|service_patterns| service_patterns.patterns.get_untracked())
+                            .unwrap_or_default();
+                        graph.with_untracked(|g| updated_line.auto_assign_platforms(g, handedness, &patterns, reference_week));
+                        on_save(updated_line);
+                    }
+                }
+            >
+                "Auto-assign platforms"
+            </button>
+        </Show>
+    }
+}
+
 #[component]
 #[allow(clippy::too_many_lines)]
 pub fn StopsTab(
@@ -263,7 +349,33 @@ pub fn StopsTab(
                             TimeDisplayMode::Absolute => "Cumulative time from start",
                         }}
                     </span>
+                    <AutoAssignPlatformsButton
+                        edited_line=edited_line
+                        graph=graph
+                        settings=settings
+                        on_save=on_save_stored.get_value()
+                    />
+                    <MirrorTimingsButton
+                        edited_line=edited_line
+                        route_direction=route_direction
+                        on_save=on_save_stored.get_value()
+                    />
                 </div>
+                {move || edited_line.get()
+                    .and_then(|line| graph.with(|g| line.validate_route_directions(g).err()))
+                    .map(|message| view! { <small class="route-direction-warning">{message}</small> })}
+                {move || edited_line.with(|line| line.as_ref().and_then(repeated_edge_warning))
+                    .map(|message| view! { <small class="route-direction-warning">{message}</small> })}
+                {move || edited_line.get()
+                    .and_then(|line| graph.with(|g| line.validate_connectivity(g).err()))
+                    .map(|gaps| {
+                        let segments = gaps.iter().map(|idx| (idx + 1).to_string()).collect::<Vec<_>>().join(", ");
+                        view! {
+                            <small class="route-connectivity-error">
+                                {format!("Forward route is broken after segment {segments}: the next segment doesn't continue from the same station")}
+                            </small>
+                        }
+                    })}
                 <div class="stops-list">
                     <Show
                         when=move || route_is_empty.get()
@@ -1,9 +1,9 @@
 use super::stop_row::calculate_cumulative_seconds;
 use crate::components::{duration_input::OptionalDurationInput, time_input::TimeInput};
-use crate::models::{Line, RouteDirection};
+use crate::models::{Line, RailwayGraph, RouteDirection};
 use crate::constants::BASE_MIDNIGHT;
 use crate::time::format_duration_hms;
-use leptos::{component, view, ReadSignal, IntoView, SignalGetUntracked, SignalGet, Show};
+use leptos::{component, view, ReadSignal, IntoView, Signal, SignalGetUntracked, SignalGet, SignalWith, Show};
 use chrono::Duration;
 use std::rc::Rc;
 
@@ -89,15 +89,46 @@ fn update_absolute_time(
     }
 }
 
+/// Implied speed for a segment as display text and whether it exceeds the track's maximum speed.
+/// Returns `None` when the segment can't be checked (no distance or no duration).
+fn implied_speed_status(graph: &RailwayGraph, edge_index: usize, duration: Option<Duration>) -> Option<(String, bool)> {
+    let track = graph.graph.edge_weight(petgraph::graph::EdgeIndex::new(edge_index))?;
+    let duration = duration?;
+    let speed = track.implied_speed_kph(duration)?;
+    Some((format!("{speed:.0} km/h"), track.exceeds_speed_limit(duration)))
+}
+
+#[component]
+fn ImpliedSpeed(
+    graph: ReadSignal<RailwayGraph>,
+    edge_index: Signal<Option<usize>>,
+    duration: Signal<Option<Duration>>,
+) -> impl IntoView {
+    let status = Signal::derive(move || {
+        let edge_index = edge_index.get()?;
+        graph.with(|g| implied_speed_status(g, edge_index, duration.get()))
+    });
+
+    view! {
+        <span
+            class="implied-speed"
+            class:over-limit=move || status.get().is_some_and(|(_, over)| over)
+            title="Average speed implied by track distance and travel time"
+        >
+            {move || status.get().map_or_else(|| "— km/h".to_string(), |(text, _)| text)}
+        </span>
+    }
+}
+
 #[component]
 pub fn TimeColumn(
     time_mode: TimeDisplayMode,
     index: usize,
     route_direction: RouteDirection,
     edited_line: ReadSignal<Option<Line>>,
+    graph: ReadSignal<RailwayGraph>,
     on_save: Rc<dyn Fn(Line)>,
 ) -> impl IntoView {
-    use leptos::{Signal, SignalWith};
 
     // Compute all values reactively from edited_line
     let segment_duration = Signal::derive(move || {
@@ -119,6 +150,18 @@ pub fn TimeColumn(
         })
     });
 
+    let edge_index = Signal::derive(move || {
+        edited_line.with(|line| {
+            line.as_ref().and_then(|l| {
+                let route = match route_direction {
+                    RouteDirection::Forward => &l.forward_route,
+                    RouteDirection::Return => &l.return_route,
+                };
+                route.get(index).map(|s| s.edge_index)
+            })
+        })
+    });
+
     let cumulative_seconds = Signal::derive(move || {
         edited_line.with(|line| {
             line.as_ref().map_or(0, |l| {
@@ -174,6 +217,7 @@ pub fn TimeColumn(
                         }}
                     </Show>
                     <span class="cumulative-preview">{move || format_cumulative_time(cumulative_seconds.get())}</span>
+                    <ImpliedSpeed graph=graph edge_index=edge_index duration=segment_duration/>
                 </div>
             }.into_view()
         }
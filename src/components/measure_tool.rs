@@ -0,0 +1,64 @@
+use leptos::{component, view, IntoView, MaybeSignal, ReadSignal, Signal, SignalGet, SignalSet, WriteSignal};
+use crate::components::graph_canvas::measure::Measurement;
+
+/// Format a duration in hours as `H:MM:SS`
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn format_elapsed(hours: f64) -> String {
+    let total_seconds = (hours * 3600.0).round() as u64;
+    format!("{}:{:02}:{:02}", total_seconds / 3600, (total_seconds % 3600) / 60, total_seconds % 60)
+}
+
+#[component]
+#[must_use]
+pub fn MeasureTool(
+    is_measuring: ReadSignal<bool>,
+    set_is_measuring: WriteSignal<bool>,
+    /// Number of points clicked so far in the current measurement
+    point_count: Signal<usize>,
+    measurement: Signal<Option<Measurement>>,
+    /// Right offset in pixels, keeping the tool clear of the sidebar
+    #[prop(into)]
+    right_offset: MaybeSignal<f64>,
+) -> impl IntoView {
+    let readout = move || {
+        if !is_measuring.get() {
+            return None;
+        }
+        let text = match (point_count.get(), measurement.get()) {
+            (_, Some(measurement)) => {
+                let distance = measurement.distance_km
+                    .map_or_else(|| "— km".to_string(), |d| format!("{d:.2} km"));
+                let speed = measurement.speed_kmh
+                    .map_or_else(|| "— km/h".to_string(), |s| format!("{s:.0} km/h"));
+                format!("{} · {distance} · {speed}", format_elapsed(measurement.elapsed_hours))
+            }
+            (1, None) => "Click the second point".to_string(),
+            _ => "Click the first point".to_string(),
+        };
+        Some(view! { <span class="measure-readout">{text}</span> })
+    };
+
+    view! {
+        <div class="measure-tool" style=move || format!("right: {}px;", right_offset.get())>
+            <button
+                class=move || if is_measuring.get() { "measure-button active" } else { "measure-button" }
+                on:click=move |_| set_is_measuring.set(!is_measuring.get())
+                title="Measure time, distance and speed between two points"
+            >
+                <i class="fa-solid fa-ruler"></i>
+            </button>
+            {readout}
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_elapsed() {
+        assert_eq!(format_elapsed(0.25), "0:15:00");
+        assert_eq!(format_elapsed(1.5 + 10.0 / 3600.0), "1:30:10");
+    }
+}
@@ -6,6 +6,8 @@ pub mod alpha_disclaimer;
 pub mod canvas_controls_hint;
 pub mod canvas_viewport;
 pub mod changelog_popup;
+pub mod checkpoint_list;
+pub mod history_panel;
 pub mod modal_overlay;
 pub mod confirmation_dialog;
 pub mod connect_to_station;
@@ -26,32 +28,45 @@ pub mod days_of_week_selector;
 pub mod dropdown_menu;
 pub mod duration_input;
 pub mod error_list;
+pub mod geo_reference_editor;
 pub mod graph_canvas;
 pub mod importer;
 pub mod infrastructure_canvas;
+pub mod infrastructure_minimap;
 pub mod infrastructure_toolbar;
 pub mod infrastructure_view;
 pub mod multi_select_toolbar;
+pub mod platform_timeline;
+pub mod playback_controls;
 pub mod keyboard_shortcuts_editor;
+pub mod journey_tooltip;
 pub mod label_position_grid;
 pub mod legend;
 pub mod line_controls;
 pub mod line_editor;
 pub mod line_settings_panel;
 pub mod line_sort_selector;
+pub mod measure_tool;
 pub mod platform_editor;
+pub mod project_diff;
 pub mod project_manager;
 pub mod report_issue_button;
 pub mod settings;
+pub mod service_patterns_editor;
 pub mod routing_rule_editor;
+pub mod save_indicator;
 pub mod sidebar;
 pub mod station_label_tooltip;
+pub mod station_search;
+pub mod storage_warning;
 pub mod tab_shortcuts;
 pub mod tab_view;
 pub mod text_input_dialog;
 pub mod time_graph;
 pub mod time_input;
+pub mod timetable_export_button;
 pub mod toast;
+pub mod track_tooltip;
 pub mod tree_item;
 pub mod view_creation;
 pub mod window;
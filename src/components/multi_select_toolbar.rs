@@ -1,9 +1,11 @@
-use leptos::{component, view, IntoView, ReadSignal, WriteSignal, Callback, SignalGet, SignalSet, SignalWith, Callable, use_context, create_signal, Signal};
+use leptos::{component, view, IntoView, ReadSignal, WriteSignal, Callback, SignalGet, SignalSet, SignalUpdate, SignalWith, Callable, use_context, create_signal, Signal};
 use petgraph::stable_graph::NodeIndex;
-use crate::models::{RailwayGraph, Line, Stations, ProjectSettings, UserSettings};
+use crate::models::{RailwayGraph, Line, Stations, Junctions, ProjectSettings, UserSettings, StationClipboard, PASTE_OFFSET};
 use crate::components::label_position_grid::LabelPositionGrid;
 
 const SELECTION_PADDING: f64 = 20.0;
+/// localStorage key holding copied stations, shared across projects and tabs
+const STATION_CLIPBOARD_KEY: &str = "station_clipboard";
 
 pub fn delete_selected_stations(
     selected_stations: ReadSignal<Vec<NodeIndex>>,
@@ -11,6 +13,7 @@ pub fn delete_selected_stations(
     set_graph: WriteSignal<RailwayGraph>,
     lines: ReadSignal<Vec<Line>>,
     set_lines: WriteSignal<Vec<Line>>,
+    set_settings: WriteSignal<ProjectSettings>,
     set_selected_stations: WriteSignal<Vec<NodeIndex>>,
 ) {
     let stations = selected_stations.get();
@@ -28,12 +31,69 @@ pub fn delete_selected_stations(
             line.update_route_after_deletion(&removed_edges, &bypass_mapping);
         }
     }
+    current_graph.prune_junction_rules();
 
     set_graph.set(current_graph);
     set_lines.set(current_lines);
+    set_settings.update(|settings| {
+        for &station_idx in &stations {
+            settings.forget_deleted_station(station_idx);
+        }
+    });
     set_selected_stations.set(Vec::new());
 }
 
+/// Copy the selected stations and the tracks between them to the station clipboard
+pub fn copy_selected_stations(
+    selected_stations: ReadSignal<Vec<NodeIndex>>,
+    graph: ReadSignal<RailwayGraph>,
+) {
+    let stations = selected_stations.get();
+    if stations.is_empty() {
+        return;
+    }
+
+    let json = match graph.with(|g| StationClipboard::from_selection(g, &stations).to_json()) {
+        Ok(json) => json,
+        Err(e) => {
+            leptos::logging::error!("{}", e);
+            return;
+        }
+    };
+    let Some(storage) = web_sys::window().and_then(|w| w.local_storage().ok().flatten()) else { return };
+    if storage.set_item(STATION_CLIPBOARD_KEY, &json).is_err() {
+        leptos::logging::error!("Failed to store copied stations");
+    }
+}
+
+/// Paste stations from the station clipboard and select them
+pub fn paste_stations(
+    graph: ReadSignal<RailwayGraph>,
+    set_graph: WriteSignal<RailwayGraph>,
+    set_selected_stations: WriteSignal<Vec<NodeIndex>>,
+    set_selection_bounds: WriteSignal<Option<(f64, f64, f64, f64)>>,
+) {
+    let Some(json) = web_sys::window()
+        .and_then(|w| w.local_storage().ok().flatten())
+        .and_then(|storage| storage.get_item(STATION_CLIPBOARD_KEY).ok().flatten())
+    else {
+        return;
+    };
+    let clipboard = match StationClipboard::from_json(&json) {
+        Ok(clipboard) => clipboard,
+        Err(e) => {
+            leptos::logging::warn!("{}", e);
+            return;
+        }
+    };
+
+    let mut current_graph = graph.get();
+    let pasted = clipboard.paste_into(&mut current_graph, PASTE_OFFSET);
+    update_selection_bounds(&current_graph, &pasted, set_selection_bounds);
+    set_graph.set(current_graph);
+    set_selected_stations.set(pasted);
+}
+
 pub fn add_platform_to_selected(
     selected_stations: ReadSignal<Vec<NodeIndex>>,
     graph: ReadSignal<RailwayGraph>,
@@ -52,6 +112,7 @@ pub fn add_platform_to_selected(
                 let next_num = station.platforms.len() + 1;
                 station.platforms.push(crate::models::Platform {
                     name: next_num.to_string(),
+                    capacity: crate::models::DEFAULT_PLATFORM_CAPACITY,
                 });
             }
         }
@@ -359,14 +420,16 @@ pub fn align_selected_stations(
     let dir_x = line_angle_rad.cos();
     let dir_y = line_angle_rad.sin();
 
+    let current_settings = settings.get();
+
     // Snap the first position to grid
-    let snapped_first = crate::components::infrastructure_canvas::auto_layout::snap_to_grid(first_pos.0, first_pos.1);
+    let snapped_first = crate::components::infrastructure_canvas::auto_layout::snap_to_grid(first_pos.0, first_pos.1, current_settings.grid_size);
 
     // Calculate required spacing based on project settings
-    // For 0°/180° (horizontal) or 90°/270° (vertical): grid_squares * 30
-    // For 45° angles: grid_squares * 30 * sqrt(2)
-    let grid_size = 30.0;
-    let grid_squares = settings.get().default_node_distance_grid_squares;
+    // For 0°/180° (horizontal) or 90°/270° (vertical): grid_squares * grid_size
+    // For 45° angles: grid_squares * grid_size * sqrt(2)
+    let grid_size = current_settings.grid_square_size();
+    let grid_squares = current_settings.default_node_distance_grid_squares;
     let spacing = if (use_angle % 90.0).abs() < 0.1 {
         // Horizontal or vertical
         grid_size * grid_squares
@@ -403,6 +466,7 @@ fn rotate_stations_by_angle(
     stations: &[NodeIndex],
     graph: &mut RailwayGraph,
     angle_degrees: f64,
+    grid_size: f64,
 ) {
     if stations.len() < 2 {
         return;
@@ -490,7 +554,7 @@ fn rotate_stations_by_angle(
 
         // Snap the first endpoint to grid
         let (snapped_endpoint1_x, snapped_endpoint1_y) = crate::components::infrastructure_canvas::auto_layout::snap_to_grid(
-            endpoint1_rotated_x, endpoint1_rotated_y
+            endpoint1_rotated_x, endpoint1_rotated_y, grid_size
         );
 
         // Calculate the angle and distance from endpoint1 to endpoint2
@@ -507,7 +571,7 @@ fn rotate_stations_by_angle(
         let rotated_ep2_x = snapped_endpoint1_x + line_length * snapped_angle.cos();
         let rotated_ep2_y = snapped_endpoint1_y + line_length * snapped_angle.sin();
         let (snapped_endpoint2_x, snapped_endpoint2_y) = crate::components::infrastructure_canvas::auto_layout::snap_to_grid(
-            rotated_ep2_x, rotated_ep2_y
+            rotated_ep2_x, rotated_ep2_y, grid_size
         );
 
         // Calculate each station's position along the original line (0.0 to 1.0)
@@ -550,7 +614,7 @@ fn rotate_stations_by_angle(
         // Second pass: snap first node to grid and calculate offset
         if let Some((_first_idx, first_x, first_y)) = rotated_positions.first() {
             let (snapped_first_x, snapped_first_y) =
-                crate::components::infrastructure_canvas::auto_layout::snap_to_grid(*first_x, *first_y);
+                crate::components::infrastructure_canvas::auto_layout::snap_to_grid(*first_x, *first_y, grid_size);
 
             // Calculate the offset from snapping
             let snap_offset_x = snapped_first_x - first_x;
@@ -570,10 +634,11 @@ pub fn rotate_selected_stations_clockwise(
     graph: ReadSignal<RailwayGraph>,
     set_graph: WriteSignal<RailwayGraph>,
     set_selection_bounds: WriteSignal<Option<(f64, f64, f64, f64)>>,
+    grid_size: f64,
 ) {
     let stations = selected_stations.get();
     let mut current_graph = graph.get();
-    rotate_stations_by_angle(&stations, &mut current_graph, 45.0);
+    rotate_stations_by_angle(&stations, &mut current_graph, 45.0, grid_size);
 
     // Recalculate bounds after rotation
     update_selection_bounds(&current_graph, &stations, set_selection_bounds);
@@ -587,10 +652,11 @@ pub fn rotate_selected_stations_counterclockwise(
     graph: ReadSignal<RailwayGraph>,
     set_graph: WriteSignal<RailwayGraph>,
     set_selection_bounds: WriteSignal<Option<(f64, f64, f64, f64)>>,
+    grid_size: f64,
 ) {
     let stations = selected_stations.get();
     let mut current_graph = graph.get();
-    rotate_stations_by_angle(&stations, &mut current_graph, -45.0);
+    rotate_stations_by_angle(&stations, &mut current_graph, -45.0, grid_size);
 
     // Recalculate bounds after rotation
     update_selection_bounds(&current_graph, &stations, set_selection_bounds);
@@ -679,6 +745,9 @@ pub fn MultiSelectToolbar(
     /// Callback for Delete operation
     #[prop(optional)]
     on_delete: Option<Callback<()>>,
+    /// Callback for Copy operation
+    #[prop(optional)]
+    on_copy: Option<Callback<()>>,
     /// Callback for Set Label Position operation
     #[prop(optional)]
     on_set_label_position: Option<Callback<Option<crate::components::infrastructure_canvas::station_renderer::LabelPosition>>>,
@@ -959,6 +1028,20 @@ pub fn MultiSelectToolbar(
 
                     <div class="toolbar-divider"></div>
 
+                    <button
+                        class="toolbar-button"
+                        title=format_title_with_shortcut(
+                            format!("Copy {} station{}", count, if count == 1 { "" } else { "s" }),
+                            "multi_select_copy"
+                        )
+                        on:click=move |_| {
+                            if let Some(callback) = on_copy {
+                                callback.call(());
+                            }
+                        }
+                    >
+                        <i class="fa-solid fa-copy"></i>
+                    </button>
                     <button
                         class="toolbar-button toolbar-button-danger"
                         title=format_title_with_shortcut(
@@ -1,4 +1,4 @@
-use crate::models::Platform;
+use crate::models::{Platform, DEFAULT_PLATFORM_CAPACITY};
 use leptos::{component, view, ReadSignal, WriteSignal, IntoView, SignalUpdate, SignalGet, event_target_value, use_context, create_effect};
 
 #[component]
@@ -22,6 +22,7 @@ pub fn PlatformEditor(
             let next_num = p.len() + 1;
             p.push(Platform {
                 name: next_num.to_string(),
+                capacity: DEFAULT_PLATFORM_CAPACITY,
             });
         });
     };
@@ -46,6 +47,7 @@ pub fn PlatformEditor(
                     {move || {
                         platforms.get().iter().enumerate().map(|(i, platform)| {
                             let platform_name = platform.name.clone();
+                            let platform_capacity = platform.capacity;
                             view! {
                                 <div class="track-column">
                                     <input
@@ -61,6 +63,22 @@ pub fn PlatformEditor(
                                             });
                                         }
                                     />
+                                    <input
+                                        type="number"
+                                        class="platform-capacity-input"
+                                        min="1"
+                                        title="Trains this platform can hold at once"
+                                        value=platform_capacity
+                                        on:change=move |ev| {
+                                            if let Ok(capacity) = event_target_value(&ev).parse::<usize>() {
+                                                set_platforms.update(|p| {
+                                                    if let Some(platform) = p.get_mut(i) {
+                                                        platform.capacity = capacity.max(1);
+                                                    }
+                                                });
+                                            }
+                                        }
+                                    />
                                     {if platforms.get().len() > 1 {
                                         view! {
                                             <button
@@ -0,0 +1,181 @@
+use crate::conflict::stop_platform;
+use crate::models::RailwayGraph;
+use crate::time::time_to_fraction;
+use crate::train_journey::TrainJourney;
+use chrono::NaiveDateTime;
+use leptos::{component, view, IntoView, ReadSignal, Signal, SignalGet, SignalWith};
+use petgraph::stable_graph::NodeIndex;
+use std::collections::HashMap;
+
+/// A single train's use of one platform at a station
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlatformInterval {
+    pub platform_idx: usize,
+    pub arrival: NaiveDateTime,
+    pub departure: NaiveDateTime,
+    pub train_number: String,
+    pub color: String,
+}
+
+impl PlatformInterval {
+    /// Trains that pass through without stopping are drawn as thin markers
+    #[must_use]
+    pub fn is_pass_through(&self) -> bool {
+        self.arrival == self.departure
+    }
+}
+
+/// Collect every platform interval at `station`, sorted by arrival time
+#[must_use]
+pub fn station_platform_intervals<'a>(
+    station: NodeIndex,
+    journeys: impl IntoIterator<Item = &'a TrainJourney>,
+) -> Vec<PlatformInterval> {
+    let mut intervals: Vec<PlatformInterval> = journeys
+        .into_iter()
+        .flat_map(|journey| {
+            journey.station_times.iter()
+                .enumerate()
+                .filter(move |(_, (node_idx, _, _))| *node_idx == station)
+                .map(move |(i, (_, arrival, departure))| PlatformInterval {
+                    platform_idx: stop_platform(journey, i).0,
+                    arrival: *arrival,
+                    departure: *departure,
+                    train_number: journey.train_number.clone(),
+                    color: journey.color.clone(),
+                })
+        })
+        .collect();
+    intervals.sort_by_key(|interval| interval.arrival);
+    intervals
+}
+
+/// Whole hours spanned by the intervals, used as the timeline's horizontal range
+fn timeline_range(intervals: &[PlatformInterval]) -> (f64, f64) {
+    let start = intervals.iter().map(|i| time_to_fraction(i.arrival)).fold(f64::INFINITY, f64::min);
+    let end = intervals.iter().map(|i| time_to_fraction(i.departure)).fold(f64::NEG_INFINITY, f64::max);
+    if start > end {
+        return (0.0, 24.0);
+    }
+    (start.floor(), end.ceil().max(start.floor() + 1.0))
+}
+
+#[component]
+fn PlatformRow(
+    name: String,
+    intervals: Vec<PlatformInterval>,
+    range: (f64, f64),
+) -> impl IntoView {
+    let (range_start, range_end) = range;
+    let span = range_end - range_start;
+    view! {
+        <div class="platform-timeline-row">
+            <span class="platform-timeline-label">{name}</span>
+            <div class="platform-timeline-track">
+                {intervals.into_iter().map(|interval| {
+                    let left = (time_to_fraction(interval.arrival) - range_start) / span * 100.0;
+                    let width = (time_to_fraction(interval.departure) - time_to_fraction(interval.arrival)) / span * 100.0;
+                    let title = format!(
+                        "{} {}–{}",
+                        interval.train_number,
+                        interval.arrival.format("%H:%M:%S"),
+                        interval.departure.format("%H:%M:%S")
+                    );
+                    view! {
+                        <div
+                            class="platform-timeline-bar"
+                            class:pass-through=interval.is_pass_through()
+                            style=format!("left: {left}%; width: {width}%; background-color: {};", interval.color)
+                            title=title
+                        ></div>
+                    }
+                }).collect::<Vec<_>>()}
+            </div>
+        </div>
+    }
+}
+
+#[component]
+#[must_use]
+pub fn PlatformTimeline(
+    station: Signal<Option<NodeIndex>>,
+    graph: ReadSignal<RailwayGraph>,
+    train_journeys: ReadSignal<HashMap<uuid::Uuid, TrainJourney>>,
+) -> impl IntoView {
+    view! {
+        <div class="platform-timeline">
+            {move || {
+                let Some(station_idx) = station.get() else {
+                    return view! {}.into_view();
+                };
+                let platform_names: Vec<String> = graph.with(|g| {
+                    g.graph.node_weight(station_idx)
+                        .and_then(|node| node.as_station())
+                        .map(|s| s.platforms.iter().map(|p| p.name.clone()).collect())
+                        .unwrap_or_default()
+                });
+                let intervals = train_journeys.with(|journeys| station_platform_intervals(station_idx, journeys.values()));
+                if intervals.is_empty() {
+                    return view! { <p class="help-text">"No trains call at this station"</p> }.into_view();
+                }
+                let range = timeline_range(&intervals);
+
+                platform_names.into_iter().enumerate().map(|(platform_idx, name)| {
+                    let platform_intervals: Vec<PlatformInterval> = intervals.iter()
+                        .filter(|interval| interval.platform_idx == platform_idx)
+                        .cloned()
+                        .collect();
+                    view! { <PlatformRow name=name intervals=platform_intervals range=range /> }
+                }).collect::<Vec<_>>().into_view()
+            }}
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::BASE_DATE;
+    use crate::train_journey::JourneySegment;
+
+    fn journey(number: &str, station_times: Vec<(NodeIndex, NaiveDateTime, NaiveDateTime)>, platforms: &[(usize, usize)]) -> TrainJourney {
+        TrainJourney {
+            id: uuid::Uuid::new_v4(),
+            line_id: uuid::Uuid::new_v4(),
+            train_number: number.to_string(),
+            departure_time: station_times[0].2,
+            segments: platforms.iter().enumerate().map(|(i, &(origin_platform, destination_platform))| JourneySegment {
+                edge_index: i,
+                track_index: 0,
+                origin_platform,
+                destination_platform,
+                reverses: false,
+            }).collect(),
+            timing_inherited: vec![false; station_times.len()],
+            route_start_node: station_times.first().map(|s| s.0),
+            route_end_node: station_times.last().map(|s| s.0),
+            station_times,
+            color: "#ff0000".to_string(),
+            thickness: 2.0,
+            is_forward: true,
+            passed_stations: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_intervals_assigned_to_arrival_and_origin_platforms() {
+        let time = |h, m| BASE_DATE.and_hms_opt(h, m, 0).expect("valid time");
+        let (a, b, c) = (NodeIndex::new(0), NodeIndex::new(1), NodeIndex::new(2));
+
+        let stopping = journey("S1", vec![(a, time(8, 0), time(8, 0)), (b, time(8, 10), time(8, 12)), (c, time(8, 20), time(8, 20))], &[(0, 2), (1, 0)]);
+        let passing = journey("P1", vec![(a, time(9, 0), time(9, 0)), (b, time(9, 5), time(9, 5)), (c, time(9, 10), time(9, 10))], &[(0, 1), (1, 0)]);
+        let starting = journey("T1", vec![(b, time(7, 0), time(7, 0)), (c, time(7, 10), time(7, 10))], &[(3, 0)]);
+
+        let intervals = station_platform_intervals(b, [&stopping, &passing, &starting]);
+        let summary: Vec<(&str, usize, bool)> = intervals.iter()
+            .map(|i| (i.train_number.as_str(), i.platform_idx, i.is_pass_through()))
+            .collect();
+
+        assert_eq!(summary, vec![("T1", 3, true), ("S1", 2, false), ("P1", 1, true)]);
+    }
+}
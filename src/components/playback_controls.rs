@@ -0,0 +1,144 @@
+use leptos::{component, view, event_target_value, on_cleanup, store_value, IntoView, ReadSignal, Signal, SignalGet, SignalGetUntracked, SignalSet, SignalUpdate, StoredValue, WriteSignal, create_signal};
+use chrono::{Duration, NaiveDateTime};
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_bindgen::{JsCast, closure::Closure};
+use crate::time::time_to_fraction;
+
+/// Simulated seconds per real second offered in the speed selector
+const SPEED_OPTIONS: [u32; 6] = [10, 30, 60, 120, 300, 600];
+const DEFAULT_SPEED: u32 = 60;
+
+type FrameCallback = Rc<RefCell<Option<Closure<dyn FnMut(f64)>>>>;
+
+fn request_frame(callback: &FrameCallback) {
+    let Some(window) = web_sys::window() else { return };
+    if let Some(closure) = callback.borrow().as_ref() {
+        let _ = window.request_animation_frame(closure.as_ref().unchecked_ref());
+    }
+}
+
+/// Advance the visualization time on every animation frame until playback is
+/// paused, a newer playback loop has been started or the controls are unmounted
+#[allow(clippy::cast_possible_truncation)]
+fn start_playback_loop(
+    generation: u32,
+    playback_generation: StoredValue<u32>,
+    speed: ReadSignal<u32>,
+    time_window: Signal<(f64, f64)>,
+    is_playing: ReadSignal<bool>,
+    set_is_playing: WriteSignal<bool>,
+    set_visualization_time: WriteSignal<NaiveDateTime>,
+) {
+    let callback: FrameCallback = Rc::new(RefCell::new(None));
+    let callback_handle = callback.clone();
+    let mut last_timestamp: Option<f64> = None;
+
+    *callback.borrow_mut() = Some(Closure::new(move |timestamp: f64| {
+        // Signals are disposed with the controls; treat that like a pause
+        if is_playing.try_get_untracked() != Some(true) || playback_generation.try_get_value() != Some(generation) {
+            // Drop our handle so the closure is cleaned up once it returns
+            callback_handle.borrow_mut().take();
+            return;
+        }
+
+        if let Some(last) = last_timestamp {
+            let simulated_ms = ((timestamp - last) * f64::from(speed.try_get_untracked().unwrap_or(DEFAULT_SPEED))) as i64;
+            let end_hour = time_window.try_get_untracked().map_or(f64::MAX, |(_, end)| end);
+            let reached_end = set_visualization_time.try_update(|time| {
+                *time += Duration::milliseconds(simulated_ms);
+                time_to_fraction(*time) >= end_hour
+            });
+            if reached_end == Some(true) {
+                set_is_playing.try_set(false);
+            }
+        }
+        last_timestamp = Some(timestamp);
+        request_frame(&callback_handle);
+    }));
+
+    request_frame(&callback);
+}
+
+#[component]
+#[must_use]
+pub fn PlaybackControls(
+    visualization_time: ReadSignal<NaiveDateTime>,
+    set_visualization_time: WriteSignal<NaiveDateTime>,
+    is_playing: ReadSignal<bool>,
+    set_is_playing: WriteSignal<bool>,
+    /// Start and end hour of the graph; playback pauses at the end
+    time_window: Signal<(f64, f64)>,
+) -> impl IntoView {
+    let (speed, set_speed) = create_signal(DEFAULT_SPEED);
+    let playback_generation = store_value(0_u32);
+    // Invalidate a running loop so it stops with the controls
+    on_cleanup(move || {
+        playback_generation.try_update_value(|generation| *generation = generation.wrapping_add(1));
+    });
+    // Time at which playback was started, restored when stopping
+    let playback_origin = store_value(None::<NaiveDateTime>);
+
+    let toggle_playback = move |_| {
+        if is_playing.get_untracked() {
+            set_is_playing.set(false);
+            return;
+        }
+
+        if playback_origin.get_value().is_none() {
+            playback_origin.set_value(Some(visualization_time.get_untracked()));
+        }
+        playback_generation.update_value(|generation| *generation = generation.wrapping_add(1));
+        set_is_playing.set(true);
+        start_playback_loop(
+            playback_generation.get_value(),
+            playback_generation,
+            speed,
+            time_window,
+            is_playing,
+            set_is_playing,
+            set_visualization_time,
+        );
+    };
+
+    let stop_playback = move |_| {
+        set_is_playing.set(false);
+        if let Some(origin) = playback_origin.get_value() {
+            set_visualization_time.set(origin);
+        }
+        playback_origin.set_value(None);
+    };
+
+    view! {
+        <div class="playback-controls">
+            <button
+                class="playback-button"
+                on:click=toggle_playback
+                title=move || if is_playing.get() { "Pause playback" } else { "Play" }
+            >
+                <i class=move || if is_playing.get() { "fa-solid fa-pause" } else { "fa-solid fa-play" }></i>
+            </button>
+            <button
+                class="playback-button"
+                on:click=stop_playback
+                title="Stop and return to the start time"
+            >
+                <i class="fa-solid fa-stop"></i>
+            </button>
+            <select
+                class="playback-speed"
+                title="Playback speed"
+                prop:value=move || speed.get().to_string()
+                on:change=move |ev| {
+                    if let Ok(value) = event_target_value(&ev).parse::<u32>() {
+                        set_speed.set(value);
+                    }
+                }
+            >
+                {SPEED_OPTIONS.iter().map(|&value| view! {
+                    <option value=value.to_string() selected=value == DEFAULT_SPEED>{format!("{value}×")}</option>
+                }).collect::<Vec<_>>()}
+            </select>
+        </div>
+    }
+}
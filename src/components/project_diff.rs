@@ -0,0 +1,60 @@
+use leptos::{component, view, IntoView};
+use crate::storage::{DiffEntry, DiffKind, ProjectDiff};
+
+fn kind_label(kind: DiffKind) -> (&'static str, &'static str) {
+    match kind {
+        DiffKind::Added => ("Added", "diff-added"),
+        DiffKind::Removed => ("Removed", "diff-removed"),
+        DiffKind::Changed => ("Changed", "diff-changed"),
+    }
+}
+
+fn render_section(title: &'static str, entries: Vec<DiffEntry>) -> impl IntoView {
+    let count = entries.len();
+    view! {
+        <div class="project-diff-section">
+            <h3>{format!("{title} ({count})")}</h3>
+            {if entries.is_empty() {
+                view! { <div class="project-diff-empty">"No changes"</div> }.into_view()
+            } else {
+                view! {
+                    <ul class="project-diff-entries">
+                        {entries.into_iter().map(|entry| {
+                            let (label, class) = kind_label(entry.kind);
+                            view! {
+                                <li class=format!("project-diff-entry {class}")>
+                                    <span class="project-diff-kind">{label}</span>
+                                    <span class="project-diff-name" title=entry.id>{entry.name}</span>
+                                    {(!entry.details.is_empty()).then(|| view! {
+                                        <span class="project-diff-details">{entry.details.join(", ")}</span>
+                                    })}
+                                </li>
+                            }
+                        }).collect::<Vec<_>>()}
+                    </ul>
+                }.into_view()
+            }}
+        </div>
+    }
+}
+
+/// Structural differences between two projects, grouped by stations, tracks and lines
+#[component]
+#[must_use]
+pub fn ProjectDiffPanel(diff: ProjectDiff) -> impl IntoView {
+    if diff.is_empty() {
+        return view! {
+            <div class="project-diff-panel">
+                <div class="project-diff-empty">"The projects have the same stations, tracks and lines."</div>
+            </div>
+        }.into_view();
+    }
+
+    view! {
+        <div class="project-diff-panel">
+            {render_section("Stations", diff.stations)}
+            {render_section("Tracks", diff.tracks)}
+            {render_section("Lines", diff.lines)}
+        </div>
+    }.into_view()
+}
@@ -1,9 +1,11 @@
-use leptos::{component, view, IntoView, Signal, create_signal, SignalGet, SignalSet, spawn_local, Callback, Callable, WriteSignal, create_effect, wasm_bindgen, create_node_ref};
+use leptos::{component, view, IntoView, Signal, create_signal, SignalGet, SignalGetUntracked, SignalSet, SignalUpdate, SignalWith, spawn_local, Callback, Callable, ReadSignal, WriteSignal, create_effect, wasm_bindgen, create_node_ref, event_target_value, use_context};
 use crate::components::window::Window;
+use crate::components::graph_canvas::ReferenceOverlay;
+use crate::components::project_diff::ProjectDiffPanel;
 use crate::components::confirmation_dialog::ConfirmationDialog;
 use crate::components::text_input_dialog::TextInputDialog;
-use crate::models::{Project, ProjectMetadata};
-use crate::storage::{self, Storage, IndexedDbStorage, format_bytes};
+use crate::models::{Project, ProjectMetadata, UserSettings};
+use crate::storage::{self, Storage, IndexedDbStorage, ProjectDiff, diff_projects, format_bytes, storage_usage_exceeds};
 use std::rc::Rc;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
@@ -47,6 +49,43 @@ fn duplicate_project_action(
     });
 }
 
+fn compare_project_action(
+    project_id: String,
+    storage: IndexedDbStorage,
+    current_project: Project,
+    set_comparison: WriteSignal<Option<(String, ProjectDiff)>>,
+    set_error: WriteSignal<Option<String>>,
+) {
+    spawn_local(async move {
+        match storage.load_project(&project_id).await {
+            Ok(other) => {
+                let title = format!("{} → {}", current_project.metadata.name, other.metadata.name);
+                set_comparison.set(Some((title, diff_projects(&current_project, &other))));
+            }
+            Err(e) => set_error.set(Some(format!("Failed to load project for comparison: {e}"))),
+        }
+    });
+}
+
+fn overlay_reference_action(
+    project_id: String,
+    storage: IndexedDbStorage,
+    reference_overlay: ReferenceOverlay,
+    on_close: Rc<impl Fn() + 'static>,
+    set_error: WriteSignal<Option<String>>,
+) {
+    spawn_local(async move {
+        match storage.load_project(&project_id).await {
+            Ok(project) => {
+                reference_overlay.set_project.set(Some(project));
+                reference_overlay.set_visible.set(true);
+                on_close();
+            }
+            Err(e) => set_error.set(Some(format!("Failed to load reference timetable: {e}"))),
+        }
+    });
+}
+
 fn export_project_action(
     project_id: String,
     project_name: String,
@@ -90,6 +129,8 @@ fn render_project_row(
     set_delete_target_id: WriteSignal<Option<String>>,
     set_delete_target_name: WriteSignal<String>,
     set_show_delete_confirm: WriteSignal<bool>,
+    on_compare: Callback<String>,
+    on_overlay: Callback<String>,
 ) -> impl IntoView {
     let is_active = metadata.id == current_project_id;
     let project_id = Rc::new(metadata.id.clone());
@@ -112,6 +153,7 @@ fn render_project_row(
                 </span>
             </div>
             <div class="project-list-col-date">{date_str}</div>
+            <div class="project-list-col-size">{format_bytes(metadata.size_bytes)}</div>
             <div class="project-list-col-actions">
                 <button
                     class="action-button"
@@ -151,6 +193,28 @@ fn render_project_row(
                 >
                     <i class="fa-solid fa-copy"></i>
                 </button>
+                <button
+                    class="action-button"
+                    on:click={
+                        let project_id = Rc::clone(&project_id);
+                        move |_| on_compare.call((*project_id).clone())
+                    }
+                    title="Compare with the open project"
+                    prop:disabled=is_active
+                >
+                    <i class="fa-solid fa-code-compare"></i>
+                </button>
+                <button
+                    class="action-button"
+                    on:click={
+                        let project_id = Rc::clone(&project_id);
+                        move |_| on_overlay.call((*project_id).clone())
+                    }
+                    title="Show as reference timetable behind the open project"
+                    prop:disabled=is_active
+                >
+                    <i class="fa-solid fa-layer-group"></i>
+                </button>
                 <button
                     class="action-button"
                     on:click={
@@ -200,6 +264,8 @@ pub fn ProjectManager(
 ) -> impl IntoView {
     let storage = IndexedDbStorage;
     let on_close = Rc::new(on_close);
+    let (user_settings, set_user_settings) = use_context::<(ReadSignal<UserSettings>, WriteSignal<UserSettings>)>()
+        .expect("UserSettings context not found");
 
     let (projects, set_projects) = create_signal(Vec::<ProjectMetadata>::new());
     let (error_message, set_error_message) = create_signal(None::<String>);
@@ -211,6 +277,20 @@ pub fn ProjectManager(
     let (delete_target_id, set_delete_target_id) = create_signal(None::<String>);
     let (delete_target_name, set_delete_target_name) = create_signal(String::new());
 
+    // Comparison with the open project, as (title, diff)
+    let (comparison, set_comparison) = create_signal(None::<(String, ProjectDiff)>);
+    let on_compare = Callback::new(move |project_id: String| {
+        compare_project_action(project_id, storage, current_project.get_untracked(), set_comparison, set_error_message);
+    });
+
+    let reference_overlay = use_context::<ReferenceOverlay>().expect("ReferenceOverlay context not found");
+    let on_overlay = {
+        let on_close = Rc::clone(&on_close);
+        Callback::new(move |project_id: String| {
+            overlay_reference_action(project_id, storage, reference_overlay, Rc::clone(&on_close), set_error_message);
+        })
+    };
+
     // Save As dialog state
     let (show_save_as_dialog, set_show_save_as_dialog) = create_signal(false);
     let (save_as_name, set_save_as_name) = create_signal(String::new());
@@ -250,7 +330,7 @@ pub fn ProjectManager(
                     set_storage_warning.set(Some(format!(
                         "Storage critically low: {used_str} / {total_str} ({usage_percent:.0}% used)"
                     )));
-                } else if usage_percent > 75.0 {
+                } else if storage_usage_exceeds(Some((used, total)), user_settings.get_untracked().storage_warning_fraction) {
                     let used_str = format_bytes(used);
                     let total_str = format_bytes(total);
                     set_storage_warning.set(Some(format!(
@@ -402,8 +482,15 @@ pub fn ProjectManager(
     });
 
     // Process imported project file
-    let process_import = move |bytes: Vec<u8>, filename: Option<String>| {
-        let project = match storage::deserialize_project_from_bytes(&bytes) {
+    let process_import = move |bytes: Vec<u8>, filename: Option<String>, is_json: bool| {
+        let parsed = if is_json {
+            String::from_utf8(bytes)
+                .map_err(|_| "Invalid project JSON: not valid UTF-8".to_string())
+                .and_then(|json| storage::export::project_from_json(&json))
+        } else {
+            storage::deserialize_project_from_bytes(&bytes)
+        };
+        let project = match parsed {
             Ok(p) => p,
             Err(e) => {
                 set_error_message.set(Some(e));
@@ -433,6 +520,8 @@ pub fn ProjectManager(
         let Some(files) = input.files() else { return };
         let Some(file) = files.get(0) else { return };
 
+        let is_json = file.name().to_lowercase().ends_with(".json");
+
         // Extract filename without extension
         let filename_without_ext = std::path::Path::new(&file.name())
             .file_stem()
@@ -461,7 +550,7 @@ pub fn ProjectManager(
                 let uint8_array = js_sys::Uint8Array::new(&array_buffer);
                 let bytes = uint8_array.to_vec();
 
-                process_import(bytes, filename_clone.clone());
+                process_import(bytes, filename_clone.clone(), is_json);
             }) as Box<dyn FnMut(_)>);
 
             reader.set_onload(Some(onload.as_ref().unchecked_ref()));
@@ -471,6 +560,23 @@ pub fn ProjectManager(
         });
     };
 
+    // Export the current project as portable JSON
+    let handle_export_json = move |_| {
+        let project = current_project.get();
+        let json = match storage::export::project_to_json(&project) {
+            Ok(json) => json,
+            Err(e) => {
+                set_error_message.set(Some(e));
+                return;
+            }
+        };
+
+        let filename = storage::export::create_json_export_filename(&project.metadata.name);
+        if let Err(e) = storage::trigger_download(json.as_bytes(), &filename) {
+            set_error_message.set(Some(e));
+        }
+    };
+
     view! {
         <Window
             is_open=is_open
@@ -533,7 +639,7 @@ pub fn ProjectManager(
                     </button>
                     <input
                         type="file"
-                        accept=".rgproject"
+                        accept=".rgproject,.json"
                         node_ref=import_file_input_ref
                         on:change=handle_import_file
                         style="display: none;"
@@ -548,12 +654,20 @@ pub fn ProjectManager(
                         <i class="fa-solid fa-upload"></i>
                         " Import Project"
                     </button>
+                    <button
+                        on:click=handle_export_json
+                        title="Export the current project as JSON"
+                    >
+                        <i class="fa-solid fa-file-export"></i>
+                        " Export Project"
+                    </button>
                 </div>
 
                 <div class="project-list">
                     <div class="project-list-header">
                         <div class="project-list-col-name">"Name"</div>
                         <div class="project-list-col-date">"Last Modified"</div>
+                        <div class="project-list-col-size">"Size"</div>
                         <div class="project-list-col-actions">"Actions"</div>
                     </div>
                     {move || {
@@ -578,6 +692,8 @@ pub fn ProjectManager(
                                     set_delete_target_id,
                                     set_delete_target_name,
                                     set_show_delete_confirm,
+                                    on_compare,
+                                    on_overlay,
                                 )
                             }).collect::<Vec<_>>().into_view()
                         }
@@ -600,17 +716,80 @@ pub fn ProjectManager(
                                 <div
                                     class="storage-meter-fill"
                                     style:width=format!("{usage_percent:.1}%")
-                                    class:warning={usage_percent > 75.0}
+                                    class:warning=move || storage_usage_exceeds(Some((used, total)), user_settings.with(|s| s.storage_warning_fraction))
                                     class:critical={usage_percent > 90.0}
                                 ></div>
                             </div>
-                            <div class="storage-meter-percent">{format!("{usage_percent:.0}% used")}</div>
+                            <div class="storage-meter-footer">
+                                <label class="storage-meter-threshold" title="Show a storage warning above this usage">
+                                    "Warn at "
+                                    <input
+                                        type="number"
+                                        min="10"
+                                        max="100"
+                                        step="5"
+                                        prop:value=move || format!("{:.0}", user_settings.with(|s| s.storage_warning_fraction) * 100.0)
+                                        on:change=move |ev| {
+                                            let Ok(percent) = event_target_value(&ev).parse::<f64>() else { return };
+                                            let settings_to_save = set_user_settings.try_update(|settings| {
+                                                settings.storage_warning_fraction = (percent / 100.0).clamp(0.1, 1.0);
+                                                settings.clone()
+                                            });
+                                            let Some(settings_to_save) = settings_to_save else { return };
+                                            spawn_local(async move {
+                                                if let Err(e) = settings_to_save.save().await {
+                                                    leptos::logging::error!("Failed to save storage warning threshold: {}", e);
+                                                }
+                                            });
+                                        }
+                                    />
+                                    "%"
+                                </label>
+                                <span class="storage-meter-percent">{format!("{usage_percent:.0}% used")}</span>
+                            </div>
                         </div>
                     }
                 })}
+
+                <label class="auto-save-delay" title="Save the project once you stop editing for this long">
+                    "Auto-save after "
+                    <input
+                        type="number"
+                        min="0"
+                        max="60"
+                        step="0.5"
+                        prop:value=move || user_settings.with(|s| s.auto_save_delay_seconds).to_string()
+                        on:change=move |ev| {
+                            let Ok(seconds) = event_target_value(&ev).parse::<f64>() else { return };
+                            let settings_to_save = set_user_settings.try_update(|settings| {
+                                settings.auto_save_delay_seconds = seconds.clamp(0.0, 60.0);
+                                settings.clone()
+                            });
+                            let Some(settings_to_save) = settings_to_save else { return };
+                            spawn_local(async move {
+                                if let Err(e) = settings_to_save.save().await {
+                                    leptos::logging::error!("Failed to save auto-save delay: {}", e);
+                                }
+                            });
+                        }
+                    />
+                    "s"
+                </label>
             </div>
         </Window>
 
+        <Window
+            is_open=Signal::derive(move || comparison.with(Option::is_some))
+            title=Signal::derive(move || comparison.with(|c| {
+                c.as_ref().map_or_else(String::new, |(title, _)| format!("Compare: {title}"))
+            }))
+            on_close=move || set_comparison.set(None)
+            max_size=(600.0, 700.0)
+            position_key="project-diff"
+        >
+            {move || comparison.get().map(|(_, diff)| view! { <ProjectDiffPanel diff=diff /> })}
+        </Window>
+
         // New Project Dialog
         <TextInputDialog
             is_open=show_new_project_dialog.into()
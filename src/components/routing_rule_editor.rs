@@ -33,16 +33,18 @@ pub fn RoutingRuleEditor(
         result
     };
 
-    // Get the junction's current routing rules
+    // Get the junction's current routing rules, ignoring any left over from deleted edges
     let get_junction = move || {
         let idx = junction_idx.get()?;
 
         let current_graph = graph.get();
-        current_graph
+        let mut junction = current_graph
             .graph
             .node_weight(idx)
             .and_then(|node| node.as_junction())
-            .cloned()
+            .cloned()?;
+        junction.prune_invalid_rules(&current_graph, idx);
+        Some(junction)
     };
 
     // Helper to get node name (station or junction)
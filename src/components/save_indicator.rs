@@ -0,0 +1,57 @@
+use leptos::{component, create_signal, on_cleanup, view, IntoView, Signal, SignalGet, SignalSet, SignalWith};
+use crate::storage::SaveStatus;
+use std::time::Duration;
+
+/// How often the "saved ago" text is refreshed
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+/// Saves more recent than this are reported as just now, in seconds
+const JUST_NOW_SECONDS: f64 = 5.0;
+
+fn saved_ago_label(saved_at_ms: f64, now_ms: f64) -> String {
+    let seconds = ((now_ms - saved_at_ms) / 1000.0).max(0.0);
+    if seconds < JUST_NOW_SECONDS {
+        "Saved just now".to_string()
+    } else if seconds < 60.0 {
+        format!("Saved {seconds:.0}s ago")
+    } else {
+        format!("Saved {:.0}m ago", (seconds / 60.0).floor())
+    }
+}
+
+/// Header indicator showing whether the project has been persisted
+#[component]
+#[must_use]
+pub fn SaveIndicator(status: Signal<SaveStatus>) -> impl IntoView {
+    let (now, set_now) = create_signal(js_sys::Date::now());
+    let handle = leptos::leptos_dom::helpers::set_interval_with_handle(
+        move || set_now.set(js_sys::Date::now()),
+        REFRESH_INTERVAL,
+    ).ok();
+    on_cleanup(move || {
+        if let Some(handle) = handle {
+            handle.clear();
+        }
+    });
+
+    let label = move || match status.get() {
+        SaveStatus::Idle => String::new(),
+        SaveStatus::Unsaved => "Unsaved changes".to_string(),
+        SaveStatus::Saving => "Saving…".to_string(),
+        SaveStatus::Saved(saved_at) => saved_ago_label(saved_at, now.get()),
+        SaveStatus::Failed(_) => "Save failed".to_string(),
+    };
+    let error = move || status.with(|status| match status {
+        SaveStatus::Failed(error) => error.clone(),
+        _ => String::new(),
+    });
+
+    view! {
+        <span
+            class="save-indicator"
+            class:failed=move || status.with(|status| matches!(status, SaveStatus::Failed(_)))
+            title=error
+        >
+            {label}
+        </span>
+    }
+}
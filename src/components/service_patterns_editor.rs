@@ -0,0 +1,90 @@
+use crate::components::days_of_week_selector::DaysOfWeekSelector;
+use crate::models::{DaysOfWeek, ServicePattern};
+use leptos::{component, view, For, IntoView, ReadSignal, Signal, SignalGet, SignalUpdate, SignalWith, WriteSignal, event_target_value};
+use uuid::Uuid;
+
+/// Operating-day calendars shared by lines, stored with the project
+#[derive(Clone, Copy)]
+pub struct ServicePatterns {
+    pub patterns: ReadSignal<Vec<ServicePattern>>,
+    pub set_patterns: WriteSignal<Vec<ServicePattern>>,
+}
+
+impl ServicePatterns {
+    fn update_pattern(self, id: Uuid, f: impl FnOnce(&mut ServicePattern)) {
+        self.set_patterns.update(|patterns| {
+            if let Some(pattern) = patterns.iter_mut().find(|p| p.id == id) {
+                f(pattern);
+            }
+        });
+    }
+}
+
+#[component]
+#[must_use]
+pub fn ServicePatternsEditor(service_patterns: ServicePatterns) -> impl IntoView {
+    let handle_add_pattern = move |_| {
+        service_patterns.set_patterns.update(|patterns| {
+            let name = format!("Pattern {}", patterns.len() + 1);
+            patterns.push(ServicePattern::new(name, DaysOfWeek::ALL_DAYS));
+        });
+    };
+
+    view! {
+        <div class="service-patterns-editor">
+            <For
+                each=move || service_patterns.patterns.get()
+                key=|pattern| pattern.id
+                children=move |pattern| {
+                    let id = pattern.id;
+                    let days = Signal::derive(move || service_patterns.patterns.with(|patterns| {
+                        patterns.iter().find(|p| p.id == id).map_or(DaysOfWeek::empty(), |p| p.days)
+                    }));
+
+                    view! {
+                        <div class="service-pattern">
+                            <div class="service-pattern-header">
+                                <input
+                                    type="text"
+                                    class="service-pattern-name"
+                                    value=pattern.name.clone()
+                                    on:change=move |ev| {
+                                        let name = event_target_value(&ev);
+                                        service_patterns.update_pattern(id, |p| p.name = name);
+                                    }
+                                />
+                                <button
+                                    class="remove-service-pattern"
+                                    title="Delete service pattern; lines using it fall back to their own operating days"
+                                    on:click=move |_| service_patterns.set_patterns.update(|patterns| patterns.retain(|p| p.id != id))
+                                >
+                                    <i class="fa-solid fa-trash"></i>
+                                </button>
+                            </div>
+                            <DaysOfWeekSelector
+                                days_of_week=days
+                                set_days_of_week=move |days| service_patterns.update_pattern(id, |p| p.days = days)
+                                label="Runs on:"
+                            />
+                            <label>"Exceptions"</label>
+                            <input
+                                type="text"
+                                class="service-pattern-exceptions"
+                                placeholder="YYYY-MM-DD, YYYY-MM-DD"
+                                value=pattern.exceptions_text()
+                                on:change=move |ev| {
+                                    let text = event_target_value(&ev);
+                                    service_patterns.update_pattern(id, |p| p.set_exceptions_from_text(&text));
+                                }
+                            />
+                        </div>
+                    }
+                }
+            />
+            <button class="add-service-pattern" on:click=handle_add_pattern>
+                <i class="fa-solid fa-plus"></i>
+                " Add service pattern"
+            </button>
+        </div>
+    }
+}
@@ -1,11 +1,22 @@
-use leptos::{component, view, Signal, IntoView, create_signal, create_rw_signal, SignalGet, SignalSet};
+use leptos::{component, view, Signal, IntoView, create_signal, create_rw_signal, use_context, SignalGet, SignalSet};
 use crate::components::window::Window;
 use crate::components::button::Button;
 use crate::components::tab_view::{TabView, TabPanel, Tab};
 use crate::components::keyboard_shortcuts_editor::KeyboardShortcutsEditor;
-use crate::components::duration_input::DurationInput;
-use crate::models::{ProjectSettings, TrackHandedness};
-use chrono::Duration;
+use crate::components::duration_input::{DurationInput, OptionalDurationInput};
+use crate::components::service_patterns_editor::{ServicePatterns, ServicePatternsEditor};
+use crate::components::geo_reference_editor::GeoReferenceEditor;
+use crate::models::{ConflictWindow, GeoReference, ProjectSettings, TrackHandedness};
+use petgraph::stable_graph::NodeIndex;
+use chrono::{Duration, NaiveDate};
+use crate::constants::BASE_DATE;
+
+const MIN_CURVE_RADIUS_SETTING: f64 = 2.0;
+const MAX_CURVE_RADIUS_SETTING: f64 = 100.0;
+const MAX_GRAPH_HOUR_SETTING: u32 = 48;
+const MAX_GRID_SIZE_SETTING: f64 = 200.0;
+const MIN_ANGLE_SNAP_SETTING: f64 = 5.0;
+const MAX_ANGLE_SNAP_SETTING: f64 = 90.0;
 
 #[component]
 #[allow(clippy::too_many_lines)]
@@ -13,8 +24,13 @@ pub fn Settings(
     settings: Signal<ProjectSettings>,
     set_settings: impl Fn(ProjectSettings) + 'static + Copy,
     #[prop(optional)] on_open_changelog: Option<impl Fn() + 'static + Copy>,
+    /// Fills missing track distances from station positions, returning how many were filled
+    #[prop(optional)] on_auto_fill_distances: Option<impl Fn() -> usize + 'static + Copy>,
+    /// Stations that can be pinned to real-world coordinates
+    #[prop(optional)] stations: Option<Signal<Vec<(NodeIndex, String)>>>,
 ) -> impl IntoView {
     let (is_open, set_is_open) = create_signal(false);
+    let (auto_fill_result, set_auto_fill_result) = create_signal(None::<usize>);
     let active_tab = create_rw_signal("project".to_string());
 
     let handle_handedness_change = move |handedness: TrackHandedness| {
@@ -26,6 +42,18 @@ pub fn Settings(
             minimum_separation: current.minimum_separation,
             station_margin: current.station_margin,
             ignore_same_direction_platform_conflicts: current.ignore_same_direction_platform_conflicts,
+            near_miss_threshold: current.near_miss_threshold,
+            angle_snap_degrees: current.angle_snap_degrees,
+            px_per_km: current.px_per_km,
+            min_curve_radius: current.min_curve_radius,
+            graph_start_hour: current.graph_start_hour,
+            graph_end_hour: current.graph_end_hour,
+            grid_size: current.grid_size,
+            show_grid: current.show_grid,
+            constant_line_width: current.constant_line_width,
+            geo_reference: current.geo_reference,
+            reference_week: current.reference_week,
+            conflict_window: current.conflict_window,
         });
     };
 
@@ -39,6 +67,18 @@ pub fn Settings(
             minimum_separation: current.minimum_separation,
             station_margin: current.station_margin,
             ignore_same_direction_platform_conflicts: current.ignore_same_direction_platform_conflicts,
+            near_miss_threshold: current.near_miss_threshold,
+            angle_snap_degrees: current.angle_snap_degrees,
+            px_per_km: current.px_per_km,
+            min_curve_radius: current.min_curve_radius,
+            graph_start_hour: current.graph_start_hour,
+            graph_end_hour: current.graph_end_hour,
+            grid_size: current.grid_size,
+            show_grid: current.show_grid,
+            constant_line_width: current.constant_line_width,
+            geo_reference: current.geo_reference,
+            reference_week: current.reference_week,
+            conflict_window: current.conflict_window,
         });
     };
 
@@ -51,6 +91,18 @@ pub fn Settings(
             minimum_separation: duration,
             station_margin: current.station_margin,
             ignore_same_direction_platform_conflicts: current.ignore_same_direction_platform_conflicts,
+            near_miss_threshold: current.near_miss_threshold,
+            angle_snap_degrees: current.angle_snap_degrees,
+            px_per_km: current.px_per_km,
+            min_curve_radius: current.min_curve_radius,
+            graph_start_hour: current.graph_start_hour,
+            graph_end_hour: current.graph_end_hour,
+            grid_size: current.grid_size,
+            show_grid: current.show_grid,
+            constant_line_width: current.constant_line_width,
+            geo_reference: current.geo_reference,
+            reference_week: current.reference_week,
+            conflict_window: current.conflict_window,
         });
     };
 
@@ -63,6 +115,18 @@ pub fn Settings(
             minimum_separation: current.minimum_separation,
             station_margin: duration,
             ignore_same_direction_platform_conflicts: current.ignore_same_direction_platform_conflicts,
+            near_miss_threshold: current.near_miss_threshold,
+            angle_snap_degrees: current.angle_snap_degrees,
+            px_per_km: current.px_per_km,
+            min_curve_radius: current.min_curve_radius,
+            graph_start_hour: current.graph_start_hour,
+            graph_end_hour: current.graph_end_hour,
+            grid_size: current.grid_size,
+            show_grid: current.show_grid,
+            constant_line_width: current.constant_line_width,
+            geo_reference: current.geo_reference,
+            reference_week: current.reference_week,
+            conflict_window: current.conflict_window,
         });
     };
 
@@ -75,6 +139,259 @@ pub fn Settings(
             minimum_separation: current.minimum_separation,
             station_margin: current.station_margin,
             ignore_same_direction_platform_conflicts: checked,
+            near_miss_threshold: current.near_miss_threshold,
+            angle_snap_degrees: current.angle_snap_degrees,
+            px_per_km: current.px_per_km,
+            min_curve_radius: current.min_curve_radius,
+            graph_start_hour: current.graph_start_hour,
+            graph_end_hour: current.graph_end_hour,
+            grid_size: current.grid_size,
+            show_grid: current.show_grid,
+            constant_line_width: current.constant_line_width,
+            geo_reference: current.geo_reference,
+            reference_week: current.reference_week,
+            conflict_window: current.conflict_window,
+        });
+    };
+
+    let handle_near_miss_threshold_change = move |threshold: Option<Duration>| {
+        let current = settings.get();
+        set_settings(ProjectSettings {
+            track_handedness: current.track_handedness,
+            line_sort_mode: current.line_sort_mode,
+            default_node_distance_grid_squares: current.default_node_distance_grid_squares,
+            minimum_separation: current.minimum_separation,
+            station_margin: current.station_margin,
+            ignore_same_direction_platform_conflicts: current.ignore_same_direction_platform_conflicts,
+            near_miss_threshold: threshold,
+            angle_snap_degrees: current.angle_snap_degrees,
+            px_per_km: current.px_per_km,
+            min_curve_radius: current.min_curve_radius,
+            graph_start_hour: current.graph_start_hour,
+            graph_end_hour: current.graph_end_hour,
+            grid_size: current.grid_size,
+            show_grid: current.show_grid,
+            constant_line_width: current.constant_line_width,
+            geo_reference: current.geo_reference,
+            reference_week: current.reference_week,
+            conflict_window: current.conflict_window,
+        });
+    };
+
+    let handle_angle_snap_change = move |degrees: f64| {
+        let current = settings.get();
+        set_settings(ProjectSettings {
+            track_handedness: current.track_handedness,
+            line_sort_mode: current.line_sort_mode,
+            default_node_distance_grid_squares: current.default_node_distance_grid_squares,
+            minimum_separation: current.minimum_separation,
+            station_margin: current.station_margin,
+            ignore_same_direction_platform_conflicts: current.ignore_same_direction_platform_conflicts,
+            near_miss_threshold: current.near_miss_threshold,
+            angle_snap_degrees: degrees.clamp(MIN_ANGLE_SNAP_SETTING, MAX_ANGLE_SNAP_SETTING),
+            px_per_km: current.px_per_km,
+            min_curve_radius: current.min_curve_radius,
+            graph_start_hour: current.graph_start_hour,
+            graph_end_hour: current.graph_end_hour,
+            grid_size: current.grid_size,
+            show_grid: current.show_grid,
+            constant_line_width: current.constant_line_width,
+            geo_reference: current.geo_reference,
+            reference_week: current.reference_week,
+            conflict_window: current.conflict_window,
+        });
+    };
+
+    let handle_px_per_km_change = move |px_per_km: f64| {
+        let current = settings.get();
+        set_settings(ProjectSettings {
+            track_handedness: current.track_handedness,
+            line_sort_mode: current.line_sort_mode,
+            default_node_distance_grid_squares: current.default_node_distance_grid_squares,
+            minimum_separation: current.minimum_separation,
+            station_margin: current.station_margin,
+            ignore_same_direction_platform_conflicts: current.ignore_same_direction_platform_conflicts,
+            near_miss_threshold: current.near_miss_threshold,
+            angle_snap_degrees: current.angle_snap_degrees,
+            px_per_km: px_per_km.max(1.0),
+            min_curve_radius: current.min_curve_radius,
+            graph_start_hour: current.graph_start_hour,
+            graph_end_hour: current.graph_end_hour,
+            grid_size: current.grid_size,
+            show_grid: current.show_grid,
+            constant_line_width: current.constant_line_width,
+            geo_reference: current.geo_reference,
+            reference_week: current.reference_week,
+            conflict_window: current.conflict_window,
+        });
+    };
+
+    let handle_min_curve_radius_change = move |radius: f64| {
+        let current = settings.get();
+        set_settings(ProjectSettings {
+            track_handedness: current.track_handedness,
+            line_sort_mode: current.line_sort_mode,
+            default_node_distance_grid_squares: current.default_node_distance_grid_squares,
+            minimum_separation: current.minimum_separation,
+            station_margin: current.station_margin,
+            ignore_same_direction_platform_conflicts: current.ignore_same_direction_platform_conflicts,
+            near_miss_threshold: current.near_miss_threshold,
+            angle_snap_degrees: current.angle_snap_degrees,
+            px_per_km: current.px_per_km,
+            min_curve_radius: radius.clamp(MIN_CURVE_RADIUS_SETTING, MAX_CURVE_RADIUS_SETTING),
+            graph_start_hour: current.graph_start_hour,
+            graph_end_hour: current.graph_end_hour,
+            grid_size: current.grid_size,
+            show_grid: current.show_grid,
+            constant_line_width: current.constant_line_width,
+            geo_reference: current.geo_reference,
+            reference_week: current.reference_week,
+            conflict_window: current.conflict_window,
+        });
+    };
+
+    let handle_time_window_change = move |start_hour: u32, end_hour: u32| {
+        let current = settings.get();
+        let start_hour = start_hour.min(MAX_GRAPH_HOUR_SETTING - 1);
+        set_settings(ProjectSettings {
+            track_handedness: current.track_handedness,
+            line_sort_mode: current.line_sort_mode,
+            default_node_distance_grid_squares: current.default_node_distance_grid_squares,
+            minimum_separation: current.minimum_separation,
+            station_margin: current.station_margin,
+            ignore_same_direction_platform_conflicts: current.ignore_same_direction_platform_conflicts,
+            near_miss_threshold: current.near_miss_threshold,
+            angle_snap_degrees: current.angle_snap_degrees,
+            px_per_km: current.px_per_km,
+            min_curve_radius: current.min_curve_radius,
+            graph_start_hour: start_hour,
+            graph_end_hour: end_hour.clamp(start_hour + 1, MAX_GRAPH_HOUR_SETTING),
+            grid_size: current.grid_size,
+            show_grid: current.show_grid,
+            constant_line_width: current.constant_line_width,
+            geo_reference: current.geo_reference,
+            reference_week: current.reference_week,
+            conflict_window: current.conflict_window,
+        });
+    };
+
+    let handle_grid_change = move |grid_size: f64, show_grid: bool| {
+        let current = settings.get();
+        set_settings(ProjectSettings {
+            track_handedness: current.track_handedness,
+            line_sort_mode: current.line_sort_mode,
+            default_node_distance_grid_squares: current.default_node_distance_grid_squares,
+            minimum_separation: current.minimum_separation,
+            station_margin: current.station_margin,
+            ignore_same_direction_platform_conflicts: current.ignore_same_direction_platform_conflicts,
+            near_miss_threshold: current.near_miss_threshold,
+            angle_snap_degrees: current.angle_snap_degrees,
+            px_per_km: current.px_per_km,
+            min_curve_radius: current.min_curve_radius,
+            graph_start_hour: current.graph_start_hour,
+            graph_end_hour: current.graph_end_hour,
+            grid_size: grid_size.clamp(0.0, MAX_GRID_SIZE_SETTING),
+            show_grid,
+            constant_line_width: current.constant_line_width,
+            geo_reference: current.geo_reference,
+            reference_week: current.reference_week,
+            conflict_window: current.conflict_window,
+        });
+    };
+
+    let handle_constant_line_width_change = move |constant_line_width: bool| {
+        let current = settings.get();
+        set_settings(ProjectSettings {
+            track_handedness: current.track_handedness,
+            line_sort_mode: current.line_sort_mode,
+            default_node_distance_grid_squares: current.default_node_distance_grid_squares,
+            minimum_separation: current.minimum_separation,
+            station_margin: current.station_margin,
+            ignore_same_direction_platform_conflicts: current.ignore_same_direction_platform_conflicts,
+            near_miss_threshold: current.near_miss_threshold,
+            angle_snap_degrees: current.angle_snap_degrees,
+            px_per_km: current.px_per_km,
+            min_curve_radius: current.min_curve_radius,
+            graph_start_hour: current.graph_start_hour,
+            graph_end_hour: current.graph_end_hour,
+            grid_size: current.grid_size,
+            show_grid: current.show_grid,
+            constant_line_width,
+            geo_reference: current.geo_reference,
+            reference_week: current.reference_week,
+            conflict_window: current.conflict_window,
+        });
+    };
+
+    let handle_conflict_window_change = move |conflict_window: ConflictWindow| {
+        let current = settings.get();
+        set_settings(ProjectSettings {
+            track_handedness: current.track_handedness,
+            line_sort_mode: current.line_sort_mode,
+            default_node_distance_grid_squares: current.default_node_distance_grid_squares,
+            minimum_separation: current.minimum_separation,
+            station_margin: current.station_margin,
+            ignore_same_direction_platform_conflicts: current.ignore_same_direction_platform_conflicts,
+            near_miss_threshold: current.near_miss_threshold,
+            angle_snap_degrees: current.angle_snap_degrees,
+            px_per_km: current.px_per_km,
+            min_curve_radius: current.min_curve_radius,
+            graph_start_hour: current.graph_start_hour,
+            graph_end_hour: current.graph_end_hour,
+            grid_size: current.grid_size,
+            show_grid: current.show_grid,
+            constant_line_width: current.constant_line_width,
+            geo_reference: current.geo_reference,
+            reference_week: current.reference_week,
+            conflict_window,
+        });
+    };
+
+    let handle_reference_week_change = move |reference_week: Option<NaiveDate>| {
+        let current = settings.get();
+        set_settings(ProjectSettings {
+            track_handedness: current.track_handedness,
+            line_sort_mode: current.line_sort_mode,
+            default_node_distance_grid_squares: current.default_node_distance_grid_squares,
+            minimum_separation: current.minimum_separation,
+            station_margin: current.station_margin,
+            ignore_same_direction_platform_conflicts: current.ignore_same_direction_platform_conflicts,
+            near_miss_threshold: current.near_miss_threshold,
+            angle_snap_degrees: current.angle_snap_degrees,
+            px_per_km: current.px_per_km,
+            min_curve_radius: current.min_curve_radius,
+            graph_start_hour: current.graph_start_hour,
+            graph_end_hour: current.graph_end_hour,
+            grid_size: current.grid_size,
+            show_grid: current.show_grid,
+            constant_line_width: current.constant_line_width,
+            geo_reference: current.geo_reference,
+            reference_week,
+            conflict_window: current.conflict_window,
+        });
+    };
+
+    let handle_geo_reference_change = move |geo_reference: Option<GeoReference>| {
+        let current = settings.get();
+        set_settings(ProjectSettings {
+            track_handedness: current.track_handedness,
+            line_sort_mode: current.line_sort_mode,
+            default_node_distance_grid_squares: current.default_node_distance_grid_squares,
+            minimum_separation: current.minimum_separation,
+            station_margin: current.station_margin,
+            ignore_same_direction_platform_conflicts: current.ignore_same_direction_platform_conflicts,
+            near_miss_threshold: current.near_miss_threshold,
+            angle_snap_degrees: current.angle_snap_degrees,
+            px_per_km: current.px_per_km,
+            min_curve_radius: current.min_curve_radius,
+            graph_start_hour: current.graph_start_hour,
+            graph_end_hour: current.graph_end_hour,
+            grid_size: current.grid_size,
+            show_grid: current.show_grid,
+            constant_line_width: current.constant_line_width,
+            geo_reference,
+            reference_week: current.reference_week,
+            conflict_window: current.conflict_window,
         });
     };
 
@@ -160,8 +477,9 @@ pub fn Settings(
                                     <span class="help-text">
                                         {move || {
                                             #[allow(clippy::cast_possible_truncation)]
-                                            let grid_squares = settings.get().default_node_distance_grid_squares.round() as i32;
-                                            format!("(grid squares, {} px)", grid_squares * 30)
+                                            let current = settings.get();
+                                            let px = current.default_node_distance_grid_squares.round() * current.grid_square_size();
+                                            format!("(grid squares, {px} px)")
                                         }}
                                     </span>
                                 </label>
@@ -185,6 +503,221 @@ pub fn Settings(
                                     "Affects auto-layout, alignment, and rotation operations. Range: 1-20. Default: 4 (120 px)."
                                 </p>
                             </div>
+
+                            <div class="form-field">
+                                <label>
+                                    "Grid Size "
+                                    <span class="help-text">"(px)"</span>
+                                </label>
+                                <input
+                                    type="number"
+                                    min="0"
+                                    max=MAX_GRID_SIZE_SETTING
+                                    step="5"
+                                    prop:value=move || settings.get().grid_size.to_string()
+                                    on:input=move |ev| {
+                                        if let Ok(val) = leptos::event_target_value(&ev).parse::<f64>() {
+                                            handle_grid_change(val, settings.get().show_grid);
+                                        }
+                                    }
+                                />
+                                <label class="checkbox-label">
+                                    <input
+                                        type="checkbox"
+                                        checked=move || settings.get().show_grid
+                                        on:change=move |ev| handle_grid_change(settings.get().grid_size, leptos::event_target_checked(&ev))
+                                    />
+                                    " Show grid"
+                                </label>
+                                <p class="help-text">
+                                    "Stations snap to this grid when placed or dragged. 0 disables snapping. Default: 30."
+                                </p>
+                            </div>
+
+                            <div class="form-field">
+                                <label>
+                                    "Branch Angle Snap "
+                                    <span class="help-text">"(degrees)"</span>
+                                </label>
+                                <input
+                                    type="number"
+                                    min=MIN_ANGLE_SNAP_SETTING
+                                    max=MAX_ANGLE_SNAP_SETTING
+                                    step="5"
+                                    prop:value=move || settings.get().angle_snap_degrees.to_string()
+                                    on:input=move |ev| {
+                                        if let Ok(val) = leptos::event_target_value(&ev).parse::<f64>() {
+                                            handle_angle_snap_change(val);
+                                        }
+                                    }
+                                />
+                                <p class="help-text">
+                                    "Dragged branches snap to multiples of this angle when auto-layout is on. Range: 5-90. Default: 45."
+                                </p>
+                            </div>
+
+                            <div class="form-field">
+                                <label>
+                                    "Map Scale "
+                                    <span class="help-text">"(px per km)"</span>
+                                </label>
+                                <input
+                                    type="number"
+                                    min="1"
+                                    step="1"
+                                    prop:value=move || settings.get().px_per_km.to_string()
+                                    on:input=move |ev| {
+                                        if let Ok(val) = leptos::event_target_value(&ev).parse::<f64>() {
+                                            handle_px_per_km_change(val);
+                                        }
+                                    }
+                                />
+                                {on_auto_fill_distances.map(|handler| view! {
+                                    <button
+                                        class="auto-fill-distances-button"
+                                        on:click=move |_| set_auto_fill_result.set(Some(handler()))
+                                    >
+                                        "Auto-fill distances"
+                                    </button>
+                                })}
+                                {move || auto_fill_result.get().map(|count| view! {
+                                    <span class="help-text">{format!(" Filled {count} track distances")}</span>
+                                })}
+                                <p class="help-text">
+                                    "Used to fill in missing track distances from station positions. Default: 30 (one grid square per km)."
+                                </p>
+                            </div>
+
+                            <div class="form-field">
+                                <label>
+                                    "Minimum Curve Radius "
+                                    <span class="help-text">"(px)"</span>
+                                </label>
+                                <input
+                                    type="number"
+                                    min=MIN_CURVE_RADIUS_SETTING
+                                    max=MAX_CURVE_RADIUS_SETTING
+                                    step="1"
+                                    prop:value=move || settings.get().min_curve_radius.to_string()
+                                    on:input=move |ev| {
+                                        if let Ok(val) = leptos::event_target_value(&ev).parse::<f64>() {
+                                            handle_min_curve_radius_change(val);
+                                        }
+                                    }
+                                />
+                                <p class="help-text">
+                                    "Tightest curve lines may take through junctions and stations in line mode. Range: 2-100. Default: 20."
+                                </p>
+                            </div>
+
+                            <div class="form-field">
+                                <label class="checkbox-label">
+                                    <input
+                                        type="checkbox"
+                                        checked=move || settings.get().constant_line_width
+                                        on:change=move |ev| handle_constant_line_width_change(leptos::event_target_checked(&ev))
+                                    />
+                                    " Constant line width"
+                                </label>
+                                <p class="help-text">
+                                    "Keep lines the same width on screen at every zoom level in line mode, instead of scaling them with the map."
+                                </p>
+                            </div>
+                        </div>
+
+                        {stations.map(|stations| view! {
+                            <div class="settings-section">
+                                <h3>"Map Reference"</h3>
+                                <p class="section-description">
+                                    "Pin two stations to their real longitude and latitude so GeoJSON exports line up with real maps. Without a reference, exports use canvas coordinates."
+                                </p>
+                                <GeoReferenceEditor
+                                    stations=stations
+                                    geo_reference=Signal::derive(move || settings.get().geo_reference)
+                                    set_geo_reference=handle_geo_reference_change
+                                />
+                            </div>
+                        })}
+
+                        <div class="settings-section">
+                            <h3>"Time Graph"</h3>
+
+                            <div class="form-field">
+                                <label>
+                                    "Time Axis Range "
+                                    <span class="help-text">"(hours)"</span>
+                                </label>
+                                <input
+                                    type="number"
+                                    min="0"
+                                    max=MAX_GRAPH_HOUR_SETTING - 1
+                                    step="1"
+                                    prop:value=move || settings.get().graph_start_hour.to_string()
+                                    on:change=move |ev| {
+                                        if let Ok(val) = leptos::event_target_value(&ev).parse::<u32>() {
+                                            handle_time_window_change(val, settings.get().graph_end_hour);
+                                        }
+                                    }
+                                />
+                                " to "
+                                <input
+                                    type="number"
+                                    min="1"
+                                    max=MAX_GRAPH_HOUR_SETTING
+                                    step="1"
+                                    prop:value=move || settings.get().graph_end_hour.to_string()
+                                    on:change=move |ev| {
+                                        if let Ok(val) = leptos::event_target_value(&ev).parse::<u32>() {
+                                            handle_time_window_change(settings.get().graph_start_hour, val);
+                                        }
+                                    }
+                                />
+                                <p class="help-text">
+                                    "Hours shown across the time graph before zooming. Use hours past 24 for overnight services, e.g. 4 to 28. Default: 0 to 48."
+                                </p>
+                            </div>
+
+                            <div class="form-field">
+                                <label class="checkbox-label">
+                                    <input
+                                        type="checkbox"
+                                        checked=move || settings.get().conflict_window == ConflictWindow::TimeAxis
+                                        on:change=move |ev| handle_conflict_window_change(if leptos::event_target_checked(&ev) { ConflictWindow::TimeAxis } else { ConflictWindow::AllTimes })
+                                    />
+                                    " Only check conflicts within the time axis range"
+                                </label>
+                                <p class="help-text">
+                                    "Skip trains and conflicts outside the hours above. Speeds up conflict detection on large multi-day timetables."
+                                </p>
+                            </div>
+                        </div>
+
+                        <div class="settings-section">
+                            <h3>"Calendar"</h3>
+
+                            <div class="form-field">
+                                <label>"Reference Week"</label>
+                                <input
+                                    type="date"
+                                    prop:value=move || settings.get().reference_week.map(|date| date.to_string()).unwrap_or_default()
+                                    on:change=move |ev| {
+                                        handle_reference_week_change(NaiveDate::parse_from_str(&leptos::event_target_value(&ev), "%Y-%m-%d").ok());
+                                    }
+                                />
+                                <p class="help-text">
+                                    {format!("Any date in the real week the simulated Monday to Sunday stands for. Line operating periods and service pattern exceptions are matched against this week. Default: the week of {BASE_DATE}.")}
+                                </p>
+                            </div>
+
+                            {use_context::<ServicePatterns>().map(|service_patterns| view! {
+                                <div class="form-field">
+                                    <label>"Service Patterns"</label>
+                                    <ServicePatternsEditor service_patterns=service_patterns/>
+                                    <p class="help-text">
+                                        "Named operating-day calendars lines can use instead of their own days. Exceptions are real dates on which the pattern does not run, such as public holidays."
+                                    </p>
+                                </div>
+                            })}
                         </div>
 
                         <div class="settings-section">
@@ -219,6 +752,19 @@ pub fn Settings(
                                 </p>
                             </div>
 
+                            <div class="form-field">
+                                <label>
+                                    "Near-miss Threshold"
+                                </label>
+                                <OptionalDurationInput
+                                    duration=Signal::derive(move || settings.get().near_miss_threshold)
+                                    on_change=handle_near_miss_threshold_change
+                                />
+                                <p class="help-text">
+                                    "Flag trains that pass a station on the same track closer together than this, even without a conflict. Leave empty to disable."
+                                </p>
+                            </div>
+
                             <div>
                                 <label class="checkbox-label">
                                     <input
@@ -3,8 +3,9 @@ use crate::components::line_controls::LineControls;
 use crate::components::line_editor::LineEditor;
 use crate::components::button::Button;
 use crate::components::importer::Importer;
+use crate::components::history_panel::HistoryPanel;
 use crate::components::settings::Settings;
-use crate::models::{RailwayGraph, Line, LineFolder, ProjectSettings, GraphView};
+use crate::models::{RailwayGraph, Line, LineFolder, ProjectSettings, GraphView, Stations, Tracks};
 use wasm_bindgen::closure::Closure;
 use wasm_bindgen::JsCast;
 use web_sys::MouseEvent;
@@ -183,15 +184,25 @@ pub fn Sidebar(
                     <i class="fa-solid fa-plus"></i>
                 </Button>
                 <Importer lines=lines set_lines=set_lines graph=graph set_graph=set_graph settings=settings />
+                <HistoryPanel />
                 {footer_children.as_ref().map(|f| f())}
                 <Settings
                     settings=leptos::Signal::derive(move || settings.get())
                     set_settings=move |s| set_settings.set(s)
+                    stations=Signal::derive(move || graph.with(|g| {
+                        g.get_all_stations_ordered().into_iter().map(|(idx, station)| (idx, station.name)).collect()
+                    }))
                     on_open_changelog=move || {
                         if let Some(callback) = on_open_changelog {
                             callback.call(());
                         }
                     }
+                    on_auto_fill_distances=move || {
+                        let px_per_km = settings.get_untracked().px_per_km;
+                        let mut filled = 0;
+                        set_graph.update(|g| filled = g.auto_fill_distances(px_per_km));
+                        filled
+                    }
                 />
             </div>
 
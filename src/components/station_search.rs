@@ -0,0 +1,69 @@
+use crate::models::{RailwayGraph, Stations, Tracks};
+use leptos::{component, create_signal, event_target_value, view, Callable, Callback, IntoView, ReadSignal, Signal, SignalGet, SignalSet, SignalWith};
+use petgraph::stable_graph::NodeIndex;
+
+const MAX_SEARCH_RESULTS: usize = 10;
+
+#[component]
+#[must_use]
+pub fn StationSearch(
+    graph: ReadSignal<RailwayGraph>,
+    on_select: Callback<NodeIndex>,
+    /// Restrict results to these nodes (e.g. the stations shown in a view)
+    #[prop(optional)]
+    candidates: Option<Signal<Vec<NodeIndex>>>,
+) -> impl IntoView {
+    let (query, set_query) = create_signal(String::new());
+
+    let results = Signal::derive(move || {
+        let mut matches = graph.with(|g| {
+            let query = query.get();
+            let mut matches = g.search_stations(&query);
+            matches.extend(g.search_track_notes(&query));
+            matches
+        });
+        if let Some(candidates) = candidates {
+            candidates.with(|allowed| matches.retain(|(idx, _)| allowed.contains(idx)));
+        }
+        matches.truncate(MAX_SEARCH_RESULTS);
+        matches
+    });
+
+    let select = move |idx: NodeIndex| {
+        set_query.set(String::new());
+        on_select.call(idx);
+    };
+
+    let handle_keydown = move |ev: web_sys::KeyboardEvent| match ev.key().as_str() {
+        "Enter" => {
+            if let Some((idx, _)) = results.get().first() {
+                select(*idx);
+            }
+        }
+        "Escape" => set_query.set(String::new()),
+        _ => {}
+    };
+
+    view! {
+        <div class="station-search">
+            <i class="fa-solid fa-magnifying-glass"></i>
+            <input
+                type="text"
+                placeholder="Search stations and track notes..."
+                prop:value=move || query.get()
+                on:input=move |ev| set_query.set(event_target_value(&ev))
+                on:keydown=handle_keydown
+            />
+            {move || {
+                let matches = results.get();
+                (!matches.is_empty()).then(|| view! {
+                    <ul class="station-search-results">
+                        {matches.into_iter().map(|(idx, name)| view! {
+                            <li on:mousedown=move |_| select(idx)>{name}</li>
+                        }).collect::<Vec<_>>()}
+                    </ul>
+                })
+            }}
+        </div>
+    }
+}
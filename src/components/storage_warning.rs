@@ -0,0 +1,130 @@
+use leptos::{component, create_effect, create_signal, spawn_local, use_context, view, IntoView, ReadSignal, Signal, SignalGet, SignalGetUntracked, SignalSet, SignalWith, WriteSignal};
+use crate::components::confirmation_dialog::ConfirmationDialog;
+use crate::models::{ProjectMetadata, UserSettings};
+use crate::storage::{format_bytes, storage_usage_exceeds, IndexedDbStorage, Storage};
+use std::rc::Rc;
+
+/// Number of projects suggested for deletion
+const MAX_SUGGESTED_PROJECTS: usize = 5;
+
+/// Saved projects other than the open one, largest first
+fn largest_projects(mut projects: Vec<ProjectMetadata>, current_project_id: &str) -> Vec<ProjectMetadata> {
+    projects.retain(|project| project.id != current_project_id);
+    projects.sort_by_key(|project| std::cmp::Reverse(project.size_bytes));
+    projects.truncate(MAX_SUGGESTED_PROJECTS);
+    projects
+}
+
+/// Refresh the quota and, when it is over the warning threshold, the project sizes
+fn check_storage(
+    warning_fraction: f64,
+    set_quota: WriteSignal<Option<(u64, u64)>>,
+    set_projects: WriteSignal<Vec<ProjectMetadata>>,
+) {
+    spawn_local(async move {
+        let storage = IndexedDbStorage;
+        let quota = storage.get_storage_quota().await.ok().flatten();
+        if !storage_usage_exceeds(quota, warning_fraction) {
+            set_quota.set(None);
+            return;
+        }
+        set_quota.set(quota);
+        match storage.list_projects().await {
+            Ok(projects) => set_projects.set(projects),
+            Err(e) => leptos::logging::warn!("Failed to list projects for storage warning: {}", e),
+        }
+    });
+}
+
+/// Banner shown when browser storage is nearly full, suggesting large projects to delete
+#[component]
+#[must_use]
+pub fn StorageWarning(
+    /// Check storage once this becomes true, e.g. after the project has loaded
+    ready: Signal<bool>,
+    current_project_id: Signal<String>,
+) -> impl IntoView {
+    let (user_settings, _) = use_context::<(ReadSignal<UserSettings>, WriteSignal<UserSettings>)>()
+        .expect("UserSettings context not found");
+
+    let (quota, set_quota) = create_signal(None::<(u64, u64)>);
+    let (projects, set_projects) = create_signal(Vec::<ProjectMetadata>::new());
+    let (dismissed, set_dismissed) = create_signal(false);
+    let (delete_target, set_delete_target) = create_signal(None::<ProjectMetadata>);
+
+    create_effect(move |_| {
+        if ready.get() {
+            check_storage(user_settings.with(|s| s.storage_warning_fraction), set_quota, set_projects);
+        }
+    });
+
+    let suggestions = move || largest_projects(projects.get(), &current_project_id.get());
+
+    let confirm_delete: Rc<dyn Fn()> = Rc::new(move || {
+        let Some(target) = delete_target.get_untracked() else { return };
+        set_delete_target.set(None);
+        let warning_fraction = user_settings.get_untracked().storage_warning_fraction;
+        spawn_local(async move {
+            if let Err(e) = IndexedDbStorage.delete_project(&target.id).await {
+                leptos::logging::error!("Failed to delete project: {}", e);
+            }
+            check_storage(warning_fraction, set_quota, set_projects);
+        });
+    });
+    let cancel_delete: Rc<dyn Fn()> = Rc::new(move || set_delete_target.set(None));
+
+    view! {
+        {move || quota.get().filter(|_| !dismissed.get()).map(|(used, total)| {
+            #[allow(clippy::cast_precision_loss)]
+            let usage_percent = used as f64 / total as f64 * 100.0;
+            view! {
+                <div class="storage-quota-warning">
+                    <div class="storage-quota-warning-header">
+                        <i class="fa-solid fa-hard-drive"></i>
+                        <span>
+                            {format!("Storage nearly full: {} / {} ({usage_percent:.0}% used)", format_bytes(used), format_bytes(total))}
+                        </span>
+                        <button class="storage-quota-warning-close" on:click=move |_| set_dismissed.set(true) title="Dismiss">"×"</button>
+                    </div>
+                    {move || {
+                        let suggested = suggestions();
+                        (!suggested.is_empty()).then(|| view! {
+                            <p class="storage-quota-warning-hint">"Deleting large projects frees up space:"</p>
+                            <ul class="storage-quota-projects">
+                                {suggested.into_iter().map(|project| {
+                                    let target = project.clone();
+                                    view! {
+                                        <li>
+                                            <span class="project-name">{project.name}</span>
+                                            <span class="project-size">{format_bytes(project.size_bytes)}</span>
+                                            <button
+                                                class="action-button danger"
+                                                on:click=move |_| set_delete_target.set(Some(target.clone()))
+                                                title="Delete project"
+                                            >
+                                                <i class="fa-solid fa-trash"></i>
+                                            </button>
+                                        </li>
+                                    }
+                                }).collect::<Vec<_>>()}
+                            </ul>
+                        })
+                    }}
+                </div>
+            }
+        })}
+
+        <ConfirmationDialog
+            is_open=Signal::derive(move || delete_target.with(Option::is_some))
+            title=Signal::derive(|| "Delete Project".to_string())
+            message=Signal::derive(move || format!(
+                "Are you sure you want to delete '{}'? This action cannot be undone.",
+                delete_target.with(|t| t.as_ref().map(|p| p.name.clone()).unwrap_or_default())
+            ))
+            on_confirm=confirm_delete
+            on_cancel=cancel_delete
+            confirm_text="Delete".to_string()
+            cancel_text="Cancel".to_string()
+        />
+    }
+}
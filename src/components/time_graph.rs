@@ -1,16 +1,18 @@
 use crate::components::{
     day_selector::DaySelector,
     error_list::ErrorList,
-    graph_canvas::GraphCanvas,
+    graph_canvas::{GraphCanvas, HighlightedJourney, VisualizationTime},
+    station_search::StationSearch,
     legend::Legend,
-    sidebar::Sidebar
+    sidebar::Sidebar,
+    timetable_export_button::TimetableExportButton,
 };
-use crate::conflict::Conflict;
+use crate::conflict::{cycle_conflict_index, Conflict};
 #[allow(unused_imports)]
 use crate::logging::log;
-use crate::models::{Line, RailwayGraph, GraphView, Stations, Routes};
+use crate::models::{Line, RailwayGraph, GraphView, Stations, UserSettings};
 use crate::train_journey::TrainJourney;
-use leptos::{component, view, Signal, IntoView, SignalGet, SignalGetUntracked, create_signal, create_memo, ReadSignal, WriteSignal, SignalUpdate, SignalSet, create_effect, Callable};
+use leptos::{component, view, Signal, IntoView, SignalGet, SignalGetUntracked, SignalWith, SignalWithUntracked, create_signal, create_memo, ReadSignal, WriteSignal, SignalUpdate, SignalSet, create_effect, Callable, use_context};
 use petgraph::visit::EdgeRef;
 
 #[inline]
@@ -24,6 +26,9 @@ fn compute_display_nodes(
             graph_view.get_nodes_for_display(&current_graph)
         } else {
             current_graph.get_all_nodes_ordered()
+                .into_iter()
+                .filter(|(_, node)| !node.is_depot())
+                .collect()
         }
     })
 }
@@ -36,16 +41,7 @@ fn compute_edge_path(
         let current_graph = graph.get();
         if let Some(ref graph_view) = view {
             // Use view's edge_path if available, otherwise calculate from station_range
-            let edge_path = if let Some(ref edge_path) = graph_view.edge_path {
-                edge_path.clone()
-            } else if let Some((from, to)) = graph_view.station_range {
-                // Calculate edge path from station range
-                current_graph.find_path_between_nodes(from, to)
-                    .map(|edges| edges.iter().map(|e| e.index()).collect())
-                    .unwrap_or_default()
-            } else {
-                Vec::new()
-            };
+            let edge_path = graph_view.display_edge_path(&current_graph);
 
             // Log the computed edge path
             log!("View '{}' edge_path: {:?}", graph_view.name, edge_path);
@@ -87,11 +83,16 @@ fn build_station_index_mapping(graph: &RailwayGraph) -> std::collections::HashMa
     queue.push_back(start_node);
     seen.insert(start_node);
 
+    // Depots are not shown on the time graph and take no display index
+    let is_depot = |node_idx| graph.graph.node_weight(node_idx).is_some_and(crate::models::Node::is_depot);
+
     while let Some(node_idx) = queue.pop_front() {
-        if let Some(&enum_idx) = node_to_enum_idx.get(&node_idx) {
-            map.insert(enum_idx, display_idx);
+        if !is_depot(node_idx) {
+            if let Some(&enum_idx) = node_to_enum_idx.get(&node_idx) {
+                map.insert(enum_idx, display_idx);
+            }
+            display_idx += 1;
         }
-        display_idx += 1;
 
         for edge in graph.graph.edges(node_idx) {
             let target = edge.target();
@@ -103,7 +104,7 @@ fn build_station_index_mapping(graph: &RailwayGraph) -> std::collections::HashMa
 
     // Handle disconnected nodes
     for node_idx in graph.graph.node_indices() {
-        if !seen.insert(node_idx) {
+        if !seen.insert(node_idx) || is_depot(node_idx) {
             continue;
         }
         if let Some(&enum_idx) = node_to_enum_idx.get(&node_idx) {
@@ -158,12 +159,16 @@ pub fn TimeGraph(
     on_open_project_manager: Option<leptos::Callback<()>>,
     sidebar_visible: ReadSignal<bool>,
 ) -> impl IntoView {
-    let (visualization_time, set_visualization_time) =
-        create_signal(chrono::Local::now().naive_local());
+    let (visualization_time, set_visualization_time) = leptos::use_context::<VisualizationTime>()
+        .map_or_else(|| create_signal(chrono::Local::now().naive_local()), |shared| (shared.time, shared.set_time));
 
     // Extract legend signals
     let show_conflicts = Signal::derive(move || legend.get().show_conflicts);
     let show_line_blocks = Signal::derive(move || legend.get().show_line_blocks);
+    let show_headway_bands = Signal::derive(move || legend.get().show_headway_bands);
+    let headway_separation = Signal::derive(move || {
+        show_headway_bands.get().then(|| settings.with(|s| s.minimum_separation))
+    });
     let spacing_mode = Signal::derive(move || legend.get().spacing_mode);
 
     let set_show_conflicts = move |value: bool| {
@@ -172,13 +177,26 @@ pub fn TimeGraph(
     let set_show_line_blocks = move |value: bool| {
         set_legend.update(|l| l.show_line_blocks = value);
     };
+    let set_show_headway_bands = move |value: bool| {
+        set_legend.update(|l| l.show_headway_bands = value);
+    };
     let set_spacing_mode = move |value: crate::models::SpacingMode| {
         set_legend.update(|l| l.spacing_mode = value);
     };
+    let legend_entries = Signal::derive(move || legend.with(|l| l.entries.clone()));
+    let set_legend_entries = move |entries: Vec<crate::models::LegendEntry>| {
+        set_legend.update(|l| l.entries = entries);
+    };
+    let regenerate_legend = move || {
+        lines.with_untracked(|lines| set_legend.update(|l| l.regenerate_entries(lines)));
+    };
 
     // Track hovered journey for block visualization
     let (hovered_journey_id, set_hovered_journey_id) = create_signal(None::<uuid::Uuid>);
 
+    // Journey highlighted across the time graph and infrastructure view
+    let highlighted_journey = leptos::use_context::<HighlightedJourney>();
+
     // Track which lines currently have editors open (for dimming other journeys)
     let (edited_line_ids, set_edited_line_ids) = create_signal(std::collections::HashSet::<uuid::Uuid>::new());
 
@@ -211,7 +229,11 @@ pub fn TimeGraph(
                 .collect();
             set_filtered_journeys.set(filtered_map);
         } else {
-            // No view, show all journeys
+            // No view, show all journeys, drawing passed stations like the filtered views do
+            let mut all_journeys = all_journeys;
+            for journey in all_journeys.values_mut().filter(|journey| !journey.passed_stations.is_empty()) {
+                *journey = journey.with_passed_stations().into_owned();
+            }
             set_filtered_journeys.set(all_journeys);
         }
     });
@@ -243,9 +265,54 @@ pub fn TimeGraph(
 
     // Signal for panning to conflicts
     let (pan_to_conflict, set_pan_to_conflict) = create_signal(None::<(f64, f64)>);
+    let (pan_to_station, set_pan_to_station) = create_signal(None::<usize>);
+
+    // Pan to a conflict and highlight the first train involved
+    let focus_conflict = move |time_fraction: f64, station_pos: f64, conflict: &Conflict| {
+        set_pan_to_conflict.set(Some((time_fraction, station_pos)));
+        if let Some(highlighted) = highlighted_journey {
+            let journeys = filtered_journeys.get_untracked();
+            let journey_id = TrainJourney::find_by_train_number(journeys.values(), &conflict.journey1_id, conflict.time)
+                .map(|journey| journey.id);
+            highlighted.set_journey_id.set(journey_id);
+        }
+    };
+
+    // Cycle through conflicts with the next/previous conflict shortcuts
+    let (current_conflict_index, set_current_conflict_index) = create_signal(None::<usize>);
+    let (user_settings, _) = use_context::<(ReadSignal<UserSettings>, WriteSignal<UserSettings>)>()
+        .expect("UserSettings context not found");
+    let (is_capturing_shortcut, _) = use_context::<(ReadSignal<bool>, WriteSignal<bool>)>()
+        .expect("is_capturing_shortcut context not found");
+    let shortcuts = create_memo(move |_| user_settings.get().keyboard_shortcuts);
+    crate::models::setup_shortcut_handler(is_capturing_shortcut, shortcuts, move |action_id, ev| {
+        let forward = match action_id {
+            "next_conflict" => true,
+            "prev_conflict" => false,
+            _ => return,
+        };
+        ev.prevent_default();
+
+        let current_conflicts = conflicts_memo.get_untracked();
+        let Some(index) = cycle_conflict_index(current_conflict_index.get_untracked(), current_conflicts.len(), forward) else {
+            return;
+        };
+        set_current_conflict_index.set(Some(index));
+        let conflict = &current_conflicts[index];
+        if let Some((time_fraction, station_pos)) = conflict.graph_position(&station_idx_map.get_untracked()) {
+            focus_conflict(time_fraction, station_pos, conflict);
+        }
+    });
+
+    let search_candidates = Signal::derive(move || display_stations.get().into_iter().map(|(idx, _)| idx).collect::<Vec<_>>());
+    let focus_station = leptos::Callback::new(move |station_idx: petgraph::stable_graph::NodeIndex| {
+        let display_idx = display_stations.get_untracked().iter().position(|(idx, _)| *idx == station_idx);
+        set_pan_to_station.set(display_idx);
+    });
 
     // Sidebar width state
     let initial_sidebar_width = view.as_ref().map_or(320.0, |v| v.viewport_state.sidebar_width);
+    let timetable_filename = leptos::store_value(view.as_ref().map_or_else(|| "timetable.html".to_string(), |v| format!("{} timetable.html", v.name)));
     let (sidebar_width, set_sidebar_width) = create_signal(initial_sidebar_width);
 
     // Callback for when sidebar width changes
@@ -276,11 +343,13 @@ pub fn TimeGraph(
                     set_visualization_time=set_visualization_time
                     show_conflicts=show_conflicts
                     show_line_blocks=show_line_blocks
+                    headway_separation=headway_separation
                     spacing_mode=spacing_mode
                     hovered_journey_id=hovered_journey_id
                     set_hovered_journey_id=set_hovered_journey_id
                     conflicts_memo=conflicts_memo
                     pan_to_conflict_signal=pan_to_conflict
+                    pan_to_station_signal=pan_to_station
                     display_stations=display_stations
                     station_idx_map=station_idx_map
                     view_edge_path=view_edge_path
@@ -288,8 +357,10 @@ pub fn TimeGraph(
                     on_viewport_change=wrapped_viewport_change
                     edited_line_ids=edited_line_ids
                     sidebar_width=sidebar_width
+                    time_window=Signal::derive(move || settings.with(|s| (f64::from(s.graph_start_hour), f64::from(s.graph_end_hour))))
                 />
             </div>
+            <StationSearch graph=graph on_select=focus_station candidates=search_candidates/>
             {move || sidebar_visible.get().then(|| view! {
                 <Sidebar
                     lines=lines
@@ -315,11 +386,16 @@ pub fn TimeGraph(
                         />
                         <ErrorList
                             conflicts=conflicts
-                            on_conflict_click=move |time_fraction, station_pos| {
-                                set_pan_to_conflict.set(Some((time_fraction, station_pos)));
-                            }
+                            on_conflict_click=focus_conflict
                             graph=graph
                             station_idx_map=station_idx_map
+                            lines=lines
+                            train_journeys=filtered_journeys
+                        />
+                        <TimetableExportButton
+                            train_journeys=filtered_journeys
+                            graph=graph
+                            filename=timetable_filename.get_value()
                         />
                     }.into_view().into()))
                     footer_children=Some(Box::new(move || view! {
@@ -328,8 +404,13 @@ pub fn TimeGraph(
                             set_show_conflicts=set_show_conflicts
                             show_line_blocks=show_line_blocks
                             set_show_line_blocks=set_show_line_blocks
+                            show_headway_bands=show_headway_bands
+                            set_show_headway_bands=set_show_headway_bands
                             spacing_mode=spacing_mode
                             set_spacing_mode=set_spacing_mode
+                            entries=legend_entries
+                            set_entries=set_legend_entries
+                            on_regenerate=regenerate_legend
                         />
                     }.into_view().into()))
                 />
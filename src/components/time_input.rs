@@ -1,9 +1,11 @@
-use leptos::{component, view, Signal, IntoView, SignalGet, SignalGetUntracked, event_target_value, create_node_ref, html::Input};
+use leptos::{component, view, Signal, IntoView, SignalGet, SignalGetUntracked, SignalSet, event_target_value, create_node_ref, create_signal, html::Input};
 use chrono::{NaiveDateTime, Duration};
 use crate::constants::BASE_DATE;
 use web_sys::KeyboardEvent;
 use std::rc::Rc;
 
+const INVALID_TIME_HINT: &str = "Enter a time like 8:05, 0805, 8h5m or 485s";
+
 #[component]
 #[must_use]
 pub fn TimeInput(
@@ -14,6 +16,7 @@ pub fn TimeInput(
     #[prop(optional)] show_next_day_indicator: Option<Signal<bool>>,
 ) -> impl IntoView {
     let input_ref = create_node_ref::<Input>();
+    let (invalid, set_invalid) = create_signal(false);
     let on_change = Rc::new(on_change);
     let on_change_clone = on_change.clone();
 
@@ -24,12 +27,19 @@ pub fn TimeInput(
                 <input
                     type="text"
                     class="time-input"
+                    class:invalid=invalid
+                    title=move || invalid.get().then_some(INVALID_TIME_HINT)
                     prop:value=move || value.get().format("%H:%M:%S").to_string()
                     placeholder=default_time
                     node_ref=input_ref
+                    on:input=move |ev| {
+                        let time_str = event_target_value(&ev);
+                        set_invalid.set(!time_str.trim().is_empty() && crate::time::parse_flexible_time(&time_str).is_none());
+                    }
                     on:change=move |ev| {
                         let time_str = event_target_value(&ev);
-                        if let Ok(naive_time) = crate::time::parse_time_hms(&time_str) {
+                        set_invalid.set(false);
+                        if let Some(naive_time) = crate::time::parse_flexible_time(&time_str) {
                             let new_datetime = BASE_DATE.and_time(naive_time);
                             on_change(new_datetime);
                         } else {
@@ -0,0 +1,33 @@
+use crate::export::table;
+use crate::models::RailwayGraph;
+use crate::storage::trigger_download;
+use crate::train_journey::TrainJourney;
+use leptos::{component, view, IntoView, ReadSignal, SignalWithUntracked};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Download the journeys shown in the graph as a printable HTML timetable
+#[component]
+#[must_use]
+pub fn TimetableExportButton(
+    train_journeys: ReadSignal<HashMap<Uuid, TrainJourney>>,
+    graph: ReadSignal<RailwayGraph>,
+    filename: String,
+) -> impl IntoView {
+    let export = move |_| {
+        let html = train_journeys.with_untracked(|journeys| {
+            let journeys: Vec<TrainJourney> = journeys.values().cloned().collect();
+            graph.with_untracked(|graph| table(&journeys, graph))
+        });
+        if let Err(e) = trigger_download(html.as_bytes(), &filename) {
+            leptos::logging::error!("Failed to export timetable: {}", e);
+        }
+    };
+
+    view! {
+        <button class="timetable-export-button" on:click=export title="Download the shown trains as a printable timetable">
+            <i class="fa-solid fa-table"></i>
+            " Export timetable"
+        </button>
+    }
+}
@@ -0,0 +1,46 @@
+use crate::models::RailwayGraph;
+use leptos::{component, IntoView, ReadSignal, SignalGet, SignalWith, view};
+use petgraph::stable_graph::EdgeIndex;
+
+fn track_tooltip_text(graph: &RailwayGraph, edge_idx: EdgeIndex) -> Option<String> {
+    let segment = graph.graph.edge_weight(edge_idx)?;
+    let mut parts = vec![format!("{} track(s)", segment.tracks.len())];
+    if let Some(distance) = segment.distance {
+        parts.push(format!("{distance:.1} km"));
+    }
+    if let Some(gradient) = segment.gradient_permille {
+        parts.push(format!("{gradient:+.1}‰"));
+    }
+    parts.push(segment.electrification.as_str().to_string());
+    if !segment.notes.is_empty() {
+        parts.push(segment.notes.clone());
+    }
+    Some(parts.join(" · "))
+}
+
+#[component]
+#[must_use]
+pub fn TrackTooltip(
+    hovered_track: ReadSignal<Option<(EdgeIndex, f64, f64)>>,
+    graph: ReadSignal<RailwayGraph>,
+) -> impl IntoView {
+    view! {
+        {move || {
+            let tooltip = hovered_track.get().and_then(|(edge_idx, x, y)| {
+                graph.with(|g| track_tooltip_text(g, edge_idx)).map(|text| (text, x, y))
+            });
+            if let Some((text, tooltip_x, tooltip_y)) = tooltip {
+                view! {
+                    <div
+                        class="track-tooltip"
+                        style=format!("left: {}px; top: {}px;", tooltip_x + 10.0, tooltip_y - 30.0)
+                    >
+                        {text}
+                    </div>
+                }.into_view()
+            } else {
+                view! { <div class="track-tooltip-hidden"></div> }.into_view()
+            }
+        }}
+    }
+}
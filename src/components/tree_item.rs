@@ -1,7 +1,7 @@
-use leptos::{component, view, ReadSignal, WriteSignal, IntoView, create_memo, Signal, SignalGet, SignalGetUntracked, SignalUpdate, SignalSet, SignalWith, For, store_value, Callback, Callable};
-use crate::models::{Line, LineFolder, RailwayGraph, GraphView, ViewportState, LineSortMode, Routes};
+use leptos::{component, view, ReadSignal, WriteSignal, IntoView, create_memo, Signal, SignalGet, SignalGetUntracked, SignalUpdate, SignalSet, SignalWith, For, store_value, Callback, Callable, use_context, event_target_value};
+use crate::models::{line_capacity_tph, peak_capacity_tph, Line, LineFolder, RailwayGraph, GraphView, ViewportState, LineSortMode, Routes};
 use crate::components::dropdown_menu::{DropdownMenu, MenuItem};
-use crate::components::line_controls::{handle_drop_into_folder, handle_drop_in_zone};
+use crate::components::line_controls::{handle_drop_into_folder, handle_drop_in_zone, LineSelection, MissingJourneys};
 use std::collections::HashMap;
 use std::rc::Rc;
 
@@ -11,7 +11,22 @@ pub enum TreeItem {
         folder: LineFolder,
         children: Vec<TreeItem>,
     },
-    Line(Line),
+    Line(Box<Line>),
+}
+
+/// Trains per hour with at most one decimal, dropping it for whole numbers
+fn format_tph(tph: f64) -> String {
+    let rounded = format!("{tph:.1}");
+    rounded.strip_suffix(".0").map_or_else(|| rounded.clone(), str::to_string)
+}
+
+/// Short capacity figure for a line, e.g. "4 tph" or "4–6 tph" when frequency bands add a peak
+fn capacity_label(line: &Line) -> String {
+    let base = line_capacity_tph(line);
+    match peak_capacity_tph(line).filter(|peak| format_tph(*peak) != format_tph(base)) {
+        Some(peak) => format!("{}–{} tph", format_tph(base.min(peak)), format_tph(base.max(peak))),
+        None => format!("{} tph", format_tph(base)),
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -55,7 +70,7 @@ pub fn build_tree(lines: Vec<Line>, folders: Vec<LineFolder>, sort_mode: LineSor
 
     // Add all lines as tree items
     for line in lines {
-        let tree_item = TreeItem::Line(line.clone());
+        let tree_item = TreeItem::Line(Box::new(line.clone()));
         // If line references an invalid folder, treat it as a root line
         let effective_folder_id = match line.folder_id {
             Some(id) if valid_folder_ids.contains(&id) => Some(id),
@@ -236,6 +251,17 @@ pub fn find_folder_children(items: &[TreeItem], target_id: uuid::Uuid) -> Option
     None
 }
 
+/// Ids of all lines in the order the sidebar lists them, including those in folders
+#[must_use]
+pub fn lines_in_tree_order(items: &[TreeItem]) -> Vec<uuid::Uuid> {
+    items.iter()
+        .flat_map(|item| match item {
+            TreeItem::Folder { children, .. } => lines_in_tree_order(children),
+            TreeItem::Line(line) => vec![line.id],
+        })
+        .collect()
+}
+
 // Find an item in the tree and return its siblings and parent folder context
 #[must_use]
 pub fn find_item_context(
@@ -285,17 +311,22 @@ pub fn LineControl(
     let on_edit = store_value(on_edit);
     let on_delete = store_value(on_delete);
     let on_duplicate = store_value(on_duplicate);
+    let selection = use_context::<LineSelection>();
+    let missing_journeys = use_context::<MissingJourneys>();
+    let missing_reason = move || missing_journeys.and_then(|m| m.0.with(|reasons| reasons.get(&line_id).copied()));
 
     view! {
         {move || {
             current_line.get().map(|line| {
                 let is_dragging = move || dragged_item.get() == Some(DraggedItem::Line(line_id));
+                let is_selected = move || selection.is_some_and(|s| s.selected.with(|ids| ids.contains(&line_id)));
 
                 view! {
                     <div
                         class=move || {
                             let mut classes = vec!["line-control"];
                             if is_dragging() { classes.push("dragging"); }
+                            if is_selected() { classes.push("selected"); }
                             classes.join(" ")
                         }
                         style=format!("--line-color: {}; margin-left: {}px", line.color, depth * 16)
@@ -315,13 +346,43 @@ pub fn LineControl(
                     >
                         <div
                             class="line-header"
+                            on:click=move |ev| {
+                                if ev.ctrl_key() || ev.meta_key() {
+                                    if let Some(selection) = selection {
+                                        selection.toggle(line_id);
+                                    }
+                                }
+                            }
                             on:dblclick=move |_| on_edit.with_value(|f| f(line_id))
                         >
                             <div class="drag-handle">
                                 <i class="fa-solid fa-grip-vertical"></i>
                             </div>
                             <strong>{line.name.clone()}</strong>
+                            <span class="line-capacity" title="Estimated trains per hour">{capacity_label(&line)}</span>
+                            {move || missing_reason().map(|reason| view! {
+                                <span class="line-warning-badge" title=reason.description()>
+                                    <i class="fa-solid fa-triangle-exclamation"></i>
+                                </span>
+                            })}
                             <div class="line-header-controls">
+                                <input
+                                    type="number"
+                                    class="section-order-input"
+                                    step="1"
+                                    placeholder="Auto"
+                                    title="Section order (lower is drawn further left, empty orders by stopping pattern)"
+                                    prop:value=line.section_order_override.map(|order| order.to_string()).unwrap_or_default()
+                                    on:dblclick=|ev| ev.stop_propagation()
+                                    on:change=move |ev| {
+                                        let order = event_target_value(&ev).trim().parse::<i32>().ok();
+                                        set_lines.update(|lines_vec| {
+                                            if let Some(line) = lines_vec.iter_mut().find(|l| l.id == line_id) {
+                                                line.section_order_override = order;
+                                            }
+                                        });
+                                    }
+                                />
                                 <button
                                     class="visibility-toggle"
                                     on:click={
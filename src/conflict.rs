@@ -1,10 +1,11 @@
 use crate::constants::{BASE_DATE, BASE_MIDNIGHT};
 #[allow(unused_imports)]
 use crate::logging::log;
-use crate::models::{RailwayGraph, TrackDirection, Junctions};
+use crate::models::{RailwayGraph, TrackDirection, Junctions, BlockSections, ConflictSettings};
 use crate::time::time_to_fraction;
 use crate::train_journey::TrainJourney;
 use chrono::NaiveDateTime;
+use petgraph::stable_graph::NodeIndex;
 use std::collections::HashMap;
 
 // Conflict detection constants
@@ -44,12 +45,38 @@ static LOOP_ITERATIONS: AtomicU64 = AtomicU64::new(0);
 #[cfg(target_arch = "wasm32")]
 static TIME_OVERLAP_CHECKS: AtomicU64 = AtomicU64::new(0);
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum ConflictType {
     HeadOn,            // Trains meeting on same track, opposite directions
     Overtaking,        // Train catching up on same track, same direction
     BlockViolation,    // Two trains in same single-track block simultaneously
     PlatformViolation, // Two trains using same platform at same time
+    JunctionConflict,  // Two trains making fouling movements through a junction
+    NearMiss,          // Two trains closer than the near-miss threshold without conflicting
+}
+
+impl ConflictType {
+    pub const ALL: [ConflictType; 6] = [
+        ConflictType::HeadOn,
+        ConflictType::Overtaking,
+        ConflictType::BlockViolation,
+        ConflictType::PlatformViolation,
+        ConflictType::JunctionConflict,
+        ConflictType::NearMiss,
+    ];
+
+    /// Get a short name for the conflict type
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            ConflictType::HeadOn => "Head-on Conflict",
+            ConflictType::Overtaking => "Overtaking",
+            ConflictType::BlockViolation => "Block Violation",
+            ConflictType::PlatformViolation => "Platform Violation",
+            ConflictType::JunctionConflict => "Junction Conflict",
+            ConflictType::NearMiss => "Near Miss",
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
@@ -73,10 +100,30 @@ pub struct Conflict {
 }
 
 impl Conflict {
+    /// Distance in km from the first station to the conflict, when the edge's length is known.
+    /// `None` for block sections spanning several edges, whose stations are not the edge's ends
+    #[must_use]
+    pub fn distance_along(&self, graph: &RailwayGraph) -> Option<f64> {
+        let edge_idx = petgraph::stable_graph::EdgeIndex::new(self.edge_index?);
+        let (source, target) = graph.graph.edge_endpoints(edge_idx)?;
+        // Conflict station indices enumerate the graph's nodes
+        let station_index = |node| graph.graph.node_indices().position(|n| n == node);
+        let (source, target) = (station_index(source)?, station_index(target)?);
+        let stations = (self.station1_idx.min(self.station2_idx), self.station1_idx.max(self.station2_idx));
+        if (source.min(target), source.max(target)) != stations {
+            return None;
+        }
+        graph.graph[edge_idx].distance.map(|distance| distance * self.position)
+    }
+
     /// Format a human-readable message describing the conflict (without timestamp)
     /// For `PlatformViolation` conflicts, caller should use `format_platform_message` instead for better performance
+    /// `distance_along` (see [`Conflict::distance_along`]) locates conflicts between stations more precisely
     #[must_use]
-    pub fn format_message(&self, station1_name: &str, station2_name: &str) -> String {
+    pub fn format_message(&self, station1_name: &str, station2_name: &str, distance_along: Option<f64>) -> String {
+        let from_station1 = distance_along
+            .map(|km| format!(", {km:.1} km from {station1_name}"))
+            .unwrap_or_default();
         let base_message = match self.conflict_type {
             ConflictType::PlatformViolation => {
                 format!(
@@ -86,22 +133,35 @@ impl Conflict {
             }
             ConflictType::HeadOn => {
                 format!(
-                    "{} conflicts with {} between {} and {}",
+                    "{} conflicts with {} between {} and {}{from_station1}",
                     self.journey1_id, self.journey2_id, station1_name, station2_name
                 )
             }
             ConflictType::Overtaking => {
                 format!(
-                    "{} overtakes {} between {} and {}",
+                    "{} overtakes {} between {} and {}{from_station1}",
                     self.journey2_id, self.journey1_id, station1_name, station2_name
                 )
             }
             ConflictType::BlockViolation => {
                 format!(
-                    "{} block violation with {} between {} and {}",
+                    "{} block violation with {} between {} and {}{from_station1}",
                     self.journey1_id, self.journey2_id, station1_name, station2_name
                 )
             }
+            ConflictType::JunctionConflict => {
+                format!(
+                    "{} conflicts with {} through {}",
+                    self.journey1_id, self.journey2_id, station1_name
+                )
+            }
+            ConflictType::NearMiss => {
+                let near_station = if self.position < 0.5 { station1_name } else { station2_name };
+                format!(
+                    "{} passes close to {} at {}",
+                    self.journey1_id, self.journey2_id, near_station
+                )
+            }
         };
 
         if self.timing_uncertain {
@@ -129,21 +189,150 @@ impl Conflict {
     /// Get a short name for the conflict type
     #[must_use]
     pub fn type_name(&self) -> &'static str {
+        self.conflict_type.name()
+    }
+
+    /// Position on the time graph as (fraction of a day, fractional display station index)
+    /// Returns `None` if either station is not shown in the current view
+    #[must_use]
+    pub fn graph_position(&self, station_idx_map: &HashMap<usize, usize>) -> Option<(f64, f64)> {
+        let display_idx1 = *station_idx_map.get(&self.station1_idx)?;
+        let display_idx2 = *station_idx_map.get(&self.station2_idx)?;
+
+        // Handle bidirectional travel by using min/max to ensure valid range
+        let (min_idx, max_idx) = (display_idx1.min(display_idx2), display_idx1.max(display_idx2));
+        #[allow(clippy::cast_precision_loss)]
+        let station_position = min_idx as f64 + (self.position * (max_idx as f64 - min_idx as f64));
+        Some((time_to_fraction(self.time), station_position))
+    }
+
+    /// Station a conflict is pinned to, for conflicts located at a station rather than along a track
+    fn spot_station(&self) -> Option<usize> {
         match self.conflict_type {
-            ConflictType::HeadOn => "Head-on Conflict",
-            ConflictType::Overtaking => "Overtaking",
-            ConflictType::BlockViolation => "Block Violation",
-            ConflictType::PlatformViolation => "Platform Violation",
+            ConflictType::NearMiss if self.position < 0.5 => Some(self.station1_idx),
+            ConflictType::NearMiss => Some(self.station2_idx),
+            _ => (self.station1_idx == self.station2_idx).then_some(self.station1_idx),
         }
     }
 }
 
+/// Keep conflicts matching the selected types that involve at least one of the selected lines.
+/// An empty `types` or `line_ids` set leaves that criterion unrestricted.
+#[must_use]
+pub fn filter_conflicts(
+    conflicts: &[Conflict],
+    types: &std::collections::HashSet<ConflictType>,
+    line_ids: &std::collections::HashSet<uuid::Uuid>,
+    journeys: &[TrainJourney],
+) -> Vec<Conflict> {
+    // Conflicts identify trains by train number, so resolve each number to the lines running it
+    let mut train_lines: HashMap<&str, Vec<uuid::Uuid>> = HashMap::new();
+    if !line_ids.is_empty() {
+        for journey in journeys {
+            train_lines.entry(journey.train_number.as_str()).or_default().push(journey.line_id);
+        }
+    }
+    let involves_selected_line = |train_number: &str| {
+        train_lines.get(train_number).is_some_and(|lines| lines.iter().any(|id| line_ids.contains(id)))
+    };
+
+    conflicts.iter()
+        .filter(|c| types.is_empty() || types.contains(&c.conflict_type))
+        .filter(|c| line_ids.is_empty() || involves_selected_line(&c.journey1_id) || involves_selected_line(&c.journey2_id))
+        .cloned()
+        .collect()
+}
+
+/// Identifies a conflict across journey regenerations, for remembering which conflicts a planner has accepted.
+/// Built from train numbers and station nodes rather than journey ids or times, so it survives regenerating
+/// an unchanged schedule and deleting unrelated stations, and ordered so it does not depend on which train
+/// was found first.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct ConflictKey {
+    pub conflict_type: ConflictType,
+    pub journeys: (String, String),
+    pub stations: (NodeIndex, NodeIndex),
+}
+
+/// Graph nodes in the order conflict station indices enumerate them
+#[must_use]
+pub fn conflict_station_nodes(graph: &RailwayGraph) -> Vec<NodeIndex> {
+    graph.graph.node_indices().collect()
+}
+
+impl Conflict {
+    /// Key of the conflict, resolving its station indices through `station_nodes` from [`conflict_station_nodes`]
+    #[must_use]
+    pub fn key(&self, station_nodes: &[NodeIndex]) -> ConflictKey {
+        let journeys = if self.journey1_id <= self.journey2_id {
+            (self.journey1_id.clone(), self.journey2_id.clone())
+        } else {
+            (self.journey2_id.clone(), self.journey1_id.clone())
+        };
+        let node = |idx: usize| station_nodes.get(idx).copied().unwrap_or_else(NodeIndex::end);
+        let (station1, station2) = (node(self.station1_idx), node(self.station2_idx));
+        ConflictKey {
+            conflict_type: self.conflict_type,
+            journeys,
+            stations: (station1.min(station2), station1.max(station2)),
+        }
+    }
+}
+
+/// Drop acknowledged conflicts, returning the rest along with how many were hidden
+#[must_use]
+pub fn without_acknowledged(conflicts: &[Conflict], acknowledged: &std::collections::HashSet<ConflictKey>, graph: &RailwayGraph) -> (Vec<Conflict>, usize) {
+    if acknowledged.is_empty() {
+        return (conflicts.to_vec(), 0);
+    }
+    let station_nodes = conflict_station_nodes(graph);
+    let shown: Vec<Conflict> = conflicts.iter()
+        .filter(|c| !acknowledged.contains(&c.key(&station_nodes)))
+        .cloned()
+        .collect();
+    let hidden = conflicts.len() - shown.len();
+    (shown, hidden)
+}
+
+/// Step through `len` conflicts one at a time, wrapping around at either end.
+/// Without a valid current index, starts from the first (forward) or last (backward) conflict.
+#[must_use]
+pub fn cycle_conflict_index(current: Option<usize>, len: usize, forward: bool) -> Option<usize> {
+    if len == 0 {
+        return None;
+    }
+    Some(match current.filter(|&index| index < len) {
+        Some(index) if forward => (index + 1) % len,
+        Some(index) => (index + len - 1) % len,
+        None if forward => 0,
+        None => len - 1,
+    })
+}
+
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct StationCrossing {
     pub time: NaiveDateTime,
     pub station_idx: usize,
     pub journey1_id: String,
     pub journey2_id: String,
+    /// Platform the first train uses at the station, when known
+    #[serde(default)]
+    pub platform1: Option<usize>,
+    /// Platform the second train uses at the station, when known
+    #[serde(default)]
+    pub platform2: Option<usize>,
+}
+
+/// Platform a journey uses at `station_idx` when it is an end of the journey's segment `seg_idx`
+fn platform_at_station(journey: &TrainJourney, segment: &JourneySegment, seg_idx: usize, station_idx: usize) -> Option<usize> {
+    let info = journey.segments.get(seg_idx)?;
+    if station_idx == segment.idx_start {
+        Some(info.origin_platform)
+    } else if station_idx == segment.idx_end {
+        Some(info.destination_platform)
+    } else {
+        None
+    }
 }
 
 struct ConflictResults {
@@ -165,6 +354,91 @@ struct ConflictContext<'a> {
     station_margin: chrono::Duration,
     minimum_separation: chrono::Duration,
     ignore_same_direction_platform_conflicts: bool,
+    /// Per-station margin overrides keyed by display station index
+    station_margin_overrides: HashMap<usize, chrono::Duration>,
+    /// Display indices of stations with passing loops
+    passing_loops: std::collections::HashSet<usize>,
+    /// Separation below which non-conflicting trains are reported as near-misses
+    near_miss_threshold: Option<chrono::Duration>,
+    /// Capacity of platforms holding more than one train, keyed by (display station index, platform index)
+    platform_capacities: HashMap<(usize, usize), usize>,
+}
+
+impl ConflictContext<'_> {
+    /// Station margin at a station, using its override when one is set
+    fn station_margin_at(&self, station_idx: usize) -> chrono::Duration {
+        self.station_margin_overrides
+            .get(&station_idx)
+            .copied()
+            .unwrap_or(self.station_margin)
+    }
+
+    /// Platform buffer at a station, using its margin override when one is set
+    fn platform_buffer_at(&self, station_idx: usize) -> chrono::Duration {
+        self.station_margin_overrides
+            .get(&station_idx)
+            .copied()
+            .unwrap_or(self.minimum_separation)
+    }
+
+    /// Whether trains can overtake on a segment: the edge has multiple tracks
+    /// or one of the stations it connects has a passing loop
+    fn allows_overtaking(&self, edge_index: usize, segment: &JourneySegment) -> bool {
+        let track_count = self.serializable_ctx.edge_info
+            .get(&edge_index)
+            .map_or(0, |&(_, count)| count);
+
+        track_count > 1
+            || self.passing_loops.contains(&segment.idx_start)
+            || self.passing_loops.contains(&segment.idx_end)
+    }
+
+    /// Block shared by two different edges, if both have been assigned to the same block section
+    fn shared_block(&self, edge1_index: usize, edge2_index: usize) -> Option<usize> {
+        if edge1_index == edge2_index {
+            return None;
+        }
+        let block1 = self.serializable_ctx.edge_blocks.get(&edge1_index)?;
+        let block2 = self.serializable_ctx.edge_blocks.get(&edge2_index)?;
+        (block1 == block2).then_some(*block1)
+    }
+
+    /// Whether a junction's routing rules permit a movement from one edge to another
+    fn junction_routing_allowed(&self, junction: usize, (from_edge, to_edge): (usize, usize)) -> bool {
+        from_edge != to_edge
+            && self.serializable_ctx.junction_rules
+                .get(&junction)
+                .and_then(|rules| rules.iter().find(|&&(from, to, _)| from == from_edge && to == to_edge))
+                .is_none_or(|&(_, _, allowed)| allowed)
+    }
+
+    /// Whether two permitted movements through a junction share a fouling point:
+    /// they converge onto the same exit edge, or their legs interleave around the
+    /// junction so their paths cross
+    fn movements_foul(&self, junction: usize, a: (usize, usize), b: (usize, usize)) -> bool {
+        if a == b || !self.junction_routing_allowed(junction, a) || !self.junction_routing_allowed(junction, b) {
+            return false;
+        }
+        if a.1 == b.1 {
+            return true;
+        }
+
+        let Some(legs) = self.serializable_ctx.junction_legs.get(&junction) else {
+            return false;
+        };
+        let position = |edge: usize| legs.iter().position(|&leg| leg == edge);
+        let (Some(a_from), Some(a_to), Some(b_from), Some(b_to)) =
+            (position(a.0), position(a.1), position(b.0), position(b.1)) else {
+            return false;
+        };
+        if a_from == b_from || a_from == b_to || a_to == b_from {
+            return false;
+        }
+
+        let (low, high) = (a_from.min(a_to), a_from.max(a_to));
+        let between = |leg: usize| low < leg && leg < high;
+        between(b_from) != between(b_to)
+    }
 }
 
 /// Serializable context for conflict detection (no references, no complex graph types)
@@ -181,6 +455,36 @@ pub struct SerializableConflictContext {
     pub station_margin_secs: i64,
     pub minimum_separation_secs: i64,
     pub ignore_same_direction_platform_conflicts: bool,
+    /// Maps display station index -> margin override in seconds
+    #[serde(default)]
+    pub station_margin_overrides: HashMap<usize, i64>,
+    /// Set of station node indices (as usize) that have passing loops
+    #[serde(default)]
+    pub passing_loops: std::collections::HashSet<usize>,
+    /// Maps edge index -> block section id for edges assigned to a block
+    #[serde(default)]
+    pub edge_blocks: HashMap<usize, usize>,
+    /// Maps junction node index -> routing rules as (`from_edge`, `to_edge`, `allowed`)
+    #[serde(default)]
+    pub junction_rules: HashMap<usize, Vec<(usize, usize, bool)>>,
+    /// Maps junction node index -> connected edge indices in angular order around the junction
+    #[serde(default)]
+    pub junction_legs: HashMap<usize, Vec<usize>>,
+    /// Separation in seconds below which non-conflicting trains are reported as near-misses
+    #[serde(default)]
+    pub near_miss_threshold_secs: Option<i64>,
+    /// Maps (station node index, platform index) -> capacity for platforms holding more than one train
+    #[serde(default)]
+    pub platform_capacities: HashMap<(usize, usize), usize>,
+    /// Set of depot node indices (as usize), where dwelling never causes platform conflicts
+    #[serde(default)]
+    pub depots: std::collections::HashSet<usize>,
+    /// Only conflicts at or after this time are reported
+    #[serde(default)]
+    pub window_start: Option<NaiveDateTime>,
+    /// Only conflicts at or before this time are reported
+    #[serde(default)]
+    pub window_end: Option<NaiveDateTime>,
 }
 
 impl SerializableConflictContext {
@@ -189,9 +493,7 @@ impl SerializableConflictContext {
     pub fn from_graph(
         graph: &RailwayGraph,
         station_indices: HashMap<petgraph::stable_graph::NodeIndex, usize>,
-        station_margin: chrono::Duration,
-        minimum_separation: chrono::Duration,
-        ignore_same_direction_platform_conflicts: bool,
+        settings: &ConflictSettings,
     ) -> Self {
         use petgraph::visit::{EdgeRef, IntoEdgeReferences};
 
@@ -201,8 +503,7 @@ impl SerializableConflictContext {
         for edge in graph.graph.edge_references() {
             let edge_idx = edge.id().index();
             let track_segment = edge.weight();
-            let is_single_bidirectional = track_segment.tracks.len() == 1
-                && matches!(track_segment.tracks[0].direction, TrackDirection::Bidirectional);
+            let is_single_bidirectional = track_segment.is_single_track_bidirectional();
             edge_info.insert(edge_idx, (is_single_bidirectional, track_segment.tracks.len()));
 
             // Store direction for each track
@@ -218,6 +519,61 @@ impl SerializableConflictContext {
             .map(petgraph::prelude::NodeIndex::index)
             .collect();
 
+        // Extract junction routing rules and leg geometry for junction conflict checks
+        let junction_rules = graph.graph.node_indices()
+            .filter_map(|idx| graph.get_junction(idx).map(|junction| (idx.index(), junction)))
+            .map(|(idx, junction)| {
+                let rules = junction.routing_rules.iter()
+                    .map(|rule| (rule.from_edge.index(), rule.to_edge.index(), rule.allowed))
+                    .collect();
+                (idx, rules)
+            })
+            .collect();
+        let junction_legs = graph.graph.node_indices()
+            .filter(|&idx| graph.is_junction(idx))
+            .map(|idx| (idx.index(), graph.junction_legs_by_angle(idx).iter().map(|edge| edge.index()).collect()))
+            .collect();
+
+        // Extract passing loop stations
+        let passing_loops = graph.graph.node_indices()
+            .filter(|&idx| {
+                graph.graph.node_weight(idx)
+                    .and_then(|node| node.as_station())
+                    .is_some_and(|station| station.passing_loop)
+            })
+            .map(petgraph::prelude::NodeIndex::index)
+            .collect();
+
+        // Extract depots, whose stabling dwell is not checked for platform conflicts
+        let depots = graph.graph.node_indices()
+            .filter(|&idx| graph.graph.node_weight(idx).is_some_and(crate::models::Node::is_depot))
+            .map(petgraph::prelude::NodeIndex::index)
+            .collect();
+
+        // Extract platforms that can hold more than one train
+        let platform_capacities = graph.graph.node_indices()
+            .filter_map(|idx| graph.graph.node_weight(idx)?.as_station().map(|station| (idx.index(), station)))
+            .flat_map(|(idx, station)| {
+                station.platforms.iter()
+                    .enumerate()
+                    .filter(|(_, platform)| platform.capacity > 1)
+                    .map(move |(platform_idx, platform)| ((idx, platform_idx), platform.capacity))
+            })
+            .collect();
+
+        // Extract per-station margin overrides, keyed like the other station data by display index
+        let station_margin_overrides = station_indices.iter()
+            .filter_map(|(&idx, &display_idx)| {
+                let station = graph.graph.node_weight(idx)?.as_station()?;
+                station.conflict_margin_secs.map(|secs| (display_idx, secs))
+            })
+            .collect();
+
+        // Extract block section membership
+        let edge_blocks = graph.graph.edge_indices()
+            .filter_map(|edge| graph.block_for_edge(edge).map(|block_id| (edge.index(), block_id)))
+            .collect();
+
         // Convert station_indices to use usize keys
         let station_indices = station_indices.into_iter()
             .map(|(k, v)| (k.index(), v))
@@ -228,11 +584,55 @@ impl SerializableConflictContext {
             edge_info,
             track_directions,
             junctions,
-            station_margin_secs: station_margin.num_seconds(),
-            minimum_separation_secs: minimum_separation.num_seconds(),
-            ignore_same_direction_platform_conflicts,
+            station_margin_secs: settings.station_margin.num_seconds(),
+            minimum_separation_secs: settings.minimum_separation.num_seconds(),
+            ignore_same_direction_platform_conflicts: settings.ignore_same_direction_platform_conflicts,
+            station_margin_overrides,
+            passing_loops,
+            edge_blocks,
+            junction_rules,
+            junction_legs,
+            near_miss_threshold_secs: settings.near_miss_threshold.map(|threshold| threshold.num_seconds()),
+            platform_capacities,
+            depots,
+            window_start: None,
+            window_end: None,
         }
     }
+
+    /// Replace the per-station margin overrides read from the graph (display station index -> seconds)
+    /// Stations without an override fall back to the global station margin
+    #[must_use]
+    pub fn with_station_margin_overrides(mut self, overrides: HashMap<usize, i64>) -> Self {
+        self.station_margin_overrides = overrides;
+        self
+    }
+
+    /// Set the near-miss threshold in seconds, or `None` to disable near-miss reporting
+    #[must_use]
+    pub fn with_near_miss_threshold(mut self, threshold_secs: Option<i64>) -> Self {
+        self.near_miss_threshold_secs = threshold_secs;
+        self
+    }
+
+    /// Restrict detection to conflicts between `start` and `end`; `None` leaves that side open
+    #[must_use]
+    pub fn with_time_window(mut self, start: Option<NaiveDateTime>, end: Option<NaiveDateTime>) -> Self {
+        self.window_start = start;
+        self.window_end = end;
+        self
+    }
+
+    /// Whether `time` falls inside the detection window
+    fn in_time_window(&self, time: NaiveDateTime) -> bool {
+        self.window_start.is_none_or(|start| time >= start) && self.window_end.is_none_or(|end| time <= end)
+    }
+
+    /// Whether a journey running from `start` to `end` can have conflicts inside the detection window
+    fn overlaps_time_window(&self, start: NaiveDateTime, end: NaiveDateTime) -> bool {
+        self.window_start.is_none_or(|window_start| end >= window_start)
+            && self.window_end.is_none_or(|window_end| start <= window_end)
+    }
 }
 
 struct PlatformOccupancy {
@@ -242,6 +642,115 @@ struct PlatformOccupancy {
     time_end: NaiveDateTime,
     timing_uncertain: bool,
     arrival_edge_index: Option<usize>,
+    /// The train reverses here, so it leaves toward trains arriving behind it
+    reverses: bool,
+}
+
+/// A train's passage through a junction from its entry edge to its exit edge
+struct JunctionMovement {
+    junction: usize,
+    station_idx: usize,
+    from_edge: usize,
+    to_edge: usize,
+    time: NaiveDateTime,
+    timing_uncertain: bool,
+}
+
+/// Occupancy intervals of every train at each platform that holds more than one train,
+/// keyed by (display station index, platform index)
+type SharedPlatformOccupants = HashMap<(usize, usize), Vec<(NaiveDateTime, NaiveDateTime)>>;
+
+/// Per-journey data derived once for conflict detection
+struct CachedJourney {
+    segments: Vec<CachedSegment>,
+    platform_occupancies: Vec<PlatformOccupancy>,
+    junction_movements: Vec<JunctionMovement>,
+}
+
+/// Memoizes each journey's segment list, platform occupancies and junction movements
+/// across detection runs, so unchanged journeys skip the rebuild
+///
+/// Entries are keyed by journey id and a hash of the journey's timing and routing,
+/// and are all dropped when the detection context changes.
+#[derive(Default)]
+pub struct ConflictCache {
+    context_hash: u64,
+    entries: HashMap<(uuid::Uuid, u64), CachedJourney>,
+    /// Number of journeys built since the cache was created
+    builds: usize,
+}
+
+impl ConflictCache {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build entries for new or changed journeys and evict entries for journeys no longer present.
+    /// Returns the cache keys of `train_journeys` in order.
+    fn refresh(&mut self, train_journeys: &[TrainJourney], ctx: &ConflictContext) -> Vec<(uuid::Uuid, u64)> {
+        let context_hash = context_hash(ctx.serializable_ctx);
+        if context_hash != self.context_hash {
+            self.entries.clear();
+            self.context_hash = context_hash;
+        }
+
+        let keys: Vec<_> = train_journeys.iter()
+            .map(|journey| (journey.id, journey_content_hash(journey)))
+            .collect();
+
+        for (journey, key) in train_journeys.iter().zip(&keys) {
+            if !self.entries.contains_key(key) {
+                self.entries.insert(*key, CachedJourney {
+                    segments: build_segment_list_with_bounds(journey, ctx),
+                    platform_occupancies: extract_platform_occupancies(journey, ctx),
+                    junction_movements: extract_junction_movements(journey, ctx),
+                });
+                self.builds += 1;
+            }
+        }
+
+        let live: std::collections::HashSet<_> = keys.iter().copied().collect();
+        self.entries.retain(|key, _| live.contains(key));
+
+        keys
+    }
+}
+
+/// Hash of the parts of a journey the cached detection data is derived from
+fn journey_content_hash(journey: &TrainJourney) -> u64 {
+    use std::hash::{Hash, Hasher};
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    journey.station_times.hash(&mut hasher);
+    journey.segments.hash(&mut hasher);
+    journey.timing_inherited.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Hash of the context settings the cached detection data depends on
+fn context_hash(ctx: &SerializableConflictContext) -> u64 {
+    use std::hash::{Hash, Hasher};
+
+    let mut station_indices: Vec<_> = ctx.station_indices.iter().map(|(&k, &v)| (k, v)).collect();
+    station_indices.sort_unstable();
+    let mut junctions: Vec<_> = ctx.junctions.iter().copied().collect();
+    junctions.sort_unstable();
+    let mut margin_overrides: Vec<_> = ctx.station_margin_overrides.iter().map(|(&k, &v)| (k, v)).collect();
+    margin_overrides.sort_unstable();
+    let mut depots: Vec<_> = ctx.depots.iter().copied().collect();
+    depots.sort_unstable();
+    let mut platform_capacities: Vec<_> = ctx.platform_capacities.iter().map(|(&k, &v)| (k, v)).collect();
+    platform_capacities.sort_unstable();
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    station_indices.hash(&mut hasher);
+    junctions.hash(&mut hasher);
+    margin_overrides.hash(&mut hasher);
+    depots.hash(&mut hasher);
+    platform_capacities.hash(&mut hasher);
+    ctx.minimum_separation_secs.hash(&mut hasher);
+    hasher.finish()
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -268,6 +777,16 @@ mod timing {
 pub fn detect_line_conflicts(
     train_journeys: &[TrainJourney],
     serializable_ctx: &SerializableConflictContext,
+) -> (Vec<Conflict>, Vec<StationCrossing>) {
+    detect_line_conflicts_cached(train_journeys, serializable_ctx, &mut ConflictCache::new())
+}
+
+/// Detect conflicts, reusing per-journey data from `cache` for journeys unchanged since the last run
+#[must_use]
+pub fn detect_line_conflicts_cached(
+    train_journeys: &[TrainJourney],
+    serializable_ctx: &SerializableConflictContext,
+    cache: &mut ConflictCache,
 ) -> (Vec<Conflict>, Vec<StationCrossing>) {
     #[cfg(not(target_arch = "wasm32"))]
     let total_start = std::time::Instant::now();
@@ -297,6 +816,12 @@ pub fn detect_line_conflicts(
         TIME_OVERLAP_CHECKS.store(0, Ordering::Relaxed);
     }
 
+    // Passed stations bound segments like any stop, so detection runs on the full route
+    let expanded: Option<Vec<TrainJourney>> = train_journeys.iter()
+        .any(|journey| !journey.passed_stations.is_empty())
+        .then(|| train_journeys.iter().map(|journey| journey.with_passed_stations().into_owned()).collect());
+    let train_journeys = expanded.as_deref().unwrap_or(train_journeys);
+
     let mut results = ConflictResults {
         conflicts: Vec::new(),
         station_crossings: Vec::new(),
@@ -320,6 +845,21 @@ pub fn detect_line_conflicts(
         station_margin: chrono::Duration::seconds(serializable_ctx.station_margin_secs),
         minimum_separation: chrono::Duration::seconds(serializable_ctx.minimum_separation_secs),
         ignore_same_direction_platform_conflicts: serializable_ctx.ignore_same_direction_platform_conflicts,
+        station_margin_overrides: serializable_ctx.station_margin_overrides
+            .iter()
+            .map(|(&idx, &secs)| (idx, chrono::Duration::seconds(secs)))
+            .collect(),
+        passing_loops: serializable_ctx.passing_loops
+            .iter()
+            .filter_map(|&node| serializable_ctx.station_indices.get(&node).copied())
+            .collect(),
+        near_miss_threshold: serializable_ctx.near_miss_threshold_secs.map(chrono::Duration::seconds),
+        platform_capacities: serializable_ctx.platform_capacities
+            .iter()
+            .filter_map(|(&(node, platform), &capacity)| {
+                serializable_ctx.station_indices.get(&node).map(|&station| ((station, platform), capacity))
+            })
+            .collect(),
     };
 
     #[cfg(not(target_arch = "wasm32"))]
@@ -333,7 +873,7 @@ pub fn detect_line_conflicts(
         log!("  Setup (context conversion): {:.2}ms", elapsed);
     }
 
-    detect_conflicts_sweep_line(train_journeys, &ctx, &mut results);
+    detect_conflicts_sweep_line(train_journeys, &ctx, cache, &mut results);
 
     #[cfg(not(target_arch = "wasm32"))]
     {
@@ -382,6 +922,7 @@ pub fn detect_line_conflicts(
 fn detect_conflicts_sweep_line(
     train_journeys: &[TrainJourney],
     ctx: &ConflictContext,
+    cache: &mut ConflictCache,
     results: &mut ConflictResults,
 ) {
     // Sweep-line algorithm: sort journeys by start time, only compare overlapping ones
@@ -408,6 +949,7 @@ fn detect_conflicts_sweep_line(
                 None
             }
         })
+        .filter(|(start, end, _)| ctx.serializable_ctx.overlaps_time_window(*start, *end))
         .collect();
 
     // Sort by start time
@@ -430,49 +972,28 @@ fn detect_conflicts_sweep_line(
     #[cfg(not(target_arch = "wasm32"))]
     let comparison_start = std::time::Instant::now();
 
-    // Pre-build all segment lookup maps and platform occupancies once
+    // Build segment lists, platform occupancies and junction movements for new or changed journeys
     #[cfg(not(target_arch = "wasm32"))]
     let cache_start = std::time::Instant::now();
 
     #[cfg(target_arch = "wasm32")]
     let cache_start = web_sys::window().and_then(|w| w.performance()).map(|p| p.now());
 
-    #[cfg(target_arch = "wasm32")]
-    let plat_occ_start = web_sys::window().and_then(|w| w.performance()).map(|p| p.now());
-
-    let platform_occupancies: Vec<_> = train_journeys
-        .iter()
-        .map(|journey| extract_platform_occupancies(journey, ctx))
-        .collect();
-
-    #[cfg(target_arch = "wasm32")]
-    if let Some(elapsed) = plat_occ_start.and_then(|s| web_sys::window()?.performance().map(|p| p.now() - s)) {
-        log!("      Platform occupancies: {:.2}ms", elapsed);
-    }
-
-    #[cfg(target_arch = "wasm32")]
-    let seg_list_start = web_sys::window().and_then(|w| w.performance()).map(|p| p.now());
-
-    // Pre-build segment lists with resolved indices and pre-computed bounds for all journeys
-    let segment_lists: Vec<_> = train_journeys
-        .iter()
-        .map(|journey| build_segment_list_with_bounds(journey, ctx))
-        .collect();
-
-    #[cfg(target_arch = "wasm32")]
-    if let Some(elapsed) = seg_list_start.and_then(|s| web_sys::window()?.performance().map(|p| p.now() - s)) {
-        log!("      Segment lists: {:.2}ms", elapsed);
-    }
+    let builds_before = cache.builds;
+    let keys = cache.refresh(train_journeys, ctx);
+    let cached: Vec<&CachedJourney> = keys.iter().map(|key| &cache.entries[key]).collect();
+    let shared_platforms = collect_shared_platform_occupants(&cached, ctx);
+    let rebuilt = cache.builds - builds_before;
 
     #[cfg(not(target_arch = "wasm32"))]
     {
         let cache_time = cache_start.elapsed();
-        eprintln!("Segment map & platform cache build time: {cache_time:?}");
+        eprintln!("Journey cache refresh time: {cache_time:?} ({rebuilt} of {} journeys rebuilt)", train_journeys.len());
     }
 
     #[cfg(target_arch = "wasm32")]
     if let Some(elapsed) = cache_start.and_then(|s| web_sys::window()?.performance().map(|p| p.now() - s)) {
-        log!("    Cache build time: {:.2}ms", elapsed);
+        log!("    Cache refresh time: {:.2}ms ({} of {} journeys rebuilt)", elapsed, rebuilt, train_journeys.len());
     }
 
     #[cfg(target_arch = "wasm32")]
@@ -486,8 +1007,7 @@ fn detect_conflicts_sweep_line(
 
         let (start_i, end_i, idx_i) = journey_times[i];
         let journey_i = &train_journeys[idx_i];
-        let plat_occ_i = &platform_occupancies[idx_i];
-        let seg_list_i = &segment_lists[idx_i];
+        let cached_i = cached[idx_i];
 
         // Only check journeys that start before journey_i ends
         // Once we find a journey that starts after journey_i ends, we can stop
@@ -509,9 +1029,9 @@ fn detect_conflicts_sweep_line(
             }
 
             let journey_j = &train_journeys[*idx_j];
-            let plat_occ_j = &platform_occupancies[*idx_j];
-            let seg_list_j = &segment_lists[*idx_j];
-            check_journey_pair_with_all_cached(journey_i, journey_j, ctx, results, plat_occ_i, plat_occ_j, seg_list_i, seg_list_j);
+            let conflicts_before = results.conflicts.len();
+            check_journey_pair_with_all_cached(journey_i, journey_j, ctx, results, cached_i, cached[*idx_j], &shared_platforms);
+            drop_conflicts_outside_window(results, conflicts_before, ctx.serializable_ctx);
 
             if results.conflicts.len() >= MAX_CONFLICTS {
                 break;
@@ -559,16 +1079,25 @@ fn detect_conflicts_sweep_line(
     }
 }
 
-#[allow(clippy::too_many_arguments)]
+/// Discard conflicts found since `from` that fall outside the detection window
+fn drop_conflicts_outside_window(results: &mut ConflictResults, from: usize, ctx: &SerializableConflictContext) {
+    if ctx.window_start.is_none() && ctx.window_end.is_none() {
+        return;
+    }
+    let in_window: Vec<Conflict> = results.conflicts.drain(from..)
+        .filter(|conflict| ctx.in_time_window(conflict.time))
+        .collect();
+    results.conflicts.extend(in_window);
+}
+
 fn check_journey_pair_with_all_cached(
     journey1: &TrainJourney,
     journey2: &TrainJourney,
     ctx: &ConflictContext,
     results: &mut ConflictResults,
-    plat_occ1: &[PlatformOccupancy],
-    plat_occ2: &[PlatformOccupancy],
-    seg_list1: &[CachedSegment],
-    seg_list2: &[CachedSegment],
+    cached1: &CachedJourney,
+    cached2: &CachedJourney,
+    shared_platforms: &SharedPlatformOccupants,
 ) {
     // Check for platform conflicts first using pre-cached occupancies
     #[cfg(not(target_arch = "wasm32"))]
@@ -577,7 +1106,7 @@ fn check_journey_pair_with_all_cached(
     #[cfg(target_arch = "wasm32")]
     let platform_start = web_sys::window().and_then(|w| w.performance()).map(|p| p.now());
 
-    check_platform_conflicts_cached(journey1, journey2, results, plat_occ1, plat_occ2, ctx);
+    check_platform_conflicts_cached(journey1, journey2, results, (&cached1.platform_occupancies, &cached2.platform_occupancies), shared_platforms, ctx);
 
     #[cfg(not(target_arch = "wasm32"))]
     timing::add_duration(&timing::PLATFORM_TIME, platform_start.elapsed());
@@ -591,13 +1120,15 @@ fn check_journey_pair_with_all_cached(
     #[cfg(target_arch = "wasm32")]
     let segment_start = web_sys::window().and_then(|w| w.performance()).map(|p| p.now());
 
-    check_segments_for_pair_cached(journey1, journey2, ctx, results, seg_list1, seg_list2);
+    check_segments_for_pair_cached(journey1, journey2, ctx, results, &cached1.segments, &cached2.segments);
 
     #[cfg(target_arch = "wasm32")]
     if let Some(elapsed) = segment_start.and_then(|s| web_sys::window()?.performance().map(|p| p.now() - s)) {
         // Store as microseconds to preserve decimal precision
         SEGMENT_CHECK_TIME.fetch_add((elapsed * 1000.0) as u64, Ordering::Relaxed);
     }
+
+    check_junction_conflicts(journey1, journey2, &cached1.junction_movements, &cached2.junction_movements, ctx, results);
 }
 
 #[allow(clippy::similar_names)]
@@ -609,6 +1140,9 @@ fn check_segments_for_pair_cached(
     segments1: &[CachedSegment],
     segments2: &[CachedSegment],
 ) {
+    // Block sections already reported for this pair, so a shared block is flagged once
+    let mut reported_blocks: Vec<usize> = Vec::new();
+
     // Check all segment pairs using binary search to find overlapping ranges
     for cached1 in segments1 {
         let seg1 = &cached1.segment;
@@ -635,6 +1169,16 @@ fn check_segments_for_pair_cached(
                 continue;
             }
 
+            // Different edges within the same block section conflict whenever both are occupied
+            if let Some(block_id) = ctx.shared_block(cached1.edge_index, cached2.edge_index) {
+                if !reported_blocks.contains(&block_id)
+                    && check_block_section_pair(cached1, cached2, journey1, journey2, results)
+                {
+                    reported_blocks.push(block_id);
+                }
+                continue;
+            }
+
             // Quick spatial overlap check before calling expensive function
             // This filters out ~50% of segment pairs that don't spatially overlap
             if cached1.idx_max <= cached2.idx_min || cached2.idx_max <= cached1.idx_min {
@@ -663,10 +1207,122 @@ fn check_segments_for_pair_cached(
                 continue; // Different tracks on same edge, no conflict
             }
 
+            let conflicts_before = results.conflicts.len();
             check_segment_pair(
                 seg1, seg2, cached1.idx_min, cached1.idx_max, cached1.edge_index,
                 journey1, journey2, cached1.segment_idx, cached2.segment_idx, ctx, results,
             );
+            if results.conflicts.len() == conflicts_before {
+                check_near_miss(cached1, cached2, journey1, journey2, ctx, results);
+            }
+
+            if results.conflicts.len() >= MAX_CONFLICTS {
+                return;
+            }
+        }
+    }
+}
+
+/// Report a block violation when two segments on different edges of one block section overlap in time.
+/// Returns whether a conflict was recorded.
+fn check_block_section_pair(
+    cached1: &CachedSegment,
+    cached2: &CachedSegment,
+    journey1: &TrainJourney,
+    journey2: &TrainJourney,
+    results: &mut ConflictResults,
+) -> bool {
+    let (seg1, seg2) = (&cached1.segment, &cached2.segment);
+    let time_overlap = seg1.time_start < seg2.time_end && seg2.time_start < seg1.time_end;
+    let conflict_time = seg1.time_start.max(seg2.time_start);
+    if !time_overlap || conflict_time < BASE_MIDNIGHT {
+        return false;
+    }
+
+    // Place the conflict where the trailing train enters the block
+    let station1_idx = cached1.idx_min.min(cached2.idx_min);
+    let station2_idx = cached1.idx_max.max(cached2.idx_max);
+    let entry_idx = if seg1.time_start >= seg2.time_start { seg1.idx_start } else { seg2.idx_start };
+    #[allow(clippy::cast_precision_loss)]
+    let position = if station2_idx > station1_idx {
+        (entry_idx - station1_idx) as f64 / (station2_idx - station1_idx) as f64
+    } else {
+        0.0
+    };
+
+    let timing_uncertain = has_inherited_timing_at_segment(journey1, cached1.segment_idx)
+        || has_inherited_timing_at_segment(journey2, cached2.segment_idx);
+
+    results.conflicts.push(Conflict {
+        time: conflict_time,
+        position,
+        station1_idx,
+        station2_idx,
+        journey1_id: journey1.train_number.clone(),
+        journey2_id: journey2.train_number.clone(),
+        conflict_type: ConflictType::BlockViolation,
+        segment1_times: Some((seg1.time_start, seg1.time_end)),
+        segment2_times: Some((seg2.time_start, seg2.time_end)),
+        platform_idx: None,
+        edge_index: Some(cached1.edge_index),
+        timing_uncertain,
+    });
+
+    true
+}
+
+/// Collect every movement a journey makes through a junction
+fn extract_junction_movements(journey: &TrainJourney, ctx: &ConflictContext) -> Vec<JunctionMovement> {
+    journey.station_times.iter()
+        .enumerate()
+        .skip(1)
+        .take(journey.segments.len().saturating_sub(1))
+        .filter(|(_, (node_idx, _, _))| ctx.serializable_ctx.junctions.contains(&node_idx.index()))
+        .filter_map(|(i, (node_idx, arrival, _))| Some(JunctionMovement {
+            junction: node_idx.index(),
+            station_idx: *ctx.station_indices.get(node_idx)?,
+            from_edge: journey.segments[i - 1].edge_index,
+            to_edge: journey.segments[i].edge_index,
+            time: *arrival,
+            timing_uncertain: journey.timing_inherited.get(i).copied().unwrap_or(false),
+        }))
+        .collect()
+}
+
+/// Report junction conflicts where two journeys make fouling movements through
+/// the same junction within the station margin of each other
+fn check_junction_conflicts(
+    journey1: &TrainJourney,
+    journey2: &TrainJourney,
+    movements1: &[JunctionMovement],
+    movements2: &[JunctionMovement],
+    ctx: &ConflictContext,
+    results: &mut ConflictResults,
+) {
+    for m1 in movements1 {
+        for m2 in movements2.iter().filter(|m2| m2.junction == m1.junction) {
+            let conflict_time = m1.time.max(m2.time);
+            if (m1.time - m2.time).abs() >= ctx.station_margin_at(m1.station_idx)
+                || conflict_time < BASE_MIDNIGHT
+                || !ctx.movements_foul(m1.junction, (m1.from_edge, m1.to_edge), (m2.from_edge, m2.to_edge))
+            {
+                continue;
+            }
+
+            results.conflicts.push(Conflict {
+                time: conflict_time,
+                position: 0.0,
+                station1_idx: m1.station_idx,
+                station2_idx: m1.station_idx,
+                journey1_id: journey1.train_number.clone(),
+                journey2_id: journey2.train_number.clone(),
+                conflict_type: ConflictType::JunctionConflict,
+                segment1_times: None,
+                segment2_times: None,
+                platform_idx: None,
+                edge_index: None,
+                timing_uncertain: m1.timing_uncertain || m2.timing_uncertain,
+            });
 
             if results.conflicts.len() >= MAX_CONFLICTS {
                 return;
@@ -675,6 +1331,74 @@ fn check_segments_for_pair_cached(
     }
 }
 
+/// Report a near-miss when two trains follow each other over the same track without conflicting
+/// but pass a shared station closer together than the near-miss threshold
+fn check_near_miss(
+    cached1: &CachedSegment,
+    cached2: &CachedSegment,
+    journey1: &TrainJourney,
+    journey2: &TrainJourney,
+    ctx: &ConflictContext,
+    results: &mut ConflictResults,
+) {
+    let Some(threshold) = ctx.near_miss_threshold else {
+        return;
+    };
+    let (seg1, seg2) = (&cached1.segment, &cached2.segment);
+    if cached1.edge_index != cached2.edge_index || seg1.idx_start != seg2.idx_start || seg1.idx_end != seg2.idx_end {
+        return;
+    }
+
+    // The closest approach of two non-crossing trains is at one end of the shared segment
+    let start_gap = (seg1.time_start - seg2.time_start).abs();
+    let end_gap = (seg1.time_end - seg2.time_end).abs();
+    let (station_idx, separation, time) = if start_gap <= end_gap {
+        (seg1.idx_start, start_gap, seg1.time_start.max(seg2.time_start))
+    } else {
+        (seg1.idx_end, end_gap, seg1.time_end.max(seg2.time_end))
+    };
+    if separation >= threshold
+        || time < BASE_MIDNIGHT
+        || has_conflict_at(results, journey1, journey2, station_idx, cached1.edge_index)
+    {
+        return;
+    }
+
+    results.conflicts.push(Conflict {
+        time,
+        position: if station_idx == cached1.idx_min { 0.0 } else { 1.0 },
+        station1_idx: cached1.idx_min,
+        station2_idx: cached1.idx_max,
+        journey1_id: journey1.train_number.clone(),
+        journey2_id: journey2.train_number.clone(),
+        conflict_type: ConflictType::NearMiss,
+        segment1_times: Some((seg1.time_start, seg1.time_end)),
+        segment2_times: Some((seg2.time_start, seg2.time_end)),
+        platform_idx: None,
+        edge_index: Some(cached1.edge_index),
+        timing_uncertain: has_inherited_timing_at_segment(journey1, cached1.segment_idx)
+            || has_inherited_timing_at_segment(journey2, cached2.segment_idx),
+    });
+}
+
+/// Whether this pair already has a conflict or near-miss at a station, or a real conflict on an edge.
+/// A pair's conflicts are reported together, so only the tail of the list needs checking.
+fn has_conflict_at(
+    results: &ConflictResults,
+    journey1: &TrainJourney,
+    journey2: &TrainJourney,
+    station_idx: usize,
+    edge_index: usize,
+) -> bool {
+    results.conflicts.iter()
+        .rev()
+        .take_while(|c| c.journey1_id == journey1.train_number && c.journey2_id == journey2.train_number)
+        .any(|c| {
+            c.spot_station() == Some(station_idx)
+                || (c.conflict_type != ConflictType::NearMiss && c.edge_index == Some(edge_index))
+        })
+}
+
 /// Segment with pre-computed spatial bounds and edge info for faster checking
 #[derive(Debug, Clone, Copy)]
 struct CachedSegment {
@@ -897,7 +1621,7 @@ fn check_segment_pair(
     timing::add_duration(&timing::INTERSECTION_TIME, intersection_start.elapsed());
 
     // Check if crossing happens very close to a station
-    if is_near_station(&intersection, segment1, segment2, ctx.station_margin) {
+    if is_near_station(&intersection, segment1, segment2, ctx) {
         // This is a successful station crossing - add it to the list (if in current week)
         // Skip crossings that occur before the week start (day -1 Sunday)
         if intersection.time >= BASE_MIDNIGHT {
@@ -907,6 +1631,8 @@ fn check_segment_pair(
                 station_idx,
                 journey1_id: journey1.train_number.clone(),
                 journey2_id: journey2.train_number.clone(),
+                platform1: platform_at_station(journey1, segment1, seg1_idx, station_idx),
+                platform2: platform_at_station(journey2, segment2, seg2_idx, station_idx),
             });
         }
 
@@ -927,7 +1653,9 @@ fn check_segment_pair(
     }
 
     // Determine conflict type based on track type
-    let conflict_type = if is_single_track {
+    // Overtaking is impossible without a second track or a passing loop, so it is a block violation
+    let overtaking_impossible = same_direction && !ctx.allows_overtaking(edge_index, segment1);
+    let conflict_type = if is_single_track || overtaking_impossible {
         ConflictType::BlockViolation
     } else if same_direction {
         ConflictType::Overtaking
@@ -1025,19 +1753,19 @@ fn is_near_station(
     intersection: &Intersection,
     segment1: &JourneySegment,
     segment2: &JourneySegment,
-    station_margin: chrono::Duration,
+    ctx: &ConflictContext,
 ) -> bool {
     // Only check the 4 relevant station times instead of all station times
-    let times = [
-        segment1.time_start,
-        segment1.time_end,
-        segment2.time_start,
-        segment2.time_end,
+    let times_with_idx = [
+        (segment1.time_start, segment1.idx_start),
+        (segment1.time_end, segment1.idx_end),
+        (segment2.time_start, segment2.idx_start),
+        (segment2.time_end, segment2.idx_end),
     ];
 
-    times
+    times_with_idx
         .iter()
-        .any(|t| (*t - intersection.time).abs() < station_margin)
+        .any(|(t, idx)| (*t - intersection.time).abs() < ctx.station_margin_at(*idx))
 }
 
 fn find_nearest_station(
@@ -1132,13 +1860,33 @@ fn calculate_intersection(
     }
 }
 
+/// Platform a journey occupies at its `stop_index`-th station, and the edge it arrived on
+#[must_use]
+pub fn stop_platform(journey: &TrainJourney, stop_index: usize) -> (usize, Option<usize>) {
+    let stop_index = journey.route_position(stop_index);
+    // A train can only occupy ONE platform at a time during a stop
+    // Priority: use arrival platform (where train stops), or departure platform if no arrival
+    if stop_index > 0 && stop_index - 1 < journey.segments.len() {
+        // Not the first station: use the destination platform of the previous segment (arrival platform)
+        // and capture the edge index the train arrived on
+        let segment = &journey.segments[stop_index - 1];
+        (segment.destination_platform, Some(segment.edge_index))
+    } else if stop_index < journey.segments.len() {
+        // First station: use the origin platform of the current segment (departure platform)
+        // No arrival edge since this is the origin
+        (journey.segments[stop_index].origin_platform, None)
+    } else {
+        // Single station (no segments) - use platform 0, no arrival edge
+        (0, None)
+    }
+}
+
 /// Extract all platform occupancies from a journey
 fn extract_platform_occupancies(
     journey: &TrainJourney,
     ctx: &ConflictContext,
 ) -> Vec<PlatformOccupancy> {
     let mut occupancies = Vec::new();
-    let buffer = ctx.minimum_separation;
 
     for (i, (node_idx, arrival_time, departure_time)) in
         journey.station_times.iter().enumerate()
@@ -1152,21 +1900,14 @@ fn extract_platform_occupancies(
             continue;
         }
 
-        // Determine which platform this journey uses at this station
-        // A train can only occupy ONE platform at a time during a stop
-        // Priority: use arrival platform (where train stops), or departure platform if no arrival
-        let (platform_idx, arrival_edge_index) = if i > 0 && i - 1 < journey.segments.len() {
-            // Not the first station: use the destination platform of the previous segment (arrival platform)
-            // and capture the edge index the train arrived on
-            (journey.segments[i - 1].destination_platform, Some(journey.segments[i - 1].edge_index))
-        } else if i < journey.segments.len() {
-            // First station: use the origin platform of the current segment (departure platform)
-            // No arrival edge since this is the origin
-            (journey.segments[i].origin_platform, None)
-        } else {
-            // Single station (no segments) - use platform 0, no arrival edge
-            (0, None)
-        };
+        // Skip depots - stabled trains don't occupy running platforms
+        if ctx.serializable_ctx.depots.contains(&node_idx.index()) {
+            continue;
+        }
+
+        let (platform_idx, arrival_edge_index) = stop_platform(journey, i);
+
+        let buffer = ctx.platform_buffer_at(station_idx);
 
         // Determine if this is the first or last station in the journey
         let is_first_station = i == 0;
@@ -1195,19 +1936,68 @@ fn extract_platform_occupancies(
             time_end,
             timing_uncertain: journey.timing_inherited.get(i).copied().unwrap_or(false),
             arrival_edge_index,
+            reverses: i.checked_sub(1).and_then(|prev| journey.segments.get(prev)).is_some_and(|s| s.reverses),
         });
     }
 
     occupancies
 }
 
+/// Gather every occupancy of the platforms that can hold more than one train
+fn collect_shared_platform_occupants(cached: &[&CachedJourney], ctx: &ConflictContext) -> SharedPlatformOccupants {
+    let mut occupants = SharedPlatformOccupants::new();
+    if ctx.platform_capacities.is_empty() {
+        return occupants;
+    }
+    for occupancy in cached.iter().flat_map(|journey| &journey.platform_occupancies) {
+        let key = (occupancy.station_idx, occupancy.platform_idx);
+        if ctx.platform_capacities.contains_key(&key) {
+            occupants.entry(key).or_default().push((occupancy.time_start, occupancy.time_end));
+        }
+    }
+    occupants
+}
+
+/// Whether a multi-train platform stays within its capacity for the whole of `window`.
+/// Always false for single-train platforms, where any overlap is a conflict.
+fn within_platform_capacity(
+    ctx: &ConflictContext,
+    shared_platforms: &SharedPlatformOccupants,
+    platform_key: (usize, usize),
+    window: (NaiveDateTime, NaiveDateTime),
+) -> bool {
+    match (ctx.platform_capacities.get(&platform_key), shared_platforms.get(&platform_key)) {
+        (Some(&capacity), Some(occupants)) => peak_occupancy(occupants, window) <= capacity,
+        _ => false,
+    }
+}
+
+/// Most trains at a platform at any one moment within `window`, found by sweeping arrivals and departures
+fn peak_occupancy(occupants: &[(NaiveDateTime, NaiveDateTime)], window: (NaiveDateTime, NaiveDateTime)) -> usize {
+    let (window_start, window_end) = window;
+    let mut events: Vec<(NaiveDateTime, i32)> = occupants.iter()
+        .filter(|&&(start, end)| start < window_end && window_start < end)
+        .flat_map(|&(start, end)| [(start.max(window_start), 1), (end.min(window_end), -1)])
+        .collect();
+    // Departures sort before arrivals at the same instant, freeing the platform first
+    events.sort_unstable();
+
+    let mut current = 0;
+    let mut peak = 0;
+    for (_, delta) in events {
+        current += delta;
+        peak = peak.max(current);
+    }
+    usize::try_from(peak).unwrap_or(0)
+}
+
 /// Check for platform conflicts using pre-cached occupancies
 fn check_platform_conflicts_cached(
     journey1: &TrainJourney,
     journey2: &TrainJourney,
     results: &mut ConflictResults,
-    occupancies1: &[PlatformOccupancy],
-    occupancies2: &[PlatformOccupancy],
+    (occupancies1, occupancies2): (&[PlatformOccupancy], &[PlatformOccupancy]),
+    shared_platforms: &SharedPlatformOccupants,
     ctx: &ConflictContext,
 ) {
     #[cfg(not(target_arch = "wasm32"))]
@@ -1230,12 +2020,20 @@ fn check_platform_conflicts_cached(
                     continue;
                 }
 
-                // If setting is enabled, skip conflicts where trains arrived from the same direction
-                let same_direction = matches!((occ1.arrival_edge_index, occ2.arrival_edge_index), (Some(e1), Some(e2)) if e1 == e2);
+                // If setting is enabled, skip conflicts where trains arrived from the same direction,
+                // unless one of them reverses and heads back toward the other
+                let same_direction = !occ1.reverses && !occ2.reverses
+                    && matches!((occ1.arrival_edge_index, occ2.arrival_edge_index), (Some(e1), Some(e2)) if e1 == e2);
                 if ctx.ignore_same_direction_platform_conflicts && same_direction {
                     continue;
                 }
 
+                // A platform holding several trains only conflicts once it is over capacity while both are there
+                let overlap = (conflict_time, occ1.time_end.min(occ2.time_end));
+                if within_platform_capacity(ctx, shared_platforms, (occ1.station_idx, occ1.platform_idx), overlap) {
+                    continue;
+                }
+
                 let timing_uncertain = occ1.timing_uncertain || occ2.timing_uncertain;
 
                 results.conflicts.push(Conflict {
@@ -1269,10 +2067,56 @@ mod tests {
     use super::*;
     use crate::models::{RailwayGraph, Stations, Tracks, Track, TrackDirection};
     use crate::train_journey::JourneySegment;
+    use petgraph::stable_graph::{EdgeIndex, NodeIndex};
+    use std::collections::HashSet;
 
     const TEST_COLOR: &str = "#FF0000";
     const TEST_THICKNESS: f64 = 2.0;
 
+    fn test_settings() -> ConflictSettings {
+        ConflictSettings {
+            station_margin: STATION_MARGIN,
+            minimum_separation: PLATFORM_BUFFER,
+            ignore_same_direction_platform_conflicts: false,
+            near_miss_threshold: None,
+        }
+    }
+
+    /// Journey calling at `stops` as (node, arrival, departure), running each of `edges` on
+    /// track 0 and platform 0; tests adjust the returned journey where they need something else
+    fn journey(number: &str, stops: &[(NodeIndex, NaiveDateTime, NaiveDateTime)], edges: &[EdgeIndex]) -> TrainJourney {
+        TrainJourney {
+            id: uuid::Uuid::new_v4(),
+            line_id: uuid::Uuid::new_v4(),
+            train_number: number.to_string(),
+            departure_time: stops.first().map_or(BASE_MIDNIGHT, |&(_, arrival, _)| arrival),
+            station_times: stops.to_vec(),
+            segments: edges.iter().map(|edge| JourneySegment {
+                edge_index: edge.index(),
+                track_index: 0,
+                origin_platform: 0,
+                destination_platform: 0,
+                reverses: false,
+            }).collect(),
+            color: TEST_COLOR.to_string(),
+            thickness: TEST_THICKNESS,
+            route_start_node: stops.first().map(|&(node, _, _)| node),
+            route_end_node: stops.last().map(|&(node, _, _)| node),
+            timing_inherited: vec![false; stops.len()],
+            is_forward: true,
+            passed_stations: Vec::new(),
+        }
+    }
+
+    /// Context for `graph`, numbering stations in node order
+    fn ctx_for(graph: &RailwayGraph, settings: &ConflictSettings) -> SerializableConflictContext {
+        let station_indices = graph.graph.node_indices()
+            .enumerate()
+            .map(|(idx, node_idx)| (node_idx, idx))
+            .collect();
+        SerializableConflictContext::from_graph(graph, station_indices, settings)
+    }
+
     #[test]
     fn test_conflict_type_name() {
         let conflict = Conflict {
@@ -1314,7 +2158,7 @@ mod tests {
             timing_uncertain: false,
         };
 
-        let message = conflict.format_message("Station 1", "Station 2");
+        let message = conflict.format_message("Station 1", "Station 2", None);
         assert_eq!(message, "Train A conflicts with Train B between Station 1 and Station 2");
     }
 
@@ -1327,8 +2171,8 @@ mod tests {
         if let Some(station_node) = graph.graph.node_weight_mut(station_idx) {
             if let Some(station) = station_node.as_station_mut() {
                 station.platforms = vec![
-                    crate::models::Platform { name: "1".to_string() },
-                    crate::models::Platform { name: "2".to_string() },
+                    crate::models::Platform { name: "1".to_string(), capacity: 1 },
+                    crate::models::Platform { name: "2".to_string(), capacity: 1 },
                 ];
             }
         }
@@ -1348,7 +2192,7 @@ mod tests {
             timing_uncertain: false,
         };
 
-        let message = conflict.format_message("Central Station", "Central Station");
+        let message = conflict.format_message("Central Station", "Central Station", None);
         assert_eq!(message, "Train A conflicts with Train B at Central Station Platform ?");
     }
 
@@ -1373,17 +2217,61 @@ mod tests {
             timing_uncertain: false,
         };
 
-        let message = conflict.format_message("A", "B");
+        let message = conflict.format_message("A", "B", None);
         assert_eq!(message, "Fast overtakes Slow between A and B");
     }
 
+    #[test]
+    fn test_conflict_distance_along_edge() {
+        let mut graph = RailwayGraph::new();
+        let a = graph.add_or_get_station("A".to_string());
+        let b = graph.add_or_get_station("B".to_string());
+        let edge = graph.add_track(a, b, vec![Track { direction: TrackDirection::Bidirectional }]);
+
+        let mut conflict = Conflict {
+            time: BASE_DATE.and_hms_opt(12, 0, 0).expect("valid time"),
+            position: 0.25,
+            station1_idx: 0,
+            station2_idx: 1,
+            journey1_id: "Train A".to_string(),
+            journey2_id: "Train B".to_string(),
+            conflict_type: ConflictType::HeadOn,
+            segment1_times: None,
+            segment2_times: None,
+            platform_idx: None,
+            edge_index: Some(edge.index()),
+            timing_uncertain: false,
+        };
+
+        // Without a length the wording is unchanged
+        assert_eq!(conflict.distance_along(&graph), None);
+        assert_eq!(conflict.format_message("A", "B", conflict.distance_along(&graph)), "Train A conflicts with Train B between A and B");
+
+        graph.graph[edge].distance = Some(10.0);
+        let distance = conflict.distance_along(&graph).expect("edge has a length");
+        assert!((distance - 2.5).abs() < 1e-9);
+        assert_eq!(
+            conflict.format_message("A", "B", Some(distance)),
+            "Train A conflicts with Train B between A and B, 2.5 km from A"
+        );
+
+        // A block section over several edges has no single edge to measure along
+        graph.add_or_get_station("C".to_string());
+        conflict.station2_idx = 2;
+        assert_eq!(conflict.distance_along(&graph), None);
+
+        conflict.station2_idx = 1;
+        conflict.edge_index = None;
+        assert_eq!(conflict.distance_along(&graph), None);
+    }
+
     #[test]
     fn test_detect_line_conflicts_empty() {
         let graph = RailwayGraph::new();
         let journeys = vec![];
 
         let station_indices = HashMap::new();
-        let ctx = SerializableConflictContext::from_graph(&graph, station_indices, STATION_MARGIN, PLATFORM_BUFFER, false);
+        let ctx = SerializableConflictContext::from_graph(&graph, station_indices, &test_settings());
         let (conflicts, crossings) = detect_line_conflicts(&journeys, &ctx);
 
         assert_eq!(conflicts.len(), 0);
@@ -1412,6 +2300,7 @@ mod tests {
                 track_index: 0,
                 origin_platform: 0,
                 destination_platform: 0,
+                reverses: false,
             }],
             color: TEST_COLOR.to_string(),
             thickness: TEST_THICKNESS,
@@ -1419,13 +2308,14 @@ mod tests {
             route_end_node: Some(idx2),
             timing_inherited: vec![false, false], // Test journey with explicit timing
             is_forward: true,
+            passed_stations: Vec::new(),
         };
 
         let station_indices = graph.graph.node_indices()
             .enumerate()
             .map(|(idx, node_idx)| (node_idx, idx))
             .collect();
-        let ctx = SerializableConflictContext::from_graph(&graph, station_indices, STATION_MARGIN, PLATFORM_BUFFER, false);
+        let ctx = SerializableConflictContext::from_graph(&graph, station_indices, &test_settings());
         let (conflicts, _) = detect_line_conflicts(&[journey], &ctx);
         assert_eq!(conflicts.len(), 0);
     }
@@ -1445,13 +2335,17 @@ mod tests {
             Track { direction: TrackDirection::Backward },
         ]);
 
-        let serializable_ctx = SerializableConflictContext::from_graph(&graph, HashMap::new(), STATION_MARGIN, PLATFORM_BUFFER, false);
+        let serializable_ctx = SerializableConflictContext::from_graph(&graph, HashMap::new(), &test_settings());
         let ctx = ConflictContext {
             station_indices: HashMap::new(),
             serializable_ctx: &serializable_ctx,
             station_margin: STATION_MARGIN,
             minimum_separation: PLATFORM_BUFFER,
             ignore_same_direction_platform_conflicts: false,
+            station_margin_overrides: HashMap::new(),
+            passing_loops: std::collections::HashSet::new(),
+            near_miss_threshold: None,
+            platform_capacities: HashMap::new(),
         };
 
         assert!(is_single_track_bidirectional(&ctx, edge1.index()));
@@ -1465,6 +2359,8 @@ mod tests {
             station_idx: 1,
             journey1_id: "J1".to_string(),
             journey2_id: "J2".to_string(),
+            platform1: Some(0),
+            platform2: Some(1),
         };
 
         let crossing2 = StationCrossing {
@@ -1472,11 +2368,45 @@ mod tests {
             station_idx: 1,
             journey1_id: "J1".to_string(),
             journey2_id: "J2".to_string(),
+            platform1: Some(0),
+            platform2: Some(1),
         };
 
         assert_eq!(crossing1, crossing2);
     }
 
+    /// Two trains meeting at B on a single-track line, using the given platforms there
+    fn crossing_at_b(platform1: usize, platform2: usize) -> StationCrossing {
+        let mut graph = RailwayGraph::new();
+        let idx_a = graph.add_or_get_station("A".to_string());
+        let idx_b = graph.add_or_get_station("B".to_string());
+        let edge = graph.add_track(idx_a, idx_b, vec![Track { direction: TrackDirection::Bidirectional }]);
+        let time = |h, m, s| BASE_DATE.and_hms_opt(h, m, s).expect("valid time");
+
+        // The second train leaves B just before the first arrives
+        let mut up = journey("Up", &[(idx_a, time(8, 0, 0), time(8, 0, 0)), (idx_b, time(8, 10, 0), time(8, 10, 0))], &[edge]);
+        up.segments[0].destination_platform = platform1;
+        let mut down = journey("Down", &[(idx_b, time(8, 9, 40), time(8, 9, 40)), (idx_a, time(8, 20, 0), time(8, 20, 0))], &[edge]);
+        down.segments[0].origin_platform = platform2;
+        let journeys = [up, down];
+
+        let ctx = ctx_for(&graph, &test_settings());
+        let (_, crossings) = detect_line_conflicts(&journeys, &ctx);
+        assert_eq!(crossings.len(), 1);
+        crossings.into_iter().next().expect("one crossing")
+    }
+
+    #[test]
+    fn test_station_crossing_records_platforms() {
+        let cross_platform = crossing_at_b(0, 1);
+        assert_eq!(cross_platform.station_idx, 1);
+        let platforms = [cross_platform.platform1, cross_platform.platform2];
+        assert!(platforms.contains(&Some(0)) && platforms.contains(&Some(1)));
+
+        let same_platform = crossing_at_b(1, 1);
+        assert_eq!((same_platform.platform1, same_platform.platform2), (Some(1), Some(1)));
+    }
+
     #[test]
     fn test_conflict_type_equality() {
         assert_eq!(ConflictType::HeadOn, ConflictType::HeadOn);
@@ -1517,4 +2447,550 @@ mod tests {
 
         assert!(intersection.is_none());
     }
+
+    fn head_on_journeys(graph: &mut RailwayGraph) -> Vec<TrainJourney> {
+        let idx_a = graph.add_or_get_station("A".to_string());
+        let idx_b = graph.add_or_get_station("B".to_string());
+        let edge = graph.add_track(idx_a, idx_b, vec![Track { direction: TrackDirection::Bidirectional }]);
+        let time = |h, m, s| BASE_DATE.and_hms_opt(h, m, s).expect("valid time");
+
+        // Trains cross at 08:09:30, 30 seconds from both departure and arrival at B
+        let down = journey("Down", &[(idx_a, time(8, 0, 0), time(8, 0, 0)), (idx_b, time(8, 10, 0), time(8, 10, 0))], &[edge]);
+        let mut up = journey("Up", &[(idx_b, time(8, 9, 0), time(8, 9, 0)), (idx_a, time(8, 19, 0), time(8, 19, 0))], &[edge]);
+        up.segments[0].origin_platform = 1;
+        up.segments[0].destination_platform = 1;
+        up.is_forward = false;
+        vec![down, up]
+    }
+
+    /// Give a station its own conflict margin, as set in the station dialog
+    fn set_station_margin(graph: &mut RailwayGraph, name: &str, secs: Option<i64>) {
+        let idx = graph.get_station_index(name).expect("station exists");
+        if let Some(station) = graph.graph.node_weight_mut(idx).and_then(crate::models::Node::as_station_mut) {
+            station.conflict_margin_secs = secs;
+        }
+    }
+
+    #[test]
+    fn test_station_margin_override_applies_per_station() {
+        let mut graph = RailwayGraph::new();
+        let journeys = head_on_journeys(&mut graph);
+        let loose_margin = chrono::Duration::seconds(60);
+        let track_conflict_count = |graph: &RailwayGraph| {
+            let ctx = ctx_for(graph, &ConflictSettings { station_margin: loose_margin, ..test_settings() });
+            let (conflicts, _) = detect_line_conflicts(&journeys, &ctx);
+            conflicts.iter().filter(|c| c.conflict_type != ConflictType::PlatformViolation).count()
+        };
+
+        // Loose global margin treats the meeting as a station crossing
+        assert_eq!(track_conflict_count(&graph), 0);
+        // Tight margin at A does not affect a crossing next to B
+        set_station_margin(&mut graph, "A", Some(15));
+        assert_eq!(track_conflict_count(&graph), 0);
+        // Tight margin at B turns the crossing into a conflict
+        set_station_margin(&mut graph, "A", None);
+        set_station_margin(&mut graph, "B", Some(15));
+        assert_eq!(track_conflict_count(&graph), 1);
+    }
+
+    #[test]
+    fn test_station_margin_override_widens_platform_buffer() {
+        let mut graph = RailwayGraph::new();
+        let mut journeys = head_on_journeys(&mut graph);
+        // Both trains use platform 0 at B, half a minute apart
+        journeys[1].segments[0].origin_platform = 0;
+        let platform_conflict_count = |graph: &RailwayGraph| {
+            let ctx = ctx_for(graph, &test_settings());
+            let (conflicts, _) = detect_line_conflicts(&journeys, &ctx);
+            conflicts.iter().filter(|c| c.conflict_type == ConflictType::PlatformViolation).count()
+        };
+
+        // Half a minute clears the global buffer, but not a two minute margin at B
+        assert_eq!(platform_conflict_count(&graph), 0);
+        set_station_margin(&mut graph, "B", Some(120));
+        assert_eq!(platform_conflict_count(&graph), 1);
+    }
+
+    #[test]
+    fn test_detection_uses_project_conflict_settings() {
+        let mut graph = RailwayGraph::new();
+        let journeys = head_on_journeys(&mut graph);
+        let track_conflict_count = |settings: &crate::models::ProjectSettings| {
+            let ctx = ctx_for(&graph, &settings.conflict_settings());
+            assert_eq!(ctx.station_margin_secs, settings.station_margin.num_seconds());
+            let (conflicts, _) = detect_line_conflicts(&journeys, &ctx);
+            conflicts.iter().filter(|c| c.conflict_type != ConflictType::PlatformViolation).count()
+        };
+
+        let mut settings = crate::models::ProjectSettings {
+            station_margin: chrono::Duration::seconds(60),
+            ..Default::default()
+        };
+        assert_eq!(track_conflict_count(&settings), 0);
+        settings.station_margin = chrono::Duration::seconds(15);
+        assert_eq!(track_conflict_count(&settings), 1);
+    }
+
+    fn overtaking_conflict_types(tracks: Vec<Track>, passing_loop: bool) -> Vec<ConflictType> {
+        let mut graph = RailwayGraph::new();
+        let idx_a = graph.add_or_get_station("A".to_string());
+        let idx_b = graph.add_or_get_station("B".to_string());
+        if let Some(station) = graph.graph.node_weight_mut(idx_b).and_then(|n| n.as_station_mut()) {
+            station.passing_loop = passing_loop;
+        }
+        let edge = graph.add_track(idx_a, idx_b, tracks);
+        let time = |h, m| BASE_DATE.and_hms_opt(h, m, 0).expect("valid time");
+
+        // Fast train departs after the slow one and catches it around 08:06:40
+        let run = |number: &str, depart, arrive| journey(number, &[(idx_a, depart, depart), (idx_b, arrive, arrive)], &[edge]);
+        let journeys = [
+            run("Slow", time(8, 0), time(8, 20)),
+            run("Fast", time(8, 5), time(8, 10)),
+        ];
+
+        let ctx = ctx_for(&graph, &test_settings());
+        let (conflicts, _) = detect_line_conflicts(&journeys, &ctx);
+        conflicts.into_iter()
+            .map(|c| c.conflict_type)
+            .filter(|t| *t != ConflictType::PlatformViolation)
+            .collect()
+    }
+
+    #[test]
+    fn test_overtaking_on_double_track_stays_overtaking() {
+        let tracks = vec![
+            Track { direction: TrackDirection::Forward },
+            Track { direction: TrackDirection::Forward },
+        ];
+        assert_eq!(overtaking_conflict_types(tracks, false), vec![ConflictType::Overtaking]);
+    }
+
+    #[test]
+    fn test_overtaking_on_single_track_becomes_block_violation() {
+        let tracks = vec![Track { direction: TrackDirection::Forward }];
+        assert_eq!(overtaking_conflict_types(tracks, false), vec![ConflictType::BlockViolation]);
+    }
+
+    #[test]
+    fn test_overtaking_on_single_track_with_passing_loop_stays_overtaking() {
+        let tracks = vec![Track { direction: TrackDirection::Forward }];
+        assert_eq!(overtaking_conflict_types(tracks, true), vec![ConflictType::Overtaking]);
+    }
+
+    fn block_section_conflict_types(assign_block: bool) -> Vec<ConflictType> {
+        use crate::models::BlockSections;
+
+        let mut graph = RailwayGraph::new();
+        let idx_a = graph.add_or_get_station("A".to_string());
+        let idx_b = graph.add_or_get_station("B".to_string());
+        let idx_c = graph.add_or_get_station("C".to_string());
+        let edge_ab = graph.add_track(idx_a, idx_b, vec![Track { direction: TrackDirection::Bidirectional }]);
+        let edge_bc = graph.add_track(idx_b, idx_c, vec![Track { direction: TrackDirection::Bidirectional }]);
+        if assign_block {
+            graph.assign_edges_to_block(1, &[edge_ab, edge_bc]);
+        }
+        let time = |h, m| BASE_DATE.and_hms_opt(h, m, 0).expect("valid time");
+
+        // One train runs A -> B while another runs B -> C at the same time
+        let journeys = [
+            journey("First", &[(idx_a, time(8, 0), time(8, 0)), (idx_b, time(8, 10), time(8, 10))], &[edge_ab]),
+            journey("Second", &[(idx_b, time(8, 2), time(8, 2)), (idx_c, time(8, 8), time(8, 8))], &[edge_bc]),
+        ];
+
+        let ctx = ctx_for(&graph, &test_settings());
+        let (conflicts, _) = detect_line_conflicts(&journeys, &ctx);
+        conflicts.into_iter()
+            .map(|c| c.conflict_type)
+            .filter(|t| *t != ConflictType::PlatformViolation)
+            .collect()
+    }
+
+    #[test]
+    fn test_adjacent_edges_in_same_block_conflict() {
+        assert_eq!(block_section_conflict_types(true), vec![ConflictType::BlockViolation]);
+    }
+
+    #[test]
+    fn test_adjacent_edges_without_block_do_not_conflict() {
+        assert!(block_section_conflict_types(false).is_empty());
+    }
+
+    /// Run two trains through a four-way flat junction, each entering and leaving
+    /// by the named compass legs, and return the non-platform conflicts found
+    fn junction_conflict_types(route1: (&str, &str), route2: (&str, &str)) -> Vec<ConflictType> {
+        use crate::models::Junction;
+
+        let mut graph = RailwayGraph::new();
+        let junction = graph.add_junction(Junction {
+            name: Some("Flat Junction".to_string()),
+            position: Some((0.0, 0.0)),
+            routing_rules: vec![],
+            label_position: None,
+        });
+        let legs: HashMap<&str, (petgraph::stable_graph::NodeIndex, petgraph::stable_graph::EdgeIndex)> =
+            [("N", (0.0, 10.0)), ("E", (10.0, 0.0)), ("S", (0.0, -10.0)), ("W", (-10.0, 0.0))]
+                .into_iter()
+                .map(|(name, position)| {
+                    let station = graph.add_or_get_station(name.to_string());
+                    graph.set_station_position(station, position);
+                    let edge = graph.add_track(station, junction, vec![Track { direction: TrackDirection::Bidirectional }]);
+                    (name, (station, edge))
+                })
+                .collect();
+        let time = |h, m| BASE_DATE.and_hms_opt(h, m, 0).expect("valid time");
+
+        let through = |number: &str, (from, to): (&str, &str)| {
+            let (from_station, from_edge) = legs[from];
+            let (to_station, to_edge) = legs[to];
+            let stops = [
+                (from_station, time(8, 0), time(8, 0)),
+                (junction, time(8, 5), time(8, 5)),
+                (to_station, time(8, 10), time(8, 10)),
+            ];
+            journey(number, &stops, &[from_edge, to_edge])
+        };
+        let journeys = [through("First", route1), through("Second", route2)];
+
+        let ctx = ctx_for(&graph, &test_settings());
+        let (conflicts, _) = detect_line_conflicts(&journeys, &ctx);
+        conflicts.into_iter()
+            .map(|c| c.conflict_type)
+            .filter(|t| *t != ConflictType::PlatformViolation)
+            .collect()
+    }
+
+    #[test]
+    fn test_crossing_movements_through_junction_conflict() {
+        assert_eq!(junction_conflict_types(("N", "S"), ("E", "W")), vec![ConflictType::JunctionConflict]);
+    }
+
+    #[test]
+    fn test_parallel_movements_through_junction_do_not_conflict() {
+        assert!(junction_conflict_types(("N", "E"), ("S", "W")).is_empty());
+    }
+
+    #[test]
+    fn test_cache_reuses_unchanged_journeys() {
+        let mut graph = RailwayGraph::new();
+        let idx_a = graph.add_or_get_station("A".to_string());
+        let idx_b = graph.add_or_get_station("B".to_string());
+        let edge = graph.add_track(idx_a, idx_b, vec![Track { direction: TrackDirection::Bidirectional }]);
+        let time = |h, m| BASE_DATE.and_hms_opt(h, m, 0).expect("valid time");
+
+        let journeys: Vec<TrainJourney> = (0..50).map(|i| {
+            let depart = time(6, 0) + chrono::Duration::minutes(i * 15);
+            let arrive = depart + chrono::Duration::minutes(10);
+            journey(&format!("T{i}"), &[(idx_a, depart, depart), (idx_b, arrive, arrive)], &[edge])
+        }).collect();
+
+        let ctx = ctx_for(&graph, &test_settings());
+        let mut cache = ConflictCache::new();
+
+        let first = detect_line_conflicts_cached(&journeys, &ctx, &mut cache);
+        assert_eq!(cache.builds, journeys.len());
+
+        // Identical journeys are served entirely from the cache
+        let second = detect_line_conflicts_cached(&journeys, &ctx, &mut cache);
+        assert_eq!(cache.builds, journeys.len());
+        assert_eq!(first, second);
+
+        // Retiming one journey rebuilds only that journey and evicts its stale entry
+        let mut changed = journeys.clone();
+        changed[0].station_times[1].1 += chrono::Duration::minutes(1);
+        let _ = detect_line_conflicts_cached(&changed, &ctx, &mut cache);
+        assert_eq!(cache.builds, journeys.len() + 1);
+        assert_eq!(cache.entries.len(), journeys.len());
+
+        // A different context invalidates every entry
+        let wider = ctx.clone().with_station_margin_overrides(HashMap::from([(0, 120)]));
+        let _ = detect_line_conflicts_cached(&changed, &wider, &mut cache);
+        assert_eq!(cache.builds, 2 * journeys.len() + 1);
+    }
+
+    #[test]
+    fn test_cache_follows_depot_toggle() {
+        let mut graph = RailwayGraph::new();
+        let idx_a = graph.add_or_get_station("A".to_string());
+        let idx_b = graph.add_or_get_station("B".to_string());
+        let edge = graph.add_track(idx_a, idx_b, vec![
+            Track { direction: TrackDirection::Forward },
+            Track { direction: TrackDirection::Backward },
+        ]);
+        let time = |h, m| BASE_DATE.and_hms_opt(h, m, 0).expect("valid time");
+
+        // Two trains stable on the same platform at B at the same time
+        let stable = |number: &str, depart: NaiveDateTime| {
+            let stops = [(idx_a, depart, depart), (idx_b, depart + chrono::Duration::minutes(10), time(9, 0))];
+            journey(number, &stops, &[edge])
+        };
+        let journeys = vec![stable("T1", time(6, 0)), stable("T2", time(7, 0))];
+
+        let mut cache = ConflictCache::new();
+        let mut platform_conflicts = |graph: &RailwayGraph| {
+            let ctx = ctx_for(graph, &test_settings());
+            detect_line_conflicts_cached(&journeys, &ctx, &mut cache).0.iter()
+                .filter(|c| c.conflict_type == ConflictType::PlatformViolation)
+                .count()
+        };
+
+        assert_eq!(platform_conflicts(&graph), 1);
+        if let Some(station) = graph.graph[idx_b].as_station_mut() {
+            station.is_depot = true;
+        }
+        assert_eq!(platform_conflicts(&graph), 0);
+        if let Some(station) = graph.graph[idx_b].as_station_mut() {
+            station.is_depot = false;
+        }
+        assert_eq!(platform_conflicts(&graph), 1);
+    }
+
+    fn following_train_conflict_types(headway_secs: i64) -> Vec<ConflictType> {
+        let mut graph = RailwayGraph::new();
+        let idx_a = graph.add_or_get_station("A".to_string());
+        let idx_b = graph.add_or_get_station("B".to_string());
+        let edge = graph.add_track(idx_a, idx_b, vec![
+            Track { direction: TrackDirection::Forward },
+            Track { direction: TrackDirection::Backward },
+        ]);
+        let time = |h, m| BASE_DATE.and_hms_opt(h, m, 0).expect("valid time");
+
+        let run = |number: &str, depart: NaiveDateTime| {
+            let arrive = depart + chrono::Duration::minutes(10);
+            journey(number, &[(idx_a, depart, depart), (idx_b, arrive, arrive)], &[edge])
+        };
+        let journeys = [
+            run("First", time(8, 0)),
+            run("Second", time(8, 0) + chrono::Duration::seconds(headway_secs)),
+        ];
+
+        let ctx = ctx_for(&graph, &test_settings()).with_near_miss_threshold(Some(90));
+        let (conflicts, _) = detect_line_conflicts(&journeys, &ctx);
+        conflicts.into_iter().map(|c| c.conflict_type).collect()
+    }
+
+    #[test]
+    fn test_time_window_limits_conflicts_to_window() {
+        let mut graph = RailwayGraph::new();
+        let idx_a = graph.add_or_get_station("A".to_string());
+        let idx_b = graph.add_or_get_station("B".to_string());
+        let edge = graph.add_track(idx_a, idx_b, vec![Track { direction: TrackDirection::Bidirectional }]);
+        let time = |h| BASE_DATE.and_hms_opt(h, 0, 0).expect("valid time");
+
+        // Opposing trains meet on the single track every hour
+        let run = |from, to, depart: NaiveDateTime| {
+            let arrive = depart + chrono::Duration::minutes(10);
+            let number = depart.format("%H%M").to_string();
+            TrainJourney { is_forward: from == idx_a, ..journey(&number, &[(from, depart, depart), (to, arrive, arrive)], &[edge]) }
+        };
+        let journeys: Vec<TrainJourney> = [7, 8, 9].into_iter()
+            .flat_map(|h| [run(idx_a, idx_b, time(h)), run(idx_b, idx_a, time(h))])
+            .collect();
+
+        let ctx = ctx_for(&graph, &test_settings());
+        let (all_conflicts, _) = detect_line_conflicts(&journeys, &ctx);
+
+        let (window_start, window_end) = (time(8) - chrono::Duration::minutes(5), time(9) - chrono::Duration::minutes(5));
+        let windowed = ctx.with_time_window(Some(window_start), Some(window_end));
+        let (conflicts, _) = detect_line_conflicts(&journeys, &windowed);
+
+        let in_window = |c: &&Conflict| c.time >= window_start && c.time <= window_end;
+        assert!(!conflicts.is_empty());
+        assert!(conflicts.len() < all_conflicts.len());
+        assert!(conflicts.iter().all(|c| in_window(&c)));
+        assert_eq!(conflicts.len(), all_conflicts.iter().filter(in_window).count());
+    }
+
+    #[test]
+    fn test_trains_80s_apart_are_a_near_miss() {
+        assert_eq!(following_train_conflict_types(80), vec![ConflictType::NearMiss]);
+    }
+
+    #[test]
+    fn test_trains_beyond_threshold_are_not_a_near_miss() {
+        assert!(following_train_conflict_types(120).is_empty());
+    }
+
+    #[test]
+    fn test_near_miss_does_not_duplicate_platform_conflict() {
+        let types = following_train_conflict_types(20);
+        assert!(types.contains(&ConflictType::PlatformViolation));
+        assert!(!types.contains(&ConflictType::NearMiss));
+    }
+
+    fn filter_fixture() -> (Vec<Conflict>, Vec<TrainJourney>, uuid::Uuid, uuid::Uuid) {
+        let time = BASE_DATE.and_hms_opt(8, 0, 0).expect("valid time");
+        let (line_a, line_b) = (uuid::Uuid::new_v4(), uuid::Uuid::new_v4());
+        let on_line = |number: &str, line_id| TrainJourney { line_id, ..journey(number, &[], &[]) };
+        let conflict = |j1: &str, j2: &str, conflict_type| Conflict {
+            time,
+            position: 0.0,
+            station1_idx: 0,
+            station2_idx: 0,
+            journey1_id: j1.to_string(),
+            journey2_id: j2.to_string(),
+            conflict_type,
+            segment1_times: None,
+            segment2_times: None,
+            platform_idx: None,
+            edge_index: None,
+            timing_uncertain: false,
+        };
+        let journeys = vec![on_line("A1", line_a), on_line("A2", line_a), on_line("B1", line_b), on_line("B2", line_b)];
+        let conflicts = vec![
+            conflict("A1", "A2", ConflictType::HeadOn),
+            conflict("A1", "B1", ConflictType::PlatformViolation),
+            conflict("B1", "B2", ConflictType::HeadOn),
+            conflict("B2", "B1", ConflictType::Overtaking),
+        ];
+        (conflicts, journeys, line_a, line_b)
+    }
+
+    fn pairs(conflicts: &[Conflict]) -> Vec<(&str, &str)> {
+        conflicts.iter().map(|c| (c.journey1_id.as_str(), c.journey2_id.as_str())).collect()
+    }
+
+    #[test]
+    fn test_filter_conflicts_empty_filters_show_all() {
+        let (conflicts, journeys, _, _) = filter_fixture();
+        let filtered = filter_conflicts(&conflicts, &HashSet::new(), &HashSet::new(), &journeys);
+        assert_eq!(filtered, conflicts);
+    }
+
+    #[test]
+    fn test_filter_conflicts_by_type() {
+        let (conflicts, journeys, _, _) = filter_fixture();
+        let types = HashSet::from([ConflictType::HeadOn, ConflictType::Overtaking]);
+        let filtered = filter_conflicts(&conflicts, &types, &HashSet::new(), &journeys);
+        assert_eq!(pairs(&filtered), vec![("A1", "A2"), ("B1", "B2"), ("B2", "B1")]);
+    }
+
+    #[test]
+    fn test_filter_conflicts_by_line() {
+        let (conflicts, journeys, line_a, _) = filter_fixture();
+        let filtered = filter_conflicts(&conflicts, &HashSet::new(), &HashSet::from([line_a]), &journeys);
+        assert_eq!(pairs(&filtered), vec![("A1", "A2"), ("A1", "B1")]);
+    }
+
+    #[test]
+    fn test_filter_conflicts_by_type_and_line() {
+        let (conflicts, journeys, _, line_b) = filter_fixture();
+        let types = HashSet::from([ConflictType::HeadOn]);
+        let filtered = filter_conflicts(&conflicts, &types, &HashSet::from([line_b]), &journeys);
+        assert_eq!(pairs(&filtered), vec![("B1", "B2")]);
+    }
+
+    #[test]
+    fn test_conflict_key_is_stable_across_regenerations() {
+        // Each run generates journeys with fresh ids, as regenerating the schedule does
+        let nodes = [NodeIndex::new(0), NodeIndex::new(1), NodeIndex::new(2), NodeIndex::new(3)];
+        let first: Vec<ConflictKey> = island_platform_conflicts(3).iter().map(|c| c.key(&nodes)).collect();
+        let second: Vec<ConflictKey> = island_platform_conflicts(3).iter().map(|c| c.key(&nodes)).collect();
+        assert!(!first.is_empty());
+        assert_eq!(first, second);
+
+        // The key does not depend on which train the conflict lists first
+        let (conflicts, _, _, _) = filter_fixture();
+        let original = Conflict { station1_idx: 1, station2_idx: 3, ..conflicts[0].clone() };
+        let swapped = Conflict {
+            journey1_id: original.journey2_id.clone(),
+            journey2_id: original.journey1_id.clone(),
+            station1_idx: 3,
+            station2_idx: 1,
+            ..original.clone()
+        };
+        assert_eq!(swapped.key(&nodes), original.key(&nodes));
+        assert_ne!(conflicts[0].key(&nodes), original.key(&nodes));
+    }
+
+    #[test]
+    fn test_conflict_key_survives_deleting_another_station() {
+        let mut graph = RailwayGraph::new();
+        let removed = graph.add_or_get_station("X".to_string());
+        graph.add_or_get_station("A".to_string());
+        graph.add_or_get_station("B".to_string());
+        let (conflicts, _, _, _) = filter_fixture();
+
+        // A and B are the second and third node before X is deleted, and the first two after
+        let before = Conflict { station1_idx: 1, station2_idx: 2, ..conflicts[0].clone() };
+        let before_key = before.key(&conflict_station_nodes(&graph));
+        graph.graph.remove_node(removed);
+        let after = Conflict { station1_idx: 0, station2_idx: 1, ..conflicts[0].clone() };
+
+        assert_eq!(after.key(&conflict_station_nodes(&graph)), before_key);
+        let (shown, hidden) = without_acknowledged(&[after], &HashSet::from([before_key]), &graph);
+        assert!(shown.is_empty());
+        assert_eq!(hidden, 1);
+    }
+
+    #[test]
+    fn test_without_acknowledged_hides_and_counts() {
+        let (conflicts, _, _, _) = filter_fixture();
+        let graph = RailwayGraph::new();
+        let (shown, hidden) = without_acknowledged(&conflicts, &HashSet::new(), &graph);
+        assert_eq!((shown, hidden), (conflicts.clone(), 0));
+
+        // Acknowledging one pair's head-on conflict leaves its overtaking conflict visible
+        let acknowledged = HashSet::from([conflicts[2].key(&conflict_station_nodes(&graph))]);
+        let (shown, hidden) = without_acknowledged(&conflicts, &acknowledged, &graph);
+        assert_eq!(hidden, 1);
+        assert_eq!(pairs(&shown), vec![("A1", "A2"), ("A1", "B1"), ("B2", "B1")]);
+
+        // Un-acknowledging brings it back
+        let (shown, hidden) = without_acknowledged(&conflicts, &HashSet::new(), &graph);
+        assert_eq!((shown.len(), hidden), (4, 0));
+    }
+
+    #[test]
+    fn test_cycle_conflict_index_wraps_around() {
+        assert_eq!(cycle_conflict_index(None, 3, true), Some(0));
+        assert_eq!(cycle_conflict_index(None, 3, false), Some(2));
+        assert_eq!(cycle_conflict_index(Some(1), 3, true), Some(2));
+        assert_eq!(cycle_conflict_index(Some(2), 3, true), Some(0));
+        assert_eq!(cycle_conflict_index(Some(0), 3, false), Some(2));
+        // A stale index from a longer list restarts the cycle
+        assert_eq!(cycle_conflict_index(Some(5), 3, true), Some(0));
+        assert_eq!(cycle_conflict_index(Some(0), 0, true), None);
+    }
+
+    fn island_platform_conflicts(train_count: i64) -> Vec<Conflict> {
+        let mut graph = RailwayGraph::new();
+        let idx_a = graph.add_or_get_station("A".to_string());
+        let idx_b = graph.add_or_get_station("B".to_string());
+        if let Some(station) = graph.graph.node_weight_mut(idx_b).and_then(|node| node.as_station_mut()) {
+            station.platforms[0].capacity = 2;
+        }
+        let edge = graph.add_track(idx_a, idx_b, vec![
+            Track { direction: TrackDirection::Forward },
+            Track { direction: TrackDirection::Backward },
+        ]);
+        let time = |h, m| BASE_DATE.and_hms_opt(h, m, 0).expect("valid time");
+
+        // Trains leave A two minutes apart and all dwell at B's island platform
+        let journeys: Vec<TrainJourney> = (0..train_count).map(|i| {
+            let depart = time(8, 0) + chrono::Duration::minutes(2 * i);
+            let arrive = depart + chrono::Duration::minutes(10);
+            let mut train = journey(&format!("T{i}"), &[(idx_a, depart, depart), (idx_b, arrive, arrive + chrono::Duration::minutes(10))], &[edge]);
+            train.segments[0].origin_platform = 1;
+            train
+        }).collect();
+
+        let ctx = ctx_for(&graph, &test_settings());
+        detect_line_conflicts(&journeys, &ctx).0
+    }
+
+    fn island_platform_conflict_count(train_count: i64) -> usize {
+        island_platform_conflicts(train_count).iter()
+            .filter(|c| c.conflict_type == ConflictType::PlatformViolation)
+            .count()
+    }
+
+    #[test]
+    fn test_two_trains_fit_a_capacity_two_platform() {
+        assert_eq!(island_platform_conflict_count(2), 0);
+    }
+
+    #[test]
+    fn test_three_trains_overfill_a_capacity_two_platform() {
+        assert!(island_platform_conflict_count(3) > 0);
+    }
 }
@@ -1,4 +1,4 @@
-use crate::conflict::{detect_line_conflicts, Conflict, SerializableConflictContext};
+use crate::conflict::{detect_line_conflicts_cached, Conflict, ConflictCache, SerializableConflictContext};
 #[allow(unused_imports)]
 use crate::logging::log;
 use crate::train_journey::TrainJourney;
@@ -40,7 +40,10 @@ impl Codec for BincodeCodec {
     }
 }
 
-pub struct ConflictWorker;
+pub struct ConflictWorker {
+    /// Per-journey detection data kept between requests
+    cache: ConflictCache,
+}
 
 impl Worker for ConflictWorker {
     type Input = ConflictRequest;
@@ -48,7 +51,7 @@ impl Worker for ConflictWorker {
     type Message = ();
 
     fn create(_scope: &WorkerScope<Self>) -> Self {
-        Self
+        Self { cache: ConflictCache::new() }
     }
 
     fn update(&mut self, _scope: &WorkerScope<Self>, _msg: Self::Message) {
@@ -57,7 +60,7 @@ impl Worker for ConflictWorker {
 
     fn received(&mut self, scope: &WorkerScope<Self>, msg: Self::Input, id: HandlerId) {
         let start = web_sys::window().and_then(|w| w.performance()).map(|p| p.now());
-        let (conflicts, _) = detect_line_conflicts(&msg.journeys, &msg.context);
+        let (conflicts, _) = detect_line_conflicts_cached(&msg.journeys, &msg.context, &mut self.cache);
         if let Some(elapsed) = start.and_then(|s| web_sys::window()?.performance().map(|p| p.now() - s)) {
             log!("Worker conflict detection took {:.2}ms ({} conflicts from {} journeys)",
                 elapsed, conflicts.len(), msg.journeys.len());
@@ -0,0 +1,4 @@
+pub mod table;
+
+// Re-export commonly used items
+pub use table::table;
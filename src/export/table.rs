@@ -0,0 +1,212 @@
+use crate::models::{RailwayGraph, Stations};
+use crate::train_journey::TrainJourney;
+use petgraph::stable_graph::NodeIndex;
+use std::fmt::Write;
+
+const TIME_FORMAT: &str = "%H:%M";
+const PASSING_MARKER: &str = "|";
+const TABLE_STYLE: &str = "body{font-family:sans-serif}\
+table{border-collapse:collapse;font-size:10pt}\
+th,td{border:1px solid #999;padding:2px 6px;text-align:center;white-space:nowrap}\
+tbody th{text-align:left}\
+td.passing{color:#999}\
+@media print{thead{display:table-header-group}}";
+
+/// Build a printable HTML timetable with stations down the side and trains across the top
+///
+/// Columns are the journeys sorted by departure. Rows are the union of every journey's
+/// stations, skipped ones included, so trains with different stopping patterns line up.
+/// A cell shows the arrival and departure at that station, a passing marker where the
+/// train runs through without calling (a skipped stop, or one with no dwell), and
+/// nothing outside the train's route.
+#[must_use]
+pub fn table(journeys: &[TrainJourney], graph: &RailwayGraph) -> String {
+    let expanded: Vec<_> = journeys.iter().map(TrainJourney::with_passed_stations).collect();
+    let mut columns: Vec<&TrainJourney> = expanded.iter().map(AsRef::as_ref).collect();
+    columns.sort_by_key(|journey| journey.departure_time);
+    let stations = station_rows(&columns, graph);
+
+    let mut html = String::from("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    let _ = writeln!(html, "<title>Timetable</title>\n<style>{TABLE_STYLE}</style>\n</head>\n<body>\n<table>");
+
+    html.push_str("<thead>\n<tr><th scope=\"col\">Station</th>");
+    for journey in &columns {
+        let _ = write!(html, "<th scope=\"col\">{}</th>", escape_html(&journey.train_number));
+    }
+    html.push_str("</tr>\n</thead>\n<tbody>\n");
+
+    for (row, &station) in stations.iter().enumerate() {
+        let name = graph.get_station_name(station).unwrap_or_default();
+        let _ = write!(html, "<tr><th scope=\"row\">{}</th>", escape_html(name));
+        for journey in &columns {
+            html.push_str(&journey_cell(journey, &stations, row));
+        }
+        html.push_str("</tr>\n");
+    }
+
+    html.push_str("</tbody>\n</table>\n</body>\n</html>\n");
+    html
+}
+
+/// Union of the stations visited by the journeys, in running order.
+/// A station not yet placed goes right after the previous station of the journey that
+/// visits it, or before the next one if it is the first of the journey.
+fn station_rows(journeys: &[&TrainJourney], graph: &RailwayGraph) -> Vec<NodeIndex> {
+    let mut ordered = journeys.to_vec();
+    ordered.sort_by_key(|journey| !journey.is_forward);
+
+    let mut rows: Vec<NodeIndex> = Vec::new();
+    for journey in ordered {
+        let mut path: Vec<NodeIndex> = journey_stations(journey, graph).collect();
+        if !journey.is_forward {
+            path.reverse();
+        }
+        insert_path(&mut rows, &path);
+    }
+    rows
+}
+
+/// Merge one journey's station sequence into the row order
+fn insert_path(rows: &mut Vec<NodeIndex>, path: &[NodeIndex]) {
+    let mut previous: Option<usize> = None;
+    for (i, &station) in path.iter().enumerate() {
+        if let Some(position) = rows.iter().position(|&s| s == station) {
+            previous = Some(position);
+            continue;
+        }
+        let position = match previous {
+            Some(prev) => prev + 1,
+            None => path[i + 1..].iter()
+                .find_map(|next| rows.iter().position(|s| s == next))
+                .unwrap_or(rows.len()),
+        };
+        rows.insert(position, station);
+        previous = Some(position);
+    }
+}
+
+/// Stations a journey calls at, skipping junctions
+fn journey_stations<'a>(journey: &'a TrainJourney, graph: &'a RailwayGraph) -> impl Iterator<Item = NodeIndex> + 'a {
+    journey.station_times.iter()
+        .map(|(node, _, _)| *node)
+        .filter(|&node| graph.get_station_name(node).is_some())
+}
+
+/// Table cell for a journey at the station in row `row`
+fn journey_cell(journey: &TrainJourney, stations: &[NodeIndex], row: usize) -> String {
+    let station = stations[row];
+    if let Some(stop) = journey.station_times.iter().position(|(node, _, _)| *node == station) {
+        let (_, arrival, departure) = journey.station_times[stop];
+        if stop > 0 && stop + 1 < journey.station_times.len() && arrival == departure {
+            return format!("<td class=\"passing\">{PASSING_MARKER}</td>");
+        }
+        let arrival = arrival.format(TIME_FORMAT);
+        let departure = departure.format(TIME_FORMAT);
+        return if arrival.to_string() == departure.to_string() {
+            format!("<td>{departure}</td>")
+        } else {
+            format!("<td>{arrival}<br>{departure}</td>")
+        };
+    }
+
+    let visited_rows: Vec<usize> = journey.station_times.iter()
+        .filter_map(|(node, _, _)| stations.iter().position(|s| s == node))
+        .collect();
+    let first = visited_rows.iter().min();
+    let last = visited_rows.iter().max();
+    match (first, last) {
+        (Some(&first), Some(&last)) if first < row && row < last => {
+            format!("<td class=\"passing\">{PASSING_MARKER}</td>")
+        }
+        _ => "<td></td>".to_string(),
+    }
+}
+
+/// Escape text for use inside HTML elements
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::BASE_DATE;
+
+    fn journey(train_number: &str, stops: &[(NodeIndex, u32, u32)]) -> TrainJourney {
+        let at = |minutes: u32| BASE_DATE.and_hms_opt(8 + minutes / 60, minutes % 60, 0).expect("valid time");
+        let station_times: Vec<_> = stops.iter().map(|&(node, arrival, departure)| (node, at(arrival), at(departure))).collect();
+        TrainJourney {
+            id: uuid::Uuid::new_v4(),
+            line_id: uuid::Uuid::new_v4(),
+            train_number: train_number.to_string(),
+            departure_time: station_times[0].2,
+            timing_inherited: vec![false; station_times.len()],
+            route_start_node: station_times.first().map(|s| s.0),
+            route_end_node: station_times.last().map(|s| s.0),
+            station_times,
+            segments: Vec::new(),
+            color: "#000000".to_string(),
+            thickness: 2.0,
+            is_forward: true,
+            passed_stations: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_table_dimensions_match_stations_and_journeys() {
+        let mut graph = RailwayGraph::new();
+        let a = graph.add_or_get_station("A".to_string());
+        let b = graph.add_or_get_station("B".to_string());
+        let c = graph.add_or_get_station("C".to_string());
+        let d = graph.add_or_get_station("D".to_string());
+
+        // Express listed first but departing later, skipping B; stopper only runs to C
+        let journeys = vec![
+            journey("Express", &[(a, 30, 30), (c, 45, 46), (d, 55, 55)]),
+            journey("Stopper", &[(a, 0, 0), (b, 10, 12), (c, 20, 20)]),
+        ];
+
+        let html = table(&journeys, &graph);
+        let body = html.split("<tbody>").nth(1).expect("table body");
+        let rows: Vec<&str> = body.lines().filter(|line| line.starts_with("<tr>")).collect();
+
+        assert_eq!(rows.len(), 4);
+        assert!(rows.iter().all(|row| row.matches("<td").count() == journeys.len()));
+        assert_eq!(html.matches("<th scope=\"col\">").count(), journeys.len() + 1);
+
+        // Sorted by departure, aligned on the union of stations
+        assert!(html.find(">Stopper<").expect("stopper column") < html.find(">Express<").expect("express column"));
+        assert!(rows[1].starts_with("<tr><th scope=\"row\">B</th><td>08:10<br>08:12</td><td class=\"passing\">"));
+        assert!(rows[3].ends_with("<td></td><td>08:55</td></tr>"));
+    }
+
+    #[test]
+    fn test_table_marks_skipped_and_zero_dwell_stops_as_passing() {
+        let mut graph = RailwayGraph::new();
+        let a = graph.add_or_get_station("A".to_string());
+        let b = graph.add_or_get_station("B".to_string());
+        let c = graph.add_or_get_station("C".to_string());
+        let d = graph.add_or_get_station("D".to_string());
+
+        // B has no dwell; C is skipped, so it is only listed among the passed stations
+        let mut express = journey("Express", &[(a, 0, 0), (b, 10, 10), (d, 30, 30)]);
+        express.passed_stations.push(crate::train_journey::PassedStation {
+            route_position: 2,
+            node: c,
+            time: BASE_DATE.and_hms_opt(8, 20, 0).expect("valid time"),
+            timing_inherited: false,
+        });
+
+        let html = table(&[express], &graph);
+        let body = html.split("<tbody>").nth(1).expect("table body");
+        let rows: Vec<&str> = body.lines().filter(|line| line.starts_with("<tr>")).collect();
+
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[1], "<tr><th scope=\"row\">B</th><td class=\"passing\">|</td></tr>");
+        assert_eq!(rows[2], "<tr><th scope=\"row\">C</th><td class=\"passing\">|</td></tr>");
+        assert!(rows[3].ends_with("<td>08:30</td></tr>"));
+    }
+}
@@ -1,3 +1,6 @@
+use serde_json::json;
+use crate::models::{GeoAnchor, GeoReference, Node, RailwayGraph};
+
 /// Calculates the shortest angular distance between two angles in radians.
 ///
 /// Returns a value in the range [0, π], representing the smallest angle
@@ -78,35 +81,65 @@ pub fn line_segments_intersect(
     (0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u)
 }
 
-/// Calculates the minimum distance from a point to a line segment.
+/// Projects a point onto a line segment.
 ///
-/// # Arguments
-/// * `point` - The point to measure from
-/// * `seg_start` - Starting point of the line segment
-/// * `seg_end` - Ending point of the line segment
+/// Returns the clamped position along the segment (0.0 at `seg_start`, 1.0 at
+/// `seg_end`) and the closest point on the segment. A zero-length segment
+/// projects everything onto its start.
 #[must_use]
-pub fn point_to_line_segment_distance(point: (f64, f64), seg_start: (f64, f64), seg_end: (f64, f64)) -> f64 {
+pub fn project_onto_segment(point: (f64, f64), seg_start: (f64, f64), seg_end: (f64, f64)) -> (f64, (f64, f64)) {
     let dx = seg_end.0 - seg_start.0;
     let dy = seg_end.1 - seg_start.1;
     let len_sq = dx * dx + dy * dy;
 
     if len_sq == 0.0 {
-        let px = point.0 - seg_start.0;
-        let py = point.1 - seg_start.1;
-        return (px * px + py * py).sqrt();
+        return (0.0, seg_start);
     }
 
     let t = ((point.0 - seg_start.0) * dx + (point.1 - seg_start.1) * dy) / len_sq;
     let t = t.clamp(0.0, 1.0);
 
-    let closest_x = seg_start.0 + t * dx;
-    let closest_y = seg_start.1 + t * dy;
+    (t, (seg_start.0 + t * dx, seg_start.1 + t * dy))
+}
 
-    let px = point.0 - closest_x;
-    let py = point.1 - closest_y;
+/// Calculates the minimum distance from a point to a line segment.
+///
+/// # Arguments
+/// * `point` - The point to measure from
+/// * `seg_start` - Starting point of the line segment
+/// * `seg_end` - Ending point of the line segment
+#[must_use]
+pub fn point_to_line_segment_distance(point: (f64, f64), seg_start: (f64, f64), seg_end: (f64, f64)) -> f64 {
+    let (_, closest) = project_onto_segment(point, seg_start, seg_end);
+    let px = point.0 - closest.0;
+    let py = point.1 - closest.1;
     (px * px + py * py).sqrt()
 }
 
+/// Whether a point lies inside a polygon, using the even-odd rule.
+///
+/// The polygon is closed implicitly from its last vertex back to its first. Regions
+/// enclosed an even number of times by a self-intersecting outline count as outside.
+#[must_use]
+pub fn point_in_polygon(point: (f64, f64), polygon: &[(f64, f64)]) -> bool {
+    let mut inside = false;
+    let Some(&last) = polygon.last() else {
+        return false;
+    };
+    let mut prev = last;
+    for &current in polygon {
+        // Count crossings of a ray cast from the point towards +x
+        if (current.1 > point.1) != (prev.1 > point.1) {
+            let crossing_x = current.0 + (point.1 - current.1) * (prev.0 - current.0) / (prev.1 - current.1);
+            if point.0 < crossing_x {
+                inside = !inside;
+            }
+        }
+        prev = current;
+    }
+    inside
+}
+
 /// Calculates the minimum distance between two line segments.
 ///
 /// Returns 0.0 if the segments intersect.
@@ -136,3 +169,261 @@ pub fn line_segment_distance(
 
     d1.min(d2).min(d3).min(d4)
 }
+
+/// Multiplies two 2D vectors as complex numbers, i.e. rotates and scales `b` by `a`
+fn complex_mul(a: (f64, f64), b: (f64, f64)) -> (f64, f64) {
+    (a.0 * b.0 - a.1 * b.1, a.0 * b.1 + a.1 * b.0)
+}
+
+/// Maps canvas positions to longitude/latitude with a rotation, uniform scale and translation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LonLatProjection {
+    rotation_scale: (f64, f64),
+    offset: (f64, f64),
+    lon_scale: f64,
+}
+
+impl LonLatProjection {
+    /// Solves the projection that carries each canvas point onto its `(lon, lat)` pair.
+    ///
+    /// Canvas y grows downwards, so it is flipped to point north. Longitudes are
+    /// scaled by the cosine of the references' mean latitude so the canvas isn't
+    /// stretched east–west away from the equator. Returns `None` when the two canvas
+    /// points coincide or the references sit on a pole.
+    #[must_use]
+    pub fn from_reference_points(canvas: [(f64, f64); 2], lon_lat: [(f64, f64); 2]) -> Option<Self> {
+        let lon_scale = ((lon_lat[0].1 + lon_lat[1].1) / 2.0).to_radians().cos();
+        let z = canvas.map(|(x, y)| (x, -y));
+        let w = lon_lat.map(|(lon, lat)| (lon * lon_scale, lat));
+        let dz = (z[1].0 - z[0].0, z[1].1 - z[0].1);
+        let dw = (w[1].0 - w[0].0, w[1].1 - w[0].1);
+        let denom = dz.0 * dz.0 + dz.1 * dz.1;
+        if denom < f64::EPSILON || lon_scale < f64::EPSILON {
+            return None;
+        }
+
+        // dw / dz as complex division
+        let rotation_scale = ((dw.0 * dz.0 + dw.1 * dz.1) / denom, (dw.1 * dz.0 - dw.0 * dz.1) / denom);
+        let rotated = complex_mul(rotation_scale, z[0]);
+        Some(Self { rotation_scale, offset: (w[0].0 - rotated.0, w[0].1 - rotated.1), lon_scale })
+    }
+
+    /// Builds the projection from a project's geo-reference, using the anchored stations'
+    /// current canvas positions. Returns `None` if either station is missing or unplaced.
+    #[must_use]
+    pub fn from_reference(graph: &RailwayGraph, reference: &GeoReference) -> Option<Self> {
+        let position = |anchor: &GeoAnchor| graph.graph.node_weight(anchor.station)?.position();
+        let canvas = [position(&reference.first)?, position(&reference.second)?];
+        Self::from_reference_points(canvas, [reference.first.lon_lat, reference.second.lon_lat])
+    }
+
+    /// Converts a canvas position to `(longitude, latitude)` in degrees
+    #[must_use]
+    pub fn project_to_lonlat(&self, (x, y): (f64, f64)) -> (f64, f64) {
+        let (u, v) = complex_mul(self.rotation_scale, (x, -y));
+        ((u + self.offset.0) / self.lon_scale, v + self.offset.1)
+    }
+}
+
+/// Converts a canvas position to `(longitude, latitude)` in degrees.
+///
+/// Without a projection the position is returned unchanged, so callers fall back to
+/// raw canvas coordinates when the project has no usable geo-reference.
+#[must_use]
+pub fn project_to_lonlat(position: (f64, f64), projection: Option<&LonLatProjection>) -> (f64, f64) {
+    projection.map_or(position, |p| p.project_to_lonlat(position))
+}
+
+/// Exports the infrastructure graph as a `GeoJSON` `FeatureCollection`.
+///
+/// Stations and junctions become `Point` features and each track edge becomes a
+/// `LineString` between its endpoints. With a usable geo-reference, coordinates are
+/// projected to longitude/latitude so the export overlays on real maps; otherwise
+/// they are the nodes' raw canvas positions. Nodes without a position, and edges
+/// touching one, are exported with a `null` geometry so every node and edge has a feature.
+///
+/// # Arguments
+/// * `graph` - The railway graph to export
+/// * `geo_reference` - The project's geo-reference, if one is set
+#[must_use]
+pub fn to_geojson(graph: &RailwayGraph, geo_reference: Option<&GeoReference>) -> String {
+    let projection = geo_reference.and_then(|reference| LonLatProjection::from_reference(graph, reference));
+    let project = |position: (f64, f64)| project_to_lonlat(position, projection.as_ref());
+    let point = |position: Option<(f64, f64)>| {
+        position.map(project).map_or(serde_json::Value::Null, |(x, y)| json!({ "type": "Point", "coordinates": [x, y] }))
+    };
+
+    let node_features = graph.graph.node_indices().filter_map(|idx| {
+        let properties = match graph.graph.node_weight(idx)? {
+            Node::Station(station) => json!({
+                "node_index": idx.index(),
+                "name": station.name,
+                "platform_count": station.platforms.len(),
+                "passing_loop": station.passing_loop,
+            }),
+            Node::Junction(junction) => json!({
+                "node_index": idx.index(),
+                "name": junction.name,
+                "junction": true,
+            }),
+        };
+        let position = graph.graph.node_weight(idx)?.position();
+        Some(json!({ "type": "Feature", "geometry": point(position), "properties": properties }))
+    });
+
+    let edge_features = graph.graph.edge_indices().filter_map(|idx| {
+        let (source, target) = graph.graph.edge_endpoints(idx)?;
+        let track = graph.graph.edge_weight(idx)?;
+        let source_pos = graph.graph.node_weight(source)?.position().map(project);
+        let target_pos = graph.graph.node_weight(target)?.position().map(project);
+        let geometry = source_pos.zip(target_pos).map_or(serde_json::Value::Null, |((x1, y1), (x2, y2))| {
+            json!({ "type": "LineString", "coordinates": [[x1, y1], [x2, y2]] })
+        });
+        Some(json!({
+            "type": "Feature",
+            "geometry": geometry,
+            "properties": {
+                "edge_index": idx.index(),
+                "track_count": track.tracks.len(),
+                "distance": track.distance,
+            },
+        }))
+    });
+
+    json!({
+        "type": "FeatureCollection",
+        "features": node_features.chain(edge_features).collect::<Vec<_>>(),
+    })
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Junction, Junctions, Stations, Track, TrackDirection, Tracks};
+    use petgraph::stable_graph::NodeIndex;
+
+    #[test]
+    fn test_point_in_polygon_concave_and_self_intersecting() {
+        // U shape opening upwards
+        let u_shape = [(0.0, 0.0), (30.0, 0.0), (30.0, 30.0), (20.0, 30.0), (20.0, 10.0), (10.0, 10.0), (10.0, 30.0), (0.0, 30.0)];
+        assert!(point_in_polygon((5.0, 20.0), &u_shape));
+        assert!(point_in_polygon((25.0, 20.0), &u_shape));
+        assert!(point_in_polygon((15.0, 5.0), &u_shape));
+        assert!(!point_in_polygon((15.0, 20.0), &u_shape));
+        assert!(!point_in_polygon((40.0, 5.0), &u_shape));
+
+        // Pentagram: the points are enclosed once, the centre twice
+        let star = [(0.0, -10.0), (5.9, 8.1), (-9.5, -3.1), (9.5, -3.1), (-5.9, 8.1)];
+        assert!(point_in_polygon((0.0, -7.0), &star));
+        assert!(!point_in_polygon((0.0, 0.0), &star));
+
+        assert!(!point_in_polygon((0.0, 0.0), &[]));
+    }
+
+    #[test]
+    fn test_project_onto_segment_clamps_to_endpoints() {
+        let (t, closest) = project_onto_segment((3.0, 4.0), (0.0, 0.0), (10.0, 0.0));
+        assert!((t - 0.3).abs() < 1e-9);
+        assert_eq!(closest, (3.0, 0.0));
+
+        let (t, closest) = project_onto_segment((-5.0, 2.0), (0.0, 0.0), (10.0, 0.0));
+        assert!(t.abs() < 1e-9);
+        assert_eq!(closest, (0.0, 0.0));
+
+        let (t, closest) = project_onto_segment((15.0, -1.0), (0.0, 0.0), (10.0, 0.0));
+        assert!((t - 1.0).abs() < 1e-9);
+        assert_eq!(closest, (10.0, 0.0));
+
+        let (t, closest) = project_onto_segment((1.0, 1.0), (2.0, 2.0), (2.0, 2.0));
+        assert!(t.abs() < 1e-9);
+        assert_eq!(closest, (2.0, 2.0));
+    }
+
+    #[test]
+    fn test_to_geojson_features_match_graph() {
+        let mut graph = RailwayGraph::new();
+        let a = graph.add_or_get_station("A".to_string());
+        let b = graph.add_or_get_station("B".to_string());
+        let j = graph.add_junction(Junction {
+            name: Some("J".to_string()),
+            position: Some((5.0, 5.0)),
+            routing_rules: vec![],
+            label_position: None,
+        });
+        graph.graph[a].set_position(Some((0.0, 0.0)));
+        graph.graph[b].set_position(Some((10.0, 2.5)));
+        graph.add_track(a, j, vec![Track { direction: TrackDirection::Bidirectional }]);
+        graph.add_track(j, b, vec![Track { direction: TrackDirection::Bidirectional }]);
+
+        let parsed: serde_json::Value = serde_json::from_str(&to_geojson(&graph, None)).expect("valid JSON");
+        let features = parsed["features"].as_array().expect("features array");
+
+        let points: Vec<_> = features.iter().filter(|f| f["geometry"]["type"] == "Point").collect();
+        let lines: Vec<_> = features.iter().filter(|f| f["geometry"]["type"] == "LineString").collect();
+        assert_eq!(points.len(), graph.graph.node_count());
+        assert_eq!(lines.len(), graph.graph.edge_count());
+
+        for feature in &points {
+            let idx = NodeIndex::new(usize::try_from(feature["properties"]["node_index"].as_u64().expect("node index")).expect("fits usize"));
+            let coords = &feature["geometry"]["coordinates"];
+            let position = (coords[0].as_f64().expect("x"), coords[1].as_f64().expect("y"));
+            assert_eq!(Some(position), graph.graph[idx].position());
+        }
+
+        let junction_feature = points.iter().find(|f| f["properties"]["name"] == "J").expect("junction feature");
+        assert_eq!(junction_feature["properties"]["junction"], true);
+    }
+
+    #[test]
+    fn test_lonlat_projection_solves_from_two_reference_points() {
+        let canvas = [(0.0, 0.0), (100.0, 0.0)];
+        let lon_lat = [(10.0, 60.0), (11.0, 60.0)];
+        let projection = LonLatProjection::from_reference_points(canvas, lon_lat).expect("solvable");
+
+        for (point, expected) in canvas.into_iter().zip(lon_lat) {
+            let (lon, lat) = projection.project_to_lonlat(point);
+            assert!((lon - expected.0).abs() < 1e-9 && (lat - expected.1).abs() < 1e-9);
+        }
+
+        let (lon, lat) = project_to_lonlat((50.0, 0.0), Some(&projection));
+        assert!((lon - 10.5).abs() < 1e-9 && (lat - 60.0).abs() < 1e-9);
+        assert_eq!(project_to_lonlat((50.0, 0.0), None), (50.0, 0.0));
+
+        // Up on the canvas is north; at 60° a degree of longitude spans half a degree of latitude
+        let (lon, lat) = projection.project_to_lonlat((0.0, -100.0));
+        assert!((lon - 10.0).abs() < 1e-9);
+        assert!((lat - 60.5).abs() < 1e-9);
+
+        assert!(LonLatProjection::from_reference_points([(5.0, 5.0), (5.0, 5.0)], lon_lat).is_none());
+    }
+
+    #[test]
+    fn test_to_geojson_projects_with_geo_reference() {
+        let mut graph = RailwayGraph::new();
+        let a = graph.add_or_get_station("A".to_string());
+        let b = graph.add_or_get_station("B".to_string());
+        let c = graph.add_or_get_station("C".to_string());
+        graph.graph[a].set_position(Some((0.0, 0.0)));
+        graph.graph[b].set_position(Some((0.0, -10.0)));
+        graph.graph[c].set_position(Some((0.0, -5.0)));
+        let reference = GeoReference {
+            first: GeoAnchor { station: a, lon_lat: (2.0, 48.0) },
+            second: GeoAnchor { station: b, lon_lat: (2.0, 49.0) },
+        };
+
+        let parsed: serde_json::Value = serde_json::from_str(&to_geojson(&graph, Some(&reference))).expect("valid JSON");
+        let features = parsed["features"].as_array().expect("features array");
+        let c_feature = features.iter().find(|f| f["properties"]["name"] == "C").expect("station feature");
+        let coords = &c_feature["geometry"]["coordinates"];
+        assert!((coords[0].as_f64().expect("lon") - 2.0).abs() < 1e-9);
+        assert!((coords[1].as_f64().expect("lat") - 48.5).abs() < 1e-9);
+
+        // An anchor that no longer exists falls back to raw canvas coordinates
+        graph.graph.remove_node(b);
+        let parsed: serde_json::Value = serde_json::from_str(&to_geojson(&graph, Some(&reference))).expect("valid JSON");
+        let c_feature = parsed["features"].as_array().expect("features array").iter()
+            .find(|f| f["properties"]["name"] == "C").cloned().expect("station feature");
+        assert_eq!(c_feature["geometry"]["coordinates"][1].as_f64(), Some(-5.0));
+    }
+}
@@ -1,8 +1,14 @@
 use chrono::{Duration, NaiveDateTime};
 use std::collections::HashMap;
 use crate::models::{Line, RailwayGraph, RouteSegment, Stations, Tracks};
+use crate::train_journey::TrainJourney;
 use petgraph::stable_graph::{EdgeIndex, NodeIndex};
 
+/// Delimiters tried when detecting the dialect, in order of preference
+const CANDIDATE_DELIMITERS: [char; 3] = ['\t', ',', ';'];
+/// Non-empty lines inspected when detecting the dialect
+const DIALECT_SAMPLE_LINES: usize = 10;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ColumnType {
     StationName,
@@ -76,12 +82,65 @@ pub struct CsvImportConfig {
     pub filename: Option<String>,
     /// If true, only use existing infrastructure (no new stations/tracks created)
     pub disable_infrastructure: bool,
+    /// Field delimiter used when reading and writing CSV
+    pub delimiter: u8,
+}
+
+/// Detect the field delimiter of `sample` and whether its first row is a header.
+///
+/// Tab, comma and semicolon are tried; the one splitting the most lines into the same
+/// number of fields wins, so text pasted from a spreadsheet needs no configuration.
+/// Falls back to a comma when none of them appear.
+#[must_use]
+pub fn detect_csv_dialect(sample: &str) -> (char, bool) {
+    let lines: Vec<&str> = sample.lines()
+        .filter(|line| !line.trim().is_empty())
+        .take(DIALECT_SAMPLE_LINES)
+        .collect();
+
+    let mut best: Option<(char, (usize, usize))> = None;
+    for delimiter in CANDIDATE_DELIMITERS {
+        let Some(score) = delimiter_score(&lines, delimiter) else { continue };
+        if best.is_none_or(|(_, best_score)| score > best_score) {
+            best = Some((delimiter, score));
+        }
+    }
+    let delimiter = best.map_or(',', |(delimiter, _)| delimiter);
+
+    let has_header = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .delimiter(u8::try_from(delimiter).unwrap_or(b','))
+        .from_reader(sample.as_bytes())
+        .records()
+        .next()
+        .and_then(Result::ok)
+        .is_some_and(|row| looks_like_header(&row));
+
+    (delimiter, has_header)
+}
+
+/// How consistently `delimiter` splits `lines`, as the number of lines sharing the most
+/// common field count and then that count. `None` if the delimiter never appears.
+fn delimiter_score(lines: &[&str], delimiter: char) -> Option<(usize, usize)> {
+    let mut line_counts: HashMap<usize, usize> = HashMap::new();
+    for line in lines {
+        let occurrences = line.matches(delimiter).count();
+        if occurrences > 0 {
+            *line_counts.entry(occurrences).or_default() += 1;
+        }
+    }
+    line_counts.into_iter()
+        .map(|(occurrences, lines)| (lines, occurrences))
+        .max()
 }
 
 /// Analyze CSV content and suggest column mappings
 pub fn analyze_csv(content: &str, filename: Option<String>) -> Option<CsvImportConfig> {
+    let (delimiter, has_headers) = detect_csv_dialect(content);
+    let delimiter = u8::try_from(delimiter).unwrap_or(b',');
     let mut reader = csv::ReaderBuilder::new()
         .has_headers(false)
+        .delimiter(delimiter)
         .from_reader(content.as_bytes());
 
     let mut records = reader.records();
@@ -99,9 +158,6 @@ pub fn analyze_csv(content: &str, filename: Option<String>) -> Option<CsvImportC
         }
     }
 
-    // Determine if first row is a header
-    let has_headers = looks_like_header(&first_row);
-
     // Build column mappings
     let mut columns = Vec::new();
     for (col_idx, field) in first_row.iter().enumerate() {
@@ -154,6 +210,7 @@ pub fn analyze_csv(content: &str, filename: Option<String>) -> Option<CsvImportC
         group_line_names,
         filename,
         disable_infrastructure: false,
+        delimiter,
     })
 }
 
@@ -572,6 +629,7 @@ fn is_duration_format(s: &str) -> bool {
 pub fn parse_csv_with_mapping(content: &str, config: &CsvImportConfig, graph: &mut RailwayGraph, existing_line_count: usize, handedness: crate::models::TrackHandedness) -> Vec<Line> {
     let mut reader = csv::ReaderBuilder::new()
         .has_headers(false)
+        .delimiter(config.delimiter)
         .from_reader(content.as_bytes());
 
     let mut records = reader.records();
@@ -603,6 +661,7 @@ pub fn parse_csv_with_mapping(content: &str, config: &CsvImportConfig, graph: &m
         return Vec::new();
     }
 
+    super::shared::add_junction_through_rules(graph, &lines);
     lines
 }
 
@@ -620,6 +679,7 @@ pub fn parse_csv_with_existing_infrastructure(
 ) -> Result<Vec<Line>, String> {
     let mut reader = csv::ReaderBuilder::new()
         .has_headers(false)
+        .delimiter(config.delimiter)
         .from_reader(content.as_bytes());
 
     let mut records = reader.records();
@@ -640,6 +700,7 @@ pub fn parse_csv_with_existing_infrastructure(
     let station_data = collect_station_data(&mut records, config, &line_groups);
 
     build_routes(&mut lines, graph, &station_data, &line_groups, config, handedness)?;
+    super::shared::add_junction_through_rules(graph, &lines);
 
     Ok(lines)
 }
@@ -968,6 +1029,7 @@ fn create_manual_departure(
         train_number: None,
         repeat_interval: None,
         repeat_until: None,
+        direction: crate::models::RouteDirection::Forward,
     })
 }
 
@@ -999,6 +1061,7 @@ fn generate_return_route(
             destination_platform: forward_segment.origin_platform,
             duration: forward_segment.duration,
             wait_time: return_wait_time,
+            reverses: false,
         });
     }
     return_route
@@ -1072,6 +1135,7 @@ fn create_pathfound_segments(
             destination_platform: dest_platform,
             duration: segment_duration,
             wait_time: segment_wait_time,
+            reverses: false,
         }
     }).collect()
 }
@@ -1351,6 +1415,7 @@ fn build_routes(
                     destination_platform,
                     duration: Some(travel_time),
                     wait_time: station_wait_time,
+                    reverses: false,
                 });
             }
 
@@ -1432,6 +1497,179 @@ struct LineStationData {
 }
 
 
+/// Export journeys as CSV using the column layout of an import configuration
+///
+/// Each station visited by the journeys becomes a row, ordered by first appearance.
+/// Global columns (station name, track distance, distance offset) are written once and
+/// the columns of the first group are repeated for every journey. Alongside the CSV, the
+/// configuration mapping every journey's columns is returned; importing the output with it
+/// yields one line per journey with the same routes and times.
+///
+/// # Errors
+/// Returns error if a row cannot be written or the output is not valid UTF-8
+pub fn export_timetable(journeys: &[TrainJourney], graph: &RailwayGraph, config: &CsvImportConfig) -> Result<(String, CsvImportConfig), String> {
+    let (before, group, after) = export_column_layout(config);
+    let export_config = export_import_config(config, &before, &group, &after, journeys);
+    let stations = export_station_order(journeys, graph);
+
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(config.delimiter)
+        .from_writer(Vec::new());
+
+    if config.has_headers {
+        let global_header = |c: &&ColumnMapping| c.header.clone().unwrap_or_else(|| c.column_type.as_str().to_string());
+        let header: Vec<String> = before.iter().map(global_header)
+            .chain(journeys.iter().flat_map(|journey| {
+                group.iter().map(|c| format!("{} {}", journey.train_number, c.column_type.as_str()))
+            }))
+            .chain(after.iter().map(global_header))
+            .collect();
+        writer.write_record(&header).map_err(|e| format!("Failed to write CSV header: {e}"))?;
+    }
+
+    let mut cumulative_distance = 0.0;
+    for (row_idx, &station_idx) in stations.iter().enumerate() {
+        let next_track = stations.get(row_idx + 1)
+            .and_then(|&next| graph.graph.find_edge(station_idx, next).or_else(|| graph.graph.find_edge(next, station_idx)))
+            .and_then(|edge| graph.graph.edge_weight(edge));
+
+        let global_cell = |c: &&ColumnMapping| match c.column_type {
+            ColumnType::StationName => graph.get_station_name(station_idx).unwrap_or_default().to_string(),
+            ColumnType::TrackDistance => next_track.and_then(|t| t.distance).map(|d| d.to_string()).unwrap_or_default(),
+            ColumnType::DistanceOffset => cumulative_distance.to_string(),
+            ColumnType::TrackNumber => next_track.map(|t| t.tracks.len().to_string()).unwrap_or_default(),
+            _ => String::new(),
+        };
+        let row: Vec<String> = before.iter().map(global_cell)
+            .chain(journeys.iter().flat_map(|journey| {
+                group.iter().map(move |c| export_journey_cell(c.column_type, journey, station_idx, graph))
+            }))
+            .chain(after.iter().map(global_cell))
+            .collect();
+        writer.write_record(&row).map_err(|e| format!("Failed to write CSV row: {e}"))?;
+
+        if let Some(distance) = next_track.and_then(|t| t.distance) {
+            cumulative_distance += distance;
+        }
+    }
+
+    let bytes = writer.into_inner().map_err(|e| format!("Failed to finish CSV: {e}"))?;
+    let csv = String::from_utf8(bytes).map_err(|e| format!("CSV output is not valid UTF-8: {e}"))?;
+    Ok((csv, export_config))
+}
+
+/// Import configuration matching the exported layout: global columns keep their types and
+/// the group template is mapped once per journey, each journey in its own group
+fn export_import_config(
+    config: &CsvImportConfig,
+    before: &[&ColumnMapping],
+    group: &[&ColumnMapping],
+    after: &[&ColumnMapping],
+    journeys: &[TrainJourney],
+) -> CsvImportConfig {
+    let global = |c: &ColumnMapping| (c.column_type, c.header.clone(), None);
+    let journey_columns = journeys.iter().enumerate().flat_map(|(group_idx, journey)| {
+        group.iter().map(move |c| {
+            let header = format!("{} {}", journey.train_number, c.column_type.as_str());
+            (c.column_type, Some(header), Some(group_idx))
+        })
+    });
+    let columns = before.iter().map(|c| global(c))
+        .chain(journey_columns)
+        .chain(after.iter().map(|c| global(c)))
+        .enumerate()
+        .map(|(column_index, (column_type, header, group_index))| ColumnMapping {
+            column_index,
+            column_type,
+            header: header.filter(|_| config.has_headers),
+            sample_values: Vec::new(),
+            auto_detected_type: column_type,
+            group_index,
+        })
+        .collect();
+
+    CsvImportConfig {
+        columns,
+        has_headers: config.has_headers,
+        defaults: config.defaults.clone(),
+        pattern_repeat: (journeys.len() > 1).then_some(group.len()),
+        group_line_names: journeys.iter()
+            .enumerate()
+            .map(|(group_idx, journey)| (group_idx, journey.train_number.clone()))
+            .collect(),
+        filename: config.filename.clone(),
+        disable_infrastructure: config.disable_infrastructure,
+        delimiter: config.delimiter,
+    }
+}
+
+/// Split configured columns into global columns before the first group, the first
+/// group's columns (the per-journey template), and global columns after it
+fn export_column_layout(config: &CsvImportConfig) -> (Vec<&ColumnMapping>, Vec<&ColumnMapping>, Vec<&ColumnMapping>) {
+    let mut columns: Vec<&ColumnMapping> = config.columns.iter().collect();
+    columns.sort_by_key(|c| c.column_index);
+
+    let group: Vec<&ColumnMapping> = columns.iter()
+        .copied()
+        .filter(|c| c.group_index == Some(0))
+        .collect();
+    let first_group_column = group.first().map(|c| c.column_index);
+
+    let (before, after) = columns.into_iter()
+        .filter(|c| c.group_index.is_none())
+        .partition(|c| first_group_column.is_none_or(|first| c.column_index < first));
+
+    (before, group, after)
+}
+
+/// Stations visited by the journeys, in order of first appearance
+fn export_station_order(journeys: &[TrainJourney], graph: &RailwayGraph) -> Vec<NodeIndex> {
+    let mut seen = std::collections::HashSet::new();
+    journeys.iter()
+        .flat_map(|journey| journey.station_times.iter().map(|(node, _, _)| *node))
+        .filter(|&node| graph.get_station_name(node).is_some() && seen.insert(node))
+        .collect()
+}
+
+/// Value of a per-journey column at a station, empty if the journey does not call there
+fn export_journey_cell(column_type: ColumnType, journey: &TrainJourney, station_idx: NodeIndex, graph: &RailwayGraph) -> String {
+    let Some(stop_idx) = journey.station_times.iter().position(|(node, _, _)| *node == station_idx) else {
+        return String::new();
+    };
+    let (_, arrival, departure) = journey.station_times[stop_idx];
+
+    match column_type {
+        ColumnType::ArrivalTime => arrival.format("%H:%M:%S").to_string(),
+        ColumnType::DepartureTime => departure.format("%H:%M:%S").to_string(),
+        ColumnType::Offset => format_export_duration(arrival - journey.departure_time),
+        ColumnType::WaitTime => format_export_duration(departure - arrival),
+        ColumnType::TravelTime => format_export_duration(stop_idx.checked_sub(1)
+            .map_or_else(Duration::zero, |prev| arrival - journey.station_times[prev].2)),
+        ColumnType::Platform => {
+            let segments_aligned = journey.segments.len() + 1 == journey.station_times.len() + journey.passed_stations.len();
+            let position = journey.route_position(stop_idx);
+            let platform = if !segments_aligned {
+                None
+            } else if position > 0 {
+                journey.segments.get(position - 1).map(|s| s.destination_platform)
+            } else {
+                journey.segments.first().map(|s| s.origin_platform)
+            };
+            platform
+                .and_then(|p| graph.graph.node_weight(station_idx)?.as_station()?.platforms.get(p))
+                .map(|p| p.name.clone())
+                .unwrap_or_default()
+        }
+        _ => String::new(),
+    }
+}
+
+/// Format a duration as `H:MM:SS`
+fn format_export_duration(duration: Duration) -> String {
+    let total = duration.num_seconds().max(0);
+    format!("{}:{:02}:{:02}", total / 3600, (total % 3600) / 60, total % 60)
+}
+
 /// Parse a time string to Duration
 fn parse_time_to_duration(s: &str) -> Option<Duration> {
     use chrono::Timelike;
@@ -1529,6 +1767,36 @@ mod tests {
         assert_eq!(config.columns[2].column_type, ColumnType::TravelTime);
     }
 
+    #[test]
+    fn test_detect_csv_dialect() {
+        let rows = [["Station", "Arrival", "Departure"], ["Alpha", "08:00:00", "08:01:00"], ["Beta", "08:10:00", "08:11:00"]];
+        for delimiter in ['\t', ',', ';'] {
+            let join = |rows: &[[&str; 3]]| {
+                rows.iter().map(|row| row.join(&delimiter.to_string())).collect::<Vec<_>>().join("\n")
+            };
+            assert_eq!(detect_csv_dialect(&join(&rows)), (delimiter, true), "with header, {delimiter:?}");
+            assert_eq!(detect_csv_dialect(&join(&rows[1..])), (delimiter, false), "without header, {delimiter:?}");
+        }
+
+        // Commas inside tab-separated station names don't win
+        let pasted = "Station\tTime\nPort, Harbour\t08:00:00\nHill\t08:10:00\n";
+        assert_eq!(detect_csv_dialect(pasted).0, '\t');
+
+        // Without any delimiter, fall back to a comma
+        assert_eq!(detect_csv_dialect("Alpha\nBeta\n").0, ',');
+    }
+
+    #[test]
+    fn test_analyze_csv_tab_separated() {
+        let tsv = "Station\tLine1\nA\t0:00:00\nB\t0:10:00\n";
+        let config = analyze_csv(tsv, None).expect("Should parse TSV");
+
+        assert_eq!(config.delimiter, b'\t');
+        assert!(config.has_headers);
+        assert_eq!(config.columns.len(), 2);
+        assert_eq!(config.columns[0].column_type, ColumnType::StationName);
+    }
+
     #[test]
     fn test_analyze_csv_no_headers() {
         let csv = "Station A,0:00:00,0:00:00\nStation B,0:10:00,0:15:00\n";
@@ -1539,6 +1807,37 @@ mod tests {
         assert_eq!(config.columns[0].column_type, ColumnType::StationName);
     }
 
+    #[test]
+    fn test_import_adds_through_routing_rule_at_junction() {
+        use crate::models::{Junctions, Stations};
+
+        let csv = "Station,Arrival,Departure\nA,,08:00:00\nSplit (J),08:05:00,08:05:00\nB,08:10:00,\n";
+        let config = analyze_csv(csv, Some("T1".to_string())).expect("Should parse CSV");
+        let mut graph = RailwayGraph::new();
+        let lines = parse_csv_with_mapping(csv, &config, &mut graph, 0, crate::models::TrackHandedness::RightHand);
+        assert_eq!(lines.len(), 1);
+
+        let a = graph.get_station_index("A").expect("A imported");
+        let b = graph.get_station_index("B").expect("B imported");
+        let junction_idx = graph.graph.node_indices()
+            .find(|&idx| graph.is_junction(idx))
+            .expect("junction imported");
+        let entry = graph.graph.find_edge(a, junction_idx).expect("edge into junction");
+        let exit = graph.graph.find_edge(junction_idx, b).expect("edge out of junction");
+
+        let junction = graph.get_junction(junction_idx).expect("junction exists");
+        let through_rules: Vec<_> = junction.routing_rules.iter()
+            .filter(|rule| rule.from_edge == entry && rule.to_edge == exit)
+            .collect();
+        assert_eq!(through_rules.len(), 1);
+        assert!(through_rules[0].allowed);
+
+        // Running the pass again does not add duplicates
+        let rule_count = junction.routing_rules.len();
+        crate::import::shared::add_junction_through_rules(&mut graph, &lines);
+        assert_eq!(graph.get_junction(junction_idx).map(|j| j.routing_rules.len()), Some(rule_count));
+    }
+
     #[test]
     fn test_infrastructure_only_import() {
         let csv_content = std::fs::read_to_string("test-data/infra.csv")
@@ -1670,9 +1969,7 @@ mod tests {
         let context = SerializableConflictContext::from_graph(
             &graph,
             station_indices,
-            chrono::Duration::seconds(30),
-            chrono::Duration::seconds(30),
-            false,
+            &crate::models::ConflictSettings::default(),
         );
 
         // Run conflict detection
@@ -1704,4 +2001,76 @@ mod tests {
         );
     }
 
+
+    #[test]
+    fn test_export_timetable_matches_import_columns() {
+        use crate::constants::BASE_DATE;
+
+        let csv_content = "Station,Arrival,Departure\nA,,08:00:00\nB,08:10:00,08:11:00\nC,08:20:00,\n";
+        let config = analyze_csv(csv_content, Some("T1".to_string())).expect("Should parse CSV");
+
+        let mut graph = RailwayGraph::new();
+        let mut lines = parse_csv_with_mapping(csv_content, &config, &mut graph, 0, crate::models::TrackHandedness::RightHand);
+        assert_eq!(lines.len(), 1);
+        lines[0].first_departure = BASE_DATE.and_hms_opt(8, 0, 0).expect("valid time");
+        lines[0].last_departure = BASE_DATE.and_hms_opt(8, 0, 0).expect("valid time");
+
+        let mut journeys: Vec<TrainJourney> = TrainJourney::generate_journeys(&lines, &graph, Some(chrono::Weekday::Mon))
+            .into_values()
+            .filter(|j| j.is_forward)
+            .collect();
+        journeys.sort_by_key(|j| j.departure_time);
+        journeys.truncate(1);
+        let train_number = journeys[0].train_number.clone();
+
+        let (exported, _) = export_timetable(&journeys, &graph, &config).expect("Should export CSV");
+        let rows: Vec<&str> = exported.lines().collect();
+
+        assert_eq!(rows[0], format!("Station,{train_number} Arrival Time,{train_number} Departure Time"));
+        assert_eq!(rows[2], "B,08:10:00,08:11:00");
+        assert_eq!(rows.len(), 4);
+    }
+
+    #[test]
+    fn test_export_timetable_round_trips_several_journeys() {
+        use crate::constants::BASE_DATE;
+
+        let csv_content = "Station,Arrival,Departure\nA,,08:00:00\nB,08:10:00,08:11:00\nC,08:20:00,\n";
+        let config = analyze_csv(csv_content, Some("T1".to_string())).expect("Should parse CSV");
+
+        let mut graph = RailwayGraph::new();
+        let mut lines = parse_csv_with_mapping(csv_content, &config, &mut graph, 0, crate::models::TrackHandedness::RightHand);
+        lines[0].first_departure = BASE_DATE.and_hms_opt(8, 0, 0).expect("valid time");
+        lines[0].last_departure = BASE_DATE.and_hms_opt(9, 0, 0).expect("valid time");
+        lines[0].schedule_mode = crate::models::ScheduleMode::Auto;
+        lines[0].frequency = chrono::Duration::minutes(30);
+
+        let mut journeys: Vec<TrainJourney> = TrainJourney::generate_journeys(&lines, &graph, Some(chrono::Weekday::Mon))
+            .into_values()
+            .filter(|j| j.is_forward)
+            .collect();
+        journeys.sort_by_key(|j| j.departure_time);
+        journeys.truncate(2);
+        assert_eq!(journeys.len(), 2);
+
+        let (exported, export_config) = export_timetable(&journeys, &graph, &config).expect("Should export CSV");
+
+        let mut imported_graph = RailwayGraph::new();
+        let imported = parse_csv_with_mapping(&exported, &export_config, &mut imported_graph, 0, crate::models::TrackHandedness::RightHand);
+        assert_eq!(imported.len(), 2);
+
+        for (journey, line) in journeys.iter().zip(&imported) {
+            assert_eq!(line.name, journey.train_number);
+            let reimported = TrainJourney::generate_journeys(std::slice::from_ref(line), &imported_graph, Some(chrono::Weekday::Mon))
+                .into_values()
+                .filter(|j| j.is_forward)
+                .min_by_key(|j| j.departure_time)
+                .expect("Imported line should run");
+            // The origin's arrival is not part of the export; every departure and later arrival is
+            let departures = |j: &TrainJourney| j.station_times.iter().map(|&(_, _, departure)| departure).collect::<Vec<_>>();
+            let arrivals = |j: &TrainJourney| j.station_times.iter().skip(1).map(|&(_, arrival, _)| arrival).collect::<Vec<_>>();
+            assert_eq!(departures(&reimported), departures(journey));
+            assert_eq!(arrivals(&reimported), arrivals(journey));
+        }
+    }
 }
@@ -0,0 +1,384 @@
+//! Import of GTFS feeds to bootstrap a network
+//!
+//! Supported subset:
+//! - `stops.txt` rows become stations, matched to existing ones by `stop_name`
+//! - `trips.txt` and `stop_times.txt` give each trip's stop sequence. GTFS has no track
+//!   topology, so a single-track bidirectional segment is created for every pair of
+//!   consecutive stops seen in any trip
+//! - Trips of the same route with the same stop sequence and timings are grouped into one
+//!   line, named after `routes.txt` (`route_short_name`, falling back to `route_long_name`)
+//!   and coloured by `route_color`. Each trip becomes a manual departure
+//!
+//! Not supported: `calendar.txt` (all trips run every day), frequencies, shapes and transfers.
+
+use serde::Deserialize;
+use crate::models::{RailwayGraph, Line, RouteSegment, ManualDeparture, ScheduleMode, DaysOfWeek, Stations, Tracks, Track, TrackDirection, RouteDirection};
+use crate::constants::BASE_DATE;
+use chrono::{Duration, NaiveTime};
+use petgraph::stable_graph::{NodeIndex, EdgeIndex};
+use std::collections::HashMap;
+
+const REQUIRED_FILES: [&str; 4] = ["stops.txt", "routes.txt", "trips.txt", "stop_times.txt"];
+const SECONDS_PER_DAY: i64 = 86400;
+
+#[derive(Debug, Deserialize)]
+struct GtfsStop {
+    stop_id: String,
+    #[serde(default)]
+    stop_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GtfsRoute {
+    #[serde(rename = "route_id")]
+    id: String,
+    #[serde(rename = "route_short_name", default)]
+    short_name: String,
+    #[serde(rename = "route_long_name", default)]
+    long_name: String,
+    #[serde(rename = "route_color", default)]
+    color: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GtfsTrip {
+    route_id: String,
+    trip_id: String,
+    #[serde(default)]
+    trip_short_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GtfsStopTime {
+    trip_id: String,
+    #[serde(default)]
+    arrival_time: String,
+    #[serde(default)]
+    departure_time: String,
+    stop_id: String,
+    stop_sequence: u32,
+}
+
+/// Stop sequence and timing of one trip, with run and dwell times in seconds
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TripPattern {
+    route_id: String,
+    nodes: Vec<NodeIndex>,
+    run_times: Vec<i64>,
+    dwell_times: Vec<i64>,
+}
+
+/// Parse a GTFS feed into a new graph and the lines running on it
+/// `files` maps file names (e.g. `stops.txt`) to their contents
+///
+/// # Errors
+/// Returns an error if a required file is missing or malformed, or a trip references
+/// an unknown stop
+pub fn parse_gtfs(files: &HashMap<String, String>) -> Result<(RailwayGraph, Vec<Line>), String> {
+    let mut graph = RailwayGraph::new();
+    let lines = import_gtfs(files, &mut graph, 0, &[])?;
+    Ok((graph, lines))
+}
+
+/// Import a GTFS feed into `graph` and return the lines to add
+/// Stations are matched to existing ones by name
+///
+/// # Errors
+/// Returns an error if a required file is missing or malformed, or a trip references
+/// an unknown stop
+pub fn import_gtfs(
+    files: &HashMap<String, String>,
+    graph: &mut RailwayGraph,
+    starting_line_count: usize,
+    existing_line_names: &[String],
+) -> Result<Vec<Line>, String> {
+    if let Some(missing) = REQUIRED_FILES.iter().find(|name| !files.contains_key(**name)) {
+        return Err(format!("GTFS feed is missing required file '{missing}'"));
+    }
+
+    let stops: Vec<GtfsStop> = read_records(files, "stops.txt")?;
+    let routes: Vec<GtfsRoute> = read_records(files, "routes.txt")?;
+    let trips: Vec<GtfsTrip> = read_records(files, "trips.txt")?;
+    let stop_times: Vec<GtfsStopTime> = read_records(files, "stop_times.txt")?;
+
+    let stop_nodes: HashMap<&str, NodeIndex> = stops.iter()
+        .map(|stop| {
+            let name = if stop.stop_name.is_empty() { stop.stop_id.clone() } else { stop.stop_name.clone() };
+            (stop.stop_id.as_str(), graph.add_or_get_station(name))
+        })
+        .collect();
+
+    let mut trip_stop_times: HashMap<&str, Vec<&GtfsStopTime>> = HashMap::new();
+    for stop_time in &stop_times {
+        trip_stop_times.entry(stop_time.trip_id.as_str()).or_default().push(stop_time);
+    }
+
+    let mut edge_map: HashMap<(NodeIndex, NodeIndex), EdgeIndex> = HashMap::new();
+    let mut groups: Vec<(TripPattern, Vec<(&GtfsTrip, NaiveTime)>)> = Vec::new();
+    for trip in &trips {
+        let Some(trip_times) = trip_stop_times.get_mut(trip.trip_id.as_str()) else {
+            continue;
+        };
+        trip_times.sort_by_key(|stop_time| stop_time.stop_sequence);
+        let Some((pattern, departure)) = trip_pattern(trip, trip_times, &stop_nodes)? else {
+            continue;
+        };
+        add_edges(graph, &mut edge_map, &pattern.nodes);
+        match groups.iter_mut().find(|(existing, _)| *existing == pattern) {
+            Some((_, members)) => members.push((trip, departure)),
+            None => groups.push((pattern, vec![(trip, departure)])),
+        }
+    }
+
+    let route_names: HashMap<&str, &GtfsRoute> = routes.iter().map(|r| (r.id.as_str(), r)).collect();
+    groups.iter().enumerate()
+        .map(|(index, (pattern, members))| {
+            let route = route_names.get(pattern.route_id.as_str()).copied();
+            let patterns_on_route = groups.iter().filter(|(p, _)| p.route_id == pattern.route_id).count();
+            let name = line_name(graph, route, pattern, patterns_on_route > 1, index, existing_line_names);
+            create_line(graph, &edge_map, route, pattern, members, name, starting_line_count + index)
+        })
+        .collect()
+}
+
+fn read_records<T: serde::de::DeserializeOwned>(files: &HashMap<String, String>, name: &str) -> Result<Vec<T>, String> {
+    let contents = files.get(name).map_or("", String::as_str);
+    csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(contents.trim_start_matches('\u{feff}').as_bytes())
+        .deserialize()
+        .collect::<Result<Vec<T>, _>>()
+        .map_err(|e| format!("Failed to parse {name}: {e}"))
+}
+
+/// Parse a GTFS time into seconds past midnight; hours may exceed 23 for trips running past midnight
+fn parse_gtfs_seconds(time: &str) -> Option<i64> {
+    let mut parts = time.split(':').map(|part| part.parse::<i64>().ok());
+    let (hours, minutes, seconds) = (parts.next()??, parts.next()??, parts.next()??);
+    (parts.next().is_none() && minutes < 60 && seconds < 60).then_some(hours * 3600 + minutes * 60 + seconds)
+}
+
+/// Extract the pattern and first departure of a trip, or `None` if it has fewer than two timed stops.
+/// Untimed stops between timed ones are kept with times spread evenly over the gap; untimed stops
+/// before the first or after the last timed stop are dropped.
+fn trip_pattern(
+    trip: &GtfsTrip,
+    trip_times: &[&GtfsStopTime],
+    stop_nodes: &HashMap<&str, NodeIndex>,
+) -> Result<Option<(TripPattern, NaiveTime)>, String> {
+    let mut stops = Vec::new();
+    for stop_time in trip_times {
+        let node = stop_nodes.get(stop_time.stop_id.as_str()).copied()
+            .ok_or_else(|| format!("Trip '{}' references unknown stop '{}'", trip.trip_id, stop_time.stop_id))?;
+        let arrival = parse_gtfs_seconds(&stop_time.arrival_time);
+        let departure = parse_gtfs_seconds(&stop_time.departure_time);
+        stops.push((node, arrival.or(departure).zip(departure.or(arrival))));
+    }
+
+    let Some((first_timed, last_timed)) = stops.iter().position(|(_, t)| t.is_some())
+        .zip(stops.iter().rposition(|(_, t)| t.is_some()))
+        .filter(|(first, last)| first < last)
+    else {
+        return Ok(None);
+    };
+    let stops = &stops[first_timed..=last_timed];
+    let nodes: Vec<NodeIndex> = stops.iter().map(|(node, _)| *node).collect();
+    let times = interpolate_untimed_stops(stops.iter().map(|(_, t)| *t));
+
+    let run_times = times.windows(2).map(|pair| pair[1].0 - pair[0].1).collect();
+    let dwell_times = times.iter().map(|&(arrival, departure)| departure - arrival).collect();
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let first_departure = NaiveTime::from_num_seconds_from_midnight_opt(times[0].1.rem_euclid(SECONDS_PER_DAY) as u32, 0)
+        .ok_or_else(|| format!("Trip '{}' has an invalid departure time", trip.trip_id))?;
+
+    Ok(Some((TripPattern { route_id: trip.route_id.clone(), nodes, run_times, dwell_times }, first_departure)))
+}
+
+/// Fill untimed stops with zero-dwell times spread evenly between the surrounding timed stops.
+/// The first and last stop must be timed.
+fn interpolate_untimed_stops(stop_times: impl Iterator<Item = Option<(i64, i64)>>) -> Vec<(i64, i64)> {
+    let stop_times: Vec<Option<(i64, i64)>> = stop_times.collect();
+    let mut times = Vec::with_capacity(stop_times.len());
+    let mut previous_timed = 0;
+    for (index, stop) in stop_times.iter().enumerate() {
+        if let Some(time) = *stop {
+            times.push(time);
+            previous_timed = index;
+            continue;
+        }
+        let Some((next_timed, next)) = stop_times.iter().enumerate().skip(index).find_map(|(i, t)| t.map(|t| (i, t))) else {
+            break;
+        };
+        let (_, from) = times[previous_timed];
+        #[allow(clippy::cast_possible_wrap)]
+        let time = from + (next.0 - from) * (index - previous_timed) as i64 / (next_timed - previous_timed) as i64;
+        times.push((time, time));
+    }
+    times
+}
+
+/// Add a single-track segment for each consecutive stop pair not yet connected
+fn add_edges(graph: &mut RailwayGraph, edge_map: &mut HashMap<(NodeIndex, NodeIndex), EdgeIndex>, nodes: &[NodeIndex]) {
+    for pair in nodes.windows(2) {
+        let (from, to) = (pair[0], pair[1]);
+        if from == to {
+            continue;
+        }
+        edge_map.entry((from.min(to), from.max(to)))
+            .or_insert_with(|| graph.add_track(from.min(to), from.max(to), vec![Track { direction: TrackDirection::Bidirectional }]));
+    }
+}
+
+fn line_name(
+    graph: &RailwayGraph,
+    route: Option<&GtfsRoute>,
+    pattern: &TripPattern,
+    route_has_several_patterns: bool,
+    index: usize,
+    existing_line_names: &[String],
+) -> String {
+    let station_name = |node: Option<&NodeIndex>| {
+        node.and_then(|&n| graph.get_station_name(n)).unwrap_or("?").to_string()
+    };
+    let endpoints = format!("{} - {}", station_name(pattern.nodes.first()), station_name(pattern.nodes.last()));
+    let route_name = route
+        .map(|r| if r.short_name.is_empty() { r.long_name.clone() } else { r.short_name.clone() })
+        .filter(|n| !n.is_empty());
+
+    let name = match route_name {
+        Some(route_name) if route_has_several_patterns => format!("{route_name}: {endpoints}"),
+        Some(route_name) => route_name,
+        None => endpoints,
+    };
+    if existing_line_names.contains(&name) {
+        format!("{name} ({index})")
+    } else {
+        name
+    }
+}
+
+fn create_line(
+    graph: &RailwayGraph,
+    edge_map: &HashMap<(NodeIndex, NodeIndex), EdgeIndex>,
+    route: Option<&GtfsRoute>,
+    pattern: &TripPattern,
+    members: &[(&GtfsTrip, NaiveTime)],
+    name: String,
+    color_seed: usize,
+) -> Result<Line, String> {
+    let forward_route = pattern.nodes.windows(2).enumerate()
+        .filter(|(_, pair)| pair[0] != pair[1])
+        .map(|(i, pair)| {
+            let (from, to) = (pair[0], pair[1]);
+            let edge = edge_map.get(&(from.min(to), from.max(to))).copied()
+                .ok_or_else(|| format!("No track connects stops {} and {}", from.index(), to.index()))?;
+            let traveling_backward = graph.get_track_endpoints(edge).is_some_and(|(source, _)| source != from);
+            Ok(RouteSegment {
+                edge_index: edge.index(),
+                track_index: graph.select_track_for_direction(edge, traveling_backward),
+                origin_platform: 0,
+                destination_platform: 0,
+                duration: Some(Duration::seconds(pattern.run_times[i])),
+                wait_time: Duration::seconds(pattern.dwell_times[i + 1]),
+                reverses: false,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let (first_node, last_node) = (pattern.nodes[0], pattern.nodes[pattern.nodes.len() - 1]);
+    let manual_departures: Vec<ManualDeparture> = members.iter()
+        .map(|(trip, departure)| ManualDeparture {
+            id: uuid::Uuid::new_v4(),
+            time: BASE_DATE.and_time(*departure),
+            from_station: first_node,
+            to_station: last_node,
+            days_of_week: DaysOfWeek::ALL_DAYS,
+            train_number: Some(if trip.trip_short_name.is_empty() { trip.trip_id.clone() } else { trip.trip_short_name.clone() }),
+            repeat_interval: None,
+            repeat_until: None,
+            direction: RouteDirection::Forward,
+        })
+        .collect();
+
+    let mut line = Line::create_from_ids(&[name], color_seed).remove(0);
+    if let Some(color) = route.map(|r| r.color.as_str()).filter(|c| c.len() == 6) {
+        line.color = format!("#{color}");
+    }
+    line.first_departure = manual_departures[0].time;
+    line.schedule_mode = ScheduleMode::Manual;
+    line.manual_departures = manual_departures;
+    line.forward_route = forward_route;
+    line.sync_routes = false;
+    Ok(line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn minimal_feed() -> HashMap<String, String> {
+        [
+            ("stops.txt", "stop_id,stop_name,stop_lat,stop_lon\nA,Alpha,0,0\nB,Beta,0,1\nC,Gamma,0,2\n"),
+            ("routes.txt", "route_id,route_short_name,route_long_name,route_type,route_color\nR1,R1,Alpha - Gamma,2,FF0000\n"),
+            ("trips.txt", "route_id,service_id,trip_id,trip_short_name\nR1,daily,t1,101\nR1,daily,t2,103\nR1,daily,t3,102\n"),
+            ("stop_times.txt", "trip_id,arrival_time,departure_time,stop_id,stop_sequence\n\
+                t1,08:00:00,08:00:00,A,1\nt1,08:10:00,08:11:00,B,2\nt1,08:20:00,08:20:00,C,3\n\
+                t2,09:00:00,09:00:00,A,1\nt2,09:10:00,09:11:00,B,2\nt2,09:20:00,09:20:00,C,3\n\
+                t3,24:40:00,24:40:00,C,1\nt3,24:50:00,24:51:00,B,2\nt3,25:00:00,25:00:00,A,3\n"),
+        ]
+        .into_iter()
+        .map(|(name, contents)| (name.to_string(), contents.to_string()))
+        .collect()
+    }
+
+    #[test]
+    fn test_parse_minimal_gtfs() {
+        let (graph, lines) = parse_gtfs(&minimal_feed()).expect("feed should import");
+
+        assert_eq!(graph.graph.node_count(), 3);
+        // Both directions share the Alpha - Beta and Beta - Gamma segments
+        assert_eq!(graph.graph.edge_count(), 2);
+        // The two identical morning trips share a line; the reverse trip gets its own
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].name, "R1: Alpha - Gamma");
+        assert_eq!(lines[0].color, "#FF0000");
+        assert_eq!(lines[0].manual_departures.len(), 2);
+        assert_eq!(lines[0].forward_route.len(), 2);
+        assert_eq!(lines[0].forward_route[0].duration, Some(Duration::minutes(10)));
+        assert_eq!(lines[0].forward_route[0].wait_time, Duration::minutes(1));
+        assert_eq!(lines[1].name, "R1: Gamma - Alpha");
+        assert_eq!(lines[1].manual_departures[0].time.time(), NaiveTime::from_hms_opt(0, 40, 0).expect("valid time"));
+    }
+
+    #[test]
+    fn test_missing_required_file_is_rejected() {
+        let mut files = minimal_feed();
+        files.remove("stop_times.txt");
+
+        let error = parse_gtfs(&files).expect_err("stop_times.txt is required");
+        assert!(error.contains("stop_times.txt"), "unexpected error: {error}");
+    }
+
+    #[test]
+    fn test_untimed_stops_are_interpolated() {
+        let mut files = minimal_feed();
+        files.insert("stop_times.txt".to_string(), "trip_id,arrival_time,departure_time,stop_id,stop_sequence\n\
+            t1,08:00:00,08:00:00,A,1\nt1,,,B,2\nt1,08:20:00,08:20:00,C,3\n".to_string());
+
+        let (_, lines) = parse_gtfs(&files).expect("feed should import");
+
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].forward_route.len(), 2);
+        assert_eq!(lines[0].forward_route[0].duration, Some(Duration::minutes(10)));
+        assert_eq!(lines[0].forward_route[0].wait_time, Duration::zero());
+        assert_eq!(lines[0].forward_route[1].duration, Some(Duration::minutes(10)));
+    }
+
+    #[test]
+    fn test_parse_gtfs_seconds_past_midnight() {
+        assert_eq!(parse_gtfs_seconds("25:10:30"), Some(25 * 3600 + 10 * 60 + 30));
+        assert_eq!(parse_gtfs_seconds("8:05:00"), Some(8 * 3600 + 5 * 60));
+        assert_eq!(parse_gtfs_seconds(""), None);
+        assert_eq!(parse_gtfs_seconds("08:75:00"), None);
+    }
+}
@@ -1,9 +1,19 @@
 use serde::Deserialize;
-use crate::models::{RailwayGraph, Line, LineStyle, RouteSegment, ManualDeparture, ScheduleMode, DaysOfWeek, Stations, Tracks, generate_random_color};
+use crate::models::{RailwayGraph, Line, LineStyle, RouteSegment, ManualDeparture, ScheduleMode, DaysOfWeek, ServicePattern, Stations, Tracks, generate_random_color};
 use crate::constants::BASE_DATE;
-use chrono::{Duration, Timelike};
+use chrono::{Duration, NaiveDate, Timelike};
 use petgraph::stable_graph::{NodeIndex, EdgeIndex};
 use std::collections::HashMap;
+use std::fmt::Write;
+use quick_xml::escape::escape;
+
+/// Days in `JTrainGraph` day-mask order (Monday first)
+const EXPORT_WEEKDAYS: [chrono::Weekday; 7] = [
+    chrono::Weekday::Mon, chrono::Weekday::Tue, chrono::Weekday::Wed, chrono::Weekday::Thu,
+    chrono::Weekday::Fri, chrono::Weekday::Sat, chrono::Weekday::Sun,
+];
+
+const JTRAINGRAPH_XML_HEADER: &str = r##"<?xml version="1.0" encoding="UTF-8"?><jTrainGraph_timetable version="012" name="" tMin="04:00" tMax="02:00" d="1111111" bgC="#FFFFFF" sFont="font(SansSerif;0;16)" trFont="font(SansSerif;0;11)" hFont="font(SansSerif;0;12)" tFont="font(SansSerif;0;9)" sHor="true" sLine="00:05" shKm="false" sStation="-1" eStation="-1" cNr="1" exW="1800" hpH="818.1818" shV="1" shT="true" shC="false" hlI="01:00" hlC="#808080" p="true" pC="1" mpP="03:00" rT="true" shMu="false" dTt="00:02" odBT="03:00" isTV="true">"##;
 
 #[derive(Debug, Deserialize, PartialEq)]
 #[serde(rename = "jTrainGraph_timetable")]
@@ -305,7 +315,7 @@ fn add_platforms_to_station(
 
     // Replace platforms with imported data
     station_node.platforms = platforms.iter()
-        .map(|p| crate::models::Platform { name: p.name.clone() })
+        .map(|p| crate::models::Platform { name: p.name.clone(), capacity: crate::models::DEFAULT_PLATFORM_CAPACITY })
         .collect();
 }
 
@@ -467,6 +477,7 @@ fn create_route_segments(
             destination_platform,
             duration,
             wait_time,
+            reverses: false,
         });
     }
 
@@ -520,10 +531,10 @@ pub fn import_jtraingraph(
             // dTi (default platform in) = platform when arriving at destination station
             // These are platform names, we need to find their index
             let from_platforms: Vec<_> = from_station.platforms.iter()
-                .map(|p| crate::models::Platform { name: p.name.clone() })
+                .map(|p| crate::models::Platform { name: p.name.clone(), capacity: crate::models::DEFAULT_PLATFORM_CAPACITY })
                 .collect();
             let to_platforms: Vec<_> = to_station.platforms.iter()
-                .map(|p| crate::models::Platform { name: p.name.clone() })
+                .map(|p| crate::models::Platform { name: p.name.clone(), capacity: crate::models::DEFAULT_PLATFORM_CAPACITY })
                 .collect();
             let default_platform_source = super::shared::find_platform_by_name(&from_platforms, &from_station.default_platform_away);
             let default_platform_target = super::shared::find_platform_by_name(&to_platforms, &to_station.default_platform_in);
@@ -657,6 +668,7 @@ pub fn import_jtraingraph(
                     train_number: Some(train.name.clone()),
                     repeat_interval: None,
                     repeat_until: None,
+                    direction: crate::models::RouteDirection::Forward,
                 })
             })
             .collect();
@@ -700,6 +712,15 @@ pub fn import_jtraingraph(
             style: LineStyle::default(),
             forward_turnaround: false,
             return_turnaround: false,
+            frequency_bands: Vec::new(),
+            skip_pattern: None,
+            skip_pattern_on_even: false,
+            rolling_stock: None,
+            clock_face_snap: None,
+            section_order_override: None,
+            valid_from: None,
+            valid_to: None,
+            service_pattern_id: None,
         };
 
         new_lines.push(line);
@@ -708,6 +729,202 @@ pub fn import_jtraingraph(
     Ok(new_lines)
 }
 
+/// Export the railway graph and line timetables as `JTrainGraph` XML (`.fpl`)
+///
+/// Stations are written in graph traversal order, which matches the order the
+/// importer creates them in, so the output can be imported back as a corridor.
+/// Every generated journey becomes a `ti` (ascending) or `ta` (descending) train.
+///
+/// Lossy fields:
+/// - Only stations are exported; junctions are dropped and branches are flattened
+///   into a single corridor, so tracks between non-adjacent stations are lost
+/// - Station positions, label positions and passing loop flags are not exported
+/// - Line names, codes, styles, folders and schedule settings are not exported;
+///   trains are regrouped into lines by stop pattern on re-import
+/// - Intermediate stations without a dwell time are written as pass-throughs, so
+///   segment durations are re-derived from the stop times
+/// - Speed limits, rolling stock and track directions are not exported
+#[must_use]
+pub fn export_jtraingraph(graph: &RailwayGraph, lines: &[Line], service_patterns: &[ServicePattern], reference_week: Option<NaiveDate>) -> String {
+    let stations = graph.get_all_stations_ordered();
+    let station_positions: HashMap<NodeIndex, usize> = stations.iter()
+        .enumerate()
+        .map(|(pos, (node_idx, _))| (*node_idx, pos))
+        .collect();
+
+    let mut xml = String::from(JTRAINGRAPH_XML_HEADER);
+    xml.push_str("<stations>");
+    let mut km = 0.0;
+    for (pos, (node_idx, station)) in stations.iter().enumerate() {
+        let prev_edge = pos.checked_sub(1)
+            .and_then(|prev| graph.graph.find_edge(stations[prev].0, *node_idx));
+        let next_edge = stations.get(pos + 1)
+            .and_then(|(next_idx, _)| graph.graph.find_edge(*node_idx, *next_idx));
+
+        if let Some(distance) = prev_edge.and_then(|e| graph.graph.edge_weight(e)).and_then(|t| t.distance) {
+            km += distance;
+        }
+
+        xml.push_str(&export_station(graph, station, km, prev_edge, next_edge));
+    }
+    xml.push_str("</stations><trains>");
+
+    let weekly_trains = collect_weekly_trains(graph, lines, service_patterns, reference_week);
+
+    // The parser expects all ti trains before any ta trains
+    let (trains_away, trains_in): (Vec<_>, Vec<_>) = weekly_trains.iter()
+        .enumerate()
+        .filter_map(|(train_id, (journey, days))| {
+            let line = lines.iter().find(|l| l.id == journey.line_id)?;
+            export_train(graph, journey, line, *days, &station_positions, stations.len(), train_id)
+        })
+        .partition(|(is_return, _)| *is_return);
+    xml.extend(trains_in.into_iter().map(|(_, train)| train));
+    xml.extend(trains_away.into_iter().map(|(_, train)| train));
+    xml.push_str("</trains></jTrainGraph_timetable>");
+
+    xml
+}
+
+/// Generate each day's journeys and merge trains running at the same time on several days
+fn collect_weekly_trains(graph: &RailwayGraph, lines: &[Line], service_patterns: &[ServicePattern], reference_week: Option<NaiveDate>) -> Vec<(crate::train_journey::TrainJourney, DaysOfWeek)> {
+    let mut trains: Vec<(crate::train_journey::TrainJourney, DaysOfWeek)> = Vec::new();
+    let mut train_lookup: HashMap<(uuid::Uuid, String, chrono::NaiveDateTime, bool), usize> = HashMap::new();
+
+    for (day_index, weekday) in EXPORT_WEEKDAYS.into_iter().enumerate() {
+        let Some(day) = DaysOfWeek::from_index(day_index) else {
+            continue;
+        };
+        for journey in crate::train_journey::TrainJourney::generate_journeys_with_patterns(lines, graph, Some(weekday), service_patterns, reference_week).into_values() {
+            let key = (journey.line_id, journey.train_number.clone(), journey.departure_time, journey.is_forward);
+            if let Some(&existing) = train_lookup.get(&key) {
+                trains[existing].1 |= day;
+            } else {
+                train_lookup.insert(key, trains.len());
+                trains.push((journey, day));
+            }
+        }
+    }
+
+    trains.sort_by(|(a, _), (b, _)| a.departure_time.cmp(&b.departure_time).then_with(|| a.train_number.cmp(&b.train_number)));
+    trains
+}
+
+/// Serialize a single station, including its platforms and defaults for adjacent tracks
+fn export_station(
+    graph: &RailwayGraph,
+    station: &crate::models::StationNode,
+    km: f64,
+    prev_edge: Option<EdgeIndex>,
+    next_edge: Option<EdgeIndex>,
+) -> String {
+    let platform_name = |index: Option<usize>| {
+        index
+            .and_then(|i| station.platforms.get(i))
+            .map(|p| p.name.clone())
+            .unwrap_or_default()
+    };
+
+    let next_segment = next_edge.and_then(|e| graph.graph.edge_weight(e));
+    let track_count = next_segment.map_or(1, |t| t.tracks.len().max(1));
+    let default_in = platform_name(prev_edge.and_then(|e| graph.graph.edge_weight(e)).and_then(|t| t.default_platform_target));
+    let default_away = platform_name(next_segment.and_then(|t| t.default_platform_source));
+
+    let mut xml = format!(
+        r##"<sta name="{}" kml="{km:.3}" kmr="{km:.3}" cl="#000000" sh="true" sz="1" sy="0" sri="true" sra="true" tr="{track_count}" dTi="{}" dTa="{}">"##,
+        escape(&station.name),
+        escape(&default_in),
+        escape(&default_away),
+    );
+    xml.extend(station.platforms.iter().map(|platform| format!(r#"<track name="{}"></track>"#, escape(&platform.name))));
+    xml.push_str("</sta>");
+    xml
+}
+
+/// Serialize a journey as a `ti`/`ta` train, returning whether it is a `ta` (return) train,
+/// or `None` if it touches fewer than two exported stations
+fn export_train(
+    graph: &RailwayGraph,
+    journey: &crate::train_journey::TrainJourney,
+    line: &Line,
+    days: DaysOfWeek,
+    station_positions: &HashMap<NodeIndex, usize>,
+    station_count: usize,
+    train_id: usize,
+) -> Option<(bool, String)> {
+    let mut times = vec![(String::new(), String::new(), String::new(), String::new()); station_count];
+    let segments_aligned = journey.segments.len() + 1 == journey.station_times.len() + journey.passed_stations.len();
+    let last = journey.station_times.len().checked_sub(1)?;
+
+    let mut visited = Vec::new();
+    for (i, (node_idx, arrival, departure)) in journey.station_times.iter().enumerate() {
+        let Some(&pos) = station_positions.get(node_idx) else {
+            continue;
+        };
+        let is_stop = i == 0 || i == last || departure > arrival;
+        if !is_stop {
+            continue;
+        }
+
+        let platform_name = |index: Option<usize>| {
+            index
+                .and_then(|p| graph.graph.node_weight(*node_idx)?.as_station()?.platforms.get(p))
+                .map(|p| p.name.clone())
+                .unwrap_or_default()
+        };
+        let position = journey.route_position(i);
+        let arrival_platform = platform_name(position.checked_sub(1).filter(|_| segments_aligned).and_then(|s| journey.segments.get(s)).map(|s| s.destination_platform));
+        let departure_platform = platform_name(journey.segments.get(position).filter(|_| segments_aligned && i < last).map(|s| s.origin_platform));
+
+        let arrival_str = if i == 0 { String::new() } else { format_jtraingraph_time(arrival.time()) };
+        let departure_str = if i == last { String::new() } else { format_jtraingraph_time(departure.time()) };
+        times[pos] = (arrival_str, departure_str, arrival_platform, departure_platform);
+        visited.push(pos);
+    }
+
+    let (&first_pos, &last_pos) = (visited.first()?, visited.last()?);
+    if first_pos == last_pos {
+        return None;
+    }
+
+    // The importer reads arrival/departure platforms of return trains swapped
+    let is_return = last_pos < first_pos;
+    let tag = if is_return { "ta" } else { "ti" };
+
+    let mut xml = format!(
+        r#"<{tag} name="{}" cm="{}" cl="{}" sh="true" sz="1" sy="0" d="{}" id="{train_id}">"#,
+        escape(&journey.train_number),
+        escape(&line.name),
+        escape(&line.color),
+        days.to_jtraingraph_format(),
+    );
+    for (arrival, departure, arrival_platform, departure_platform) in &times {
+        let (at, dt) = if is_return {
+            (departure_platform, arrival_platform)
+        } else {
+            (arrival_platform, departure_platform)
+        };
+        let _ = write!(
+            xml,
+            r#"<t a="{arrival}" d="{departure}" at="{}" dt="{}"></t>"#,
+            escape(at),
+            escape(dt),
+        );
+    }
+    let _ = write!(xml, "</{tag}>");
+
+    Some((is_return, xml))
+}
+
+/// Format a time as `HH:MM`, or `HH:MM:SS` when seconds are present
+fn format_jtraingraph_time(time: chrono::NaiveTime) -> String {
+    if time.second() == 0 {
+        time.format("%H:%M").to_string()
+    } else {
+        time.format("%H:%M:%S").to_string()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -995,4 +1212,46 @@ mod tests {
             }
         }
     }
+
+    fn route_station_names(graph: &RailwayGraph, line: &Line) -> Vec<String> {
+        line.forward_route.iter()
+            .filter_map(|seg| graph.get_track_endpoints(EdgeIndex::new(seg.edge_index)))
+            .flat_map(|(from, to)| [from, to])
+            .filter_map(|node| graph.get_station_name(node).map(str::to_string))
+            .collect()
+    }
+
+    #[test]
+    fn test_export_round_trip_preserves_stations_and_routes() {
+        let xml_content = std::fs::read_to_string("test-data/dortmund.fpl")
+            .expect("Failed to read dortmund.fpl");
+        let timetable = parse_jtraingraph(&xml_content).expect("Failed to parse dortmund.fpl");
+        let mut graph = RailwayGraph::new();
+        let lines = import_jtraingraph(&timetable, &mut graph, 0, &[], crate::models::TrackHandedness::RightHand)
+            .expect("Import should succeed");
+
+        let exported = export_jtraingraph(&graph, &lines, &[], None);
+        let reparsed = parse_jtraingraph(&exported).expect("Exported XML should parse");
+        let mut reimported_graph = RailwayGraph::new();
+        let reimported_lines = import_jtraingraph(&reparsed, &mut reimported_graph, 0, &[], crate::models::TrackHandedness::RightHand)
+            .expect("Re-import should succeed");
+
+        assert_eq!(graph.get_all_station_names(), reimported_graph.get_all_station_names());
+
+        let original_routes: std::collections::HashSet<_> = lines.iter()
+            .map(|line| route_station_names(&graph, line))
+            .collect();
+        let reimported_routes: std::collections::HashSet<_> = reimported_lines.iter()
+            .map(|line| route_station_names(&reimported_graph, line))
+            .collect();
+        assert_eq!(original_routes, reimported_routes);
+
+        // Track distances survive via the km posts
+        let original_distances: Vec<_> = graph.graph.edge_weights().map(|t| t.distance).collect();
+        let reimported_distances: Vec<_> = reimported_graph.graph.edge_weights().map(|t| t.distance).collect();
+        assert_eq!(original_distances, reimported_distances);
+
+        let departures = |lines: &[Line]| lines.iter().map(|l| l.manual_departures.len()).sum::<usize>();
+        assert_eq!(departures(&lines), departures(&reimported_lines));
+    }
 }
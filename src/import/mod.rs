@@ -1,8 +1,12 @@
 pub mod csv;
+pub mod gtfs;
 pub mod jtraingraph;
+pub mod railml;
 pub mod shared;
 
 // Re-export commonly used items
 pub use csv::{CsvImportConfig, ColumnType, ColumnMapping};
+pub use gtfs::parse_gtfs;
 pub use jtraingraph::import_jtraingraph;
+pub use railml::parse_railml;
 pub use shared::{create_tracks_with_count, ensure_platforms_up_to, get_or_add_platform};
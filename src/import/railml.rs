@@ -0,0 +1,463 @@
+//! Import of railML 2.x infrastructure and timetables
+//!
+//! Supported subset:
+//! - `infrastructure/operationControlPoints/ocp` become stations, named by `@name` (falling back to `@id`)
+//! - `infrastructure/tracks/track` become track segments between the ocps referenced by
+//!   `trackTopology/trackBegin/macroscopicNode` and `trackEnd/macroscopicNode`.
+//!   Parallel tracks between the same ocps are merged into one multi-track segment, and the
+//!   distance is taken from the `@pos` difference of `trackBegin`/`trackEnd` (metres)
+//! - `timetable/trainParts/trainPart/ocpsTT/ocpTT` become manual departures. Train parts with
+//!   the same ocp sequence and timings are grouped into one line. Scheduled `times` are preferred
+//!
+//! Not supported: microscopic topology (connections and switches), railML 3, operating periods
+//! (all trains run every day), platforms, formations and rolling stock.
+
+use serde::Deserialize;
+use crate::models::{RailwayGraph, Line, LineStyle, RouteSegment, ManualDeparture, ScheduleMode, DaysOfWeek, Stations, Tracks, Track, TrackDirection, RouteDirection, generate_random_color};
+use crate::constants::BASE_DATE;
+use chrono::{Duration, NaiveTime, Timelike};
+use petgraph::stable_graph::{NodeIndex, EdgeIndex};
+use std::collections::HashMap;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename = "railml")]
+pub struct RailMlDocument {
+    #[serde(rename = "@version", default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub infrastructure: Option<RailMlInfrastructure>,
+    #[serde(default)]
+    pub timetable: Option<RailMlTimetable>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RailMlInfrastructure {
+    #[serde(default)]
+    pub tracks: Option<RailMlTracks>,
+    #[serde(rename = "operationControlPoints", default)]
+    pub operation_control_points: Option<RailMlOcps>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RailMlTracks {
+    #[serde(rename = "track", default)]
+    pub tracks: Vec<RailMlTrack>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RailMlTrack {
+    #[serde(rename = "@id")]
+    pub id: String,
+    #[serde(rename = "trackTopology")]
+    pub topology: RailMlTrackTopology,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RailMlTrackTopology {
+    #[serde(rename = "trackBegin")]
+    pub begin: RailMlTrackNode,
+    #[serde(rename = "trackEnd")]
+    pub end: RailMlTrackNode,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RailMlTrackNode {
+    #[serde(rename = "@pos", default)]
+    pub pos: Option<f64>,
+    #[serde(rename = "macroscopicNode", default)]
+    pub macroscopic_node: Option<RailMlMacroscopicNode>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RailMlMacroscopicNode {
+    #[serde(rename = "@ocpRef")]
+    pub ocp_ref: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RailMlOcps {
+    #[serde(rename = "ocp", default)]
+    pub ocps: Vec<RailMlOcp>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RailMlOcp {
+    #[serde(rename = "@id")]
+    pub id: String,
+    #[serde(rename = "@name", default)]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RailMlTimetable {
+    #[serde(rename = "trainParts", default)]
+    pub train_parts: Option<RailMlTrainParts>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RailMlTrainParts {
+    #[serde(rename = "trainPart", default)]
+    pub train_parts: Vec<RailMlTrainPart>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RailMlTrainPart {
+    #[serde(rename = "@id")]
+    pub id: String,
+    #[serde(rename = "@trainNumber", default)]
+    pub train_number: Option<String>,
+    #[serde(rename = "ocpsTT", default)]
+    pub ocps_tt: Option<RailMlOcpsTT>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RailMlOcpsTT {
+    #[serde(rename = "ocpTT", default)]
+    pub stops: Vec<RailMlOcpTT>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RailMlOcpTT {
+    #[serde(rename = "@ocpRef")]
+    pub ocp_ref: String,
+    #[serde(rename = "times", default)]
+    pub times: Vec<RailMlTimes>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RailMlTimes {
+    #[serde(rename = "@scope", default)]
+    pub scope: Option<String>,
+    #[serde(rename = "@arrival", default)]
+    pub arrival: Option<String>,
+    #[serde(rename = "@departure", default)]
+    pub departure: Option<String>,
+}
+
+impl RailMlOcpTT {
+    /// Arrival and departure, preferring scheduled times; a missing one mirrors the other
+    fn arrival_departure(&self) -> Option<(NaiveTime, NaiveTime)> {
+        let times = self.times.iter()
+            .find(|t| t.scope.as_deref() == Some("scheduled"))
+            .or_else(|| self.times.first())?;
+        let parse = |time: Option<&String>| time.and_then(|t| super::shared::parse_time(t));
+        let arrival = parse(times.arrival.as_ref());
+        let departure = parse(times.departure.as_ref());
+        arrival.or(departure).zip(departure.or(arrival))
+    }
+}
+
+/// Timing of one train part: ocp sequence, run times and dwell times in seconds
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct TrainPattern {
+    nodes: Vec<NodeIndex>,
+    run_times: Vec<i64>,
+    dwell_times: Vec<i64>,
+}
+
+/// Parse a railML 2.x document into a new graph and the lines running on it
+///
+/// # Errors
+/// Returns an error if the XML is malformed, uses an unsupported railML construct,
+/// or references unknown ocps
+pub fn parse_railml(xml: &str) -> Result<(RailwayGraph, Vec<Line>), String> {
+    let document = parse_railml_document(xml)?;
+    let mut graph = RailwayGraph::new();
+    let lines = import_railml(&document, &mut graph, 0, &[])?;
+    Ok((graph, lines))
+}
+
+/// Deserialize a railML document, rejecting versions other than 2.x
+///
+/// # Errors
+/// Returns an error if the XML is malformed or declares an unsupported version
+pub fn parse_railml_document(xml: &str) -> Result<RailMlDocument, String> {
+    let document: RailMlDocument = quick_xml::de::from_str(xml)
+        .map_err(|e| format!("Failed to parse railML: {e}"))?;
+
+    if let Some(version) = document.version.as_deref() {
+        if !version.starts_with('2') {
+            return Err(format!("railML version {version} is not supported, only railML 2.x"));
+        }
+    }
+    Ok(document)
+}
+
+/// Import railML infrastructure into `graph` and return the lines to add
+/// Stations are matched to existing ones by name
+///
+/// # Errors
+/// Returns an error for unsupported topology, unknown ocp references or
+/// train parts running between ocps without a connecting track
+pub fn import_railml(
+    document: &RailMlDocument,
+    graph: &mut RailwayGraph,
+    starting_line_count: usize,
+    existing_line_names: &[String],
+) -> Result<Vec<Line>, String> {
+    let infrastructure = document.infrastructure.as_ref();
+    let ocps = infrastructure
+        .and_then(|i| i.operation_control_points.as_ref())
+        .map_or(&[][..], |o| &o.ocps[..]);
+    let tracks = infrastructure
+        .and_then(|i| i.tracks.as_ref())
+        .map_or(&[][..], |t| &t.tracks[..]);
+
+    let ocp_nodes: HashMap<&str, NodeIndex> = ocps.iter()
+        .map(|ocp| {
+            let name = ocp.name.clone().filter(|n| !n.is_empty()).unwrap_or_else(|| ocp.id.clone());
+            (ocp.id.as_str(), graph.add_or_get_station(name))
+        })
+        .collect();
+
+    let edge_map = import_tracks(tracks, &ocp_nodes, graph)?;
+
+    let train_parts = document.timetable.as_ref()
+        .and_then(|t| t.train_parts.as_ref())
+        .map_or(&[][..], |p| &p.train_parts[..]);
+    let mut groups: Vec<(TrainPattern, Vec<(&RailMlTrainPart, NaiveTime)>)> = Vec::new();
+    for train_part in train_parts {
+        let Some((pattern, departure)) = train_pattern(train_part, &ocp_nodes)? else {
+            continue;
+        };
+        match groups.iter_mut().find(|(existing, _)| *existing == pattern) {
+            Some((_, members)) => members.push((train_part, departure)),
+            None => groups.push((pattern, vec![(train_part, departure)])),
+        }
+    }
+
+    groups.iter().enumerate()
+        .map(|(index, (pattern, members))| {
+            let name = line_name(graph, pattern, index, existing_line_names);
+            create_line(graph, &edge_map, pattern, members, name, starting_line_count + index)
+        })
+        .collect()
+}
+
+/// Create track segments, merging parallel railML tracks between the same ocps
+fn import_tracks(
+    tracks: &[RailMlTrack],
+    ocp_nodes: &HashMap<&str, NodeIndex>,
+    graph: &mut RailwayGraph,
+) -> Result<HashMap<(NodeIndex, NodeIndex), EdgeIndex>, String> {
+    let mut edge_map: HashMap<(NodeIndex, NodeIndex), EdgeIndex> = HashMap::new();
+
+    for track in tracks {
+        let endpoint = |node: &RailMlTrackNode, end: &str| -> Result<NodeIndex, String> {
+            let ocp_ref = node.macroscopic_node.as_ref()
+                .map(|m| m.ocp_ref.as_str())
+                .ok_or_else(|| format!(
+                    "Track '{}' has no macroscopicNode at its {end}; only macroscopic topology is supported",
+                    track.id
+                ))?;
+            ocp_nodes.get(ocp_ref).copied()
+                .ok_or_else(|| format!("Track '{}' references unknown ocp '{ocp_ref}'", track.id))
+        };
+        let from = endpoint(&track.topology.begin, "trackBegin")?;
+        let to = endpoint(&track.topology.end, "trackEnd")?;
+        if from == to {
+            return Err(format!("Track '{}' begins and ends at the same ocp", track.id));
+        }
+
+        let key = (from.min(to), from.max(to));
+        if let Some(&edge) = edge_map.get(&key) {
+            graph.graph[edge].tracks.push(Track { direction: TrackDirection::Bidirectional });
+            continue;
+        }
+
+        let edge = graph.add_track(key.0, key.1, vec![Track { direction: TrackDirection::Bidirectional }]);
+        if let (Some(begin), Some(end)) = (track.topology.begin.pos, track.topology.end.pos) {
+            // Positions are in metres; keep metre precision
+            graph.graph[edge].distance = Some(((end - begin).abs()).round() / 1000.0);
+        }
+        edge_map.insert(key, edge);
+    }
+
+    Ok(edge_map)
+}
+
+/// Extract the pattern and first departure of a train part, or `None` if it has fewer than two timed stops
+fn train_pattern(
+    train_part: &RailMlTrainPart,
+    ocp_nodes: &HashMap<&str, NodeIndex>,
+) -> Result<Option<(TrainPattern, NaiveTime)>, String> {
+    let stops = train_part.ocps_tt.as_ref().map_or(&[][..], |o| &o.stops[..]);
+    let mut nodes = Vec::new();
+    let mut times = Vec::new();
+    for stop in stops {
+        let node = ocp_nodes.get(stop.ocp_ref.as_str()).copied()
+            .ok_or_else(|| format!("Train part '{}' references unknown ocp '{}'", train_part.id, stop.ocp_ref))?;
+        let Some(stop_times) = stop.arrival_departure() else {
+            return Err(format!("Train part '{}' has no times at ocp '{}'", train_part.id, stop.ocp_ref));
+        };
+        nodes.push(node);
+        times.push(stop_times);
+    }
+
+    if nodes.len() < 2 {
+        return Ok(None);
+    }
+
+    let seconds = |time: NaiveTime| i64::from(time.num_seconds_from_midnight());
+    let run_times = times.windows(2)
+        .map(|pair| super::shared::calculate_duration_with_wraparound(seconds(pair[0].1), seconds(pair[1].0)))
+        .collect();
+    let dwell_times = times.iter()
+        .map(|&(arrival, departure)| super::shared::calculate_duration_with_wraparound(seconds(arrival), seconds(departure)))
+        .collect();
+
+    Ok(Some((TrainPattern { nodes, run_times, dwell_times }, times[0].1)))
+}
+
+fn line_name(graph: &RailwayGraph, pattern: &TrainPattern, index: usize, existing_line_names: &[String]) -> String {
+    let station_name = |node: Option<&NodeIndex>| {
+        node.and_then(|&n| graph.get_station_name(n)).unwrap_or("?").to_string()
+    };
+    let name = format!("{} - {}", station_name(pattern.nodes.first()), station_name(pattern.nodes.last()));
+    if existing_line_names.contains(&name) {
+        format!("{name} ({index})")
+    } else {
+        name
+    }
+}
+
+fn create_line(
+    graph: &RailwayGraph,
+    edge_map: &HashMap<(NodeIndex, NodeIndex), EdgeIndex>,
+    pattern: &TrainPattern,
+    members: &[(&RailMlTrainPart, NaiveTime)],
+    name: String,
+    color_seed: usize,
+) -> Result<Line, String> {
+    let forward_route = pattern.nodes.windows(2).enumerate()
+        .map(|(i, pair)| {
+            let (from, to) = (pair[0], pair[1]);
+            let edge = edge_map.get(&(from.min(to), from.max(to))).copied()
+                .ok_or_else(|| format!(
+                    "No track connects '{}' and '{}'",
+                    graph.get_station_name(from).unwrap_or("?"),
+                    graph.get_station_name(to).unwrap_or("?")
+                ))?;
+            let traveling_backward = graph.get_track_endpoints(edge).is_some_and(|(source, _)| source != from);
+            Ok(RouteSegment {
+                edge_index: edge.index(),
+                track_index: graph.select_track_for_direction(edge, traveling_backward),
+                origin_platform: 0,
+                destination_platform: 0,
+                duration: Some(Duration::seconds(pattern.run_times[i])),
+                wait_time: Duration::seconds(pattern.dwell_times[i + 1]),
+                reverses: false,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let (first_node, last_node) = (pattern.nodes[0], pattern.nodes[pattern.nodes.len() - 1]);
+    let manual_departures: Vec<ManualDeparture> = members.iter()
+        .map(|(train_part, departure)| ManualDeparture {
+            id: uuid::Uuid::new_v4(),
+            time: BASE_DATE.and_time(*departure),
+            from_station: first_node,
+            to_station: last_node,
+            days_of_week: DaysOfWeek::ALL_DAYS,
+            train_number: Some(train_part.train_number.clone().unwrap_or_else(|| train_part.id.clone())),
+            repeat_interval: None,
+            repeat_until: None,
+            direction: RouteDirection::Forward,
+        })
+        .collect();
+
+    Ok(Line {
+        id: uuid::Uuid::new_v4(),
+        name,
+        frequency: Duration::hours(1),
+        color: generate_random_color(color_seed),
+        thickness: 2.0,
+        first_departure: manual_departures[0].time,
+        return_first_departure: BASE_DATE.and_hms_opt(0, 0, 0).ok_or_else(|| "Invalid return departure time".to_string())?,
+        visible: true,
+        schedule_mode: ScheduleMode::Manual,
+        days_of_week: DaysOfWeek::ALL_DAYS,
+        manual_departures,
+        forward_route,
+        return_route: Vec::new(),
+        sync_routes: false,
+        auto_train_number_format: "{line} {seq:04}".to_string(),
+        last_departure: BASE_DATE.and_hms_opt(23, 59, 0).ok_or_else(|| "Invalid last departure time".to_string())?,
+        return_last_departure: BASE_DATE.and_hms_opt(23, 59, 0).ok_or_else(|| "Invalid last departure time".to_string())?,
+        default_wait_time: Duration::seconds(30),
+        first_stop_wait_time: Duration::zero(),
+        return_first_stop_wait_time: Duration::zero(),
+        sort_index: None,
+        sync_departure_offsets: false,
+        folder_id: None,
+        code: String::new(),
+        style: LineStyle::default(),
+        forward_turnaround: false,
+        return_turnaround: false,
+        frequency_bands: Vec::new(),
+        skip_pattern: None,
+        skip_pattern_on_even: false,
+        rolling_stock: None,
+        clock_face_snap: None,
+        section_order_override: None,
+        valid_from: None,
+        valid_to: None,
+        service_pattern_id: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sample_railml() {
+        let xml = std::fs::read_to_string("test-data/sample.railml")
+            .expect("Failed to read sample.railml");
+
+        let (graph, lines) = parse_railml(&xml).expect("sample should import");
+
+        assert_eq!(graph.graph.node_count(), 4);
+        assert_eq!(graph.graph.edge_count(), 3);
+        // Two identical up trains share a line; the down train and the short working get their own
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0].name, "Alpha - Delta");
+        assert_eq!(lines[0].manual_departures.len(), 2);
+        assert_eq!(lines[0].forward_route.len(), 3);
+        assert_eq!(lines[0].forward_route[0].duration, Some(Duration::minutes(5)));
+        assert_eq!(lines[0].forward_route[0].wait_time, Duration::minutes(1));
+
+        let double_track = graph.get_station_index("Beta")
+            .zip(graph.get_station_index("Gamma"))
+            .and_then(|(b, c)| graph.graph.find_edge(b, c))
+            .expect("Beta - Gamma edge");
+        assert_eq!(graph.graph[double_track].tracks.len(), 2);
+        assert_eq!(graph.graph[double_track].distance, Some(4.2));
+    }
+
+    #[test]
+    fn test_microscopic_topology_is_rejected() {
+        let xml = r#"<railml version="2.4">
+            <infrastructure>
+                <tracks>
+                    <track id="tr1">
+                        <trackTopology>
+                            <trackBegin pos="0"><connection id="c1" ref="c2"/></trackBegin>
+                            <trackEnd pos="100"><macroscopicNode ocpRef="a"/></trackEnd>
+                        </trackTopology>
+                    </track>
+                </tracks>
+                <operationControlPoints><ocp id="a" name="A"/></operationControlPoints>
+            </infrastructure>
+        </railml>"#;
+
+        let error = parse_railml(xml).expect_err("connections are unsupported");
+        assert!(error.contains("macroscopic"), "unexpected error: {error}");
+    }
+
+    #[test]
+    fn test_railml_3_is_rejected() {
+        let error = parse_railml(r#"<railml version="3.1"></railml>"#).expect_err("railML 3 is unsupported");
+        assert!(error.contains("3.1"), "unexpected error: {error}");
+    }
+}
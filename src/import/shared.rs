@@ -1,4 +1,4 @@
-use crate::models::{RailwayGraph, Track, TrackDirection, Platform, TrackHandedness};
+use crate::models::{RailwayGraph, Track, TrackDirection, Platform, TrackHandedness, Line, RouteSegment, DEFAULT_PLATFORM_CAPACITY};
 use petgraph::stable_graph::{NodeIndex, EdgeIndex};
 use chrono::NaiveTime;
 
@@ -68,6 +68,7 @@ pub fn ensure_platforms_up_to(graph: &mut RailwayGraph, station_idx: NodeIndex,
         let next_number = station_node.platforms.len() + 1;
         station_node.platforms.push(Platform {
             name: next_number.to_string(),
+            capacity: DEFAULT_PLATFORM_CAPACITY,
         });
     }
 
@@ -94,6 +95,7 @@ pub fn get_or_add_platform(graph: &mut RailwayGraph, station_idx: NodeIndex, pla
         // Add new platform
         station_node.platforms.push(Platform {
             name: platform_name.to_string(),
+            capacity: DEFAULT_PLATFORM_CAPACITY,
         });
         station_node.platforms.len() - 1
     } else {
@@ -196,3 +198,38 @@ pub fn find_connected_junction(
                 .map(|_| neighbor)
         })
 }
+
+/// Node shared by two consecutive route edges, if any
+fn shared_node(graph: &RailwayGraph, first: EdgeIndex, second: EdgeIndex) -> Option<NodeIndex> {
+    use crate::models::Tracks;
+
+    let (a1, a2) = graph.get_track_endpoints(first)?;
+    let (b1, b2) = graph.get_track_endpoints(second)?;
+    [a1, a2].into_iter().find(|&node| node == b1 || node == b2)
+}
+
+fn add_route_through_rules(graph: &mut RailwayGraph, route: &[RouteSegment]) {
+    use crate::models::Junctions;
+
+    for pair in route.windows(2) {
+        let from_edge = EdgeIndex::new(pair[0].edge_index);
+        let to_edge = EdgeIndex::new(pair[1].edge_index);
+        let Some(node) = shared_node(graph, from_edge, to_edge) else { continue };
+        let Some(junction) = graph.get_junction_mut(node) else { continue };
+
+        let has_rule = junction.routing_rules.iter()
+            .any(|rule| rule.from_edge == from_edge && rule.to_edge == to_edge);
+        if !has_rule {
+            junction.set_routing_rule(from_edge, to_edge, true);
+        }
+    }
+}
+
+/// Allow the through routes imported lines take at junctions, so they are drawn with the right curves.
+/// Rules that already exist are left as they are.
+pub fn add_junction_through_rules(graph: &mut RailwayGraph, lines: &[Line]) {
+    for line in lines {
+        add_route_through_rules(graph, &line.forward_route);
+        add_route_through_rules(graph, &line.return_route);
+    }
+}
@@ -126,6 +126,27 @@ impl DaysOfWeek {
             result
         }
     }
+
+    /// Move every day forward by `days` (backward when negative), wrapping Sunday to Monday
+    #[must_use]
+    pub fn rotated(self, days: i64) -> Self {
+        let offset = usize::try_from(days.rem_euclid(7)).unwrap_or(0);
+        (0..7)
+            .filter(|&i| Self::from_index(i).is_some_and(|day| self.contains(day)))
+            .filter_map(|i| Self::from_index((i + offset) % 7))
+            .fold(Self::empty(), |result, day| result | day)
+    }
+
+    /// Format as `JTrainGraph` days string (7 characters of 1s and 0s, Mon-Sun)
+    #[must_use]
+    pub fn to_jtraingraph_format(self) -> String {
+        (0..7)
+            .map(|i| match Self::from_index(i) {
+                Some(day) if self.contains(day) => '1',
+                _ => '0',
+            })
+            .collect()
+    }
 }
 
 // Custom serialization to store as u8
@@ -259,4 +280,21 @@ mod tests {
         let days = DaysOfWeek::from_jtraingraph_format("0000000");
         assert!(days.is_all_days());
     }
+
+    #[test]
+    fn test_to_jtraingraph_format_round_trip() {
+        assert_eq!(DaysOfWeek::ALL_DAYS.to_jtraingraph_format(), "1111111");
+        assert_eq!(DaysOfWeek::WEEKDAYS.to_jtraingraph_format(), "1111100");
+
+        let days = DaysOfWeek::MONDAY | DaysOfWeek::FRIDAY | DaysOfWeek::SUNDAY;
+        assert_eq!(DaysOfWeek::from_jtraingraph_format(&days.to_jtraingraph_format()), days);
+    }
+
+    #[test]
+    fn test_rotated_wraps_around_week() {
+        let days = DaysOfWeek::FRIDAY | DaysOfWeek::SUNDAY;
+        assert_eq!(days.rotated(1), DaysOfWeek::SATURDAY | DaysOfWeek::MONDAY);
+        assert_eq!(days.rotated(-1), DaysOfWeek::THURSDAY | DaysOfWeek::SATURDAY);
+        assert_eq!(DaysOfWeek::ALL_DAYS.rotated(3), DaysOfWeek::ALL_DAYS);
+    }
 }
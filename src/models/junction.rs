@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
-use petgraph::stable_graph::EdgeIndex;
+use petgraph::stable_graph::{EdgeIndex, NodeIndex};
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use super::railway_graph::RailwayGraph;
 use crate::components::infrastructure_canvas::station_renderer::LabelPosition;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -59,6 +62,18 @@ impl Junction {
         });
     }
 
+    /// Drop rules whose edges are no longer connected to this junction, e.g. after a track was deleted
+    pub fn prune_invalid_rules(&mut self, graph: &RailwayGraph, junction_idx: NodeIndex) {
+        let incident: Vec<EdgeIndex> = graph.graph.edges_directed(junction_idx, Direction::Outgoing)
+            .chain(graph.graph.edges_directed(junction_idx, Direction::Incoming))
+            .map(|edge| edge.id())
+            .collect();
+
+        self.routing_rules.retain(|rule| {
+            incident.contains(&rule.from_edge) && incident.contains(&rule.to_edge)
+        });
+    }
+
     /// Get all allowed outgoing edges from a given incoming edge
     #[must_use]
     pub fn get_allowed_outgoing_edges(&self, from_edge: EdgeIndex, all_edges: &[EdgeIndex]) -> Vec<EdgeIndex> {
@@ -190,6 +205,36 @@ mod tests {
         assert_eq!(junction.routing_rules.len(), 2);
     }
 
+    #[test]
+    fn test_prune_invalid_rules_after_edge_deleted() {
+        use crate::models::{Junctions, Stations, Track, TrackDirection, Tracks};
+
+        let mut graph = RailwayGraph::new();
+        let a = graph.add_or_get_station("A".to_string());
+        let b = graph.add_or_get_station("B".to_string());
+        let c = graph.add_or_get_station("C".to_string());
+        let junction_idx = graph.add_junction(Junction {
+            name: None,
+            position: None,
+            routing_rules: vec![],
+            label_position: None,
+        });
+        let track = || vec![Track { direction: TrackDirection::Bidirectional }];
+        let to_a = graph.add_track(a, junction_idx, track());
+        let to_b = graph.add_track(junction_idx, b, track());
+        let to_c = graph.add_track(junction_idx, c, track());
+
+        let mut junction = graph.get_junction(junction_idx).cloned().expect("junction exists");
+        junction.set_routing_rule(to_a, to_b, false);
+        junction.set_routing_rule(to_a, to_c, true);
+
+        graph.graph.remove_edge(to_c);
+        junction.prune_invalid_rules(&graph, junction_idx);
+
+        assert_eq!(junction.routing_rules.len(), 1);
+        assert_eq!(junction.routing_rules[0].to_edge, to_b);
+    }
+
     #[test]
     fn test_remove_routing_rule() {
         let mut junction = Junction {
@@ -3,6 +3,20 @@ use std::collections::HashMap;
 use leptos::SignalGet;
 use wasm_bindgen::JsCast;
 
+/// Shortcuts toggling the visibility of the first nine lines in sidebar order,
+/// as (id, description, default key code). The position is the line's index.
+pub const TOGGLE_LINE_SHORTCUTS: [(&str, &str, &str); 9] = [
+    ("toggle_line_1", "Toggle Line 1", "Digit1"),
+    ("toggle_line_2", "Toggle Line 2", "Digit2"),
+    ("toggle_line_3", "Toggle Line 3", "Digit3"),
+    ("toggle_line_4", "Toggle Line 4", "Digit4"),
+    ("toggle_line_5", "Toggle Line 5", "Digit5"),
+    ("toggle_line_6", "Toggle Line 6", "Digit6"),
+    ("toggle_line_7", "Toggle Line 7", "Digit7"),
+    ("toggle_line_8", "Toggle Line 8", "Digit8"),
+    ("toggle_line_9", "Toggle Line 9", "Digit9"),
+];
+
 /// Keyboard shortcut definition
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[allow(clippy::struct_excessive_bools)]
@@ -39,6 +53,12 @@ impl KeyboardShortcut {
         Self::new(code.to_string(), false, false, false, false)
     }
 
+    /// Create a shortcut with Alt + key
+    #[must_use]
+    pub fn alt(code: &str) -> Self {
+        Self::new(code.to_string(), false, false, true, false)
+    }
+
     /// Create a shortcut with Ctrl + Shift + key
     #[must_use]
     pub fn ctrl_shift(code: &str) -> Self {
@@ -177,6 +197,7 @@ impl KeyboardShortcut {
 pub enum ShortcutCategory {
     Navigation,
     Infrastructure,
+    Lines,
     Project,
 }
 
@@ -186,6 +207,7 @@ impl ShortcutCategory {
         match self {
             ShortcutCategory::Navigation => "Navigation",
             ShortcutCategory::Infrastructure => "Infrastructure",
+            ShortcutCategory::Lines => "Lines",
             ShortcutCategory::Project => "Project",
         }
     }
@@ -236,18 +258,22 @@ pub fn is_input_field_target(ev: &web_sys::KeyboardEvent) -> bool {
     tag_name == "input" || tag_name == "textarea"
 }
 
+/// Get all shortcut definitions for the current platform
+fn get_all_shortcut_definitions() -> Vec<ShortcutEntry> {
+    shortcut_definitions(is_mac_platform())
+}
+
 /// Get all shortcut definitions
 /// TO ADD A NEW SHORTCUT: Just add one entry to this function!
 #[allow(clippy::too_many_lines)]
-fn get_all_shortcut_definitions() -> Vec<ShortcutEntry> {
-    let is_mac = is_mac_platform();
+fn shortcut_definitions(is_mac: bool) -> Vec<ShortcutEntry> {
     let primary_shift = if is_mac {
         KeyboardShortcut::meta_shift
     } else {
         KeyboardShortcut::ctrl_shift
     };
 
-    vec![
+    let mut definitions = vec![
         // Navigation
         ShortcutEntry {
             id: "pan_up",
@@ -309,6 +335,18 @@ fn get_all_shortcut_definitions() -> Vec<ShortcutEntry> {
             category: ShortcutCategory::Navigation,
             default_shortcut: KeyboardShortcut::key_only("KeyR"),
         },
+        ShortcutEntry {
+            id: "next_conflict",
+            description: "Next Conflict",
+            category: ShortcutCategory::Navigation,
+            default_shortcut: KeyboardShortcut::key_only("KeyN"),
+        },
+        ShortcutEntry {
+            id: "prev_conflict",
+            description: "Previous Conflict",
+            category: ShortcutCategory::Navigation,
+            default_shortcut: KeyboardShortcut::key_only("KeyP"),
+        },
         // Infrastructure
         ShortcutEntry {
             id: "add_station",
@@ -383,6 +421,26 @@ fn get_all_shortcut_definitions() -> Vec<ShortcutEntry> {
             category: ShortcutCategory::Infrastructure,
             default_shortcut: KeyboardShortcut::none(),
         },
+        ShortcutEntry {
+            id: "multi_select_copy",
+            description: "Copy Selected Stations",
+            category: ShortcutCategory::Infrastructure,
+            default_shortcut: if is_mac {
+                KeyboardShortcut::new("KeyC".to_string(), false, false, false, true)
+            } else {
+                KeyboardShortcut::new("KeyC".to_string(), true, false, false, false)
+            },
+        },
+        ShortcutEntry {
+            id: "paste_stations",
+            description: "Paste Stations",
+            category: ShortcutCategory::Infrastructure,
+            default_shortcut: if is_mac {
+                KeyboardShortcut::new("KeyV".to_string(), false, false, false, true)
+            } else {
+                KeyboardShortcut::new("KeyV".to_string(), true, false, false, false)
+            },
+        },
         // Project
         ShortcutEntry {
             id: "manage_projects",
@@ -428,7 +486,23 @@ fn get_all_shortcut_definitions() -> Vec<ShortcutEntry> {
                 KeyboardShortcut::new("KeyY".to_string(), true, false, false, false)
             },
         },
-    ]
+        // Lines
+        ShortcutEntry {
+            id: "solo_line",
+            description: "Show Only Selected Lines",
+            category: ShortcutCategory::Lines,
+            default_shortcut: KeyboardShortcut::alt("Digit0"),
+        },
+    ];
+
+    definitions.extend(TOGGLE_LINE_SHORTCUTS.iter().map(|&(id, description, code)| ShortcutEntry {
+        id,
+        description,
+        category: ShortcutCategory::Lines,
+        default_shortcut: KeyboardShortcut::alt(code),
+    }));
+
+    definitions
 }
 
 /// Shortcut metadata (description and category)
@@ -667,3 +741,22 @@ pub fn setup_single_shortcut_handler<F>(
         handler(&ev);
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_default_shortcuts_are_unique() {
+        for is_mac in [false, true] {
+            let mut seen = HashSet::new();
+            for entry in shortcut_definitions(is_mac) {
+                if entry.default_shortcut.code.is_empty() {
+                    continue;
+                }
+                assert!(seen.insert(entry.default_shortcut.clone()), "{} reuses {:?}", entry.id, entry.default_shortcut);
+            }
+        }
+    }
+}
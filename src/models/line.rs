@@ -1,8 +1,29 @@
-use chrono::{Duration, NaiveDateTime};
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 use crate::constants::{BASE_DATE, BASE_MIDNIGHT};
 use petgraph::stable_graph::NodeIndex;
-use super::{RailwayGraph, TrackSegment, TrackDirection, Tracks, DaysOfWeek, RouteDirection, TrackHandedness, Stations, Routes, StationPosition};
+use super::{RailwayGraph, TrackSegment, TrackDirection, Tracks, DaysOfWeek, RouteDirection, TrackHandedness, Routes, StationMerge, StationPosition, ServicePattern};
+
+/// Upper bound on departures materialized by a single generated series
+pub const MAX_GENERATED_DEPARTURES: usize = 500;
+
+const SECONDS_PER_HOUR: i64 = 3600;
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// How long a departing train is taken to keep holding its platform when spreading stops over platforms,
+/// matching the default minimum separation between platform occupants
+const PLATFORM_CLEARANCE: Duration = Duration::seconds(30);
+
+/// Time a train holds a platform, from arrival until it has cleared it after departing
+type OccupancyWindow = (NaiveDateTime, NaiveDateTime);
+
+/// Split a run time at `fraction`, giving the remainder to the second part so the two add back up
+#[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+fn split_duration(duration: Duration, fraction: f64) -> (Duration, Duration) {
+    let first = Duration::milliseconds((duration.num_milliseconds() as f64 * fraction).round() as i64);
+    (first, duration - first)
+}
 
 #[must_use]
 #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss, clippy::cast_possible_wrap)]
@@ -33,6 +54,81 @@ pub fn generate_random_color(seed: usize) -> String {
     )
 }
 
+/// Apply `color` to every line in `line_ids`, leaving other lines untouched
+pub fn recolor_lines(line_ids: &[uuid::Uuid], color: &str, lines: &mut [Line]) {
+    for line in lines.iter_mut().filter(|l| line_ids.contains(&l.id)) {
+        line.color = color.to_string();
+    }
+}
+
+/// Give each line in `line_ids` a distinct generated color, in selection order
+pub fn apply_palette(line_ids: &[uuid::Uuid], lines: &mut [Line]) {
+    for (position, id) in line_ids.iter().enumerate() {
+        if let Some(line) = lines.iter_mut().find(|l| l.id == *id) {
+            line.color = generate_random_color(position);
+        }
+    }
+}
+
+/// Trains per hour a line runs: set by its frequency on an auto schedule, or by the busiest hour
+/// of manual departures in either direction on a manual one
+#[must_use]
+pub fn line_capacity_tph(line: &Line) -> f64 {
+    match line.schedule_mode {
+        ScheduleMode::Auto => trains_per_hour(line.frequency),
+        // A day holds at most 86,400 departures, well within f64 precision
+        #[allow(clippy::cast_precision_loss)]
+        ScheduleMode::Manual => densest_manual_hour(line) as f64,
+    }
+}
+
+/// Highest trains per hour of an auto-scheduled line with frequency bands, counting the base frequency too
+#[must_use]
+pub fn peak_capacity_tph(line: &Line) -> Option<f64> {
+    if line.schedule_mode != ScheduleMode::Auto || line.frequency_bands.is_empty() {
+        return None;
+    }
+    let peak = line.frequency_bands.iter()
+        .map(|(_, _, frequency)| trains_per_hour(*frequency))
+        .fold(trains_per_hour(line.frequency), f64::max);
+    Some(peak)
+}
+
+fn trains_per_hour(frequency: Duration) -> f64 {
+    let seconds = frequency.num_seconds();
+    if seconds <= 0 {
+        return 0.0;
+    }
+    // Frequencies are far below 2^52 seconds
+    #[allow(clippy::cast_precision_loss)]
+    let tph = SECONDS_PER_HOUR as f64 / seconds as f64;
+    tph
+}
+
+/// Most manual departures leaving within an hour of each other on one day in one direction
+fn densest_manual_hour(line: &Line) -> usize {
+    let days = [
+        DaysOfWeek::MONDAY, DaysOfWeek::TUESDAY, DaysOfWeek::WEDNESDAY, DaysOfWeek::THURSDAY,
+        DaysOfWeek::FRIDAY, DaysOfWeek::SATURDAY, DaysOfWeek::SUNDAY,
+    ];
+    let mut densest = 0;
+    for day in days {
+        for direction in [RouteDirection::Forward, RouteDirection::Return] {
+            let mut times: Vec<i64> = line.manual_departures.iter()
+                .filter(|departure| departure.direction == direction && departure.days_of_week.contains(day))
+                .flat_map(ManualDeparture::seconds_of_day)
+                .collect();
+            times.sort_unstable();
+            // Count departures in the hour starting at each departure
+            for (start, &time) in times.iter().enumerate() {
+                let count = times[start..].partition_point(|&other| other < time + SECONDS_PER_HOUR);
+                densest = densest.max(count);
+            }
+        }
+    }
+    densest
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct RouteSegment {
     pub edge_index: usize,
@@ -46,6 +142,9 @@ pub struct RouteSegment {
     pub duration: Option<Duration>,
     #[serde(with = "duration_serde", default = "default_wait_time")]
     pub wait_time: Duration,
+    /// The train reverses at the end of this segment, leaving the station the way it came in
+    #[serde(default)]
+    pub reverses: bool,
 }
 
 fn default_wait_time() -> Duration {
@@ -64,6 +163,16 @@ pub enum LineStyle {
     CenterLined,
 }
 
+/// Train performance used to derive run times with acceleration and braking
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct RollingStockProfile {
+    pub max_speed_kph: f64,
+    /// Acceleration in m/s²
+    pub accel_ms2: f64,
+    /// Deceleration in m/s²
+    pub decel_ms2: f64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[derive(Default)]
 pub enum ScheduleMode {
@@ -91,6 +200,69 @@ pub struct ManualDeparture {
     pub repeat_interval: Option<Duration>,
     #[serde(with = "option_naive_datetime_serde", default)]
     pub repeat_until: Option<NaiveDateTime>,
+    #[serde(default)]
+    pub direction: RouteDirection,
+}
+
+/// Where a generated series of manual departures stops
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DepartureSeriesEnd {
+    /// Generate exactly this many departures
+    Count(usize),
+    /// Generate departures up to and including this time
+    Until(NaiveDateTime),
+}
+
+impl ManualDeparture {
+    /// Departure times of each train this departure runs when the first leaves at `first`.
+    /// Repeats stop at `repeat_until`, kept at the same offset from the departure so it can
+    /// fall on the next day, or otherwise at the end of the first train's day
+    pub fn repeat_times(&self, first: NaiveDateTime) -> impl Iterator<Item = NaiveDateTime> {
+        let interval = self.repeat_interval.filter(|interval| *interval > Duration::zero());
+        let end_of_day = first.date().and_time(NaiveTime::MIN) + Duration::seconds(SECONDS_PER_DAY - 1);
+        let until = match interval {
+            Some(_) => self.repeat_until.map_or(end_of_day, |until| first + (until - self.time)),
+            None => first,
+        };
+        std::iter::successors(Some(first), move |time| interval.map(|interval| *time + interval))
+            .take_while(move |time| *time <= until)
+    }
+
+    /// Seconds past midnight of each train this departure runs
+    fn seconds_of_day(&self) -> Vec<i64> {
+        let first = i64::from(self.time.time().num_seconds_from_midnight());
+        self.repeat_times(self.time)
+            .map(|time| first + (time - self.time).num_seconds())
+            .collect()
+    }
+
+    /// Individual copies of this departure every `interval`, starting at its own time.
+    /// Unlike `repeat_interval`, each copy is a separate editable entry: copies keep the
+    /// stations, direction and days but get a fresh id and no train number or repeat.
+    /// The series stops at midnight and at `MAX_GENERATED_DEPARTURES`; a non-positive
+    /// interval yields at most the first departure.
+    #[must_use]
+    pub fn series(&self, interval: Duration, end: DepartureSeriesEnd) -> Vec<ManualDeparture> {
+        let (count, until) = match end {
+            DepartureSeriesEnd::Count(count) => (count.min(MAX_GENERATED_DEPARTURES), None),
+            DepartureSeriesEnd::Until(until) => (MAX_GENERATED_DEPARTURES, Some(until)),
+        };
+        let count = if interval > Duration::zero() { count } else { count.min(1) };
+        let day_end = BASE_MIDNIGHT + Duration::days(1);
+
+        std::iter::successors(Some(self.time), |time| Some(*time + interval))
+            .take(count)
+            .take_while(|time| *time < day_end && until.is_none_or(|until| *time <= until))
+            .map(|time| ManualDeparture {
+                id: uuid::Uuid::new_v4(),
+                time,
+                train_number: None,
+                repeat_interval: None,
+                repeat_until: None,
+                ..self.clone()
+            })
+            .collect()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -148,6 +320,32 @@ pub struct Line {
     pub forward_turnaround: bool,
     #[serde(default)]
     pub return_turnaround: bool,
+    /// Time-of-day frequency overrides as (start, end, frequency), end exclusive
+    #[serde(with = "frequency_bands_serde", default)]
+    pub frequency_bands: Vec<(NaiveTime, NaiveTime, Duration)>,
+    /// Per forward-route stop, whether skip-stop journeys pass without stopping
+    #[serde(default)]
+    pub skip_pattern: Option<Vec<bool>>,
+    /// Apply the skip pattern to even-numbered departures instead of odd-numbered ones
+    #[serde(default)]
+    pub skip_pattern_on_even: bool,
+    #[serde(default)]
+    pub rolling_stock: Option<RollingStockProfile>,
+    /// Round forward departures to the nearest multiple of this interval past the hour
+    #[serde(with = "option_duration_serde", default)]
+    pub clock_face_snap: Option<Duration>,
+    /// Manual position among lines sharing a section; lower values are drawn further left
+    #[serde(default)]
+    pub section_order_override: Option<i32>,
+    /// First calendar date the line operates, checked against the project's reference week
+    #[serde(default)]
+    pub valid_from: Option<NaiveDate>,
+    /// Last calendar date the line operates, checked against the project's reference week
+    #[serde(default)]
+    pub valid_to: Option<NaiveDate>,
+    /// Shared service pattern deciding the operating days; overrides `days_of_week` when set
+    #[serde(default)]
+    pub service_pattern_id: Option<uuid::Uuid>,
 }
 
 fn default_visible() -> bool {
@@ -221,6 +419,15 @@ impl Line {
                     style: LineStyle::default(),
                     forward_turnaround: false,
                     return_turnaround: false,
+                    frequency_bands: Vec::new(),
+                    skip_pattern: None,
+                    skip_pattern_on_even: false,
+                    rolling_stock: None,
+                    clock_face_snap: None,
+                    section_order_override: None,
+                    valid_from: None,
+                    valid_to: None,
+                    service_pattern_id: None,
                 }
             })
             .collect()
@@ -238,6 +445,44 @@ impl Line {
         self.return_route = Self::update_single_route(&self.return_route, removed_edges, bypass_mapping);
     }
 
+    /// Update routes after `merge_stations` folded another station into `keep`.
+    /// Segments follow the edges that replaced theirs, the hop between the two stations is
+    /// dropped, and platforms at the removed station move to their counterparts at `keep`
+    pub fn update_route_after_merge(&mut self, merge: &StationMerge, keep: NodeIndex, graph: &RailwayGraph) {
+        Self::remap_merged_route(&mut self.forward_route, merge, keep, graph);
+        Self::remap_merged_route(&mut self.return_route, merge, keep, graph);
+        self.validate_and_fix_track_indices(graph);
+    }
+
+    fn remap_merged_route(route: &mut Vec<RouteSegment>, merge: &StationMerge, keep: NodeIndex, graph: &RailwayGraph) {
+        let mut moved = Vec::new();
+        let old_route = std::mem::take(route);
+        for mut segment in old_route {
+            match merge.edges.get(&segment.edge_index) {
+                None => {}
+                Some(None) => continue,
+                Some(Some(new_edge)) => {
+                    segment.edge_index = *new_edge;
+                    moved.push(route.len());
+                }
+            }
+            route.push(segment);
+        }
+
+        // A moved segment starts at the merged station if the previous one ended there
+        let route_nodes = crate::train_journey::TrainJourney::build_route_nodes(route, graph);
+        let merged_platform = |platform: usize| merge.platforms.get(platform).copied().unwrap_or(platform);
+        for i in moved {
+            let starts_at_keep = route_nodes.get(i).copied().flatten() == Some(keep);
+            let segment = &mut route[i];
+            if starts_at_keep {
+                segment.origin_platform = merged_platform(segment.origin_platform);
+            } else {
+                segment.destination_platform = merged_platform(segment.destination_platform);
+            }
+        }
+    }
+
     fn update_single_route(
         route: &[RouteSegment],
         removed_edges: &[usize],
@@ -279,6 +524,7 @@ impl Line {
                 destination_platform: next_segment.destination_platform,
                 duration: combined_duration,
                 wait_time: next_segment.wait_time,
+                reverses: false,
             });
 
             i += 2; // Skip both segments
@@ -540,13 +786,15 @@ impl Line {
     }
 
     /// Replace an edge that was split by a junction with two new edges
-    /// This is used when inserting a junction in the middle of an existing edge
-    pub fn replace_split_edge(&mut self, old_edge: usize, new_edge1: usize, new_edge2: usize, track_count: usize, graph: &RailwayGraph, middle_node_platform_count: usize, handedness: TrackHandedness) {
-        Self::replace_split_edge_in_route(&mut self.forward_route, old_edge, new_edge1, new_edge2, track_count, graph, middle_node_platform_count, handedness);
-        Self::replace_split_edge_in_route(&mut self.return_route, old_edge, new_edge2, new_edge1, track_count, graph, middle_node_platform_count, handedness);
+    /// This is used when inserting a junction in the middle of an existing edge.
+    /// `split_fraction` is the share of the old edge covered by `new_edge1`; run times are split in the same proportion
+    #[allow(clippy::too_many_arguments)]
+    pub fn replace_split_edge(&mut self, old_edge: usize, new_edge1: usize, new_edge2: usize, split_fraction: f64, track_count: usize, graph: &RailwayGraph, middle_node_platform_count: usize, handedness: TrackHandedness) {
+        Self::replace_split_edge_in_route(&mut self.forward_route, old_edge, (new_edge1, new_edge2), split_fraction, track_count, graph, middle_node_platform_count, handedness);
+        Self::replace_split_edge_in_route(&mut self.return_route, old_edge, (new_edge2, new_edge1), 1.0 - split_fraction, track_count, graph, middle_node_platform_count, handedness);
     }
 
-    fn replace_split_edge_in_route(route: &mut Vec<RouteSegment>, old_edge: usize, first_edge: usize, second_edge: usize, track_count: usize, graph: &RailwayGraph, middle_node_platform_count: usize, handedness: TrackHandedness) {
+    fn replace_split_edge_in_route(route: &mut Vec<RouteSegment>, old_edge: usize, (first_edge, second_edge): (usize, usize), first_fraction: f64, track_count: usize, graph: &RailwayGraph, middle_node_platform_count: usize, handedness: TrackHandedness) {
         use petgraph::prelude::*;
 
         let mut new_route = Vec::new();
@@ -573,21 +821,24 @@ impl Line {
                 );
 
                 // Split this segment into two through the junction
+                let durations = segment.duration.map(|d| split_duration(d, first_fraction));
                 new_route.push(RouteSegment {
                     edge_index: first_edge,
                     track_index: segment.track_index.min(track_count.saturating_sub(1)),
                     origin_platform: segment.origin_platform,
                     destination_platform: middle_platform_arriving,
-                    duration: segment.duration.map(|d| d / 2),
+                    duration: durations.map(|(first, _)| first),
                     wait_time: segment.wait_time,
+                    reverses: false,
                 });
                 new_route.push(RouteSegment {
                     edge_index: second_edge,
                     track_index: segment.track_index.min(track_count.saturating_sub(1)),
                     origin_platform: middle_platform_departing,
                     destination_platform: segment.destination_platform,
-                    duration: segment.duration.map(|d| d / 2),
+                    duration: durations.map(|(_, second)| second),
                     wait_time: Duration::zero(),
+                    reverses: false,
                 });
             } else {
                 new_route.push(segment.clone());
@@ -688,6 +939,7 @@ impl Line {
                     destination_platform: *destination_platform,
                     duration: None,
                     wait_time: *wait_time,
+                    reverses: self.return_segment_reverses(i),
                 });
             } else {
                 // This is a new edge not in the return route, use defaults from forward route
@@ -709,6 +961,7 @@ impl Line {
                     destination_platform: forward_seg.origin_platform,
                     duration: None,
                     wait_time,
+                    reverses: self.return_segment_reverses(i),
                 });
             }
         }
@@ -716,6 +969,122 @@ impl Line {
         self.return_route = new_return_route;
     }
 
+    /// Spread the line's stops over their stations' platforms so its own trains don't share one at the same time.
+    /// Stops are placed in route order, forward route first, on the platform where this line's already placed
+    /// stops overlap least with it in the generated journeys. Ties keep the handedness default.
+    /// Only the station's existing platforms are used; adding platforms is left to the station editor.
+    pub fn auto_assign_platforms(&mut self, graph: &RailwayGraph, handedness: TrackHandedness, service_patterns: &[ServicePattern], reference_week: Option<NaiveDate>) {
+        let occupancy = self.stop_occupancy(graph, service_patterns, reference_week);
+        let mut placed: HashMap<NodeIndex, Vec<(usize, &[OccupancyWindow])>> = HashMap::new();
+
+        for forward in [true, false] {
+            let route_nodes = crate::train_journey::TrainJourney::build_route_nodes(self.route(forward), graph);
+            for (stop, node) in route_nodes.into_iter().enumerate() {
+                let Some(node) = node else { continue };
+                let platform_count = graph.graph.node_weight(node)
+                    .and_then(super::Node::as_station)
+                    .map_or(0, |station| station.platforms.len());
+                let Some(windows) = occupancy.get(&(forward, stop)) else { continue };
+                if platform_count < 2 {
+                    continue;
+                }
+
+                let preferred = self.default_stop_platform(forward, stop, node, platform_count, graph, handedness);
+                let on_station = placed.entry(node).or_default();
+                let platform = (0..platform_count)
+                    .min_by_key(|&platform| {
+                        let overlap: i64 = on_station.iter()
+                            .filter(|(other_platform, _)| *other_platform == platform)
+                            .map(|(_, other)| overlap_secs(windows, other))
+                            .sum();
+                        (overlap, platform != preferred)
+                    })
+                    .unwrap_or(preferred);
+                on_station.push((platform, windows));
+
+                let route = if forward { &mut self.forward_route } else { &mut self.return_route };
+                if let Some(arrival) = stop.checked_sub(1).and_then(|i| route.get_mut(i)) {
+                    arrival.destination_platform = platform;
+                }
+                if let Some(departure) = route.get_mut(stop) {
+                    departure.origin_platform = platform;
+                }
+            }
+        }
+    }
+
+    fn route(&self, forward: bool) -> &[RouteSegment] {
+        if forward { &self.forward_route } else { &self.return_route }
+    }
+
+    /// Platform the handedness rules pick for a stop, from the edge the train arrives on (or leaves by at the origin)
+    fn default_stop_platform(&self, forward: bool, stop: usize, node: NodeIndex, platform_count: usize, graph: &RailwayGraph, handedness: TrackHandedness) -> usize {
+        let route = self.route(forward);
+        let segment = if stop == 0 { route.first() } else { route.get(stop - 1) };
+        segment.map_or(0, |segment| {
+            let edge = petgraph::graph::EdgeIndex::new(segment.edge_index);
+            let at_target = graph.get_track_endpoints(edge).is_some_and(|(_, target)| target == node);
+            graph.get_default_platform_for_arrival(edge, at_target, platform_count, handedness).min(platform_count - 1)
+        })
+    }
+
+    /// When the line's generated trains occupy each stop, keyed by direction (true for forward) and stop index
+    fn stop_occupancy(&self, graph: &RailwayGraph, service_patterns: &[ServicePattern], reference_week: Option<NaiveDate>) -> HashMap<(bool, usize), Vec<OccupancyWindow>> {
+        let journeys = crate::train_journey::TrainJourney::generate_journeys_with_patterns(
+            std::slice::from_ref(self), graph, None, service_patterns, reference_week,
+        );
+        let mut occupancy: HashMap<(bool, usize), Vec<OccupancyWindow>> = HashMap::new();
+        for journey in journeys.values() {
+            let journey = journey.with_passed_stations();
+            // Journeys with an unresolvable node don't line up with the route's stops
+            if journey.station_times.len() != self.route(journey.is_forward).len() + 1 {
+                continue;
+            }
+            for (stop, &(_, arrival, departure)) in journey.station_times.iter().enumerate() {
+                occupancy.entry((journey.is_forward, stop))
+                    .or_default()
+                    .push((arrival, departure + PLATFORM_CLEARANCE));
+            }
+        }
+        occupancy
+    }
+
+    /// Copy travel and wait times from the forward route onto the return route.
+    /// Durations are mirrored with the same span inheritance used for synced routes.
+    ///
+    /// # Errors
+    /// Returns an error and leaves the line untouched if the return route is not
+    /// the forward route reversed.
+    pub fn mirror_forward_to_return(&mut self) -> Result<(), String> {
+        let len = self.forward_route.len();
+        let is_reversed = self.return_route.len() == len
+            && self.forward_route.iter().rev()
+                .zip(&self.return_route)
+                .all(|(fwd, ret)| fwd.edge_index == ret.edge_index);
+        if !is_reversed {
+            return Err(format!(
+                "Return route ({} segments) does not retrace the forward route ({} segments)",
+                self.return_route.len(), len
+            ));
+        }
+
+        let durations = crate::train_journey::TrainJourney::build_synced_return_durations(&self.forward_route, len);
+        for (i, (segment, duration)) in self.return_route.iter_mut().zip(durations).enumerate() {
+            segment.duration = duration;
+            // Wait times and reversals sit at the destination, so shift by one stop when reversing
+            segment.wait_time = if i < len - 1 {
+                self.forward_route[len - i - 2].wait_time
+            } else {
+                self.first_stop_wait_time
+            };
+            segment.reverses = i < len - 1 && self.forward_route[len - i - 2].reverses;
+        }
+        if let Some(last) = self.forward_route.last() {
+            self.return_first_stop_wait_time = last.wait_time;
+        }
+        Ok(())
+    }
+
     #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
     fn reroute_single_direction(
         route: &mut Vec<RouteSegment>,
@@ -760,6 +1129,7 @@ impl Line {
                     destination_platform: if i == path.len() - 1 { segment.destination_platform } else { 0 },
                     duration: segment.duration.map(|d| d / path.len().max(1) as i32),
                     wait_time: if i == 0 { segment.wait_time } else { Duration::zero() },
+                    reverses: false,
                 };
                 new_segments.push(new_segment);
             }
@@ -772,6 +1142,239 @@ impl Line {
         changed
     }
 
+    /// Validate that frequency bands are well-formed and do not overlap
+    ///
+    /// # Errors
+    ///
+    /// Returns an error describing the first invalid or overlapping band
+    pub fn validate_frequency_bands(&self) -> Result<(), String> {
+        let mut bands = self.frequency_bands.clone();
+        bands.sort_by_key(|(start, _, _)| *start);
+
+        for (start, end, frequency) in &bands {
+            if start >= end {
+                return Err(format!("Frequency band {} - {} must end after it starts", start.format("%H:%M"), end.format("%H:%M")));
+            }
+            if *frequency <= Duration::zero() {
+                return Err(format!("Frequency band {} - {} must have a positive frequency", start.format("%H:%M"), end.format("%H:%M")));
+            }
+        }
+
+        if let Some(pair) = bands.windows(2).find(|pair| pair[0].1 > pair[1].0) {
+            return Err(format!(
+                "Frequency bands {} - {} and {} - {} overlap",
+                pair[0].0.format("%H:%M"), pair[0].1.format("%H:%M"),
+                pair[1].0.format("%H:%M"), pair[1].1.format("%H:%M")
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Validate that every manual departure has a route to run on
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a return-direction departure exists but the line has no return route
+    pub fn validate_manual_departures(&self) -> Result<(), String> {
+        let has_return_departure = self.manual_departures
+            .iter()
+            .any(|dep| dep.direction == RouteDirection::Return);
+        if has_return_departure && self.return_route.is_empty() {
+            return Err("Return departures require a return route".to_string());
+        }
+        Ok(())
+    }
+
+    /// Get the departure frequency in effect at a time of day
+    /// Uses the frequency band containing the time, falling back to the line frequency.
+    /// Bands are not checked here; use `frequency_schedule` when they may be invalid
+    #[must_use]
+    pub fn frequency_at(&self, time: NaiveTime) -> Duration {
+        self.frequency_bands
+            .iter()
+            .find(|(start, end, _)| *start <= time && time < *end)
+            .map_or(self.frequency, |(_, _, frequency)| *frequency)
+    }
+
+    /// Departure frequency by time of day for journey generation, validating the bands once up front
+    /// Invalid bands are ignored so a bad configuration can't stall journey generation
+    pub fn frequency_schedule(&self) -> impl Fn(NaiveTime) -> Duration + '_ {
+        let bands_valid = self.validate_frequency_bands().is_ok();
+        move |time| if bands_valid { self.frequency_at(time) } else { self.frequency }
+    }
+
+    /// Round a departure to the nearest multiple of `clock_face_snap` past the hour
+    /// Returns the time unchanged when no snap interval is set
+    #[must_use]
+    pub fn snap_to_clock_face(&self, time: NaiveDateTime) -> NaiveDateTime {
+        let Some(snap_seconds) = self.clock_face_snap.map(|snap| snap.num_seconds()).filter(|s| *s > 0) else {
+            return time;
+        };
+        let seconds_past_hour = i64::from(time.minute() * 60 + time.second());
+        let snapped = (seconds_past_hour + snap_seconds / 2) / snap_seconds * snap_seconds;
+        let hour_start = time.with_nanosecond(0).unwrap_or(time) - Duration::seconds(seconds_past_hour);
+        hour_start + Duration::seconds(snapped)
+    }
+
+    /// Check that every frequency is a whole multiple of the clock-face interval,
+    /// otherwise snapped departures would drift off a regular pattern
+    ///
+    /// # Errors
+    ///
+    /// Returns a warning message naming the first frequency that doesn't fit the interval
+    pub fn validate_clock_face_snap(&self) -> Result<(), String> {
+        let Some(snap) = self.clock_face_snap else {
+            return Ok(());
+        };
+        if snap <= Duration::zero() {
+            return Err("Clock-face interval must be positive".to_string());
+        }
+        let frequencies = std::iter::once(self.frequency)
+            .chain(self.frequency_bands.iter().map(|(_, _, frequency)| *frequency));
+        for frequency in frequencies {
+            if frequency.num_seconds() % snap.num_seconds() != 0 {
+                return Err(format!(
+                    "Frequency of {} min is not a multiple of the {} min clock-face interval",
+                    frequency.num_minutes(), snap.num_minutes()
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that neither route runs against the direction of a one-way track
+    ///
+    /// # Errors
+    ///
+    /// Returns a warning message naming the route and the first segment that cannot be traversed
+    pub fn validate_route_directions(&self, graph: &RailwayGraph) -> Result<(), String> {
+        graph.validate_route_directions(&self.forward_route)
+            .map_err(|e| format!("Forward route: {e}"))?;
+        graph.validate_route_directions(&self.return_route)
+            .map_err(|e| format!("Return route: {e}"))
+    }
+
+    /// Check that each forward route segment chains onto the next one
+    ///
+    /// # Errors
+    ///
+    /// Returns the indices of segments whose edge is missing or doesn't share
+    /// the node reached so far with the following segment
+    pub fn validate_connectivity(&self, graph: &RailwayGraph) -> Result<(), Vec<usize>> {
+        let route = &self.forward_route;
+        let route_nodes = crate::train_journey::TrainJourney::build_route_nodes(route, graph);
+
+        let gaps: Vec<usize> = route
+            .iter()
+            .enumerate()
+            .filter(|&(idx, segment)| {
+                if graph.get_track_endpoints(petgraph::graph::EdgeIndex::new(segment.edge_index)).is_none() {
+                    return true;
+                }
+                let Some(next_segment) = route.get(idx + 1) else {
+                    return false;
+                };
+                let reached = route_nodes.get(idx + 1).copied().flatten();
+                let next_endpoints = graph.get_track_endpoints(petgraph::graph::EdgeIndex::new(next_segment.edge_index));
+                match (reached, next_endpoints) {
+                    (Some(node), Some((from, to))) => node != from && node != to,
+                    _ => false,
+                }
+            })
+            .map(|(idx, _)| idx)
+            .collect();
+
+        if gaps.is_empty() {
+            Ok(())
+        } else {
+            Err(gaps)
+        }
+    }
+
+    /// Route and index of the first segment repeating an edge already used earlier in that route,
+    /// checking the forward route first.
+    /// Retracing edges after a reversal is expected; any other repeat is treated as an accidental loop.
+    #[must_use]
+    pub fn has_repeated_edges(&self) -> Option<(RouteDirection, usize)> {
+        Self::first_repeated_edge(&self.forward_route).map(|idx| (RouteDirection::Forward, idx))
+            .or_else(|| Self::first_repeated_edge(&self.return_route).map(|idx| (RouteDirection::Return, idx)))
+    }
+
+    /// Index of the first segment of `route` whose edge an earlier segment since the last reversal already uses
+    fn first_repeated_edge(route: &[RouteSegment]) -> Option<usize> {
+        let mut seen = std::collections::HashSet::new();
+        route.iter().position(|segment| {
+            let repeated = !seen.insert(segment.edge_index);
+            if segment.reverses {
+                seen.clear();
+            }
+            repeated
+        })
+    }
+
+    /// Whether the return segment at `return_index` ends where a synced forward route reverses.
+    /// Return segment `i` retraces forward segment `len - 1 - i`, so it arrives where forward segment `len - 2 - i` did.
+    fn return_segment_reverses(&self, return_index: usize) -> bool {
+        let len = self.forward_route.len();
+        return_index + 1 < len && self.forward_route[len - 2 - return_index].reverses
+    }
+
+    /// Move every departure of the line by `delta`, wrapping times around midnight.
+    /// Shifts the forward and return departure windows and all manual departures; when a
+    /// departure crosses midnight its operating days move with it, so it still runs on the
+    /// same services as before (a Friday 23:30 train shifted by an hour runs Saturday 00:30).
+    /// A repeat end that falls before its shifted departure moves to the following day
+    pub fn shift_schedule(&mut self, delta: Duration) {
+        let (first_departure, days_crossed) = shift_time_of_day(self.first_departure, delta);
+        self.first_departure = first_departure;
+        self.days_of_week = self.days_of_week.rotated(days_crossed);
+        self.last_departure = shift_time_of_day(self.last_departure, delta).0;
+        self.return_first_departure = shift_time_of_day(self.return_first_departure, delta).0;
+        self.return_last_departure = shift_time_of_day(self.return_last_departure, delta).0;
+
+        for departure in &mut self.manual_departures {
+            let (time, days_crossed) = shift_time_of_day(departure.time, delta);
+            departure.time = time;
+            departure.days_of_week = departure.days_of_week.rotated(days_crossed);
+            // Keep the end of the repeat on or after the departure, rolling it into the next day
+            departure.repeat_until = departure.repeat_until.map(|until| {
+                let until = shift_time_of_day(until, delta).0;
+                if until < time { until + Duration::days(1) } else { until }
+            });
+        }
+    }
+
+    /// Whether the real `date` falls within the line's operating period (inclusive)
+    #[must_use]
+    pub fn operates_on_date(&self, date: NaiveDate) -> bool {
+        self.valid_from.is_none_or(|from| date >= from) && self.valid_to.is_none_or(|to| date <= to)
+    }
+
+    /// Forward route stop indices skipped by skip-stop journeys
+    /// The first and last stops are always served
+    #[must_use]
+    pub fn skipped_stop_indices(&self) -> Vec<usize> {
+        let Some(pattern) = &self.skip_pattern else {
+            return Vec::new();
+        };
+        let last_stop = self.forward_route.len();
+
+        pattern
+            .iter()
+            .enumerate()
+            .filter(|&(idx, &skip)| skip && idx > 0 && idx < last_stop)
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    /// Whether the forward departure at `departure_index` (0-based) runs the skip pattern
+    #[must_use]
+    pub fn applies_skip_pattern(&self, departure_index: usize) -> bool {
+        let is_even_departure = (departure_index + 1) % 2 == 0;
+        self.skip_pattern.is_some() && is_even_departure == self.skip_pattern_on_even
+    }
+
     /// Generate a name for a duplicated line
     /// If the name ends with (N), increments N. Otherwise appends (1).
     #[must_use]
@@ -790,96 +1393,57 @@ impl Line {
     }
 
     /// Create a duplicate of this line with a new ID and updated name
+    /// Routes, schedule and days of operation are copied; manual departures get fresh IDs
     #[must_use]
     pub fn duplicate(&self) -> Self {
         let mut duplicated = self.clone();
         duplicated.id = uuid::Uuid::new_v4();
         duplicated.name = Self::generate_duplicate_name(&self.name);
+        for departure in &mut duplicated.manual_departures {
+            departure.id = uuid::Uuid::new_v4();
+        }
         duplicated
     }
 
-    /// Creates a route between two stations and adds it to the specified direction.
-    ///
-    /// # Arguments
-    /// * `first_name` - Name of the starting station
-    /// * `second_name` - Name of the ending station
-    /// * `graph` - Railway graph to use for pathfinding
-    /// * `direction` - Which route to populate (Forward or Return)
-    /// * `handedness` - Track handedness for platform selection
+    /// Proposes the shortest route between two stations, passing through `via` in order,
+    /// with default platforms and wait times filled in like stops added by hand
     ///
     /// # Returns
-    /// `true` if the route was created successfully, `false` if no path exists
-    pub fn create_route_between_stations(
-        &mut self,
-        first_name: &str,
-        second_name: &str,
+    /// The proposed segments, or `None` if the stations are not connected
+    #[must_use]
+    pub fn propose_route(
+        &self,
         graph: &RailwayGraph,
-        direction: RouteDirection,
+        from: NodeIndex,
+        to: NodeIndex,
+        via: &[NodeIndex],
         handedness: TrackHandedness,
-    ) -> bool {
-        let Some(first_idx) = graph.get_station_index(first_name) else {
-            return false;
-        };
-        let Some(second_idx) = graph.get_station_index(second_name) else {
-            return false;
-        };
-        let Some(path) = graph.find_path_between_nodes(first_idx, second_idx) else {
-            return false;
-        };
-
-        for edge in &path {
-            let Some((source, target)) = graph.graph.edge_endpoints(*edge) else {
-                continue;
-            };
-
-            let is_passing_loop = graph.graph.node_weight(source)
-                .and_then(|node| node.as_station())
-                .is_some_and(|s| s.passing_loop);
-            let default_wait = if is_passing_loop {
-                Duration::seconds(0)
+    ) -> Option<Vec<RouteSegment>> {
+        let mut route = graph.shortest_route(from, to, via)?;
+        let mut current_node = from;
+
+        for segment in &mut route {
+            let edge = petgraph::stable_graph::EdgeIndex::new(segment.edge_index);
+            let (source, target) = graph.graph.edge_endpoints(edge)?;
+            current_node = if current_node == source { target } else { source };
+
+            // Wait time is spent at the stop the segment arrives at
+            let passes_without_stopping = graph.graph.node_weight(current_node)
+                .is_some_and(|node| node.is_junction() || node.as_station().is_some_and(|s| s.passing_loop));
+            segment.wait_time = if passes_without_stopping {
+                Duration::zero()
             } else {
                 self.default_wait_time
             };
 
-            let source_platform_count = graph.graph.node_weight(source)
-                .and_then(|n| n.as_station())
-                .map_or(1, |s| s.platforms.len());
-
-            let target_platform_count = graph.graph.node_weight(target)
+            let platform_count = |node: NodeIndex| graph.graph.node_weight(node)
                 .and_then(|n| n.as_station())
                 .map_or(1, |s| s.platforms.len());
-
-            let origin_platform = graph.get_default_platform_for_arrival(*edge, false, source_platform_count, handedness);
-            let destination_platform = graph.get_default_platform_for_arrival(*edge, true, target_platform_count, handedness);
-
-            // Select track compatible with route direction
-            let traveling_backward = matches!(direction, RouteDirection::Return);
-            let track_index = graph.select_track_for_direction(*edge, traveling_backward);
-
-            let segment = RouteSegment {
-                edge_index: edge.index(),
-                track_index,
-                origin_platform,
-                destination_platform,
-                duration: None,
-                wait_time: default_wait,
-            };
-
-            match direction {
-                RouteDirection::Forward => {
-                    self.forward_route.push(segment);
-                }
-                RouteDirection::Return => {
-                    self.return_route.push(segment);
-                }
-            }
-        }
-
-        if matches!(direction, RouteDirection::Forward) {
-            self.apply_route_sync_if_enabled();
+            segment.origin_platform = graph.get_default_platform_for_arrival(edge, false, platform_count(source), handedness);
+            segment.destination_platform = graph.get_default_platform_for_arrival(edge, true, platform_count(target), handedness);
         }
 
-        true
+        Some(route)
     }
 
     /// Adds a station to a route at the specified position (start or end).
@@ -1032,6 +1596,7 @@ impl Line {
                 destination_platform,
                 duration: None,
                 wait_time: default_wait,
+                reverses: false,
             });
 
             current_node = next_node;
@@ -1083,7 +1648,7 @@ pub mod duration_serde {
     }
 }
 
-mod option_duration_serde {
+pub mod option_duration_serde {
     use chrono::Duration;
     use serde::{Deserialize, Deserializer, Serializer};
 
@@ -1154,6 +1719,42 @@ mod option_naive_datetime_serde {
     }
 }
 
+mod frequency_bands_serde {
+    use chrono::{Duration, NaiveTime};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    const TIME_FORMAT: &str = "%H:%M:%S";
+
+    pub fn serialize<S>(bands: &[(NaiveTime, NaiveTime, Duration)], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        bands
+            .iter()
+            .map(|(start, end, frequency)| {
+                (start.format(TIME_FORMAT).to_string(), end.format(TIME_FORMAT).to_string(), frequency.num_seconds())
+            })
+            .collect::<Vec<_>>()
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<(NaiveTime, NaiveTime, Duration)>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Vec::<(String, String, i64)>::deserialize(deserializer)?
+            .into_iter()
+            .map(|(start, end, seconds)| {
+                Ok((
+                    NaiveTime::parse_from_str(&start, TIME_FORMAT).map_err(serde::de::Error::custom)?,
+                    NaiveTime::parse_from_str(&end, TIME_FORMAT).map_err(serde::de::Error::custom)?,
+                    Duration::seconds(seconds),
+                ))
+            })
+            .collect()
+    }
+}
+
 mod node_index_serde {
     use petgraph::stable_graph::NodeIndex;
     use serde::{Deserialize, Deserializer, Serializer};
@@ -1176,11 +1777,26 @@ mod node_index_serde {
     }
 }
 
+/// Total seconds trains in `a` and `b` spend on the platform at the same time
+fn overlap_secs(a: &[OccupancyWindow], b: &[OccupancyWindow]) -> i64 {
+    a.iter()
+        .flat_map(|&(start_a, end_a)| b.iter().map(move |&(start_b, end_b)| {
+            (end_a.min(end_b) - start_a.max(start_b)).num_seconds().max(0)
+        }))
+        .sum()
+}
+
+/// Move the time of day of `time` by `delta`, returning it on `BASE_DATE` with the number of days crossed
+fn shift_time_of_day(time: NaiveDateTime, delta: Duration) -> (NaiveDateTime, i64) {
+    let shifted = BASE_DATE.and_time(time.time()) + delta;
+    (BASE_DATE.and_time(shifted.time()), (shifted.date() - BASE_DATE).num_days())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::models::{RailwayGraph, Stations, Tracks};
-    use crate::models::track::{Track, TrackDirection};
+    use crate::models::track::{Electrification, Track, TrackDirection};
 
     fn create_test_segment(edge_index: usize) -> RouteSegment {
         RouteSegment {
@@ -1190,6 +1806,7 @@ mod tests {
             destination_platform: 0,
             duration: Some(Duration::minutes(5)),
             wait_time: Duration::seconds(30),
+            reverses: false,
         }
     }
 
@@ -1217,6 +1834,260 @@ mod tests {
         assert_eq!(lines[0].schedule_mode, ScheduleMode::Auto);
     }
 
+    #[test]
+    fn test_duplicate_copies_routes_and_schedule() {
+        let mut line = Line::create_from_ids(&["Line 1".to_string()], 0).remove(0);
+        line.forward_route = vec![create_test_segment(0), create_test_segment(1)];
+        line.return_route = vec![create_test_segment(1), create_test_segment(0)];
+        line.days_of_week = DaysOfWeek::WEEKDAYS;
+        line.manual_departures = vec![ManualDeparture {
+            id: uuid::Uuid::new_v4(),
+            time: BASE_DATE.and_hms_opt(10, 0, 0).expect("valid time"),
+            from_station: NodeIndex::new(0),
+            to_station: NodeIndex::new(1),
+            days_of_week: DaysOfWeek::ALL_DAYS,
+            train_number: Some("101".to_string()),
+            repeat_interval: None,
+            repeat_until: None,
+            direction: RouteDirection::Forward,
+        }];
+
+        let duplicated = line.duplicate();
+
+        assert_ne!(duplicated.id, line.id);
+        assert_ne!(duplicated.name, line.name);
+        assert_eq!(duplicated.forward_route, line.forward_route);
+        assert_eq!(duplicated.return_route, line.return_route);
+        assert_eq!(duplicated.days_of_week, line.days_of_week);
+        assert_eq!(duplicated.manual_departures.len(), 1);
+        assert_eq!(duplicated.manual_departures[0].time, line.manual_departures[0].time);
+        assert_ne!(duplicated.manual_departures[0].id, line.manual_departures[0].id);
+    }
+
+    fn run_times(journey: &crate::train_journey::TrainJourney) -> Vec<Duration> {
+        journey.station_times.windows(2).map(|pair| pair[1].1 - pair[0].2).collect()
+    }
+
+    #[test]
+    fn test_mirror_forward_to_return_gives_symmetric_run_times() {
+        let mut graph = RailwayGraph::new();
+        let stations: Vec<_> = ["A", "B", "C", "D"].iter()
+            .map(|name| graph.add_or_get_station((*name).to_string()))
+            .collect();
+        let edges: Vec<usize> = stations.windows(2)
+            .map(|pair| graph.add_track(pair[0], pair[1], vec![Track { direction: TrackDirection::Bidirectional }]).index())
+            .collect();
+
+        let mut line = Line::create_from_ids(&["Line 1".to_string()], 0).remove(0);
+        line.sync_routes = false;
+        line.forward_route = edges.iter().map(|&e| create_test_segment(e)).collect();
+        line.forward_route[0].duration = Some(Duration::minutes(6));
+        line.forward_route[1].duration = None;
+        line.forward_route[1].wait_time = Duration::minutes(1);
+        line.return_route = edges.iter().rev().map(|&e| RouteSegment {
+            duration: Some(Duration::minutes(1)),
+            wait_time: Duration::zero(),
+            ..create_test_segment(e)
+        }).collect();
+
+        line.mirror_forward_to_return().expect("routes retrace each other");
+
+        let journeys = crate::train_journey::TrainJourney::generate_journeys(&[line], &graph, None);
+        let forward = journeys.values().find(|j| j.is_forward).expect("forward journey");
+        let backward = journeys.values().find(|j| !j.is_forward).expect("return journey");
+
+        let mut forward_runs = run_times(forward);
+        forward_runs.reverse();
+        assert_eq!(run_times(backward), forward_runs);
+        assert_eq!(backward.station_times[1].2 - backward.station_times[1].1, forward.station_times[2].2 - forward.station_times[2].1);
+    }
+
+    #[test]
+    fn test_auto_assign_platforms_alternates_at_two_platform_station() {
+        let mut graph = RailwayGraph::new();
+        let stations: Vec<_> = ["A", "B", "C"].iter()
+            .map(|name| graph.add_or_get_station((*name).to_string()))
+            .collect();
+        let edges: Vec<usize> = stations.windows(2)
+            .map(|pair| graph.add_track(pair[0], pair[1], vec![
+                Track { direction: TrackDirection::Forward },
+                Track { direction: TrackDirection::Backward },
+            ]).index())
+            .collect();
+        // Both directions default to B's first platform, as a freshly drawn line would
+        for (edge, at_target) in [(edges[0], true), (edges[1], false)] {
+            let track = graph.graph.edge_weight_mut(petgraph::graph::EdgeIndex::new(edge)).expect("edge exists");
+            if at_target { track.default_platform_target = Some(0) } else { track.default_platform_source = Some(0) }
+        }
+
+        // Every 10 minutes each way, with both directions stopping at B over the same minutes
+        let mut line = Line::create_from_ids(&["Line 1".to_string()], 0).remove(0);
+        line.sync_routes = false;
+        line.frequency = Duration::minutes(10);
+        line.first_departure = BASE_DATE.and_hms_opt(8, 0, 0).expect("valid time");
+        line.return_first_departure = line.first_departure;
+        line.forward_route = edges.iter().map(|&e| RouteSegment { wait_time: Duration::minutes(4), ..create_test_segment(e) }).collect();
+        line.return_route = edges.iter().rev().map(|&e| RouteSegment {
+            track_index: 1,
+            wait_time: Duration::minutes(4),
+            ..create_test_segment(e)
+        }).collect();
+        assert!(!crate::train_journey::TrainJourney::generate_journeys(&[line.clone()], &graph, None).is_empty());
+
+        let mut offset = line.clone();
+        line.auto_assign_platforms(&graph, TrackHandedness::RightHand, &[], None);
+
+        // B is the stop between the two segments in each direction
+        assert_eq!((line.forward_route[0].destination_platform, line.forward_route[1].origin_platform), (0, 0));
+        assert_eq!((line.return_route[0].destination_platform, line.return_route[1].origin_platform), (1, 1));
+
+        // Return trains reaching B while forward trains are away leave both on the default platform
+        offset.return_first_departure = offset.first_departure + Duration::minutes(5);
+        offset.auto_assign_platforms(&graph, TrackHandedness::RightHand, &[], None);
+        assert_eq!(offset.forward_route[0].destination_platform, 0);
+        assert_eq!(offset.return_route[0].destination_platform, 0);
+    }
+
+    #[test]
+    fn test_mirror_forward_to_return_rejects_mismatched_routes() {
+        let mut line = Line::create_from_ids(&["Line 1".to_string()], 0).remove(0);
+        line.forward_route = vec![create_test_segment(0), create_test_segment(1)];
+        line.return_route = vec![create_test_segment(1)];
+        line.return_route[0].duration = Some(Duration::minutes(9));
+
+        assert!(line.mirror_forward_to_return().is_err());
+        assert_eq!(line.return_route[0].duration, Some(Duration::minutes(9)));
+    }
+
+    #[test]
+    fn test_return_manual_departure_requires_return_route() {
+        let mut line = Line::create_from_ids(&["Line 1".to_string()], 0).remove(0);
+        line.forward_route = vec![create_test_segment(0)];
+        line.manual_departures = vec![ManualDeparture {
+            id: uuid::Uuid::new_v4(),
+            time: BASE_DATE.and_hms_opt(10, 0, 0).expect("valid time"),
+            from_station: NodeIndex::new(1),
+            to_station: NodeIndex::new(0),
+            days_of_week: DaysOfWeek::ALL_DAYS,
+            train_number: None,
+            repeat_interval: None,
+            repeat_until: None,
+            direction: RouteDirection::Return,
+        }];
+
+        assert!(line.validate_manual_departures().is_err());
+
+        line.return_route = vec![create_test_segment(0)];
+        assert!(line.validate_manual_departures().is_ok());
+    }
+
+    #[test]
+    fn test_recolor_lines_only_changes_selection() {
+        let names: Vec<String> = (1..=3).map(|i| format!("Line {i}")).collect();
+        let mut lines = Line::create_from_ids(&names, 0);
+        let untouched_color = lines[1].color.clone();
+        let selected = [lines[0].id, lines[2].id];
+
+        recolor_lines(&selected, "#123456", &mut lines);
+
+        assert_eq!(lines[0].color, "#123456");
+        assert_eq!(lines[2].color, "#123456");
+        assert_eq!(lines[1].color, untouched_color);
+    }
+
+    #[test]
+    fn test_apply_palette_assigns_distinct_colors_in_order() {
+        let names: Vec<String> = (1..=3).map(|i| format!("Line {i}")).collect();
+        let mut lines = Line::create_from_ids(&names, 10);
+        let untouched_color = lines[0].color.clone();
+        let selected = [lines[2].id, lines[1].id];
+
+        apply_palette(&selected, &mut lines);
+
+        assert_eq!(lines[2].color, generate_random_color(0));
+        assert_eq!(lines[1].color, generate_random_color(1));
+        assert_ne!(lines[1].color, lines[2].color);
+        assert_eq!(lines[0].color, untouched_color);
+    }
+
+    #[test]
+    fn test_frequency_bands_validation_and_lookup() {
+        let time = |h, m| NaiveTime::from_hms_opt(h, m, 0).expect("valid time");
+        let mut line = Line::create_from_ids(&["Line 1".to_string()], 0).remove(0);
+        line.frequency = Duration::minutes(30);
+        line.frequency_bands = vec![
+            (time(16, 0), time(18, 0), Duration::minutes(10)),
+            (time(7, 0), time(9, 0), Duration::minutes(10)),
+        ];
+
+        assert!(line.validate_frequency_bands().is_ok());
+        assert_eq!(line.frequency_at(time(8, 0)), Duration::minutes(10));
+        assert_eq!(line.frequency_at(time(9, 0)), Duration::minutes(30));
+        assert_eq!(line.frequency_at(time(17, 30)), Duration::minutes(10));
+
+        // Overlapping bands are rejected and ignored when scheduling
+        assert_eq!(line.frequency_schedule()(time(8, 0)), Duration::minutes(10));
+        line.frequency_bands.push((time(8, 30), time(10, 0), Duration::minutes(15)));
+        assert!(line.validate_frequency_bands().is_err());
+        assert_eq!(line.frequency_schedule()(time(8, 0)), Duration::minutes(30));
+    }
+
+    #[test]
+    fn test_line_capacity_tph_auto_and_banded() {
+        let mut line = Line::create_from_ids(&["Line 1".to_string()], 0).remove(0);
+        line.frequency = Duration::minutes(15);
+        assert!((line_capacity_tph(&line) - 4.0).abs() < f64::EPSILON);
+        assert_eq!(peak_capacity_tph(&line), None);
+
+        // Bands raise the peak but not the base figure
+        let time = |h| NaiveTime::from_hms_opt(h, 0, 0).expect("valid time");
+        line.frequency_bands = vec![
+            (time(7), time(9), Duration::minutes(10)),
+            (time(20), time(23), Duration::minutes(30)),
+        ];
+        assert!((line_capacity_tph(&line) - 4.0).abs() < f64::EPSILON);
+        let peak = peak_capacity_tph(&line).expect("banded line has a peak");
+        assert!((peak - 6.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_line_capacity_tph_manual_uses_densest_hour() {
+        let mut line = Line::create_from_ids(&["Line 1".to_string()], 0).remove(0);
+        line.schedule_mode = ScheduleMode::Manual;
+        let departure = |h, m, direction| ManualDeparture {
+            id: uuid::Uuid::new_v4(),
+            time: BASE_DATE.and_hms_opt(h, m, 0).expect("valid time"),
+            from_station: NodeIndex::new(0),
+            to_station: NodeIndex::new(1),
+            days_of_week: DaysOfWeek::ALL_DAYS,
+            train_number: None,
+            repeat_interval: None,
+            repeat_until: None,
+            direction,
+        };
+        assert!(line_capacity_tph(&line).abs() < f64::EPSILON);
+
+        // Three forward trains between 8:00 and 8:50, return trains counted separately
+        line.manual_departures = vec![
+            departure(6, 0, RouteDirection::Forward),
+            departure(8, 0, RouteDirection::Forward),
+            departure(8, 20, RouteDirection::Forward),
+            departure(8, 50, RouteDirection::Forward),
+            departure(9, 0, RouteDirection::Forward),
+            departure(8, 10, RouteDirection::Return),
+            departure(8, 30, RouteDirection::Return),
+        ];
+        assert!((line_capacity_tph(&line) - 3.0).abs() < f64::EPSILON);
+
+        // A departure repeating every 12 minutes from 17:00 runs five trains in its busiest hour
+        line.manual_departures.push(ManualDeparture {
+            repeat_interval: Some(Duration::minutes(12)),
+            repeat_until: Some(BASE_DATE.and_hms_opt(19, 0, 0).expect("valid time")),
+            ..departure(17, 0, RouteDirection::Return)
+        });
+        assert!((line_capacity_tph(&line) - 5.0).abs() < f64::EPSILON);
+    }
+
     #[test]
     fn test_uses_edge() {
         let line = Line {
@@ -1247,6 +2118,15 @@ mod tests {
             style: LineStyle::default(),
             forward_turnaround: false,
             return_turnaround: false,
+            frequency_bands: Vec::new(),
+            skip_pattern: None,
+            skip_pattern_on_even: false,
+            rolling_stock: None,
+            clock_face_snap: None,
+            section_order_override: None,
+            valid_from: None,
+            valid_to: None,
+            service_pattern_id: None,
         };
 
         assert!(line.uses_edge(1));
@@ -1285,6 +2165,15 @@ mod tests {
             style: LineStyle::default(),
             forward_turnaround: false,
             return_turnaround: false,
+            frequency_bands: Vec::new(),
+            skip_pattern: None,
+            skip_pattern_on_even: false,
+            rolling_stock: None,
+            clock_face_snap: None,
+            section_order_override: None,
+            valid_from: None,
+            valid_to: None,
+            service_pattern_id: None,
         };
 
         assert!(line.uses_any_edge(&[1, 5, 6]));
@@ -1326,6 +2215,15 @@ mod tests {
             style: LineStyle::default(),
             forward_turnaround: false,
             return_turnaround: false,
+            frequency_bands: Vec::new(),
+            skip_pattern: None,
+            skip_pattern_on_even: false,
+            rolling_stock: None,
+            clock_face_snap: None,
+            section_order_override: None,
+            valid_from: None,
+            valid_to: None,
+            service_pattern_id: None,
         };
 
         // Simulate deleting a station that used edges 1 and 2, creating bypass edge 10
@@ -1345,6 +2243,95 @@ mod tests {
         assert_eq!(line.forward_route[0].duration, Some(expected_duration));
     }
 
+    #[test]
+    fn test_merge_stations_updates_routes() {
+        use crate::models::{Node, Platform};
+
+        let mut graph = RailwayGraph::new();
+        let a = graph.add_or_get_station("A".to_string());
+        let b = graph.add_or_get_station("B".to_string());
+        let c = graph.add_or_get_station("C".to_string());
+        let duplicate = graph.add_or_get_station("B (old)".to_string());
+        if let Some(station) = graph.graph.node_weight_mut(duplicate).and_then(Node::as_station_mut) {
+            station.platforms = vec![
+                Platform { name: "3".to_string(), capacity: 1 },
+                Platform { name: "2".to_string(), capacity: 1 },
+            ];
+        }
+        let bidirectional = || Track { direction: TrackDirection::Bidirectional };
+        let a_b = graph.add_track(a, b, vec![bidirectional(), bidirectional()]).index();
+        let a_dup = graph.add_track(a, duplicate, vec![bidirectional()]).index();
+        let dup_c = graph.add_track(duplicate, c, vec![bidirectional()]).index();
+        let b_dup = graph.add_track(b, duplicate, vec![bidirectional()]).index();
+
+        let mut lines = Line::create_from_ids(&["L1".to_string(), "L2".to_string()], 0);
+        lines[0].forward_route = vec![create_test_segment(a_dup), create_test_segment(dup_c)];
+        lines[0].forward_route[0].destination_platform = 0;
+        lines[0].forward_route[1].origin_platform = 1;
+        lines[1].forward_route = vec![create_test_segment(a_b), create_test_segment(b_dup), create_test_segment(dup_c)];
+
+        let merge = graph.merge_stations(b, duplicate).expect("both are stations");
+        for line in &mut lines {
+            line.update_route_after_merge(&merge, b, &graph);
+        }
+
+        assert_eq!(graph.graph.node_count(), 3);
+        assert_eq!(graph.graph.edge_count(), 2);
+        assert_eq!(graph.get_station_index("B (old)"), None);
+        assert_eq!(graph.get_track(petgraph::stable_graph::EdgeIndex::new(a_b)).map(|t| t.tracks.len()), Some(2));
+        assert_eq!(merge.edges.get(&b_dup), Some(&None));
+        let b_c = graph.graph.find_edge(b, c).expect("edge moved onto kept station").index();
+
+        for line in &lines {
+            let edges: Vec<usize> = line.forward_route.iter().map(|s| s.edge_index).collect();
+            assert_eq!(edges, vec![a_b, b_c]);
+        }
+        assert_eq!(lines[0].forward_route[0].destination_platform, 2);
+        assert_eq!(lines[0].forward_route[1].origin_platform, 1);
+        assert_eq!(graph.graph.node_weight(b).and_then(Node::as_station).map(|s| s.platforms.len()), Some(3));
+        assert!(graph.merge_stations(b, b).is_err());
+    }
+
+    #[test]
+    fn test_merge_stations_single_segment_routes() {
+        use crate::models::{Node, Platform};
+
+        let mut graph = RailwayGraph::new();
+        let a = graph.add_or_get_station("A".to_string());
+        let b = graph.add_or_get_station("B".to_string());
+        let c = graph.add_or_get_station("C".to_string());
+        let duplicate = graph.add_or_get_station("B (old)".to_string());
+        if let Some(station) = graph.graph.node_weight_mut(duplicate).and_then(Node::as_station_mut) {
+            station.platforms = vec![
+                Platform { name: "3".to_string(), capacity: 1 },
+                Platform { name: "2".to_string(), capacity: 1 },
+            ];
+        }
+        let bidirectional = || Track { direction: TrackDirection::Bidirectional };
+        let a_dup = graph.add_track(a, duplicate, vec![bidirectional()]).index();
+        let dup_c = graph.add_track(duplicate, c, vec![bidirectional()]).index();
+
+        // One line arrives at the merged station, the other leaves from it
+        let mut lines = Line::create_from_ids(&["L1".to_string(), "L2".to_string()], 0);
+        lines[0].forward_route = vec![create_test_segment(a_dup)];
+        lines[0].forward_route[0].origin_platform = 0;
+        lines[0].forward_route[0].destination_platform = 0;
+        lines[1].forward_route = vec![create_test_segment(dup_c)];
+        lines[1].forward_route[0].origin_platform = 0;
+        lines[1].forward_route[0].destination_platform = 0;
+
+        let merge = graph.merge_stations(b, duplicate).expect("both are stations");
+        for line in &mut lines {
+            line.update_route_after_merge(&merge, b, &graph);
+        }
+
+        // Only the platform at the merged station moves to its counterpart
+        assert_eq!(lines[0].forward_route[0].origin_platform, 0);
+        assert_eq!(lines[0].forward_route[0].destination_platform, 2);
+        assert_eq!(lines[1].forward_route[0].origin_platform, 2);
+        assert_eq!(lines[1].forward_route[0].destination_platform, 0);
+    }
+
     #[test]
     fn test_update_route_after_deletion_without_bypass() {
         let mut line = Line {
@@ -1378,6 +2365,15 @@ mod tests {
             style: LineStyle::default(),
             forward_turnaround: false,
             return_turnaround: false,
+            frequency_bands: Vec::new(),
+            skip_pattern: None,
+            skip_pattern_on_even: false,
+            rolling_stock: None,
+            clock_face_snap: None,
+            section_order_override: None,
+            valid_from: None,
+            valid_to: None,
+            service_pattern_id: None,
         };
 
         // Remove edge 1 but no bypass mapping
@@ -1422,6 +2418,7 @@ mod tests {
                 destination_platform: 0,
                 duration: Some(Duration::minutes(5)),
                 wait_time: Duration::seconds(30),
+                reverses: false,
             }],
             return_route: vec![],
             sync_routes: true,
@@ -1438,6 +2435,15 @@ mod tests {
             style: LineStyle::default(),
             forward_turnaround: false,
             return_turnaround: false,
+            frequency_bands: Vec::new(),
+            skip_pattern: None,
+            skip_pattern_on_even: false,
+            rolling_stock: None,
+            clock_face_snap: None,
+            section_order_override: None,
+            valid_from: None,
+            valid_to: None,
+            service_pattern_id: None,
         };
 
         line.fix_track_indices_after_change(edge.index(), 2, &graph);
@@ -1446,6 +2452,42 @@ mod tests {
         assert_eq!(line.forward_route[0].track_index, 0);
     }
 
+    #[test]
+    fn test_split_platform_uses_track_handedness_override() {
+        use crate::models::{Stations, Tracks};
+
+        let mut graph = RailwayGraph::new();
+        let a = graph.add_or_get_station("A".to_string());
+        let middle = graph.add_or_get_station("Middle".to_string());
+        let b = graph.add_or_get_station("B".to_string());
+        let first = graph.add_track(a, middle, vec![Track { direction: TrackDirection::Bidirectional }]);
+        let second = graph.add_track(middle, b, vec![Track { direction: TrackDirection::Bidirectional }]);
+
+        let split = |graph: &RailwayGraph| {
+            let mut line = Line::create_from_ids(&["L".to_string()], 0).remove(0);
+            line.forward_route = vec![RouteSegment {
+                edge_index: 99,
+                track_index: 0,
+                origin_platform: 0,
+                destination_platform: 0,
+                duration: Some(Duration::minutes(4)),
+                wait_time: Duration::zero(),
+                reverses: false,
+            }];
+            line.replace_split_edge(99, first.index(), second.index(), 0.5, 1, graph, 2, TrackHandedness::RightHand);
+            (line.forward_route[0].destination_platform, line.forward_route[1].origin_platform)
+        };
+
+        // Right-hand default: arrive on the last platform, depart from the first
+        assert_eq!(split(&graph), (1, 0));
+
+        // Left-hand running on the first edge changes only the arrival platform
+        if let Some(segment) = graph.graph.edge_weight_mut(first) {
+            segment.handedness = Some(TrackHandedness::LeftHand);
+        }
+        assert_eq!(split(&graph), (0, 0));
+    }
+
     #[test]
     fn test_is_track_incompatible() {
         let segment = TrackSegment {
@@ -1456,6 +2498,11 @@ mod tests {
             distance: None,
             default_platform_source: None,
             default_platform_target: None,
+            speed_limit_kph: None,
+            electrification: Electrification::None,
+            gradient_permille: None,
+            handedness: None,
+            notes: String::new(),
         };
 
         // Forward route should be compatible with Forward track (index 0)
@@ -1482,6 +2529,11 @@ mod tests {
             distance: None,
             default_platform_source: None,
             default_platform_target: None,
+            speed_limit_kph: None,
+            electrification: Electrification::None,
+            gradient_permille: None,
+            handedness: None,
+            notes: String::new(),
         };
 
         // For forward route, should find first compatible track (index 1 - Forward)
@@ -1539,6 +2591,15 @@ mod tests {
             style: LineStyle::default(),
             forward_turnaround: false,
             return_turnaround: false,
+            frequency_bands: Vec::new(),
+            skip_pattern: None,
+            skip_pattern_on_even: false,
+            rolling_stock: None,
+            clock_face_snap: None,
+            section_order_override: None,
+            valid_from: None,
+            valid_to: None,
+            service_pattern_id: None,
         };
 
         // Create a minimal test graph for platform assignment
@@ -1546,8 +2607,8 @@ mod tests {
         let platform_count = 2; // Passing loop with 2 platforms
         let handedness = TrackHandedness::RightHand;
 
-        // Split edge 10 into edges 20 and 21
-        line.replace_split_edge(10, 20, 21, 1, &graph, platform_count, handedness);
+        // Split edge 10 into edges 20 and 21, a fifth of the way along
+        line.replace_split_edge(10, 20, 21, 0.2, 1, &graph, platform_count, handedness);
 
         // Forward route should have: 5, 20, 21, 15
         assert_eq!(line.forward_route.len(), 4);
@@ -1563,9 +2624,11 @@ mod tests {
         assert_eq!(line.return_route[2].edge_index, 20);
         assert_eq!(line.return_route[3].edge_index, 5);
 
-        // Check duration is split in half
-        assert_eq!(line.forward_route[1].duration, Some(Duration::minutes(5) / 2));
-        assert_eq!(line.forward_route[2].duration, Some(Duration::minutes(5) / 2));
+        // Durations follow the split point in both directions
+        assert_eq!(line.forward_route[1].duration, Some(Duration::minutes(1)));
+        assert_eq!(line.forward_route[2].duration, Some(Duration::minutes(4)));
+        assert_eq!(line.return_route[1].duration, Some(Duration::minutes(4)));
+        assert_eq!(line.return_route[2].duration, Some(Duration::minutes(1)));
     }
 
     #[test]
@@ -1624,6 +2687,15 @@ mod tests {
             style: LineStyle::default(),
             forward_turnaround: false,
             return_turnaround: false,
+            frequency_bands: Vec::new(),
+            skip_pattern: None,
+            skip_pattern_on_even: false,
+            rolling_stock: None,
+            clock_face_snap: None,
+            section_order_override: None,
+            valid_from: None,
+            valid_to: None,
+            service_pattern_id: None,
         };
 
         // Delete the direct edge B -> C
@@ -1678,6 +2750,15 @@ mod tests {
             style: LineStyle::default(),
             forward_turnaround: false,
             return_turnaround: false,
+            frequency_bands: Vec::new(),
+            skip_pattern: None,
+            skip_pattern_on_even: false,
+            rolling_stock: None,
+            clock_face_snap: None,
+            section_order_override: None,
+            valid_from: None,
+            valid_to: None,
+            service_pattern_id: None,
         };
 
         // Delete the edge
@@ -1691,4 +2772,156 @@ mod tests {
         assert_eq!(line.forward_route.len(), 1);
         assert_eq!(line.forward_route[0].edge_index, e1.index());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_validate_connectivity() {
+        let mut graph = RailwayGraph::new();
+        let a = graph.add_or_get_station("A".to_string());
+        let b = graph.add_or_get_station("B".to_string());
+        let c = graph.add_or_get_station("C".to_string());
+        let d = graph.add_or_get_station("D".to_string());
+        let track = || vec![Track { direction: TrackDirection::Bidirectional }];
+        let a_b = graph.add_track(a, b, track());
+        let b_c = graph.add_track(b, c, track());
+        let c_d = graph.add_track(c, d, track());
+
+        let mut line = Line::create_from_ids(&["Line 1".to_string()], 0).remove(0);
+
+        // Connected route
+        line.forward_route = vec![create_test_segment(a_b.index()), create_test_segment(b_c.index()), create_test_segment(c_d.index())];
+        assert_eq!(line.validate_connectivity(&graph), Ok(()));
+
+        // A → B then C → D leaves a gap after the first segment
+        line.forward_route = vec![create_test_segment(a_b.index()), create_test_segment(c_d.index())];
+        assert_eq!(line.validate_connectivity(&graph), Err(vec![0]));
+
+        // A single segment is always connected
+        line.forward_route = vec![create_test_segment(b_c.index())];
+        assert_eq!(line.validate_connectivity(&graph), Ok(()));
+    }
+
+    #[test]
+    fn test_has_repeated_edges() {
+        let mut line = Line::create_from_ids(&["Line 1".to_string()], 0).remove(0);
+
+        // Clean route
+        line.forward_route = vec![create_test_segment(0), create_test_segment(1), create_test_segment(2)];
+        line.return_route = vec![create_test_segment(2), create_test_segment(1), create_test_segment(0)];
+        assert_eq!(line.has_repeated_edges(), None);
+
+        // The return route loops back over its first edge
+        line.return_route.push(create_test_segment(2));
+        assert_eq!(line.has_repeated_edges(), Some((RouteDirection::Return, 3)));
+
+        // Forward repeats are reported first
+        line.forward_route.insert(2, create_test_segment(1));
+        assert_eq!(line.has_repeated_edges(), Some((RouteDirection::Forward, 2)));
+    }
+
+    #[test]
+    fn test_departure_series_materializes_entries() {
+        let template = ManualDeparture {
+            id: uuid::Uuid::new_v4(),
+            time: BASE_DATE.and_hms_opt(7, 10, 0).expect("valid time"),
+            from_station: NodeIndex::new(0),
+            to_station: NodeIndex::new(1),
+            days_of_week: DaysOfWeek::WEEKDAYS,
+            train_number: Some("101".to_string()),
+            repeat_interval: Some(Duration::minutes(60)),
+            repeat_until: None,
+            direction: RouteDirection::Return,
+        };
+
+        let series = template.series(Duration::minutes(20), DepartureSeriesEnd::Count(5));
+
+        assert_eq!(series.len(), 5);
+        let times: Vec<_> = series.iter().map(|dep| dep.time.format("%H:%M").to_string()).collect();
+        assert_eq!(times, ["07:10", "07:30", "07:50", "08:10", "08:30"]);
+        assert!(series.iter().all(|dep| dep.days_of_week == DaysOfWeek::WEEKDAYS
+            && dep.direction == RouteDirection::Return
+            && dep.to_station == NodeIndex::new(1)
+            && dep.train_number.is_none()
+            && dep.repeat_interval.is_none()));
+        let ids: std::collections::HashSet<_> = series.iter().map(|dep| dep.id).collect();
+        assert_eq!(ids.len(), 5);
+
+        // An end time is inclusive
+        let until = BASE_DATE.and_hms_opt(8, 10, 0).expect("valid time");
+        assert_eq!(template.series(Duration::minutes(20), DepartureSeriesEnd::Until(until)).len(), 4);
+    }
+
+    #[test]
+    fn test_shift_schedule_later() {
+        let mut line = Line::create_from_ids(&["L".to_string()], 0).remove(0);
+        line.first_departure = BASE_DATE.and_hms_opt(6, 0, 0).expect("valid time");
+        line.last_departure = BASE_DATE.and_hms_opt(20, 0, 0).expect("valid time");
+        line.days_of_week = DaysOfWeek::WEEKDAYS;
+        line.manual_departures = vec![ManualDeparture {
+            id: uuid::Uuid::new_v4(),
+            time: BASE_DATE.and_hms_opt(12, 15, 0).expect("valid time"),
+            from_station: NodeIndex::new(0),
+            to_station: NodeIndex::new(1),
+            days_of_week: DaysOfWeek::MONDAY,
+            train_number: None,
+            repeat_interval: None,
+            repeat_until: None,
+            direction: RouteDirection::Forward,
+        }];
+
+        line.shift_schedule(Duration::minutes(45));
+
+        assert_eq!(line.first_departure, BASE_DATE.and_hms_opt(6, 45, 0).expect("valid time"));
+        assert_eq!(line.last_departure, BASE_DATE.and_hms_opt(20, 45, 0).expect("valid time"));
+        assert_eq!(line.days_of_week, DaysOfWeek::WEEKDAYS);
+        assert_eq!(line.manual_departures[0].time, BASE_DATE.and_hms_opt(13, 0, 0).expect("valid time"));
+        assert_eq!(line.manual_departures[0].days_of_week, DaysOfWeek::MONDAY);
+    }
+
+    #[test]
+    fn test_shift_schedule_earlier_across_midnight() {
+        let mut graph = RailwayGraph::new();
+        let a = graph.add_or_get_station("A".to_string());
+        let b = graph.add_or_get_station("B".to_string());
+        let edge = graph.add_track(a, b, vec![Track { direction: TrackDirection::Bidirectional }]);
+
+        let mut line = Line::create_from_ids(&["L".to_string()], 0).remove(0);
+        line.schedule_mode = ScheduleMode::Manual;
+        line.forward_route = vec![create_test_segment(edge.index())];
+        line.first_departure = BASE_DATE.and_hms_opt(0, 20, 0).expect("valid time");
+        line.last_departure = BASE_DATE.and_hms_opt(5, 0, 0).expect("valid time");
+        line.days_of_week = DaysOfWeek::MONDAY | DaysOfWeek::SATURDAY;
+        line.manual_departures = vec![ManualDeparture {
+            id: uuid::Uuid::new_v4(),
+            time: BASE_DATE.and_hms_opt(0, 10, 0).expect("valid time"),
+            from_station: a,
+            to_station: b,
+            days_of_week: DaysOfWeek::MONDAY,
+            train_number: None,
+            repeat_interval: Some(Duration::minutes(30)),
+            repeat_until: Some(BASE_DATE.and_hms_opt(2, 0, 0).expect("valid time")),
+            direction: RouteDirection::Forward,
+        }];
+        let trains_on = |line: &Line, day| crate::train_journey::TrainJourney::generate_journeys(std::slice::from_ref(line), &graph, Some(day)).len();
+        assert_eq!(trains_on(&line, chrono::Weekday::Mon), 4);
+
+        line.shift_schedule(Duration::minutes(-30));
+
+        // The first departure moves to the evening before, so the operating days move back a day
+        assert_eq!(line.first_departure, BASE_DATE.and_hms_opt(23, 50, 0).expect("valid time"));
+        assert_eq!(line.last_departure, BASE_DATE.and_hms_opt(4, 30, 0).expect("valid time"));
+        assert_eq!(line.days_of_week, DaysOfWeek::SUNDAY | DaysOfWeek::FRIDAY);
+        let departure = &line.manual_departures[0];
+        assert_eq!(departure.time, BASE_DATE.and_hms_opt(23, 40, 0).expect("valid time"));
+        assert_eq!(departure.days_of_week, DaysOfWeek::SUNDAY);
+        assert_eq!(departure.repeat_until, Some(BASE_DATE.and_hms_opt(1, 30, 0).expect("valid time") + Duration::days(1)));
+
+        // The repeats carry on past midnight, so the same four trains still run
+        assert_eq!(trains_on(&line, chrono::Weekday::Sun), 4);
+
+        // Shifting back restores the original schedule
+        line.shift_schedule(Duration::minutes(30));
+        assert_eq!(line.first_departure, BASE_DATE.and_hms_opt(0, 20, 0).expect("valid time"));
+        assert_eq!(line.days_of_week, DaysOfWeek::MONDAY | DaysOfWeek::SATURDAY);
+        assert_eq!(line.manual_departures[0].days_of_week, DaysOfWeek::MONDAY);
+    }
+}
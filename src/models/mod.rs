@@ -4,9 +4,12 @@ mod junction;
 mod keyboard_shortcuts;
 mod line;
 mod node;
+mod palette;
 mod project;
 mod railway_graph;
+mod service_pattern;
 mod station;
+mod station_clipboard;
 mod track;
 mod undo;
 mod user_settings;
@@ -16,22 +19,26 @@ pub use days_of_week::DaysOfWeek;
 pub use folder::LineFolder;
 pub use junction::{Junction, RoutingRule};
 pub use keyboard_shortcuts::{
-    KeyboardShortcut, KeyboardShortcuts, ShortcutCategory, ShortcutMetadata,
+    KeyboardShortcut, KeyboardShortcuts, ShortcutCategory, ShortcutMetadata, TOGGLE_LINE_SHORTCUTS,
     setup_shortcut_handler, setup_single_shortcut_handler,
     is_mac_platform, is_windows_platform, is_input_field_target,
 };
-pub use line::{Line, LineStyle, ScheduleMode, ManualDeparture, RouteSegment, generate_random_color};
+pub use line::{Line, LineStyle, ScheduleMode, ManualDeparture, DepartureSeriesEnd, RouteSegment, RollingStockProfile, generate_random_color, recolor_lines, apply_palette, line_capacity_tph, peak_capacity_tph};
 pub use node::Node;
-pub use project::{Project, ProjectMetadata, Legend, SpacingMode, ProjectSettings, TrackHandedness, LineSortMode};
-pub use railway_graph::{RailwayGraph, Stations, Tracks, Routes, Junctions};
-pub use station::{StationNode, Platform};
-pub use track::{TrackSegment, Track, TrackDirection};
-pub use undo::{UndoManager, UndoSnapshot};
+pub use palette::{ColorVisionMode, generate_palette, assign_accessible_colors};
+pub use project::{Project, ProjectMetadata, Legend, LegendEntry, SpacingMode, ConflictWindow, ProjectSettings, GeoAnchor, GeoReference, ConflictSettings, TrackHandedness, LineSortMode, DEFAULT_GRID_SIZE};
+pub use service_pattern::ServicePattern;
+pub use railway_graph::{RailwayGraph, Stations, StationMerge, Tracks, Routes, Junctions, BlockSection, BlockSections};
+pub use station::{StationNode, Platform, DEFAULT_PLATFORM_CAPACITY};
+pub use station_clipboard::{StationClipboard, ClipboardTrack, PASTE_OFFSET};
+pub use track::{TrackSegment, Track, TrackDirection, Electrification, has_suspicious_directions, check_gradient, parse_gradient};
+pub use undo::{CheckpointEntry, HistoryEntry, UndoManager, UndoSnapshot};
 pub use user_settings::UserSettings;
 pub use view::{GraphView, ViewportState};
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
 pub enum RouteDirection {
+    #[default]
     Forward,
     Return,
 }
@@ -43,6 +43,11 @@ impl Node {
         matches!(self, Node::Junction(_))
     }
 
+    #[must_use]
+    pub fn is_depot(&self) -> bool {
+        self.as_station().is_some_and(|s| s.is_depot)
+    }
+
     #[must_use]
     pub fn as_station(&self) -> Option<&StationNode> {
         match self {
@@ -87,6 +92,11 @@ mod tests {
             passing_loop: false,
             platforms: default_platforms(),
             label_position: None,
+            is_depot: false,
+            min_dwell_secs: None,
+            label_angle: 0.0,
+            label_offset: (0.0, 0.0),
+            conflict_margin_secs: None,
         };
         let node = Node::Station(station);
 
@@ -133,6 +143,11 @@ mod tests {
             passing_loop: false,
             platforms: default_platforms(),
             label_position: None,
+            is_depot: false,
+            min_dwell_secs: None,
+            label_angle: 0.0,
+            label_offset: (0.0, 0.0),
+            conflict_margin_secs: None,
         };
         let mut node = Node::Station(station);
 
@@ -148,6 +163,11 @@ mod tests {
             passing_loop: false,
             platforms: default_platforms(),
             label_position: None,
+            is_depot: false,
+            min_dwell_secs: None,
+            label_angle: 0.0,
+            label_offset: (0.0, 0.0),
+            conflict_margin_secs: None,
         };
         let node = Node::Station(station);
 
@@ -0,0 +1,149 @@
+use serde::{Deserialize, Serialize};
+use super::Line;
+
+/// Okabe-Ito palette, distinguishable under red-green color blindness, with grey in place of
+/// its black so every color shows on the dark theme
+const DEUTERANOPIA_PRESET: [&str; 8] = [
+    "#0072B2", "#E69F00", "#009E73", "#CC79A7", "#56B4E9", "#D55E00", "#F0E442", "#999999",
+];
+
+/// Paul Tol's vibrant scheme, which keeps reds apart by lightness for protanopes
+const PROTANOPIA_PRESET: [&str; 7] = [
+    "#0077BB", "#EE7733", "#009988", "#EE3377", "#33BBEE", "#CC3311", "#BBBBBB",
+];
+
+/// Red/cyan and lightness contrasts, avoiding blue-green and yellow-violet pairs
+const TRITANOPIA_PRESET: [&str; 8] = [
+    "#C00000", "#00796B", "#FF6F61", "#80CBC4", "#FFB300", "#F48FB1", "#004D40", "#9E9E9E",
+];
+
+/// Viénot et al. (1999) projections of RGB onto what each dichromat perceives
+const DEUTERANOPIA_MATRIX: [[f64; 3]; 3] = [[0.625, 0.375, 0.0], [0.7, 0.3, 0.0], [0.0, 0.3, 0.7]];
+const PROTANOPIA_MATRIX: [[f64; 3]; 3] = [[0.567, 0.433, 0.0], [0.558, 0.442, 0.0], [0.0, 0.242, 0.758]];
+const TRITANOPIA_MATRIX: [[f64; 3]; 3] = [[0.95, 0.05, 0.0], [0.0, 0.433, 0.567], [0.0, 0.475, 0.525]];
+
+/// Type of color vision deficiency accessible line colors are chosen for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ColorVisionMode {
+    #[default]
+    Deuteranopia,
+    Protanopia,
+    Tritanopia,
+}
+
+impl ColorVisionMode {
+    pub const ALL: [ColorVisionMode; 3] = [Self::Deuteranopia, Self::Protanopia, Self::Tritanopia];
+
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Deuteranopia => "Deuteranopia",
+            Self::Protanopia => "Protanopia",
+            Self::Tritanopia => "Tritanopia",
+        }
+    }
+
+    /// Colors safe to tell apart for this deficiency
+    #[must_use]
+    pub fn preset(self) -> &'static [&'static str] {
+        match self {
+            Self::Deuteranopia => &DEUTERANOPIA_PRESET,
+            Self::Protanopia => &PROTANOPIA_PRESET,
+            Self::Tritanopia => &TRITANOPIA_PRESET,
+        }
+    }
+
+    fn matrix(self) -> [[f64; 3]; 3] {
+        match self {
+            Self::Deuteranopia => DEUTERANOPIA_MATRIX,
+            Self::Protanopia => PROTANOPIA_MATRIX,
+            Self::Tritanopia => TRITANOPIA_MATRIX,
+        }
+    }
+
+    /// Approximate how a `#RRGGBB` color appears with this deficiency
+    fn simulate(self, hex: &str) -> [f64; 3] {
+        let rgb = parse_hex(hex);
+        self.matrix().map(|row| row.iter().zip(rgb).map(|(weight, channel)| weight * channel).sum())
+    }
+}
+
+fn parse_hex(hex: &str) -> [f64; 3] {
+    let channel = |range: std::ops::Range<usize>| {
+        hex.trim_start_matches('#').get(range)
+            .and_then(|digits| u8::from_str_radix(digits, 16).ok())
+            .map_or(0.0, f64::from)
+    };
+    [channel(0..2), channel(2..4), channel(4..6)]
+}
+
+fn distance(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| (x - y).powi(2)).sum::<f64>().sqrt()
+}
+
+/// Pick `n` colors from the preset of `mode`, each as far as possible from those already
+/// chosen as seen with that deficiency. Beyond the preset size, the sequence repeats.
+#[must_use]
+pub fn generate_palette(n: usize, mode: ColorVisionMode) -> Vec<String> {
+    let preset = mode.preset();
+    let simulated: Vec<[f64; 3]> = preset.iter().map(|hex| mode.simulate(hex)).collect();
+
+    let mut order: Vec<usize> = vec![0];
+    while order.len() < preset.len() {
+        let farthest = (0..preset.len())
+            .filter(|candidate| !order.contains(candidate))
+            .max_by(|&a, &b| {
+                let spread = |candidate: usize| order.iter()
+                    .map(|&chosen| distance(simulated[candidate], simulated[chosen]))
+                    .fold(f64::INFINITY, f64::min);
+                spread(a).total_cmp(&spread(b))
+            });
+        let Some(farthest) = farthest else { break };
+        order.push(farthest);
+    }
+
+    order.iter().cycle().take(n).map(|&idx| preset[idx].to_string()).collect()
+}
+
+/// Recolor every line with an accessible palette, in list order
+pub fn assign_accessible_colors(lines: &mut [Line], mode: ColorVisionMode) {
+    let palette = generate_palette(lines.len(), mode);
+    for (line, color) in lines.iter_mut().zip(palette) {
+        line.color = color;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_generate_palette_returns_distinct_preset_colors() {
+        for mode in ColorVisionMode::ALL {
+            for n in 0..=mode.preset().len() {
+                let palette = generate_palette(n, mode);
+                assert_eq!(palette.len(), n);
+                assert_eq!(palette.iter().collect::<HashSet<_>>().len(), n, "{mode:?} repeated a color");
+                assert!(palette.iter().all(|color| mode.preset().contains(&color.as_str())));
+            }
+        }
+
+        // The second pick is the preset color that looks most different from the first
+        let mode = ColorVisionMode::Deuteranopia;
+        let palette = generate_palette(2, mode);
+        let first = mode.simulate(&palette[0]);
+        let second_distance = distance(first, mode.simulate(&palette[1]));
+        assert!(mode.preset().iter().all(|hex| distance(first, mode.simulate(hex)) <= second_distance));
+    }
+
+    #[test]
+    fn test_presets_avoid_black() {
+        // Lines drawn in black vanish against the dark theme's background
+        for mode in ColorVisionMode::ALL {
+            for hex in mode.preset() {
+                assert!(parse_hex(hex).iter().any(|channel| *channel >= 64.0), "{mode:?} includes near-black {hex}");
+            }
+        }
+    }
+}
@@ -1,8 +1,15 @@
 use serde::{Deserialize, Serialize};
-use super::{Line, LineFolder, RailwayGraph, GraphView, ViewportState};
+use super::{Line, LineFolder, RailwayGraph, GraphView, ServicePattern, ViewportState};
 use crate::storage::{CURRENT_PROJECT_VERSION, idb};
+use crate::constants::BASE_MIDNIGHT;
+use crate::conflict::ConflictKey;
+use std::collections::HashSet;
 use wasm_bindgen::prelude::*;
-use chrono::Duration;
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use petgraph::stable_graph::NodeIndex;
+
+/// Station snapping grid spacing used when a project doesn't set one
+pub const DEFAULT_GRID_SIZE: f64 = 30.0;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProjectMetadata {
@@ -10,6 +17,9 @@ pub struct ProjectMetadata {
     pub name: String,
     pub created_at: String,
     pub updated_at: String,
+    /// Stored size estimate, filled in when listing projects
+    #[serde(skip)]
+    pub size_bytes: u64,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
@@ -41,12 +51,105 @@ pub struct ProjectSettings {
     pub station_margin: Duration,
     #[serde(default)]
     pub ignore_same_direction_platform_conflicts: bool,
+    #[serde(with = "crate::models::line::option_duration_serde", default)]
+    pub near_miss_threshold: Option<Duration>,
+    /// Branches dragged with auto layout snap to multiples of this angle
+    #[serde(default = "default_angle_snap_degrees")]
+    pub angle_snap_degrees: f64,
+    /// Scale used to derive track distances from station positions
+    #[serde(default = "default_px_per_km")]
+    pub px_per_km: f64,
+    /// Smallest radius used for line curves at junctions and stations in line mode
+    #[serde(default = "default_min_curve_radius")]
+    pub min_curve_radius: f64,
+    /// Hour at the left edge of the time graph at default zoom
+    #[serde(default)]
+    pub graph_start_hour: u32,
+    /// Hour at the right edge of the time graph at default zoom; values past 24 reach into the next day
+    #[serde(default = "default_graph_end_hour")]
+    pub graph_end_hour: u32,
+    /// Spacing of the station snapping grid in the infrastructure editor; 0 disables snapping
+    #[serde(default = "default_grid_size")]
+    pub grid_size: f64,
+    /// Draw the snapping grid behind the infrastructure map
+    #[serde(default = "default_show_grid")]
+    pub show_grid: bool,
+    /// Keep lines a fixed on-screen width in line mode instead of scaling them with zoom
+    #[serde(default)]
+    pub constant_line_width: bool,
+    /// Two stations pinned to real-world locations so exports can be placed on a map
+    #[serde(default)]
+    pub geo_reference: Option<GeoReference>,
+    /// Real week the simulated Monday to Sunday stands for, given by any of its dates.
+    /// Operating periods and service pattern exceptions are real dates checked against it;
+    /// without one they are read as dates of the week starting `BASE_DATE`
+    #[serde(default)]
+    pub reference_week: Option<NaiveDate>,
+    /// Hours conflict detection looks at; limiting it keeps large timetables responsive
+    #[serde(default)]
+    pub conflict_window: ConflictWindow,
+}
+
+/// A station whose real-world location is known
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GeoAnchor {
+    pub station: NodeIndex,
+    /// Longitude and latitude in degrees
+    pub lon_lat: (f64, f64),
+}
+
+/// Two anchored stations that fix the canvas's scale, rotation and placement on the globe
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GeoReference {
+    pub first: GeoAnchor,
+    pub second: GeoAnchor,
+}
+
+impl GeoReference {
+    /// Whether either anchor is pinned to the station
+    #[must_use]
+    pub fn anchors(&self, station: NodeIndex) -> bool {
+        self.first.station == station || self.second.station == station
+    }
+
+    /// Moves anchors from `remove` onto `keep` after the two stations were merged
+    pub fn remap_merged_station(&mut self, keep: NodeIndex, remove: NodeIndex) {
+        for anchor in [&mut self.first, &mut self.second] {
+            if anchor.station == remove {
+                anchor.station = keep;
+            }
+        }
+    }
 }
 
 fn default_node_distance() -> f64 {
     2.0
 }
 
+fn default_angle_snap_degrees() -> f64 {
+    45.0
+}
+
+fn default_px_per_km() -> f64 {
+    30.0
+}
+
+fn default_min_curve_radius() -> f64 {
+    20.0
+}
+
+fn default_graph_end_hour() -> u32 {
+    48
+}
+
+fn default_grid_size() -> f64 {
+    DEFAULT_GRID_SIZE
+}
+
+fn default_show_grid() -> bool {
+    true
+}
+
 fn default_minimum_separation() -> Duration {
     Duration::seconds(30)
 }
@@ -64,8 +167,80 @@ impl Default for ProjectSettings {
             minimum_separation: default_minimum_separation(),
             station_margin: default_station_margin(),
             ignore_same_direction_platform_conflicts: false,
+            near_miss_threshold: None,
+            angle_snap_degrees: default_angle_snap_degrees(),
+            px_per_km: default_px_per_km(),
+            min_curve_radius: default_min_curve_radius(),
+            graph_start_hour: 0,
+            graph_end_hour: default_graph_end_hour(),
+            grid_size: default_grid_size(),
+            show_grid: default_show_grid(),
+            constant_line_width: false,
+            geo_reference: None,
+            reference_week: None,
+            conflict_window: ConflictWindow::AllTimes,
+        }
+    }
+}
+
+/// Thresholds conflict detection runs with, as chosen in the project's settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConflictSettings {
+    pub station_margin: Duration,
+    pub minimum_separation: Duration,
+    pub ignore_same_direction_platform_conflicts: bool,
+    pub near_miss_threshold: Option<Duration>,
+}
+
+impl Default for ConflictSettings {
+    fn default() -> Self {
+        ProjectSettings::default().conflict_settings()
+    }
+}
+
+impl ProjectSettings {
+    /// The settings that affect conflict detection
+    #[must_use]
+    pub fn conflict_settings(&self) -> ConflictSettings {
+        ConflictSettings {
+            station_margin: self.station_margin,
+            minimum_separation: self.minimum_separation,
+            ignore_same_direction_platform_conflicts: self.ignore_same_direction_platform_conflicts,
+            near_miss_threshold: self.near_miss_threshold,
+        }
+    }
+
+    /// Times conflict detection is limited to, when it is limited to the time axis range
+    #[must_use]
+    pub fn conflict_time_window(&self) -> Option<(NaiveDateTime, NaiveDateTime)> {
+        (self.conflict_window == ConflictWindow::TimeAxis).then(|| (
+            BASE_MIDNIGHT + Duration::hours(i64::from(self.graph_start_hour)),
+            BASE_MIDNIGHT + Duration::hours(i64::from(self.graph_end_hour)),
+        ))
+    }
+
+    /// Drops the geo-reference if it anchors a deleted station, since the freed
+    /// node index may later be reused by an unrelated station
+    pub fn forget_deleted_station(&mut self, station: NodeIndex) {
+        if self.geo_reference.is_some_and(|reference| reference.anchors(station)) {
+            self.geo_reference = None;
         }
     }
+
+    /// Size of one grid square used for layout spacing, even when snapping is disabled
+    #[must_use]
+    pub fn grid_square_size(&self) -> f64 {
+        if self.grid_size > 0.0 { self.grid_size } else { DEFAULT_GRID_SIZE }
+    }
+}
+
+/// Which hours of the timetable conflict detection covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum ConflictWindow {
+    #[default]
+    AllTimes,
+    /// Only the hours of the time graph's time axis range
+    TimeAxis,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
@@ -81,6 +256,10 @@ pub struct Legend {
     pub show_line_blocks: bool,
     #[serde(default)]
     pub spacing_mode: SpacingMode,
+    #[serde(default)]
+    pub entries: Vec<LegendEntry>,
+    #[serde(default)]
+    pub show_headway_bands: bool,
 }
 
 impl Default for Legend {
@@ -89,10 +268,57 @@ impl Default for Legend {
             show_conflicts: true,
             show_line_blocks: false,
             spacing_mode: SpacingMode::default(),
+            entries: Vec::new(),
+            show_headway_bands: false,
         }
     }
 }
 
+/// A colour swatch in the legend, either generated from a line or added by hand
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LegendEntry {
+    pub label: String,
+    pub color: String,
+    /// Line the entry was generated from; `None` for manual annotations
+    #[serde(default)]
+    pub line_id: Option<uuid::Uuid>,
+}
+
+impl LegendEntry {
+    /// Build entries for the visible lines, ordered by `sort_index` then name
+    #[must_use]
+    pub fn from_lines(lines: &[Line]) -> Vec<Self> {
+        let mut visible: Vec<&Line> = lines.iter().filter(|line| line.visible).collect();
+        visible.sort_by(|a, b| {
+            let a_index = a.sort_index.unwrap_or(f64::INFINITY);
+            let b_index = b.sort_index.unwrap_or(f64::INFINITY);
+            a_index.total_cmp(&b_index).then_with(|| a.name.cmp(&b.name))
+        });
+
+        visible
+            .into_iter()
+            .map(|line| Self {
+                label: line.name.clone(),
+                color: line.color.clone(),
+                line_id: Some(line.id),
+            })
+            .collect()
+    }
+
+    #[must_use]
+    pub fn is_manual(&self) -> bool {
+        self.line_id.is_none()
+    }
+}
+
+impl Legend {
+    /// Replace the line entries with ones generated from `lines`, keeping manual annotations after them
+    pub fn regenerate_entries(&mut self, lines: &[Line]) {
+        let manual = self.entries.iter().filter(|entry| entry.is_manual()).cloned();
+        self.entries = LegendEntry::from_lines(lines).into_iter().chain(manual).collect();
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Project {
     #[serde(flatten)]
@@ -111,6 +337,11 @@ pub struct Project {
     pub infrastructure_viewport: ViewportState,
     #[serde(default)]
     pub folders: Vec<LineFolder>,
+    #[serde(default)]
+    pub service_patterns: Vec<ServicePattern>,
+    /// Conflicts the planner has accepted, hidden from the conflict list
+    #[serde(default)]
+    pub acknowledged_conflicts: HashSet<ConflictKey>,
 }
 
 impl Project {
@@ -123,6 +354,7 @@ impl Project {
                 name: "Untitled Project".to_string(),
                 created_at: now.clone(),
                 updated_at: now,
+                size_bytes: 0,
             },
             lines: Vec::new(),
             graph: RailwayGraph::new(),
@@ -132,6 +364,8 @@ impl Project {
             active_tab_id: None,
             infrastructure_viewport: ViewportState::default(),
             folders: Vec::new(),
+            service_patterns: Vec::new(),
+            acknowledged_conflicts: HashSet::new(),
         }
     }
 
@@ -144,6 +378,7 @@ impl Project {
                 name: "Untitled Project".to_string(),
                 created_at: now.clone(),
                 updated_at: now,
+                size_bytes: 0,
             },
             lines,
             graph,
@@ -153,6 +388,8 @@ impl Project {
             active_tab_id: None,
             infrastructure_viewport: ViewportState::default(),
             folders: Vec::new(),
+            service_patterns: Vec::new(),
+            acknowledged_conflicts: HashSet::new(),
         }
     }
 
@@ -165,6 +402,7 @@ impl Project {
                 name,
                 created_at: now.clone(),
                 updated_at: now,
+                size_bytes: 0,
             },
             lines: Vec::new(),
             graph: RailwayGraph::new(),
@@ -174,6 +412,8 @@ impl Project {
             active_tab_id: None,
             infrastructure_viewport: ViewportState::default(),
             folders: Vec::new(),
+            service_patterns: Vec::new(),
+            acknowledged_conflicts: HashSet::new(),
         }
     }
 
@@ -190,6 +430,7 @@ impl Project {
                 name: new_name,
                 created_at: now.clone(),
                 updated_at: now,
+                size_bytes: 0,
             },
             lines: self.lines.clone(),
             graph: self.graph.clone(),
@@ -199,6 +440,8 @@ impl Project {
             active_tab_id: self.active_tab_id.clone(),
             infrastructure_viewport: self.infrastructure_viewport.clone(),
             folders: self.folders.clone(),
+            service_patterns: self.service_patterns.clone(),
+            acknowledged_conflicts: self.acknowledged_conflicts.clone(),
         }
     }
 }
@@ -380,8 +623,9 @@ impl Project {
 
                 // Skip version bytes and deserialize only metadata
                 let project_bytes = &bytes[4..];
-                let metadata: ProjectMetadata = rmp_serde::from_slice(project_bytes)
+                let mut metadata: ProjectMetadata = rmp_serde::from_slice(project_bytes)
                     .map_err(|e| format!("Failed to parse project metadata: {e}"))?;
+                metadata.size_bytes = bytes.len() as u64;
                 projects.push(metadata);
             }
         }
@@ -474,6 +718,7 @@ impl Project {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::HashMap;
 
     #[test]
     fn test_project_empty() {
@@ -484,6 +729,37 @@ mod tests {
         assert!(project.active_tab_id.is_none());
     }
 
+    #[test]
+    fn test_conflict_time_window_follows_time_axis() {
+        let mut settings = ProjectSettings { graph_start_hour: 4, graph_end_hour: 28, ..ProjectSettings::default() };
+        assert_eq!(settings.conflict_time_window(), None);
+
+        settings.conflict_window = ConflictWindow::TimeAxis;
+        assert_eq!(
+            settings.conflict_time_window(),
+            Some((BASE_MIDNIGHT + Duration::hours(4), BASE_MIDNIGHT + Duration::hours(28))),
+        );
+    }
+
+    #[test]
+    fn test_geo_reference_follows_merged_and_deleted_stations() {
+        let anchor = |station: usize, lon_lat| GeoAnchor { station: NodeIndex::new(station), lon_lat };
+        let mut settings = ProjectSettings {
+            geo_reference: Some(GeoReference { first: anchor(0, (10.0, 60.0)), second: anchor(1, (11.0, 60.0)) }),
+            ..ProjectSettings::default()
+        };
+
+        let reference = settings.geo_reference.as_mut().expect("set above");
+        reference.remap_merged_station(NodeIndex::new(2), NodeIndex::new(1));
+        assert_eq!(reference.second, anchor(2, (11.0, 60.0)));
+        assert_eq!(reference.first, anchor(0, (10.0, 60.0)));
+
+        settings.forget_deleted_station(NodeIndex::new(1));
+        assert!(settings.geo_reference.is_some());
+        settings.forget_deleted_station(NodeIndex::new(2));
+        assert!(settings.geo_reference.is_none());
+    }
+
     #[test]
     fn test_project_new_with_name() {
         let name = "Test Project".to_string();
@@ -524,6 +800,7 @@ mod tests {
             name: "Test Project".to_string(),
             created_at: "2024-01-01T00:00:00Z".to_string(),
             updated_at: "2024-01-02T00:00:00Z".to_string(),
+            size_bytes: 0,
         };
 
         // Test serialization round-trip
@@ -552,6 +829,21 @@ mod tests {
         assert_eq!(metadata.updated_at, project.metadata.updated_at);
     }
 
+    #[test]
+    fn test_project_round_trips_conflict_settings() {
+        let mut project = Project::new_with_name("Margins".to_string());
+        project.settings.station_margin = Duration::seconds(75);
+        project.settings.minimum_separation = Duration::seconds(120);
+        project.settings.ignore_same_direction_platform_conflicts = true;
+        project.settings.near_miss_threshold = Some(Duration::seconds(180));
+
+        let serialized = rmp_serde::to_vec(&project).expect("Failed to serialize project");
+        let restored: Project = rmp_serde::from_slice(&serialized).expect("Failed to deserialize project");
+
+        assert_eq!(restored.settings.conflict_settings(), project.settings.conflict_settings());
+        assert_ne!(restored.settings.conflict_settings(), ConflictSettings::default());
+    }
+
     #[test]
     fn test_project_serialization_round_trip() {
         let original = Project::new_with_name("Round Trip Test".to_string());
@@ -569,4 +861,52 @@ mod tests {
         assert_eq!(deserialized.lines.len(), original.lines.len());
         assert_eq!(deserialized.views.len(), original.views.len());
     }
+
+    #[test]
+    fn test_view_viewport_survives_save_and_reload() {
+        let mut project = Project::new_with_name("Viewport Test".to_string());
+        let view = GraphView::default_main_line(&project.graph);
+        let view_id = view.id;
+        let viewport = ViewportState {
+            zoom_level: 2.5,
+            zoom_level_x: Some(1.5),
+            pan_offset_x: -120.0,
+            pan_offset_y: 48.0,
+            ..ViewportState::default()
+        };
+        let viewports = HashMap::from([(view_id, viewport)]);
+        project.views = vec![view.with_saved_viewport(&viewports)];
+
+        let bytes = project.serialize_to_bytes().expect("Failed to serialize project");
+        let reloaded = Project::deserialize_from_bytes(&bytes).expect("Failed to deserialize project");
+
+        let restored = &GraphView::viewports(&reloaded.views)[&view_id];
+        assert_eq!(restored.zoom_level, 2.5);
+        assert_eq!(restored.zoom_level_x, Some(1.5));
+        assert_eq!(restored.pan_offset_x, -120.0);
+        assert_eq!(restored.pan_offset_y, 48.0);
+    }
+
+    #[test]
+    fn test_legend_entries_from_visible_sorted_lines() {
+        let names = ["Zeta", "Alpha", "Hidden", "Beta"].map(String::from);
+        let mut lines = Line::create_from_ids(&names, 0);
+        lines[0].sort_index = Some(0.0);
+        lines[1].sort_index = Some(2.0);
+        lines[2].sort_index = Some(1.0);
+        lines[2].visible = false;
+        lines[3].sort_index = Some(1.5);
+
+        let mut legend = Legend {
+            entries: vec![LegendEntry { label: "Planned".to_string(), color: "#888".to_string(), line_id: None }],
+            ..Legend::default()
+        };
+        legend.regenerate_entries(&lines);
+
+        let labels: Vec<&str> = legend.entries.iter().map(|entry| entry.label.as_str()).collect();
+        assert_eq!(labels, vec!["Zeta", "Beta", "Alpha", "Planned"]);
+        assert_eq!(legend.entries[0].color, lines[0].color);
+        assert_eq!(legend.entries[0].line_id, Some(lines[0].id));
+        assert!(legend.entries[3].is_manual());
+    }
 }
@@ -7,17 +7,56 @@ use super::node::Node;
 use super::track::TrackSegment;
 use super::project::SpacingMode;
 
+pub mod block_sections;
 pub mod junctions;
 pub mod stations;
 pub mod tracks;
 pub mod routes;
 
 // Re-export extension traits
+pub use block_sections::{BlockSection, BlockSections};
 pub use junctions::Junctions;
-pub use stations::Stations;
+pub use stations::{Stations, StationMerge};
 pub use tracks::Tracks;
 pub use routes::Routes;
 
+/// Smallest gap between stations in distance-based spacing, as a fraction of the average known gap
+const MIN_DISTANCE_SPACING_FRACTION: f64 = 0.25;
+
+/// Map distances between consecutive stations to Y positions spanning `total_height`.
+/// Unknown or very short gaps get a minimum spacing relative to the average known gap,
+/// so their stations stay readable.
+#[allow(clippy::cast_precision_loss)]
+fn distances_to_positions(segment_distances: &[Option<f64>], total_height: f64, top_margin: f64) -> Vec<f64> {
+    let known: Vec<f64> = segment_distances.iter().flatten().copied().collect();
+    let average = if known.is_empty() {
+        1.0
+    } else {
+        known.iter().sum::<f64>() / known.len() as f64
+    };
+    let min_spacing = average * MIN_DISTANCE_SPACING_FRACTION;
+
+    let mut cumulative_distances = vec![0.0];
+    for distance in segment_distances {
+        let spacing = distance.map_or(min_spacing, |d| d.max(min_spacing));
+        let last_cumulative = cumulative_distances.last().copied().unwrap_or(0.0);
+        cumulative_distances.push(last_cumulative + spacing);
+    }
+
+    // Normalize to fit within total_height
+    let total_distance = cumulative_distances.last().copied().unwrap_or(0.0);
+    let scale = if total_distance > 0.0 {
+        total_height / total_distance
+    } else {
+        1.0
+    };
+
+    cumulative_distances
+        .iter()
+        .map(|&cum_dist| top_margin + (cum_dist * scale))
+        .collect()
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RailwayGraph {
     #[serde(with = "graph_serde")]
@@ -25,6 +64,8 @@ pub struct RailwayGraph {
     pub station_name_to_index: HashMap<String, NodeIndex>,
     #[serde(default)]
     pub branch_angles: HashMap<(usize, usize), f64>,
+    #[serde(default)]
+    pub block_sections: Vec<BlockSection>,
 }
 
 impl RailwayGraph {
@@ -34,6 +75,7 @@ impl RailwayGraph {
             graph: StableGraph::new(),
             station_name_to_index: HashMap::new(),
             branch_angles: HashMap::new(),
+            block_sections: Vec::new(),
         }
     }
 
@@ -75,64 +117,22 @@ impl RailwayGraph {
         }
     }
 
-    #[allow(clippy::cast_precision_loss)]
     fn calculate_distance_based_positions(
         &self,
         stations: &[(NodeIndex, Node)],
         total_height: f64,
         top_margin: f64,
     ) -> Vec<f64> {
-        // First pass: collect all valid distances to calculate average
-        let mut valid_distances = Vec::new();
-        for i in 0..stations.len() - 1 {
-            let from_idx = stations[i].0;
-            let to_idx = stations[i + 1].0;
-            let distance = self.find_shortest_distance(from_idx, to_idx);
-            if distance > 0.0 {
-                valid_distances.push(distance);
-            }
-        }
+        let segment_distances: Vec<Option<f64>> = stations
+            .windows(2)
+            .map(|pair| self.find_shortest_distance(pair[0].0, pair[1].0))
+            .collect();
 
-        // Calculate fallback distance (average of valid distances, or 1.0 if none)
-        let fallback_distance = if valid_distances.is_empty() {
-            1.0
-        } else {
-            valid_distances.iter().sum::<f64>() / valid_distances.len() as f64
-        };
-
-        // Second pass: build cumulative distances using fallback for invalid segments
-        let mut cumulative_distances = vec![0.0];
-        for i in 0..stations.len() - 1 {
-            let from_idx = stations[i].0;
-            let to_idx = stations[i + 1].0;
-
-            let distance = self.find_shortest_distance(from_idx, to_idx);
-            let segment_distance = if distance > 0.0 {
-                distance
-            } else {
-                fallback_distance
-            };
-
-            let last_cumulative = cumulative_distances.last().copied().unwrap_or(0.0);
-            cumulative_distances.push(last_cumulative + segment_distance);
-        }
-
-        // Normalize to fit within total_height
-        let total_distance = cumulative_distances.last().copied().unwrap_or(1.0);
-        let scale = if total_distance > 0.0 {
-            total_height / total_distance
-        } else {
-            1.0
-        };
-
-        // Convert cumulative distances to Y positions (centered in each station's area)
-        cumulative_distances
-            .iter()
-            .map(|&cum_dist| top_margin + (cum_dist * scale))
-            .collect()
+        distances_to_positions(&segment_distances, total_height, top_margin)
     }
 
-    fn find_shortest_distance(&self, from: NodeIndex, to: NodeIndex) -> f64 {
+    /// Shortest known track distance between two nodes, or `None` if no distances are set along the way
+    fn find_shortest_distance(&self, from: NodeIndex, to: NodeIndex) -> Option<f64> {
         // Use Dijkstra's algorithm with distance as edge weight
         let distances = dijkstra(
             &self.graph,
@@ -142,11 +142,11 @@ impl RailwayGraph {
                 edge.weight()
                     .distance
                     .filter(|&d| d > 0.0) // Only use valid positive distances
-                    .unwrap_or(1.0) // Default to 1.0 for missing distances (normalization is handled in calculate_distance_based_positions)
+                    .unwrap_or(0.0) // Missing distances contribute nothing; fallback spacing is applied by the caller
             },
         );
 
-        distances.get(&to).copied().unwrap_or(0.0)
+        distances.get(&to).copied().filter(|&d| d > 0.0)
     }
 
     /// Finds the longest simple path in the graph (path with most nodes, no cycles).
@@ -352,4 +352,43 @@ mod tests {
         assert_eq!(graph.graph.node_count(), 0);
         assert_eq!(graph.graph.edge_count(), 0);
     }
+
+    #[test]
+    fn test_distances_to_positions_is_proportional() {
+        // 1 km then 3 km over a 400 px tall graph
+        let positions = distances_to_positions(&[Some(1.0), Some(3.0)], 400.0, 10.0);
+        assert_eq!(positions, vec![10.0, 110.0, 410.0]);
+    }
+
+    #[test]
+    fn test_distances_to_positions_gives_unknown_gaps_minimum_spacing() {
+        // Average known gap is 2 km, so the unknown gap gets 0.5 km
+        let positions = distances_to_positions(&[Some(2.0), None, Some(2.0)], 450.0, 0.0);
+        assert_eq!(positions, vec![0.0, 200.0, 250.0, 450.0]);
+
+        // Nothing known falls back to equal spacing
+        let positions = distances_to_positions(&[None, None], 100.0, 0.0);
+        assert_eq!(positions, vec![0.0, 50.0, 100.0]);
+    }
+
+    #[test]
+    fn test_distance_based_positions_follow_track_distances() {
+        use crate::models::{Stations, Tracks, Track, TrackDirection};
+
+        let mut graph = RailwayGraph::new();
+        let a = graph.add_or_get_station("A".to_string());
+        let b = graph.add_or_get_station("B".to_string());
+        let c = graph.add_or_get_station("C".to_string());
+        let ab = graph.add_track(a, b, vec![Track { direction: TrackDirection::Bidirectional }]);
+        let bc = graph.add_track(b, c, vec![Track { direction: TrackDirection::Bidirectional }]);
+        for (edge, distance) in [(ab, 1.0), (bc, 4.0)] {
+            if let Some(segment) = graph.graph.edge_weight_mut(edge) {
+                segment.distance = Some(distance);
+            }
+        }
+
+        let stations = graph.get_all_nodes_ordered();
+        let positions = graph.calculate_station_positions(&stations, SpacingMode::DistanceBased, 500.0, 0.0);
+        assert_eq!(positions, vec![0.0, 100.0, 500.0]);
+    }
 }
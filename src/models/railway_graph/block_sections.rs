@@ -0,0 +1,97 @@
+use petgraph::stable_graph::EdgeIndex;
+use serde::{Deserialize, Serialize};
+use super::RailwayGraph;
+
+/// A signalling block covering one or more track edges.
+/// Only one train may occupy a block at a time, regardless of which edge within it it is on.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BlockSection {
+    pub id: usize,
+    pub edges: Vec<EdgeIndex>,
+}
+
+/// Extension trait for signalling block operations on `RailwayGraph`
+pub trait BlockSections {
+    /// Assign edges to a block, creating the block if needed.
+    /// Edges are removed from any block they previously belonged to, and blocks left empty are dropped.
+    fn assign_edges_to_block(&mut self, block_id: usize, edges: &[EdgeIndex]);
+
+    /// Get the id of the block containing an edge, if it has been assigned to one
+    fn block_for_edge(&self, edge: EdgeIndex) -> Option<usize>;
+
+    /// Remove edges from their blocks, dropping blocks left empty.
+    /// Call before removing edges from the graph, as their indices may be reused.
+    fn remove_edges_from_blocks(&mut self, edges: &[EdgeIndex]);
+}
+
+impl BlockSections for RailwayGraph {
+    fn assign_edges_to_block(&mut self, block_id: usize, edges: &[EdgeIndex]) {
+        for block in &mut self.block_sections {
+            block.edges.retain(|edge| !edges.contains(edge));
+        }
+
+        if let Some(block) = self.block_sections.iter_mut().find(|b| b.id == block_id) {
+            block.edges.extend_from_slice(edges);
+        } else {
+            self.block_sections.push(BlockSection { id: block_id, edges: edges.to_vec() });
+        }
+
+        self.block_sections.retain(|block| !block.edges.is_empty());
+    }
+
+    fn block_for_edge(&self, edge: EdgeIndex) -> Option<usize> {
+        self.block_sections
+            .iter()
+            .find(|block| block.edges.contains(&edge))
+            .map(|block| block.id)
+    }
+
+    fn remove_edges_from_blocks(&mut self, edges: &[EdgeIndex]) {
+        for block in &mut self.block_sections {
+            block.edges.retain(|edge| !edges.contains(edge));
+        }
+        self.block_sections.retain(|block| !block.edges.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Stations, Track, TrackDirection, Tracks};
+
+    #[test]
+    fn test_assign_edges_to_block_moves_edges_between_blocks() {
+        let mut graph = RailwayGraph::new();
+        let a = graph.add_or_get_station("A".to_string());
+        let b = graph.add_or_get_station("B".to_string());
+        let c = graph.add_or_get_station("C".to_string());
+        let ab = graph.add_track(a, b, vec![Track { direction: TrackDirection::Bidirectional }]);
+        let bc = graph.add_track(b, c, vec![Track { direction: TrackDirection::Bidirectional }]);
+
+        graph.assign_edges_to_block(1, &[ab, bc]);
+        assert_eq!(graph.block_for_edge(ab), Some(1));
+        assert_eq!(graph.block_for_edge(bc), Some(1));
+
+        graph.assign_edges_to_block(2, &[bc]);
+        assert_eq!(graph.block_for_edge(ab), Some(1));
+        assert_eq!(graph.block_for_edge(bc), Some(2));
+
+        graph.assign_edges_to_block(2, &[ab]);
+        assert_eq!(graph.block_sections.len(), 1);
+    }
+
+    #[test]
+    fn test_delete_station_prunes_its_edges_from_blocks() {
+        let mut graph = RailwayGraph::new();
+        let a = graph.add_or_get_station("A".to_string());
+        let b = graph.add_or_get_station("B".to_string());
+        let c = graph.add_or_get_station("C".to_string());
+        let ab = graph.add_track(a, b, vec![Track { direction: TrackDirection::Bidirectional }]);
+        let bc = graph.add_track(b, c, vec![Track { direction: TrackDirection::Bidirectional }]);
+        graph.assign_edges_to_block(1, &[ab, bc]);
+
+        graph.delete_station(b);
+
+        assert!(graph.block_sections.iter().all(|block| !block.edges.contains(&ab) && !block.edges.contains(&bc)));
+    }
+}
@@ -1,6 +1,7 @@
-use petgraph::stable_graph::NodeIndex;
+use petgraph::stable_graph::{EdgeIndex, NodeIndex};
 use super::RailwayGraph;
 use super::tracks::Tracks;
+use super::block_sections::BlockSections;
 use crate::models::junction::Junction;
 use crate::models::node::Node;
 
@@ -45,6 +46,15 @@ pub trait Junctions {
     /// If the junction has no position set or if `force` is true, calculates a position
     /// by averaging the positions of connected stations. Returns true if position was updated.
     fn interpolate_junction_position(&mut self, index: NodeIndex, force: bool) -> bool;
+
+    /// Edges connected to a junction, ordered by the angle of their far end around it
+    ///
+    /// Edges whose far end has no position are left out, as are all edges when the
+    /// junction itself has no position.
+    fn junction_legs_by_angle(&self, index: NodeIndex) -> Vec<EdgeIndex>;
+
+    /// Drop routing rules at every junction that reference edges no longer connected to it
+    fn prune_junction_rules(&mut self);
 }
 
 impl Junctions for RailwayGraph {
@@ -52,6 +62,17 @@ impl Junctions for RailwayGraph {
         self.graph.add_node(Node::Junction(junction))
     }
 
+    fn prune_junction_rules(&mut self) {
+        let junctions: Vec<NodeIndex> = self.graph.node_indices().filter(|&idx| self.is_junction(idx)).collect();
+        for idx in junctions {
+            let Some(mut junction) = self.get_junction(idx).cloned() else { continue };
+            junction.prune_invalid_rules(self, idx);
+            if let Some(existing) = self.get_junction_mut(idx) {
+                existing.routing_rules = junction.routing_rules;
+            }
+        }
+    }
+
     fn get_junction(&self, index: NodeIndex) -> Option<&Junction> {
         self.graph.node_weight(index).and_then(|node| node.as_junction())
     }
@@ -166,6 +187,8 @@ impl Junctions for RailwayGraph {
                     .map(|e| e.id().index())
             )
             .collect();
+        let removed: Vec<EdgeIndex> = removed_edges.iter().map(|&e| EdgeIndex::new(e)).collect();
+        self.remove_edges_from_blocks(&removed);
 
         // Remove the junction node (this also removes all connected edges)
         self.graph.remove_node(index);
@@ -325,6 +348,28 @@ impl Junctions for RailwayGraph {
 
         true
     }
+
+    fn junction_legs_by_angle(&self, index: NodeIndex) -> Vec<EdgeIndex> {
+        use petgraph::visit::EdgeRef;
+        use petgraph::Direction;
+        use super::stations::Stations;
+
+        let Some((jx, jy)) = self.get_station_position(index) else {
+            return Vec::new();
+        };
+
+        let mut legs: Vec<(f64, EdgeIndex)> = self.graph.edges(index)
+            .map(|edge| (edge.id(), edge.target()))
+            .chain(self.graph.edges_directed(index, Direction::Incoming).map(|edge| (edge.id(), edge.source())))
+            .filter_map(|(edge, other)| {
+                let (x, y) = self.get_station_position(other)?;
+                Some(((y - jy).atan2(x - jx), edge))
+            })
+            .collect();
+
+        legs.sort_by(|a, b| a.0.total_cmp(&b.0));
+        legs.into_iter().map(|(_, edge)| edge).collect()
+    }
 }
 
 #[cfg(test)]
@@ -577,6 +622,7 @@ mod tests {
                 destination_platform: 0,
                 duration: Some(Duration::minutes(5)),
                 wait_time: Duration::seconds(0),
+                reverses: false,
             },
             RouteSegment {
                 edge_index: e2.index(),
@@ -585,6 +631,7 @@ mod tests {
                 destination_platform: 0,
                 duration: Some(Duration::minutes(5)),
                 wait_time: Duration::seconds(30),
+                reverses: false,
             },
         ];
 
@@ -629,6 +676,7 @@ mod tests {
                 destination_platform: 0,
                 duration: Some(Duration::minutes(5)),
                 wait_time: Duration::seconds(0),
+                reverses: false,
             },
             RouteSegment {
                 edge_index: e2.index(),
@@ -637,6 +685,7 @@ mod tests {
                 destination_platform: 0,
                 duration: Some(Duration::minutes(5)),
                 wait_time: Duration::seconds(30),
+                reverses: false,
             },
         ];
 
@@ -817,6 +866,7 @@ mod tests {
                 destination_platform: 0,
                 duration: Some(Duration::minutes(5)),
                 wait_time: Duration::seconds(0),
+                reverses: false,
             },
             RouteSegment {
                 edge_index: e2.index(),
@@ -825,6 +875,7 @@ mod tests {
                 destination_platform: 0,
                 duration: Some(Duration::minutes(5)),
                 wait_time: Duration::seconds(30),
+                reverses: false,
             },
         ];
 
@@ -839,6 +890,7 @@ mod tests {
                 destination_platform: 0,
                 duration: Some(Duration::minutes(5)),
                 wait_time: Duration::seconds(0),
+                reverses: false,
             },
             RouteSegment {
                 edge_index: e1_rev.index(),
@@ -847,6 +899,7 @@ mod tests {
                 destination_platform: 0,
                 duration: Some(Duration::minutes(5)),
                 wait_time: Duration::seconds(30),
+                reverses: false,
             },
         ];
 
@@ -1010,4 +1063,30 @@ mod tests {
         let updated = graph.interpolate_junction_position(s, false);
         assert!(!updated);
     }
+
+    #[test]
+    fn test_junction_legs_by_angle() {
+        let mut graph = RailwayGraph::new();
+        let east = graph.add_or_get_station("East".to_string());
+        let north = graph.add_or_get_station("North".to_string());
+        let west = graph.add_or_get_station("West".to_string());
+        let unplaced = graph.add_or_get_station("Unplaced".to_string());
+        graph.set_station_position(east, (10.0, 0.0));
+        graph.set_station_position(north, (0.0, 10.0));
+        graph.set_station_position(west, (-10.0, 0.0));
+
+        let j = graph.add_junction(Junction {
+            name: None,
+            position: Some((0.0, 0.0)),
+            routing_rules: vec![],
+            label_position: None,
+        });
+        let track = || vec![Track { direction: TrackDirection::Bidirectional }];
+        let to_west = graph.add_track(j, west, track());
+        let from_east = graph.add_track(east, j, track());
+        let to_north = graph.add_track(j, north, track());
+        graph.add_track(j, unplaced, track());
+
+        assert_eq!(graph.junction_legs_by_angle(j), vec![from_east, to_north, to_west]);
+    }
 }
@@ -49,6 +49,24 @@ pub trait Routes {
         &self,
         waypoints: &[NodeIndex],
     ) -> Option<Vec<EdgeIndex>>;
+
+    /// Check that no segment of a route runs against the direction of a one-way track
+    ///
+    /// # Errors
+    /// Returns a message naming the first segment that cannot be traversed in its travel direction
+    fn validate_route_directions(&self, route: &[crate::models::RouteSegment]) -> Result<(), String>;
+
+    /// Find the shortest route from `from` to `to`, passing through each `via` node in order
+    /// Uses track distances when every edge has one, otherwise the number of segments.
+    /// Respects one-way tracks and junction routing rules. Platforms and timings are left
+    /// at their defaults for the caller to fill in
+    /// Returns None if any leg is unreachable
+    fn shortest_route(
+        &self,
+        from: NodeIndex,
+        to: NodeIndex,
+        via: &[NodeIndex],
+    ) -> Option<Vec<crate::models::RouteSegment>>;
 }
 
 impl Routes for RailwayGraph {
@@ -359,9 +377,137 @@ impl Routes for RailwayGraph {
 
         Some(complete_path)
     }
+
+    fn shortest_route(
+        &self,
+        from: NodeIndex,
+        to: NodeIndex,
+        via: &[NodeIndex],
+    ) -> Option<Vec<crate::models::RouteSegment>> {
+        use super::tracks::Tracks;
+
+        let use_distances = self.graph.edge_weights().all(|segment| segment.distance.is_some());
+        let waypoints: Vec<NodeIndex> = std::iter::once(from)
+            .chain(via.iter().copied())
+            .chain(std::iter::once(to))
+            .collect();
+
+        let mut route = Vec::new();
+        let mut incoming_edge = None;
+        for leg in waypoints.windows(2) {
+            for (edge, leaving) in self.shortest_leg(leg[0], leg[1], incoming_edge, use_distances)? {
+                let traveling_backward = self.get_track_endpoints(edge).is_some_and(|(source, _)| source != leaving);
+                route.push(crate::models::RouteSegment {
+                    edge_index: edge.index(),
+                    track_index: self.select_track_for_direction(edge, traveling_backward),
+                    origin_platform: 0,
+                    destination_platform: 0,
+                    duration: None,
+                    wait_time: chrono::Duration::zero(),
+                    reverses: false,
+                });
+                incoming_edge = Some(edge);
+            }
+        }
+
+        Some(route)
+    }
+
+    fn validate_route_directions(&self, route: &[crate::models::RouteSegment]) -> Result<(), String> {
+        use super::tracks::Tracks;
+
+        let mut current_node: Option<NodeIndex> = None;
+        for (idx, segment) in route.iter().enumerate() {
+            let edge_idx = EdgeIndex::new(segment.edge_index);
+            let Some((edge_from, edge_to)) = self.get_track_endpoints(edge_idx) else {
+                continue;
+            };
+            let (travel_from, travel_to) = self.determine_travel_direction(
+                edge_from,
+                edge_to,
+                current_node,
+                route.get(idx + 1),
+            );
+
+            if !self.is_traversable(edge_idx, travel_from) {
+                return Err(format!(
+                    "{} → {} runs against the direction of a one-way track",
+                    self.get_node_name(travel_from).unwrap_or_default(),
+                    self.get_node_name(travel_to).unwrap_or_default()
+                ));
+            }
+            current_node = Some(travel_to);
+        }
+
+        Ok(())
+    }
 }
 
 impl RailwayGraph {
+    /// Dijkstra search for one leg of `shortest_route`
+    /// Returns the edges taken with the node each one is left from
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn shortest_leg(
+        &self,
+        from: NodeIndex,
+        to: NodeIndex,
+        arrived_by: Option<EdgeIndex>,
+        use_distances: bool,
+    ) -> Option<Vec<(EdgeIndex, NodeIndex)>> {
+        use std::cmp::Reverse;
+        use std::collections::{BinaryHeap, HashMap};
+        use petgraph::visit::EdgeRef;
+        use super::tracks::Tracks;
+
+        if from == to {
+            return None;
+        }
+
+        let edge_cost = |edge: EdgeIndex| -> u64 {
+            if use_distances {
+                // Whole metres keep costs integral
+                self.graph.edge_weight(edge)
+                    .and_then(|segment| segment.distance)
+                    .map_or(1, |km| (km * 1000.0).round().max(1.0) as u64)
+            } else {
+                1
+            }
+        };
+
+        // Junction rules depend on the arriving edge, so it is part of the state
+        let start: SearchState = (from, arrived_by);
+        let mut best: HashMap<SearchState, u64> = HashMap::from([(start, 0)]);
+        let mut came_from: HashMap<SearchState, SearchState> = HashMap::new();
+        let mut heap = BinaryHeap::from([Reverse((0_u64, start))]);
+
+        while let Some(Reverse((cost, state))) = heap.pop() {
+            let (current, incoming_edge) = state;
+            if current == to {
+                return Some(reconstruct_leg(&came_from, state));
+            }
+            if best.get(&state).is_some_and(|&known| known < cost) {
+                continue;
+            }
+
+            let outgoing = self.graph.edges(current).map(|e| (e.id(), e.target()));
+            let incoming = self.graph.edges_directed(current, petgraph::Direction::Incoming).map(|e| (e.id(), e.source()));
+            for (edge, neighbor) in outgoing.chain(incoming) {
+                if !self.is_traversable(edge, current) || !self.is_junction_routing_allowed(current, incoming_edge, edge) {
+                    continue;
+                }
+                let next: SearchState = (neighbor, Some(edge));
+                let next_cost = cost + edge_cost(edge);
+                if best.get(&next).is_none_or(|&known| next_cost < known) {
+                    best.insert(next, next_cost);
+                    came_from.insert(next, state);
+                    heap.push(Reverse((next_cost, next)));
+                }
+            }
+        }
+
+        None
+    }
+
     /// Check if routing through a junction is allowed
     /// Returns true if node is not a junction, or if routing is allowed
     fn is_junction_routing_allowed(
@@ -448,6 +594,24 @@ impl RailwayGraph {
 
 }
 
+/// Search state: current node and the edge used to arrive there
+type SearchState = (NodeIndex, Option<EdgeIndex>);
+
+/// Walk back from the end state of a leg search to the edges taken, in travel order
+fn reconstruct_leg(
+    came_from: &std::collections::HashMap<SearchState, SearchState>,
+    end: SearchState,
+) -> Vec<(EdgeIndex, NodeIndex)> {
+    let mut path = Vec::new();
+    let mut step = end;
+    while let (Some(&previous), Some(edge)) = (came_from.get(&step), step.1) {
+        path.push((edge, previous.0));
+        step = previous;
+    }
+    path.reverse();
+    path
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -463,6 +627,7 @@ mod tests {
             destination_platform: 0,
             duration: Some(Duration::minutes(5)),
             wait_time: Duration::seconds(30),
+            reverses: false,
         }
     }
 
@@ -761,6 +926,7 @@ mod tests {
                     destination_platform: 0,
                     duration: Some(Duration::minutes(5)),
                     wait_time: Duration::seconds(30),
+                    reverses: false,
                 });
             }
         }
@@ -919,4 +1085,83 @@ mod tests {
             assert_eq!(path[3], e3, "Fourth edge should be J->C");
         }
     }
+
+    #[test]
+    fn test_validate_route_directions_on_one_way_tracks() {
+        let mut graph = RailwayGraph::new();
+        let a = graph.add_or_get_station("A".to_string());
+        let b = graph.add_or_get_station("B".to_string());
+        let c = graph.add_or_get_station("C".to_string());
+        let one_way = graph.add_track(a, b, vec![Track { direction: TrackDirection::Forward }]);
+        let two_way = graph.add_track(b, c, vec![Track { direction: TrackDirection::Bidirectional }]);
+
+        // A → B → C follows the one-way track
+        let forward = vec![create_test_route_segment(one_way.index()), create_test_route_segment(two_way.index())];
+        assert!(graph.validate_route_directions(&forward).is_ok());
+
+        // C → B → A runs against it
+        let backward = vec![create_test_route_segment(two_way.index()), create_test_route_segment(one_way.index())];
+        let error = graph.validate_route_directions(&backward).expect_err("B → A is one-way");
+        assert!(error.contains("B → A"), "unexpected error: {error}");
+
+        // Bidirectional tracks are fine either way
+        assert!(graph.validate_route_directions(&[create_test_route_segment(two_way.index())]).is_ok());
+        let c_to_b_to_c = vec![create_test_route_segment(two_way.index()), create_test_route_segment(two_way.index())];
+        assert!(graph.validate_route_directions(&c_to_b_to_c).is_ok());
+    }
+
+    #[test]
+    fn test_shortest_route_prefers_shorter_distance() {
+        let mut graph = RailwayGraph::new();
+        let a = graph.add_or_get_station("A".to_string());
+        let b = graph.add_or_get_station("B".to_string());
+        let c = graph.add_or_get_station("C".to_string());
+        let bidirectional = || vec![Track { direction: TrackDirection::Bidirectional }];
+        let direct = graph.add_track(a, c, bidirectional());
+        let a_b = graph.add_track(a, b, bidirectional());
+        let b_c = graph.add_track(b, c, bidirectional());
+
+        // Without distances the single hop wins
+        let route = graph.shortest_route(a, c, &[]).expect("C is reachable");
+        assert_eq!(route.iter().map(|s| s.edge_index).collect::<Vec<_>>(), vec![direct.index()]);
+
+        // With distances the two shorter hops win
+        graph.graph[direct].distance = Some(10.0);
+        graph.graph[a_b].distance = Some(2.0);
+        graph.graph[b_c].distance = Some(3.0);
+        let route = graph.shortest_route(a, c, &[]).expect("C is reachable");
+        assert_eq!(route.iter().map(|s| s.edge_index).collect::<Vec<_>>(), vec![a_b.index(), b_c.index()]);
+        assert_eq!(graph.get_stations_from_route(&route, crate::models::RouteDirection::Forward).len(), 3);
+    }
+
+    #[test]
+    fn test_shortest_route_through_via_station() {
+        let mut graph = RailwayGraph::new();
+        let a = graph.add_or_get_station("A".to_string());
+        let b = graph.add_or_get_station("B".to_string());
+        let c = graph.add_or_get_station("C".to_string());
+        let bidirectional = || vec![Track { direction: TrackDirection::Bidirectional }];
+        let direct = graph.add_track(a, c, bidirectional());
+        let a_b = graph.add_track(a, b, bidirectional());
+        let b_c = graph.add_track(b, c, bidirectional());
+
+        let route = graph.shortest_route(a, c, &[b]).expect("C is reachable via B");
+        let edges: Vec<usize> = route.iter().map(|s| s.edge_index).collect();
+        assert_eq!(edges, vec![a_b.index(), b_c.index()]);
+        assert!(!edges.contains(&direct.index()));
+    }
+
+    #[test]
+    fn test_shortest_route_respects_one_way_tracks() {
+        let mut graph = RailwayGraph::new();
+        let a = graph.add_or_get_station("A".to_string());
+        let b = graph.add_or_get_station("B".to_string());
+        let c = graph.add_or_get_station("C".to_string());
+        graph.add_track(a, b, vec![Track { direction: TrackDirection::Forward }]);
+        graph.add_track(b, c, vec![Track { direction: TrackDirection::Bidirectional }]);
+
+        assert!(graph.shortest_route(a, c, &[]).is_some());
+        assert!(graph.shortest_route(c, a, &[]).is_none(), "B → A runs against the one-way track");
+        assert!(graph.shortest_route(a, a, &[]).is_none());
+    }
 }
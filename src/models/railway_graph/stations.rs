@@ -1,9 +1,21 @@
-use petgraph::stable_graph::NodeIndex;
+use std::collections::{HashMap, HashSet};
+use petgraph::stable_graph::{EdgeIndex, NodeIndex};
 use petgraph::visit::EdgeRef;
 use super::RailwayGraph;
-use crate::models::station::{StationNode, default_platforms};
+use super::block_sections::BlockSections;
+use super::junctions::Junctions;
+use crate::models::station::{Platform, StationNode, default_platforms};
 use crate::models::node::Node;
 
+/// Outcome of merging one station into another, for fixing up line routes
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StationMerge {
+    /// Old edge index -> edge now covering it, or `None` for an edge that joined the two stations
+    pub edges: HashMap<usize, Option<usize>>,
+    /// Platform index at the removed station -> platform index at the kept station
+    pub platforms: Vec<usize>,
+}
+
 /// Extension trait for station-related operations on `RailwayGraph`
 pub trait Stations {
     /// Add a station node if it doesn't exist, return its `NodeIndex`
@@ -46,6 +58,31 @@ pub trait Stations {
     /// Get all station names in order
     fn get_all_station_names(&self) -> Vec<String>;
 
+    /// Find stations whose name contains `query` (case-insensitive), sorted by name
+    /// Returns nothing for a blank query and skips stations with empty names
+    fn search_stations(&self, query: &str) -> Vec<(NodeIndex, String)>;
+
+    /// Station names used by more than one station, each with the stations sharing it, sorted by name
+    fn duplicate_station_names(&self) -> Vec<(String, Vec<NodeIndex>)>;
+
+    /// A station other than `index` that is already called `name`
+    fn station_named_other_than(&self, index: NodeIndex, name: &str) -> Option<NodeIndex>;
+
+    /// Rename a station and keep the name lookup pointing at a station with each name
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if another station already has `new_name`, or `index` is not a station
+    fn rename_station(&mut self, index: NodeIndex, new_name: String) -> Result<(), String>;
+
+    /// Merge `remove` into `keep`: its edges move onto `keep`, joining an existing parallel edge
+    /// where there is one, and its platforms are added to `keep`'s by name
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the two are the same node or either is not a station
+    fn merge_stations(&mut self, keep: NodeIndex, remove: NodeIndex) -> Result<StationMerge, String>;
+
     /// Find adjacent non-passing-loop stations for a passing loop
     /// Returns (`previous_station`, `next_station`) or None if not found
     fn find_adjacent_stations_for_passing_loop(&self, passing_loop_idx: NodeIndex) -> Option<(NodeIndex, NodeIndex)>;
@@ -66,6 +103,11 @@ impl Stations for RailwayGraph {
                 passing_loop: false,
                 platforms: default_platforms(),
                 label_position: None,
+                is_depot: false,
+                min_dwell_secs: None,
+                label_angle: 0.0,
+                label_offset: (0.0, 0.0),
+                conflict_margin_secs: None,
             }));
             self.station_name_to_index.insert(name, index);
             index
@@ -194,6 +236,8 @@ impl Stations for RailwayGraph {
 
         // Get edges that will be removed
         let removed_edges = self.get_station_edges(index);
+        let removed: Vec<EdgeIndex> = removed_edges.iter().map(|&e| EdgeIndex::new(e)).collect();
+        self.remove_edges_from_blocks(&removed);
 
         // Remove station from name mapping
         if let Some(node) = self.graph.node_weight(index) {
@@ -304,6 +348,107 @@ impl Stations for RailwayGraph {
             .collect()
     }
 
+    fn search_stations(&self, query: &str) -> Vec<(NodeIndex, String)> {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        // Walk the nodes rather than the name lookup, which holds one station per name
+        let mut matches: Vec<(NodeIndex, String)> = self.graph.node_indices()
+            .filter_map(|idx| Some((idx, self.get_station_name(idx)?)))
+            .filter(|(_, name)| !name.trim().is_empty() && name.to_lowercase().contains(&query))
+            .map(|(idx, name)| (idx, name.to_string()))
+            .collect();
+        matches.sort_by(|a, b| a.1.to_lowercase().cmp(&b.1.to_lowercase()).then(a.0.cmp(&b.0)));
+        matches
+    }
+
+    fn duplicate_station_names(&self) -> Vec<(String, Vec<NodeIndex>)> {
+        let mut by_name: std::collections::BTreeMap<&str, Vec<NodeIndex>> = std::collections::BTreeMap::new();
+        for idx in self.graph.node_indices() {
+            if let Some(name) = self.get_station_name(idx) {
+                by_name.entry(name).or_default().push(idx);
+            }
+        }
+        by_name.into_iter()
+            .filter(|(_, stations)| stations.len() > 1)
+            .map(|(name, stations)| (name.to_string(), stations))
+            .collect()
+    }
+
+    fn station_named_other_than(&self, index: NodeIndex, name: &str) -> Option<NodeIndex> {
+        self.graph.node_indices()
+            .find(|&idx| idx != index && self.get_station_name(idx) == Some(name))
+    }
+
+    fn rename_station(&mut self, index: NodeIndex, new_name: String) -> Result<(), String> {
+        // Stations that already share a name, e.g. from an import, can still be saved unchanged
+        if self.get_station_name(index) == Some(new_name.as_str()) {
+            return Ok(());
+        }
+        if self.station_named_other_than(index, &new_name).is_some() {
+            return Err(format!("A station named \"{new_name}\" already exists"));
+        }
+        let station = self.graph.node_weight_mut(index)
+            .and_then(Node::as_station_mut)
+            .ok_or_else(|| "Not a station".to_string())?;
+        let old_name = std::mem::replace(&mut station.name, new_name.clone());
+
+        // Hand the old name to any remaining duplicate instead of dropping it
+        if self.station_name_to_index.get(&old_name) == Some(&index) {
+            match self.station_named_other_than(index, &old_name) {
+                Some(other) => self.station_name_to_index.insert(old_name, other),
+                None => self.station_name_to_index.remove(&old_name),
+            };
+        }
+        self.station_name_to_index.insert(new_name, index);
+        Ok(())
+    }
+
+    fn merge_stations(&mut self, keep: NodeIndex, remove: NodeIndex) -> Result<StationMerge, String> {
+        if keep == remove {
+            return Err("Cannot merge a station into itself".to_string());
+        }
+        let removed = self.graph.node_weight(remove)
+            .and_then(Node::as_station)
+            .cloned()
+            .ok_or_else(|| "Not a station".to_string())?;
+        let kept = self.graph.node_weight_mut(keep)
+            .and_then(Node::as_station_mut)
+            .ok_or_else(|| "Not a station".to_string())?;
+        let platforms = union_platforms(&mut kept.platforms, &removed.platforms);
+        kept.min_dwell_secs = kept.min_dwell_secs.max(removed.min_dwell_secs);
+        kept.conflict_margin_secs = kept.conflict_margin_secs.max(removed.conflict_margin_secs);
+
+        let incident: Vec<(EdgeIndex, NodeIndex, NodeIndex)> = self.graph
+            .edges_directed(remove, petgraph::Direction::Outgoing)
+            .chain(self.graph.edges_directed(remove, petgraph::Direction::Incoming))
+            .map(|e| (e.id(), e.source(), e.target()))
+            .collect();
+        let mut edges = HashMap::new();
+        for (edge, source, target) in incident {
+            let moved = |node: NodeIndex| if node == remove { keep } else { node };
+            let (source, target) = (moved(source), moved(target));
+            let new_edge = (source != target).then(|| redirect_edge(self, edge, source, target));
+            edges.insert(edge.index(), new_edge.map(EdgeIndex::index));
+        }
+
+        remap_junction_rules(self, &edges);
+        remap_block_sections(self, &edges);
+
+        self.graph.remove_node(remove);
+        if self.station_name_to_index.get(&removed.name) == Some(&remove) {
+            match self.station_named_other_than(remove, &removed.name) {
+                Some(other) => self.station_name_to_index.insert(removed.name, other),
+                None => self.station_name_to_index.remove(&removed.name),
+            };
+        }
+        self.prune_junction_rules();
+
+        Ok(StationMerge { edges, platforms })
+    }
+
     fn find_adjacent_stations_for_passing_loop(&self, passing_loop_idx: NodeIndex) -> Option<(NodeIndex, NodeIndex)> {
         use petgraph::Direction;
         use std::collections::HashSet;
@@ -448,6 +593,68 @@ impl Stations for RailwayGraph {
     }
 }
 
+/// Add platforms not already present by name, returning where each of `added` ended up
+fn union_platforms(platforms: &mut Vec<Platform>, added: &[Platform]) -> Vec<usize> {
+    added.iter().map(|platform| {
+        platforms.iter().position(|p| p.name == platform.name).unwrap_or_else(|| {
+            platforms.push(platform.clone());
+            platforms.len() - 1
+        })
+    }).collect()
+}
+
+/// Move an edge onto new endpoints, reusing an edge already joining them.
+/// A reused edge takes the moved edge's tracks when it has more and runs the same way.
+fn redirect_edge(graph: &mut RailwayGraph, edge: EdgeIndex, source: NodeIndex, target: NodeIndex) -> EdgeIndex {
+    let segment = graph.graph[edge].clone();
+    let same_direction = graph.graph.find_edge(source, target);
+    let Some(existing) = same_direction.or_else(|| graph.graph.find_edge(target, source)) else {
+        return graph.graph.add_edge(source, target, segment);
+    };
+
+    let parallel = &mut graph.graph[existing];
+    if same_direction.is_some() && segment.tracks.len() > parallel.tracks.len() {
+        parallel.tracks = segment.tracks;
+    }
+    if parallel.distance.is_none() {
+        parallel.distance = segment.distance;
+    }
+    existing
+}
+
+/// Point junction routing rules at the edges that replaced merged ones
+fn remap_junction_rules(graph: &mut RailwayGraph, edges: &HashMap<usize, Option<usize>>) {
+    let remap = |edge: &mut EdgeIndex| {
+        if let Some(&Some(new_edge)) = edges.get(&edge.index()) {
+            *edge = EdgeIndex::new(new_edge);
+        }
+    };
+    for node in graph.graph.node_weights_mut() {
+        if let Some(junction) = node.as_junction_mut() {
+            for rule in &mut junction.routing_rules {
+                remap(&mut rule.from_edge);
+                remap(&mut rule.to_edge);
+            }
+        }
+    }
+}
+
+/// Point block edges at their redirected edges, dropping edges that were removed.
+/// An edge already claimed by a block keeps that membership.
+fn remap_block_sections(graph: &mut RailwayGraph, edges: &HashMap<usize, Option<usize>>) {
+    let mut claimed: HashSet<EdgeIndex> = graph.block_sections.iter()
+        .flat_map(|block| block.edges.iter().copied())
+        .filter(|edge| !edges.contains_key(&edge.index()))
+        .collect();
+    for block in &mut graph.block_sections {
+        block.edges = block.edges.iter().filter_map(|&edge| match edges.get(&edge.index()) {
+            None => Some(edge),
+            Some(new_edge) => new_edge.map(EdgeIndex::new).filter(|&new_edge| claimed.insert(new_edge)),
+        }).collect();
+    }
+    graph.block_sections.retain(|block| !block.edges.is_empty());
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -467,6 +674,96 @@ mod tests {
         assert_eq!(graph.get_station_name(idx2), Some("Station B"));
     }
 
+    #[test]
+    fn test_search_stations_case_insensitive_substring() {
+        let mut graph = RailwayGraph::new();
+        let central = graph.add_or_get_station("Oslo Central".to_string());
+        let airport = graph.add_or_get_station("Oslo Airport".to_string());
+        graph.add_or_get_station("Bergen".to_string());
+        graph.add_or_get_station(String::new());
+
+        let results = graph.search_stations("  oSLo ");
+        assert_eq!(results, vec![
+            (airport, "Oslo Airport".to_string()),
+            (central, "Oslo Central".to_string()),
+        ]);
+        assert!(graph.search_stations("").is_empty());
+        assert!(graph.search_stations("stockholm").is_empty());
+    }
+
+    #[test]
+    fn test_search_stations_keeps_duplicate_names() {
+        let mut graph = RailwayGraph::new();
+        let first = graph.add_or_get_station("Central".to_string());
+        let second = add_named_station(&mut graph, "Central");
+
+        assert_eq!(graph.search_stations("central"), vec![
+            (first, "Central".to_string()),
+            (second, "Central".to_string()),
+        ]);
+    }
+
+    fn add_named_station(graph: &mut RailwayGraph, name: &str) -> NodeIndex {
+        // Bypasses the name lookup, as importers and pasted stations can
+        graph.graph.add_node(Node::Station(StationNode {
+            name: name.to_string(),
+            position: None,
+            passing_loop: false,
+            platforms: default_platforms(),
+            label_position: None,
+            is_depot: false,
+            min_dwell_secs: None,
+            label_angle: 0.0,
+            label_offset: (0.0, 0.0),
+            conflict_margin_secs: None,
+        }))
+    }
+
+    #[test]
+    fn test_duplicate_station_names() {
+        let mut graph = RailwayGraph::new();
+        let first = graph.add_or_get_station("Central".to_string());
+        graph.add_or_get_station("North".to_string());
+        let second = add_named_station(&mut graph, "Central");
+        assert!(RailwayGraph::new().duplicate_station_names().is_empty());
+
+        assert_eq!(graph.duplicate_station_names(), vec![("Central".to_string(), vec![first, second])]);
+    }
+
+    #[test]
+    fn test_rename_station_refuses_collision() {
+        let mut graph = RailwayGraph::new();
+        let central = graph.add_or_get_station("Central".to_string());
+        let north = graph.add_or_get_station("North".to_string());
+
+        assert!(graph.rename_station(north, "Central".to_string()).is_err());
+        assert_eq!(graph.get_station_name(north), Some("North"));
+        assert_eq!(graph.get_station_index("Central"), Some(central));
+        assert_eq!(graph.get_station_index("North"), Some(north));
+
+        // Keeping the own name or picking a free one is fine
+        assert!(graph.rename_station(north, "North".to_string()).is_ok());
+        assert!(graph.rename_station(north, "Harbour".to_string()).is_ok());
+        assert_eq!(graph.get_station_index("Harbour"), Some(north));
+        assert_eq!(graph.get_station_index("North"), None);
+    }
+
+    #[test]
+    fn test_rename_station_hands_old_name_to_duplicate() {
+        let mut graph = RailwayGraph::new();
+        let first = graph.add_or_get_station("Central".to_string());
+        let second = add_named_station(&mut graph, "Central");
+
+        // Saving a duplicate under its current name is not a collision
+        assert!(graph.rename_station(second, "Central".to_string()).is_ok());
+        assert_eq!(graph.get_station_index("Central"), Some(first));
+
+        graph.rename_station(first, "Central East".to_string()).expect("name is free");
+
+        assert_eq!(graph.get_station_index("Central"), Some(second));
+        assert!(graph.duplicate_station_names().is_empty());
+    }
+
     #[test]
     fn test_add_or_get_station_returns_existing() {
         let mut graph = RailwayGraph::new();
@@ -522,6 +819,29 @@ mod tests {
         assert_eq!(stations.len(), 0);
     }
 
+    #[test]
+    fn test_merge_stations_keeps_redirected_edges_in_their_blocks() {
+        let mut graph = RailwayGraph::new();
+        let a = graph.add_or_get_station("A".to_string());
+        let b = graph.add_or_get_station("B".to_string());
+        let c = graph.add_or_get_station("C".to_string());
+        let duplicate = graph.add_or_get_station("B (old)".to_string());
+        let bidirectional = || vec![Track { direction: TrackDirection::Bidirectional }];
+        let a_b = graph.add_track(a, b, bidirectional());
+        let a_dup = graph.add_track(a, duplicate, bidirectional());
+        let dup_c = graph.add_track(duplicate, c, bidirectional());
+        let b_dup = graph.add_track(b, duplicate, bidirectional());
+        graph.assign_edges_to_block(1, &[a_dup, dup_c]);
+        graph.assign_edges_to_block(2, &[b_dup]);
+
+        graph.merge_stations(b, duplicate).expect("both are stations");
+
+        let b_c = graph.graph.find_edge(b, c).expect("edge moved onto kept station");
+        assert_eq!(graph.block_for_edge(a_b), Some(1));
+        assert_eq!(graph.block_for_edge(b_c), Some(1));
+        assert_eq!(graph.block_sections.len(), 1);
+    }
+
     #[test]
     fn test_delete_station_creates_bypass() {
         let mut graph = RailwayGraph::new();
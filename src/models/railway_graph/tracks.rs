@@ -1,6 +1,6 @@
 use petgraph::stable_graph::{NodeIndex, EdgeIndex};
-use super::RailwayGraph;
-use crate::models::track::{Track, TrackSegment};
+use super::{RailwayGraph, Stations};
+use crate::models::track::{has_suspicious_directions, Electrification, Track, TrackSegment};
 use crate::models::TrackHandedness;
 
 /// Extension trait for track-related operations on `RailwayGraph`
@@ -25,7 +25,7 @@ pub trait Tracks {
     /// * `edge_idx` - The edge being traveled on
     /// * `arriving_at_target` - true if arriving at target node, false if arriving at source node
     /// * `platform_count` - Number of platforms at the arrival station
-    /// * `handedness` - Track handedness (right-hand or left-hand traffic)
+    /// * `handedness` - Project track handedness, used unless the track overrides it
     fn get_default_platform_for_arrival(&self, edge_idx: EdgeIndex, arriving_at_target: bool, platform_count: usize, handedness: TrackHandedness) -> usize;
 
     /// Select appropriate track index for a given travel direction
@@ -36,6 +36,28 @@ pub trait Tracks {
     /// * `edge_idx` - The edge being traveled on
     /// * `traveling_backward` - true for backward/return direction, false for forward direction
     fn select_track_for_direction(&self, edge_idx: EdgeIndex, traveling_backward: bool) -> usize;
+
+    /// Whether a train leaving `from_node` can run over the edge
+    /// At least one track must allow that direction; returns false if `from_node` is not an endpoint
+    fn is_traversable(&self, edge_idx: EdgeIndex, from_node: NodeIndex) -> bool;
+
+    /// Derive a track distance in km from the straight-line distance between the edge's endpoints
+    /// Returns `None` if either endpoint has no position or the scale is not positive
+    fn auto_distance_for_edge(&self, edge: EdgeIndex, px_per_km: f64) -> Option<f64>;
+
+    /// Fill in the distance of every edge that has none from its endpoint positions
+    /// Returns the number of edges that were filled
+    fn auto_fill_distances(&mut self, px_per_km: f64) -> usize;
+
+    /// Tracks whose notes contain `query` (case-insensitive), each as its source node and a
+    /// "From – To: note" label so it can be listed alongside station search results
+    fn search_track_notes(&self, query: &str) -> Vec<(NodeIndex, String)>;
+
+    /// Edges whose only track is used in both directions, where trains must take turns
+    fn single_track_sections(&self) -> Vec<EdgeIndex>;
+
+    /// Edges with no tracks or whose tracks all run one way, usually an import or editing mistake
+    fn suspicious_track_configs(&self) -> Vec<EdgeIndex>;
 }
 
 impl Tracks for RailwayGraph {
@@ -45,6 +67,11 @@ impl Tracks for RailwayGraph {
             distance: None,
             default_platform_source: None,
             default_platform_target: None,
+            speed_limit_kph: None,
+            electrification: Electrification::None,
+            gradient_permille: None,
+            handedness: None,
+            notes: String::new(),
         })
     }
 
@@ -56,6 +83,31 @@ impl Tracks for RailwayGraph {
         self.graph.edge_endpoints(edge_idx)
     }
 
+    fn auto_distance_for_edge(&self, edge: EdgeIndex, px_per_km: f64) -> Option<f64> {
+        if px_per_km <= 0.0 {
+            return None;
+        }
+        let (from, to) = self.graph.edge_endpoints(edge)?;
+        let (x1, y1) = self.get_station_position(from)?;
+        let (x2, y2) = self.get_station_position(to)?;
+
+        // Round to metres
+        let km = (x2 - x1).hypot(y2 - y1) / px_per_km;
+        Some((km * 1000.0).round() / 1000.0)
+    }
+
+    fn auto_fill_distances(&mut self, px_per_km: f64) -> usize {
+        let missing: Vec<(EdgeIndex, f64)> = self.graph.edge_indices()
+            .filter(|&edge| self.graph[edge].distance.is_none())
+            .filter_map(|edge| self.auto_distance_for_edge(edge, px_per_km).map(|km| (edge, km)))
+            .collect();
+
+        for &(edge, km) in &missing {
+            self.graph[edge].distance = Some(km);
+        }
+        missing.len()
+    }
+
     fn toggle_segment_double_track(&mut self, station1_name: &str, station2_name: &str) -> Vec<(usize, usize)> {
         use super::stations::Stations;
 
@@ -117,7 +169,8 @@ impl Tracks for RailwayGraph {
             return platform;
         }
 
-        // Fall back to handedness-based default
+        // Fall back to handedness-based default, preferring the track's own running side
+        let handedness = track_segment.and_then(|seg| seg.handedness).unwrap_or(handedness);
         match (handedness, arriving_at_target) {
             // Right-hand: forward trains use right (last) platform, backward trains use left (first) platform
             // Left-hand: backward trains use right (last) platform
@@ -144,6 +197,57 @@ impl Tracks for RailwayGraph {
             })
             .unwrap_or(0)
     }
+
+    fn is_traversable(&self, edge_idx: EdgeIndex, from_node: NodeIndex) -> bool {
+        use crate::models::track::TrackDirection;
+
+        let Some((source, target)) = self.graph.edge_endpoints(edge_idx) else {
+            return false;
+        };
+        let traveling_backward = if from_node == source {
+            false
+        } else if from_node == target {
+            true
+        } else {
+            return false;
+        };
+
+        self.graph[edge_idx].tracks.iter().any(|t| match t.direction {
+            TrackDirection::Bidirectional => true,
+            TrackDirection::Forward => !traveling_backward,
+            TrackDirection::Backward => traveling_backward,
+        })
+    }
+
+    fn search_track_notes(&self, query: &str) -> Vec<(NodeIndex, String)> {
+        use petgraph::visit::{EdgeRef, IntoEdgeReferences};
+
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let name = |idx: NodeIndex| self.graph.node_weight(idx).map(crate::models::Node::display_name).unwrap_or_default();
+        self.graph.edge_references()
+            .filter(|edge| edge.weight().notes.to_lowercase().contains(&query))
+            .map(|edge| {
+                let label = format!("{} – {}: {}", name(edge.source()), name(edge.target()), edge.weight().notes);
+                (edge.source(), label)
+            })
+            .collect()
+    }
+
+    fn single_track_sections(&self) -> Vec<EdgeIndex> {
+        self.graph.edge_indices()
+            .filter(|&edge| self.graph[edge].is_single_track_bidirectional())
+            .collect()
+    }
+
+    fn suspicious_track_configs(&self) -> Vec<EdgeIndex> {
+        self.graph.edge_indices()
+            .filter(|&edge| has_suspicious_directions(&self.graph[edge].tracks))
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -166,6 +270,82 @@ mod tests {
         assert_eq!(graph.get_track_endpoints(edge), Some((idx1, idx2)));
     }
 
+    #[test]
+    fn test_search_track_notes() {
+        let mut graph = RailwayGraph::new();
+        let a = graph.add_or_get_station("A".to_string());
+        let b = graph.add_or_get_station("B".to_string());
+        let c = graph.add_or_get_station("C".to_string());
+        let noted = graph.add_track(a, b, vec![Track { direction: TrackDirection::Bidirectional }]);
+        graph.add_track(b, c, vec![Track { direction: TrackDirection::Bidirectional }]);
+        graph.graph[noted].notes = "Temporary speed restriction until June".to_string();
+
+        assert_eq!(
+            graph.search_track_notes("speed RESTRICTION"),
+            vec![(a, "A – B: Temporary speed restriction until June".to_string())]
+        );
+        assert!(graph.search_track_notes("  ").is_empty());
+        assert!(graph.search_track_notes("closure").is_empty());
+    }
+
+    #[test]
+    fn test_single_track_sections_in_mixed_network() {
+        let mut graph = RailwayGraph::new();
+        let a = graph.add_or_get_station("A".to_string());
+        let b = graph.add_or_get_station("B".to_string());
+        let c = graph.add_or_get_station("C".to_string());
+        let d = graph.add_or_get_station("D".to_string());
+        let single = graph.add_track(a, b, vec![Track { direction: TrackDirection::Bidirectional }]);
+        graph.add_track(b, c, vec![
+            Track { direction: TrackDirection::Forward },
+            Track { direction: TrackDirection::Backward },
+        ]);
+        // One-way single track never sees opposing trains
+        graph.add_track(c, d, vec![Track { direction: TrackDirection::Forward }]);
+        let other_single = graph.add_track(d, a, vec![Track { direction: TrackDirection::Bidirectional }]);
+
+        assert_eq!(graph.single_track_sections(), vec![single, other_single]);
+    }
+
+    #[test]
+    fn test_suspicious_track_configs_flags_one_way_double_track() {
+        let mut graph = RailwayGraph::new();
+        let a = graph.add_or_get_station("A".to_string());
+        let b = graph.add_or_get_station("B".to_string());
+        let c = graph.add_or_get_station("C".to_string());
+        let d = graph.add_or_get_station("D".to_string());
+        graph.add_track(a, b, vec![
+            Track { direction: TrackDirection::Forward },
+            Track { direction: TrackDirection::Backward },
+        ]);
+        let all_forward = graph.add_track(b, c, vec![
+            Track { direction: TrackDirection::Forward },
+            Track { direction: TrackDirection::Forward },
+        ]);
+        graph.add_track(c, d, vec![Track { direction: TrackDirection::Forward }]);
+        let empty = graph.add_track(d, a, vec![]);
+
+        assert_eq!(graph.suspicious_track_configs(), vec![all_forward, empty]);
+    }
+
+    #[test]
+    fn test_suspicious_track_configs_accepts_valid_double_track() {
+        let mut graph = RailwayGraph::new();
+        let a = graph.add_or_get_station("A".to_string());
+        let b = graph.add_or_get_station("B".to_string());
+        let c = graph.add_or_get_station("C".to_string());
+        graph.add_track(a, b, vec![
+            Track { direction: TrackDirection::Forward },
+            Track { direction: TrackDirection::Backward },
+        ]);
+        graph.add_track(b, c, vec![
+            Track { direction: TrackDirection::Bidirectional },
+            Track { direction: TrackDirection::Bidirectional },
+        ]);
+
+        assert!(graph.suspicious_track_configs().is_empty());
+    }
+
     #[test]
     fn test_get_track() {
         let mut graph = RailwayGraph::new();
@@ -192,6 +372,38 @@ mod tests {
         assert_eq!(endpoints, Some((idx1, idx2)));
     }
 
+    #[test]
+    fn test_auto_distance_for_edge() {
+        let mut graph = RailwayGraph::new();
+        let idx1 = graph.add_or_get_station("Station A".to_string());
+        let idx2 = graph.add_or_get_station("Station B".to_string());
+        graph.set_station_position(idx1, (0.0, 0.0));
+        graph.set_station_position(idx2, (300.0, 400.0));
+        let edge = graph.add_track(idx1, idx2, vec![Track { direction: TrackDirection::Bidirectional }]);
+
+        assert_eq!(graph.auto_distance_for_edge(edge, 100.0), Some(5.0));
+        assert_eq!(graph.auto_distance_for_edge(edge, 30.0), Some(16.667));
+        assert_eq!(graph.auto_distance_for_edge(edge, 0.0), None);
+    }
+
+    #[test]
+    fn test_auto_fill_distances_keeps_existing() {
+        let mut graph = RailwayGraph::new();
+        let idx1 = graph.add_or_get_station("Station A".to_string());
+        let idx2 = graph.add_or_get_station("Station B".to_string());
+        let idx3 = graph.add_or_get_station("Station C".to_string());
+        graph.set_station_position(idx1, (0.0, 0.0));
+        graph.set_station_position(idx2, (100.0, 0.0));
+        graph.set_station_position(idx3, (100.0, 50.0));
+        let filled = graph.add_track(idx1, idx2, vec![Track { direction: TrackDirection::Bidirectional }]);
+        let kept = graph.add_track(idx2, idx3, vec![Track { direction: TrackDirection::Bidirectional }]);
+        graph.graph[kept].distance = Some(7.5);
+
+        assert_eq!(graph.auto_fill_distances(50.0), 1);
+        assert_eq!(graph.graph[filled].distance, Some(2.0));
+        assert_eq!(graph.graph[kept].distance, Some(7.5));
+    }
+
     #[test]
     fn test_toggle_segment_double_track() {
         let mut graph = RailwayGraph::new();
@@ -246,4 +458,23 @@ mod tests {
         assert_eq!(graph.get_track(edge1).expect("track should exist").tracks.len(), 2);
         assert_eq!(graph.get_track(edge2).expect("track should exist").tracks.len(), 2);
     }
+
+    #[test]
+    fn test_is_traversable_respects_track_direction() {
+        let mut graph = RailwayGraph::new();
+        let a = graph.add_or_get_station("A".to_string());
+        let b = graph.add_or_get_station("B".to_string());
+        let c = graph.add_or_get_station("C".to_string());
+        let forward = graph.add_track(a, b, vec![Track { direction: TrackDirection::Forward }]);
+        let backward = graph.add_track(b, c, vec![Track { direction: TrackDirection::Backward }]);
+        let both = graph.add_track(a, c, vec![Track { direction: TrackDirection::Bidirectional }]);
+
+        assert!(graph.is_traversable(forward, a));
+        assert!(!graph.is_traversable(forward, b));
+        assert!(!graph.is_traversable(backward, b));
+        assert!(graph.is_traversable(backward, c));
+        assert!(graph.is_traversable(both, a));
+        assert!(graph.is_traversable(both, c));
+        assert!(!graph.is_traversable(forward, c));
+    }
 }
@@ -0,0 +1,70 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::DaysOfWeek;
+
+/// A named operating-day calendar, such as "Weekday" or "Sunday", shared by lines
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ServicePattern {
+    pub id: Uuid,
+    pub name: String,
+    pub days: DaysOfWeek,
+    /// Real dates on which the pattern does not run, such as public holidays,
+    /// checked against the project's reference week
+    #[serde(default)]
+    pub exceptions: Vec<NaiveDate>,
+}
+
+impl ServicePattern {
+    #[must_use]
+    pub fn new(name: String, days: DaysOfWeek) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            name,
+            days,
+            exceptions: Vec::new(),
+        }
+    }
+
+    /// Whether the pattern runs on the real `date`, whose day of week is `day`
+    #[must_use]
+    pub fn runs_on(&self, day: DaysOfWeek, date: NaiveDate) -> bool {
+        self.days.contains(day) && !self.exceptions.contains(&date)
+    }
+
+    /// Exception dates as entered in the editor, comma separated
+    #[must_use]
+    pub fn exceptions_text(&self) -> String {
+        self.exceptions.iter().map(NaiveDate::to_string).collect::<Vec<_>>().join(", ")
+    }
+
+    /// Replace the exceptions with the `YYYY-MM-DD` dates in `text`, sorted and without duplicates.
+    /// Entries that are not dates are dropped.
+    pub fn set_exceptions_from_text(&mut self, text: &str) {
+        let mut exceptions: Vec<NaiveDate> = text
+            .split([',', ';', '\n'])
+            .filter_map(|entry| NaiveDate::parse_from_str(entry.trim(), "%Y-%m-%d").ok())
+            .collect();
+        exceptions.sort_unstable();
+        exceptions.dedup();
+        self.exceptions = exceptions;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exceptions_text_round_trip() {
+        let mut pattern = ServicePattern::new("Weekday".to_string(), DaysOfWeek::WEEKDAYS);
+        pattern.set_exceptions_from_text("2025-12-26, 2025-12-25; not a date,2025-12-25");
+
+        let christmas = NaiveDate::from_ymd_opt(2025, 12, 25).expect("valid date");
+        let boxing_day = NaiveDate::from_ymd_opt(2025, 12, 26).expect("valid date");
+        assert_eq!(pattern.exceptions, vec![christmas, boxing_day]);
+        assert_eq!(pattern.exceptions_text(), "2025-12-25, 2025-12-26");
+        assert!(!pattern.runs_on(DaysOfWeek::THURSDAY, christmas));
+    }
+}
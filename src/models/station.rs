@@ -1,15 +1,25 @@
 use serde::{Deserialize, Serialize};
 use crate::components::infrastructure_canvas::station_renderer::LabelPosition;
 
+/// Trains a platform holds at once unless set otherwise
+pub const DEFAULT_PLATFORM_CAPACITY: usize = 1;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Platform {
     pub name: String,
+    /// Number of trains the platform can hold at once, e.g. 2 for an island platform face shared by two tracks
+    #[serde(default = "default_platform_capacity")]
+    pub capacity: usize,
+}
+
+fn default_platform_capacity() -> usize {
+    DEFAULT_PLATFORM_CAPACITY
 }
 
 pub fn default_platforms() -> Vec<Platform> {
     vec![
-        Platform { name: "1".to_string() },
-        Platform { name: "2".to_string() },
+        Platform { name: "1".to_string(), capacity: DEFAULT_PLATFORM_CAPACITY },
+        Platform { name: "2".to_string(), capacity: DEFAULT_PLATFORM_CAPACITY },
     ]
 }
 
@@ -24,6 +34,21 @@ pub struct StationNode {
     pub platforms: Vec<Platform>,
     #[serde(default)]
     pub label_position: Option<LabelPosition>,
+    /// Stabling yard: shown on the map but left off the time graph
+    #[serde(default)]
+    pub is_depot: bool,
+    /// Shortest dwell in seconds a stopping train needs here, e.g. for boarding
+    #[serde(default)]
+    pub min_dwell_secs: Option<i64>,
+    /// Extra rotation of the map label around the station, in degrees clockwise
+    #[serde(default)]
+    pub label_angle: f64,
+    /// Shift of the map label from where it is placed, in world units
+    #[serde(default)]
+    pub label_offset: (f64, f64),
+    /// Conflict margin in seconds here, replacing the project-wide station margin and platform buffer
+    #[serde(default)]
+    pub conflict_margin_secs: Option<i64>,
 }
 
 #[cfg(test)]
@@ -44,8 +69,13 @@ mod tests {
             name: "Test Station".to_string(),
             position: Some((10.0, 20.0)),
             passing_loop: true,
-            platforms: vec![Platform { name: "A".to_string() }],
+            platforms: vec![Platform { name: "A".to_string(), capacity: 1 }],
             label_position: None,
+            is_depot: false,
+            min_dwell_secs: None,
+            label_angle: 0.0,
+            label_offset: (0.0, 0.0),
+            conflict_margin_secs: None,
         };
 
         assert_eq!(station.name, "Test Station");
@@ -57,7 +87,7 @@ mod tests {
 
     #[test]
     fn test_platform_creation() {
-        let platform = Platform { name: "Platform 1".to_string() };
+        let platform = Platform { name: "Platform 1".to_string(), capacity: 1 };
         assert_eq!(platform.name, "Platform 1");
     }
 }
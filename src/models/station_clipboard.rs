@@ -0,0 +1,212 @@
+use serde::{Deserialize, Serialize};
+use petgraph::stable_graph::{EdgeIndex, NodeIndex};
+use petgraph::visit::{EdgeRef, IntoEdgeReferences};
+use std::collections::HashMap;
+use super::junction::Junction;
+use super::node::Node;
+use super::railway_graph::RailwayGraph;
+use super::track::TrackSegment;
+
+/// Distance pasted stations are moved from the copied ones, so they don't cover the originals
+pub const PASTE_OFFSET: (f64, f64) = (40.0, 40.0);
+
+/// A track between two copied nodes, referring to them by position in `StationClipboard::nodes`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClipboardTrack {
+    pub from: usize,
+    pub to: usize,
+    /// Index of the edge in the copied graph, used to remap junction routing rules
+    pub original_edge: usize,
+    pub segment: TrackSegment,
+}
+
+/// A cluster of stations and the tracks between them, detached from any graph
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StationClipboard {
+    pub nodes: Vec<Node>,
+    pub tracks: Vec<ClipboardTrack>,
+}
+
+impl StationClipboard {
+    /// Copy the given nodes and every track connecting two of them
+    #[must_use]
+    pub fn from_selection(graph: &RailwayGraph, selection: &[NodeIndex]) -> Self {
+        let selection: Vec<NodeIndex> = selection
+            .iter()
+            .copied()
+            .filter(|&index| graph.graph.node_weight(index).is_some())
+            .collect();
+        let positions: HashMap<NodeIndex, usize> = selection.iter().enumerate().map(|(i, &index)| (index, i)).collect();
+
+        let nodes = selection.iter().map(|&index| graph.graph[index].clone()).collect();
+        let tracks = graph
+            .graph
+            .edge_references()
+            .filter_map(|edge| {
+                Some(ClipboardTrack {
+                    from: *positions.get(&edge.source())?,
+                    to: *positions.get(&edge.target())?,
+                    original_edge: edge.id().index(),
+                    segment: edge.weight().clone(),
+                })
+            })
+            .collect();
+
+        Self { nodes, tracks }
+    }
+
+    /// # Errors
+    /// Returns an error if JSON serialization fails
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string(self).map_err(|e| format!("Failed to serialize stations: {e}"))
+    }
+
+    /// # Errors
+    /// Returns an error if the text is not a copied station cluster
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| format!("Clipboard does not contain stations: {e}"))
+    }
+
+    /// Insert the copied nodes and tracks into `graph` as new nodes, moved by `offset`.
+    /// Station names already in use get a numbered suffix. Returns the new node indices.
+    pub fn paste_into(&self, graph: &mut RailwayGraph, offset: (f64, f64)) -> Vec<NodeIndex> {
+        let new_nodes: Vec<NodeIndex> = self
+            .nodes
+            .iter()
+            .map(|node| {
+                let mut node = node.clone();
+                if let Some((x, y)) = node.position() {
+                    node.set_position(Some((x + offset.0, y + offset.1)));
+                }
+                if let Node::Station(station) = &mut node {
+                    station.name = unique_station_name(graph, &station.name);
+                    let name = station.name.clone();
+                    let index = graph.graph.add_node(node);
+                    graph.station_name_to_index.insert(name, index);
+                    index
+                } else {
+                    graph.graph.add_node(node)
+                }
+            })
+            .collect();
+
+        let edge_map: HashMap<EdgeIndex, EdgeIndex> = self
+            .tracks
+            .iter()
+            .map(|track| {
+                let new_edge = graph.graph.add_edge(new_nodes[track.from], new_nodes[track.to], track.segment.clone());
+                (EdgeIndex::new(track.original_edge), new_edge)
+            })
+            .collect();
+
+        for &index in &new_nodes {
+            if let Some(Node::Junction(junction)) = graph.graph.node_weight_mut(index) {
+                remap_routing_rules(junction, &edge_map);
+            }
+        }
+
+        new_nodes
+    }
+}
+
+/// Point routing rules at the pasted edges, dropping rules for tracks that were not copied
+fn remap_routing_rules(junction: &mut Junction, edge_map: &HashMap<EdgeIndex, EdgeIndex>) {
+    junction.routing_rules.retain_mut(|rule| {
+        match (edge_map.get(&rule.from_edge), edge_map.get(&rule.to_edge)) {
+            (Some(&from_edge), Some(&to_edge)) => {
+                rule.from_edge = from_edge;
+                rule.to_edge = to_edge;
+                true
+            }
+            _ => false,
+        }
+    });
+}
+
+/// `name`, or `name (2)`, `name (3)`, ... if a station with that name already exists
+fn unique_station_name(graph: &RailwayGraph, name: &str) -> String {
+    if !graph.station_name_to_index.contains_key(name) {
+        return name.to_string();
+    }
+    // One more candidate than there are stations, so a free name is always found
+    (2..=graph.station_name_to_index.len() + 1)
+        .map(|n| format!("{name} ({n})"))
+        .find(|candidate| !graph.station_name_to_index.contains_key(candidate))
+        .unwrap_or_else(|| name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Stations, Track, TrackDirection, Tracks};
+
+    fn bidirectional() -> Vec<Track> {
+        vec![Track { direction: TrackDirection::Bidirectional }]
+    }
+
+    #[test]
+    fn test_round_trip_three_station_cluster() {
+        let mut source = RailwayGraph::new();
+        let a = source.add_or_get_station("A".to_string());
+        let b = source.add_or_get_station("B".to_string());
+        let c = source.add_or_get_station("C".to_string());
+        let outside = source.add_or_get_station("Outside".to_string());
+        source.set_station_position(a, (0.0, 0.0));
+        source.set_station_position(b, (100.0, 0.0));
+        source.set_station_position(c, (200.0, 50.0));
+        source.add_track(a, b, bidirectional());
+        source.add_track(b, c, bidirectional());
+        source.add_track(c, outside, bidirectional());
+
+        let json = StationClipboard::from_selection(&source, &[a, b, c]).to_json().expect("serializes");
+        let clipboard = StationClipboard::from_json(&json).expect("deserializes");
+        assert_eq!(clipboard.nodes.len(), 3);
+        assert_eq!(clipboard.tracks.len(), 2);
+
+        // Pasting back into the source graph renames the copies and keeps the originals intact
+        let pasted = clipboard.paste_into(&mut source, (10.0, 20.0));
+        assert_eq!(pasted.len(), 3);
+        assert_eq!(source.get_station_name(pasted[0]), Some("A (2)"));
+        assert_eq!(source.get_station_index("C (2)"), Some(pasted[2]));
+        assert_eq!(source.get_station_position(pasted[1]), Some((110.0, 20.0)));
+        assert!(source.graph.find_edge(pasted[0], pasted[1]).is_some());
+        assert!(source.graph.find_edge(pasted[1], pasted[2]).is_some());
+        assert!(source.graph.find_edge(pasted[0], pasted[2]).is_none());
+        assert!(source.graph.find_edge(pasted[2], outside).is_none());
+        assert_eq!(source.graph.node_count(), 7);
+        assert_eq!(source.graph.edge_count(), 5);
+    }
+
+    #[test]
+    fn test_paste_remaps_junction_routing_rules() {
+        use crate::models::Junctions;
+
+        let mut source = RailwayGraph::new();
+        let a = source.add_or_get_station("A".to_string());
+        let b = source.add_or_get_station("B".to_string());
+        let outside = source.add_or_get_station("Outside".to_string());
+        let junction = source.add_junction(Junction {
+            name: None,
+            position: Some((50.0, 0.0)),
+            routing_rules: Vec::new(),
+            label_position: None,
+        });
+        let to_a = source.add_track(junction, a, bidirectional());
+        let to_b = source.add_track(junction, b, bidirectional());
+        let to_outside = source.add_track(junction, outside, bidirectional());
+        if let Some(j) = source.get_junction_mut(junction) {
+            j.set_routing_rule(to_a, to_b, false);
+            j.set_routing_rule(to_a, to_outside, false);
+        }
+
+        let clipboard = StationClipboard::from_selection(&source, &[a, b, junction]);
+        let mut target = RailwayGraph::new();
+        let pasted = clipboard.paste_into(&mut target, (0.0, 0.0));
+
+        let new_junction = target.get_junction(pasted[2]).expect("junction pasted");
+        let new_to_a = target.graph.find_edge(pasted[2], pasted[0]).expect("edge to A");
+        let new_to_b = target.graph.find_edge(pasted[2], pasted[1]).expect("edge to B");
+        assert_eq!(new_junction.routing_rules.len(), 1);
+        assert!(!new_junction.is_routing_allowed(new_to_a, new_to_b));
+    }
+}
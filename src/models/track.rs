@@ -1,4 +1,9 @@
+use chrono::Duration;
 use serde::{Deserialize, Serialize};
+use super::project::TrackHandedness;
+
+/// Steepest gradient accepted for a track segment, in per mille
+pub const MAX_GRADIENT_PERMILLE: f64 = 100.0;
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
 pub enum TrackDirection {
@@ -7,22 +12,100 @@ pub enum TrackDirection {
     Backward,   // From target to source only
 }
 
+/// Type of electrification fitted to a track segment
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Electrification {
+    #[default]
+    None,
+    Overhead,
+    ThirdRail,
+}
+
+impl Electrification {
+    pub const ALL: [Self; 3] = [Self::None, Self::Overhead, Self::ThirdRail];
+
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::None => "Not electrified",
+            Self::Overhead => "Overhead line",
+            Self::ThirdRail => "Third rail",
+        }
+    }
+
+    #[must_use]
+    pub const fn is_electrified(self) -> bool {
+        !matches!(self, Self::None)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Track {
     pub direction: TrackDirection,
 }
 
+/// Whether a set of tracks looks misconfigured: no tracks at all, or several tracks that all
+/// run the same single direction, leaving no return path. A lone one-way track is a deliberate
+/// one-way section and isn't flagged.
+#[must_use]
+pub fn has_suspicious_directions(tracks: &[Track]) -> bool {
+    match tracks {
+        [] => true,
+        [_] => false,
+        [first, rest @ ..] => first.direction != TrackDirection::Bidirectional
+            && rest.iter().all(|track| track.direction == first.direction),
+    }
+}
+
+/// Check a gradient in per mille is within `MAX_GRADIENT_PERMILLE`
+///
+/// # Errors
+/// Returns a message when the gradient isn't finite or is too steep
+pub fn check_gradient(gradient: f64) -> Result<f64, String> {
+    if !gradient.is_finite() || gradient.abs() > MAX_GRADIENT_PERMILLE {
+        return Err(format!("Gradient of {gradient}‰ exceeds the maximum of {MAX_GRADIENT_PERMILLE}‰"));
+    }
+    Ok(gradient)
+}
+
+/// Parse a gradient typed in per mille. Blank input means no gradient.
+///
+/// # Errors
+/// Returns a message when the input isn't a number or is steeper than `MAX_GRADIENT_PERMILLE`
+pub fn parse_gradient(input: &str) -> Result<Option<f64>, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Ok(None);
+    }
+    let gradient = input.parse::<f64>().ok().filter(|g| g.is_finite()).ok_or("Gradient must be a number")?;
+    check_gradient(gradient).map(Some)
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TrackSegment {
     pub tracks: Vec<Track>,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
     pub distance: Option<f64>,
     /// Default platform index when arriving at the source station (traveling backward on edge)
-    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
     pub default_platform_source: Option<usize>,
     /// Default platform index when arriving at the target station (traveling forward on edge)
-    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
     pub default_platform_target: Option<usize>,
+    #[serde(default)]
+    pub electrification: Electrification,
+    /// Average grade in per mille, negative values are downhill from source to target
+    #[serde(default)]
+    pub gradient_permille: Option<f64>,
+    /// Running side on this track, overriding the project's handedness when set
+    #[serde(default)]
+    pub handedness: Option<TrackHandedness>,
+    /// Free-text planning note, e.g. a temporary speed restriction; purely informational
+    #[serde(default)]
+    pub notes: String,
+    /// Maximum line speed in km/h, used to derive run times from distance
+    #[serde(default)]
+    pub speed_limit_kph: Option<f64>,
 }
 
 impl TrackSegment {
@@ -33,6 +116,11 @@ impl TrackSegment {
             distance: None,
             default_platform_source: None,
             default_platform_target: None,
+            speed_limit_kph: None,
+            electrification: Electrification::None,
+            gradient_permille: None,
+            handedness: None,
+            notes: String::new(),
         }
     }
 
@@ -46,6 +134,39 @@ impl TrackSegment {
             distance: None,
             default_platform_source: None,
             default_platform_target: None,
+            speed_limit_kph: None,
+            electrification: Electrification::None,
+            gradient_permille: None,
+            handedness: None,
+            notes: String::new(),
+        }
+    }
+
+    /// Whether trains in both directions share the segment's only track
+    #[must_use]
+    pub fn is_single_track_bidirectional(&self) -> bool {
+        matches!(self.tracks.as_slice(), [Track { direction: TrackDirection::Bidirectional }])
+    }
+
+    /// Average speed in km/h needed to cover this segment in `duration`.
+    /// Returns `None` when the segment has no distance or the duration is not positive.
+    #[must_use]
+    pub fn implied_speed_kph(&self, duration: Duration) -> Option<f64> {
+        let distance = self.distance.filter(|d| *d > 0.0)?;
+        #[allow(clippy::cast_precision_loss)]
+        let seconds = duration.num_milliseconds() as f64 / 1000.0;
+        if seconds <= 0.0 {
+            return None;
+        }
+        Some(distance / (seconds / 3600.0))
+    }
+
+    /// Check whether covering this segment in `duration` requires exceeding its speed limit
+    #[must_use]
+    pub fn exceeds_speed_limit(&self, duration: Duration) -> bool {
+        match (self.implied_speed_kph(duration), self.speed_limit_kph) {
+            (Some(speed), Some(limit)) => speed > limit,
+            _ => false,
         }
     }
 
@@ -88,6 +209,26 @@ mod tests {
         assert_eq!(segment.distance, None);
     }
 
+    #[test]
+    fn test_track_segment_msgpack_round_trip_without_distance() {
+        let segment = TrackSegment { speed_limit_kph: Some(80.0), ..TrackSegment::new_single_track() };
+
+        let bytes = rmp_serde::to_vec(&segment).expect("segment should serialize");
+        let restored: TrackSegment = rmp_serde::from_slice(&bytes).expect("segment should deserialize");
+
+        assert_eq!(restored.distance, None);
+        assert_eq!(restored.speed_limit_kph, Some(80.0));
+        assert_eq!(restored.electrification, Electrification::None);
+    }
+
+    #[test]
+    fn test_parse_gradient_rejects_invalid_input() {
+        assert_eq!(parse_gradient("  "), Ok(None));
+        assert_eq!(parse_gradient("-12.5"), Ok(Some(-12.5)));
+        assert!(parse_gradient("steep").is_err());
+        assert!(parse_gradient(&format!("{}", MAX_GRADIENT_PERMILLE + 1.0)).is_err());
+    }
+
     #[test]
     fn test_new_double_track() {
         let segment = TrackSegment::new_double_track();
@@ -104,8 +245,36 @@ mod tests {
             distance: Some(100.5),
             default_platform_source: None,
             default_platform_target: None,
+            speed_limit_kph: None,
+            electrification: Electrification::None,
+            gradient_permille: None,
+            handedness: None,
+            notes: String::new(),
         };
         assert_eq!(segment.tracks.len(), 1);
         assert_eq!(segment.distance, Some(100.5));
     }
+
+    #[test]
+    fn test_new_track_defaults_to_non_electrified() {
+        assert_eq!(TrackSegment::new_single_track().electrification, Electrification::None);
+        assert_eq!(TrackSegment::new_double_track().electrification, Electrification::None);
+        assert!(!Electrification::default().is_electrified());
+        assert!(Electrification::Overhead.is_electrified());
+    }
+
+    #[test]
+    fn test_implied_speed_kph() {
+        let mut segment = TrackSegment::new_single_track();
+        assert_eq!(segment.implied_speed_kph(Duration::minutes(5)), None);
+
+        segment.distance = Some(10.0);
+        assert_eq!(segment.implied_speed_kph(Duration::minutes(5)), Some(120.0));
+        assert_eq!(segment.implied_speed_kph(Duration::zero()), None);
+
+        assert!(!segment.exceeds_speed_limit(Duration::minutes(5)));
+        segment.speed_limit_kph = Some(100.0);
+        assert!(segment.exceeds_speed_limit(Duration::minutes(5)));
+        assert!(!segment.exceeds_speed_limit(Duration::minutes(6)));
+    }
 }
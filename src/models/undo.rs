@@ -1,10 +1,17 @@
+use chrono::NaiveDateTime;
 use super::{RailwayGraph, Line};
 
+/// Maximum number of named checkpoints kept before the oldest is evicted
+const MAX_CHECKPOINTS: usize = 20;
+
 /// A snapshot of the application state for undo/redo functionality
 #[derive(Clone)]
 pub struct UndoSnapshot {
     pub graph: RailwayGraph,
     pub lines: Vec<Line>,
+    /// Short description of the change that led to this state, filled in when recorded
+    pub label: String,
+    pub taken_at: NaiveDateTime,
 }
 
 impl UndoSnapshot {
@@ -13,8 +20,49 @@ impl UndoSnapshot {
         Self {
             graph,
             lines,
+            label: String::new(),
+            taken_at: chrono::Local::now().naive_local(),
         }
     }
+
+    /// Describe what changed between `previous` and this snapshot
+    fn describe_change(&self, previous: Option<&UndoSnapshot>) -> String {
+        let Some(previous) = previous else {
+            return "Initial state".to_string();
+        };
+
+        let describe = |before: usize, after: usize, noun: &str| match after.cmp(&before) {
+            std::cmp::Ordering::Greater => Some(format!("Added {noun}")),
+            std::cmp::Ordering::Less => Some(format!("Deleted {noun}")),
+            std::cmp::Ordering::Equal => None,
+        };
+
+        describe(previous.lines.len(), self.lines.len(), "line")
+            .or_else(|| describe(previous.graph.graph.node_count(), self.graph.graph.node_count(), "station"))
+            .or_else(|| describe(previous.graph.graph.edge_count(), self.graph.graph.edge_count(), "track"))
+            .unwrap_or_else(|| if previous.lines == self.lines {
+                "Edited infrastructure".to_string()
+            } else {
+                "Edited lines".to_string()
+            })
+    }
+}
+
+/// An entry in the undo history, as shown in the history panel
+#[derive(Clone, Debug, PartialEq)]
+pub struct HistoryEntry {
+    pub label: String,
+    pub taken_at: NaiveDateTime,
+    pub is_current: bool,
+}
+
+/// A named checkpoint, as shown in the checkpoint list
+#[derive(Clone, Debug, PartialEq)]
+pub struct CheckpointEntry {
+    /// Stable identifier, unique within one `UndoManager`
+    pub id: u64,
+    pub label: String,
+    pub taken_at: NaiveDateTime,
 }
 
 /// Manages undo/redo stacks with a configurable maximum depth
@@ -23,6 +71,8 @@ pub struct UndoManager {
     undo_stack: Vec<UndoSnapshot>,
     redo_stack: Vec<UndoSnapshot>,
     max_levels: usize,
+    checkpoints: Vec<(CheckpointEntry, UndoSnapshot)>,
+    next_checkpoint_id: u64,
 }
 
 impl UndoManager {
@@ -33,15 +83,21 @@ impl UndoManager {
             undo_stack: Vec::new(),
             redo_stack: Vec::new(),
             max_levels,
+            checkpoints: Vec::new(),
+            next_checkpoint_id: 0,
         }
     }
 
     /// Push a new snapshot onto the undo stack
     /// This clears the redo stack and enforces the maximum depth limit
-    pub fn push_snapshot(&mut self, snapshot: UndoSnapshot) {
+    pub fn push_snapshot(&mut self, mut snapshot: UndoSnapshot) {
         // Clear redo stack when new changes are made
         self.redo_stack.clear();
 
+        if snapshot.label.is_empty() {
+            snapshot.label = snapshot.describe_change(self.undo_stack.last());
+        }
+
         // Add to undo stack
         self.undo_stack.push(snapshot);
 
@@ -52,44 +108,78 @@ impl UndoManager {
     }
 
     /// Perform an undo operation, returning the previous snapshot if available
-    /// The current state should be provided to push onto the redo stack
-    pub fn undo(&mut self, current_snapshot: UndoSnapshot) -> Option<UndoSnapshot> {
+    /// The current state should be provided to push onto the redo stack.
+    /// The restored snapshot stays on top of the undo stack as the current state.
+    pub fn undo(&mut self, mut current_snapshot: UndoSnapshot) -> Option<UndoSnapshot> {
+        if !self.can_undo() {
+            return None;
+        }
+
         // The last item in undo_stack is the current state (since we record after changes)
-        // Pop it to discard it
-        self.undo_stack.pop()?;
-
-        // Now pop again to get the actual previous state
-        if let Some(previous_snapshot) = self.undo_stack.pop() {
-            // Push current state to redo stack
-            self.redo_stack.push(current_snapshot);
-
-            // Enforce maximum depth on redo stack
-            if self.redo_stack.len() > self.max_levels {
-                self.redo_stack.remove(0);
-            }
-
-            Some(previous_snapshot)
-        } else {
-            None
+        // Replace it with the freshest copy of the current state on the redo stack
+        let recorded = self.undo_stack.pop()?;
+        current_snapshot.label = recorded.label;
+        self.redo_stack.push(current_snapshot);
+
+        // Enforce maximum depth on redo stack
+        if self.redo_stack.len() > self.max_levels {
+            self.redo_stack.remove(0);
         }
+
+        self.undo_stack.last().cloned()
     }
 
     /// Perform a redo operation, returning the next snapshot if available
-    /// The current state should be provided to push onto the undo stack
-    pub fn redo(&mut self, current_snapshot: UndoSnapshot) -> Option<UndoSnapshot> {
-        if let Some(snapshot) = self.redo_stack.pop() {
-            // Push current state to undo stack
-            self.undo_stack.push(current_snapshot);
-
-            // Enforce maximum depth on undo stack
-            if self.undo_stack.len() > self.max_levels {
-                self.undo_stack.remove(0);
-            }
-
-            Some(snapshot)
-        } else {
-            None
+    /// The current state should be provided to replace the top of the undo stack
+    pub fn redo(&mut self, mut current_snapshot: UndoSnapshot) -> Option<UndoSnapshot> {
+        let snapshot = self.redo_stack.pop()?;
+
+        if let Some(recorded) = self.undo_stack.pop() {
+            current_snapshot.label = recorded.label;
+        }
+        self.undo_stack.push(current_snapshot);
+        self.undo_stack.push(snapshot.clone());
+
+        // Enforce maximum depth on undo stack
+        while self.undo_stack.len() > self.max_levels {
+            self.undo_stack.remove(0);
         }
+
+        Some(snapshot)
+    }
+
+    /// List the full history, oldest first, including states that can be redone
+    #[must_use]
+    pub fn history(&self) -> Vec<HistoryEntry> {
+        let current_index = self.undo_stack.len().checked_sub(1);
+        self.undo_stack.iter()
+            .chain(self.redo_stack.iter().rev())
+            .enumerate()
+            .map(|(index, snapshot)| HistoryEntry {
+                label: snapshot.label.clone(),
+                taken_at: snapshot.taken_at,
+                is_current: Some(index) == current_index,
+            })
+            .collect()
+    }
+
+    /// Rewind or replay to the history entry at `index`, returning its snapshot
+    /// Later entries move to the redo stack and are discarded by the next recorded change
+    pub fn restore_to(&mut self, index: usize) -> Option<UndoSnapshot> {
+        if index >= self.undo_stack.len() + self.redo_stack.len() {
+            return None;
+        }
+
+        while self.undo_stack.len() > index + 1 {
+            let snapshot = self.undo_stack.pop()?;
+            self.redo_stack.push(snapshot);
+        }
+        while self.undo_stack.len() < index + 1 {
+            let snapshot = self.redo_stack.pop()?;
+            self.undo_stack.push(snapshot);
+        }
+
+        self.undo_stack.last().cloned()
     }
 
     /// Check if undo is available
@@ -122,6 +212,37 @@ impl UndoManager {
     pub fn redo_depth(&self) -> usize {
         self.redo_stack.len()
     }
+
+    /// Store a labeled snapshot that survives undo/redo so it can be restored later
+    /// Only the most recent `MAX_CHECKPOINTS` checkpoints are kept
+    pub fn named_checkpoint(&mut self, label: &str, snapshot: UndoSnapshot) {
+        let entry = CheckpointEntry { id: self.next_checkpoint_id, label: label.to_string(), taken_at: snapshot.taken_at };
+        self.next_checkpoint_id += 1;
+        self.checkpoints.push((entry, snapshot));
+
+        if self.checkpoints.len() > MAX_CHECKPOINTS {
+            self.checkpoints.remove(0);
+        }
+    }
+
+    /// Remove all named checkpoints
+    pub fn clear_checkpoints(&mut self) {
+        self.checkpoints.clear();
+    }
+
+    /// List checkpoints, most recent first
+    #[must_use]
+    pub fn checkpoints(&self) -> Vec<CheckpointEntry> {
+        self.checkpoints.iter().rev().map(|(entry, _)| entry.clone()).collect()
+    }
+
+    /// Get the snapshot of the checkpoint with the given id, if it hasn't been evicted
+    #[must_use]
+    pub fn restore_checkpoint(&self, id: u64) -> Option<UndoSnapshot> {
+        self.checkpoints.iter()
+            .find(|(entry, _)| entry.id == id)
+            .map(|(_, snapshot)| snapshot.clone())
+    }
 }
 
 impl Default for UndoManager {
@@ -129,3 +250,133 @@ impl Default for UndoManager {
         Self::new(20)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Stations, Track, TrackDirection, Tracks};
+
+    fn two_station_graph() -> RailwayGraph {
+        let mut graph = RailwayGraph::new();
+        let a = graph.add_or_get_station("A".to_string());
+        let b = graph.add_or_get_station("B".to_string());
+        graph.add_track(a, b, vec![Track { direction: TrackDirection::Bidirectional }]);
+        graph
+    }
+
+    fn record_station_additions(manager: &mut UndoManager, count: usize) -> RailwayGraph {
+        let mut graph = RailwayGraph::new();
+        manager.push_snapshot(UndoSnapshot::new(graph.clone(), Vec::new()));
+        for i in 0..count {
+            graph.add_or_get_station(format!("Station {i}"));
+            manager.push_snapshot(UndoSnapshot::new(graph.clone(), Vec::new()));
+        }
+        graph
+    }
+
+    #[test]
+    fn test_restore_to_older_state() {
+        let mut manager = UndoManager::default();
+        record_station_additions(&mut manager, 3);
+
+        let history = manager.history();
+        assert_eq!(history.len(), 4);
+        assert_eq!(history[0].label, "Initial state");
+        assert_eq!(history[1].label, "Added station");
+        assert!(history[3].is_current);
+
+        let restored = manager.restore_to(1).expect("entry exists");
+        assert_eq!(restored.graph.graph.node_count(), 1);
+        assert!(manager.history()[1].is_current);
+        assert_eq!(manager.history().len(), 4);
+        assert_eq!(manager.redo_depth(), 2);
+
+        let replayed = manager.restore_to(3).expect("entry exists");
+        assert_eq!(replayed.graph.graph.node_count(), 3);
+        assert_eq!(manager.redo_depth(), 0);
+        assert!(manager.restore_to(4).is_none());
+    }
+
+    #[test]
+    fn test_new_edit_after_jump_discards_future() {
+        let mut manager = UndoManager::default();
+        record_station_additions(&mut manager, 3);
+
+        let mut restored = manager.restore_to(0).expect("entry exists");
+        restored.graph.add_or_get_station("Branch".to_string());
+        manager.push_snapshot(UndoSnapshot::new(restored.graph, Vec::new()));
+
+        let history = manager.history();
+        assert_eq!(history.len(), 2);
+        assert!(history[1].is_current);
+        assert!(!manager.can_redo());
+    }
+
+    #[test]
+    fn test_undo_keeps_restored_state_in_history() {
+        let mut manager = UndoManager::default();
+        let graph = record_station_additions(&mut manager, 2);
+
+        let previous = manager.undo(UndoSnapshot::new(graph.clone(), Vec::new())).expect("can undo");
+        assert_eq!(previous.graph.graph.node_count(), 1);
+        assert!(manager.history()[1].is_current);
+
+        let next = manager.redo(UndoSnapshot::new(previous.graph, Vec::new())).expect("can redo");
+        assert_eq!(next.graph.graph.node_count(), 2);
+        assert_eq!(manager.history().len(), 3);
+        assert!(manager.history()[2].is_current);
+    }
+
+    #[test]
+    fn test_named_checkpoint_restores_pre_delete_state() {
+        let mut manager = UndoManager::default();
+        let mut graph = two_station_graph();
+
+        manager.named_checkpoint("Delete station B", UndoSnapshot::new(graph.clone(), Vec::new()));
+        let b = graph.get_station_index("B").expect("station B");
+        graph.delete_station(b);
+        assert_eq!(graph.graph.node_count(), 1);
+
+        let checkpoints = manager.checkpoints();
+        assert_eq!(checkpoints.len(), 1);
+        assert_eq!(checkpoints[0].label, "Delete station B");
+        let restored = manager.restore_checkpoint(checkpoints[0].id).expect("checkpoint");
+        assert_eq!(restored.graph.graph.node_count(), 2);
+        assert_eq!(restored.graph.graph.edge_count(), 1);
+        assert!(restored.graph.get_station_index("B").is_some());
+    }
+
+    #[test]
+    fn test_checkpoints_are_capped_and_survive_undo_history() {
+        let mut manager = UndoManager::default();
+        for i in 0..MAX_CHECKPOINTS + 5 {
+            manager.named_checkpoint(&format!("Checkpoint {i}"), UndoSnapshot::new(RailwayGraph::new(), Vec::new()));
+        }
+        manager.clear();
+
+        let checkpoints = manager.checkpoints();
+        assert_eq!(checkpoints.len(), MAX_CHECKPOINTS);
+        assert_eq!(checkpoints[0].label, format!("Checkpoint {}", MAX_CHECKPOINTS + 4));
+        // The first checkpoint got id 0 and has been evicted
+        assert!(manager.restore_checkpoint(0).is_none());
+    }
+
+    #[test]
+    fn test_checkpoints_with_same_label_restore_their_own_state() {
+        let mut manager = UndoManager::default();
+        let mut graph = two_station_graph();
+
+        manager.named_checkpoint("Delete track", UndoSnapshot::new(graph.clone(), Vec::new()));
+        graph.add_or_get_station("C".to_string());
+        manager.named_checkpoint("Delete track", UndoSnapshot::new(graph.clone(), Vec::new()));
+
+        let checkpoints = manager.checkpoints();
+        assert_eq!(checkpoints.len(), 2);
+        assert_ne!(checkpoints[0].id, checkpoints[1].id);
+
+        let newest = manager.restore_checkpoint(checkpoints[0].id).expect("newest checkpoint");
+        let oldest = manager.restore_checkpoint(checkpoints[1].id).expect("oldest checkpoint");
+        assert_eq!(newest.graph.graph.node_count(), 3);
+        assert_eq!(oldest.graph.graph.node_count(), 2);
+    }
+}
@@ -1,16 +1,45 @@
 use serde::{Deserialize, Serialize};
 use super::keyboard_shortcuts::KeyboardShortcuts;
+use super::palette::ColorVisionMode;
 use crate::storage::idb;
 use wasm_bindgen::JsValue;
 
 const USER_SETTINGS_STORE: &str = "user_settings";
 const USER_SETTINGS_KEY: &str = "settings";
 
+fn default_storage_warning_fraction() -> f64 {
+    crate::storage::DEFAULT_STORAGE_WARNING_FRACTION
+}
+
+fn default_auto_save_delay_seconds() -> f64 {
+    crate::storage::DEFAULT_AUTO_SAVE_DELAY_SECONDS
+}
+
 /// User settings that persist across projects
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct UserSettings {
     #[serde(default)]
     pub keyboard_shortcuts: KeyboardShortcuts,
+    /// Fraction of the browser storage quota above which a warning is shown
+    #[serde(default = "default_storage_warning_fraction")]
+    pub storage_warning_fraction: f64,
+    /// Color vision deficiency that accessible line colors are chosen for
+    #[serde(default)]
+    pub color_vision_mode: ColorVisionMode,
+    /// Seconds without edits before the project is saved
+    #[serde(default = "default_auto_save_delay_seconds")]
+    pub auto_save_delay_seconds: f64,
+}
+
+impl Default for UserSettings {
+    fn default() -> Self {
+        Self {
+            keyboard_shortcuts: KeyboardShortcuts::default(),
+            storage_warning_fraction: default_storage_warning_fraction(),
+            color_vision_mode: ColorVisionMode::default(),
+            auto_save_delay_seconds: default_auto_save_delay_seconds(),
+        }
+    }
 }
 
 impl UserSettings {
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use petgraph::stable_graph::{NodeIndex, EdgeIndex};
 use uuid::Uuid;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use super::RailwayGraph;
 use super::railway_graph::stations::Stations;
 use super::railway_graph::routes::Routes;
@@ -128,6 +128,21 @@ fn dfs_longest_path(
 }
 
 impl GraphView {
+    /// Collect the saved viewport of each view, keyed by view id
+    #[must_use]
+    pub fn viewports(views: &[GraphView]) -> HashMap<Uuid, ViewportState> {
+        views.iter().map(|view| (view.id, view.viewport_state.clone())).collect()
+    }
+
+    /// Replace this view's viewport with the latest one remembered for it, if any
+    #[must_use]
+    pub fn with_saved_viewport(mut self, viewports: &HashMap<Uuid, ViewportState>) -> Self {
+        if let Some(viewport) = viewports.get(&self.id) {
+            self.viewport_state = viewport.clone();
+        }
+        self
+    }
+
     /// Create a default view showing the longest path in the graph (the "main line")
     /// Returns a view even if the graph is empty (`station_range` will be None until data is imported)
     #[must_use]
@@ -339,20 +354,53 @@ impl GraphView {
 
     /// Get the ordered list of nodes (stations and junctions) for rendering this view
     /// Returns Vec<(`NodeIndex`, `Node`)>
+    /// Depots at either end of the path are left off
     #[must_use]
     pub fn get_nodes_for_display(&self, graph: &RailwayGraph) -> Vec<(NodeIndex, crate::models::Node)> {
-        if let Some(path) = self.calculate_path(graph) {
+        if let Some(path) = self.display_path(graph) {
             path.iter()
                 .filter_map(|&node_idx| {
                     graph.graph.node_weight(node_idx).map(|node| (node_idx, node.clone()))
                 })
                 .collect()
         } else {
-            // No station range means show all nodes
+            // No station range means show all nodes except depots
             graph.get_all_nodes_ordered()
+                .into_iter()
+                .filter(|(_, node)| !node.is_depot())
+                .collect()
         }
     }
 
+    /// Node path shown on the time graph: the view path without depots at either end
+    /// Depots inside the path are kept so it stays contiguous
+    fn display_path(&self, graph: &RailwayGraph) -> Option<Vec<NodeIndex>> {
+        let path = self.calculate_path(graph)?;
+        let (leading, trailing) = depot_ends(graph, &path);
+        Some(path[leading..path.len() - trailing].to_vec())
+    }
+
+    /// Edge path matching `get_nodes_for_display`, without the edges leading into end depots
+    #[must_use]
+    pub fn display_edge_path(&self, graph: &RailwayGraph) -> Vec<usize> {
+        let edge_path = if let Some(ref edge_path) = self.edge_path {
+            edge_path.clone()
+        } else if let Some((from, to)) = self.station_range {
+            graph.find_path_between_nodes(from, to)
+                .map(|edges| edges.iter().map(|e| e.index()).collect())
+                .unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+
+        let Some(path) = self.calculate_path(graph) else {
+            return edge_path;
+        };
+        let (leading, trailing) = depot_ends(graph, &path);
+        let end = edge_path.len().saturating_sub(trailing).max(leading);
+        edge_path.get(leading..end).map(<[usize]>::to_vec).unwrap_or_default()
+    }
+
     /// Build a mapping from full-graph node indices to view display indices
     /// This is used for rendering conflicts/crossings which store indices from the full graph
     /// The display index accounts for ALL nodes (stations and junctions) in the view
@@ -368,7 +416,7 @@ impl GraphView {
             .map(|(enum_idx, node_idx)| (node_idx, enum_idx))
             .collect();
 
-        if let Some(path) = self.calculate_path(graph) {
+        if let Some(path) = self.display_path(graph) {
             // Map enumeration indices to display positions in the view
             path.iter()
                 .enumerate()
@@ -377,9 +425,10 @@ impl GraphView {
                 })
                 .collect()
         } else {
-            // No station range - get all nodes in BFS order
+            // No station range - get all nodes except depots in BFS order
             let all_nodes = graph.get_all_nodes_ordered();
             all_nodes.iter()
+                .filter(|(_, node)| !node.is_depot())
                 .enumerate()
                 .filter_map(|(display_idx, (node_idx, _))| {
                     node_to_enum_idx.get(node_idx).map(|&enum_idx| (enum_idx, display_idx))
@@ -399,6 +448,7 @@ impl GraphView {
         let visible_stations = self.visible_stations(graph);
 
         journeys.iter()
+            .map(TrainJourney::with_passed_stations)
             .filter_map(|journey| {
                 // If view has an edge path, check if journey uses any of those edges
                 if let Some(ref edges) = edge_set {
@@ -437,7 +487,7 @@ impl GraphView {
                         .map(|(_, seg)| seg.clone())
                         .collect();
 
-                    let mut filtered_journey = journey.clone();
+                    let mut filtered_journey = journey.clone().into_owned();
                     filtered_journey.station_times = filtered_times;
                     filtered_journey.segments = filtered_segments;
                     // Preserve original route start/end nodes so we know true endpoints
@@ -502,6 +552,16 @@ impl GraphView {
     }
 }
 
+/// Count the depots at the start and end of a path; a path of only depots is kept whole
+fn depot_ends(graph: &RailwayGraph, path: &[NodeIndex]) -> (usize, usize) {
+    let is_depot = |idx: &&NodeIndex| graph.graph.node_weight(**idx).is_some_and(crate::models::Node::is_depot);
+    let leading = path.iter().take_while(is_depot).count();
+    if leading == path.len() {
+        return (0, 0);
+    }
+    (leading, path.iter().rev().take_while(is_depot).count())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
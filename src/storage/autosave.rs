@@ -0,0 +1,186 @@
+/// Auto-save delay used until the user picks another, in seconds
+pub const DEFAULT_AUTO_SAVE_DELAY_SECONDS: f64 = 2.0;
+
+/// What the save indicator shows
+#[derive(Debug, Clone, PartialEq)]
+pub enum SaveStatus {
+    /// Nothing edited or saved yet
+    Idle,
+    /// Edits are waiting for the debounce to elapse
+    Unsaved,
+    Saving,
+    /// Last save finished at this time, in milliseconds since the epoch
+    Saved(f64),
+    Failed(String),
+}
+
+/// Debounce and in-flight guard for auto-saving, kept free of timers and storage.
+///
+/// Every edit gets a number. A save starts only when the debounce timer for the latest
+/// edit fires and no other save is running; edits made during a save trigger another
+/// save once it finishes. A flush saves the latest edit straight away, before the
+/// project is swapped out or the page goes away.
+#[derive(Debug, Clone, Default)]
+pub struct AutoSave {
+    latest_edit: u64,
+    saved_edit: u64,
+    saving: Option<u64>,
+    last_saved_at: Option<f64>,
+    error: Option<String>,
+}
+
+impl AutoSave {
+    /// Record an edit, returning its number for the debounce timer
+    pub fn record_edit(&mut self) -> u64 {
+        self.latest_edit += 1;
+        self.latest_edit
+    }
+
+    #[must_use]
+    pub fn latest_edit(&self) -> u64 {
+        self.latest_edit
+    }
+
+    /// Called when the debounce timer for `edit` fires; returns whether a save should start
+    pub fn start_save(&mut self, edit: u64) -> bool {
+        if edit != self.latest_edit || self.saving.is_some() || self.saved_edit == self.latest_edit {
+            return false;
+        }
+        self.saving = Some(edit);
+        true
+    }
+
+    /// Save the latest edit now without waiting for its debounce, even while another save runs.
+    /// Returns the edit to save, or `None` when it is already saved or being saved.
+    pub fn flush(&mut self) -> Option<u64> {
+        if self.saved_edit == self.latest_edit || self.saving == Some(self.latest_edit) {
+            return None;
+        }
+        self.saving = Some(self.latest_edit);
+        Some(self.latest_edit)
+    }
+
+    /// Record the outcome of the save of `edit` at `now_ms`.
+    /// Returns whether edits arrived while saving and no newer save is running, so another save should follow.
+    pub fn finish_save(&mut self, edit: u64, result: Result<(), String>, now_ms: f64) -> bool {
+        if self.saving == Some(edit) {
+            self.saving = None;
+        }
+        match result {
+            Ok(()) => {
+                self.saved_edit = self.saved_edit.max(edit);
+                self.last_saved_at = Some(now_ms);
+                self.error = None;
+            }
+            Err(e) => self.error = Some(e),
+        }
+        self.saving.is_none() && self.latest_edit != edit
+    }
+
+    #[must_use]
+    pub fn status(&self) -> SaveStatus {
+        if self.saving.is_some() {
+            SaveStatus::Saving
+        } else if let Some(error) = &self.error {
+            SaveStatus::Failed(error.clone())
+        } else if self.saved_edit != self.latest_edit {
+            SaveStatus::Unsaved
+        } else {
+            self.last_saved_at.map_or(SaveStatus::Idle, SaveStatus::Saved)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debounce_saves_only_latest_edit() {
+        let mut auto_save = AutoSave::default();
+        assert_eq!(auto_save.status(), SaveStatus::Idle);
+
+        let first = auto_save.record_edit();
+        let second = auto_save.record_edit();
+        assert_eq!(auto_save.status(), SaveStatus::Unsaved);
+
+        // The timer for the superseded edit does nothing
+        assert!(!auto_save.start_save(first));
+        assert!(auto_save.start_save(second));
+        assert_eq!(auto_save.status(), SaveStatus::Saving);
+
+        assert!(!auto_save.finish_save(second, Ok(()), 1_000.0));
+        assert_eq!(auto_save.status(), SaveStatus::Saved(1_000.0));
+
+        // Nothing new to save
+        assert!(!auto_save.start_save(second));
+    }
+
+    #[test]
+    fn test_edits_during_save_wait_for_it_then_save_again() {
+        let mut auto_save = AutoSave::default();
+        let first = auto_save.record_edit();
+        assert!(auto_save.start_save(first));
+
+        // The guard keeps a second save from overlapping the first
+        let second = auto_save.record_edit();
+        assert!(!auto_save.start_save(second));
+        assert_eq!(auto_save.status(), SaveStatus::Saving);
+
+        assert!(auto_save.finish_save(first, Ok(()), 1_000.0));
+        assert_eq!(auto_save.status(), SaveStatus::Unsaved);
+        assert!(auto_save.start_save(second));
+        assert!(!auto_save.finish_save(second, Ok(()), 2_000.0));
+        assert_eq!(auto_save.status(), SaveStatus::Saved(2_000.0));
+    }
+
+    #[test]
+    fn test_failed_save_is_reported_until_a_save_succeeds() {
+        let mut auto_save = AutoSave::default();
+        let edit = auto_save.record_edit();
+        assert!(auto_save.start_save(edit));
+        assert!(!auto_save.finish_save(edit, Err("quota exceeded".to_string()), 1_000.0));
+        assert_eq!(auto_save.status(), SaveStatus::Failed("quota exceeded".to_string()));
+
+        // The failed edit is still unsaved, so the next timer retries it
+        let retry = auto_save.record_edit();
+        assert!(auto_save.start_save(retry));
+        assert!(!auto_save.finish_save(retry, Ok(()), 2_000.0));
+        assert_eq!(auto_save.status(), SaveStatus::Saved(2_000.0));
+    }
+
+    #[test]
+    fn test_flush_saves_pending_edit_before_debounce() {
+        let mut auto_save = AutoSave::default();
+        assert_eq!(auto_save.flush(), None);
+
+        // An edit still waiting for its timer is saved at once
+        let edit = auto_save.record_edit();
+        assert_eq!(auto_save.flush(), Some(edit));
+        assert_eq!(auto_save.status(), SaveStatus::Saving);
+        assert_eq!(auto_save.flush(), None);
+
+        // The timer firing afterwards does not save it twice
+        assert!(!auto_save.start_save(edit));
+        assert!(!auto_save.finish_save(edit, Ok(()), 1_000.0));
+        assert_eq!(auto_save.status(), SaveStatus::Saved(1_000.0));
+        assert_eq!(auto_save.flush(), None);
+    }
+
+    #[test]
+    fn test_flush_during_save_writes_newer_edits() {
+        let mut auto_save = AutoSave::default();
+        let first = auto_save.record_edit();
+        assert!(auto_save.start_save(first));
+
+        // Switching projects mid-save still writes the edit made since
+        let second = auto_save.record_edit();
+        assert_eq!(auto_save.flush(), Some(second));
+
+        // The older save finishing neither clears the flush nor asks for another save
+        assert!(!auto_save.finish_save(first, Ok(()), 1_000.0));
+        assert_eq!(auto_save.status(), SaveStatus::Saving);
+        assert!(!auto_save.finish_save(second, Ok(()), 2_000.0));
+        assert_eq!(auto_save.status(), SaveStatus::Saved(2_000.0));
+    }
+}
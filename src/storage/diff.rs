@@ -0,0 +1,300 @@
+use crate::models::{Line, Node, Project, RailwayGraph, TrackSegment};
+use petgraph::visit::{EdgeRef, IntoEdgeReferences};
+use std::collections::BTreeMap;
+
+/// How an item differs between the two compared projects
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// A single station, track or line that differs between two projects
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffEntry {
+    pub kind: DiffKind,
+    /// Name for stations, endpoint names for tracks, uuid for lines
+    pub id: String,
+    /// Name in the second project, or in the first one for removed items
+    pub name: String,
+    /// Human-readable description of what changed, empty for added and removed items
+    pub details: Vec<String>,
+}
+
+/// Structural differences between two projects, per category
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProjectDiff {
+    pub stations: Vec<DiffEntry>,
+    pub tracks: Vec<DiffEntry>,
+    pub lines: Vec<DiffEntry>,
+}
+
+impl ProjectDiff {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.stations.is_empty() && self.tracks.is_empty() && self.lines.is_empty()
+    }
+}
+
+/// Compare two projects structurally; positions and other layout-only properties are ignored.
+///
+/// Stations and junctions are matched by name, since node indices differ between projects built
+/// separately, and tracks by the names of their endpoints. Lines are matched by id.
+#[must_use]
+pub fn diff_projects(a: &Project, b: &Project) -> ProjectDiff {
+    ProjectDiff {
+        stations: diff_keyed(&node_map(&a.graph), &node_map(&b.graph), Node::display_name, node_changes),
+        tracks: diff_keyed(&track_map(&a.graph), &track_map(&b.graph), |t| t.name.clone(), track_changes),
+        lines: diff_keyed(&line_map(&a.lines), &line_map(&b.lines), |l| l.name.clone(), line_changes),
+    }
+}
+
+/// Report added, removed and changed entries between two keyed collections
+fn diff_keyed<T>(
+    a: &BTreeMap<String, T>,
+    b: &BTreeMap<String, T>,
+    name: impl Fn(&T) -> String,
+    changes: impl Fn(&T, &T) -> Vec<String>,
+) -> Vec<DiffEntry> {
+    let entry = |kind, id: &String, item: &T, details| DiffEntry { kind, id: id.clone(), name: name(item), details };
+
+    let mut entries: Vec<DiffEntry> = a
+        .iter()
+        .filter_map(|(id, old)| match b.get(id) {
+            None => Some(entry(DiffKind::Removed, id, old, Vec::new())),
+            Some(new) => {
+                let details = changes(old, new);
+                (!details.is_empty()).then(|| entry(DiffKind::Changed, id, new, details))
+            }
+        })
+        .collect();
+
+    entries.extend(
+        b.iter()
+            .filter(|(id, _)| !a.contains_key(*id))
+            .map(|(id, new)| entry(DiffKind::Added, id, new, Vec::new())),
+    );
+    entries
+}
+
+/// Collect items under their keys, numbering repeats (e.g. parallel tracks) so none are lost
+fn numbered_map<T>(items: impl Iterator<Item = (String, T)>) -> BTreeMap<String, T> {
+    let mut map = BTreeMap::new();
+    for (key, item) in items {
+        let mut unique = key.clone();
+        let mut repeat = 1;
+        while map.contains_key(&unique) {
+            repeat += 1;
+            unique = format!("{key} #{repeat}");
+        }
+        map.insert(unique, item);
+    }
+    map
+}
+
+fn node_map(graph: &RailwayGraph) -> BTreeMap<String, Node> {
+    numbered_map(graph.graph.node_indices().map(|index| {
+        let node = graph.graph[index].clone();
+        (node.display_name(), node)
+    }))
+}
+
+fn node_changes(old: &Node, new: &Node) -> Vec<String> {
+    let mut details = Vec::new();
+    match (old, new) {
+        (Node::Station(old), Node::Station(new)) => {
+            if old.platforms.len() != new.platforms.len() {
+                details.push(format!("Platforms: {} → {}", old.platforms.len(), new.platforms.len()));
+            }
+            if old.passing_loop != new.passing_loop {
+                details.push(format!("Passing loop: {} → {}", old.passing_loop, new.passing_loop));
+            }
+            if old.is_depot != new.is_depot {
+                details.push(format!("Depot: {} → {}", old.is_depot, new.is_depot));
+            }
+        }
+        (Node::Junction(_), Node::Junction(_)) => {}
+        _ => details.push("Changed between station and junction".to_string()),
+    }
+    details
+}
+
+/// A track segment together with the names of the nodes it connects
+struct NamedTrack {
+    name: String,
+    segment: TrackSegment,
+}
+
+/// Key tracks by the names of their endpoints, so that node and edge index changes do not
+/// show up as differences; parallel tracks between the same stations are numbered in edge order
+fn track_map(graph: &RailwayGraph) -> BTreeMap<String, NamedTrack> {
+    let mut edges: Vec<_> = graph.graph.edge_references().collect();
+    edges.sort_by_key(EdgeRef::id);
+    numbered_map(edges.into_iter().map(|edge| {
+        let mut names = [graph.graph[edge.source()].display_name(), graph.graph[edge.target()].display_name()];
+        let name = names.join(" – ");
+        names.sort();
+        (names.join(" – "), NamedTrack { name, segment: edge.weight().clone() })
+    }))
+}
+
+fn format_optional(value: Option<f64>, unit: &str) -> String {
+    value.map_or_else(|| "unset".to_string(), |v| format!("{v} {unit}"))
+}
+
+fn track_changes(old: &NamedTrack, new: &NamedTrack) -> Vec<String> {
+    let (old, new) = (&old.segment, &new.segment);
+    let mut details = Vec::new();
+    if old.tracks.len() != new.tracks.len() {
+        details.push(format!("Tracks: {} → {}", old.tracks.len(), new.tracks.len()));
+    }
+    if old.distance != new.distance {
+        details.push(format!("Distance: {} → {}", format_optional(old.distance, "km"), format_optional(new.distance, "km")));
+    }
+    if old.speed_limit_kph != new.speed_limit_kph {
+        details.push(format!(
+            "Speed limit: {} → {}",
+            format_optional(old.speed_limit_kph, "km/h"),
+            format_optional(new.speed_limit_kph, "km/h")
+        ));
+    }
+    if old.electrification != new.electrification {
+        details.push("Electrification changed".to_string());
+    }
+    details
+}
+
+fn line_map(lines: &[Line]) -> BTreeMap<String, Line> {
+    lines.iter().map(|line| (line.id.to_string(), line.clone())).collect()
+}
+
+fn line_changes(old: &Line, new: &Line) -> Vec<String> {
+    if old == new {
+        return Vec::new();
+    }
+
+    let mut details = Vec::new();
+    if old.name != new.name {
+        details.push(format!("Renamed from '{}'", old.name));
+    }
+    if old.forward_route != new.forward_route || old.return_route != new.return_route {
+        details.push("Route changed".to_string());
+    }
+    if old.frequency != new.frequency {
+        details.push(format!(
+            "Frequency: {} → {} min",
+            old.frequency.num_minutes(),
+            new.frequency.num_minutes()
+        ));
+    }
+    if old.first_departure != new.first_departure
+        || old.last_departure != new.last_departure
+        || old.manual_departures != new.manual_departures
+        || old.schedule_mode != new.schedule_mode
+    {
+        details.push("Schedule changed".to_string());
+    }
+    if details.is_empty() {
+        details.push("Settings changed".to_string());
+    }
+    details
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Stations, Track, TrackDirection, Tracks};
+
+    fn base_project() -> Project {
+        let mut project = Project::new_with_name("Base".to_string());
+        let a = project.graph.add_or_get_station("A".to_string());
+        let b = project.graph.add_or_get_station("B".to_string());
+        let c = project.graph.add_or_get_station("C".to_string());
+        let track = || vec![Track { direction: TrackDirection::Bidirectional }];
+        project.graph.add_track(a, b, track());
+        project.graph.add_track(b, c, track());
+        project.lines = Line::create_from_ids(&["L1".to_string()], 0);
+        project
+    }
+
+    #[test]
+    fn test_identical_projects_have_no_diff() {
+        let project = base_project();
+        assert!(diff_projects(&project, &project.clone()).is_empty());
+    }
+
+    #[test]
+    fn test_diff_added_line() {
+        let a = base_project();
+        let mut b = a.clone();
+        let added = Line::create_from_ids(&["L2".to_string()], 1).remove(0);
+        b.lines.push(added.clone());
+
+        let diff = diff_projects(&a, &b);
+        assert_eq!(diff.lines.len(), 1);
+        assert_eq!(diff.lines[0].kind, DiffKind::Added);
+        assert_eq!(diff.lines[0].id, added.id.to_string());
+        assert_eq!(diff.lines[0].name, "L2");
+        assert!(diff.stations.is_empty());
+        assert!(diff.tracks.is_empty());
+    }
+
+    #[test]
+    fn test_diff_removed_station() {
+        let a = base_project();
+        let mut b = a.clone();
+        let c = b.graph.get_station_index("C").expect("station exists");
+        b.graph.graph.remove_node(c);
+        b.graph.station_name_to_index.remove("C");
+
+        let diff = diff_projects(&a, &b);
+        assert_eq!(diff.stations.len(), 1);
+        assert_eq!(diff.stations[0].kind, DiffKind::Removed);
+        assert_eq!(diff.stations[0].name, "C");
+        // The track to the removed station goes with it
+        assert_eq!(diff.tracks.len(), 1);
+        assert_eq!(diff.tracks[0].kind, DiffKind::Removed);
+        assert_eq!(diff.tracks[0].name, "B – C");
+    }
+
+    #[test]
+    fn test_diff_matches_by_name_not_index() {
+        // The same network built in another order, with a second track added between A and B
+        let a = base_project();
+        let mut b = Project::new_with_name("Rebuilt".to_string());
+        let c = b.graph.add_or_get_station("C".to_string());
+        let station_b = b.graph.add_or_get_station("B".to_string());
+        let station_a = b.graph.add_or_get_station("A".to_string());
+        let track = || vec![Track { direction: TrackDirection::Bidirectional }];
+        b.graph.add_track(c, station_b, track());
+        b.graph.add_track(station_a, station_b, track());
+        b.graph.add_track(station_b, station_a, track());
+        b.graph.set_station_position(station_b, (120.0, 40.0));
+        b.lines.clone_from(&a.lines);
+
+        let diff = diff_projects(&a, &b);
+        assert!(diff.stations.is_empty());
+        assert_eq!(diff.tracks.len(), 1);
+        assert_eq!(diff.tracks[0].kind, DiffKind::Added);
+        assert_eq!(diff.tracks[0].id, "A – B #2");
+        assert_eq!(diff.tracks[0].name, "B – A");
+    }
+
+    #[test]
+    fn test_diff_changed_station() {
+        let a = base_project();
+        let mut b = a.clone();
+        let index = b.graph.get_station_index("B").expect("station exists");
+        if let Node::Station(station) = &mut b.graph.graph[index] {
+            station.passing_loop = true;
+        }
+
+        let diff = diff_projects(&a, &b);
+        assert_eq!(diff.stations.len(), 1);
+        let entry = &diff.stations[0];
+        assert_eq!(entry.kind, DiffKind::Changed);
+        assert_eq!(entry.id, "B");
+        assert_eq!(entry.details, vec!["Passing loop: false → true".to_string()]);
+    }
+}
@@ -0,0 +1,130 @@
+use crate::models::Project;
+use crate::storage::CURRENT_PROJECT_VERSION;
+use serde::{Deserialize, Serialize};
+
+/// JSON envelope wrapping a project with the schema version it was written with
+#[derive(Serialize)]
+struct ProjectJsonExport<'a> {
+    schema_version: u32,
+    project: &'a Project,
+}
+
+#[derive(Deserialize)]
+struct ProjectJsonHeader {
+    schema_version: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct ProjectJsonImport {
+    project: Project,
+}
+
+/// Serialize a project to a portable JSON document
+///
+/// # Errors
+/// Returns an error if JSON serialization fails
+pub fn project_to_json(project: &Project) -> Result<String, String> {
+    serde_json::to_string(&ProjectJsonExport {
+        schema_version: CURRENT_PROJECT_VERSION,
+        project,
+    })
+    .map_err(|e| format!("Failed to serialize project: {e}"))
+}
+
+/// Deserialize a project from a JSON document produced by [`project_to_json`]
+///
+/// # Errors
+/// Returns an error if the JSON is invalid, has no or an incompatible schema version,
+/// or does not contain a valid project
+pub fn project_from_json(json: &str) -> Result<Project, String> {
+    let header: ProjectJsonHeader = serde_json::from_str(json)
+        .map_err(|e| format!("Invalid project JSON: {e}"))?;
+
+    match header.schema_version {
+        Some(CURRENT_PROJECT_VERSION) => {}
+        Some(version) => {
+            return Err(format!(
+                "Incompatible project schema version {version}; this version of the app reads version {CURRENT_PROJECT_VERSION}"
+            ));
+        }
+        None => return Err("Invalid project JSON: missing schema_version".to_string()),
+    }
+
+    let mut project = serde_json::from_str::<ProjectJsonImport>(json)
+        .map_err(|e| format!("Failed to parse project: {e}"))?
+        .project;
+
+    // Validate and fix any invalid track indices in all lines
+    project.fix_invalid_track_indices();
+
+    // Populate missing line codes from line names
+    project.populate_missing_line_codes();
+
+    Ok(project)
+}
+
+/// Create a download filename for a JSON project export
+#[must_use]
+pub fn create_json_export_filename(project_name: &str) -> String {
+    let now = chrono::Utc::now();
+    format!(
+        "{}.{}.json",
+        project_name.replace(['/', '\\', ':', '*', '?', '"', '<', '>', '|'], "_"),
+        now.format("%Y-%m-%d-%H%M%S")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Line, Stations, Track, TrackDirection, Tracks};
+
+    fn sample_project() -> Project {
+        let mut project = Project::new_with_name("Round Trip".to_string());
+        let a = project.graph.add_or_get_station("A".to_string());
+        let b = project.graph.add_or_get_station("B".to_string());
+        let edge = project.graph.add_track(a, b, vec![Track { direction: TrackDirection::Bidirectional }]);
+        if let Some(segment) = project.graph.graph.edge_weight_mut(edge) {
+            segment.distance = Some(4.2);
+        }
+        project.lines = Line::create_from_ids(&["L1".to_string(), "L2".to_string()], 0);
+        project
+    }
+
+    #[test]
+    fn test_project_json_round_trip() {
+        let project = sample_project();
+        let json = project_to_json(&project).expect("Failed to serialize");
+        let restored = project_from_json(&json).expect("Failed to deserialize");
+
+        assert_eq!(restored.metadata.id, project.metadata.id);
+        assert_eq!(restored.metadata.name, project.metadata.name);
+        assert_eq!(restored.graph.get_all_station_names(), project.graph.get_all_station_names());
+        assert_eq!(restored.graph.graph.edge_count(), 1);
+        assert_eq!(
+            restored.graph.graph.edge_weights().next().and_then(|t| t.distance),
+            Some(4.2)
+        );
+        let names = |p: &Project| p.lines.iter().map(|l| (l.id, l.name.clone())).collect::<Vec<_>>();
+        assert_eq!(names(&restored), names(&project));
+    }
+
+    #[test]
+    fn test_project_json_rejects_incompatible_version() {
+        let json = project_to_json(&sample_project()).expect("Failed to serialize");
+        let bumped = json.replacen(
+            &format!("\"schema_version\":{CURRENT_PROJECT_VERSION}"),
+            &format!("\"schema_version\":{}", CURRENT_PROJECT_VERSION + 1),
+            1,
+        );
+
+        let err = project_from_json(&bumped).expect_err("Expected version error");
+        assert!(err.contains("Incompatible project schema version"));
+    }
+
+    #[test]
+    fn test_project_json_requires_schema_version() {
+        let err = project_from_json("{\"project\":{}}").expect_err("Expected error");
+        assert!(err.contains("missing schema_version"));
+    }
+}
@@ -1,8 +1,13 @@
 mod indexeddb;
 mod file;
 pub mod idb;
+pub mod export;
+mod diff;
+mod autosave;
 
 pub use indexeddb::IndexedDbStorage;
+pub use autosave::{AutoSave, SaveStatus, DEFAULT_AUTO_SAVE_DELAY_SECONDS};
+pub use diff::{diff_projects, DiffEntry, DiffKind, ProjectDiff};
 pub use file::{serialize_project_to_bytes, deserialize_project_from_bytes, create_export_filename, trigger_download, regenerate_project_ids};
 
 use crate::models::{Project, ProjectMetadata};
@@ -14,6 +19,9 @@ const GB: f64 = 1_073_741_824.0;
 const MB: f64 = 1_048_576.0;
 const KB: f64 = 1_024.0;
 
+/// Fraction of the storage quota above which the user is warned by default
+pub const DEFAULT_STORAGE_WARNING_FRACTION: f64 = 0.8;
+
 /// Format bytes into a human-readable string with appropriate units
 #[must_use]
 pub fn format_bytes(bytes: u64) -> String {
@@ -31,6 +39,17 @@ pub fn format_bytes(bytes: u64) -> String {
     }
 }
 
+/// Whether used storage is above `warning_fraction` of the quota.
+/// Backends without quota information (`None`) or with an empty quota never warn.
+#[must_use]
+pub fn storage_usage_exceeds(quota: Option<(u64, u64)>, warning_fraction: f64) -> bool {
+    match quota {
+        #[allow(clippy::cast_precision_loss)]
+        Some((used, total)) if total > 0 => used as f64 / total as f64 > warning_fraction,
+        _ => false,
+    }
+}
+
 /// Storage trait for project persistence
 #[allow(async_fn_in_trait)]
 pub trait Storage {
@@ -59,3 +78,20 @@ pub trait Storage {
         Ok(None)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_storage_usage_exceeds_threshold() {
+        assert!(!storage_usage_exceeds(Some((700, 1000)), 0.8));
+        assert!(!storage_usage_exceeds(Some((800, 1000)), 0.8));
+        assert!(storage_usage_exceeds(Some((801, 1000)), 0.8));
+        assert!(storage_usage_exceeds(Some((600, 1000)), 0.5));
+
+        // No quota information hides the warning
+        assert!(!storage_usage_exceeds(None, 0.8));
+        assert!(!storage_usage_exceeds(Some((10, 0)), 0.8));
+    }
+}
@@ -1,7 +1,12 @@
-use chrono::{NaiveDateTime, NaiveTime};
+use chrono::{Duration, NaiveDateTime, NaiveTime};
 use crate::constants::BASE_DATE;
 use wasm_bindgen::JsValue;
 
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Unit suffixes accepted by the flexible parsers, largest first
+const TIME_UNITS: [(char, i64); 3] = [('h', 3600), ('m', 60), ('s', 1)];
+
 /// Convert a `NaiveDateTime` to a fraction of hours since `BASE_DATE`
 #[must_use]
 pub fn time_to_fraction(time: NaiveDateTime) -> f64 {
@@ -42,8 +47,7 @@ pub fn time_to_fraction(time: NaiveDateTime) -> f64 {
 /// - Three numbers: hours, minutes, seconds (e.g., "5.15." = 05:15:00)
 /// - Separators: . , : ; (e.g., "1:2:3" or "1.2.3" or "1,2,3" or "1;2;3")
 /// - Empty parts treated as zero (e.g., "6.." = 06:00:00)
-#[must_use]
-pub fn parse_flexible_time(input: &str) -> Option<(i64, i64, i64)> {
+fn parse_time_parts(input: &str) -> Option<(i64, i64, i64)> {
     // Reject empty or whitespace-only strings
     if input.trim().is_empty() {
         return None;
@@ -83,6 +87,100 @@ pub fn parse_flexible_time(input: &str) -> Option<(i64, i64, i64)> {
     }
 }
 
+/// Parse a unit-suffixed amount such as "8h5m" or "485s" into seconds
+///
+/// Units must appear at most once and in h, m, s order.
+fn parse_unit_seconds(input: &str) -> Option<i64> {
+    let mut total: i64 = 0;
+    let mut digits = String::new();
+    let mut last_rank = None;
+
+    for c in input.chars().filter(|c| !c.is_whitespace()) {
+        if c.is_ascii_digit() {
+            digits.push(c);
+            continue;
+        }
+        let rank = TIME_UNITS.iter().position(|(unit, _)| *unit == c.to_ascii_lowercase())?;
+        if digits.is_empty() || last_rank.is_some_and(|last| rank <= last) {
+            return None;
+        }
+        let value: i64 = digits.parse().ok()?;
+        total = total.checked_add(value.checked_mul(TIME_UNITS[rank].1)?)?;
+        digits.clear();
+        last_rank = Some(rank);
+    }
+
+    (digits.is_empty() && last_rank.is_some()).then_some(total)
+}
+
+/// Build a time of day, rejecting out-of-range components
+fn clock_time(hours: i64, minutes: i64, seconds: i64) -> Option<NaiveTime> {
+    if !(0..24).contains(&hours) || !(0..60).contains(&minutes) || !(0..60).contains(&seconds) {
+        return None;
+    }
+    time_from_seconds(hours * 3600 + minutes * 60 + seconds)
+}
+
+fn time_from_seconds(seconds: i64) -> Option<NaiveTime> {
+    if !(0..SECONDS_PER_DAY).contains(&seconds) {
+        return None;
+    }
+    NaiveTime::from_num_seconds_from_midnight_opt(u32::try_from(seconds).ok()?, 0)
+}
+
+/// Parse digits without separators: "805"/"0805" as HHMM, "080530" as HHMMSS
+fn parse_compact_time(digits: &str) -> Option<NaiveTime> {
+    let value: i64 = digits.parse().ok()?;
+    match digits.len() {
+        3 | 4 => clock_time(value / 100, value % 100, 0),
+        6 => clock_time(value / 10_000, value / 100 % 100, value % 100),
+        _ => None,
+    }
+}
+
+/// Parse a time of day typed in any of the common forms
+///
+/// Supports:
+/// - Clock notation with a single colon: "8:05" = 08:05:00
+/// - Compact digits: "0805" or "805" = 08:05:00, "080530" = 08:05:30
+/// - Unit suffixes: "8h5m" = 08:05:00, "485s" = 00:08:05
+/// - Everything `parse_time_hms` accepts in NIMBY Rails format, e.g. "5.15." or "1:2:3"
+#[must_use]
+pub fn parse_flexible_time(input: &str) -> Option<NaiveTime> {
+    let input = input.trim();
+    if input.chars().any(|c| c.is_ascii_alphabetic()) {
+        return parse_unit_seconds(input).and_then(time_from_seconds);
+    }
+    if input.len() > 2 && input.chars().all(|c| c.is_ascii_digit()) {
+        return parse_compact_time(input);
+    }
+    if let Some((hours, minutes)) = input.split_once(':').filter(|(_, rest)| !rest.contains([':', '.', ',', ';'])) {
+        return clock_time(hours.parse().ok()?, minutes.parse().ok()?, 0);
+    }
+    let (hours, minutes, seconds) = parse_time_parts(input)?;
+    clock_time(hours, minutes, seconds)
+}
+
+/// Parse a duration typed in any of the common forms
+///
+/// Supports unit suffixes ("8h5m", "485s") and NIMBY Rails format, where a
+/// single number is seconds and "8:05" is minutes and seconds. Components are
+/// not capped, so "0.90.0" is 90 minutes.
+#[must_use]
+pub fn parse_flexible_duration(input: &str) -> Option<Duration> {
+    let input = input.trim();
+    let seconds = if input.chars().any(|c| c.is_ascii_alphabetic()) {
+        parse_unit_seconds(input)?
+    } else {
+        let (hours, minutes, seconds) = parse_time_parts(input)?;
+        if hours < 0 || minutes < 0 || seconds < 0 {
+            return None;
+        }
+        hours.checked_mul(3600)?.checked_add(minutes.checked_mul(60)?)?.checked_add(seconds)?
+    };
+    Some(Duration::seconds(seconds))
+}
+
 /// Parse a time string in HH:MM:SS format or NIMBY Rails format
 ///
 /// # Errors
@@ -90,7 +188,7 @@ pub fn parse_flexible_time(input: &str) -> Option<(i64, i64, i64)> {
 /// Returns an error if the string cannot be parsed as a valid time.
 pub fn parse_time_hms(s: &str) -> Result<NaiveTime, chrono::ParseError> {
     // Try flexible format first
-    if let Some((hours, minutes, seconds)) = parse_flexible_time(s) {
+    if let Some((hours, minutes, seconds)) = parse_time_parts(s) {
         // Validate ranges
         if (0..24).contains(&hours) && (0..60).contains(&minutes) && (0..60).contains(&seconds) {
             // Safe to cast: we just validated the ranges
@@ -329,4 +427,68 @@ mod tests {
         let result = parse_time_hms("1.2.61");
         assert!(result.is_err());
     }
-}
\ No newline at end of file
+
+    fn hms(hours: u32, minutes: u32, seconds: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hours, minutes, seconds).expect("valid time")
+    }
+
+    #[test]
+    fn test_flexible_time_clock_notation() {
+        assert_eq!(parse_flexible_time("8:05"), Some(hms(8, 5, 0)));
+        assert_eq!(parse_flexible_time("08:05"), Some(hms(8, 5, 0)));
+        assert_eq!(parse_flexible_time(" 23:59 "), Some(hms(23, 59, 0)));
+        assert_eq!(parse_flexible_time("08:05:30"), Some(hms(8, 5, 30)));
+    }
+
+    #[test]
+    fn test_flexible_time_compact_digits() {
+        assert_eq!(parse_flexible_time("0805"), Some(hms(8, 5, 0)));
+        assert_eq!(parse_flexible_time("805"), Some(hms(8, 5, 0)));
+        assert_eq!(parse_flexible_time("2359"), Some(hms(23, 59, 0)));
+        assert_eq!(parse_flexible_time("080530"), Some(hms(8, 5, 30)));
+    }
+
+    #[test]
+    fn test_flexible_time_unit_suffixes() {
+        assert_eq!(parse_flexible_time("8h5m"), Some(hms(8, 5, 0)));
+        assert_eq!(parse_flexible_time("8h 5m 30s"), Some(hms(8, 5, 30)));
+        assert_eq!(parse_flexible_time("8H"), Some(hms(8, 0, 0)));
+        assert_eq!(parse_flexible_time("485s"), Some(hms(0, 8, 5)));
+        assert_eq!(parse_flexible_time("90m"), Some(hms(1, 30, 0)));
+    }
+
+    #[test]
+    fn test_flexible_time_nimby_format() {
+        assert_eq!(parse_flexible_time("45"), Some(hms(0, 0, 45)));
+        assert_eq!(parse_flexible_time("3.30"), Some(hms(0, 3, 30)));
+        assert_eq!(parse_flexible_time("5.15."), Some(hms(5, 15, 0)));
+        assert_eq!(parse_flexible_time("1;2;3"), Some(hms(1, 2, 3)));
+    }
+
+    #[test]
+    fn test_flexible_time_rejects_invalid_input() {
+        for input in ["", "   ", "25:99", "24:00", "8:60", "2460", "0875", "12345", "250000", "24h", "8m5h", "8h8h", "5x", "h", "8h5", "ab:cd", "8:", "-1:00"] {
+            assert_eq!(parse_flexible_time(input), None, "{input:?} should be rejected");
+        }
+    }
+
+    #[test]
+    fn test_flexible_duration_forms() {
+        let expected = Duration::seconds(485);
+        assert_eq!(parse_flexible_duration("485s"), Some(expected));
+        assert_eq!(parse_flexible_duration("485"), Some(expected));
+        assert_eq!(parse_flexible_duration("8:05"), Some(expected));
+        assert_eq!(parse_flexible_duration("8m5s"), Some(expected));
+        assert_eq!(parse_flexible_duration("00:08:05"), Some(expected));
+        assert_eq!(parse_flexible_duration("8h5m"), Some(Duration::minutes(485)));
+        assert_eq!(parse_flexible_duration("26h"), Some(Duration::hours(26)));
+        assert_eq!(parse_flexible_duration("0.90.0"), Some(Duration::minutes(90)));
+    }
+
+    #[test]
+    fn test_flexible_duration_rejects_invalid_input() {
+        for input in ["", "-5", "0:-5", "5s8m", "5 minutes", "1:2:3:4", "5ss", "99999999999999999999s"] {
+            assert_eq!(parse_flexible_duration(input), None, "{input:?} should be rejected");
+        }
+    }
+}
@@ -1,21 +1,104 @@
-use crate::models::{Line, RailwayGraph, ScheduleMode, Tracks, DaysOfWeek};
-use crate::constants::BASE_DATE;
-use chrono::{Duration, NaiveDateTime, Timelike, Weekday};
-use std::collections::HashMap;
+use crate::models::{Line, Node, Project, RailwayGraph, ScheduleMode, Tracks, DaysOfWeek, TrackSegment, RollingStockProfile, ServicePattern};
+use crate::constants::{BASE_DATE, BASE_MIDNIGHT};
+use chrono::{Datelike, Duration, NaiveDateTime, Timelike, Weekday};
+use petgraph::stable_graph::{EdgeIndex, NodeIndex};
+use std::collections::{HashMap, HashSet};
 
 const MAX_JOURNEYS_PER_LINE: usize = 100; // Limit to prevent performance issues
 
+/// Tokens whose value depends only on the line and departure time, not on generation order
+const STABLE_NUMBER_TOKENS: [&str; 2] = ["{hhmm}", "{seq_stable}"];
+
+/// Single-track edges at or above this occupancy percentile count as critical sections
+const CRITICAL_SECTION_PERCENTILE: f64 = 0.75;
+
+/// Shortest stop at a reversal, giving the driver time to change ends
+const MIN_REVERSAL_DWELL_SECS: i64 = 180;
+
 /// Generate a train number from a format string
-/// Supports: {line} for line ID, {seq:04} for sequence number with padding
-fn generate_train_number(format: &str, line_id: &str, sequence: usize) -> String {
+/// Supports: {line} for line ID, {seq:04} for sequence number with padding,
+/// `{hhmm}` for the departure time and `{seq_stable}` for a four-digit number derived from line and departure
+fn generate_train_number(format: &str, line_id: &str, sequence: usize, line_uuid: uuid::Uuid, departure: NaiveDateTime) -> String {
     format
         .replace("{line}", line_id)
+        .replace("{hhmm}", &departure.format("%H%M").to_string())
+        .replace("{seq_stable}", &format!("{:04}", stable_sequence(line_uuid, departure)))
         .replace("{seq:04}", &format!("{sequence:04}"))
         .replace("{seq:03}", &format!("{sequence:03}"))
         .replace("{seq:02}", &format!("{sequence:02}"))
         .replace("{seq}", &sequence.to_string())
 }
 
+/// Number in 0..10000 from an FNV-1a hash of the line and the departure's time of day
+/// Unlike `DefaultHasher`, the result is the same across builds
+fn stable_sequence(line_uuid: uuid::Uuid, departure: NaiveDateTime) -> u64 {
+    const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+    line_uuid.as_bytes().iter()
+        .chain(departure.time().num_seconds_from_midnight().to_le_bytes().iter())
+        .fold(FNV_OFFSET, |hash, byte| (hash ^ u64::from(*byte)).wrapping_mul(FNV_PRIME))
+        % 10000
+}
+
+/// Days simulated when generating journeys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SimulationHorizon {
+    /// The whole anchored week, plus the Sunday before it so late trains running into Monday are kept
+    #[default]
+    FullWeek,
+    /// Only this weekday, generated on `BASE_DATE` without wraparound from the day before
+    SingleDay(Weekday),
+    /// Days `first..=last` of the anchored week as offsets from Monday (`BASE_DATE`),
+    /// plus the day before `first` so late trains running into the range are kept
+    DayRange { first: i64, last: i64 },
+}
+
+impl SimulationHorizon {
+    /// Each simulated day as its weekday and offset from `BASE_DATE`
+    fn days(self) -> Vec<(Weekday, i64)> {
+        match self {
+            Self::FullWeek => Self::DayRange { first: 0, last: 6 }.days(),
+            Self::SingleDay(day) => vec![(day, 0)],
+            Self::DayRange { first, last } => (first - 1..=last)
+                .map(|offset| ((BASE_DATE + Duration::days(offset)).weekday(), offset))
+                .collect(),
+        }
+    }
+
+    /// Journeys must reach this time to be kept; earlier ones only belong to the wraparound day
+    fn retention_start(self) -> Option<NaiveDateTime> {
+        match self {
+            Self::FullWeek => Self::DayRange { first: 0, last: 6 }.retention_start(),
+            Self::SingleDay(_) => None,
+            Self::DayRange { first, .. } => Some(BASE_MIDNIGHT + Duration::days(first)),
+        }
+    }
+
+    /// Real date a simulated day checks operating periods and pattern exceptions against
+    fn calendar_date(self, weekday: Weekday, generated_on: chrono::NaiveDate, reference_week: Option<chrono::NaiveDate>) -> chrono::NaiveDate {
+        let simulated = match self {
+            // A single selected day is generated on `BASE_DATE`, but its operating
+            // period check uses that weekday's date in the anchored week
+            Self::SingleDay(_) => BASE_DATE + Duration::days(i64::from(weekday.num_days_from_monday())),
+            Self::FullWeek | Self::DayRange { .. } => generated_on,
+        };
+        real_date(simulated, reference_week)
+    }
+}
+
+/// Time spent at the end of `segment`: none when passing through a junction, and at least
+/// long enough to change ends when the train reverses there
+fn dwell_after(segment: &crate::models::RouteSegment, arrives_at_junction: bool) -> Duration {
+    if segment.reverses {
+        segment.wait_time.max(Duration::seconds(MIN_REVERSAL_DWELL_SECS))
+    } else if arrives_at_junction {
+        Duration::zero()
+    } else {
+        segment.wait_time
+    }
+}
+
 /// Convert `chrono::Weekday` to our `DaysOfWeek` bitflag
 fn weekday_to_days_of_week(weekday: Weekday) -> DaysOfWeek {
     match weekday {
@@ -29,17 +112,90 @@ fn weekday_to_days_of_week(weekday: Weekday) -> DaysOfWeek {
     }
 }
 
+/// Real date of a day in the simulated week, which starts on `BASE_DATE` and stands for
+/// the week (Monday to Sunday) containing `reference_week`
+/// Without a reference week the simulated date is returned unchanged
+#[must_use]
+pub fn real_date(simulated: chrono::NaiveDate, reference_week: Option<chrono::NaiveDate>) -> chrono::NaiveDate {
+    let Some(reference) = reference_week else {
+        return simulated;
+    };
+    let monday = reference - Duration::days(i64::from(reference.weekday().num_days_from_monday()));
+    monday + (simulated - BASE_DATE)
+}
+
 /// Convert a `NaiveDateTime` to a specific date while preserving time components
 fn time_on_date(datetime: NaiveDateTime, date: chrono::NaiveDate) -> Option<NaiveDateTime> {
     date.and_hms_opt(datetime.hour(), datetime.minute(), datetime.second())
 }
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+/// Derive a run time from a track's distance (km)
+/// With a rolling stock profile the train accelerates to the lower of its top speed and the
+/// track speed limit; otherwise it runs at the speed limit throughout
+/// Returns `None` when the distance or a usable speed is missing
+#[must_use]
+pub fn compute_duration_from_distance(
+    track_segment: &TrackSegment,
+    rolling_stock: Option<&RollingStockProfile>,
+) -> Option<Duration> {
+    let distance_km = track_segment.distance.filter(|d| *d > 0.0)?;
+    let speed_limit_kph = track_segment.speed_limit_kph.filter(|s| *s > 0.0);
+
+    if let Some(profile) = rolling_stock {
+        let capped_profile = RollingStockProfile {
+            max_speed_kph: speed_limit_kph.map_or(profile.max_speed_kph, |limit| limit.min(profile.max_speed_kph)),
+            ..*profile
+        };
+        if let Some(duration) = compute_run_time_with_profile(distance_km, &capped_profile) {
+            return Some(duration);
+        }
+    }
+
+    let speed_kph = speed_limit_kph?;
+    Some(seconds_to_duration(distance_km / speed_kph * 3600.0))
+}
+
+/// Compute the run time over a distance (km) with a trapezoidal speed profile:
+/// accelerate to top speed, cruise, then brake to a stop
+/// Segments too short to reach top speed use a triangular profile instead
+/// Returns `None` if the profile has a non-positive speed or rate
+#[must_use]
+pub fn compute_run_time_with_profile(distance_km: f64, profile: &RollingStockProfile) -> Option<Duration> {
+    let max_speed = profile.max_speed_kph / 3.6;
+    let (accel, decel) = (profile.accel_ms2, profile.decel_ms2);
+    if max_speed <= 0.0 || accel <= 0.0 || decel <= 0.0 || distance_km <= 0.0 {
+        return None;
+    }
+
+    let distance = distance_km * 1000.0;
+    let accel_distance = max_speed * max_speed / (2.0 * accel);
+    let decel_distance = max_speed * max_speed / (2.0 * decel);
+
+    let seconds = if accel_distance + decel_distance <= distance {
+        let cruise_distance = distance - accel_distance - decel_distance;
+        max_speed / accel + max_speed / decel + cruise_distance / max_speed
+    } else {
+        let peak_speed = (2.0 * distance * accel * decel / (accel + decel)).sqrt();
+        peak_speed / accel + peak_speed / decel
+    };
+
+    Some(seconds_to_duration(seconds))
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn seconds_to_duration(seconds: f64) -> Duration {
+    Duration::milliseconds((seconds * 1000.0).round() as i64)
+}
+
+#[derive(Debug, Clone, Hash, serde::Serialize, serde::Deserialize)]
 pub struct JourneySegment {
     pub edge_index: usize,
     pub track_index: usize,
     pub origin_platform: usize,
     pub destination_platform: usize,
+    /// The train reverses at the end of this segment
+    #[serde(default)]
+    pub reverses: bool,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -56,9 +212,108 @@ pub struct TrainJourney {
     pub route_end_node: Option<petgraph::stable_graph::NodeIndex>, // Last node of the complete route
     pub timing_inherited: Vec<bool>, // Whether each station time was calculated via duration inheritance (true) or explicit (false)
     pub is_forward: bool, // True for forward journeys, false for return journeys
+    /// Stations the journey runs through without stopping, left out of `station_times`
+    #[serde(default)]
+    pub passed_stations: Vec<PassedStation>,
+}
+
+/// A station a skip-stop journey passes without stopping
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct PassedStation {
+    /// Position along the route, counting stops and passes alike
+    pub route_position: usize,
+    pub node: NodeIndex,
+    pub time: NaiveDateTime,
+    pub timing_inherited: bool,
 }
 
 impl TrainJourney {
+    /// The journey with its passed stations merged back into `station_times` as zero-dwell stops,
+    /// so every segment has a station at either end
+    #[must_use]
+    pub fn with_passed_stations(&self) -> std::borrow::Cow<'_, TrainJourney> {
+        if self.passed_stations.is_empty() {
+            return std::borrow::Cow::Borrowed(self);
+        }
+
+        let mut journey = self.clone();
+        for pass in std::mem::take(&mut journey.passed_stations) {
+            let position = pass.route_position.min(journey.station_times.len());
+            journey.station_times.insert(position, (pass.node, pass.time, pass.time));
+            journey.timing_inherited.insert(position.min(journey.timing_inherited.len()), pass.timing_inherited);
+        }
+        std::borrow::Cow::Owned(journey)
+    }
+
+    /// Position along the route of the `stop_index`-th entry of `station_times`,
+    /// counting the passed stations before it
+    #[must_use]
+    pub fn route_position(&self, stop_index: usize) -> usize {
+        self.passed_stations.iter()
+            .fold(stop_index, |position, pass| position + usize::from(pass.route_position <= position))
+    }
+
+    /// Set of track edges this journey traverses
+    #[must_use]
+    pub fn edge_indices(&self) -> HashSet<EdgeIndex> {
+        self.segments
+            .iter()
+            .map(|segment| EdgeIndex::new(segment.edge_index))
+            .collect()
+    }
+
+    /// Whether the journey is on the network at the given time
+    #[must_use]
+    pub fn is_running_at(&self, time: NaiveDateTime) -> bool {
+        match (self.station_times.first(), self.station_times.last()) {
+            (Some((_, first_arrival, _)), Some((_, _, last_departure))) => {
+                *first_arrival <= time && time <= *last_departure
+            }
+            _ => false,
+        }
+    }
+
+    /// Find the journey a conflict refers to by train number, preferring the one running at `time`
+    #[must_use]
+    pub fn find_by_train_number<'a>(
+        journeys: impl IntoIterator<Item = &'a TrainJourney>,
+        train_number: &str,
+        time: NaiveDateTime,
+    ) -> Option<&'a TrainJourney> {
+        let mut fallback = None;
+        for journey in journeys {
+            if journey.train_number != train_number {
+                continue;
+            }
+            if journey.is_running_at(time) {
+                return Some(journey);
+            }
+            fallback.get_or_insert(journey);
+        }
+        fallback
+    }
+
+    /// Check that every arrival is strictly after the previous stop's departure.
+    /// Returns the `station_times` indices whose arrival is not, i.e. whose run time is zero or negative.
+    ///
+    /// # Errors
+    ///
+    /// Returns the offending indices when any run time is zero or negative.
+    pub fn validate_monotonic_times(&self) -> Result<(), Vec<usize>> {
+        let invalid: Vec<usize> = self.station_times
+            .windows(2)
+            .enumerate()
+            .filter(|(_, pair)| pair[1].1 <= pair[0].2)
+            .map(|(i, _)| i + 1)
+            .collect();
+
+        if invalid.is_empty() {
+            Ok(())
+        } else {
+            Err(invalid)
+        }
+    }
+
     /// Process segments without duration (fallback for missing durations)
     #[allow(clippy::too_many_arguments)]
     fn process_segments_without_duration(
@@ -66,6 +321,7 @@ impl TrainJourney {
         route: &[crate::models::RouteSegment],
         route_nodes: &[Option<petgraph::stable_graph::NodeIndex>],
         graph: &RailwayGraph,
+        rolling_stock: Option<&RollingStockProfile>,
         departure_time: NaiveDateTime,
         cumulative_time: &mut Duration,
         station_times: &mut Vec<(petgraph::stable_graph::NodeIndex, NaiveDateTime, NaiveDateTime)>,
@@ -74,30 +330,34 @@ impl TrainJourney {
     ) {
         for &seg_idx in segments_without_duration {
             let seg = &route[seg_idx];
+
+            // Derive run time from distance when the track has a speed limit or the line a profile
+            let derived_duration = graph.get_track(petgraph::graph::EdgeIndex::new(seg.edge_index))
+                .and_then(|track| compute_duration_from_distance(track, rolling_stock));
+            if let Some(duration) = derived_duration {
+                *cumulative_time += duration;
+            }
             let arrival_time = departure_time + *cumulative_time;
 
-            // Only add wait time if the destination node is not a junction
             let is_junction = route_nodes.get(seg_idx + 1)
                 .and_then(|&node_idx| node_idx)
                 .and_then(|node_idx| graph.graph.node_weight(node_idx))
                 .is_some_and(|node| node.as_junction().is_some());
-
-            if !is_junction {
-                *cumulative_time += seg.wait_time;
-            }
+            *cumulative_time += dwell_after(seg, is_junction);
             let departure_from_station = departure_time + *cumulative_time;
 
             if let Some(node_idx) = route_nodes[seg_idx + 1] {
                 station_times.push((node_idx, arrival_time, departure_from_station));
 
-                // Segments without duration are fallback/inherited timing
-                timing_inherited.push(true);
+                // Segments without duration are fallback/inherited timing unless derived from speed
+                timing_inherited.push(derived_duration.is_none());
 
                 segments.push(JourneySegment {
                     edge_index: seg.edge_index,
                     track_index: seg.track_index,
                     origin_platform: seg.origin_platform,
                     destination_platform: seg.destination_platform,
+                    reverses: seg.reverses,
                 });
             }
         }
@@ -125,7 +385,7 @@ impl TrainJourney {
     }
 
     /// Build return route duration map from forward route, mirroring inheritance pattern
-    fn build_synced_return_durations(
+    pub(crate) fn build_synced_return_durations(
         forward_route: &[crate::models::RouteSegment],
         return_route_len: usize,
     ) -> Vec<Option<Duration>> {
@@ -167,6 +427,8 @@ impl TrainJourney {
     }
 
     /// Process segments with duration inheritance and add station times/segments
+    /// Inherited segments whose run time can be derived from distance use it, and the
+    /// duration is spread over the remaining segments
     #[allow(clippy::too_many_arguments)]
     fn process_segments_with_duration(
         segments_since_duration: &[usize],
@@ -174,40 +436,40 @@ impl TrainJourney {
         route: &[crate::models::RouteSegment],
         route_nodes: &[Option<petgraph::stable_graph::NodeIndex>],
         graph: &RailwayGraph,
+        rolling_stock: Option<&RollingStockProfile>,
         departure_time: NaiveDateTime,
         cumulative_time: &mut Duration,
         station_times: &mut Vec<(petgraph::stable_graph::NodeIndex, NaiveDateTime, NaiveDateTime)>,
         segments: &mut Vec<JourneySegment>,
         timing_inherited: &mut Vec<bool>,
     ) {
-        let segments_to_cover = segments_since_duration.len();
-        let duration_per_segment = if segments_to_cover > 0 {
-            duration / i32::try_from(segments_to_cover).unwrap_or(1)
-        } else {
-            duration
-        };
+        // The timed segment always shares the duration; inherited ones derive it when they can
+        let derived: Vec<Option<Duration>> = segments_since_duration.iter().enumerate()
+            .map(|(idx, &seg_idx)| {
+                let track = graph.get_track(petgraph::graph::EdgeIndex::new(route[seg_idx].edge_index))?;
+                if idx == 0 { None } else { compute_duration_from_distance(track, rolling_stock) }
+            })
+            .collect();
+        let sharing_segments = derived.iter().filter(|d| d.is_none()).count().max(1);
+        let duration_per_segment = duration / i32::try_from(sharing_segments).unwrap_or(1);
 
         for (idx, &seg_idx) in segments_since_duration.iter().enumerate() {
             let seg = &route[seg_idx];
-            *cumulative_time += duration_per_segment;
+            *cumulative_time += derived[idx].unwrap_or(duration_per_segment);
             let arrival_time = departure_time + *cumulative_time;
 
-            // Only add wait time if the destination node is not a junction
             let is_junction = route_nodes.get(seg_idx + 1)
                 .and_then(|&node_idx| node_idx)
                 .and_then(|node_idx| graph.graph.node_weight(node_idx))
                 .is_some_and(|node| node.as_junction().is_some());
-
-            if !is_junction {
-                *cumulative_time += seg.wait_time;
-            }
+            *cumulative_time += dwell_after(seg, is_junction);
             let departure_from_station = departure_time + *cumulative_time;
 
             if let Some(node_idx) = route_nodes[seg_idx + 1] {
                 station_times.push((node_idx, arrival_time, departure_from_station));
 
-                // First segment has explicit timing, subsequent segments have inherited timing
-                let is_inherited = idx > 0;
+                // First segment has explicit timing, subsequent segments inherit unless derived
+                let is_inherited = idx > 0 && derived[idx].is_none();
                 timing_inherited.push(is_inherited);
 
                 segments.push(JourneySegment {
@@ -215,6 +477,7 @@ impl TrainJourney {
                     track_index: seg.track_index,
                     origin_platform: seg.origin_platform,
                     destination_platform: seg.destination_platform,
+                    reverses: seg.reverses,
                 });
             }
         }
@@ -231,38 +494,59 @@ impl TrainJourney {
     /// Panics if `BASE_DATE` cannot be converted to a valid datetime at midnight (00:00:00)
     #[must_use]
     pub fn generate_journeys(lines: &[Line], graph: &RailwayGraph, selected_day: Option<Weekday>) -> HashMap<uuid::Uuid, TrainJourney> {
-        let mut journeys = HashMap::new();
+        Self::generate_journeys_with_patterns(lines, graph, selected_day, &[], None)
+    }
 
-        // Determine which days to simulate
-        let days_to_simulate: Vec<(Weekday, i64)> = if let Some(day) = selected_day {
-            // Only simulate the selected day
-            vec![(day, 0)]
-        } else {
-            // Simulate Sunday from previous week (day -1) to catch late Sunday trains
-            // that extend into Monday, then the full week (days 0-6)
-            vec![
-                (Weekday::Sun, -1), // Previous week's Sunday for wraparound conflicts
-                (Weekday::Mon, 0),
-                (Weekday::Tue, 1),
-                (Weekday::Wed, 2),
-                (Weekday::Thu, 3),
-                (Weekday::Fri, 4),
-                (Weekday::Sat, 5),
-                (Weekday::Sun, 6),
-            ]
-        };
+    /// Generate train journeys, resolving each line's operating days through its service pattern
+    ///
+    /// Lines without a pattern, or whose pattern is not in `service_patterns`, use their own days of week.
+    /// Operating periods and pattern exceptions are checked against the real week containing
+    /// `reference_week`, see [`real_date`].
+    ///
+    /// # Panics
+    /// Panics if `BASE_DATE` cannot be converted to a valid datetime at midnight (00:00:00)
+    #[must_use]
+    pub fn generate_journeys_with_patterns(
+        lines: &[Line],
+        graph: &RailwayGraph,
+        selected_day: Option<Weekday>,
+        service_patterns: &[ServicePattern],
+        reference_week: Option<chrono::NaiveDate>,
+    ) -> HashMap<uuid::Uuid, TrainJourney> {
+        let horizon = selected_day.map_or(SimulationHorizon::FullWeek, SimulationHorizon::SingleDay);
+        Self::generate_journeys_for_horizon(lines, graph, horizon, service_patterns, reference_week)
+    }
 
-        for (weekday, day_offset) in days_to_simulate {
+    /// Generate train journeys for the days in `horizon`
+    ///
+    /// Limiting the horizon saves work on large networks when only some days matter.
+    #[must_use]
+    pub fn generate_journeys_for_horizon(
+        lines: &[Line],
+        graph: &RailwayGraph,
+        horizon: SimulationHorizon,
+        service_patterns: &[ServicePattern],
+        reference_week: Option<chrono::NaiveDate>,
+    ) -> HashMap<uuid::Uuid, TrainJourney> {
+        let mut journeys = HashMap::new();
+
+        for (weekday, day_offset) in horizon.days() {
             let day_filter = weekday_to_days_of_week(weekday);
             let current_date = BASE_DATE + Duration::days(day_offset);
+            let calendar_date = horizon.calendar_date(weekday, current_date, reference_week);
 
             for line in lines {
                 if line.forward_route.is_empty() && line.return_route.is_empty() {
                     continue;
                 }
 
-                // Filter by day of week
-                if !line.days_of_week.contains(day_filter) {
+                // Filter by day of week, or by the line's service pattern
+                if !line_runs_on(line, service_patterns, day_filter, calendar_date) {
+                    continue;
+                }
+
+                // Filter by seasonal operating period
+                if !line.operates_on_date(calendar_date) {
                     continue;
                 }
 
@@ -285,24 +569,58 @@ impl TrainJourney {
             }
         }
 
-        // Filter out journeys from day -1 (previous Sunday) that don't extend into the current week
-        // Keep only journeys that have at least one station time >= Monday 00:00:00
-        if selected_day.is_none() {
-            let week_start = BASE_DATE.and_hms_opt(0, 0, 0).expect("Valid datetime");
+        // Filter out journeys from the wraparound day that don't extend into the horizon
+        // Keep only journeys that have at least one station time at or after its start
+        if let Some(horizon_start) = horizon.retention_start() {
             journeys.retain(|_, journey| {
-                // Check if any station time (arrival or departure) is within the current week
                 journey.station_times.iter().any(|(_, arrival, departure)| {
-                    *arrival >= week_start || *departure >= week_start
+                    *arrival >= horizon_start || *departure >= horizon_start
                 })
             });
         }
 
+        Self::disambiguate_stable_train_numbers(&mut journeys, lines);
+
         // Apply turnaround logic: extend departure times for journeys with turnaround enabled
         Self::apply_turnaround_extensions(&mut journeys, lines);
 
         journeys
     }
 
+    /// Make train numbers from stable tokens unique per line and day
+    /// Later departures sharing a number get a `-2`, `-3`, ... suffix in departure order
+    fn disambiguate_stable_train_numbers(
+        journeys: &mut HashMap<uuid::Uuid, TrainJourney>,
+        lines: &[Line],
+    ) {
+        type NumberKey = (uuid::Uuid, chrono::NaiveDate, String);
+
+        let stable_lines: HashSet<uuid::Uuid> = lines.iter()
+            .filter(|line| STABLE_NUMBER_TOKENS.iter().any(|token| line.auto_train_number_format.contains(token)))
+            .map(|line| line.id)
+            .collect();
+        if stable_lines.is_empty() {
+            return;
+        }
+
+        let mut by_number: HashMap<NumberKey, Vec<(NaiveDateTime, bool, uuid::Uuid)>> = HashMap::new();
+        for journey in journeys.values().filter(|j| stable_lines.contains(&j.line_id)) {
+            by_number
+                .entry((journey.line_id, journey.departure_time.date(), journey.train_number.clone()))
+                .or_default()
+                .push((journey.departure_time, !journey.is_forward, journey.id));
+        }
+
+        for ((_, _, number), mut sharing) in by_number.into_iter().filter(|(_, ids)| ids.len() > 1) {
+            sharing.sort();
+            for (position, (_, _, id)) in sharing.iter().enumerate().skip(1) {
+                if let Some(journey) = journeys.get_mut(id) {
+                    journey.train_number = format!("{number}-{}", position + 1);
+                }
+            }
+        }
+    }
+
     /// Apply turnaround extensions to journeys
     /// Extends the last station's departure time to match the next opposite-direction service
     fn apply_turnaround_extensions(
@@ -391,19 +709,25 @@ impl TrainJourney {
     }
 
     fn determine_start_node(
-        first_segment: &crate::models::RouteSegment,
-        second_segment: Option<&crate::models::RouteSegment>,
+        route: &[crate::models::RouteSegment],
         graph: &RailwayGraph,
     ) -> Option<petgraph::stable_graph::NodeIndex> {
-        let first_edge_idx = petgraph::graph::EdgeIndex::new(first_segment.edge_index);
-        let (from1, to1) = graph.get_track_endpoints(first_edge_idx)?;
-
-        let Some(second_seg) = second_segment else {
-            return Some(from1);
+        let endpoints = |segment: &crate::models::RouteSegment| {
+            graph.get_track_endpoints(petgraph::graph::EdgeIndex::new(segment.edge_index))
         };
+        let first_segment = route.first()?;
+        let (from1, to1) = endpoints(first_segment)?;
+
+        // Reversing straight back over the first edge returns to the start, which the segment
+        // after the reversal leaves from
+        if first_segment.reverses && route.get(1).is_some_and(|s| s.edge_index == first_segment.edge_index) {
+            let Some((from3, to3)) = route.get(2).and_then(endpoints) else {
+                return Some(from1);
+            };
+            return Some(if from1 == from3 || from1 == to3 { from1 } else { to1 });
+        }
 
-        let second_edge_idx = petgraph::graph::EdgeIndex::new(second_seg.edge_index);
-        let Some((from2, to2)) = graph.get_track_endpoints(second_edge_idx) else {
+        let Some((from2, to2)) = route.get(1).and_then(endpoints) else {
             return Some(from1);
         };
 
@@ -415,16 +739,17 @@ impl TrainJourney {
         }
     }
 
-    fn build_route_nodes(
+    /// Nodes visited by a route, chaining each segment from the previous segment's end.
+    /// After a reversal the next segment leaves by the side the train came in, which may be the same edge.
+    pub(crate) fn build_route_nodes(
         route: &[crate::models::RouteSegment],
         graph: &RailwayGraph,
     ) -> Vec<Option<petgraph::stable_graph::NodeIndex>> {
         let mut route_nodes: Vec<Option<petgraph::stable_graph::NodeIndex>> = Vec::with_capacity(route.len() + 1);
 
         // Determine the starting node
-        if let Some(first_segment) = route.first() {
-            let start_node = Self::determine_start_node(first_segment, route.get(1), graph);
-            route_nodes.push(start_node);
+        if !route.is_empty() {
+            route_nodes.push(Self::determine_start_node(route, graph));
         }
 
         // Build remaining nodes by following connections
@@ -462,12 +787,13 @@ impl TrainJourney {
         }
 
         // Convert the line's first_departure time to the current date
-        let Some(mut departure_time) = time_on_date(line.first_departure, current_date) else {
+        let Some(mut next_departure) = time_on_date(line.first_departure, current_date) else {
             return;
         };
 
-        // Pre-compute route node indices
+        // Pre-compute route node indices and the frequency lookup
         let route_nodes = Self::build_route_nodes(&line.forward_route, graph);
+        let frequency_at = line.frequency_schedule();
 
         let mut journey_count = 0;
         let line_id = line.id;
@@ -478,9 +804,18 @@ impl TrainJourney {
         // Detect if last departure should roll over to next day
         let last_departure_needs_rollover = line.last_departure.time() < line.first_departure.time();
 
+        // Skip-stop journeys run the same route without waiting at skipped stops
+        let skipped_stops = line.skipped_stop_indices();
+        let skip_stop_route = Self::build_skip_stop_route(&line.forward_route, &skipped_stops);
+
         while journey_count < MAX_JOURNEYS_PER_LINE {
+            // Snapping shifts the whole journey, keeping run times intact
+            let departure_time = line.snap_to_clock_face(next_departure);
+            let skipping = !skipped_stops.is_empty() && line.applies_skip_pattern(journey_count);
+            let route = if skipping { &skip_stop_route } else { &line.forward_route };
+
             let mut station_times = Vec::with_capacity(route_nodes.len());
-            let mut segments = Vec::with_capacity(line.forward_route.len());
+            let mut segments = Vec::with_capacity(route.len());
             let mut timing_inherited = Vec::with_capacity(route_nodes.len());
 
             // Apply first stop wait time to the first station
@@ -497,17 +832,18 @@ impl TrainJourney {
             // Walk the route, handling duration inheritance
             // When a segment has a duration, it covers all segments until the next duration
             let mut i = 0;
-            while i < line.forward_route.len() {
-                if let Some(duration) = line.forward_route[i].duration {
-                    let segments_to_cover = Self::count_segments_without_duration(&line.forward_route, i);
+            while i < route.len() {
+                if let Some(duration) = route[i].duration {
+                    let segments_to_cover = Self::count_segments_without_duration(route, i);
                     let next_index = segments_to_cover.last().copied().unwrap_or(i) + 1;
 
                     Self::process_segments_with_duration(
                         &segments_to_cover,
                         duration,
-                        &line.forward_route,
+                        route,
                         &route_nodes,
                         graph,
+                        line.rolling_stock.as_ref(),
                         departure_time,
                         &mut cumulative_time,
                         &mut station_times,
@@ -520,9 +856,10 @@ impl TrainJourney {
                     // Segment without duration and no previous duration - use fallback
                     Self::process_segments_without_duration(
                         &[i],
-                        &line.forward_route,
+                        route,
                         &route_nodes,
                         graph,
+                        line.rolling_stock.as_ref(),
                         departure_time,
                         &mut cumulative_time,
                         &mut station_times,
@@ -542,15 +879,21 @@ impl TrainJourney {
                         line_name, segments.len(), station_times.len(), station_times.len() - 1
                     )));
                     // Skip this invalid journey
-                    departure_time += line.frequency;
+                    next_departure += frequency_at(next_departure.time());
                     continue;
                 }
 
                 let id = uuid::Uuid::new_v4();
                 // Use odd numbers for forward journeys (1, 3, 5, 7, ...)
-                let train_number = generate_train_number(&line.auto_train_number_format, &line_name, (journey_count * 2) + 1);
+                let train_number = generate_train_number(&line.auto_train_number_format, &line_name, (journey_count * 2) + 1, line_id, departure_time);
                 let route_start_node = station_times.first().map(|(node_idx, _, _)| *node_idx);
                 let route_end_node = station_times.last().map(|(node_idx, _, _)| *node_idx);
+                // Stop indices only line up with station times when every route node resolved
+                let passed_stations = if skipping && station_times.len() == route.len() + 1 {
+                    Self::take_passed_stations(&skipped_stops, &mut station_times, &mut timing_inherited)
+                } else {
+                    Vec::new()
+                };
                 journeys.insert(id, TrainJourney {
                     id,
                     line_id,
@@ -564,11 +907,12 @@ impl TrainJourney {
                     route_end_node,
                     timing_inherited,
                     is_forward: true,
+                    passed_stations,
                 });
                 journey_count += 1;
             }
 
-            departure_time += line.frequency;
+            next_departure += frequency_at(next_departure.time());
 
             // Check if next departure would be after the last departure time
             let Some(mut last_departure_on_date) = time_on_date(line.last_departure, current_date) else {
@@ -578,12 +922,55 @@ impl TrainJourney {
             if last_departure_needs_rollover {
                 last_departure_on_date += chrono::Duration::days(1);
             }
-            if departure_time > last_departure_on_date {
+            if next_departure > last_departure_on_date {
                 break;
             }
         }
     }
 
+    /// Build a copy of a route with zero wait time at skipped stops, so trains pass them
+    /// Stop `n` is the destination of route segment `n - 1`
+    fn build_skip_stop_route(
+        route: &[crate::models::RouteSegment],
+        skipped_stops: &[usize],
+    ) -> Vec<crate::models::RouteSegment> {
+        route
+            .iter()
+            .enumerate()
+            .map(|(seg_idx, seg)| {
+                let mut seg = seg.clone();
+                if skipped_stops.contains(&(seg_idx + 1)) {
+                    seg.wait_time = Duration::zero();
+                }
+                seg
+            })
+            .collect()
+    }
+
+    /// Move skipped stops out of `station_times`, recording them as passed stations
+    fn take_passed_stations(
+        skipped_stops: &[usize],
+        station_times: &mut Vec<(NodeIndex, NaiveDateTime, NaiveDateTime)>,
+        timing_inherited: &mut Vec<bool>,
+    ) -> Vec<PassedStation> {
+        let last_stop = station_times.len() - 1;
+        let mut passed = Vec::with_capacity(skipped_stops.len());
+
+        // Remove from the back so earlier stop indices stay valid
+        for &stop in skipped_stops.iter().rev().filter(|&&stop| stop > 0 && stop < last_stop) {
+            let (node, arrival, _) = station_times.remove(stop);
+            passed.push(PassedStation {
+                route_position: stop,
+                node,
+                time: arrival,
+                timing_inherited: timing_inherited.remove(stop),
+            });
+        }
+
+        passed.reverse();
+        passed
+    }
+
     fn generate_manual_journeys(
         journeys: &mut HashMap<uuid::Uuid, TrainJourney>,
         line: &Line,
@@ -593,11 +980,6 @@ impl TrainJourney {
     ) {
         let mut sequence = 1;
 
-        // Determine end of day
-        let Some(end_of_day) = current_date.and_hms_opt(23, 59, 59) else {
-            return;
-        };
-
         for manual_dep in &line.manual_departures {
             // Filter by day of week
             if !manual_dep.days_of_week.contains(day_filter) {
@@ -609,79 +991,54 @@ impl TrainJourney {
                 continue;
             };
 
-            let from_idx = manual_dep.from_station;
-            let to_idx = manual_dep.to_station;
-
-            // Check if this is a repeating departure
-            if let Some(repeat_interval) = manual_dep.repeat_interval {
-                // Determine when to stop repeating
-                let repeat_until = if let Some(until_time) = manual_dep.repeat_until {
-                    time_on_date(until_time, current_date).unwrap_or(end_of_day)
-                } else {
-                    end_of_day
-                };
-
-                // Generate multiple journeys at the repeat interval
-                let mut current_departure = initial_departure_time;
-
-                while current_departure <= repeat_until {
-                    Self::try_generate_manual_journey(
-                        journeys,
-                        line,
-                        graph,
-                        current_departure,
-                        from_idx,
-                        to_idx,
-                        manual_dep.train_number.as_ref(),
-                        &mut sequence,
-                    );
-
-                    // Move to next departure time
-                    current_departure += repeat_interval;
-                }
-            } else {
-                // Single departure (no repeat)
+            // One journey per repeat, or just the one for a single departure
+            for departure_time in manual_dep.repeat_times(initial_departure_time) {
                 Self::try_generate_manual_journey(
                     journeys,
                     line,
                     graph,
-                    initial_departure_time,
-                    from_idx,
-                    to_idx,
-                    manual_dep.train_number.as_ref(),
+                    departure_time,
+                    manual_dep,
                     &mut sequence,
                 );
             }
         }
     }
 
-    /// Try to generate a single manual journey on either forward or return route
+    /// Try to generate a single manual journey in the departure's direction
+    /// Forward departures fall back to the return route if the stations only appear in that order there
     /// Returns true if a journey was successfully generated
     fn try_generate_manual_journey(
         journeys: &mut HashMap<uuid::Uuid, TrainJourney>,
         line: &Line,
         graph: &RailwayGraph,
         departure_time: NaiveDateTime,
-        from_idx: petgraph::graph::NodeIndex,
-        to_idx: petgraph::graph::NodeIndex,
-        custom_train_number: Option<&String>,
+        manual_dep: &crate::models::ManualDeparture,
         sequence: &mut usize,
     ) -> bool {
-        // Use custom train number if provided, otherwise generate one
-        let train_number = custom_train_number.cloned()
-            .unwrap_or_else(|| generate_train_number(&line.auto_train_number_format, &line.name, *sequence));
+        let from_idx = manual_dep.from_station;
+        let to_idx = manual_dep.to_station;
 
-        // Try forward route first
-        if let Some(journey) = Self::generate_manual_journey_for_route(
-            &line.forward_route,
-            line,
-            graph,
-            departure_time,
-            from_idx,
-            to_idx,
-            &train_number,
-            true, // is_forward
-        ) {
+        // Use custom train number if provided, otherwise generate one
+        let train_number = manual_dep.train_number.clone()
+            .unwrap_or_else(|| generate_train_number(&line.auto_train_number_format, &line.name, *sequence, line.id, departure_time));
+
+        // Try forward route first, unless the departure explicitly runs the return direction
+        let forward_journey = if manual_dep.direction == crate::models::RouteDirection::Forward {
+            Self::generate_manual_journey_for_route(
+                &line.forward_route,
+                line,
+                graph,
+                departure_time,
+                from_idx,
+                to_idx,
+                &train_number,
+                true, // is_forward
+            )
+        } else {
+            None
+        };
+        if let Some(journey) = forward_journey {
             journeys.insert(journey.id, journey);
             *sequence += 1;
             return true;
@@ -761,6 +1118,7 @@ impl TrainJourney {
                     route,
                     &route_nodes_opt,
                     graph,
+                    line.rolling_stock.as_ref(),
                     departure_time,
                     &mut cumulative_time,
                     &mut station_times,
@@ -775,6 +1133,7 @@ impl TrainJourney {
                     route,
                     &route_nodes_opt,
                     graph,
+                    line.rolling_stock.as_ref(),
                     departure_time,
                     &mut cumulative_time,
                     &mut station_times,
@@ -801,6 +1160,7 @@ impl TrainJourney {
                 route_end_node,
                 timing_inherited,
                 is_forward,
+                passed_stations: Vec::new(),
             })
         } else {
             None
@@ -822,8 +1182,9 @@ impl TrainJourney {
             return;
         };
 
-        // Pre-compute route node indices
+        // Pre-compute route node indices and the frequency lookup
         let route_nodes = Self::build_route_nodes(&line.return_route, graph);
+        let frequency_at = line.frequency_schedule();
 
 
         let mut return_journey_count = 0;
@@ -874,6 +1235,7 @@ impl TrainJourney {
                         &line.return_route,
                         &route_nodes,
                         graph,
+                        line.rolling_stock.as_ref(),
                         return_departure_time,
                         &mut cumulative_time,
                         &mut station_times,
@@ -888,6 +1250,7 @@ impl TrainJourney {
                         &line.return_route,
                         &route_nodes,
                         graph,
+                        line.rolling_stock.as_ref(),
                         return_departure_time,
                         &mut cumulative_time,
                         &mut station_times,
@@ -907,13 +1270,13 @@ impl TrainJourney {
                         line_name, segments.len(), station_times.len(), station_times.len() - 1
                     )));
                     // Skip this invalid journey
-                    return_departure_time += line.frequency;
+                    return_departure_time += frequency_at(return_departure_time.time());
                     continue;
                 }
 
                 let id = uuid::Uuid::new_v4();
                 // Use even numbers for return journeys (2, 4, 6, 8, ...)
-                let train_number = generate_train_number(&line.auto_train_number_format, &line_name, (return_journey_count + 1) * 2);
+                let train_number = generate_train_number(&line.auto_train_number_format, &line_name, (return_journey_count + 1) * 2, line_id, return_departure_time);
                 let route_start_node = station_times.first().map(|(node_idx, _, _)| *node_idx);
                 let route_end_node = station_times.last().map(|(node_idx, _, _)| *node_idx);
                 journeys.insert(id, TrainJourney {
@@ -928,12 +1291,13 @@ impl TrainJourney {
                     route_start_node,
                     route_end_node,
                     timing_inherited,
+                    passed_stations: Vec::new(),
                     is_forward: false,
                 });
                 return_journey_count += 1;
             }
 
-            return_departure_time += line.frequency;
+            return_departure_time += frequency_at(return_departure_time.time());
 
             // Check if next departure would be after the last departure time
             let Some(mut last_departure_on_date) = time_on_date(line.return_last_departure, current_date) else {
@@ -950,6 +1314,308 @@ impl TrainJourney {
     }
 }
 
+/// Whether `line` runs on `date`, whose day of week is `day`
+fn line_runs_on(line: &Line, service_patterns: &[ServicePattern], day: DaysOfWeek, date: chrono::NaiveDate) -> bool {
+    let pattern = line.service_pattern_id
+        .and_then(|id| service_patterns.iter().find(|pattern| pattern.id == id));
+    match pattern {
+        Some(pattern) => pattern.runs_on(day, date),
+        None => line.days_of_week.contains(day),
+    }
+}
+
+/// One journey's pass through a section or stop: when it entered and when it left
+struct Pass {
+    journey: usize,
+    entered: NaiveDateTime,
+    left: NaiveDateTime,
+}
+
+/// Find trains scheduled to overtake each other where there is nowhere to pass.
+///
+/// Trains running the same way over a section are compared by the order they enter and leave it;
+/// a reversal on a single-track section is reported at the section's start node. Likewise, a
+/// reversal while stopped at a station without a passing loop or second platform is reported at
+/// that station. Returns `(overtaking, overtaken, node)` sorted by node.
+#[must_use]
+pub fn find_illegal_overtakes<'a>(
+    journeys: impl IntoIterator<Item = &'a TrainJourney>,
+    graph: &RailwayGraph,
+) -> Vec<(uuid::Uuid, uuid::Uuid, NodeIndex)> {
+    let journeys: Vec<&TrainJourney> = journeys.into_iter().collect();
+    let mut sections: HashMap<(NodeIndex, NodeIndex, usize), Vec<Pass>> = HashMap::new();
+    let mut stops: HashMap<(NodeIndex, NodeIndex, NodeIndex), Vec<Pass>> = HashMap::new();
+
+    for (journey_idx, journey) in journeys.iter().enumerate() {
+        for (segment, window) in journey.segments.iter().zip(journey.station_times.windows(2)) {
+            let ((from, _, departure), (to, arrival, _)) = (window[0], window[1]);
+            sections
+                .entry((from, to, segment.edge_index))
+                .or_default()
+                .push(Pass { journey: journey_idx, entered: departure, left: arrival });
+        }
+        for window in journey.station_times.windows(3) {
+            let (node, arrival, departure) = window[1];
+            stops
+                .entry((window[0].0, node, window[2].0))
+                .or_default()
+                .push(Pass { journey: journey_idx, entered: arrival, left: departure });
+        }
+    }
+
+    let mut overtakes = Vec::new();
+    for ((from, _, edge_index), passes) in &sections {
+        let single_track = graph.graph.edge_weight(EdgeIndex::new(*edge_index)).is_some_and(|segment| segment.tracks.len() < 2);
+        if single_track {
+            collect_reversals(passes, &journeys, *from, &mut overtakes);
+        }
+    }
+    for ((_, node, _), passes) in &stops {
+        if !allows_passing_at(graph, *node) {
+            collect_reversals(passes, &journeys, *node, &mut overtakes);
+        }
+    }
+
+    overtakes.sort_by_key(|&(overtaking, overtaken, node)| (node, overtaking, overtaken));
+    overtakes
+}
+
+/// Whether one train can wait at `node` for another to pass
+fn allows_passing_at(graph: &RailwayGraph, node: NodeIndex) -> bool {
+    match graph.graph.node_weight(node) {
+        Some(Node::Station(station)) => station.passing_loop || station.platforms.len() > 1,
+        _ => false,
+    }
+}
+
+/// Record every pair of passes where the later train to enter is the first to leave
+fn collect_reversals(
+    passes: &[Pass],
+    journeys: &[&TrainJourney],
+    node: NodeIndex,
+    found: &mut Vec<(uuid::Uuid, uuid::Uuid, NodeIndex)>,
+) {
+    for (i, first) in passes.iter().enumerate() {
+        for second in &passes[i + 1..] {
+            let (ahead, behind) = if first.entered < second.entered && second.left < first.left {
+                (second, first)
+            } else if second.entered < first.entered && first.left < second.left {
+                (first, second)
+            } else {
+                continue;
+            };
+            found.push((journeys[ahead.journey].id, journeys[behind.journey].id, node));
+        }
+    }
+}
+
+/// Single-track edges that constrain frequency the most, busiest first.
+///
+/// Each single-track edge is scored by the total time journeys spend on it; the edges ranked
+/// above the `CRITICAL_SECTION_PERCENTILE` of those scores are returned, always at least one.
+#[must_use]
+pub fn critical_sections(journeys: &[TrainJourney], graph: &RailwayGraph) -> Vec<EdgeIndex> {
+    let mut occupancy: Vec<(EdgeIndex, Duration)> = single_track_occupancy(journeys, graph)
+        .into_iter()
+        .filter(|(_, occupied)| *occupied > Duration::zero())
+        .collect();
+    if occupancy.is_empty() {
+        return Vec::new();
+    }
+    occupancy.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    // Keep the top ranks; ties at the cut-off fall back to edge order
+    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let kept = ((1.0 - CRITICAL_SECTION_PERCENTILE) * occupancy.len() as f64).ceil() as usize;
+
+    occupancy
+        .into_iter()
+        .take(kept.max(1))
+        .map(|(edge, _)| edge)
+        .collect()
+}
+
+/// Total time journeys spend on each single-track edge
+fn single_track_occupancy(journeys: &[TrainJourney], graph: &RailwayGraph) -> HashMap<EdgeIndex, Duration> {
+    let mut occupancy: HashMap<EdgeIndex, Duration> = HashMap::new();
+    for journey in journeys {
+        for (segment, window) in journey.segments.iter().zip(journey.station_times.windows(2)) {
+            let edge = EdgeIndex::new(segment.edge_index);
+            let single_track = graph.graph.edge_weight(edge).is_some_and(|segment| segment.tracks.len() < 2);
+            if !single_track {
+                continue;
+            }
+            let ((_, _, departure), (_, arrival, _)) = (window[0], window[1]);
+            *occupancy.entry(edge).or_insert_with(Duration::zero) += arrival - departure;
+        }
+    }
+    occupancy
+}
+
+/// Intermediate stops shorter than their station's minimum dwell, as (journey id, index into `station_times`)
+///
+/// Junctions have no minimum, and calls without any dwell are non-stop passes, so neither is
+/// flagged. The first and last calls are exempt as trains only depart or arrive there.
+#[must_use]
+pub fn dwell_violations<'a>(journeys: impl IntoIterator<Item = &'a TrainJourney>, graph: &RailwayGraph) -> Vec<(uuid::Uuid, usize)> {
+    let min_dwell = |node: NodeIndex| {
+        graph.graph.node_weight(node)
+            .and_then(Node::as_station)
+            .and_then(|station| station.min_dwell_secs)
+    };
+
+    journeys.into_iter()
+        .flat_map(|journey| {
+            let intermediate = journey.station_times.len().saturating_sub(1);
+            journey.station_times.iter()
+                .enumerate()
+                .take(intermediate)
+                .skip(1)
+                .filter(|(_, (node, arrival, departure))| {
+                    let dwell = (*departure - *arrival).num_seconds();
+                    dwell > 0 && min_dwell(*node).is_some_and(|min| dwell < min)
+                })
+                .map(move |(stop, _)| (journey.id, stop))
+        })
+        .collect()
+}
+
+/// Likely reason a visible line generated no journeys
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MissingJourneysReason {
+    /// Neither route has any segments
+    EmptyRoute,
+    /// The line runs on none of the simulated days, by its days of week, service pattern or operating period
+    Days,
+    /// The line runs but its schedule yields no departures, e.g. a zero frequency or no manual departures
+    Schedule,
+}
+
+impl MissingJourneysReason {
+    #[must_use]
+    pub const fn description(self) -> &'static str {
+        match self {
+            Self::EmptyRoute => "No journeys: the route is empty",
+            Self::Days => "No journeys: the line does not run on the shown days",
+            Self::Schedule => "No journeys: the schedule produces no departures",
+        }
+    }
+}
+
+/// Visible lines with no journeys among `journeys`, each with the likely reason
+///
+/// Hidden lines are skipped as they are never generated.
+#[must_use]
+pub fn lines_without_journeys(
+    lines: &[Line],
+    journeys: &HashMap<uuid::Uuid, TrainJourney>,
+    horizon: SimulationHorizon,
+    service_patterns: &[ServicePattern],
+    reference_week: Option<chrono::NaiveDate>,
+) -> HashMap<uuid::Uuid, MissingJourneysReason> {
+    let lines_with_journeys: HashSet<uuid::Uuid> = journeys.values().map(|journey| journey.line_id).collect();
+    lines.iter()
+        .filter(|line| line.visible && !lines_with_journeys.contains(&line.id))
+        .map(|line| (line.id, missing_journeys_reason(line, horizon, service_patterns, reference_week)))
+        .collect()
+}
+
+fn missing_journeys_reason(
+    line: &Line,
+    horizon: SimulationHorizon,
+    service_patterns: &[ServicePattern],
+    reference_week: Option<chrono::NaiveDate>,
+) -> MissingJourneysReason {
+    if line.forward_route.is_empty() && line.return_route.is_empty() {
+        return MissingJourneysReason::EmptyRoute;
+    }
+    let runs_on_any_day = horizon.days().into_iter().any(|(weekday, offset)| {
+        let date = horizon.calendar_date(weekday, BASE_DATE + Duration::days(offset), reference_week);
+        line_runs_on(line, service_patterns, weekday_to_days_of_week(weekday), date) && line.operates_on_date(date)
+    });
+    if runs_on_any_day {
+        MissingJourneysReason::Schedule
+    } else {
+        MissingJourneysReason::Days
+    }
+}
+
+/// Journeys of another timetable, drawn faintly behind the current one for comparison.
+/// They are kept apart from the project's own journeys and never checked for conflicts.
+#[derive(Debug, Clone, Default)]
+pub struct ReferenceTimetable {
+    /// Name of the project the journeys were generated from
+    pub name: String,
+    pub journeys: HashMap<uuid::Uuid, TrainJourney>,
+}
+
+impl ReferenceTimetable {
+    /// Generate journeys for the visible lines of `project`, the same way as for the current project,
+    /// and move them onto `graph`. The two projects number their nodes independently, so stations
+    /// are matched by name.
+    #[must_use]
+    pub fn from_project(project: &Project, graph: &RailwayGraph, selected_day: Option<Weekday>) -> Self {
+        let visible_lines: Vec<Line> = project.lines.iter().filter(|line| line.visible).cloned().collect();
+        let journeys = TrainJourney::generate_journeys_with_patterns(
+            &visible_lines,
+            &project.graph,
+            selected_day,
+            &project.service_patterns,
+            project.settings.reference_week,
+        );
+        Self {
+            name: project.metadata.name.clone(),
+            journeys: journeys.values()
+                .flat_map(|journey| match_onto_graph(journey, &project.graph, graph))
+                .map(|journey| (journey.id, journey))
+                .collect(),
+        }
+    }
+}
+
+/// Pieces of `journey`, generated on `from`, that run over stations and tracks `to` also has.
+/// A station or track missing from `to` splits the journey; pieces left with one stop are dropped.
+fn match_onto_graph(journey: &TrainJourney, from: &RailwayGraph, to: &RailwayGraph) -> Vec<TrainJourney> {
+    use crate::models::Stations;
+
+    let journey = journey.with_passed_stations();
+    let empty = TrainJourney {
+        station_times: Vec::new(),
+        segments: Vec::new(),
+        timing_inherited: Vec::new(),
+        passed_stations: Vec::new(),
+        ..journey.as_ref().clone()
+    };
+    let mut pieces = Vec::new();
+    let mut piece = empty.clone();
+
+    for (i, &(node, arrival, departure)) in journey.station_times.iter().enumerate() {
+        let Some(node) = from.get_station_name(node).and_then(|name| to.get_station_index(name)) else {
+            pieces.push(std::mem::replace(&mut piece, empty.clone()));
+            continue;
+        };
+        let previous = piece.station_times.last().map(|&(previous, _, _)| previous);
+        let edge = previous.and_then(|previous| to.graph.find_edge(previous, node).or_else(|| to.graph.find_edge(node, previous)));
+        match (edge, i.checked_sub(1).and_then(|s| journey.segments.get(s))) {
+            (Some(edge), Some(segment)) => piece.segments.push(JourneySegment { edge_index: edge.index(), ..segment.clone() }),
+            _ => pieces.push(std::mem::replace(&mut piece, empty.clone())),
+        }
+        piece.station_times.push((node, arrival, departure));
+        piece.timing_inherited.push(journey.timing_inherited.get(i).copied().unwrap_or_default());
+    }
+    pieces.push(piece);
+
+    pieces.into_iter()
+        .filter(|piece| piece.station_times.len() > 1)
+        .map(|piece| TrainJourney {
+            id: uuid::Uuid::new_v4(),
+            route_start_node: piece.station_times.first().map(|&(node, _, _)| node),
+            route_end_node: piece.station_times.last().map(|&(node, _, _)| node),
+            ..piece
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -992,6 +1658,7 @@ mod tests {
                     destination_platform: 0,
                     duration: Some(Duration::minutes(10)),
                     wait_time: Duration::seconds(30),
+                    reverses: false,
                 },
                 RouteSegment {
                     edge_index: edge2.index(),
@@ -1000,6 +1667,7 @@ mod tests {
                     destination_platform: 0,
                     duration: Some(Duration::minutes(15)),
                     wait_time: Duration::seconds(30),
+                    reverses: false,
                 },
             ],
             return_route: vec![],
@@ -1023,9 +1691,71 @@ mod tests {
             style: crate::models::LineStyle::default(),
             forward_turnaround: false,
             return_turnaround: false,
+            frequency_bands: Vec::new(),
+            skip_pattern: None,
+            skip_pattern_on_even: false,
+            rolling_stock: None,
+            clock_face_snap: None,
+            section_order_override: None,
+            valid_from: None,
+            valid_to: None,
+            service_pattern_id: None,
         }
     }
 
+    #[test]
+    fn test_journey_reverses_at_spur_terminus() {
+        use crate::conflict::{detect_line_conflicts, stop_platform, ConflictType, SerializableConflictContext};
+
+        // Trains run A - B, up the spur to C, reverse, and come back through B to D
+        let mut graph = create_test_graph();
+        let a = graph.get_station_index("Station A").expect("Station A exists");
+        let b = graph.get_station_index("Station B").expect("Station B exists");
+        let c = graph.get_station_index("Station C").expect("Station C exists");
+        let d = graph.add_or_get_station("Station D".to_string());
+        let bd = graph.add_track(b, d, vec![Track { direction: TrackDirection::Bidirectional }]);
+        let mut line = create_test_line(&graph);
+        let bc = line.forward_route[1].clone();
+        line.forward_route[1].reverses = true;
+        line.forward_route[1].destination_platform = 1;
+        line.forward_route.push(RouteSegment { origin_platform: 1, ..bc });
+        line.forward_route.push(RouteSegment { edge_index: bd.index(), duration: Some(Duration::minutes(5)), ..bc });
+        line.frequency = Duration::minutes(2);
+        line.last_departure = line.first_departure + Duration::minutes(2);
+        assert_eq!(line.has_repeated_edges(), None);
+
+        let journeys: Vec<TrainJourney> = TrainJourney::generate_journeys(&[line], &graph, Some(Weekday::Mon))
+            .into_values()
+            .collect();
+        assert_eq!(journeys.len(), 2);
+        let first = journeys.iter().min_by_key(|j| j.departure_time).expect("two journeys");
+
+        let at = |h, m, s| BASE_DATE.and_hms_opt(h, m, s).expect("valid time");
+        assert_eq!(first.station_times, vec![
+            (a, at(8, 0, 0), at(8, 0, 0)),
+            (b, at(8, 10, 0), at(8, 10, 30)),
+            // Reversing holds the train for the minimum reversal dwell rather than its 30s wait
+            (c, at(8, 25, 30), at(8, 28, 30)),
+            (b, at(8, 43, 30), at(8, 44, 0)),
+            (d, at(8, 49, 0), at(8, 49, 30)),
+        ]);
+        assert!(first.segments[1].reverses);
+        assert_eq!(stop_platform(first, 2), (1, Some(bc.edge_index)));
+
+        // The second train arrives on the same side while the first is still reversing, which
+        // conflicts even when same-direction platform conflicts are ignored
+        let settings = crate::models::ConflictSettings {
+            ignore_same_direction_platform_conflicts: true,
+            ..crate::models::ConflictSettings::default()
+        };
+        let station_indices = graph.graph.node_indices().enumerate().map(|(idx, node)| (node, idx)).collect();
+        let ctx = SerializableConflictContext::from_graph(&graph, station_indices, &settings);
+        let (conflicts, _) = detect_line_conflicts(&journeys, &ctx);
+        assert!(conflicts.iter().any(|conflict| {
+            conflict.conflict_type == ConflictType::PlatformViolation && conflict.station1_idx == c.index()
+        }));
+    }
+
     #[test]
     fn test_journey_segment_creation() {
         let segment = JourneySegment {
@@ -1033,6 +1763,7 @@ mod tests {
             track_index: 1,
             origin_platform: 2,
             destination_platform: 3,
+            reverses: false,
         };
 
         assert_eq!(segment.edge_index, 0);
@@ -1041,6 +1772,26 @@ mod tests {
         assert_eq!(segment.destination_platform, 3);
     }
 
+    #[test]
+    fn test_critical_sections_rank_single_track_edges_by_occupancy() {
+        let mut graph = create_test_graph();
+        let line = create_test_line(&graph);
+        let first_edge = EdgeIndex::new(line.forward_route[0].edge_index);
+        let second_edge = EdgeIndex::new(line.forward_route[1].edge_index);
+        let journeys: Vec<TrainJourney> = TrainJourney::generate_journeys(&[line], &graph, None).into_values().collect();
+        let journey_count = i32::try_from(journeys.len()).expect("journey count fits");
+        assert!(journey_count > 0);
+
+        let occupancy = single_track_occupancy(&journeys, &graph);
+        assert_eq!(occupancy[&first_edge], Duration::minutes(10) * journey_count);
+        assert_eq!(occupancy[&second_edge], Duration::minutes(15) * journey_count);
+        assert_eq!(critical_sections(&journeys, &graph), vec![second_edge]);
+
+        // Double track is never a bottleneck
+        graph.graph[second_edge].tracks.push(Track { direction: TrackDirection::Bidirectional });
+        assert_eq!(critical_sections(&journeys, &graph), vec![first_edge]);
+    }
+
     #[test]
     fn test_generate_journeys_empty_lines() {
         let graph = RailwayGraph::new();
@@ -1051,6 +1802,36 @@ mod tests {
         assert_eq!(journeys.len(), 0);
     }
 
+    #[test]
+    fn test_journey_into_depot_is_left_off_time_graph() {
+        let mut graph = create_test_graph();
+        let depot = graph.get_station_index("Station C").expect("Station C exists");
+        if let Some(station) = graph.graph.node_weight_mut(depot).and_then(crate::models::Node::as_station_mut) {
+            station.is_depot = true;
+        }
+        let mut line = create_test_line(&graph);
+        line.sync_routes = false;
+        line.return_route = vec![];
+
+        let journeys = TrainJourney::generate_journeys(&[line], &graph, None);
+        let journey = journeys.values().next().expect("journeys are generated");
+        assert_eq!(journey.station_times.last().map(|(node, _, _)| *node), Some(depot));
+
+        let first = graph.get_station_index("Station A").expect("Station A exists");
+        let view = crate::models::GraphView {
+            id: uuid::Uuid::new_v4(),
+            name: "Line".to_string(),
+            viewport_state: crate::models::ViewportState::default(),
+            station_range: Some((first, depot)),
+            edge_path: None,
+            source_line_id: None,
+        };
+        let displayed: Vec<_> = view.get_nodes_for_display(&graph).into_iter().map(|(idx, _)| idx).collect();
+        assert_eq!(displayed.len(), 2);
+        assert!(!displayed.contains(&depot));
+        assert_eq!(view.display_edge_path(&graph).len(), 1);
+    }
+
     #[test]
     fn test_generate_journeys_line_with_no_route() {
         let graph = create_test_graph();
@@ -1063,6 +1844,37 @@ mod tests {
         assert_eq!(journeys.len(), 0);
     }
 
+    #[test]
+    fn test_lines_without_journeys_reports_reason() {
+        let graph = create_test_graph();
+        let running = create_test_line(&graph);
+        let mut no_route = create_test_line(&graph);
+        no_route.id = uuid::Uuid::new_v4();
+        no_route.forward_route = vec![];
+        no_route.return_route = vec![];
+        let mut no_days = create_test_line(&graph);
+        no_days.id = uuid::Uuid::new_v4();
+        no_days.days_of_week = DaysOfWeek::empty();
+        // Only the return route is set, so the route isn't to blame
+        no_days.return_route = std::mem::take(&mut no_days.forward_route);
+        let mut no_departures = create_test_line(&graph);
+        no_departures.id = uuid::Uuid::new_v4();
+        no_departures.schedule_mode = ScheduleMode::Manual;
+        let mut hidden = no_route.clone();
+        hidden.id = uuid::Uuid::new_v4();
+        hidden.visible = false;
+
+        let lines = [running.clone(), no_route.clone(), no_days.clone(), no_departures.clone(), hidden];
+        let journeys = TrainJourney::generate_journeys(&lines, &graph, None);
+        let missing = lines_without_journeys(&lines, &journeys, SimulationHorizon::FullWeek, &[], None);
+
+        assert_eq!(missing.len(), 3);
+        assert_eq!(missing.get(&no_route.id), Some(&MissingJourneysReason::EmptyRoute));
+        assert_eq!(missing.get(&no_days.id), Some(&MissingJourneysReason::Days));
+        assert_eq!(missing.get(&no_departures.id), Some(&MissingJourneysReason::Schedule));
+        assert!(!missing.contains_key(&running.id));
+    }
+
     #[test]
     fn test_generate_forward_journeys() {
         let graph = create_test_graph();
@@ -1089,6 +1901,40 @@ mod tests {
         assert_eq!(first_journey.station_times[2].0, idx3);
     }
 
+    #[test]
+    fn test_edge_indices_from_segments() {
+        let graph = create_test_graph();
+        let line = create_test_line(&graph);
+        let expected: HashSet<EdgeIndex> = line.forward_route
+            .iter()
+            .map(|segment| EdgeIndex::new(segment.edge_index))
+            .collect();
+
+        let journeys = TrainJourney::generate_journeys(&[line], &graph, None);
+        let forward_journey = journeys.values()
+            .find(|j| j.is_forward)
+            .expect("has forward journey");
+
+        assert_eq!(forward_journey.edge_indices(), expected);
+    }
+
+    #[test]
+    fn test_find_by_train_number_prefers_running_journey() {
+        let graph = create_test_graph();
+        let line = create_test_line(&graph);
+        let journeys = TrainJourney::generate_journeys(&[line], &graph, None);
+        let target = journeys.values()
+            .max_by_key(|j| j.departure_time)
+            .expect("has journeys");
+        let time = target.station_times[0].2;
+
+        let found = TrainJourney::find_by_train_number(journeys.values(), &target.train_number, time)
+            .expect("finds journey");
+        assert!(found.is_running_at(time));
+        assert_eq!(found.train_number, target.train_number);
+        assert!(TrainJourney::find_by_train_number(journeys.values(), "missing", time).is_none());
+    }
+
     #[test]
     fn test_generate_journeys_respects_frequency() {
         let graph = create_test_graph();
@@ -1110,6 +1956,182 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_generate_journeys_uses_peak_frequency_band() {
+        let graph = create_test_graph();
+        let mut line = create_test_line(&graph);
+        let time = |h, m| chrono::NaiveTime::from_hms_opt(h, m, 0).expect("valid time");
+        line.first_departure = BASE_DATE.and_hms_opt(6, 0, 0).expect("valid time");
+        line.last_departure = BASE_DATE.and_hms_opt(10, 30, 0).expect("valid time");
+        line.frequency = Duration::minutes(30);
+        line.frequency_bands = vec![(time(7, 0), time(9, 0), Duration::minutes(10))];
+
+        let journeys = TrainJourney::generate_journeys(&[line], &graph, Some(Weekday::Mon));
+
+        let mut departure_times: Vec<_> = journeys.values()
+            .map(|j| j.departure_time.time())
+            .collect();
+        departure_times.sort();
+
+        // 06:00-06:30 off-peak, 07:00-08:50 every 10 minutes, 09:00-10:30 off-peak again
+        assert_eq!(departure_times.len(), 2 + 12 + 4);
+        for pair in departure_times.windows(2) {
+            let expected = if pair[0] >= time(7, 0) && pair[0] < time(9, 0) {
+                Duration::minutes(10)
+            } else {
+                Duration::minutes(30)
+            };
+            assert_eq!(pair[1] - pair[0], expected, "unexpected spacing after {}", pair[0]);
+        }
+    }
+
+    #[test]
+    fn test_skip_stop_journeys_pass_skipped_stations() {
+        use crate::conflict::{detect_line_conflicts, stop_platform, SerializableConflictContext};
+
+        let graph = create_test_graph();
+        let mut line = create_test_line(&graph);
+        line.first_departure = BASE_DATE.and_hms_opt(8, 0, 0).expect("valid time");
+        line.last_departure = BASE_DATE.and_hms_opt(9, 0, 0).expect("valid time");
+        // Odd-numbered departures skip Station B
+        line.skip_pattern = Some(vec![false, true, false]);
+
+        let journeys = TrainJourney::generate_journeys(&[line], &graph, Some(Weekday::Mon));
+        let mut journeys: Vec<_> = journeys.into_values().collect();
+        journeys.sort_by_key(|j| j.departure_time);
+        assert_eq!(journeys.len(), 2);
+
+        let (skip_stop, full_stop) = (&journeys[0], &journeys[1]);
+        let idx2 = graph.get_station_index("Station B").expect("Station B exists");
+        let nodes = |journey: &TrainJourney| journey.station_times.iter().map(|(node, _, _)| *node).collect::<Vec<_>>();
+        let edges = |journey: &TrainJourney| journey.segments.iter().map(|segment| segment.edge_index).collect::<Vec<_>>();
+
+        // Station B is left out of the stops, but the train still runs over every edge
+        assert_eq!(skip_stop.station_times.len(), full_stop.station_times.len() - 1);
+        assert!(!nodes(skip_stop).contains(&idx2));
+        assert_eq!(skip_stop.timing_inherited.len(), skip_stop.station_times.len());
+        assert_eq!(edges(skip_stop), edges(full_stop));
+        assert_eq!(skip_stop.passed_stations.len(), 1);
+        assert_eq!((skip_stop.passed_stations[0].route_position, skip_stop.passed_stations[0].node), (1, idx2));
+
+        // Merging the pass back gives every segment a station at either end
+        let full_route = skip_stop.with_passed_stations();
+        assert_eq!(nodes(&full_route), nodes(full_stop));
+        let (_, arrival, departure) = full_route.station_times[1];
+        assert_eq!(arrival, departure);
+        for (i, segment) in full_route.segments.iter().enumerate() {
+            let endpoints = graph.graph.edge_endpoints(petgraph::stable_graph::EdgeIndex::new(segment.edge_index)).expect("edge exists");
+            let (from, to) = (full_route.station_times[i].0, full_route.station_times[i + 1].0);
+            assert!(endpoints == (from, to) || endpoints == (to, from), "segment {i} doesn't join its stops");
+        }
+
+        // Platforms at the remaining stops match the all-stops journey
+        for (stop, (node, _, _)) in skip_stop.station_times.iter().enumerate() {
+            let full_stop_index = nodes(full_stop).iter().position(|n| n == node).expect("stop served by both");
+            assert_eq!(stop_platform(skip_stop, stop), stop_platform(full_stop, full_stop_index));
+        }
+
+        // Half-hourly trains on this route never meet, skipping or not
+        let station_indices = graph.graph.node_indices().enumerate().map(|(idx, node)| (node, idx)).collect();
+        let ctx = SerializableConflictContext::from_graph(&graph, station_indices, &crate::models::ConflictSettings::default());
+        let (conflicts, _) = detect_line_conflicts(&journeys, &ctx);
+        assert!(conflicts.is_empty(), "unexpected conflicts: {conflicts:?}");
+
+        // Skipping B removes its 30 second wait from the run time
+        let run_time = |journey: &TrainJourney| journey.station_times.last().expect("has stations").1 - journey.departure_time;
+        assert_eq!(run_time(full_stop) - run_time(skip_stop), Duration::seconds(30));
+    }
+
+    #[test]
+    fn test_compute_duration_from_distance() {
+        let mut track = TrackSegment::new_single_track();
+        assert_eq!(compute_duration_from_distance(&track, None), None);
+
+        track.distance = Some(10.0);
+        assert_eq!(compute_duration_from_distance(&track, None), None);
+
+        track.speed_limit_kph = Some(120.0);
+        assert_eq!(compute_duration_from_distance(&track, None), Some(Duration::minutes(5)));
+    }
+
+    #[test]
+    fn test_run_time_with_profile_short_and_long_segments() {
+        let profile = RollingStockProfile { max_speed_kph: 100.0, accel_ms2: 1.0, decel_ms2: 1.0 };
+        let constant_speed = |distance_km: f64| seconds_to_duration(distance_km / 100.0 * 3600.0);
+
+        // 500 m is too short to reach 100 km/h: peaks at sqrt(500) m/s, taking 2 * sqrt(500) s
+        let short = compute_run_time_with_profile(0.5, &profile).expect("valid profile");
+        assert_eq!(short, seconds_to_duration(2.0 * 500.0_f64.sqrt()));
+        assert!(short > constant_speed(0.5));
+
+        // 10 km reaches cruise: 2 * 27.78 s accelerating/braking plus cruising the remainder
+        let long = compute_run_time_with_profile(10.0, &profile).expect("valid profile");
+        let top_speed = 100.0 / 3.6;
+        let expected = 2.0 * top_speed + (10_000.0 - top_speed * top_speed) / top_speed;
+        assert_eq!(long, seconds_to_duration(expected));
+        assert!(long > constant_speed(10.0));
+    }
+
+    #[test]
+    fn test_profile_is_capped_by_track_speed_limit() {
+        let mut track = TrackSegment::new_single_track();
+        track.distance = Some(10.0);
+        track.speed_limit_kph = Some(100.0);
+        let fast = RollingStockProfile { max_speed_kph: 200.0, accel_ms2: 1.0, decel_ms2: 1.0 };
+        let capped = RollingStockProfile { max_speed_kph: 100.0, ..fast };
+
+        assert_eq!(
+            compute_duration_from_distance(&track, Some(&fast)),
+            compute_run_time_with_profile(10.0, &capped)
+        );
+    }
+
+    #[test]
+    fn test_segment_without_duration_uses_speed_limit() {
+        let mut graph = create_test_graph();
+        let mut line = create_test_line(&graph);
+        let edge = petgraph::graph::EdgeIndex::new(line.forward_route[0].edge_index);
+        if let Some(track) = graph.graph.edge_weight_mut(edge) {
+            track.distance = Some(10.0);
+            track.speed_limit_kph = Some(120.0);
+        }
+        line.forward_route.truncate(1);
+        line.forward_route[0].duration = None;
+
+        let journeys = TrainJourney::generate_journeys(&[line], &graph, Some(Weekday::Mon));
+        let journey = journeys.values()
+            .min_by_key(|j| j.departure_time)
+            .expect("has journey");
+
+        let (_, arrival, _) = journey.station_times[1];
+        assert_eq!(arrival - journey.departure_time, Duration::minutes(5));
+        assert_eq!(journey.timing_inherited, vec![false, false]);
+    }
+
+    #[test]
+    fn test_timed_segment_followed_by_speed_limited_segment() {
+        let mut graph = create_test_graph();
+        let mut line = create_test_line(&graph);
+        let edge = petgraph::graph::EdgeIndex::new(line.forward_route[1].edge_index);
+        if let Some(track) = graph.graph.edge_weight_mut(edge) {
+            track.distance = Some(10.0);
+            track.speed_limit_kph = Some(120.0);
+        }
+        // A - B is timed at 10 minutes, B - C is untimed and would otherwise inherit half of it
+        line.forward_route[1].duration = None;
+
+        let journeys = TrainJourney::generate_journeys(&[line], &graph, Some(Weekday::Mon));
+        let journey = journeys.values()
+            .min_by_key(|j| j.departure_time)
+            .expect("has journey");
+
+        let (_, arrival_b, departure_b) = journey.station_times[1];
+        let (_, arrival_c, _) = journey.station_times[2];
+        assert_eq!(arrival_b - journey.departure_time, Duration::minutes(10));
+        assert_eq!(arrival_c - departure_b, Duration::minutes(5));
+        assert_eq!(journey.timing_inherited, vec![false, false, false]);
+    }
+
     #[test]
     fn test_generate_journeys_stops_at_last_departure() {
         let graph = create_test_graph();
@@ -1172,6 +2194,7 @@ mod tests {
                     destination_platform: 1,
                     duration: Some(Duration::minutes(15)),
                     wait_time: Duration::seconds(30),
+                    reverses: false,
                 },
                 RouteSegment {
                     edge_index: e2.index(),
@@ -1180,6 +2203,7 @@ mod tests {
                     destination_platform: 1,
                     duration: Some(Duration::minutes(10)),
                     wait_time: Duration::seconds(30),
+                    reverses: false,
                 },
             ];
 
@@ -1229,6 +2253,79 @@ mod tests {
         assert_eq!(journey.station_times[2].2, expected_departure_c);
     }
 
+    /// A line with one daily departure at 23:50 that arrives after midnight
+    fn late_night_line(graph: &RailwayGraph) -> Line {
+        let departure = BASE_DATE.and_hms_opt(23, 50, 0).expect("valid time");
+        Line { first_departure: departure, last_departure: departure, ..create_test_line(graph) }
+    }
+
+    fn horizon_departures(line: &Line, graph: &RailwayGraph, horizon: SimulationHorizon) -> Vec<NaiveDateTime> {
+        let mut departures: Vec<NaiveDateTime> = TrainJourney::generate_journeys_for_horizon(std::slice::from_ref(line), graph, horizon, &[], None)
+            .values()
+            .map(|journey| journey.departure_time)
+            .collect();
+        departures.sort();
+        departures
+    }
+
+    #[test]
+    fn test_full_week_horizon_keeps_previous_sunday_wraparound() {
+        let graph = create_test_graph();
+        let departures = horizon_departures(&late_night_line(&graph), &graph, SimulationHorizon::default());
+
+        assert_eq!(departures.len(), 8);
+        assert_eq!(departures[0], BASE_DATE.pred_opt().expect("valid date").and_hms_opt(23, 50, 0).expect("valid time"));
+    }
+
+    #[test]
+    fn test_single_day_horizon() {
+        let graph = create_test_graph();
+        let departures = horizon_departures(&late_night_line(&graph), &graph, SimulationHorizon::SingleDay(Weekday::Wed));
+
+        assert_eq!(departures, vec![BASE_DATE.and_hms_opt(23, 50, 0).expect("valid time")]);
+    }
+
+    #[test]
+    fn test_day_range_horizon_keeps_only_wraparound_into_range() {
+        let graph = create_test_graph();
+        let range = SimulationHorizon::DayRange { first: 1, last: 2 };
+        let day = |offset: i64| (BASE_DATE + Duration::days(offset)).and_hms_opt(23, 50, 0).expect("valid time");
+
+        // Monday's late train runs into Tuesday, so it is kept alongside Tuesday's and Wednesday's
+        assert_eq!(horizon_departures(&late_night_line(&graph), &graph, range), vec![day(0), day(1), day(2)]);
+
+        // Monday's daytime trains end on Monday and are dropped
+        let daytime = horizon_departures(&create_test_line(&graph), &graph, range);
+        assert_eq!(daytime.len(), 2 * 15);
+        assert!(daytime.iter().all(|departure| *departure >= BASE_MIDNIGHT + Duration::days(1)));
+    }
+
+    #[test]
+    fn test_dwell_violations_flag_stops_below_station_minimum() {
+        let mut graph = create_test_graph();
+        let station_b = graph.get_station_index("Station B").expect("Station B exists");
+        let line = create_test_line(&graph);
+        let journeys: Vec<TrainJourney> = TrainJourney::generate_journeys(std::slice::from_ref(&line), &graph, Some(Weekday::Mon))
+            .into_values()
+            .collect();
+        let set_min_dwell = |graph: &mut RailwayGraph, secs: i64| {
+            if let Some(station) = graph.graph.node_weight_mut(station_b).and_then(Node::as_station_mut) {
+                station.min_dwell_secs = Some(secs);
+            }
+        };
+
+        // The line waits 30 s at B, below a 60 s minimum
+        set_min_dwell(&mut graph, 60);
+        let violations = dwell_violations(&journeys, &graph);
+        assert_eq!(violations.len(), journeys.len());
+        assert!(violations.iter().all(|(_, stop)| *stop == 1));
+        assert_eq!(journeys[0].station_times[1].0, station_b);
+
+        // ...but above a 20 s minimum
+        set_min_dwell(&mut graph, 20);
+        assert!(dwell_violations(&journeys, &graph).is_empty());
+    }
+
     #[test]
     fn test_weekday_to_days_of_week_conversion() {
         assert_eq!(weekday_to_days_of_week(Weekday::Mon), DaysOfWeek::MONDAY);
@@ -1257,6 +2354,157 @@ mod tests {
         assert!(saturday_journeys.is_empty());
     }
 
+    #[test]
+    fn test_service_pattern_exception_suppresses_service() {
+        let graph = create_test_graph();
+        let mut line = create_test_line(&graph);
+
+        // The line's own mask would run every day; the pattern limits it to weekdays
+        // and Wednesday of the anchored week is a holiday
+        let mut pattern = ServicePattern::new("Weekday".to_string(), DaysOfWeek::WEEKDAYS);
+        pattern.exceptions.push(BASE_DATE + Duration::days(2));
+        line.service_pattern_id = Some(pattern.id);
+        let patterns = [pattern];
+        let lines = std::slice::from_ref(&line);
+
+        let monday = TrainJourney::generate_journeys_with_patterns(lines, &graph, Some(Weekday::Mon), &patterns, None);
+        assert!(!monday.is_empty());
+        assert!(TrainJourney::generate_journeys_with_patterns(lines, &graph, Some(Weekday::Wed), &patterns, None).is_empty());
+        assert!(TrainJourney::generate_journeys_with_patterns(lines, &graph, Some(Weekday::Sat), &patterns, None).is_empty());
+
+        // Over the whole week, only the four remaining weekdays run
+        let week = TrainJourney::generate_journeys_with_patterns(lines, &graph, None, &patterns, None);
+        assert_eq!(week.len(), monday.len() * 4);
+        assert!(week.values().all(|journey| journey.departure_time.date() != BASE_DATE + Duration::days(2)));
+
+        // Without the pattern available, the line falls back to its own days of week
+        let unresolved = TrainJourney::generate_journeys(lines, &graph, Some(Weekday::Wed));
+        assert_eq!(unresolved.len(), monday.len());
+    }
+
+    #[test]
+    fn test_service_pattern_exception_is_a_real_date() {
+        let graph = create_test_graph();
+        let mut line = create_test_line(&graph);
+
+        // Christmas Day 2025 is a Thursday; the reference week is the week it falls in
+        let christmas = chrono::NaiveDate::from_ymd_opt(2025, 12, 25).expect("valid date");
+        let mut pattern = ServicePattern::new("Weekday".to_string(), DaysOfWeek::WEEKDAYS);
+        pattern.exceptions.push(christmas);
+        line.service_pattern_id = Some(pattern.id);
+        let patterns = [pattern];
+        let lines = std::slice::from_ref(&line);
+
+        let christmas_week = Some(christmas);
+        assert!(TrainJourney::generate_journeys_with_patterns(lines, &graph, Some(Weekday::Thu), &patterns, christmas_week).is_empty());
+        assert!(!TrainJourney::generate_journeys_with_patterns(lines, &graph, Some(Weekday::Wed), &patterns, christmas_week).is_empty());
+
+        // The following week's Thursday is an ordinary weekday
+        let next_week = Some(christmas + Duration::days(7));
+        assert!(!TrainJourney::generate_journeys_with_patterns(lines, &graph, Some(Weekday::Thu), &patterns, next_week).is_empty());
+    }
+
+    #[test]
+    fn test_generate_journeys_respects_operating_period() {
+        let graph = create_test_graph();
+        let mut line = create_test_line(&graph);
+
+        // Line only runs Wednesday to Friday of the anchored week
+        line.valid_from = Some(BASE_DATE + Duration::days(2));
+        line.valid_to = Some(BASE_DATE + Duration::days(4));
+
+        let monday_journeys = TrainJourney::generate_journeys(std::slice::from_ref(&line), &graph, Some(Weekday::Mon));
+        assert!(monday_journeys.is_empty());
+
+        let wednesday_journeys = TrainJourney::generate_journeys(std::slice::from_ref(&line), &graph, Some(Weekday::Wed));
+        assert!(!wednesday_journeys.is_empty());
+
+        let saturday_journeys = TrainJourney::generate_journeys(std::slice::from_ref(&line), &graph, Some(Weekday::Sat));
+        assert!(saturday_journeys.is_empty());
+
+        // Over the whole week, every journey departs within the period
+        let week_journeys = TrainJourney::generate_journeys(std::slice::from_ref(&line), &graph, None);
+        assert_eq!(week_journeys.len(), wednesday_journeys.len() * 3);
+        assert!(week_journeys.values().all(|journey| {
+            let date = journey.departure_time.date();
+            date >= BASE_DATE + Duration::days(2) && date <= BASE_DATE + Duration::days(4)
+        }));
+    }
+
+    #[test]
+    fn test_operating_period_matches_reference_week() {
+        let graph = create_test_graph();
+        let mut line = create_test_line(&graph);
+        let date = |y, m, d| chrono::NaiveDate::from_ymd_opt(y, m, d).expect("valid date");
+
+        // A summer service running June to August
+        line.valid_from = Some(date(2025, 6, 1));
+        line.valid_to = Some(date(2025, 8, 31));
+        let lines = std::slice::from_ref(&line);
+        let generate = |day, reference_week| TrainJourney::generate_journeys_with_patterns(lines, &graph, day, &[], reference_week);
+
+        // Without a reference week the simulated week is in January 2024, so the line never runs
+        assert!(generate(None, None).is_empty());
+
+        // Any date in a July week runs the whole week
+        let daily = generate(Some(Weekday::Wed), Some(date(2025, 7, 9))).len();
+        assert!(daily > 0);
+        assert_eq!(generate(None, Some(date(2025, 7, 10))).len(), daily * 7);
+
+        // A winter reference week has no service
+        assert!(generate(Some(Weekday::Wed), Some(date(2025, 1, 15))).is_empty());
+
+        // The week of Monday 26 May 2025 only reaches the period on Sunday 1 June
+        assert!(generate(Some(Weekday::Sat), Some(date(2025, 5, 28))).is_empty());
+        assert_eq!(generate(Some(Weekday::Sun), Some(date(2025, 5, 28))).len(), daily);
+    }
+
+    fn train_numbers_by_departure(journeys: &HashMap<uuid::Uuid, TrainJourney>) -> Vec<(NaiveDateTime, bool, String)> {
+        let mut numbers: Vec<_> = journeys.values()
+            .map(|j| (j.departure_time, j.is_forward, j.train_number.clone()))
+            .collect();
+        numbers.sort();
+        numbers
+    }
+
+    #[test]
+    fn test_stable_train_numbers_survive_regeneration() {
+        let graph = create_test_graph();
+        let mut line = create_test_line(&graph);
+        line.auto_train_number_format = "{line} {seq_stable}".to_string();
+
+        let first = TrainJourney::generate_journeys(std::slice::from_ref(&line), &graph, None);
+        let second = TrainJourney::generate_journeys(std::slice::from_ref(&line), &graph, None);
+        assert_eq!(train_numbers_by_departure(&first), train_numbers_by_departure(&second));
+
+        // Dropping the first departure renumbers {seq} but not {seq_stable}
+        let mut later_start = line.clone();
+        later_start.first_departure += line.frequency;
+        let shifted = TrainJourney::generate_journeys(std::slice::from_ref(&later_start), &graph, Some(Weekday::Mon));
+        let original = TrainJourney::generate_journeys(std::slice::from_ref(&line), &graph, Some(Weekday::Mon));
+        let original_numbers = train_numbers_by_departure(&original);
+        for entry in train_numbers_by_departure(&shifted) {
+            assert!(original_numbers.contains(&entry), "{entry:?} changed number");
+        }
+    }
+
+    #[test]
+    fn test_hhmm_train_numbers_are_unique_per_day() {
+        let graph = create_test_graph();
+        let mut line = create_test_line(&graph);
+        line.auto_train_number_format = "{hhmm}".to_string();
+        // Return trains leave at the same minutes as forward ones
+        line.return_route = line.forward_route.iter().rev().cloned().collect();
+        line.return_first_departure = line.first_departure;
+
+        let journeys = TrainJourney::generate_journeys(std::slice::from_ref(&line), &graph, Some(Weekday::Mon));
+        let numbers = train_numbers_by_departure(&journeys);
+        let unique: HashSet<&String> = numbers.iter().map(|(_, _, number)| number).collect();
+        assert_eq!(unique.len(), numbers.len());
+        assert!(numbers.iter().any(|(_, is_forward, number)| *is_forward && number == "0800"));
+        assert!(numbers.iter().any(|(_, is_forward, number)| !*is_forward && number == "0800-2"));
+    }
+
     #[test]
     fn test_generate_journeys_seven_days() {
         let graph = create_test_graph();
@@ -1293,6 +2541,7 @@ mod tests {
                 train_number: None,
                 repeat_interval: None,
                 repeat_until: None,
+                direction: crate::models::RouteDirection::Forward,
             },
         ];
 
@@ -1305,6 +2554,73 @@ mod tests {
         assert_eq!(tuesday_journeys.len(), 0);
     }
 
+    #[test]
+    fn test_clock_face_snap_rounds_forward_departures() {
+        let graph = create_test_graph();
+        let mut line = create_test_line(&graph);
+        line.first_departure = BASE_DATE.and_hms_opt(8, 7, 0).expect("valid time");
+        line.last_departure = BASE_DATE.and_hms_opt(10, 0, 0).expect("valid time");
+        line.frequency = Duration::minutes(30);
+        line.clock_face_snap = Some(Duration::minutes(15));
+        assert!(line.validate_clock_face_snap().is_ok());
+
+        let journeys = TrainJourney::generate_journeys(std::slice::from_ref(&line), &graph, Some(Weekday::Mon));
+        let mut departures: Vec<_> = journeys.values()
+            .filter(|j| j.is_forward)
+            .map(|j| j.departure_time.time())
+            .collect();
+        departures.sort();
+
+        let time = |h, m| chrono::NaiveTime::from_hms_opt(h, m, 0).expect("valid time");
+        assert_eq!(departures, vec![time(8, 0), time(8, 30), time(9, 0), time(9, 30)]);
+
+        // Run times are preserved: arrival is still 25 minutes plus the intermediate wait after departure
+        let journey = journeys.values().find(|j| j.is_forward).expect("has journey");
+        let run_time = journey.station_times.last().expect("has stops").1 - journey.departure_time;
+        assert_eq!(run_time, Duration::minutes(25) + Duration::seconds(30));
+
+        line.frequency = Duration::minutes(20);
+        assert!(line.validate_clock_face_snap().is_err());
+    }
+
+    #[test]
+    fn test_manual_departure_runs_return_direction() {
+        let graph = create_test_graph();
+        let mut line = create_test_line(&graph);
+        line.return_route = line.forward_route.iter().rev().cloned().collect();
+
+        let idx_a = graph.get_station_index("Station A").expect("Station A exists");
+        let idx_b = graph.get_station_index("Station B").expect("Station B exists");
+        let idx_c = graph.get_station_index("Station C").expect("Station C exists");
+
+        let departure = |from_station, to_station| crate::models::ManualDeparture {
+            id: uuid::Uuid::new_v4(),
+            time: BASE_DATE.and_hms_opt(10, 0, 0).expect("valid time"),
+            from_station,
+            to_station,
+            days_of_week: DaysOfWeek::ALL_DAYS,
+            train_number: None,
+            repeat_interval: None,
+            repeat_until: None,
+            direction: crate::models::RouteDirection::Return,
+        };
+
+        line.schedule_mode = ScheduleMode::Manual;
+        line.manual_departures = vec![departure(idx_c, idx_a)];
+        let journeys = TrainJourney::generate_journeys(std::slice::from_ref(&line), &graph, Some(Weekday::Mon));
+        assert_eq!(journeys.len(), 1);
+        let journey = journeys.values().next().expect("has journey");
+        assert!(!journey.is_forward);
+        let stops: Vec<_> = journey.station_times.iter().map(|(idx, _, _)| *idx).collect();
+        assert_eq!(stops, vec![idx_c, idx_b, idx_a]);
+        assert!(journey.station_times[2].1 > journey.station_times[0].2);
+
+        // A return departure in forward station order has no route to run on
+        line.manual_departures = vec![departure(idx_a, idx_c)];
+        let journeys = TrainJourney::generate_journeys(std::slice::from_ref(&line), &graph, Some(Weekday::Mon));
+        assert!(journeys.is_empty());
+    }
+
     #[test]
     fn test_journey_skips_junctions() {
         use crate::models::{Junction, Junctions};
@@ -1339,6 +2655,7 @@ mod tests {
                     destination_platform: 0,
                     duration: Some(Duration::minutes(5)),
                     wait_time: Duration::seconds(0), // No wait at junction
+                    reverses: false,
                 },
                 RouteSegment {
                     edge_index: edge2.index(),
@@ -1347,6 +2664,7 @@ mod tests {
                     destination_platform: 0,
                     duration: Some(Duration::minutes(5)),
                     wait_time: Duration::seconds(30),
+                    reverses: false,
                 },
             ],
             return_route: vec![],
@@ -1370,6 +2688,15 @@ mod tests {
             style: crate::models::LineStyle::default(),
             forward_turnaround: false,
             return_turnaround: false,
+            frequency_bands: Vec::new(),
+            skip_pattern: None,
+            skip_pattern_on_even: false,
+            rolling_stock: None,
+            clock_face_snap: None,
+            section_order_override: None,
+            valid_from: None,
+            valid_to: None,
+            service_pattern_id: None,
         };
 
         let journeys = TrainJourney::generate_journeys(&[line], &graph, None);
@@ -1446,6 +2773,7 @@ mod tests {
                     destination_platform: 0,
                     duration: Some(Duration::minutes(12)), // Covers segments 0, 1, 2
                     wait_time: Duration::seconds(30),
+                    reverses: false,
                 },
                 RouteSegment {
                     edge_index: edge_bc.index(),
@@ -1454,6 +2782,7 @@ mod tests {
                     destination_platform: 0,
                     duration: None, // Gap
                     wait_time: Duration::seconds(30),
+                    reverses: false,
                 },
                 RouteSegment {
                     edge_index: edge_cd.index(),
@@ -1462,6 +2791,7 @@ mod tests {
                     destination_platform: 0,
                     duration: None, // Gap
                     wait_time: Duration::seconds(30),
+                    reverses: false,
                 },
                 RouteSegment {
                     edge_index: edge_de.index(),
@@ -1470,6 +2800,7 @@ mod tests {
                     destination_platform: 0,
                     duration: Some(Duration::minutes(6)), // Covers segments 3, 4
                     wait_time: Duration::seconds(30),
+                    reverses: false,
                 },
                 RouteSegment {
                     edge_index: edge_ef.index(),
@@ -1478,6 +2809,7 @@ mod tests {
                     destination_platform: 0,
                     duration: None, // Gap
                     wait_time: Duration::seconds(30),
+                    reverses: false,
                 },
             ],
             return_route: vec![],
@@ -1501,6 +2833,15 @@ mod tests {
             style: crate::models::LineStyle::default(),
             forward_turnaround: false,
             return_turnaround: false,
+            frequency_bands: Vec::new(),
+            skip_pattern: None,
+            skip_pattern_on_even: false,
+            rolling_stock: None,
+            clock_face_snap: None,
+            section_order_override: None,
+            valid_from: None,
+            valid_to: None,
+            service_pattern_id: None,
         };
 
         // Apply sync to create return route
@@ -1621,6 +2962,7 @@ mod tests {
                 destination_platform: 0,
                 duration: Some(Duration::minutes(10)), // Only covers segment 0
                 wait_time: Duration::seconds(30),
+                reverses: false,
             },
             RouteSegment {
                 edge_index: edge_bc.index(),
@@ -1629,6 +2971,7 @@ mod tests {
                 destination_platform: 0,
                 duration: None, // Standalone gap - not covered by anything
                 wait_time: Duration::seconds(30),
+                reverses: false,
             },
             RouteSegment {
                 edge_index: edge_cd.index(),
@@ -1637,6 +2980,7 @@ mod tests {
                 destination_platform: 0,
                 duration: Some(Duration::minutes(6)), // Covers segments 2-3 (but there's only seg 2, so just itself)
                 wait_time: Duration::seconds(30),
+                reverses: false,
             },
         ];
 
@@ -1693,6 +3037,15 @@ mod tests {
             style: crate::models::LineStyle::default(),
             forward_turnaround: false,
             return_turnaround: false,
+            frequency_bands: Vec::new(),
+            skip_pattern: None,
+            skip_pattern_on_even: false,
+            rolling_stock: None,
+            clock_face_snap: None,
+            section_order_override: None,
+            valid_from: None,
+            valid_to: None,
+            service_pattern_id: None,
         };
 
         line.apply_route_sync_if_enabled();
@@ -1736,4 +3089,153 @@ mod tests {
         println!("\n✓ Return journey is valid and renderable");
     }
 
+    fn journey_with_times(times: &[(u32, u32)]) -> TrainJourney {
+        use crate::constants::BASE_MIDNIGHT;
+        use petgraph::stable_graph::NodeIndex;
+
+        let at = |minutes: u32| BASE_MIDNIGHT + Duration::minutes(i64::from(minutes));
+        TrainJourney {
+            id: uuid::Uuid::new_v4(),
+            line_id: uuid::Uuid::new_v4(),
+            train_number: "1".to_string(),
+            departure_time: at(times[0].1),
+            station_times: times.iter()
+                .enumerate()
+                .map(|(i, &(arrival, departure))| (NodeIndex::new(i), at(arrival), at(departure)))
+                .collect(),
+            segments: Vec::new(),
+            color: "#fff".to_string(),
+            thickness: 2.0,
+            route_start_node: None,
+            route_end_node: None,
+            passed_stations: Vec::new(),
+            timing_inherited: vec![false; times.len()],
+            is_forward: true,
+        }
+    }
+
+    #[test]
+    fn test_validate_monotonic_times_accepts_increasing_journey() {
+        let journey = journey_with_times(&[(0, 0), (10, 12), (20, 20)]);
+        assert_eq!(journey.validate_monotonic_times(), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_monotonic_times_flags_backwards_and_zero_segments() {
+        // Arrives at the third stop before leaving the second, then reaches the fourth instantly
+        let journey = journey_with_times(&[(0, 0), (10, 15), (12, 14), (14, 14)]);
+        assert_eq!(journey.validate_monotonic_times(), Err(vec![2, 3]));
+    }
+
+    #[test]
+    fn test_reference_journeys_match_stations_by_name() {
+        // The reference project adds its stations in another order, so its node indices differ,
+        // and it has a station the current project lacks in the middle of the line
+        let current = create_test_graph();
+        let mut reference_graph = RailwayGraph::new();
+        let c = reference_graph.add_or_get_station("Station C".to_string());
+        let b = reference_graph.add_or_get_station("Station B".to_string());
+        let a = reference_graph.add_or_get_station("Station A".to_string());
+        let d = reference_graph.add_or_get_station("Station D".to_string());
+        let track = || vec![Track { direction: TrackDirection::Bidirectional }];
+        let edges = [
+            reference_graph.add_track(a, b, track()),
+            reference_graph.add_track(b, c, track()),
+            reference_graph.add_track(c, d, track()),
+            reference_graph.add_track(d, a, track()),
+        ];
+
+        let mut line = create_test_line(&current);
+        line.last_departure = line.first_departure;
+        line.forward_route = edges.iter().map(|edge| RouteSegment { edge_index: edge.index(), ..line.forward_route[0].clone() }).collect();
+        line.return_route = Vec::new();
+        let mut reference_project = Project::new_with_name("Previous".to_string());
+        reference_project.graph = reference_graph;
+        reference_project.lines = vec![line];
+
+        let reference = ReferenceTimetable::from_project(&reference_project, &current, Some(Weekday::Mon));
+        assert_eq!(reference.name, "Previous");
+
+        // A-B-C survives on the current graph's nodes and edges; C-D-A has nothing to run on
+        let journeys: Vec<_> = reference.journeys.values().collect();
+        assert_eq!(journeys.len(), 1);
+        let names: Vec<_> = journeys[0].station_times.iter().map(|(node, _, _)| current.get_station_name(*node)).collect();
+        assert_eq!(names, vec![Some("Station A"), Some("Station B"), Some("Station C")]);
+        let expected_edges: Vec<_> = journeys[0].station_times.windows(2)
+            .map(|pair| current.graph.find_edge(pair[0].0, pair[1].0).map(EdgeIndex::index))
+            .collect();
+        let edges: Vec<_> = journeys[0].segments.iter().map(|segment| Some(segment.edge_index)).collect();
+        assert_eq!(edges, expected_edges);
+        assert_eq!(journeys[0].route_end_node, current.get_station_index("Station C"));
+    }
+
+    fn timed_journey(stops: &[(NodeIndex, u32, u32)], graph: &RailwayGraph) -> TrainJourney {
+        let at = |minutes: u32| BASE_DATE.and_hms_opt(8 + minutes / 60, minutes % 60, 0).expect("valid time");
+        let station_times: Vec<_> = stops.iter().map(|&(node, arrival, departure)| (node, at(arrival), at(departure))).collect();
+        let segments = stops
+            .windows(2)
+            .map(|pair| JourneySegment {
+                edge_index: graph.graph.find_edge(pair[0].0, pair[1].0).expect("edge exists").index(),
+                track_index: 0,
+                origin_platform: 0,
+                destination_platform: 0,
+                reverses: false,
+            })
+            .collect();
+        TrainJourney {
+            id: uuid::Uuid::new_v4(),
+            line_id: uuid::Uuid::new_v4(),
+            train_number: String::new(),
+            departure_time: station_times[0].2,
+            timing_inherited: vec![false; station_times.len()],
+            route_start_node: station_times.first().map(|s| s.0),
+            route_end_node: station_times.last().map(|s| s.0),
+            station_times,
+            segments,
+            passed_stations: Vec::new(),
+            color: TEST_COLOR.to_string(),
+            thickness: TEST_THICKNESS,
+            is_forward: true,
+        }
+    }
+
+    #[test]
+    fn test_overtake_at_passing_loop_is_legal() {
+        let mut graph = RailwayGraph::new();
+        let a = graph.add_or_get_station("A".to_string());
+        let passing_loop = graph.add_or_get_station("Loop".to_string());
+        let b = graph.add_or_get_station("B".to_string());
+        if let Some(Node::Station(station)) = graph.graph.node_weight_mut(passing_loop) {
+            station.passing_loop = true;
+        }
+        graph.add_track(a, passing_loop, vec![Track { direction: TrackDirection::Bidirectional }]);
+        graph.add_track(passing_loop, b, vec![Track { direction: TrackDirection::Bidirectional }]);
+
+        // The slow train waits in the loop while the fast one passes
+        let slow = timed_journey(&[(a, 0, 0), (passing_loop, 10, 30), (b, 45, 45)], &graph);
+        let fast = timed_journey(&[(a, 5, 5), (passing_loop, 12, 13), (b, 20, 20)], &graph);
+
+        assert!(find_illegal_overtakes(&[slow, fast], &graph).is_empty());
+    }
+
+    #[test]
+    fn test_overtake_mid_section_is_illegal() {
+        let mut graph = RailwayGraph::new();
+        let a = graph.add_or_get_station("A".to_string());
+        let b = graph.add_or_get_station("B".to_string());
+        graph.add_track(a, b, vec![Track { direction: TrackDirection::Bidirectional }]);
+
+        let slow = timed_journey(&[(a, 0, 0), (b, 30, 30)], &graph);
+        let fast = timed_journey(&[(a, 5, 5), (b, 20, 20)], &graph);
+        let (slow_id, fast_id) = (slow.id, fast.id);
+
+        assert_eq!(find_illegal_overtakes(&[slow, fast], &graph), vec![(fast_id, slow_id, a)]);
+
+        // A second track lets the fast train pass
+        let edge = graph.graph.find_edge(a, b).expect("edge exists");
+        graph.graph[edge].tracks.push(Track { direction: TrackDirection::Bidirectional });
+        let slow = timed_journey(&[(a, 0, 0), (b, 30, 30)], &graph);
+        let fast = timed_journey(&[(a, 5, 5), (b, 20, 20)], &graph);
+        assert!(find_illegal_overtakes(&[slow, fast], &graph).is_empty());
+    }
 }
@@ -38,13 +38,12 @@ impl ConflictDetector {
             .enumerate()
             .map(|(idx, node_idx)| (node_idx, idx))
             .collect();
+        let (window_start, window_end) = settings.conflict_time_window().unzip();
         let context = SerializableConflictContext::from_graph(
             &graph,
             station_indices,
-            settings.station_margin,
-            settings.minimum_separation,
-            settings.ignore_same_direction_platform_conflicts,
-        );
+            &settings.conflict_settings(),
+        ).with_time_window(window_start, window_end);
 
         self.worker.send(ConflictRequest { journeys, context });
         if let Some(elapsed) = start.and_then(|s| web_sys::window()?.performance().map(|p| p.now() - s)) {
@@ -1,17 +1,18 @@
 use leptos::{WriteSignal, SignalSet};
-use crate::conflict::{Conflict, SerializableConflictContext};
+use crate::conflict::{Conflict, ConflictCache, SerializableConflictContext};
 use crate::train_journey::TrainJourney;
 use crate::models::{RailwayGraph, ProjectSettings};
 
 /// Synchronous version of `ConflictDetector` for non-wasm32 targets (tests, etc.)
 pub struct ConflictDetector {
     set_conflicts: WriteSignal<Vec<Conflict>>,
+    cache: ConflictCache,
 }
 
 impl ConflictDetector {
     #[must_use]
     pub fn new(set_conflicts: WriteSignal<Vec<Conflict>>) -> Self {
-        Self { set_conflicts }
+        Self { set_conflicts, cache: ConflictCache::new() }
     }
 
     #[allow(clippy::needless_pass_by_value)]
@@ -21,15 +22,14 @@ impl ConflictDetector {
             .enumerate()
             .map(|(idx, node_idx)| (node_idx, idx))
             .collect();
+        let (window_start, window_end) = settings.conflict_time_window().unzip();
         let context = SerializableConflictContext::from_graph(
             &graph,
             station_indices,
-            settings.station_margin,
-            settings.minimum_separation,
-            settings.ignore_same_direction_platform_conflicts,
-        );
+            &settings.conflict_settings(),
+        ).with_time_window(window_start, window_end);
 
-        let (conflicts, _) = crate::conflict::detect_line_conflicts(&journeys, &context);
+        let (conflicts, _) = crate::conflict::detect_line_conflicts_cached(&journeys, &context, &mut self.cache);
         self.set_conflicts.set(conflicts);
     }
 }